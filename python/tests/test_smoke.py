@@ -0,0 +1,48 @@
+"""Smoke test for the `neurodrive` extension module.
+
+Build and run with:
+
+    maturin develop --release
+    pytest python/tests
+"""
+
+import numpy as np
+
+import neurodrive
+
+
+def test_random_actions_for_1000_steps():
+    env = neurodrive.NeuroDriveEnv(seed=7)
+    obs_space = env.observation_space
+    act_space = env.action_space
+    assert act_space["n"] is None
+    assert len(obs_space["names"]) == obs_space["shape"][0]
+
+    obs, info = env.reset(seed=7)
+    assert obs.dtype == np.float32
+    assert obs.shape == obs_space["shape"]
+    assert info["seed"] == 7
+
+    rng = np.random.default_rng(0)
+    low, high = act_space["low"], act_space["high"]
+    for _ in range(1000):
+        action = rng.uniform(low, high).astype(np.float32)
+        obs, reward, terminated, truncated, info = env.step(action)
+        assert obs.shape == obs_space["shape"]
+        assert np.all(np.isfinite(obs))
+        assert np.isfinite(reward)
+        if terminated or truncated:
+            assert info["end_reason"] in ("crash", "timeout", "lap_complete")
+            obs, info = env.reset()
+
+    env.close()
+
+
+def test_seeded_reset_replays_exactly():
+    env = neurodrive.NeuroDriveEnv()
+    runs = []
+    for _ in range(2):
+        env.reset(seed=3)
+        runs.append([env.step([0.2, 0.8])[0] for _ in range(50)])
+    for first, second in zip(*runs):
+        np.testing.assert_array_equal(first, second)
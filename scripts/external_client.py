@@ -0,0 +1,80 @@
+#!/usr/bin/env python3
+"""Minimal NeuroDrive external-control client.
+
+Start the server with `cargo run --release -- --serve-external [port]`, then
+run `python3 scripts/external_client.py [port]`. The client steers away from
+the nearer wall using the first and last ray and resets after 600 ticks.
+
+Frames are a 4-byte big-endian length followed by UTF-8 JSON; see
+`src/eval/remote.rs` for the message shapes.
+"""
+
+import json
+import socket
+import struct
+import sys
+
+
+def send(sock, message):
+    body = json.dumps(message).encode("utf-8")
+    sock.sendall(struct.pack(">I", len(body)) + body)
+
+
+def recv_exact(sock, n):
+    data = b""
+    while len(data) < n:
+        chunk = sock.recv(n - len(data))
+        if not chunk:
+            raise ConnectionError("server closed the connection")
+        data += chunk
+    return data
+
+
+def recv(sock):
+    (length,) = struct.unpack(">I", recv_exact(sock, 4))
+    return json.loads(recv_exact(sock, length))
+
+
+def main():
+    port = int(sys.argv[1]) if len(sys.argv) > 1 else 5555
+    with socket.create_connection(("127.0.0.1", port)) as sock:
+        sock.setsockopt(socket.IPPROTO_TCP, socket.TCP_NODELAY, 1)
+        hello = recv(sock)
+        names = [f["name"] for f in hello["spaces"]["observation"]["features"]]
+        print(f"protocol {hello['protocol_version']}, track {hello['track_checksum']:#018x}")
+        print(f"observation: {len(names)} features")
+        send(sock, {"type": "hello", "expected_track_checksum": hello["track_checksum"]})
+
+        rays = [i for i, name in enumerate(names) if name.startswith("ray_")]
+        episode_return = 0.0
+        ticks_in_episode = 0
+        while True:
+            step = recv(sock)
+            if step["type"] != "step":
+                print(step)
+                return
+            episode_return += step["reward"]
+            ticks_in_episode += 1
+            if step["terminated"] or step["truncated"]:
+                print(f"tick {step['tick']}: episode return {episode_return:.2f}")
+                episode_return = 0.0
+                ticks_in_episode = 0
+
+            if ticks_in_episode >= 600:
+                send(sock, {"type": "reset", "tick": step["tick"]})
+                continue
+            obs = step["observation"]
+            left, right = obs[rays[0]], obs[rays[-1]]
+            send(
+                sock,
+                {
+                    "type": "action",
+                    "tick": step["tick"],
+                    "steering": max(-1.0, min(1.0, 2.0 * (right - left))),
+                    "throttle": 0.6,
+                },
+            )
+
+
+if __name__ == "__main__":
+    main()
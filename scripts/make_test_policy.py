@@ -0,0 +1,105 @@
+#!/usr/bin/env python3
+"""Writes `assets/models/test_policy.onnx`, the tiny policy used by the ONNX
+controller tests.
+
+The model is one linear layer (`Gemm`) from the default 23-feature
+observation to `[steering, throttle]`:
+
+    steering = heading_error            (feature 13)
+    throttle = 0.5 * ray_5 + 0.25       (feature 5, the forward ray)
+
+The protobuf is encoded by hand so the script needs only the standard
+library. Run `python3 scripts/make_test_policy.py` from the repository root.
+"""
+
+import struct
+
+OBS_DIM = 23
+ACTION_DIM = 2
+HEADING_ERROR = 13
+FORWARD_RAY = 5
+FLOAT = 1
+
+
+def varint(value):
+    out = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def field_varint(number, value):
+    return varint(number << 3) + varint(value)
+
+
+def field_bytes(number, payload):
+    if isinstance(payload, str):
+        payload = payload.encode("utf-8")
+    return varint((number << 3) | 2) + varint(len(payload)) + payload
+
+
+def tensor(name, dims, values):
+    body = b"".join(field_varint(1, dim) for dim in dims)
+    body += field_varint(2, FLOAT)
+    body += field_bytes(8, name)
+    body += field_bytes(9, struct.pack(f"<{len(values)}f", *values))
+    return body
+
+
+def value_info(name, dims):
+    shape = b""
+    for dim in dims:
+        if isinstance(dim, str):
+            shape += field_bytes(1, field_bytes(2, dim))
+        else:
+            shape += field_bytes(1, field_varint(1, dim))
+    tensor_type = field_varint(1, FLOAT) + field_bytes(2, shape)
+    return field_bytes(1, name) + field_bytes(2, field_bytes(1, tensor_type))
+
+
+def main():
+    weights = [0.0] * (OBS_DIM * ACTION_DIM)
+    weights[HEADING_ERROR * ACTION_DIM + 0] = 1.0
+    weights[FORWARD_RAY * ACTION_DIM + 1] = 0.5
+    bias = [0.0, 0.25]
+
+    gemm = b"".join(
+        [
+            field_bytes(1, "observation"),
+            field_bytes(1, "weight"),
+            field_bytes(1, "bias"),
+            field_bytes(2, "action"),
+            field_bytes(3, "policy"),
+            field_bytes(4, "Gemm"),
+        ]
+    )
+    graph = b"".join(
+        [
+            field_bytes(1, gemm),
+            field_bytes(2, "test_policy"),
+            field_bytes(5, tensor("weight", [OBS_DIM, ACTION_DIM], weights)),
+            field_bytes(5, tensor("bias", [ACTION_DIM], bias)),
+            field_bytes(11, value_info("observation", ["batch", OBS_DIM])),
+            field_bytes(12, value_info("action", ["batch", ACTION_DIM])),
+        ]
+    )
+    opset = field_bytes(1, "") + field_varint(2, 13)
+    model = b"".join(
+        [
+            field_varint(1, 8),
+            field_bytes(2, "neurodrive"),
+            field_bytes(7, graph),
+            field_bytes(8, opset),
+        ]
+    )
+    with open("assets/models/test_policy.onnx", "wb") as out:
+        out.write(model)
+
+
+if __name__ == "__main__":
+    main()
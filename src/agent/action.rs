@@ -1,4 +1,12 @@
+use std::f32::consts::PI;
+use std::fmt;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::observation::SensorReadings;
+use crate::game::car::Car;
+use crate::game::episode::{EpisodeConfig, EpisodeState};
 
 /// Continuous action interface for the car.
 ///
@@ -8,7 +16,7 @@ use bevy::prelude::*;
 /// ## Invariants
 /// - `steering` is clamped to `[-1, 1]` (left negative, right positive).
 /// - `throttle` is clamped to `[0, 1]` (0 = coast, 1 = full throttle).
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct CarAction {
     pub steering: f32,
     pub throttle: f32,
@@ -16,19 +24,221 @@ pub struct CarAction {
 
 impl CarAction {
     /// Returns this action clamped to its allowed ranges.
+    ///
+    /// Non-finite components (NaN or ±inf) are treated as zero, so a faulty
+    /// controller cannot poison the car state.
     pub fn clamped(self) -> Self {
         Self {
-            steering: self.steering.clamp(-1.0, 1.0),
-            throttle: self.throttle.clamp(0.0, 1.0),
+            steering: finite_or_zero(self.steering).clamp(-1.0, 1.0),
+            throttle: finite_or_zero(self.throttle).clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Replaces NaN and infinities with `0.0`.
+pub fn finite_or_zero(value: f32) -> f32 {
+    if value.is_finite() { value } else { 0.0 }
+}
+
+/// How controllers express actions.
+///
+/// In discrete mode the action set is `steering_levels × throttle_levels`.
+/// Index `i` selects steering level `i / throttle_levels` and throttle level
+/// `i % throttle_levels`; levels are evenly spaced over `[-1, 1]` steering
+/// and `[0, 1]` throttle. A single steering level steers straight ahead and a
+/// single throttle level is full throttle. `Discrete(steering_levels: 3,
+/// throttle_levels: 2)` gives the six actions hard-left / straight /
+/// hard-right × coast / full throttle.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActionMode {
+    #[default]
+    Continuous,
+    Discrete {
+        steering_levels: u32,
+        throttle_levels: u32,
+    },
+}
+
+/// Reasons a discrete action index cannot be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionIndexError {
+    /// The action mode is continuous.
+    NotDiscrete,
+    /// `index` is not below the number of discrete actions.
+    OutOfRange { index: usize, count: usize },
+}
+
+impl fmt::Display for ActionIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotDiscrete => write!(f, "action mode is continuous"),
+            Self::OutOfRange { index, count } => {
+                write!(
+                    f,
+                    "action index {index} is out of range for {count} actions"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionIndexError {}
+
+impl ActionMode {
+    /// Number of discrete actions, or `None` in continuous mode.
+    pub fn discrete_count(&self) -> Option<usize> {
+        match *self {
+            Self::Continuous => None,
+            Self::Discrete {
+                steering_levels,
+                throttle_levels,
+            } => Some(steering_levels as usize * throttle_levels as usize),
+        }
+    }
+
+    /// Maps a discrete action index to its [`CarAction`].
+    pub fn decode(&self, index: usize) -> Result<CarAction, ActionIndexError> {
+        let Self::Discrete {
+            steering_levels,
+            throttle_levels,
+        } = *self
+        else {
+            return Err(ActionIndexError::NotDiscrete);
+        };
+        let count = steering_levels as usize * throttle_levels as usize;
+        if index >= count {
+            return Err(ActionIndexError::OutOfRange { index, count });
+        }
+        let per_steering = throttle_levels as usize;
+        Ok(CarAction {
+            steering: level_value(index / per_steering, steering_levels, -1.0, 0.0),
+            throttle: level_value(index % per_steering, throttle_levels, 0.0, 1.0),
+        })
+    }
+
+    /// Index of the discrete action closest to `action`, level by level.
+    /// `None` in continuous mode or when the action set is empty.
+    pub fn nearest_index(&self, action: CarAction) -> Option<usize> {
+        let Self::Discrete {
+            steering_levels,
+            throttle_levels,
+        } = *self
+        else {
+            return None;
+        };
+        if steering_levels == 0 || throttle_levels == 0 {
+            return None;
+        }
+        let action = action.clamped();
+        let steering = nearest_level(action.steering, steering_levels, -1.0);
+        let throttle = nearest_level(action.throttle, throttle_levels, 0.0);
+        Some(steering * throttle_levels as usize + throttle)
+    }
+
+    /// The discrete action table, or `None` in continuous mode.
+    pub fn discrete_adapter(&self) -> Option<DiscreteActionAdapter> {
+        let count = self.discrete_count()?;
+        let actions = (0..count).filter_map(|index| self.decode(index).ok());
+        Some(DiscreteActionAdapter::new(actions.collect()))
+    }
+
+    /// Snaps `action` onto the discrete set; continuous mode passes it
+    /// through clamped.
+    pub fn quantize(&self, action: CarAction) -> CarAction {
+        self.nearest_index(action)
+            .and_then(|index| self.decode(index).ok())
+            .unwrap_or_else(|| action.clamped())
+    }
+}
+
+/// A fixed table of [`CarAction`]s addressed by index, for trainers that
+/// emit discrete actions.
+///
+/// [`ActionMode::discrete_adapter`] builds the table of a level grid. The
+/// default is the nine actions {left, straight, right} × {coast, half, full
+/// throttle}, steering-major:
+///
+/// | Index | Steering | Throttle |
+/// |-------|----------|----------|
+/// | 0 / 1 / 2 | `-1.0` | `0.0` / `0.5` / `1.0` |
+/// | 3 / 4 / 5 | `0.0` | `0.0` / `0.5` / `1.0` |
+/// | 6 / 7 / 8 | `1.0` | `0.0` / `0.5` / `1.0` |
+///
+/// There is no brake channel yet, so half throttle takes the place of a
+/// brake level.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiscreteActionAdapter {
+    actions: Vec<CarAction>,
+}
+
+impl Default for DiscreteActionAdapter {
+    fn default() -> Self {
+        ActionMode::Discrete {
+            steering_levels: 3,
+            throttle_levels: 3,
         }
+        .discrete_adapter()
+        .expect("discrete mode")
     }
 }
 
+impl DiscreteActionAdapter {
+    /// Table of `actions`, each clamped to its allowed ranges.
+    pub fn new(actions: Vec<CarAction>) -> Self {
+        Self {
+            actions: actions.into_iter().map(CarAction::clamped).collect(),
+        }
+    }
+
+    /// Number of discrete actions.
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Every action, in index order.
+    pub fn actions(&self) -> &[CarAction] {
+        &self.actions
+    }
+
+    /// The action at `index`.
+    pub fn action(&self, index: usize) -> Result<CarAction, ActionIndexError> {
+        self.actions
+            .get(index)
+            .copied()
+            .ok_or(ActionIndexError::OutOfRange {
+                index,
+                count: self.actions.len(),
+            })
+    }
+}
+
+/// Value of `level` among `levels` evenly spaced over `[low, 1]`, or
+/// `single` when there is only one level.
+fn level_value(level: usize, levels: u32, low: f32, single: f32) -> f32 {
+    if levels <= 1 {
+        return single;
+    }
+    low + (1.0 - low) * level as f32 / (levels - 1) as f32
+}
+
+/// Level among `levels` evenly spaced over `[low, 1]` closest to `value`.
+fn nearest_level(value: f32, levels: u32, low: f32) -> usize {
+    if levels <= 1 {
+        return 0;
+    }
+    let scaled = (value - low) / (1.0 - low) * (levels - 1) as f32;
+    (scaled.round() as usize).min(levels as usize - 1)
+}
+
 /// Resource holding the current desired and applied actions.
 ///
 /// Controllers should write `desired` once per fixed tick. Vehicle dynamics
 /// should consume `applied`, which may differ if smoothing is enabled.
-#[derive(Resource, Clone, Copy, Debug)]
+#[derive(Resource, Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct ActionState {
     pub desired: CarAction,
     pub applied: CarAction,
@@ -46,11 +256,14 @@ impl Default for ActionState {
 /// Optional action smoothing configuration.
 ///
 /// When enabled, `applied` is low-pass filtered towards `desired` each tick.
-#[derive(Resource, Clone, Copy, Debug)]
+/// The rate limits apply afterwards, whether or not the filter is enabled.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ActionSmoothing {
     pub enabled: bool,
     /// Time constant in seconds for first-order smoothing.
     pub time_constant_s: f32,
+    pub rate_limits: ActionRateLimits,
 }
 
 impl Default for ActionSmoothing {
@@ -58,65 +271,598 @@ impl Default for ActionSmoothing {
         Self {
             enabled: false,
             time_constant_s: 0.12,
+            rate_limits: ActionRateLimits::default(),
         }
     }
 }
 
+/// Maximum change per second of each applied action channel, like a
+/// physical actuator's slew rate. `None` leaves a channel unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionRateLimits {
+    pub steering_per_s: Option<f32>,
+    pub throttle_per_s: Option<f32>,
+}
+
+impl ActionRateLimits {
+    pub fn is_enabled(&self) -> bool {
+        self.steering_per_s.is_some() || self.throttle_per_s.is_some()
+    }
+
+    /// Moves `applied` towards `target` by at most each channel's rate over
+    /// `dt` seconds.
+    pub fn limit(&self, applied: CarAction, target: CarAction, dt: f32) -> CarAction {
+        let step = |from: f32, to: f32, rate: Option<f32>| match rate {
+            Some(rate) => {
+                let max_step = rate.max(0.0) * dt;
+                from + (to - from).clamp(-max_step, max_step)
+            }
+            None => to,
+        };
+        CarAction {
+            steering: step(applied.steering, target.steering, self.steering_per_s),
+            throttle: step(applied.throttle, target.throttle, self.throttle_per_s),
+        }
+    }
+}
+
+/// Optional centreline-following steering assist for manual driving.
+///
+/// The assist is a post-processing step between `desired` and `applied`: it
+/// adds a correction proportional to heading error and lateral offset to the
+/// manual steering input. `strength = 0` disables it entirely.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct SteeringAssist {
+    /// Blend factor in `[0, 1]` applied to the corrective steering.
+    pub strength: f32,
+    /// Gain on heading error normalised by π.
+    pub heading_gain: f32,
+    /// Gain on signed lateral offset normalised by `lateral_offset_scale`.
+    pub lateral_gain: f32,
+    /// Lateral offset in world units that maps to a unit lateral correction.
+    pub lateral_offset_scale: f32,
+}
+
+impl Default for SteeringAssist {
+    fn default() -> Self {
+        Self {
+            strength: 0.0,
+            heading_gain: 2.0,
+            lateral_gain: 1.0,
+            lateral_offset_scale: 75.0,
+        }
+    }
+}
+
+/// Blends a manual action with a centreline-following steering correction.
+///
+/// Positive heading error (tangent to the left of the car) steers left;
+/// positive lateral offset (car left of the centreline) steers right.
+/// Throttle passes through untouched.
+pub fn apply_steering_assist(
+    manual: CarAction,
+    heading_error: f32,
+    signed_lateral_offset: f32,
+    assist: &SteeringAssist,
+) -> CarAction {
+    let strength = assist.strength.clamp(0.0, 1.0);
+    if strength <= 0.0 {
+        return manual;
+    }
+
+    let heading_term = -assist.heading_gain * (heading_error / PI);
+    let lateral_term =
+        assist.lateral_gain * (signed_lateral_offset / assist.lateral_offset_scale.max(1e-3));
+    let correction = (heading_term + lateral_term).clamp(-1.0, 1.0);
+
+    CarAction {
+        steering: manual.steering + strength * correction,
+        throttle: manual.throttle,
+    }
+    .clamped()
+}
+
+/// Adjusts the steering-assist strength with `[` and `]` in steps of 0.1.
+pub fn steering_assist_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut assist: ResMut<SteeringAssist>,
+) {
+    let mut delta = 0.0;
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        delta -= 0.1;
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        delta += 0.1;
+    }
+    if delta != 0.0 {
+        assist.strength = (assist.strength + delta).clamp(0.0, 1.0);
+        info!("Steering assist strength: {:.1}", assist.strength);
+    }
+}
+
+/// Keyboard controller settings.
+///
+/// By default A/D steer at full lock and W is full throttle. With `analog`
+/// on, the keys move a virtual stick instead: holding A or D ramps steering
+/// towards ±1, releasing both returns it to centre, and each tap of W or S
+/// raises or lowers a held throttle target by `throttle_step`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyboardConfig {
+    pub analog: bool,
+    /// Steering change per second while A or D is held.
+    pub steering_ramp_per_s: f32,
+    /// Steering change per second back towards centre once both are released.
+    pub steering_return_per_s: f32,
+    /// Throttle target change per tap of W or S.
+    pub throttle_step: f32,
+}
+
+impl Default for KeyboardConfig {
+    fn default() -> Self {
+        Self {
+            analog: false,
+            steering_ramp_per_s: 3.0,
+            steering_return_per_s: 5.0,
+            throttle_step: 0.25,
+        }
+    }
+}
+
+/// Virtual stick position of the keyboard controller, shown on the HUD.
+///
+/// Tap detection compares against the previous tick's keys rather than
+/// `just_pressed`, which is per frame and can be seen by zero or several
+/// fixed ticks.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct KeyboardStick {
+    pub steering: f32,
+    pub throttle: f32,
+    throttle_up_held: bool,
+    throttle_down_held: bool,
+}
+
+impl KeyboardStick {
+    /// Advances the stick by one fixed tick of `dt` seconds and returns the
+    /// action it now reads.
+    pub fn update(
+        &mut self,
+        keyboard: &ButtonInput<KeyCode>,
+        config: &KeyboardConfig,
+        dt: f32,
+    ) -> CarAction {
+        let mut target = 0.0;
+        if keyboard.pressed(KeyCode::KeyA) {
+            target -= 1.0;
+        }
+        if keyboard.pressed(KeyCode::KeyD) {
+            target += 1.0;
+        }
+        let up = keyboard.pressed(KeyCode::KeyW);
+        let down = keyboard.pressed(KeyCode::KeyS);
+
+        if config.analog {
+            let rate = if target == 0.0 {
+                config.steering_return_per_s
+            } else {
+                config.steering_ramp_per_s
+            };
+            let max_step = rate.max(0.0) * dt;
+            self.steering += (target - self.steering).clamp(-max_step, max_step);
+            if up && !self.throttle_up_held {
+                self.throttle += config.throttle_step;
+            }
+            if down && !self.throttle_down_held {
+                self.throttle -= config.throttle_step;
+            }
+        } else {
+            self.steering = target;
+            self.throttle = if up { 1.0 } else { 0.0 };
+        }
+        self.throttle_up_held = up;
+        self.throttle_down_held = down;
+
+        let action = CarAction {
+            steering: self.steering,
+            throttle: self.throttle,
+        }
+        .clamped();
+        self.steering = action.steering;
+        self.throttle = action.throttle;
+        action
+    }
+
+    /// Short HUD label: a steering bar with the stick marked, and the
+    /// throttle target.
+    pub fn label(&self) -> String {
+        const HALF: f32 = 5.0;
+        let marker = (HALF + self.steering * HALF).round() as usize;
+        let bar: String = (0..=2 * HALF as usize)
+            .map(|i| {
+                if i == marker {
+                    'o'
+                } else if i == HALF as usize {
+                    '|'
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!("{bar} thr {:.2}", self.throttle)
+    }
+}
+
 /// Latches keyboard input into the fixed-tick `ActionState.desired`.
 ///
-/// This is a temporary controller used for Milestone 0 manual validation.
-/// It is intentionally minimal: A/D steer, W throttle.
+/// A/D steer, W throttles and, with [`KeyboardConfig::analog`], S lowers the
+/// throttle target. Smoothing and rate limits are left to
+/// [`action_smoothing_system`]. It also drives in gamepad mode while no pad
+/// is connected. The stick state lives in the shared [`KeyboardStick`]
+/// resource when there is one, and in a system-local stick otherwise.
 pub fn keyboard_action_input_system(
     mode: Option<Res<crate::brain::types::AgentMode>>,
+    gamepad: Option<Res<crate::agent::gamepad::ActiveGamepad>>,
+    config: Option<Res<KeyboardConfig>>,
+    time: Option<Res<Time<bevy::time::Fixed>>>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    stick: (Option<ResMut<KeyboardStick>>, Local<KeyboardStick>),
     mut action_state: ResMut<ActionState>,
 ) {
     if let Some(m) = mode {
-        if *m != crate::brain::types::AgentMode::Keyboard {
+        let pad_fallback = *m == crate::brain::types::AgentMode::Gamepad
+            && gamepad.is_none_or(|pad| !pad.is_connected());
+        if *m != crate::brain::types::AgentMode::Keyboard && !pad_fallback {
             return;
         }
     }
 
-    let mut steering = 0.0;
-    if keyboard.pressed(KeyCode::KeyA) {
-        steering -= 1.0;
-    }
-    if keyboard.pressed(KeyCode::KeyD) {
-        steering += 1.0;
-    }
-
-    let throttle = if keyboard.pressed(KeyCode::KeyW) {
-        1.0
-    } else {
-        0.0
+    let config = config.map_or_else(KeyboardConfig::default, |config| *config);
+    let dt = time
+        .map_or_else(
+            || Time::<bevy::time::Fixed>::default().timestep(),
+            |time| time.timestep(),
+        )
+        .as_secs_f32();
+    let (shared_stick, mut local_stick) = stick;
+    let stick = match shared_stick {
+        Some(stick) => stick.into_inner(),
+        None => &mut *local_stick,
     };
-
-    action_state.desired = CarAction { steering, throttle }.clamped();
+    action_state.desired = stick.update(&keyboard, &config, dt);
 }
 
 /// Updates `ActionState.applied` from `ActionState.desired`.
 ///
-/// When smoothing is disabled, this is a direct copy.
+/// In discrete [`ActionMode`] the desired action is first snapped onto the
+/// discrete set, so keyboard and continuous controllers drive with the same
+/// actions as index-based ones. In the manual (keyboard and gamepad) modes
+/// the steering assist is applied next, then the optional low-pass filter
+/// and rate limits, which every controller is subject to. With neither, the
+/// result is a direct copy. The filter and limits start each episode from
+/// the neutral action rather than the previous episode's last one. During an
+/// episode's start countdown ([`EpisodeConfig::countdown_ticks`]) the desired
+/// throttle is zero.
 pub fn action_smoothing_system(
     time: Res<Time<bevy::time::Fixed>>,
+    mode: Option<Res<crate::brain::types::AgentMode>>,
+    action_mode: Option<Res<ActionMode>>,
     smoothing: Res<ActionSmoothing>,
+    assist: Res<SteeringAssist>,
+    episode_state: Option<Res<EpisodeState>>,
+    episode_config: Option<Res<EpisodeConfig>>,
+    sensor_query: Query<&SensorReadings, With<Car>>,
     mut action_state: ResMut<ActionState>,
+    mut last_episode: Local<Option<u32>>,
 ) {
-    let desired = action_state.desired.clamped();
+    let mut desired = action_mode
+        .map_or(ActionMode::Continuous, |mode| *mode)
+        .quantize(action_state.desired);
+
+    let manual = mode.is_none_or(|m| m.is_manual());
+    if manual && let Ok(sensors) = sensor_query.single() {
+        desired = apply_steering_assist(
+            desired,
+            sensors.heading_error,
+            sensors.signed_lateral_offset,
+            &assist,
+        );
+    }
+
+    // Throttle stays off until the episode's start countdown ends.
+    let in_countdown = episode_state
+        .as_deref()
+        .zip(episode_config.as_deref())
+        .is_some_and(|(state, config)| state.countdown_remaining(config) > 0);
+    if in_countdown {
+        desired.throttle = 0.0;
+    }
 
-    if !smoothing.enabled {
+    if !smoothing.enabled && !smoothing.rate_limits.is_enabled() {
         action_state.applied = desired;
         return;
     }
 
-    let dt = time.delta_secs();
-    let tau = smoothing.time_constant_s.max(1e-4);
-    let alpha = 1.0 - (-dt / tau).exp();
+    let episode = episode_state.map(|state| state.current_episode);
+    if *last_episode != episode {
+        *last_episode = episode;
+        action_state.applied = CarAction::default();
+    }
 
+    let dt = time.delta_secs();
     let applied = action_state.applied;
-    action_state.applied = CarAction {
-        steering: applied.steering + (desired.steering - applied.steering) * alpha,
-        throttle: applied.throttle + (desired.throttle - applied.throttle) * alpha,
+    let filtered = if smoothing.enabled {
+        let tau = smoothing.time_constant_s.max(1e-4);
+        let alpha = 1.0 - (-dt / tau).exp();
+        CarAction {
+            steering: applied.steering + (desired.steering - applied.steering) * alpha,
+            throttle: applied.throttle + (desired.throttle - applied.throttle) * alpha,
+        }
+    } else {
+        desired
+    };
+    action_state.applied = smoothing.rate_limits.limit(applied, filtered, dt).clamped();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ActionIndexError, ActionMode, ActionRateLimits, CarAction, DiscreteActionAdapter,
+        KeyboardConfig, KeyboardStick, SteeringAssist, apply_steering_assist,
+    };
+    use bevy::input::ButtonInput;
+    use bevy::prelude::KeyCode;
+
+    fn action(steering: f32, throttle: f32) -> CarAction {
+        CarAction { steering, throttle }
+    }
+
+    #[test]
+    fn full_lock_steering_reversal_takes_the_configured_number_of_ticks() {
+        // 1 unit/s at 4 Hz moves 0.25 per tick, so -1 to +1 takes 8 ticks.
+        let limits = ActionRateLimits {
+            steering_per_s: Some(1.0),
+            throttle_per_s: None,
+        };
+        let dt = 0.25;
+        let target = action(1.0, 0.8);
+        let mut applied = action(-1.0, 0.0);
+        let mut ticks = 0;
+        while applied.steering < 1.0 {
+            applied = limits.limit(applied, target, dt);
+            ticks += 1;
+            assert!(ticks <= 8, "still at {applied:?} after {ticks} ticks");
+            if ticks == 7 {
+                assert_eq!(applied.steering, 0.75);
+            }
+            // The unlimited channel follows immediately.
+            assert_eq!(applied.throttle, 0.8);
+        }
+        assert_eq!(ticks, 8);
+        assert_eq!(applied, target);
+
+        let unlimited = ActionRateLimits::default();
+        assert!(!unlimited.is_enabled());
+        assert_eq!(unlimited.limit(action(-1.0, 0.0), target, dt), target);
+    }
+
+    #[test]
+    fn analog_keyboard_steering_ramps_and_returns_in_fixed_ticks() {
+        // At 8 Hz a 2/s ramp moves 0.25 per tick and a 4/s return 0.5.
+        let config = KeyboardConfig {
+            analog: true,
+            steering_ramp_per_s: 2.0,
+            steering_return_per_s: 4.0,
+            throttle_step: 0.25,
+        };
+        let dt = 0.125;
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        let mut stick = KeyboardStick::default();
+
+        keyboard.press(KeyCode::KeyD);
+        let ramp: Vec<f32> = (0..5)
+            .map(|_| stick.update(&keyboard, &config, dt).steering)
+            .collect();
+        assert_eq!(ramp, [0.25, 0.5, 0.75, 1.0, 1.0]);
+
+        keyboard.release(KeyCode::KeyD);
+        let centre: Vec<f32> = (0..3)
+            .map(|_| stick.update(&keyboard, &config, dt).steering)
+            .collect();
+        assert_eq!(centre, [0.5, 0.0, 0.0]);
+
+        // Reversing from full right ramps through centre at the ramp rate.
+        stick.steering = 1.0;
+        keyboard.press(KeyCode::KeyA);
+        let ticks = (1..)
+            .find(|_| stick.update(&keyboard, &config, dt).steering == -1.0)
+            .expect("reaches full left");
+        assert_eq!(ticks, 8);
+    }
+
+    #[test]
+    fn keyboard_throttle_taps_step_a_held_target() {
+        let config = KeyboardConfig {
+            analog: true,
+            ..KeyboardConfig::default()
+        };
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        let mut stick = KeyboardStick::default();
+        let mut tick =
+            |keyboard: &ButtonInput<KeyCode>| stick.update(keyboard, &config, 1.0 / 60.0).throttle;
+
+        // Holding W for several ticks is one tap.
+        keyboard.press(KeyCode::KeyW);
+        assert_eq!(tick(&keyboard), 0.25);
+        assert_eq!(tick(&keyboard), 0.25);
+        keyboard.release(KeyCode::KeyW);
+        assert_eq!(tick(&keyboard), 0.25);
+        for _ in 0..5 {
+            keyboard.press(KeyCode::KeyW);
+            tick(&keyboard);
+            keyboard.release(KeyCode::KeyW);
+            tick(&keyboard);
+        }
+        assert_eq!(tick(&keyboard), 1.0);
+
+        keyboard.press(KeyCode::KeyS);
+        assert_eq!(tick(&keyboard), 0.75);
+
+        // Without analog the keys drive at full scale and S does nothing.
+        let digital = KeyboardConfig::default();
+        keyboard.press(KeyCode::KeyA);
+        let driven = stick.update(&keyboard, &digital, 1.0 / 60.0);
+        assert_eq!(driven, action(-1.0, 0.0));
+        keyboard.press(KeyCode::KeyW);
+        let driven = stick.update(&keyboard, &digital, 1.0 / 60.0);
+        assert_eq!(driven, action(-1.0, 1.0));
+    }
+
+    #[test]
+    fn default_discrete_adapter_maps_the_documented_nine_actions() {
+        let adapter = DiscreteActionAdapter::default();
+        assert_eq!(adapter.len(), 9);
+        let expected = [
+            action(-1.0, 0.0),
+            action(-1.0, 0.5),
+            action(-1.0, 1.0),
+            action(0.0, 0.0),
+            action(0.0, 0.5),
+            action(0.0, 1.0),
+            action(1.0, 0.0),
+            action(1.0, 0.5),
+            action(1.0, 1.0),
+        ];
+        for (index, expected) in expected.into_iter().enumerate() {
+            assert_eq!(adapter.action(index), Ok(expected), "index {index}");
+        }
+        assert_eq!(
+            adapter.action(9),
+            Err(ActionIndexError::OutOfRange { index: 9, count: 9 })
+        );
+
+        assert_eq!(ActionMode::Continuous.discrete_adapter(), None);
+        let custom = DiscreteActionAdapter::new(vec![action(2.0, -1.0)]);
+        assert_eq!(custom.actions(), [action(1.0, 0.0)]);
+    }
+
+    #[test]
+    fn discrete_indices_map_to_the_documented_actions() {
+        let six = ActionMode::Discrete {
+            steering_levels: 3,
+            throttle_levels: 2,
+        };
+        assert_eq!(six.discrete_count(), Some(6));
+        let decoded: Vec<CarAction> = (0..6).map(|i| six.decode(i).expect("in range")).collect();
+        assert_eq!(
+            decoded,
+            [
+                action(-1.0, 0.0),
+                action(-1.0, 1.0),
+                action(0.0, 0.0),
+                action(0.0, 1.0),
+                action(1.0, 0.0),
+                action(1.0, 1.0),
+            ]
+        );
+
+        let fine = ActionMode::Discrete {
+            steering_levels: 5,
+            throttle_levels: 3,
+        };
+        assert_eq!(fine.decode(7), Ok(action(0.0, 0.5)));
+        assert_eq!(fine.decode(14), Ok(action(1.0, 1.0)));
+        for index in 0..15 {
+            let decoded = fine.decode(index).expect("in range");
+            assert_eq!(fine.nearest_index(decoded), Some(index));
+        }
+
+        let cruise = ActionMode::Discrete {
+            steering_levels: 1,
+            throttle_levels: 1,
+        };
+        assert_eq!(cruise.decode(0), Ok(action(0.0, 1.0)));
+    }
+
+    #[test]
+    fn out_of_range_and_continuous_indices_are_rejected() {
+        let six = ActionMode::Discrete {
+            steering_levels: 3,
+            throttle_levels: 2,
+        };
+        assert_eq!(
+            six.decode(6),
+            Err(ActionIndexError::OutOfRange { index: 6, count: 6 })
+        );
+        assert_eq!(
+            ActionMode::Continuous.decode(0),
+            Err(ActionIndexError::NotDiscrete)
+        );
+        let empty = ActionMode::Discrete {
+            steering_levels: 0,
+            throttle_levels: 2,
+        };
+        assert_eq!(
+            empty.decode(0),
+            Err(ActionIndexError::OutOfRange { index: 0, count: 0 })
+        );
+    }
+
+    #[test]
+    fn keyboard_actions_quantize_onto_the_discrete_set() {
+        let six: ActionMode =
+            ron::from_str("Discrete(steering_levels: 3, throttle_levels: 2)").expect("parses");
+        for steering in [-1.0, 0.0, 1.0] {
+            for throttle in [0.0, 1.0] {
+                assert_eq!(
+                    six.quantize(action(steering, throttle)),
+                    action(steering, throttle)
+                );
+            }
+        }
+        assert_eq!(six.quantize(action(-0.4, 0.7)), action(0.0, 1.0));
+        assert_eq!(six.quantize(action(0.6, f32::NAN)), action(1.0, 0.0));
+        assert_eq!(
+            ActionMode::Continuous.quantize(action(0.3, 0.4)),
+            action(0.3, 0.4)
+        );
+    }
+
+    #[test]
+    fn full_strength_assist_steers_back_towards_the_centreline() {
+        let assist = SteeringAssist {
+            strength: 1.0,
+            ..SteeringAssist::default()
+        };
+        let idle = CarAction {
+            steering: 0.0,
+            throttle: 1.0,
+        };
+
+        // Left of the centreline, heading aligned: steer right.
+        let left_of_line = apply_steering_assist(idle, 0.0, 30.0, &assist);
+        assert!(left_of_line.steering > 0.0);
+        assert_eq!(left_of_line.throttle, 1.0);
+
+        // Right of the centreline: steer left.
+        let right_of_line = apply_steering_assist(idle, 0.0, -30.0, &assist);
+        assert!(right_of_line.steering < 0.0);
+
+        // Tangent to the left of the car's heading: steer left.
+        let heading_left = apply_steering_assist(idle, 0.4, 0.0, &assist);
+        assert!(heading_left.steering < 0.0);
+    }
+
+    #[test]
+    fn zero_strength_assist_passes_manual_input_through() {
+        let assist = SteeringAssist::default();
+        let manual = CarAction {
+            steering: 0.35,
+            throttle: 0.8,
+        };
+
+        let out = apply_steering_assist(manual, 0.9, -40.0, &assist);
+        assert_eq!(out.steering, manual.steering);
+        assert_eq!(out.throttle, manual.throttle);
     }
-    .clamped();
 }
@@ -0,0 +1,383 @@
+//! Runtime-selectable action sources.
+//!
+//! [`AgentMode`] names the controller that owns `ActionState.desired` for the
+//! current tick. Keyboard and gamepad input and the learned policy are ordinary systems
+//! gated on the mode; every other source implements [`Controller`] and is
+//! registered in the [`Controllers`] resource under its mode. Each driver
+//! returns early unless its mode is active, so exactly one source writes the
+//! desired action per fixed tick.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+
+use crate::agent::action::{ActionState, CarAction};
+use crate::agent::mlp::register_mlp_controller;
+use crate::agent::observation::ObservationVector;
+use crate::agent::onnx::register_onnx_controller;
+use crate::agent::pid::{PidController, PidControllerConfig};
+use crate::agent::script::register_script_controller;
+use crate::agent::scripted::{ScriptedController, ScriptedControllerConfig};
+use crate::brain::types::AgentMode;
+use crate::game::car::Car;
+use crate::game::episode::{EpisodeResetRequest, EpisodeState};
+use crate::game::progress::TrackProgress;
+
+/// Read-only view of the controlled car handed to a [`Controller`] each tick.
+pub struct ControllerContext<'a> {
+    pub observation: &'a ObservationVector,
+    pub progress: &'a TrackProgress,
+    pub car: &'a Car,
+    pub position: Vec2,
+    /// Unit heading of the car.
+    pub forward: Vec2,
+    pub episode: u32,
+    /// Fixed ticks already taken in `episode`.
+    pub tick: u32,
+    /// Fixed timestep in seconds.
+    pub dt: f32,
+}
+
+impl<'a> ControllerContext<'a> {
+    pub fn new(
+        observation: &'a ObservationVector,
+        progress: &'a TrackProgress,
+        car: &'a Car,
+        transform: &Transform,
+        episode_state: Option<&EpisodeState>,
+        dt: f32,
+    ) -> Self {
+        let (episode, tick) = episode_state.map_or((0, 0), |state| {
+            (state.current_episode, state.ticks_in_episode)
+        });
+        Self {
+            observation,
+            progress,
+            car,
+            position: transform.translation.truncate(),
+            forward: (transform.rotation * Vec3::X)
+                .truncate()
+                .normalize_or(Vec2::X),
+            episode,
+            tick,
+            dt,
+        }
+    }
+}
+
+/// A source of driving actions.
+pub trait Controller: Send + Sync {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction;
+
+    /// Called before the first action of each episode.
+    fn begin_episode(&mut self, _episode: u32) {}
+
+    /// Whether the controller wants the current episode to end on the tick
+    /// of its last [`Self::act`]; the driver then files an
+    /// [`EpisodeResetRequest`], so the episode ends as a timeout.
+    fn wants_episode_end(&self) -> bool {
+        false
+    }
+
+    /// Appends named internal terms from the last [`Self::act`] for the
+    /// per-tick trace.
+    fn telemetry(&self, _out: &mut Vec<(&'static str, f32)>) {}
+}
+
+/// Plays back a recorded action sequence by episode tick, coasting once the
+/// recording runs out.
+#[derive(Default)]
+pub struct ReplayController {
+    pub actions: Vec<CarAction>,
+}
+
+impl Controller for ReplayController {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+        self.actions
+            .get(ctx.tick as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Drives with whatever action an outside process last wrote to its shared
+/// [`ExternalActionHandle`].
+#[derive(Default)]
+pub struct ExternalController {
+    latest: ExternalActionHandle,
+}
+
+/// Writer side of an [`ExternalController`].
+#[derive(Clone, Default)]
+pub struct ExternalActionHandle {
+    latest: Arc<Mutex<CarAction>>,
+    timeouts: Arc<AtomicU32>,
+}
+
+impl ExternalActionHandle {
+    pub fn set(&self, action: CarAction) {
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = action.clamped();
+        }
+    }
+
+    pub fn get(&self) -> CarAction {
+        self.latest.lock().map(|latest| *latest).unwrap_or_default()
+    }
+
+    /// Counts a tick the outside process left unanswered.
+    pub fn record_timeout(&self) {
+        self.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Control timeouts recorded so far.
+    pub fn timeouts(&self) -> u32 {
+        self.timeouts.load(Ordering::Relaxed)
+    }
+}
+
+impl ExternalController {
+    pub fn handle(&self) -> ExternalActionHandle {
+        self.latest.clone()
+    }
+}
+
+impl Controller for ExternalController {
+    fn act(&mut self, _ctx: &ControllerContext) -> CarAction {
+        self.latest.get()
+    }
+
+    fn telemetry(&self, out: &mut Vec<(&'static str, f32)>) {
+        out.push(("control_timeouts", self.latest.timeouts() as f32));
+    }
+}
+
+/// Registered [`Controller`]s by mode. Keyboard, gamepad, and learned modes are driven
+/// by their own systems and are never looked up here.
+#[derive(Resource)]
+pub struct Controllers {
+    registered: HashMap<AgentMode, Box<dyn Controller>>,
+    external: ExternalActionHandle,
+    episode: Option<u32>,
+    telemetry: Vec<(&'static str, f32)>,
+}
+
+/// Builds the scripted and PID controllers from the live
+/// [`ScriptedControllerConfig`] and [`PidControllerConfig`], the action
+/// script in the replay slot when one is configured, and the MLP and ONNX
+/// controllers when their weights are configured.
+impl FromWorld for Controllers {
+    fn from_world(world: &mut World) -> Self {
+        let mut controllers = Self::with_configs(
+            world
+                .get_resource::<ScriptedControllerConfig>()
+                .copied()
+                .unwrap_or_default(),
+            world
+                .get_resource::<PidControllerConfig>()
+                .copied()
+                .unwrap_or_default(),
+        );
+        register_script_controller(world, &mut controllers);
+        register_mlp_controller(world, &mut controllers);
+        register_onnx_controller(world, &mut controllers);
+        controllers
+    }
+}
+
+impl Controllers {
+    fn with_configs(scripted: ScriptedControllerConfig, pid: PidControllerConfig) -> Self {
+        let external = ExternalController::default();
+        let mut controllers = Self {
+            registered: HashMap::new(),
+            external: external.handle(),
+            episode: None,
+            telemetry: Vec::new(),
+        };
+        controllers.register(AgentMode::Scripted, ScriptedController::new(scripted));
+        controllers.register(AgentMode::Pid, PidController::new(pid));
+        controllers.register(AgentMode::Replay, ReplayController::default());
+        controllers.register(AgentMode::External, external);
+        controllers
+    }
+
+    /// Installs `controller` for `mode`, replacing any previous one.
+    pub fn register(&mut self, mode: AgentMode, controller: impl Controller + 'static) {
+        self.registered.insert(mode, Box::new(controller));
+    }
+
+    /// Writer for the default [`ExternalController`] registered under
+    /// [`AgentMode::External`].
+    pub fn external_handle(&self) -> ExternalActionHandle {
+        self.external.clone()
+    }
+
+    /// Internal terms reported by the controller that acted this tick;
+    /// empty when no registered controller is active.
+    pub fn telemetry(&self) -> &[(&'static str, f32)] {
+        &self.telemetry
+    }
+}
+
+/// Writes `ActionState.desired` from the registered controller for the active
+/// mode, if there is one.
+pub fn registered_controller_system(
+    time: Res<Time>,
+    mode: Option<Res<AgentMode>>,
+    controllers: Option<ResMut<Controllers>>,
+    episode_state: Option<Res<EpisodeState>>,
+    reset_request: Option<ResMut<EpisodeResetRequest>>,
+    car_query: Query<(&ObservationVector, &TrackProgress, &Car, &Transform)>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let (Some(mode), Some(mut controllers)) = (mode, controllers) else {
+        return;
+    };
+    controllers.telemetry.clear();
+    let Ok((observation, progress, car, transform)) = car_query.single() else {
+        return;
+    };
+    let ctx = ControllerContext::new(
+        observation,
+        progress,
+        car,
+        transform,
+        episode_state.as_deref(),
+        time.delta_secs(),
+    );
+
+    let new_episode = controllers.episode != Some(ctx.episode);
+    controllers.episode = Some(ctx.episode);
+    let Controllers {
+        registered,
+        telemetry,
+        ..
+    } = &mut *controllers;
+    let Some(controller) = registered.get_mut(&*mode) else {
+        return;
+    };
+    if new_episode {
+        controller.begin_episode(ctx.episode);
+    }
+
+    action_state.desired = controller.act(&ctx).clamped();
+    controller.telemetry(telemetry);
+    if let Some(mut request) = reset_request.filter(|_| controller.wants_episode_end()) {
+        request.pending = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Controllers, ExternalController, registered_controller_system};
+    use crate::agent::action::{ActionState, CarAction, keyboard_action_input_system};
+    use crate::agent::observation::{ObservationConfig, ObservationVector};
+    use crate::agent::scripted::{ScriptedController, ScriptedControllerConfig};
+    use crate::brain::a2c::{A2cBrain, a2c_act_system};
+    use crate::brain::types::AgentMode;
+    use crate::game::car::Car;
+    use crate::game::progress::TrackProgress;
+    use bevy::prelude::*;
+
+    fn action(steering: f32, throttle: f32) -> CarAction {
+        CarAction { steering, throttle }
+    }
+
+    fn constant(steering: f32, throttle: f32) -> ScriptedController {
+        ScriptedController::new(ScriptedControllerConfig::Constant { steering, throttle })
+    }
+
+    fn app_in_mode(mode: AgentMode) -> App {
+        let obs_dim = ObservationConfig::default().observation_dim();
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::KeyA);
+        keyboard.press(KeyCode::KeyW);
+
+        let mut controllers = Controllers::from_world(&mut World::new());
+        controllers.register(AgentMode::Scripted, constant(0.25, 0.5));
+        controllers.register(AgentMode::Pid, constant(0.375, 0.75));
+        controllers.register(AgentMode::Replay, constant(0.5, 0.25));
+        let external = ExternalController::default();
+        external.handle().set(action(0.75, 0.125));
+        controllers.register(AgentMode::External, external);
+
+        let mut app = App::new();
+        app.insert_resource(mode)
+            .insert_resource(keyboard)
+            .insert_resource(controllers)
+            .insert_resource(A2cBrain::seeded(7, obs_dim))
+            .init_resource::<ActionState>()
+            .init_resource::<Time>()
+            .add_systems(
+                Update,
+                (
+                    keyboard_action_input_system,
+                    registered_controller_system,
+                    a2c_act_system,
+                )
+                    .chain(),
+            );
+        app.world_mut().spawn((
+            Car::default(),
+            ObservationVector {
+                values: vec![0.5; obs_dim],
+            },
+            TrackProgress::default(),
+            Transform::default(),
+        ));
+        app
+    }
+
+    fn desired_in(mode: AgentMode) -> (CarAction, usize) {
+        let mut app = app_in_mode(mode);
+        app.world_mut().resource_mut::<ActionState>().desired = action(0.0, 0.0);
+        app.update();
+        (
+            app.world().resource::<ActionState>().desired,
+            app.world().resource::<A2cBrain>().step_counter,
+        )
+    }
+
+    #[test]
+    fn only_the_selected_controller_writes_the_action() {
+        assert_eq!(desired_in(AgentMode::Keyboard), (action(-1.0, 1.0), 0));
+        // No pad is connected, so gamepad mode falls back to the keyboard.
+        assert_eq!(desired_in(AgentMode::Gamepad), (action(-1.0, 1.0), 0));
+        assert_eq!(desired_in(AgentMode::Scripted), (action(0.25, 0.5), 0));
+        assert_eq!(desired_in(AgentMode::Pid), (action(0.375, 0.75), 0));
+        assert_eq!(desired_in(AgentMode::Replay), (action(0.5, 0.25), 0));
+        assert_eq!(desired_in(AgentMode::External), (action(0.75, 0.125), 0));
+
+        let (learned, steps) = desired_in(AgentMode::Learned);
+        assert_eq!(steps, 1, "only the learned mode queries the policy");
+        for other in [
+            action(0.0, 0.0),
+            action(-1.0, 1.0),
+            action(0.25, 0.5),
+            action(0.375, 0.75),
+            action(0.5, 0.25),
+            action(0.75, 0.125),
+        ] {
+            assert_ne!(learned, other);
+        }
+    }
+
+    #[test]
+    fn modes_cycle_through_every_controller_and_parse_by_name() {
+        let mut mode = AgentMode::Keyboard;
+        let mut seen = Vec::new();
+        for _ in 0..AgentMode::ALL.len() {
+            seen.push(mode);
+            mode = mode.next();
+        }
+        assert_eq!(mode, AgentMode::Keyboard);
+        assert_eq!(seen, AgentMode::ALL);
+        for mode in AgentMode::ALL {
+            assert_eq!(AgentMode::from_name(mode.name()), Some(mode));
+        }
+        assert_eq!(AgentMode::from_name("External"), Some(AgentMode::External));
+        assert_eq!(AgentMode::from_name("ai"), None);
+    }
+}
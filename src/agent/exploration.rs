@@ -0,0 +1,294 @@
+//! Additive exploration noise on controller actions.
+//!
+//! [`exploration_noise_system`] runs after every action source has written
+//! `ActionState.desired` and before smoothing, so the noisy action is the one
+//! the car drives with and the one `ActionState.applied` carries into the
+//! transition recorder, traces, and instant replays. Manual driving, the
+//! `replay` controller, and [`EvaluationMode`] runs (tournaments, `--eval`)
+//! are never perturbed. [`crate::eval::env::HeadlessEnv`] runs the system
+//! too, so headless training (Python, TCP lockstep, `--evolve`) explores.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionState, CarAction};
+use crate::brain::types::AgentMode;
+use crate::sim::evaluation::EvaluationMode;
+use crate::sim::rng::{EpisodeRng, STREAM_ACTION_NOISE, SimRng};
+
+/// Noise process added to each action channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExplorationNoiseKind {
+    #[default]
+    Off,
+    /// Independent `N(0, sigma)` every tick.
+    Gaussian,
+    /// Ornstein-Uhlenbeck: `dx = -theta x dt + sigma dW`, integrated with
+    /// Euler-Maruyama at the fixed timestep. Stationary standard deviation
+    /// `sigma / sqrt(2 theta)`; correlation after `t` seconds `exp(-theta t)`.
+    OrnsteinUhlenbeck,
+}
+
+/// Parameters of one action channel. A zero sigma draws nothing and leaves
+/// the channel untouched.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoiseChannel {
+    pub sigma: f32,
+    /// Mean reversion rate per second; Ornstein-Uhlenbeck only.
+    pub theta: f32,
+}
+
+impl Default for NoiseChannel {
+    fn default() -> Self {
+        Self {
+            sigma: 0.0,
+            theta: 1.0,
+        }
+    }
+}
+
+/// Exploration noise settings (config `exploration`, off by default).
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExplorationConfig {
+    pub kind: ExplorationNoiseKind,
+    pub steering: NoiseChannel,
+    pub throttle: NoiseChannel,
+}
+
+impl ExplorationConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.kind != ExplorationNoiseKind::Off
+            && (self.steering.sigma > 0.0 || self.throttle.sigma > 0.0)
+    }
+}
+
+/// Noise process state: the current offset per channel and the episode's
+/// draw stream.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct ExplorationNoise {
+    pub offset: CarAction,
+    /// Episode the stream was seeded for.
+    episode: Option<u32>,
+    rng: SimRng,
+}
+
+impl Default for ExplorationNoise {
+    fn default() -> Self {
+        Self {
+            offset: CarAction::default(),
+            episode: None,
+            rng: SimRng::new(0),
+        }
+    }
+}
+
+impl ExplorationNoise {
+    /// Restarts the process at zero with the episode's action-noise stream,
+    /// so an episode's noise depends only on its seed.
+    pub fn begin_episode(&mut self, episode_rng: &EpisodeRng) {
+        self.offset = CarAction::default();
+        self.episode = Some(episode_rng.episode);
+        self.rng = SimRng::new(episode_rng.seed).stream(STREAM_ACTION_NOISE);
+    }
+
+    /// Advances the process by `dt` seconds and returns the new offset.
+    pub fn sample(&mut self, config: &ExplorationConfig, dt: f32) -> CarAction {
+        self.offset = CarAction {
+            steering: advance(
+                self.offset.steering,
+                config.kind,
+                config.steering,
+                dt,
+                &mut self.rng,
+            ),
+            throttle: advance(
+                self.offset.throttle,
+                config.kind,
+                config.throttle,
+                dt,
+                &mut self.rng,
+            ),
+        };
+        self.offset
+    }
+}
+
+fn advance(
+    value: f32,
+    kind: ExplorationNoiseKind,
+    channel: NoiseChannel,
+    dt: f32,
+    rng: &mut SimRng,
+) -> f32 {
+    if channel.sigma <= 0.0 {
+        return 0.0;
+    }
+    match kind {
+        ExplorationNoiseKind::Off => 0.0,
+        ExplorationNoiseKind::Gaussian => rng.normal(0.0, channel.sigma),
+        ExplorationNoiseKind::OrnsteinUhlenbeck => {
+            let theta = channel.theta.max(0.0);
+            value - theta * value * dt + rng.normal(0.0, channel.sigma * dt.sqrt())
+        }
+    }
+}
+
+/// Adds the next noise sample to `ActionState.desired`, clamped to the
+/// action ranges.
+pub fn exploration_noise_system(
+    time: Res<Time<Fixed>>,
+    config: Option<Res<ExplorationConfig>>,
+    mode: Option<Res<AgentMode>>,
+    evaluation: Option<Res<EvaluationMode>>,
+    episode_rng: Option<Res<EpisodeRng>>,
+    mut noise: ResMut<ExplorationNoise>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let Some(config) = config.filter(|config| config.is_enabled()) else {
+        return;
+    };
+    if mode.is_some_and(|mode| mode.is_manual() || *mode == AgentMode::Replay)
+        || evaluation.is_some_and(|evaluation| evaluation.enabled)
+    {
+        return;
+    }
+    let Some(episode_rng) = episode_rng else {
+        return;
+    };
+    if noise.episode != Some(episode_rng.episode) {
+        noise.begin_episode(&episode_rng);
+    }
+
+    let offset = noise.sample(&config, time.timestep().as_secs_f32());
+    let desired = action_state.desired;
+    action_state.desired = CarAction {
+        steering: desired.steering + offset.steering,
+        throttle: desired.throttle + offset.throttle,
+    }
+    .clamped();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ExplorationConfig, ExplorationNoise, ExplorationNoiseKind, NoiseChannel,
+        exploration_noise_system,
+    };
+    use crate::agent::action::{ActionState, CarAction};
+    use crate::brain::types::AgentMode;
+    use crate::sim::rng::EpisodeRng;
+    use bevy::prelude::*;
+
+    fn ou(sigma: f32, theta: f32) -> ExplorationConfig {
+        ExplorationConfig {
+            kind: ExplorationNoiseKind::OrnsteinUhlenbeck,
+            steering: NoiseChannel { sigma, theta },
+            throttle: NoiseChannel::default(),
+        }
+    }
+
+    fn autocorrelation(samples: &[f32], lag: usize) -> f32 {
+        let n = samples.len() - lag;
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        let variance =
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / samples.len() as f32;
+        let covariance = samples
+            .iter()
+            .zip(&samples[lag..])
+            .map(|(a, b)| (a - mean) * (b - mean))
+            .sum::<f32>()
+            / n as f32;
+        covariance / variance
+    }
+
+    #[test]
+    fn ornstein_uhlenbeck_matches_its_stationary_statistics() {
+        let (sigma, theta, dt) = (0.3, 2.0, 1.0 / 60.0);
+        let config = ou(sigma, theta);
+        let mut noise = ExplorationNoise::default();
+        noise.begin_episode(&EpisodeRng::from_seed(1, 0xC0FFEE));
+        let samples: Vec<f32> = (0..400_000)
+            .map(|_| noise.sample(&config, dt).steering)
+            .collect();
+        let samples = &samples[1_000..];
+
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        let variance =
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / samples.len() as f32;
+        let expected_variance = sigma * sigma / (2.0 * theta);
+        assert!(mean.abs() < 0.02, "mean {mean}");
+        assert!(
+            (variance / expected_variance - 1.0).abs() < 0.1,
+            "variance {variance} vs {expected_variance}"
+        );
+        for lag in [1, 15, 30, 60] {
+            let expected = (-theta * dt * lag as f32).exp();
+            let measured = autocorrelation(samples, lag);
+            assert!(
+                (measured - expected).abs() < 0.05,
+                "lag {lag}: {measured} vs {expected}"
+            );
+        }
+
+        // Gaussian noise is uncorrelated tick to tick.
+        let gaussian = ExplorationConfig {
+            kind: ExplorationNoiseKind::Gaussian,
+            ..config
+        };
+        let white: Vec<f32> = (0..100_000)
+            .map(|_| noise.sample(&gaussian, dt).steering)
+            .collect();
+        assert!(autocorrelation(&white, 1).abs() < 0.02);
+    }
+
+    fn noisy_run(seed: u64, mode: AgentMode) -> Vec<CarAction> {
+        let mut app = App::new();
+        app.insert_resource(ExplorationConfig {
+            throttle: NoiseChannel {
+                sigma: 0.2,
+                theta: 3.0,
+            },
+            ..ou(0.4, 1.0)
+        })
+        .insert_resource(Time::<Fixed>::from_hz(60.0))
+        .insert_resource(mode)
+        .insert_resource(EpisodeRng::from_seed(1, seed))
+        .init_resource::<ExplorationNoise>()
+        .init_resource::<ActionState>()
+        .add_systems(Update, exploration_noise_system);
+
+        (0..120)
+            .map(|_| {
+                app.world_mut().resource_mut::<ActionState>().desired = CarAction {
+                    steering: 0.0,
+                    throttle: 0.5,
+                };
+                app.update();
+                app.world().resource::<ActionState>().desired
+            })
+            .collect()
+    }
+
+    #[test]
+    fn noise_replays_exactly_from_the_seed_and_skips_manual_driving() {
+        let first = noisy_run(42, AgentMode::Scripted);
+        assert_eq!(first, noisy_run(42, AgentMode::Scripted));
+        assert_ne!(first, noisy_run(43, AgentMode::Scripted));
+        assert!(first.iter().any(|action| action.steering != 0.0));
+        assert!(
+            first
+                .iter()
+                .all(|action| (0.0..=1.0).contains(&action.throttle))
+        );
+
+        let clean = CarAction {
+            steering: 0.0,
+            throttle: 0.5,
+        };
+        for mode in [AgentMode::Keyboard, AgentMode::Gamepad, AgentMode::Replay] {
+            assert!(noisy_run(42, mode).iter().all(|action| *action == clean));
+        }
+    }
+}
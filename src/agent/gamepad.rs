@@ -0,0 +1,203 @@
+//! Gamepad controller.
+//!
+//! In [`AgentMode::Gamepad`] the first connected pad drives the car: left
+//! stick X steers and the right trigger sets the throttle, both shaped by a
+//! deadzone and a power response curve. Like the keyboard path, the pad is
+//! sampled once per fixed tick in `SimSet::Input`, so everything downstream
+//! sees one latched action per tick. With no pad connected the keyboard
+//! drives instead, and pads may be plugged in or out at any time.
+//!
+//! The left trigger is reserved for braking; it is not read until
+//! [`CarAction`] gains a brake channel.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionState, CarAction};
+use crate::brain::types::AgentMode;
+
+/// Stick and trigger shaping for the gamepad controller.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    /// Stick deflection below this reads as centred.
+    pub steering_deadzone: f32,
+    /// Exponent of the steering response; above 1 softens small deflections.
+    pub steering_exponent: f32,
+    /// Trigger travel below this reads as released.
+    pub throttle_deadzone: f32,
+    pub throttle_exponent: f32,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            steering_deadzone: 0.1,
+            steering_exponent: 1.5,
+            throttle_deadzone: 0.05,
+            throttle_exponent: 1.0,
+        }
+    }
+}
+
+/// Maps a raw axis value in `[-1, 1]` through a deadzone and response curve.
+///
+/// The magnitude past the deadzone is rescaled to `[0, 1]` and raised to
+/// `exponent`; the sign is kept. Non-finite input reads as zero.
+pub fn shape_axis(value: f32, deadzone: f32, exponent: f32) -> f32 {
+    if !value.is_finite() {
+        return 0.0;
+    }
+    let deadzone = deadzone.clamp(0.0, 0.99);
+    let magnitude = ((value.abs() - deadzone) / (1.0 - deadzone)).clamp(0.0, 1.0);
+    magnitude.powf(exponent.max(f32::EPSILON)) * value.signum()
+}
+
+/// The pad currently driving in gamepad mode, shown on the HUD.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct ActiveGamepad {
+    pub entity: Option<Entity>,
+    pub name: Option<String>,
+}
+
+impl ActiveGamepad {
+    pub fn is_connected(&self) -> bool {
+        self.entity.is_some()
+    }
+
+    /// Short label for the HUD.
+    pub fn label(&self) -> &str {
+        match (&self.entity, &self.name) {
+            (Some(_), Some(name)) => name,
+            (Some(_), None) => "gamepad",
+            (None, _) => "no pad, keyboard",
+        }
+    }
+}
+
+/// Tracks pad hot-plugging, picking the lowest-numbered connected pad so the
+/// choice does not depend on query order.
+pub fn active_gamepad_system(
+    gamepads: Query<(Entity, Option<&Name>), With<Gamepad>>,
+    mut active: ResMut<ActiveGamepad>,
+) {
+    let first = gamepads.iter().min_by_key(|(entity, _)| *entity);
+    let entity = first.map(|(entity, _)| entity);
+    if entity == active.entity {
+        return;
+    }
+    active.entity = entity;
+    active.name = first.and_then(|(_, name)| name.map(|name| name.as_str().to_string()));
+    match entity {
+        Some(_) => info!("Gamepad connected: {}", active.label()),
+        None => info!("Gamepad disconnected; keyboard drives in gamepad mode."),
+    }
+}
+
+/// Latches the active pad into the fixed-tick `ActionState.desired` while in
+/// [`AgentMode::Gamepad`]. Does nothing without a pad, leaving the tick to
+/// the keyboard.
+pub fn gamepad_action_input_system(
+    mode: Option<Res<AgentMode>>,
+    config: Res<GamepadConfig>,
+    active: Res<ActiveGamepad>,
+    gamepads: Query<&Gamepad>,
+    mut action_state: ResMut<ActionState>,
+) {
+    if mode.is_none_or(|mode| *mode != AgentMode::Gamepad) {
+        return;
+    }
+    let Some(gamepad) = active.entity.and_then(|entity| gamepads.get(entity).ok()) else {
+        return;
+    };
+
+    let stick = gamepad.get(GamepadAxis::LeftStickX).unwrap_or(0.0);
+    let trigger = gamepad.get(GamepadButton::RightTrigger2).unwrap_or(0.0);
+    action_state.desired = CarAction {
+        steering: shape_axis(stick, config.steering_deadzone, config.steering_exponent),
+        throttle: shape_axis(trigger, config.throttle_deadzone, config.throttle_exponent),
+    }
+    .clamped();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ActiveGamepad, GamepadConfig, active_gamepad_system, gamepad_action_input_system,
+        shape_axis,
+    };
+    use crate::agent::action::{ActionState, CarAction, keyboard_action_input_system};
+    use crate::brain::types::AgentMode;
+    use bevy::prelude::*;
+
+    #[test]
+    fn shaping_applies_deadzone_and_curve() {
+        assert_eq!(shape_axis(0.05, 0.1, 1.5), 0.0);
+        assert_eq!(shape_axis(-0.1, 0.1, 1.5), 0.0);
+        assert_eq!(shape_axis(1.0, 0.1, 1.5), 1.0);
+        assert_eq!(shape_axis(-1.0, 0.1, 1.5), -1.0);
+        assert!((shape_axis(0.55, 0.1, 1.0) - 0.5).abs() < 1e-6);
+        assert!((shape_axis(-0.55, 0.1, 2.0) + 0.25).abs() < 1e-6);
+        assert_eq!(shape_axis(f32::NAN, 0.1, 1.0), 0.0);
+    }
+
+    #[test]
+    fn pad_drives_when_connected_and_keyboard_otherwise() {
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::KeyD);
+        keyboard.press(KeyCode::KeyW);
+
+        let mut app = App::new();
+        app.insert_resource(AgentMode::Gamepad)
+            .insert_resource(keyboard)
+            .insert_resource(GamepadConfig {
+                steering_deadzone: 0.0,
+                steering_exponent: 1.0,
+                throttle_deadzone: 0.0,
+                throttle_exponent: 1.0,
+            })
+            .init_resource::<ActiveGamepad>()
+            .init_resource::<ActionState>()
+            .add_systems(
+                Update,
+                (
+                    active_gamepad_system,
+                    keyboard_action_input_system,
+                    gamepad_action_input_system,
+                )
+                    .chain(),
+            );
+        let desired = |app: &mut App| {
+            app.update();
+            app.world().resource::<ActionState>().desired
+        };
+        let keyboard_action = CarAction {
+            steering: 1.0,
+            throttle: 1.0,
+        };
+
+        assert_eq!(desired(&mut app), keyboard_action);
+        assert!(!app.world().resource::<ActiveGamepad>().is_connected());
+
+        let mut gamepad = Gamepad::default();
+        gamepad.analog_mut().set(GamepadAxis::LeftStickX, -0.5);
+        gamepad.analog_mut().set(GamepadButton::RightTrigger2, 0.25);
+        let pad = app.world_mut().spawn((Name::new("Test Pad"), gamepad)).id();
+        assert_eq!(
+            desired(&mut app),
+            CarAction {
+                steering: -0.5,
+                throttle: 0.25,
+            }
+        );
+        assert_eq!(app.world().resource::<ActiveGamepad>().label(), "Test Pad");
+
+        app.world_mut().despawn(pad);
+        assert_eq!(desired(&mut app), keyboard_action);
+
+        // Outside gamepad mode a connected pad is ignored.
+        app.world_mut().spawn(Gamepad::default());
+        app.insert_resource(AgentMode::Keyboard);
+        assert_eq!(desired(&mut app), keyboard_action);
+    }
+}
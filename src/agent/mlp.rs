@@ -0,0 +1,119 @@
+//! Built-in MLP policy controller.
+//!
+//! [`MlpController`] drives with a [`PolicyMlp`] loaded from the weights file
+//! in [`MlpControllerConfig`] and is registered under [`AgentMode::Mlp`]. The
+//! network reads the observation vector and its two `tanh` outputs map to
+//! steering directly and to throttle as `(out + 1) / 2`.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::CarAction;
+use crate::agent::controller::{Controller, ControllerContext, Controllers};
+use crate::agent::observation::ObservationConfig;
+use crate::brain::common::policy_mlp::{PolicyMlp, PolicyMlpError};
+use crate::brain::types::AgentMode;
+
+/// Weights for the MLP controller (config `mlp`).
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MlpControllerConfig {
+    /// JSON (`.json`) or binary weights file loaded at startup; `None`
+    /// leaves the mode unregistered.
+    pub weights_path: Option<String>,
+}
+
+/// Drives with a fixed [`PolicyMlp`].
+pub struct MlpController {
+    net: PolicyMlp,
+}
+
+impl MlpController {
+    /// Wraps `net` after checking that it reads `observation_dim` features
+    /// and produces the two action values.
+    pub fn new(net: PolicyMlp, observation_dim: usize) -> Result<Self, PolicyMlpError> {
+        let expected = [observation_dim, 2];
+        let found = [net.input_dim(), net.output_dim()];
+        if found != expected {
+            return Err(PolicyMlpError::Format(format!(
+                "network maps {} -> {} but the controller needs {} -> {}",
+                found[0], found[1], expected[0], expected[1]
+            )));
+        }
+        Ok(Self { net })
+    }
+}
+
+impl Controller for MlpController {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+        let output = self.net.forward(&ctx.observation.values);
+        CarAction {
+            steering: output[0],
+            throttle: 0.5 * (output[1] + 1.0),
+        }
+    }
+}
+
+/// Loads the configured weights into `controllers` under [`AgentMode::Mlp`],
+/// logging instead when they are missing or do not fit the observation.
+pub fn register_mlp_controller(world: &World, controllers: &mut Controllers) {
+    let Some(path) = world
+        .get_resource::<MlpControllerConfig>()
+        .and_then(|config| config.weights_path.clone())
+    else {
+        return;
+    };
+    let observation_dim = world
+        .get_resource::<ObservationConfig>()
+        .cloned()
+        .unwrap_or_default()
+        .observation_dim();
+    match PolicyMlp::load(std::path::Path::new(&path))
+        .and_then(|net| MlpController::new(net, observation_dim))
+    {
+        Ok(controller) => {
+            info!("Loaded MLP policy {path}.");
+            controllers.register(AgentMode::Mlp, controller);
+        }
+        Err(err) => warn!("MLP controller disabled: {path}: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MlpController;
+    use crate::agent::controller::{Controller, ControllerContext};
+    use crate::agent::observation::ObservationVector;
+    use crate::brain::common::policy_mlp::PolicyMlp;
+    use crate::game::car::Car;
+    use crate::game::progress::TrackProgress;
+    use bevy::prelude::Transform;
+
+    #[test]
+    fn outputs_map_to_steering_and_throttle_ranges() {
+        // Steering copies tanh(x0); throttle saturates high from bias 10.
+        let mut params = vec![0.0; PolicyMlp::param_count(&[3, 2])];
+        params[0] = 1.0;
+        params[7] = 10.0;
+        let net = PolicyMlp::new(vec![3, 2], params).expect("valid network");
+        assert!(MlpController::new(net.clone(), 4).is_err());
+        let mut controller = MlpController::new(net, 3).expect("fits");
+
+        let observation = ObservationVector {
+            values: vec![0.5, 0.0, 0.0],
+        };
+        let (progress, car) = (TrackProgress::default(), Car::default());
+        let ctx = ControllerContext::new(
+            &observation,
+            &progress,
+            &car,
+            &Transform::default(),
+            None,
+            1.0 / 60.0,
+        );
+        let action = controller.act(&ctx);
+
+        assert!((action.steering - 0.5_f32.tanh()).abs() < 1e-6);
+        assert!((action.throttle - 1.0).abs() < 1e-6);
+    }
+}
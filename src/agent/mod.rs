@@ -9,7 +9,17 @@
 //!   fixed simulation tick.
 
 pub mod action;
+pub mod controller;
+pub mod exploration;
+pub mod gamepad;
+pub mod mlp;
 pub mod observation;
+pub mod onnx;
+pub mod pid;
 pub mod plugin;
+pub mod script;
+pub mod scripted;
+pub mod spaces;
+pub mod vision;
 
 pub use plugin::AgentPlugin;
@@ -1,33 +1,59 @@
+use std::collections::VecDeque;
 use std::f32::consts::PI;
+use std::fmt;
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
+use crate::agent::action::{ActionState, CarAction};
+use crate::agent::spaces::{FeatureSpec, Normalization};
+use crate::brain::types::AgentMode;
 use crate::game::car::Car;
+use crate::game::collision::CarBox;
+use crate::game::episode::{EpisodeConfig, EpisodeResetRequest, EpisodeState};
 use crate::game::progress::TrackProgress;
-use crate::maps::grid::TrackGrid;
+use crate::maps::centerline::TrackCenterline;
+use crate::maps::grid::{RayHit, RayHitClass, TrackGrid};
 use crate::maps::track::Track;
+use crate::sim::evaluation::EvaluationMode;
+use crate::sim::rng::{EpisodeRng, SimRng};
 
-/// Number of ray sensors in the observation model.
-pub const NUM_RAYS: usize = 11;
+/// Upper bound on configured rays.
+pub const MAX_RAYS: usize = 64;
 /// Number of lookahead samples taken from the centreline.
 pub const NUM_LOOKAHEAD_SAMPLES: usize = 4;
 /// Number of scalar lookahead features per sample.
 pub const LOOKAHEAD_FEATURES_PER_SAMPLE: usize = 2;
-/// Total observation dimension consumed by controllers.
-pub const OBSERVATION_DIM: usize =
-    NUM_RAYS + 4 + NUM_LOOKAHEAD_SAMPLES * LOOKAHEAD_FEATURES_PER_SAMPLE;
+/// Number of scalar kinematic features following the rays.
+pub const NUM_KINEMATIC_FEATURES: usize = 4;
+/// Upper bound on occupancy-grid cells (`rows * cols`).
+pub const MAX_OCCUPANCY_CELLS: usize = 1024;
 
 /// Raycast sensor readings and derived kinematics for one car.
-#[derive(Component, Clone, Debug)]
+///
+/// Ray storage is sized from [`ObservationConfig::ray_angles`] when the car
+/// spawns and reused every tick.
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
 pub struct SensorReadings {
     /// Ray distances in world units, one per configured ray angle.
-    pub ray_distances: [f32; NUM_RAYS],
+    pub ray_distances: Vec<f32>,
+    /// World-space ray origins (the rotated mount points) for debug
+    /// rendering.
+    pub ray_origins: Vec<Vec2>,
     /// World-space hit points for debug rendering.
-    pub ray_hits: [Vec2; NUM_RAYS],
+    pub ray_hits: Vec<Vec2>,
+    /// What stopped each ray.
+    pub ray_classes: Vec<RayHitClass>,
     /// Ray directions in world-space for debug rendering.
-    pub ray_directions: [Vec2; NUM_RAYS],
+    pub ray_directions: Vec<Vec2>,
+    /// Surface normal at each ray's hit, facing back along the ray; zero
+    /// for a ray that reached its maximum range.
+    pub ray_normals: Vec<Vec2>,
     /// Current scalar speed in world units / second.
     pub speed: f32,
+    /// Velocity component along the centreline tangent in world units /
+    /// second. Negative when driving backwards along the track.
+    pub speed_along_track: f32,
     /// Signed lateral offset from the centreline in world units.
     ///
     /// Positive means the car is left of the centreline relative to the
@@ -43,104 +69,849 @@ pub struct SensorReadings {
     pub lookahead_heading_deltas: [f32; NUM_LOOKAHEAD_SAMPLES],
     /// Approximate curvature (radians/world-unit) at lookahead distances.
     pub lookahead_curvatures: [f32; NUM_LOOKAHEAD_SAMPLES],
+    /// Centreline distance in world units to the next corner entry, capped
+    /// at [`ObservationConfig::corner_scan_max`].
+    pub corner_distance: f32,
+    /// Velocity in the car's body frame, in world units / second: `x` is
+    /// longitudinal (forward positive), `y` lateral slip (left positive).
+    pub body_velocity: Vec2,
+    /// Position within the track grid's bounds, `(0, 0)` south-west to
+    /// `(1, 1)` north-east.
+    pub grid_position: Vec2,
+    /// Egocentric occupancy patch, row-major: `1.0` road, `0.0` off-road.
+    /// Empty unless [`ObservationConfig::include_occupancy_grid`] is set.
+    pub occupancy: Vec<f32>,
+    /// World-space centre of each occupancy cell for debug rendering.
+    pub occupancy_centers: Vec<Vec2>,
+    /// [`TrackProgress::fraction`] this tick.
+    pub lap_fraction: f32,
+    /// Episode time left before the timeout, as a fraction of `timeout_s`:
+    /// `1.0` on an episode's first tick, approaching `0.0` at the deadline.
+    pub time_remaining: f32,
+    /// Fixed ticks since the rays and occupancy patch were last cast; see
+    /// [`ObservationConfig::sensor_update_interval`]. `Some(0)` on a cast
+    /// tick, `None` before the first cast.
+    pub ray_age_ticks: Option<u32>,
 }
 
 impl Default for SensorReadings {
     fn default() -> Self {
+        Self::for_config(&ObservationConfig::default())
+    }
+}
+
+impl SensorReadings {
+    /// Zeroed readings with storage for every configured ray.
+    pub fn for_config(config: &ObservationConfig) -> Self {
+        let num_rays = config.num_rays();
         Self {
-            ray_distances: [0.0; NUM_RAYS],
-            ray_hits: [Vec2::ZERO; NUM_RAYS],
-            ray_directions: [Vec2::X; NUM_RAYS],
+            ray_distances: vec![0.0; num_rays],
+            ray_origins: vec![Vec2::ZERO; num_rays],
+            ray_hits: vec![Vec2::ZERO; num_rays],
+            ray_classes: vec![RayHitClass::None; num_rays],
+            ray_directions: vec![Vec2::X; num_rays],
+            ray_normals: vec![Vec2::ZERO; num_rays],
             speed: 0.0,
+            speed_along_track: 0.0,
             signed_lateral_offset: 0.0,
             heading_error: 0.0,
             angular_velocity: 0.0,
             previous_heading: 0.0,
             lookahead_heading_deltas: [0.0; NUM_LOOKAHEAD_SAMPLES],
             lookahead_curvatures: [0.0; NUM_LOOKAHEAD_SAMPLES],
+            corner_distance: 0.0,
+            body_velocity: Vec2::ZERO,
+            grid_position: Vec2::ZERO,
+            occupancy: vec![0.0; config.occupancy_cells()],
+            occupancy_centers: vec![Vec2::ZERO; config.occupancy_cells()],
+            lap_fraction: 0.0,
+            time_remaining: 0.0,
+            ray_age_ticks: None,
         }
     }
+
+    /// Forgets what the readings remember from before a teleport to
+    /// `heading`: the yaw-rate sample, and the held rays so the next tick
+    /// casts.
+    pub fn reset_history(&mut self, heading: f32) {
+        self.previous_heading = heading;
+        self.angular_velocity = 0.0;
+        self.ray_age_ticks = None;
+    }
+
+    /// Resizes ray storage to `num_rays`; a no-op once sized.
+    fn resize_rays(&mut self, num_rays: usize) {
+        self.ray_distances.resize(num_rays, 0.0);
+        self.ray_origins.resize(num_rays, Vec2::ZERO);
+        self.ray_hits.resize(num_rays, Vec2::ZERO);
+        self.ray_classes.resize(num_rays, RayHitClass::None);
+        self.ray_directions.resize(num_rays, Vec2::X);
+        self.ray_normals.resize(num_rays, Vec2::ZERO);
+    }
 }
 
-/// Fixed-size, normalised observation vector consumed by controllers.
+/// Normalised observation vector consumed by controllers, of length
+/// [`ObservationConfig::observation_dim`].
 #[derive(Component, Clone, Debug)]
 pub struct ObservationVector {
     /// Feature vector in stable order:
     /// [ray distances..., speed, signed_lateral_offset, heading_error,
     ///  angular_velocity,
-    ///  lookahead_heading_delta_i, lookahead_curvature_i...]
-    pub values: [f32; OBSERVATION_DIM],
+    ///  lookahead_heading_delta_i, lookahead_curvature_i...,
+    ///  corner_distance?, body_longitudinal?, body_lateral?,
+    ///  previous_steering?, previous_throttle?, grid_x?, grid_y?,
+    ///  ray_class_i?..., occupancy_r_c?..., lap_progress?,
+    ///  time_remaining?, ray_incidence_i?..., speed_along_track?]
+    /// where `?` features are present only when enabled. The layout is the
+    /// sequence of [`ObservationConfig::feature_blocks`].
+    pub values: Vec<f32>,
 }
 
 impl Default for ObservationVector {
     fn default() -> Self {
+        Self::for_config(&ObservationConfig::default())
+    }
+}
+
+impl ObservationVector {
+    /// A zero vector of the configured dimension.
+    pub fn for_config(config: &ObservationConfig) -> Self {
         Self {
-            values: [0.0; OBSERVATION_DIM],
+            values: vec![0.0; config.observation_dim()],
         }
     }
 }
 
+/// Recent clean observations for one car, used to present delayed
+/// observations when [`ObservationConfig::observation_delay_ticks`] is set.
+#[derive(Component, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ObservationDelayBuffer {
+    /// Episode the buffered observations belong to.
+    episode: u32,
+    history: VecDeque<Vec<f32>>,
+}
+
+impl ObservationDelayBuffer {
+    /// Drops the buffered observations, so the next one is presented
+    /// undelayed as if the episode had just started.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+}
+
+/// Named ray fans, selected with [`ObservationConfig::ray_preset`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RayPreset {
+    /// The default 11 rays, denser ahead, reaching ±150°.
+    Default11,
+    /// 7 rays 15° apart within ±45°.
+    ForwardNarrow,
+    /// 9 rays 40° apart around the whole car.
+    SurroundSparse,
+    /// 19 rays 10° apart within ±90°.
+    Dense19,
+}
+
+impl RayPreset {
+    /// Every preset, in `F10` cycling order.
+    pub const ALL: [RayPreset; 4] = [
+        RayPreset::Default11,
+        RayPreset::ForwardNarrow,
+        RayPreset::SurroundSparse,
+        RayPreset::Dense19,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RayPreset::Default11 => "default_11",
+            RayPreset::ForwardNarrow => "forward_narrow",
+            RayPreset::SurroundSparse => "surround_sparse",
+            RayPreset::Dense19 => "dense_19",
+        }
+    }
+
+    /// Ray angles in radians, left to right.
+    pub fn angles(self) -> Vec<f32> {
+        let degrees: Vec<f32> = match self {
+            RayPreset::Default11 => vec![
+                -150.0, -90.0, -60.0, -35.0, -15.0, 0.0, 15.0, 35.0, 60.0, 90.0, 150.0,
+            ],
+            RayPreset::ForwardNarrow => (-3..=3).map(|i| i as f32 * 15.0).collect(),
+            RayPreset::SurroundSparse => (-4..=4).map(|i| i as f32 * 40.0).collect(),
+            RayPreset::Dense19 => (-9..=9).map(|i| i as f32 * 10.0).collect(),
+        };
+        degrees.into_iter().map(f32::to_radians).collect()
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|preset| *preset == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// A ray preset chosen with `F10`, waiting for the episode to end.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct RayPresetSwitch {
+    pub pending: Option<RayPreset>,
+}
+
 /// Sensor and observation configuration.
-#[derive(Resource, Clone, Copy, Debug)]
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ObservationConfig {
     /// Raycast max range in world units.
     pub ray_max_range: f32,
-    /// Raycast march step in world units.
+    /// Per-ray max ranges overriding `ray_max_range`, which each ray's
+    /// distance feature is also normalised by. Empty uses the shared range
+    /// for every ray; otherwise one entry per ray angle.
+    pub ray_max_ranges: Vec<f32>,
+    /// Raycast march step in world units (marching caster only).
     pub ray_step: f32,
+    /// Casts rays analytically with [`TrackGrid::raycast_boundary`]. When
+    /// disabled, rays march in `ray_step` increments and bisect the last
+    /// step; that caster is kept as a reference for the exact one.
+    pub exact_raycast: bool,
     /// Speed normalisation scale in world units / second.
     pub speed_norm_max: f32,
     /// Lateral-offset normalisation scale in world units.
     pub lateral_offset_norm_max: f32,
     /// Angular-velocity normalisation scale in radians / second.
     pub angular_velocity_norm_max: f32,
-    /// Relative ray angles around the car forward vector, in radians.
-    pub ray_angles: [f32; NUM_RAYS],
+    /// Relative ray angles around the car forward vector, in radians. The
+    /// number of entries sets the ray count.
+    pub ray_angles: Vec<f32>,
+    /// Named fan replacing `ray_angles` when the config is loaded; also the
+    /// starting point for `F10` cycling.
+    pub ray_preset: Option<RayPreset>,
+    /// Car-frame point every ray is cast from, in world units: `x` forward,
+    /// `y` left. Zero casts from the car centre.
+    pub ray_mount_offset: Vec2,
+    /// Per-ray mount points overriding `ray_mount_offset`. Empty uses the
+    /// shared offset for every ray; otherwise one entry per ray angle.
+    pub ray_mount_offsets: Vec<Vec2>,
     /// Centreline lookahead distances in world units.
     pub lookahead_distances: [f32; NUM_LOOKAHEAD_SAMPLES],
     /// Curvature normalisation scale in radians / world-unit.
     pub curvature_norm_max: f32,
+    /// Appends the car's velocity along the centreline tangent, normalised
+    /// by `speed_norm_max`, growing the observation by one.
+    pub include_speed_along_track: bool,
+    /// Appends the distance-to-next-corner feature after the lookahead
+    /// samples, growing the observation by one.
+    pub include_corner_distance: bool,
+    /// Curvature (radians / world-unit) above which the centreline counts
+    /// as a corner.
+    pub corner_curvature_threshold: f32,
+    /// How far ahead the corner scan looks, in world units; also the
+    /// feature's normalisation scale.
+    pub corner_scan_max: f32,
+    /// Corner scan resolution in world units.
+    pub corner_scan_step: f32,
+    /// Appends longitudinal and lateral body-frame velocity, normalised by
+    /// `speed_norm_max`, growing the observation by two.
+    pub include_body_velocity: bool,
+    /// Appends the action applied this tick (the one the controller's next
+    /// decision follows), growing the observation by two. Zero on the first
+    /// observation of each episode.
+    pub include_previous_action: bool,
+    /// Appends the car's normalised position within the grid bounds. This
+    /// is privileged global information, intended for ablations.
+    pub include_grid_position: bool,
+    /// Appends one feature per ray: the [`RayHitClass`] of its hit as
+    /// [`RayHitClass::normalized_id`], growing the observation by the ray
+    /// count.
+    pub include_ray_hit_classes: bool,
+    /// Appends an egocentric occupancy patch of `occupancy_rows *
+    /// occupancy_cols` cells sampled around the car, growing the
+    /// observation by the cell count.
+    pub include_occupancy_grid: bool,
+    /// Lateral cells of the occupancy patch; row 0 is the leftmost.
+    pub occupancy_rows: usize,
+    /// Longitudinal cells of the occupancy patch; column 0 is the rearmost.
+    pub occupancy_cols: usize,
+    /// Side length of one occupancy cell in world units.
+    pub occupancy_cell_size: f32,
+    /// Appends the lap fraction ([`TrackProgress::fraction`]). Progress is
+    /// otherwise kept out of the observation, so this must be opted into.
+    pub include_lap_progress: bool,
+    /// Appends the episode time remaining, `(timeout_ticks - ticks) /
+    /// timeout_ticks`, so the agent can pace itself.
+    pub include_time_remaining: bool,
+    /// Appends one feature per ray: `|cos|` of the angle between the ray
+    /// and the surface normal at its hit, `1` head-on and `0` for a glancing
+    /// hit or a ray that reached its maximum range.
+    pub include_ray_incidence: bool,
+    /// Fixed ticks between ray and occupancy casts. In between, those
+    /// readings are reused while kinematics and centreline features stay
+    /// current. Casts fall on episode ticks that are multiples of the
+    /// interval, so every episode starts with fresh rays and the schedule
+    /// replays exactly. `1` casts every tick.
+    pub sensor_update_interval: u32,
+    /// Gaussian feature noise for training; off by default.
+    pub noise: ObservationNoise,
+    /// Fixed ticks between computing an observation and presenting it to
+    /// the controller. `0` presents the current observation.
+    pub observation_delay_ticks: u32,
+}
+
+/// Per-feature Gaussian noise standard deviations, in normalised feature
+/// units. Noise is added after normalisation and the result clamped back to
+/// the feature's range; a sigma of zero leaves the feature untouched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObservationNoise {
+    pub ray_distance: f32,
+    pub speed: f32,
+    pub heading_error: f32,
+    pub yaw_rate: f32,
+}
+
+impl ObservationNoise {
+    pub fn is_enabled(&self) -> bool {
+        self.sigmas().iter().any(|&sigma| sigma > 0.0)
+    }
+
+    fn sigmas(&self) -> [f32; 4] {
+        [
+            self.ray_distance,
+            self.speed,
+            self.heading_error,
+            self.yaw_rate,
+        ]
+    }
+
+    /// Perturbs the noisy features of a normalised observation in place.
+    fn apply(&self, values: &mut [f32], num_rays: usize, rng: &mut SimRng) {
+        for value in &mut values[..num_rays] {
+            perturb(value, self.ray_distance, 0.0, rng);
+        }
+        perturb(&mut values[num_rays], self.speed, 0.0, rng);
+        perturb(&mut values[num_rays + 2], self.heading_error, -1.0, rng);
+        perturb(&mut values[num_rays + 3], self.yaw_rate, -1.0, rng);
+    }
+}
+
+/// Adds `N(0, sigma)` to `value` and clamps it to `[min, 1]`. Draws nothing
+/// when `sigma` is zero, so clean features stay bit-identical.
+fn perturb(value: &mut f32, sigma: f32, min: f32, rng: &mut SimRng) {
+    if sigma > 0.0 {
+        *value = rng.normal(*value, sigma).clamp(min, 1.0);
+    }
 }
 
 impl Default for ObservationConfig {
     fn default() -> Self {
         Self {
             ray_max_range: 375.0,
+            ray_max_ranges: Vec::new(),
             ray_step: 3.0,
+            exact_raycast: true,
             speed_norm_max: 900.0,
             lateral_offset_norm_max: 75.0,
             angular_velocity_norm_max: 8.0,
-            ray_angles: [
-                -150f32.to_radians(),
-                -90f32.to_radians(),
-                -60f32.to_radians(),
-                -35f32.to_radians(),
-                -15f32.to_radians(),
-                0.0,
-                15f32.to_radians(),
-                35f32.to_radians(),
-                60f32.to_radians(),
-                90f32.to_radians(),
-                150f32.to_radians(),
-            ],
+            ray_angles: RayPreset::Default11.angles(),
+            ray_preset: None,
+            ray_mount_offset: Vec2::ZERO,
+            ray_mount_offsets: Vec::new(),
             lookahead_distances: [50.0, 100.0, 175.0, 260.0],
             curvature_norm_max: 0.05,
+            include_speed_along_track: false,
+            include_corner_distance: false,
+            corner_curvature_threshold: 0.005,
+            corner_scan_max: 400.0,
+            corner_scan_step: 10.0,
+            include_body_velocity: false,
+            include_previous_action: false,
+            include_grid_position: false,
+            include_ray_hit_classes: false,
+            include_occupancy_grid: false,
+            occupancy_rows: 9,
+            occupancy_cols: 9,
+            occupancy_cell_size: 20.0,
+            include_lap_progress: false,
+            include_time_remaining: false,
+            include_ray_incidence: false,
+            sensor_update_interval: 1,
+            noise: ObservationNoise::default(),
+            observation_delay_ticks: 0,
+        }
+    }
+}
+
+impl ObservationConfig {
+    pub fn num_rays(&self) -> usize {
+        self.ray_angles.len()
+    }
+
+    /// Switches to `preset`'s ray fan. Per-ray mounts and ranges laid out
+    /// for a fan of a different size are dropped in favour of
+    /// `ray_mount_offset` and `ray_max_range`.
+    pub fn set_ray_preset(&mut self, preset: RayPreset) {
+        self.ray_preset = Some(preset);
+        self.ray_angles = preset.angles();
+        if self.ray_mount_offsets.len() != self.ray_angles.len() {
+            self.ray_mount_offsets.clear();
+        }
+        if self.ray_max_ranges.len() != self.ray_angles.len() {
+            self.ray_max_ranges.clear();
+        }
+    }
+
+    /// Max range of ray `index` in world units.
+    pub fn ray_range(&self, index: usize) -> f32 {
+        self.ray_max_ranges
+            .get(index)
+            .copied()
+            .unwrap_or(self.ray_max_range)
+    }
+
+    /// Car-frame mount point of ray `index`.
+    pub fn ray_mount(&self, index: usize) -> Vec2 {
+        self.ray_mount_offsets
+            .get(index)
+            .copied()
+            .unwrap_or(self.ray_mount_offset)
+    }
+
+    /// Cells in the occupancy patch; zero when the patch is disabled.
+    pub fn occupancy_cells(&self) -> usize {
+        if self.include_occupancy_grid {
+            self.occupancy_rows * self.occupancy_cols
+        } else {
+            0
+        }
+    }
+
+    /// Car-frame centre of occupancy cell `(row, col)` in world units: `x`
+    /// forward, `y` left. The patch is centred on the car, row 0 on its
+    /// left and column 0 at its rear.
+    pub fn occupancy_cell_offset(&self, row: usize, col: usize) -> Vec2 {
+        let forward =
+            (col as f32 - (self.occupancy_cols as f32 - 1.0) * 0.5) * self.occupancy_cell_size;
+        let left =
+            ((self.occupancy_rows as f32 - 1.0) * 0.5 - row as f32) * self.occupancy_cell_size;
+        Vec2::new(forward, left)
+    }
+
+    /// Length of the observation vector this config produces.
+    pub fn observation_dim(&self) -> usize {
+        self.feature_blocks().map(|block| block.len(self)).sum()
+    }
+
+    /// Enabled feature blocks, in observation order.
+    pub fn feature_blocks(&self) -> impl Iterator<Item = FeatureBlock> + '_ {
+        FeatureBlock::ALL
+            .into_iter()
+            .filter(|block| block.is_enabled(self))
+    }
+
+    /// Rejects ray layouts the sensor pipeline cannot use.
+    pub fn validate(&self) -> Result<(), ObservationConfigError> {
+        if self.ray_angles.is_empty() {
+            return Err(ObservationConfigError::NoRays);
+        }
+        if self.ray_angles.len() > MAX_RAYS {
+            return Err(ObservationConfigError::TooManyRays(self.ray_angles.len()));
+        }
+        if let Some(index) = self.ray_angles.iter().position(|angle| !angle.is_finite()) {
+            return Err(ObservationConfigError::NonFiniteRayAngle(index));
+        }
+        if !self.ray_mount_offsets.is_empty() && self.ray_mount_offsets.len() != self.num_rays() {
+            return Err(ObservationConfigError::RayMountCountMismatch {
+                rays: self.num_rays(),
+                mounts: self.ray_mount_offsets.len(),
+            });
+        }
+        if !self.ray_max_ranges.is_empty() && self.ray_max_ranges.len() != self.num_rays() {
+            return Err(ObservationConfigError::RayRangeCountMismatch {
+                rays: self.num_rays(),
+                ranges: self.ray_max_ranges.len(),
+            });
+        }
+        let valid_range = |range: &f32| range.is_finite() && *range > 0.0;
+        if !valid_range(&self.ray_max_range) || !self.ray_max_ranges.iter().all(valid_range) {
+            return Err(ObservationConfigError::InvalidRayRange);
+        }
+        if self.sensor_update_interval == 0 {
+            return Err(ObservationConfigError::InvalidSensorUpdateInterval);
+        }
+        if !self.ray_mount_offset.is_finite()
+            || self
+                .ray_mount_offsets
+                .iter()
+                .any(|mount| !mount.is_finite())
+        {
+            return Err(ObservationConfigError::NonFiniteRayMount);
+        }
+        if self
+            .noise
+            .sigmas()
+            .iter()
+            .any(|sigma| !sigma.is_finite() || *sigma < 0.0)
+        {
+            return Err(ObservationConfigError::InvalidNoiseSigma);
+        }
+        if self.include_occupancy_grid {
+            let cells = self.occupancy_rows * self.occupancy_cols;
+            if cells == 0
+                || cells > MAX_OCCUPANCY_CELLS
+                || !self.occupancy_cell_size.is_finite()
+                || self.occupancy_cell_size <= 0.0
+            {
+                return Err(ObservationConfigError::InvalidOccupancyGrid);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A contiguous group of observation features.
+///
+/// The observation builder and [`crate::agent::spaces::ObservationSpace`]
+/// both walk [`ObservationConfig::feature_blocks`], so a feature flag changes
+/// the vector and its descriptor together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeatureBlock {
+    Rays,
+    Kinematics,
+    Lookahead,
+    CornerDistance,
+    BodyVelocity,
+    PreviousAction,
+    GridPosition,
+    RayHitClasses,
+    OccupancyGrid,
+    LapProgress,
+    TimeRemaining,
+    RayIncidence,
+    SpeedAlongTrack,
+}
+
+impl FeatureBlock {
+    /// Every block, in observation order.
+    pub const ALL: [FeatureBlock; 13] = [
+        FeatureBlock::Rays,
+        FeatureBlock::Kinematics,
+        FeatureBlock::Lookahead,
+        FeatureBlock::CornerDistance,
+        FeatureBlock::BodyVelocity,
+        FeatureBlock::PreviousAction,
+        FeatureBlock::GridPosition,
+        FeatureBlock::RayHitClasses,
+        FeatureBlock::OccupancyGrid,
+        FeatureBlock::LapProgress,
+        FeatureBlock::TimeRemaining,
+        FeatureBlock::RayIncidence,
+        FeatureBlock::SpeedAlongTrack,
+    ];
+
+    /// Whether this block reads the rays or occupancy patch, which are only
+    /// refreshed every [`ObservationConfig::sensor_update_interval`] ticks.
+    pub fn is_decimated(self) -> bool {
+        matches!(
+            self,
+            FeatureBlock::Rays
+                | FeatureBlock::RayHitClasses
+                | FeatureBlock::OccupancyGrid
+                | FeatureBlock::RayIncidence
+        )
+    }
+
+    pub fn is_enabled(self, config: &ObservationConfig) -> bool {
+        match self {
+            FeatureBlock::Rays | FeatureBlock::Kinematics | FeatureBlock::Lookahead => true,
+            FeatureBlock::CornerDistance => config.include_corner_distance,
+            FeatureBlock::BodyVelocity => config.include_body_velocity,
+            FeatureBlock::PreviousAction => config.include_previous_action,
+            FeatureBlock::GridPosition => config.include_grid_position,
+            FeatureBlock::RayHitClasses => config.include_ray_hit_classes,
+            FeatureBlock::OccupancyGrid => config.include_occupancy_grid,
+            FeatureBlock::LapProgress => config.include_lap_progress,
+            FeatureBlock::TimeRemaining => config.include_time_remaining,
+            FeatureBlock::RayIncidence => config.include_ray_incidence,
+            FeatureBlock::SpeedAlongTrack => config.include_speed_along_track,
+        }
+    }
+
+    /// Number of features in this block.
+    pub fn len(self, config: &ObservationConfig) -> usize {
+        match self {
+            FeatureBlock::Rays | FeatureBlock::RayHitClasses | FeatureBlock::RayIncidence => {
+                config.num_rays()
+            }
+            FeatureBlock::Kinematics => NUM_KINEMATIC_FEATURES,
+            FeatureBlock::Lookahead => NUM_LOOKAHEAD_SAMPLES * LOOKAHEAD_FEATURES_PER_SAMPLE,
+            FeatureBlock::CornerDistance
+            | FeatureBlock::LapProgress
+            | FeatureBlock::TimeRemaining
+            | FeatureBlock::SpeedAlongTrack => 1,
+            FeatureBlock::BodyVelocity
+            | FeatureBlock::PreviousAction
+            | FeatureBlock::GridPosition => 2,
+            FeatureBlock::OccupancyGrid => config.occupancy_rows * config.occupancy_cols,
+        }
+    }
+
+    /// Name and normalised bounds of each feature in this block.
+    pub fn features(self, config: &ObservationConfig) -> Vec<FeatureSpec> {
+        match self {
+            FeatureBlock::Rays => (0..config.num_rays())
+                .map(|index| FeatureSpec::new(format!("ray_{index}"), 0.0, 1.0))
+                .collect(),
+            FeatureBlock::Kinematics => vec![
+                FeatureSpec::new("speed", 0.0, 1.0),
+                FeatureSpec::new("lateral_offset", -1.0, 1.0),
+                FeatureSpec::new("heading_error", -1.0, 1.0),
+                FeatureSpec::new("yaw_rate", -1.0, 1.0),
+            ],
+            FeatureBlock::Lookahead => (0..NUM_LOOKAHEAD_SAMPLES)
+                .flat_map(|index| {
+                    [
+                        FeatureSpec::new(format!("lookahead_{index}_heading_delta"), -1.0, 1.0),
+                        FeatureSpec::new(format!("lookahead_{index}_curvature"), -1.0, 1.0),
+                    ]
+                })
+                .collect(),
+            FeatureBlock::CornerDistance => vec![FeatureSpec::new("corner_distance", 0.0, 1.0)],
+            FeatureBlock::BodyVelocity => vec![
+                FeatureSpec::new("body_velocity_longitudinal", -1.0, 1.0),
+                FeatureSpec::new("body_velocity_lateral", -1.0, 1.0),
+            ],
+            FeatureBlock::PreviousAction => vec![
+                FeatureSpec::new("previous_steering", -1.0, 1.0),
+                FeatureSpec::new("previous_throttle", 0.0, 1.0),
+            ],
+            FeatureBlock::GridPosition => vec![
+                FeatureSpec::new("grid_x", 0.0, 1.0),
+                FeatureSpec::new("grid_y", 0.0, 1.0),
+            ],
+            FeatureBlock::RayHitClasses => (0..config.num_rays())
+                .map(|index| FeatureSpec::new(format!("ray_{index}_class"), 0.0, 1.0))
+                .collect(),
+            FeatureBlock::OccupancyGrid => (0..config.occupancy_rows)
+                .flat_map(|row| {
+                    (0..config.occupancy_cols).map(move |col| {
+                        FeatureSpec::new(format!("occupancy_{row}_{col}"), 0.0, 1.0)
+                    })
+                })
+                .collect(),
+            FeatureBlock::LapProgress => vec![FeatureSpec::new("lap_progress", 0.0, 1.0)],
+            FeatureBlock::TimeRemaining => vec![FeatureSpec::new("time_remaining", 0.0, 1.0)],
+            FeatureBlock::RayIncidence => (0..config.num_rays())
+                .map(|index| FeatureSpec::new(format!("ray_{index}_incidence"), 0.0, 1.0))
+                .collect(),
+            FeatureBlock::SpeedAlongTrack => {
+                vec![FeatureSpec::new("speed_along_track", -1.0, 1.0)]
+            }
+        }
+    }
+
+    /// How each feature of [`Self::features`] is derived from its raw sensor
+    /// value, in the same order.
+    pub fn normalizations(self, config: &ObservationConfig) -> Vec<Normalization> {
+        let symmetric = |max: f32| Normalization::MinMax { min: -max, max };
+        let from_zero = |max: f32| Normalization::MinMax { min: 0.0, max };
+        match self {
+            FeatureBlock::Rays => (0..config.num_rays())
+                .map(|index| from_zero(config.ray_range(index)))
+                .collect(),
+            FeatureBlock::Kinematics => vec![
+                from_zero(config.speed_norm_max),
+                symmetric(config.lateral_offset_norm_max),
+                symmetric(PI),
+                symmetric(config.angular_velocity_norm_max),
+            ],
+            FeatureBlock::Lookahead => (0..NUM_LOOKAHEAD_SAMPLES)
+                .flat_map(|_| [symmetric(PI), symmetric(config.curvature_norm_max)])
+                .collect(),
+            FeatureBlock::CornerDistance => vec![from_zero(config.corner_scan_max.max(1.0))],
+            FeatureBlock::BodyVelocity => vec![symmetric(config.speed_norm_max); 2],
+            FeatureBlock::PreviousAction => vec![Normalization::Identity; 2],
+            FeatureBlock::GridPosition => vec![Normalization::TrackBounds; 2],
+            FeatureBlock::RayHitClasses => vec![Normalization::Categorical; config.num_rays()],
+            FeatureBlock::OccupancyGrid => vec![Normalization::Identity; self.len(config)],
+            FeatureBlock::LapProgress | FeatureBlock::TimeRemaining => {
+                vec![Normalization::Identity]
+            }
+            FeatureBlock::RayIncidence => vec![Normalization::Identity; config.num_rays()],
+            FeatureBlock::SpeedAlongTrack => vec![symmetric(config.speed_norm_max)],
+        }
+    }
+
+    /// Writes this block's normalised features into `out`, which holds
+    /// exactly [`Self::len`] values.
+    fn encode(
+        self,
+        out: &mut [f32],
+        sensors: &SensorReadings,
+        config: &ObservationConfig,
+        previous_action: CarAction,
+    ) {
+        match self {
+            FeatureBlock::Rays => {
+                for (index, (value, distance)) in
+                    out.iter_mut().zip(&sensors.ray_distances).enumerate()
+                {
+                    *value = (*distance / config.ray_range(index)).clamp(0.0, 1.0);
+                }
+            }
+            FeatureBlock::Kinematics => {
+                out[0] = (sensors.speed / config.speed_norm_max).clamp(0.0, 1.0);
+                out[1] = (sensors.signed_lateral_offset / config.lateral_offset_norm_max)
+                    .clamp(-1.0, 1.0);
+                out[2] = (sensors.heading_error / PI).clamp(-1.0, 1.0);
+                out[3] =
+                    (sensors.angular_velocity / config.angular_velocity_norm_max).clamp(-1.0, 1.0);
+            }
+            FeatureBlock::Lookahead => {
+                for (i, pair) in out
+                    .chunks_exact_mut(LOOKAHEAD_FEATURES_PER_SAMPLE)
+                    .enumerate()
+                {
+                    pair[0] = (sensors.lookahead_heading_deltas[i] / PI).clamp(-1.0, 1.0);
+                    pair[1] = (sensors.lookahead_curvatures[i] / config.curvature_norm_max)
+                        .clamp(-1.0, 1.0);
+                }
+            }
+            FeatureBlock::CornerDistance => {
+                out[0] =
+                    (sensors.corner_distance / config.corner_scan_max.max(1.0)).clamp(0.0, 1.0);
+            }
+            FeatureBlock::BodyVelocity => {
+                out[0] = (sensors.body_velocity.x / config.speed_norm_max).clamp(-1.0, 1.0);
+                out[1] = (sensors.body_velocity.y / config.speed_norm_max).clamp(-1.0, 1.0);
+            }
+            FeatureBlock::PreviousAction => {
+                out[0] = previous_action.steering.clamp(-1.0, 1.0);
+                out[1] = previous_action.throttle.clamp(0.0, 1.0);
+            }
+            FeatureBlock::GridPosition => {
+                out[0] = sensors.grid_position.x;
+                out[1] = sensors.grid_position.y;
+            }
+            FeatureBlock::RayHitClasses => {
+                for (value, class) in out.iter_mut().zip(&sensors.ray_classes) {
+                    *value = class.normalized_id();
+                }
+            }
+            FeatureBlock::OccupancyGrid => {
+                for (value, cell) in out.iter_mut().zip(&sensors.occupancy) {
+                    *value = *cell;
+                }
+            }
+            FeatureBlock::LapProgress => out[0] = sensors.lap_fraction.clamp(0.0, 1.0),
+            FeatureBlock::TimeRemaining => out[0] = sensors.time_remaining.clamp(0.0, 1.0),
+            FeatureBlock::RayIncidence => {
+                let rays = sensors.ray_normals.iter().zip(&sensors.ray_directions);
+                for (value, (normal, direction)) in out.iter_mut().zip(rays) {
+                    *value = normal.dot(*direction).abs().clamp(0.0, 1.0);
+                }
+            }
+            FeatureBlock::SpeedAlongTrack => {
+                out[0] = (sensors.speed_along_track / config.speed_norm_max).clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
+
+/// Reasons an [`ObservationConfig`] fails validation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObservationConfigError {
+    NoRays,
+    TooManyRays(usize),
+    NonFiniteRayAngle(usize),
+    RayMountCountMismatch { rays: usize, mounts: usize },
+    NonFiniteRayMount,
+    RayRangeCountMismatch { rays: usize, ranges: usize },
+    InvalidRayRange,
+    InvalidSensorUpdateInterval,
+    InvalidNoiseSigma,
+    InvalidOccupancyGrid,
+}
+
+impl fmt::Display for ObservationConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoRays => write!(f, "ray_angles is empty"),
+            Self::TooManyRays(count) => {
+                write!(f, "{count} ray angles exceed the maximum of {MAX_RAYS}")
+            }
+            Self::NonFiniteRayAngle(index) => write!(f, "ray_angles[{index}] is not finite"),
+            Self::RayMountCountMismatch { rays, mounts } => write!(
+                f,
+                "{mounts} ray_mount_offsets for {rays} rays; give one per ray or none"
+            ),
+            Self::NonFiniteRayMount => write!(f, "ray mount offsets must be finite"),
+            Self::RayRangeCountMismatch { rays, ranges } => write!(
+                f,
+                "{ranges} ray_max_ranges for {rays} rays; give one per ray or none"
+            ),
+            Self::InvalidRayRange => write!(f, "ray max ranges must be finite and positive"),
+            Self::InvalidSensorUpdateInterval => {
+                write!(f, "sensor_update_interval must be at least 1")
+            }
+            Self::InvalidNoiseSigma => write!(f, "noise sigmas must be finite and non-negative"),
+            Self::InvalidOccupancyGrid => write!(
+                f,
+                "occupancy grid needs 1..={MAX_OCCUPANCY_CELLS} cells and a positive cell size"
+            ),
         }
     }
 }
 
+impl std::error::Error for ObservationConfigError {}
+
 /// Updates raycasts and derived kinematics on the fixed simulation tick.
+///
+/// Rays and the occupancy patch are cast only every
+/// [`ObservationConfig::sensor_update_interval`] ticks, counted in episode
+/// ticks when an [`EpisodeState`] exists and in calls otherwise.
 pub fn update_sensor_readings_system(
     time: Res<Time<bevy::time::Fixed>>,
     config: Res<ObservationConfig>,
+    episode: (Option<Res<EpisodeState>>, Option<Res<EpisodeConfig>>),
     track_query: Query<&Track>,
-    mut car_query: Query<(&Transform, &Car, &TrackProgress, &mut SensorReadings)>,
+    mut car_query: Query<(
+        Entity,
+        &Transform,
+        &Car,
+        &TrackProgress,
+        &mut SensorReadings,
+    )>,
+    mut car_boxes: Local<Vec<(Entity, CarBox)>>,
+    mut calls: Local<u32>,
 ) {
     let Ok(track) = track_query.single() else {
         return;
     };
+    let (episode_state, episode_config) = episode;
     let dt = time.delta_secs().max(1e-6);
+    let time_remaining = match (episode_state.as_deref(), episode_config) {
+        (Some(state), Some(episode_config)) => episode_time_remaining(state, &episode_config, dt),
+        _ => 0.0,
+    };
+    let tick = episode_state.map_or(*calls, |state| state.ticks_in_episode);
+    *calls = calls.wrapping_add(1);
+    let cast_tick = tick.is_multiple_of(config.sensor_update_interval.max(1));
 
-    for (transform, car, progress, mut sensors) in &mut car_query {
+    // Every car's footprint this tick; the buffer is reused across ticks.
+    car_boxes.clear();
+    car_boxes.extend(car_query.iter().map(|(entity, transform, ..)| {
+        let forward = (transform.rotation * Vec3::X).truncate();
+        let center = transform.translation.truncate();
+        let heading = forward.y.atan2(forward.x);
+        (entity, CarBox { center, heading })
+    }));
+
+    for (entity, transform, car, progress, mut sensors) in &mut car_query {
+        sensors.resize_rays(config.num_rays());
         let position = transform.translation.truncate();
         let forward = (transform.rotation * Vec3::X)
             .truncate()
@@ -148,82 +919,381 @@ pub fn update_sensor_readings_system(
         let heading = forward.y.atan2(forward.x);
 
         sensors.speed = car.velocity.length();
+        sensors.body_velocity = body_frame_velocity(car.velocity, heading);
+        sensors.grid_position = track.grid.normalized_position(position);
+        sensors.speed_along_track = speed_along_track(car.velocity, progress.tangent);
         sensors.signed_lateral_offset =
             signed_lateral_offset(position, progress.closest_point, progress.tangent);
         sensors.heading_error = signed_angle_between(forward, progress.tangent);
         sensors.angular_velocity = wrap_angle(heading - sensors.previous_heading) / dt;
         sensors.previous_heading = heading;
+        sensors.lap_fraction = progress.fraction;
+        sensors.time_remaining = time_remaining;
 
-        for (index, relative_angle) in config.ray_angles.iter().enumerate() {
+        // Between casts the rays and occupancy patch keep their last values.
+        let cast = cast_tick || sensors.ray_age_ticks.is_none();
+        sensors.ray_age_ticks = if cast {
+            Some(0)
+        } else {
+            sensors.ray_age_ticks.map(|age| age.saturating_add(1))
+        };
+
+        let body_to_world = Vec2::from_angle(heading);
+        let ray_angles: &[f32] = if cast { &config.ray_angles } else { &[] };
+        for (index, relative_angle) in ray_angles.iter().enumerate() {
             let world_angle = heading + *relative_angle;
             let dir = Vec2::new(world_angle.cos(), world_angle.sin());
-            let (distance, hit) = raycast_to_road_boundary(
+            let origin = position + body_to_world.rotate(config.ray_mount(index));
+            let range = config.ray_range(index);
+            let hit = if config.exact_raycast {
+                track.grid.raycast_hit(origin, dir, range)
+            } else {
+                raycast_to_road_boundary(&track.grid, origin, dir, range, config.ray_step)
+            };
+            // Another car in front of the wall hides it.
+            let hit = nearest_car_hit(origin, dir, hit.distance, &car_boxes, entity).unwrap_or(hit);
+            sensors.ray_distances[index] = hit.distance;
+            sensors.ray_origins[index] = origin;
+            sensors.ray_hits[index] = origin + dir * hit.distance;
+            sensors.ray_classes[index] = hit.class;
+            sensors.ray_normals[index] = hit.normal;
+            sensors.ray_directions[index] = dir;
+        }
+
+        // Without a centreline the centreline features keep their zeros.
+        if let Some(centerline) = &track.centerline {
+            for (index, lookahead_distance) in config.lookahead_distances.iter().enumerate() {
+                let lookahead_s = progress.s + *lookahead_distance;
+                let lookahead_tangent = centerline.tangent_at_s(lookahead_s);
+                let heading_delta = signed_angle_between(forward, lookahead_tangent);
+                let turn_delta = signed_angle_between(progress.tangent, lookahead_tangent);
+                let curvature = turn_delta / lookahead_distance.max(1.0);
+
+                sensors.lookahead_heading_deltas[index] = heading_delta;
+                sensors.lookahead_curvatures[index] = curvature;
+            }
+        }
+
+        let corner_centerline = track
+            .centerline
+            .as_ref()
+            .filter(|_| config.include_corner_distance);
+        if let Some(centerline) = corner_centerline {
+            sensors.corner_distance = distance_to_next_corner(
+                centerline,
+                progress.s,
+                config.corner_curvature_threshold,
+                config.corner_scan_max,
+                config.corner_scan_step,
+            );
+        }
+
+        if config.include_occupancy_grid && cast {
+            let cells = config.occupancy_cells();
+            sensors.occupancy.resize(cells, 0.0);
+            sensors.occupancy_centers.resize(cells, Vec2::ZERO);
+            let SensorReadings {
+                occupancy,
+                occupancy_centers,
+                ..
+            } = &mut *sensors;
+            sample_occupancy_grid(
                 &track.grid,
                 position,
-                dir,
-                config.ray_max_range,
-                config.ray_step,
+                heading,
+                &config,
+                occupancy,
+                occupancy_centers,
             );
-            sensors.ray_distances[index] = distance;
-            sensors.ray_hits[index] = hit;
-            sensors.ray_directions[index] = dir;
         }
+    }
+}
 
-        for (index, lookahead_distance) in config.lookahead_distances.iter().enumerate() {
-            let lookahead_s = progress.s + *lookahead_distance;
-            let lookahead_tangent = track.centerline.tangent_at_s(lookahead_s);
-            let heading_delta = signed_angle_between(forward, lookahead_tangent);
-            let turn_delta = signed_angle_between(progress.tangent, lookahead_tangent);
-            let curvature = turn_delta / lookahead_distance.max(1.0);
+/// Fraction of `timeout_s` left before the episode's current deadline,
+/// which moves out by one `timeout_s` per checkpoint timeout extension.
+pub fn episode_time_remaining(state: &EpisodeState, config: &EpisodeConfig, dt: f32) -> f32 {
+    let timeout_s = config.timeout_s.max(1e-6);
+    let deadline_s = timeout_s * (state.current_timeout_extensions + 1) as f32;
+    let elapsed_s = state.ticks_in_episode as f32 * dt;
+    ((deadline_s - elapsed_s) / timeout_s).clamp(0.0, 1.0)
+}
 
-            sensors.lookahead_heading_deltas[index] = heading_delta;
-            sensors.lookahead_curvatures[index] = curvature;
+/// Samples the configured egocentric occupancy patch into `out` (row-major,
+/// `1.0` road, `0.0` off-road) and each cell's world centre into `centers`.
+///
+/// Each cell is one [`TrackGrid::is_road_at`] query, so the cost is
+/// `rows * cols` constant-time lookups per tick.
+pub fn sample_occupancy_grid(
+    grid: &TrackGrid,
+    position: Vec2,
+    heading: f32,
+    config: &ObservationConfig,
+    out: &mut [f32],
+    centers: &mut [Vec2],
+) {
+    let body_to_world = Vec2::from_angle(heading);
+    let cols = config.occupancy_cols;
+    for row in 0..config.occupancy_rows {
+        for col in 0..cols {
+            let index = row * cols + col;
+            let center = position + body_to_world.rotate(config.occupancy_cell_offset(row, col));
+            out[index] = if grid.is_road_at(center) { 1.0 } else { 0.0 };
+            centers[index] = center;
         }
     }
 }
 
 /// Converts sensor readings into a stable, normalised observation vector.
+///
+/// Configured [`ObservationNoise`] is drawn from the episode's
+/// `observation_noise` stream, so noisy runs replay exactly from their seed.
+/// An [`EvaluationMode`] run observes clean features.
 pub fn build_observation_vector_system(
     config: Res<ObservationConfig>,
+    evaluation: Option<Res<EvaluationMode>>,
+    action_state: Option<Res<ActionState>>,
+    episode_state: Option<Res<EpisodeState>>,
+    mut episode_rng: Option<ResMut<EpisodeRng>>,
     mut query: Query<(&SensorReadings, &mut ObservationVector)>,
 ) {
+    let num_rays = config.num_rays();
+    let noisy = config.noise.is_enabled() && !evaluation.is_some_and(|mode| mode.enabled);
+    // A freshly reset episode has no previous action.
+    let previous_action = action_state
+        .filter(|_| episode_state.is_none_or(|state| state.ticks_in_episode > 0))
+        .map(|state| state.applied)
+        .unwrap_or_default();
     for (sensors, mut observation) in &mut query {
-        let mut values = [0.0; OBSERVATION_DIM];
+        let values = &mut observation.values;
+        encode_observation(values, sensors, &config, previous_action);
+
+        if let Some(rng) = episode_rng.as_deref_mut().filter(|_| noisy) {
+            config
+                .noise
+                .apply(values, num_rays, &mut rng.observation_noise);
+        }
+    }
+}
+
+/// Writes every enabled [`FeatureBlock`] into `values`, resizing it to
+/// [`ObservationConfig::observation_dim`]; allocates only when the layout
+/// grows.
+fn encode_observation(
+    values: &mut Vec<f32>,
+    sensors: &SensorReadings,
+    config: &ObservationConfig,
+    previous_action: CarAction,
+) {
+    values.resize(config.observation_dim(), 0.0);
+    values.fill(0.0);
+
+    let mut cursor = 0;
+    for block in config.feature_blocks() {
+        let len = block.len(config);
+        block.encode(
+            &mut values[cursor..cursor + len],
+            sensors,
+            config,
+            previous_action,
+        );
+        cursor += len;
+    }
+}
+
+/// Replaces each car's observation with the one built
+/// `observation_delay_ticks` ticks earlier.
+///
+/// Until the episode has produced that many observations, its first
+/// observation is presented. The buffer is cleared whenever the episode
+/// changes, so observations never carry over a reset.
+pub fn apply_observation_delay_system(
+    config: Res<ObservationConfig>,
+    episode_state: Option<Res<EpisodeState>>,
+    mut query: Query<(&mut ObservationVector, &mut ObservationDelayBuffer)>,
+) {
+    let delay = config.observation_delay_ticks as usize;
+    if delay == 0 {
+        return;
+    }
+    let episode = episode_state.map_or(0, |state| state.current_episode);
 
-        for (index, distance) in sensors.ray_distances.iter().enumerate() {
-            values[index] = (*distance / config.ray_max_range).clamp(0.0, 1.0);
+    for (mut observation, mut buffer) in &mut query {
+        if buffer.episode != episode {
+            buffer.episode = episode;
+            buffer.history.clear();
+        }
+        // Once full, the oldest entry's allocation is recycled for the
+        // newest, so steady-state ticks do not allocate.
+        let mut newest = if buffer.history.len() > delay {
+            buffer.history.pop_front().unwrap_or_default()
+        } else {
+            Vec::with_capacity(observation.values.len())
+        };
+        newest.clone_from(&observation.values);
+        buffer.history.push_back(newest);
+        while buffer.history.len() > delay + 1 {
+            buffer.history.pop_front();
         }
+        if let Some(delayed) = buffer.history.front() {
+            observation.values.clone_from(delayed);
+        }
+    }
+}
 
-        values[NUM_RAYS] = (sensors.speed / config.speed_norm_max).clamp(0.0, 1.0);
-        values[NUM_RAYS + 1] =
-            (sensors.signed_lateral_offset / config.lateral_offset_norm_max).clamp(-1.0, 1.0);
-        values[NUM_RAYS + 2] = (sensors.heading_error / PI).clamp(-1.0, 1.0);
-        values[NUM_RAYS + 3] =
-            (sensors.angular_velocity / config.angular_velocity_norm_max).clamp(-1.0, 1.0);
+/// Queues the next [`RayPreset`] on `F10` and ends the current episode so
+/// the switch lands on an episode boundary. Refused while a policy whose
+/// input width was fixed at startup drives.
+pub fn ray_preset_cycle_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mode: Option<Res<AgentMode>>,
+    config: Res<ObservationConfig>,
+    mut switch: ResMut<RayPresetSwitch>,
+    reset_request: Option<ResMut<EpisodeResetRequest>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F10) {
+        return;
+    }
+    if let Some(mode) = mode.filter(|mode| {
+        matches!(
+            **mode,
+            AgentMode::Mlp | AgentMode::Onnx | AgentMode::Evolve | AgentMode::Learned
+        )
+    }) {
+        warn!(
+            "Ray presets cannot change while the {} policy drives; its input width is fixed.",
+            mode.name()
+        );
+        return;
+    }
+    let current = switch
+        .pending
+        .or(config.ray_preset)
+        .unwrap_or(RayPreset::Default11);
+    let next = current.next();
+    switch.pending = Some(next);
+    if let Some(mut request) = reset_request {
+        request.pending = true;
+    }
+    info!(
+        "Ray preset {} ({} rays) applies from the next episode.",
+        next.name(),
+        next.angles().len()
+    );
+}
 
-        let mut cursor = NUM_RAYS + 4;
-        for i in 0..NUM_LOOKAHEAD_SAMPLES {
-            values[cursor] = (sensors.lookahead_heading_deltas[i] / PI).clamp(-1.0, 1.0);
-            cursor += 1;
-            values[cursor] =
-                (sensors.lookahead_curvatures[i] / config.curvature_norm_max).clamp(-1.0, 1.0);
-            cursor += 1;
+/// Applies a queued [`RayPresetSwitch`] on the tick an episode ends, before
+/// the new episode's first observation is built: the config, every car's
+/// sensor arrays and observation vector, and any delayed observations are
+/// rebuilt for the new fan. Descriptors follow the config on their next
+/// query.
+pub fn apply_ray_preset_switch_system(
+    mut switch: ResMut<RayPresetSwitch>,
+    episode_state: Res<EpisodeState>,
+    mut config: ResMut<ObservationConfig>,
+    mut cars: Query<(
+        &mut SensorReadings,
+        &mut ObservationVector,
+        Option<&mut ObservationDelayBuffer>,
+    )>,
+) {
+    if episode_state.current_tick_end_reason.is_none() {
+        return;
+    }
+    let Some(preset) = switch.pending.take() else {
+        return;
+    };
+    config.set_ray_preset(preset);
+    for (mut sensors, mut observation, delay_buffer) in &mut cars {
+        *sensors = SensorReadings::for_config(&config);
+        *observation = ObservationVector::for_config(&config);
+        if let Some(mut delay_buffer) = delay_buffer {
+            *delay_buffer = ObservationDelayBuffer::default();
         }
+    }
+    info!(
+        "Ray preset {}: {} rays, observation dim {}.",
+        preset.name(),
+        config.num_rays(),
+        config.observation_dim()
+    );
+}
 
-        observation.values = values;
+/// Scans the centreline ahead of `s` in `step` increments and returns the
+/// distance to the first point where the turn rate over the next step
+/// exceeds `threshold` radians per world unit, or `max_distance` if the
+/// scan finds none. Accurate to one `step`.
+pub fn distance_to_next_corner(
+    centerline: &TrackCenterline,
+    s: f32,
+    threshold: f32,
+    max_distance: f32,
+    step: f32,
+) -> f32 {
+    let step = step.max(1.0);
+    let mut ahead = 0.0;
+    let mut tangent = centerline.tangent_at_s(s);
+    while ahead < max_distance {
+        let next_tangent = centerline.tangent_at_s(s + ahead + step);
+        if signed_angle_between(tangent, next_tangent).abs() / step > threshold {
+            return ahead;
+        }
+        tangent = next_tangent;
+        ahead += step;
     }
+    max_distance
 }
 
+/// [`RayHitClass::Car`] hit on the nearest car box along the unit
+/// `direction` within `max_range`, skipping `skip` (the sensing car). Boxes whose bounding
+/// circle cannot reach the ray are rejected before the exact oriented-box
+/// test.
+pub fn nearest_car_hit(
+    origin: Vec2,
+    direction: Vec2,
+    max_range: f32,
+    cars: &[(Entity, CarBox)],
+    skip: Entity,
+) -> Option<RayHit> {
+    let radius = CarBox::bounding_radius();
+    let mut nearest: Option<RayHit> = None;
+    for (entity, car) in cars {
+        if *entity == skip {
+            continue;
+        }
+        let to_center = car.center - origin;
+        let along = to_center.dot(direction);
+        let limit = nearest.map_or(max_range, |hit| hit.distance);
+        if along < -radius
+            || along > limit + radius
+            || to_center.length_squared() - along * along > radius * radius
+        {
+            continue;
+        }
+        if let Some(distance) = car.ray_distance(origin, direction, limit) {
+            nearest = Some(RayHit {
+                distance,
+                class: RayHitClass::Car,
+                normal: car.surface_normal(origin + direction * distance),
+            });
+        }
+    }
+    nearest
+}
+
+/// Marching reference caster: ~`max_range / step` road queries per ray, and
+/// it can step over non-road slivers thinner than `step`. A hit outside the
+/// grid bounds is [`RayHitClass::OffGrid`], any other hit a
+/// [`RayHitClass::Wall`].
 fn raycast_to_road_boundary(
     grid: &TrackGrid,
     origin: Vec2,
     direction: Vec2,
     max_range: f32,
     step: f32,
-) -> (f32, Vec2) {
+) -> RayHit {
     let dir = direction.normalize_or_zero();
     if dir == Vec2::ZERO {
-        return (0.0, origin);
+        return RayHit::default();
     }
 
     let step = step.max(0.5);
@@ -234,13 +1304,26 @@ fn raycast_to_road_boundary(
         let point = origin + dir * distance;
         if !grid.is_road_at(point) {
             let refined = refine_boundary_distance(grid, origin, dir, previous_distance, distance);
-            return (refined, origin + dir * refined);
+            let class = if grid.world_to_cell(point).is_some() {
+                RayHitClass::Wall
+            } else {
+                RayHitClass::OffGrid
+            };
+            return RayHit {
+                distance: refined,
+                class,
+                normal: grid.boundary_normal_at(point).unwrap_or(-dir),
+            };
         }
         previous_distance = distance;
         distance += step;
     }
 
-    (max_range, origin + dir * max_range)
+    RayHit {
+        distance: max_range,
+        class: RayHitClass::None,
+        normal: Vec2::ZERO,
+    }
 }
 
 fn refine_boundary_distance(
@@ -272,6 +1355,17 @@ fn wrap_angle(mut angle: f32) -> f32 {
     angle
 }
 
+/// Rotates a world-space velocity into the body frame of a car facing
+/// `heading` radians: `x` forward, `y` to the left.
+pub fn body_frame_velocity(velocity: Vec2, heading: f32) -> Vec2 {
+    Vec2::from_angle(-heading).rotate(velocity)
+}
+
+/// Projects `velocity` onto the unit centreline tangent.
+pub fn speed_along_track(velocity: Vec2, tangent: Vec2) -> f32 {
+    velocity.dot(tangent.normalize_or_zero())
+}
+
 fn signed_lateral_offset(position: Vec2, closest_point: Vec2, tangent: Vec2) -> f32 {
     let tangent_n = tangent.normalize_or_zero();
     if tangent_n == Vec2::ZERO {
@@ -293,8 +1387,137 @@ fn signed_angle_between(from: Vec2, to: Vec2) -> f32 {
 
 #[cfg(test)]
 mod tests {
-    use super::signed_lateral_offset;
-    use bevy::prelude::Vec2;
+    use super::{
+        FeatureBlock, NUM_KINEMATIC_FEATURES, ObservationConfig, ObservationConfigError,
+        ObservationDelayBuffer, ObservationNoise, ObservationVector, RayPreset, RayPresetSwitch,
+        SensorReadings, apply_observation_delay_system, apply_ray_preset_switch_system,
+        body_frame_velocity, build_observation_vector_system, distance_to_next_corner,
+        encode_observation, raycast_to_road_boundary, sample_occupancy_grid, signed_lateral_offset,
+        speed_along_track, update_sensor_readings_system,
+    };
+    use crate::agent::spaces::Normalization;
+    use crate::game::episode::{EpisodeEndReason, EpisodeState};
+    use bevy::prelude::*;
+
+    #[test]
+    fn ray_presets_have_the_documented_fans_and_vector_lengths() {
+        let expected: [(RayPreset, usize, f32, f32); 4] = [
+            (RayPreset::Default11, 11, 150.0, 15.0),
+            (RayPreset::ForwardNarrow, 7, 45.0, 15.0),
+            (RayPreset::SurroundSparse, 9, 160.0, 40.0),
+            (RayPreset::Dense19, 19, 90.0, 10.0),
+        ];
+        let default_dim = ObservationConfig::default().observation_dim();
+        for (preset, count, max_degrees, min_gap_degrees) in expected {
+            let mut config = ObservationConfig {
+                ray_mount_offsets: vec![Vec2::ZERO; 11],
+                ..ObservationConfig::default()
+            };
+            config.set_ray_preset(preset);
+            let degrees: Vec<f32> = config.ray_angles.iter().map(|a| a.to_degrees()).collect();
+
+            assert_eq!(config.num_rays(), count, "{}", preset.name());
+            assert_eq!(config.observation_dim(), default_dim - 11 + count);
+            assert_eq!(
+                ObservationVector::for_config(&config).values.len(),
+                count + default_dim - 11
+            );
+            assert_eq!(
+                SensorReadings::for_config(&config).ray_distances.len(),
+                count
+            );
+            assert_eq!(config.validate(), Ok(()), "{}", preset.name());
+            // Sorted left to right, symmetric about straight ahead.
+            assert!(
+                degrees
+                    .windows(2)
+                    .all(|pair| pair[1] - pair[0] >= min_gap_degrees - 1e-3)
+            );
+            assert!((degrees[0] + max_degrees).abs() < 1e-3, "{degrees:?}");
+            assert!(
+                (degrees[count - 1] - max_degrees).abs() < 1e-3,
+                "{degrees:?}"
+            );
+            assert!(
+                degrees.iter().any(|d| d.abs() < 1e-6),
+                "one ray looks straight ahead"
+            );
+            assert_eq!(config.ray_mount_offsets.is_empty(), count != 11);
+        }
+        assert_eq!(
+            RayPreset::Default11.angles(),
+            ObservationConfig::default().ray_angles
+        );
+        let mut preset = RayPreset::Default11;
+        for expected in [
+            RayPreset::ForwardNarrow,
+            RayPreset::SurroundSparse,
+            RayPreset::Dense19,
+            RayPreset::Default11,
+        ] {
+            preset = preset.next();
+            assert_eq!(preset, expected);
+        }
+    }
+
+    #[test]
+    fn queued_ray_preset_applies_only_when_the_episode_ends() {
+        let config = ObservationConfig::default();
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .insert_resource(RayPresetSwitch {
+                pending: Some(RayPreset::Dense19),
+            })
+            .init_resource::<EpisodeState>()
+            .add_systems(
+                Update,
+                (
+                    apply_ray_preset_switch_system,
+                    build_observation_vector_system,
+                )
+                    .chain(),
+            );
+        let car = app
+            .world_mut()
+            .spawn((
+                SensorReadings::for_config(&config),
+                ObservationVector::for_config(&config),
+                ObservationDelayBuffer::default(),
+            ))
+            .id();
+
+        app.update();
+        let len = |app: &App| {
+            app.world()
+                .get::<ObservationVector>(car)
+                .unwrap()
+                .values
+                .len()
+        };
+        assert_eq!(
+            len(&app),
+            config.observation_dim(),
+            "mid-episode: unchanged"
+        );
+
+        app.world_mut()
+            .resource_mut::<EpisodeState>()
+            .current_tick_end_reason = Some(EpisodeEndReason::Timeout);
+        app.update();
+        let switched = app.world().resource::<ObservationConfig>();
+        assert_eq!(switched.ray_preset, Some(RayPreset::Dense19));
+        assert_eq!(switched.num_rays(), 19);
+        assert_eq!(len(&app), switched.observation_dim());
+        assert_eq!(
+            app.world()
+                .get::<SensorReadings>(car)
+                .unwrap()
+                .ray_distances
+                .len(),
+            19
+        );
+        assert!(app.world().resource::<RayPresetSwitch>().pending.is_none());
+    }
 
     #[test]
     fn signed_lateral_offset_is_positive_to_the_left_of_the_tangent() {
@@ -307,4 +1530,1060 @@ mod tests {
         assert!(left > 0.0);
         assert!(right < 0.0);
     }
+
+    #[test]
+    fn speed_along_track_is_full_along_tangent_and_zero_across_it() {
+        let config = ObservationConfig::default();
+        let tangent = Vec2::new(1.0, 1.0).normalize();
+        let normal = Vec2::new(-tangent.y, tangent.x);
+        let speed = config.speed_norm_max;
+
+        let along = speed_along_track(tangent * speed, tangent) / config.speed_norm_max;
+        let across = speed_along_track(normal * speed, tangent) / config.speed_norm_max;
+        let backwards = speed_along_track(-tangent * speed, tangent) / config.speed_norm_max;
+
+        assert!((along - 1.0).abs() < 1e-5);
+        assert!(across.abs() < 1e-5);
+        assert!((backwards + 1.0).abs() < 1e-5);
+
+        // Opting in appends one feature and leaves the default layout alone.
+        let enabled = ObservationConfig {
+            include_speed_along_track: true,
+            ..config.clone()
+        };
+        assert_eq!(enabled.observation_dim(), config.observation_dim() + 1);
+        assert_eq!(
+            enabled.feature_blocks().last(),
+            Some(FeatureBlock::SpeedAlongTrack)
+        );
+    }
+
+    #[test]
+    fn body_frame_velocity_splits_longitudinal_and_lateral() {
+        let north = Vec2::new(0.0, 300.0);
+        let east = 0.0;
+        let facing_north = std::f32::consts::FRAC_PI_2;
+
+        let sliding = body_frame_velocity(north, east);
+        let driving = body_frame_velocity(north, facing_north);
+
+        assert!(
+            sliding.abs_diff_eq(Vec2::new(0.0, 300.0), 1e-3),
+            "{sliding}"
+        );
+        assert!(
+            driving.abs_diff_eq(Vec2::new(300.0, 0.0), 1e-3),
+            "{driving}"
+        );
+    }
+
+    #[test]
+    fn body_velocity_features_are_appended_when_enabled() {
+        let config = ObservationConfig {
+            include_body_velocity: true,
+            ..ObservationConfig::default()
+        };
+        assert_eq!(
+            config.observation_dim(),
+            ObservationConfig::default().observation_dim() + 2
+        );
+
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, build_observation_vector_system);
+        let mut sensors = SensorReadings::for_config(&config);
+        sensors.body_velocity = Vec2::new(0.5, -0.25) * config.speed_norm_max;
+        let car = app
+            .world_mut()
+            .spawn((sensors, ObservationVector::for_config(&config)))
+            .id();
+        app.update();
+
+        let values = &app.world().get::<ObservationVector>(car).unwrap().values;
+        assert_eq!(values[values.len() - 2..], [0.5, -0.25]);
+    }
+
+    #[test]
+    fn ray_hit_classes_are_appended_when_enabled() {
+        use crate::maps::grid::RayHitClass;
+
+        let config = ObservationConfig {
+            include_ray_hit_classes: true,
+            ..ObservationConfig::default()
+        };
+        let num_rays = config.num_rays();
+        assert_eq!(
+            config.observation_dim(),
+            ObservationConfig::default().observation_dim() + num_rays
+        );
+
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, build_observation_vector_system);
+        let mut sensors = SensorReadings::for_config(&config);
+        sensors.ray_classes[0] = RayHitClass::Wall;
+        sensors.ray_classes[1] = RayHitClass::OffGrid;
+        let car = app
+            .world_mut()
+            .spawn((sensors, ObservationVector::for_config(&config)))
+            .id();
+        app.update();
+
+        let values = &app.world().get::<ObservationVector>(car).unwrap().values;
+        let classes = &values[values.len() - num_rays..];
+        assert_eq!(classes[0], 0.25);
+        assert_eq!(classes[1], 0.5);
+        assert!(classes[2..].iter().all(|&v| v == 0.0));
+    }
+
+    #[test]
+    fn ray_incidence_is_appended_from_hit_normals_and_the_marcher_agrees() {
+        use crate::maps::grid::TrackGrid;
+        use crate::maps::parts::TilePart::StraightH;
+
+        let grid = TrackGrid::new(
+            vec![vec![StraightH, StraightH, StraightH]],
+            100.0,
+            Vec2::new(0.0, 100.0),
+        );
+        let origin = grid.cell_center(0, 1);
+        let diagonal = Vec2::new(1.0, 1.0).normalize();
+        for dir in [Vec2::Y, diagonal] {
+            let exact = grid.raycast_hit(origin, dir, 375.0);
+            let marched = raycast_to_road_boundary(&grid, origin, dir, 375.0, 3.0);
+            assert!(
+                marched.normal.distance(exact.normal) < 0.1,
+                "{dir}: {} vs {}",
+                marched.normal,
+                exact.normal
+            );
+        }
+
+        let config = ObservationConfig {
+            include_ray_incidence: true,
+            ..ObservationConfig::default()
+        };
+        let num_rays = config.num_rays();
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, build_observation_vector_system);
+        let mut sensors = SensorReadings::for_config(&config);
+        sensors.ray_directions[0] = Vec2::Y;
+        sensors.ray_normals[0] = Vec2::NEG_Y;
+        sensors.ray_directions[1] = diagonal;
+        sensors.ray_normals[1] = Vec2::NEG_Y;
+        let car = app
+            .world_mut()
+            .spawn((sensors, ObservationVector::for_config(&config)))
+            .id();
+        app.update();
+
+        let values = &app.world().get::<ObservationVector>(car).unwrap().values;
+        let incidence = &values[values.len() - num_rays..];
+        assert!((incidence[0] - 1.0).abs() < 1e-6);
+        assert!((incidence[1] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        // Rays without a hit keep the zero sentinel.
+        assert!(incidence[2..].iter().all(|&v| v == 0.0));
+    }
+
+    #[test]
+    fn previous_action_lags_one_tick_and_clears_on_reset() {
+        use crate::agent::action::{ActionState, CarAction};
+        use crate::game::episode::EpisodeState;
+
+        let config = ObservationConfig {
+            include_previous_action: true,
+            ..ObservationConfig::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .init_resource::<ActionState>()
+            .init_resource::<EpisodeState>()
+            .add_systems(Update, build_observation_vector_system);
+        let car = app
+            .world_mut()
+            .spawn((
+                SensorReadings::for_config(&config),
+                ObservationVector::for_config(&config),
+            ))
+            .id();
+        let tail = |app: &App| {
+            let values = &app.world().get::<ObservationVector>(car).unwrap().values;
+            [values[values.len() - 2], values[values.len() - 1]]
+        };
+
+        // Each tick applies an action, then builds the observation the next
+        // decision is made from.
+        let mut applied = Vec::new();
+        let mut seen = Vec::new();
+        for tick in 1..=4u32 {
+            let action = CarAction {
+                steering: tick as f32 * 0.2 - 0.5,
+                throttle: tick as f32 * 0.25,
+            };
+            app.world_mut().resource_mut::<ActionState>().applied = action;
+            app.world_mut()
+                .resource_mut::<EpisodeState>()
+                .ticks_in_episode = tick;
+            app.update();
+            applied.push([action.steering, action.throttle]);
+            seen.push(tail(&app));
+        }
+        assert_eq!(seen, applied);
+
+        // The tick that ends an episode resets the counter; the new
+        // episode's first observation carries no action.
+        app.world_mut()
+            .resource_mut::<EpisodeState>()
+            .ticks_in_episode = 0;
+        app.update();
+        assert_eq!(tail(&app), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn ray_count_follows_the_configured_angles() {
+        let config = ObservationConfig {
+            ray_angles: (0..7).map(|i| (i as f32 - 3.0) * 0.4).collect(),
+            ..ObservationConfig::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, build_observation_vector_system);
+        let mut sensors = SensorReadings::for_config(&config);
+        sensors.ray_distances.fill(config.ray_max_range);
+        sensors.speed = config.speed_norm_max;
+        let car = app
+            .world_mut()
+            .spawn((sensors, ObservationVector::for_config(&config)))
+            .id();
+        app.update();
+
+        let values = &app.world().get::<ObservationVector>(car).unwrap().values;
+        assert_eq!(values.len(), config.observation_dim());
+        assert_eq!(
+            config.observation_dim(),
+            ObservationConfig::default().observation_dim() - 4
+        );
+        assert!(values[..7].iter().all(|&v| v == 1.0));
+        assert_eq!(values[config.num_rays()], 1.0);
+        assert!(config.num_rays() + NUM_KINEMATIC_FEATURES < values.len());
+    }
+
+    #[test]
+    fn each_ray_is_cast_and_normalised_by_its_own_range() {
+        let config = ObservationConfig {
+            ray_angles: vec![-0.5, 0.0, 0.5],
+            ray_max_ranges: vec![100.0, 200.0, 400.0],
+            ..ObservationConfig::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(
+            FeatureBlock::Rays.normalizations(&config),
+            [100.0, 200.0, 400.0].map(|max| Normalization::MinMax { min: 0.0, max })
+        );
+
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, build_observation_vector_system);
+        let mut sensors = SensorReadings::for_config(&config);
+        sensors.ray_distances.fill(100.0);
+        let car = app
+            .world_mut()
+            .spawn((sensors, ObservationVector::for_config(&config)))
+            .id();
+        app.update();
+        let values = &app.world().get::<ObservationVector>(car).unwrap().values;
+        assert_eq!(values[..3], [1.0, 0.5, 0.25]);
+
+        // The wall is 47.5 ahead; a shorter range stops the ray first.
+        let forward = ObservationConfig {
+            ray_angles: vec![0.0],
+            ..ObservationConfig::default()
+        };
+        let (full, _) = forward_ray_against_the_north_wall(forward.clone());
+        assert!((full - 47.5).abs() < 1e-4, "{full}");
+        let (short, _) = forward_ray_against_the_north_wall(ObservationConfig {
+            ray_max_ranges: vec![20.0],
+            ..forward.clone()
+        });
+        assert_eq!(short, 20.0);
+
+        for ranges in [vec![0.0], vec![-5.0], vec![f32::NAN]] {
+            let invalid = ObservationConfig {
+                ray_max_ranges: ranges,
+                ..forward.clone()
+            };
+            assert_eq!(
+                invalid.validate(),
+                Err(ObservationConfigError::InvalidRayRange)
+            );
+        }
+        let invalid_shared = ObservationConfig {
+            ray_max_range: 0.0,
+            ..forward.clone()
+        };
+        assert_eq!(
+            invalid_shared.validate(),
+            Err(ObservationConfigError::InvalidRayRange)
+        );
+        let mismatched = ObservationConfig {
+            ray_max_ranges: vec![100.0; 2],
+            ..forward
+        };
+        assert_eq!(
+            mismatched.validate(),
+            Err(ObservationConfigError::RayRangeCountMismatch { rays: 1, ranges: 2 })
+        );
+    }
+
+    #[test]
+    fn unusable_ray_layouts_fail_validation() {
+        let empty = ObservationConfig {
+            ray_angles: Vec::new(),
+            ..ObservationConfig::default()
+        };
+        let non_finite = ObservationConfig {
+            ray_angles: vec![0.0, f32::NAN],
+            ..ObservationConfig::default()
+        };
+
+        assert_eq!(empty.validate(), Err(ObservationConfigError::NoRays));
+        assert_eq!(
+            non_finite.validate(),
+            Err(ObservationConfigError::NonFiniteRayAngle(1))
+        );
+
+        let never = ObservationConfig {
+            sensor_update_interval: 0,
+            ..ObservationConfig::default()
+        };
+        assert_eq!(
+            never.validate(),
+            Err(ObservationConfigError::InvalidSensorUpdateInterval)
+        );
+    }
+
+    #[test]
+    fn forward_ray_reads_the_gap_to_the_car_ahead_not_the_wall_beyond() {
+        use crate::game::car::{CAR_WIDTH, Car};
+        use crate::game::progress::TrackProgress;
+        use crate::maps::grid::{RayHitClass, TrackGrid};
+        use crate::maps::parts::TilePart::StraightH;
+        use crate::maps::track::Track;
+
+        let config = ObservationConfig {
+            ray_angles: vec![0.0],
+            ..ObservationConfig::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .init_resource::<Time<bevy::time::Fixed>>()
+            .add_systems(Update, update_sensor_readings_system);
+        // A closed 500-unit straight running east from x = 0.
+        app.world_mut().spawn(Track {
+            grid: TrackGrid::new(vec![vec![StraightH; 5]], 100.0, Vec2::new(0.0, 100.0)),
+            spawn_position: Vec2::new(50.0, 50.0),
+            spawn_rotation: 0.0,
+            centerline: None,
+        });
+        let mut spawn_car = |x: f32| {
+            app.world_mut()
+                .spawn((
+                    Transform::from_xyz(x, 50.0, 0.0),
+                    Car::default(),
+                    TrackProgress::default(),
+                    SensorReadings::for_config(&config),
+                ))
+                .id()
+        };
+        let behind = spawn_car(100.0);
+        let ahead = spawn_car(160.0);
+        app.update();
+
+        let read = |app: &App, entity| {
+            let sensors = app.world().get::<SensorReadings>(entity).unwrap();
+            (sensors.ray_distances[0], sensors.ray_classes[0])
+        };
+        let (gap, class) = read(&app, behind);
+        assert!((gap - (60.0 - CAR_WIDTH * 0.5)).abs() < 1e-3, "gap {gap}");
+        assert_eq!(class, RayHitClass::Car);
+        // The leading car sees past itself to the end of the straight.
+        let (clear, class) = read(&app, ahead);
+        assert!((clear - 340.0).abs() < 1e-3, "clear {clear}");
+        assert_eq!(class, RayHitClass::OffGrid);
+
+        // Once the leader leaves, the follower reads the border again.
+        app.world_mut().despawn(ahead);
+        app.update();
+        assert_eq!(
+            read(&app, behind),
+            (config.ray_max_range, RayHitClass::None)
+        );
+    }
+
+    /// Road/off-road rows of a 5x5, 40-unit occupancy patch centred in a
+    /// 100-unit-wide horizontal straight, with the car heading `heading`.
+    fn occupancy_patch_in_a_straight(heading: f32) -> Vec<Vec<u8>> {
+        use crate::maps::grid::TrackGrid;
+        use crate::maps::parts::TilePart::StraightH;
+
+        let grid = TrackGrid::new(
+            vec![vec![StraightH, StraightH, StraightH]],
+            100.0,
+            Vec2::new(0.0, 100.0),
+        );
+        let config = ObservationConfig {
+            include_occupancy_grid: true,
+            occupancy_rows: 5,
+            occupancy_cols: 5,
+            occupancy_cell_size: 40.0,
+            ..ObservationConfig::default()
+        };
+        let mut out = vec![0.0; config.occupancy_cells()];
+        let mut centers = vec![Vec2::ZERO; config.occupancy_cells()];
+        let position = Vec2::new(150.0, 50.0);
+        sample_occupancy_grid(&grid, position, heading, &config, &mut out, &mut centers);
+
+        // Cell centres sit at their body-frame offsets rotated by the heading.
+        let body_to_world = Vec2::from_angle(heading);
+        for (index, center) in centers.iter().enumerate() {
+            let offset = config.occupancy_cell_offset(index / 5, index % 5);
+            assert!(center.distance(position + body_to_world.rotate(offset)) < 1e-3);
+        }
+        out.chunks(5)
+            .map(|row| row.iter().map(|&cell| cell as u8).collect())
+            .collect()
+    }
+
+    #[test]
+    fn occupancy_patch_sees_the_straights_walls_and_rotates_with_the_car() {
+        // Heading east, row 0 is 80 units left (north, beyond the wall) and
+        // row 4 is 80 units right (south, off the grid).
+        let east = occupancy_patch_in_a_straight(0.0);
+        assert_eq!(
+            east,
+            vec![
+                vec![0, 0, 0, 0, 0],
+                vec![1, 1, 1, 1, 1],
+                vec![1, 1, 1, 1, 1],
+                vec![1, 1, 1, 1, 1],
+                vec![0, 0, 0, 0, 0],
+            ]
+        );
+
+        // Heading north, the walls fall behind and ahead of the car, so the
+        // same pattern appears rotated into the first and last columns.
+        let north = occupancy_patch_in_a_straight(std::f32::consts::FRAC_PI_2);
+        for (row, cells) in north.iter().enumerate() {
+            assert_eq!(cells, &vec![0, 1, 1, 1, 0], "row {row}");
+            for (col, cell) in cells.iter().enumerate() {
+                assert_eq!(*cell, east[col][4 - row]);
+            }
+        }
+    }
+
+    #[test]
+    fn occupancy_grid_extends_the_layout_and_rejects_empty_patches() {
+        let config = ObservationConfig {
+            include_occupancy_grid: true,
+            occupancy_rows: 3,
+            occupancy_cols: 4,
+            ..ObservationConfig::default()
+        };
+        let base = ObservationConfig::default().observation_dim();
+
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(config.observation_dim(), base + 12);
+        assert_eq!(SensorReadings::for_config(&config).occupancy.len(), 12);
+        assert_eq!(
+            FeatureBlock::OccupancyGrid.features(&config)[5].name,
+            "occupancy_1_1"
+        );
+        assert_eq!(
+            ObservationConfig {
+                occupancy_cols: 0,
+                ..config.clone()
+            }
+            .validate(),
+            Err(ObservationConfigError::InvalidOccupancyGrid)
+        );
+        assert_eq!(
+            ObservationConfig {
+                occupancy_cell_size: f32::NAN,
+                ..config
+            }
+            .validate(),
+            Err(ObservationConfigError::InvalidOccupancyGrid)
+        );
+    }
+
+    /// Builds `ticks` observations of fixed mid-range readings, with an
+    /// episode RNG seeded from `seed` when given.
+    fn observe_repeatedly(
+        config: &ObservationConfig,
+        seed: Option<u64>,
+        ticks: usize,
+    ) -> Vec<Vec<f32>> {
+        use crate::sim::rng::EpisodeRng;
+
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, build_observation_vector_system);
+        if let Some(seed) = seed {
+            app.insert_resource(EpisodeRng::from_seed(1, seed));
+        }
+        let mut sensors = SensorReadings::for_config(config);
+        sensors.ray_distances.fill(config.ray_max_range * 0.5);
+        sensors.speed = config.speed_norm_max * 0.5;
+        sensors.signed_lateral_offset = config.lateral_offset_norm_max * 0.25;
+        let car = app
+            .world_mut()
+            .spawn((sensors, ObservationVector::for_config(config)))
+            .id();
+
+        (0..ticks)
+            .map(|_| {
+                app.update();
+                app.world()
+                    .get::<ObservationVector>(car)
+                    .unwrap()
+                    .values
+                    .clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn observation_noise_is_seeded_and_has_the_configured_spread() {
+        let clean_config = ObservationConfig::default();
+        let noisy_config = ObservationConfig {
+            noise: ObservationNoise {
+                ray_distance: 0.05,
+                speed: 0.02,
+                heading_error: 0.0,
+                yaw_rate: 0.1,
+            },
+            ..ObservationConfig::default()
+        };
+        let num_rays = clean_config.num_rays();
+        let clean = observe_repeatedly(&clean_config, None, 1)[0].clone();
+        let noisy = observe_repeatedly(&noisy_config, Some(9), 400);
+
+        let ray_diffs: Vec<f32> = noisy
+            .iter()
+            .flat_map(|values| values[..num_rays].iter().map(|v| v - 0.5))
+            .collect();
+        let mean = ray_diffs.iter().sum::<f32>() / ray_diffs.len() as f32;
+        let std = (ray_diffs.iter().map(|d| (d - mean).powi(2)).sum::<f32>()
+            / ray_diffs.len() as f32)
+            .sqrt();
+        assert!(mean.abs() < 0.005, "mean {mean}");
+        assert!((std - 0.05).abs() < 0.005, "std {std}");
+
+        for values in &noisy {
+            assert!(values[..=num_rays].iter().all(|v| (0.0..=1.0).contains(v)));
+            assert!((-1.0..=1.0).contains(&values[num_rays + 3]));
+            // Features without noise are untouched.
+            assert_eq!(values[num_rays + 1], clean[num_rays + 1]);
+            assert_eq!(values[num_rays + 2], clean[num_rays + 2]);
+        }
+        assert!(
+            noisy
+                .iter()
+                .any(|values| values[num_rays] != clean[num_rays])
+        );
+        assert_eq!(observe_repeatedly(&noisy_config, Some(9), 400), noisy);
+        assert_ne!(observe_repeatedly(&noisy_config, Some(10), 400), noisy);
+    }
+
+    #[test]
+    fn zero_sigma_noise_is_bit_identical_to_clean() {
+        let config = ObservationConfig::default();
+        assert!(!config.noise.is_enabled());
+
+        let with_rng = observe_repeatedly(&config, Some(3), 5);
+        let clean = &observe_repeatedly(&config, None, 1)[0];
+
+        for values in &with_rng {
+            let bits = |v: &[f32]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+            assert_eq!(bits(values), bits(clean));
+        }
+
+        let negative = ObservationConfig {
+            noise: ObservationNoise {
+                speed: -0.1,
+                ..ObservationNoise::default()
+            },
+            ..ObservationConfig::default()
+        };
+        assert_eq!(
+            negative.validate(),
+            Err(ObservationConfigError::InvalidNoiseSigma)
+        );
+    }
+
+    /// Observations over `ticks` ticks whose speed reading is the tick
+    /// index, with the episode advancing at `reset_tick`.
+    fn delayed_speed_features(delay: u32, ticks: u32, reset_tick: u32) -> Vec<f32> {
+        use crate::game::episode::EpisodeState;
+
+        let config = ObservationConfig {
+            observation_delay_ticks: delay,
+            ..ObservationConfig::default()
+        };
+        let speed_index = config.num_rays();
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .init_resource::<EpisodeState>()
+            .add_systems(
+                Update,
+                (
+                    build_observation_vector_system,
+                    apply_observation_delay_system,
+                )
+                    .chain(),
+            );
+        let car = app
+            .world_mut()
+            .spawn((
+                SensorReadings::for_config(&config),
+                ObservationVector::for_config(&config),
+                ObservationDelayBuffer::default(),
+            ))
+            .id();
+
+        (0..ticks)
+            .map(|tick| {
+                if tick == reset_tick {
+                    app.world_mut()
+                        .resource_mut::<EpisodeState>()
+                        .current_episode += 1;
+                }
+                app.world_mut()
+                    .get_mut::<SensorReadings>(car)
+                    .unwrap()
+                    .speed = tick as f32 * config.speed_norm_max / 100.0;
+                app.update();
+                app.world().get::<ObservationVector>(car).unwrap().values[speed_index]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn delayed_observation_lags_by_the_configured_ticks() {
+        let clean = delayed_speed_features(0, 20, u32::MAX);
+        let delayed = delayed_speed_features(3, 20, u32::MAX);
+
+        assert_eq!(delayed[..4], [clean[0]; 4]);
+        for tick in 3..20 {
+            assert_eq!(delayed[tick], clean[tick - 3], "tick {tick}");
+        }
+
+        // After a reset the new episode's first observation is repeated
+        // rather than the previous episode's.
+        let reset = delayed_speed_features(3, 20, 10);
+        assert_eq!(reset[..10], delayed[..10]);
+        assert_eq!(reset[10..14], [clean[10]; 4]);
+        assert_eq!(reset[14], clean[11]);
+    }
+
+    /// Forward ray distance of a car in the oval's top straight facing the
+    /// flat north wall, with the rays mounted per `config`.
+    fn forward_ray_against_the_north_wall(config: ObservationConfig) -> (f32, Vec2) {
+        use crate::game::car::Car;
+        use crate::game::progress::TrackProgress;
+        use crate::maps::centerline::{GridDir, TrackCenterline};
+        use crate::maps::grid::TrackGrid;
+        use crate::maps::parts::TilePart::*;
+        use crate::maps::track::Track;
+
+        let grid = TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, StraightH, StraightH, CornerNE],
+                vec![CornerSW, StraightH, StraightH, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 100.0),
+        );
+        let centerline =
+            TrackCenterline::build_closed_loop(&grid, (0, 1), GridDir::East).expect("oval loop");
+        let (spawn_position, spawn_rotation) = grid.find_spawn().expect("spawn tile");
+
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .init_resource::<Time<bevy::time::Fixed>>()
+            .add_systems(Update, update_sensor_readings_system);
+        app.world_mut().spawn(Track {
+            grid,
+            spawn_position,
+            spawn_rotation,
+            centerline: Some(centerline),
+        });
+        let car = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(0.0, 50.0, 0.0)
+                    .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+                Car::default(),
+                TrackProgress::default(),
+                SensorReadings::for_config(&config),
+            ))
+            .id();
+        app.update();
+
+        let sensors = app.world().get::<SensorReadings>(car).unwrap();
+        (sensors.ray_distances[0], sensors.ray_origins[0])
+    }
+
+    #[test]
+    fn front_mounted_rays_read_half_a_car_length_shorter() {
+        use crate::game::car::CAR_WIDTH;
+
+        let forward = ObservationConfig {
+            ray_angles: vec![0.0],
+            ..ObservationConfig::default()
+        };
+        let (centre, centre_origin) = forward_ray_against_the_north_wall(forward.clone());
+        let (bumper, bumper_origin) = forward_ray_against_the_north_wall(ObservationConfig {
+            ray_mount_offset: Vec2::new(CAR_WIDTH * 0.5, 0.0),
+            ..forward.clone()
+        });
+
+        // The wall's inner face is 47.5 north of the car centre.
+        assert!((centre - 47.5).abs() < 1e-4, "{centre}");
+        assert!((centre - bumper - CAR_WIDTH * 0.5).abs() < 1e-4, "{bumper}");
+        // The car faces north, so the forward mount rotates onto +y.
+        assert!(centre_origin.abs_diff_eq(Vec2::new(0.0, 50.0), 1e-4));
+        assert!(bumper_origin.abs_diff_eq(Vec2::new(0.0, 50.0 + CAR_WIDTH * 0.5), 1e-4));
+
+        // A per-ray mount overrides the shared one.
+        let (per_ray, _) = forward_ray_against_the_north_wall(ObservationConfig {
+            ray_mount_offset: Vec2::new(CAR_WIDTH * 0.5, 0.0),
+            ray_mount_offsets: vec![Vec2::ZERO],
+            ..forward.clone()
+        });
+        assert_eq!(per_ray, centre);
+
+        let mismatched = ObservationConfig {
+            ray_mount_offsets: vec![Vec2::ZERO; 2],
+            ..forward
+        };
+        assert_eq!(
+            mismatched.validate(),
+            Err(ObservationConfigError::RayMountCountMismatch { rays: 1, mounts: 2 })
+        );
+    }
+
+    #[test]
+    fn a_full_delay_buffer_recycles_its_allocations() {
+        let config = ObservationConfig {
+            observation_delay_ticks: 2,
+            ..ObservationConfig::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, apply_observation_delay_system);
+        let car = app
+            .world_mut()
+            .spawn((
+                ObservationVector::for_config(&config),
+                ObservationDelayBuffer::default(),
+            ))
+            .id();
+        let allocations = |app: &App| -> Vec<*const f32> {
+            let buffer = app.world().get::<ObservationDelayBuffer>(car).unwrap();
+            buffer
+                .history
+                .iter()
+                .map(|values| values.as_ptr())
+                .collect()
+        };
+
+        for _ in 0..3 {
+            app.update();
+        }
+        let mut filled = allocations(&app);
+        for _ in 0..5 {
+            app.update();
+            let mut current = allocations(&app);
+            assert_eq!(current.len(), 3);
+            current.sort();
+            filled.sort();
+            assert_eq!(current, filled);
+        }
+    }
+
+    #[test]
+    fn corner_distance_shrinks_on_the_approach_to_a_corner() {
+        use crate::maps::centerline::{GridDir, TrackCenterline};
+        use crate::maps::grid::TrackGrid;
+        use crate::maps::parts::TilePart::*;
+
+        // Spawn centre at x = -100 heading east; the NE corner tile starts
+        // at x = 150, so the straight ahead is 250 long.
+        let grid = TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, StraightH, StraightH, CornerNE],
+                vec![CornerSW, StraightH, StraightH, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 100.0),
+        );
+        let centerline =
+            TrackCenterline::build_closed_loop(&grid, (0, 1), GridDir::East).expect("oval loop");
+        let config = ObservationConfig::default();
+        let scan = |x: f32| {
+            let s = centerline.project(Vec2::new(x, 50.0)).s;
+            distance_to_next_corner(
+                &centerline,
+                s,
+                config.corner_curvature_threshold,
+                config.corner_scan_max,
+                config.corner_scan_step,
+            )
+        };
+
+        let mut previous = f32::INFINITY;
+        for x in [-100.0, -50.0, 0.0, 50.0, 100.0] {
+            let expected = 150.0 - x;
+            let distance = scan(x);
+            assert!(
+                distance <= expected + 1e-3 && distance >= expected - config.corner_scan_step,
+                "at x = {x}: {distance} vs {expected}"
+            );
+            assert!(distance < previous);
+            previous = distance;
+        }
+
+        let straight = ObservationConfig {
+            corner_scan_max: 100.0,
+            ..config
+        };
+        let s = centerline.project(Vec2::new(-100.0, 50.0)).s;
+        assert_eq!(
+            distance_to_next_corner(
+                &centerline,
+                s,
+                straight.corner_curvature_threshold,
+                straight.corner_scan_max,
+                straight.corner_scan_step,
+            ),
+            100.0
+        );
+    }
+
+    /// Uniform road samples on the Sepang grid, each with a random heading.
+    fn sepang_ray_samples(count: usize) -> (crate::maps::grid::TrackGrid, Vec<(Vec2, Vec2)>) {
+        use crate::sim::rng::SimRng;
+
+        let grid = crate::maps::monaco::build_grid();
+        let width = grid.cols() as f32 * grid.tile_size;
+        let height = grid.rows() as f32 * grid.tile_size;
+        let mut rng = SimRng::new(0x5EED);
+        let mut samples = Vec::with_capacity(count);
+        while samples.len() < count {
+            let origin = grid.origin + Vec2::new(rng.next_f32() * width, -rng.next_f32() * height);
+            if grid.is_road_at(origin) {
+                let angle = rng.next_f32() * std::f32::consts::TAU;
+                samples.push((origin, Vec2::from_angle(angle)));
+            }
+        }
+        (grid, samples)
+    }
+
+    #[test]
+    fn exact_raycast_matches_the_marching_oracle() {
+        let config = ObservationConfig::default();
+        let (grid, samples) = sepang_ray_samples(5000);
+
+        let mut disagreements = 0;
+        for &(origin, dir) in &samples {
+            let exact = grid.raycast_boundary(origin, dir, config.ray_max_range);
+            let marched =
+                raycast_to_road_boundary(&grid, origin, dir, config.ray_max_range, config.ray_step)
+                    .distance;
+
+            // Marching can only overshoot (by skipping a thin sliver), never
+            // report an earlier exit.
+            assert!(
+                exact <= marched + 0.05,
+                "{origin} {dir}: {exact} > {marched}"
+            );
+            if (exact - marched).abs() > 0.05 {
+                disagreements += 1;
+            }
+            if exact < config.ray_max_range {
+                assert!(!grid.is_road_at(origin + dir * (exact + 0.01)));
+            }
+            if exact > 0.01 {
+                assert!(grid.is_road_at(origin + dir * (exact - 0.01)));
+            }
+        }
+        assert!(
+            disagreements * 100 < samples.len(),
+            "{disagreements} of {} rays disagree",
+            samples.len()
+        );
+    }
+
+    /// Timing comparison; run with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn exact_raycast_is_faster_than_marching() {
+        let config = ObservationConfig::default();
+        let (grid, samples) = sepang_ray_samples(20_000);
+
+        let start = std::time::Instant::now();
+        let exact: f32 = samples
+            .iter()
+            .map(|&(o, d)| grid.raycast_boundary(o, d, config.ray_max_range))
+            .sum();
+        let exact_time = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let marched: f32 = samples
+            .iter()
+            .map(|&(o, d)| {
+                raycast_to_road_boundary(&grid, o, d, config.ray_max_range, config.ray_step)
+                    .distance
+            })
+            .sum();
+        let march_time = start.elapsed();
+
+        println!(
+            "{} rays: exact {exact_time:?}, marching {march_time:?} ({:.1}x); sums {exact:.0} / {marched:.0}",
+            samples.len(),
+            march_time.as_secs_f64() / exact_time.as_secs_f64().max(1e-9)
+        );
+        assert!(exact_time < march_time);
+    }
+
+    /// The ray block as the old `NUM_RAYS` layout encoded it: fixed-size
+    /// arrays with the length known at compile time.
+    fn encode_fixed_rays(out: &mut [f32; 11], distances: &[f32; 11], ranges: &[f32; 11]) {
+        for ((value, distance), range) in out.iter_mut().zip(distances).zip(ranges) {
+            *value = (*distance / *range).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Timing comparison; run with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn default_eleven_rays_encode_no_slower_than_fixed_arrays() {
+        let config = ObservationConfig::default();
+        assert_eq!(config.num_rays(), 11);
+        let mut sensors = SensorReadings::for_config(&config);
+        for (index, distance) in sensors.ray_distances.iter_mut().enumerate() {
+            *distance = 40.0 * index as f32;
+        }
+        let previous_action = crate::agent::action::CarAction::default();
+        let iterations = 200_000;
+
+        // Baseline: rays from fixed arrays, the remaining blocks as today.
+        let distances: [f32; 11] = std::array::from_fn(|i| sensors.ray_distances[i]);
+        let ranges: [f32; 11] = std::array::from_fn(|i| config.ray_range(i));
+        let tail: Vec<FeatureBlock> = config
+            .feature_blocks()
+            .filter(|block| *block != FeatureBlock::Rays)
+            .collect();
+        let mut rays = [0.0; 11];
+        let mut rest = vec![0.0; config.observation_dim() - 11];
+        let start = std::time::Instant::now();
+        let mut fixed_sum = 0.0;
+        for _ in 0..iterations {
+            encode_fixed_rays(&mut rays, std::hint::black_box(&distances), &ranges);
+            let mut cursor = 0;
+            for block in &tail {
+                let len = block.len(&config);
+                block.encode(
+                    &mut rest[cursor..cursor + len],
+                    &sensors,
+                    &config,
+                    previous_action,
+                );
+                cursor += len;
+            }
+            fixed_sum += rays[10] + rest[0];
+        }
+        let fixed_time = start.elapsed();
+
+        let mut values = Vec::with_capacity(config.observation_dim());
+        let start = std::time::Instant::now();
+        let mut vec_sum = 0.0;
+        for _ in 0..iterations {
+            encode_observation(
+                &mut values,
+                std::hint::black_box(&sensors),
+                &config,
+                previous_action,
+            );
+            vec_sum += values[10] + values[11];
+        }
+        let vec_time = start.elapsed();
+
+        println!(
+            "{iterations} default observations: fixed arrays {fixed_time:?}, runtime-sized {vec_time:?} ({:.2}x); sums {fixed_sum:.0} / {vec_sum:.0}",
+            vec_time.as_secs_f64() / fixed_time.as_secs_f64().max(1e-9)
+        );
+        assert_eq!(fixed_sum, vec_sum);
+        // Allow timer noise, but not a real regression.
+        assert!(vec_time.as_secs_f64() <= 1.25 * fixed_time.as_secs_f64());
+    }
+
+    /// Runs the sensor system `ticks` times over 16 cars spread along Sepang.
+    fn time_sixteen_car_sensors(interval: u32, ticks: usize) -> std::time::Duration {
+        use crate::game::car::Car;
+        use crate::game::progress::TrackProgress;
+
+        let config = ObservationConfig {
+            sensor_update_interval: interval,
+            ..ObservationConfig::default()
+        };
+        let track = crate::maps::monaco::build_monaco_track();
+        let points = track
+            .centerline
+            .as_ref()
+            .expect("Sepang has a centreline")
+            .points
+            .clone();
+
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .init_resource::<Time<bevy::time::Fixed>>()
+            .add_systems(Update, update_sensor_readings_system);
+        app.world_mut().spawn(track);
+        for car in 0..16 {
+            let index = car * points.len() / 16;
+            let (a, b) = (points[index], points[(index + 1) % points.len()]);
+            let heading = (b - a).to_angle();
+            app.world_mut().spawn((
+                Transform::from_xyz(a.x, a.y, 0.0).with_rotation(Quat::from_rotation_z(heading)),
+                Car::default(),
+                TrackProgress::default(),
+                SensorReadings::for_config(&config),
+            ));
+        }
+
+        let start = std::time::Instant::now();
+        for _ in 0..ticks {
+            app.update();
+        }
+        start.elapsed()
+    }
+
+    /// Timing comparison; run with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn decimated_sensors_are_faster_on_a_sixteen_car_scene() {
+        let every_tick = time_sixteen_car_sensors(1, 600);
+        let every_third = time_sixteen_car_sensors(3, 600);
+
+        println!(
+            "16 cars x 600 ticks: interval 1 {every_tick:?}, interval 3 {every_third:?} ({:.1}x)",
+            every_tick.as_secs_f64() / every_third.as_secs_f64().max(1e-9)
+        );
+        assert!(every_third < every_tick);
+    }
 }
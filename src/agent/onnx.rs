@@ -0,0 +1,394 @@
+//! Externally trained policies run in the sim through ONNX.
+//!
+//! [`OnnxController`] (cargo feature `onnx`, backed by `tract`) loads the
+//! model named by [`OnnxControllerConfig`] and is registered under
+//! [`AgentMode::Onnx`]. At load the model's input width must match the
+//! observation space and its output either the two continuous action values
+//! or one logit per discrete action. Each fixed tick feeds the observation as
+//! a `[1, dim]` `f32` tensor; an inference failure logs a warning and
+//! coasts with the zero action rather than stopping the episode.
+
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionMode, CarAction};
+use crate::agent::controller::Controllers;
+#[cfg(feature = "onnx")]
+use crate::agent::controller::{Controller, ControllerContext};
+#[cfg(feature = "onnx")]
+use crate::agent::observation::ObservationConfig;
+use crate::agent::spaces::{ActionSpace, ObservationSpace};
+#[cfg(feature = "onnx")]
+use crate::brain::types::AgentMode;
+
+/// Model selection for the ONNX controller (config `onnx`).
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OnnxControllerConfig {
+    /// `.onnx` file to load at startup; `None` leaves the mode unregistered.
+    pub model_path: Option<String>,
+}
+
+/// How the model's output row maps to a [`CarAction`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolicyOutput {
+    /// `[steering, throttle]`, clamped to the action ranges.
+    Continuous,
+    /// One logit per discrete action; the arg-max index is decoded.
+    Logits(usize),
+}
+
+#[derive(Debug)]
+pub enum OnnxError {
+    /// The model file could not be read, parsed, or optimised.
+    Load(String),
+    /// The model's input width differs from the observation dimension.
+    InputShape { expected: usize, found: usize },
+    /// The model's output width does not fit the action space; `found` is
+    /// `None` when it is not a fixed size.
+    OutputShape {
+        expected: usize,
+        found: Option<usize>,
+    },
+}
+
+impl fmt::Display for OnnxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Load(err) => write!(f, "cannot load model: {err}"),
+            Self::InputShape { expected, found } => write!(
+                f,
+                "model takes {found} inputs but the observation has {expected}"
+            ),
+            Self::OutputShape {
+                expected,
+                found: Some(found),
+            } => write!(
+                f,
+                "model produces {found} outputs but the action space needs {expected}"
+            ),
+            Self::OutputShape {
+                expected,
+                found: None,
+            } => write!(
+                f,
+                "model output is not a fixed size; the action space needs {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OnnxError {}
+
+/// Checks a model's declared input width (`None` when symbolic) against the
+/// observation space.
+pub fn check_input_dim(
+    input_dim: Option<usize>,
+    observation: &ObservationSpace,
+) -> Result<(), OnnxError> {
+    match input_dim {
+        Some(found) if found != observation.dim => Err(OnnxError::InputShape {
+            expected: observation.dim,
+            found,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Picks the output interpretation for `action`: logits in discrete mode,
+/// otherwise the continuous action values.
+pub fn check_output_dim(
+    output_dim: Option<usize>,
+    action: &ActionSpace,
+) -> Result<PolicyOutput, OnnxError> {
+    let (expected, output) = match action.discrete_count {
+        Some(count) => (count, PolicyOutput::Logits(count)),
+        None => (action.dim, PolicyOutput::Continuous),
+    };
+    if output_dim == Some(expected) {
+        Ok(output)
+    } else {
+        Err(OnnxError::OutputShape {
+            expected,
+            found: output_dim,
+        })
+    }
+}
+
+/// Turns one output row into an action, or `None` if it is too short or the
+/// chosen index does not decode.
+pub fn decode_policy_output(
+    output: PolicyOutput,
+    mode: ActionMode,
+    values: &[f32],
+) -> Option<CarAction> {
+    match output {
+        PolicyOutput::Continuous => match values {
+            [steering, throttle, ..] => Some(
+                CarAction {
+                    steering: *steering,
+                    throttle: *throttle,
+                }
+                .clamped(),
+            ),
+            _ => None,
+        },
+        PolicyOutput::Logits(count) => {
+            let (index, _) = values
+                .get(..count)?
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+            mode.decode(index).ok()
+        }
+    }
+}
+
+/// Runs an ONNX policy on the current observation.
+#[cfg(feature = "onnx")]
+pub struct OnnxController {
+    plan: tract_onnx::prelude::TypedRunnableModel<tract_onnx::prelude::TypedModel>,
+    output: PolicyOutput,
+    action_mode: ActionMode,
+    /// A failure was already logged this episode.
+    warned: bool,
+}
+
+#[cfg(feature = "onnx")]
+impl OnnxController {
+    /// Loads and validates the model at `path` for the given spaces.
+    pub fn load(
+        path: &std::path::Path,
+        observation: &ObservationSpace,
+        action: &ActionSpace,
+        action_mode: ActionMode,
+    ) -> Result<Self, OnnxError> {
+        use tract_onnx::prelude::*;
+
+        let load = |err: TractError| OnnxError::Load(format!("{}: {err}", path.display()));
+        let mut model = tract_onnx::onnx().model_for_path(path).map_err(load)?;
+        let input_dim = model
+            .input_fact(0)
+            .ok()
+            .and_then(|fact| fact.shape.dims().last().and_then(|dim| dim.concretize()))
+            .and_then(|dim| dim.to_usize().ok());
+        check_input_dim(input_dim, observation)?;
+
+        model
+            .set_input_fact(0, f32::fact([1, observation.dim]).into())
+            .map_err(load)?;
+        let model = model.into_optimized().map_err(load)?;
+        let output_dim = model
+            .output_fact(0)
+            .ok()
+            .and_then(|fact| fact.shape.last().and_then(|dim| dim.to_usize().ok()));
+        let output = check_output_dim(output_dim, action)?;
+
+        Ok(Self {
+            plan: model.into_runnable().map_err(load)?,
+            output,
+            action_mode,
+            warned: false,
+        })
+    }
+
+    fn infer(&self, observation: &[f32]) -> tract_onnx::prelude::TractResult<Vec<f32>> {
+        use tract_onnx::prelude::*;
+
+        let input = Tensor::from_shape(&[1, observation.len()], observation)?;
+        let outputs = self.plan.run(tvec!(input.into()))?;
+        Ok(outputs[0].as_slice::<f32>()?.to_vec())
+    }
+}
+
+#[cfg(feature = "onnx")]
+impl Controller for OnnxController {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+        let action = match self.infer(&ctx.observation.values) {
+            Ok(values) => decode_policy_output(self.output, self.action_mode, &values)
+                .ok_or_else(|| format!("cannot decode output {values:?}")),
+            Err(err) => Err(err.to_string()),
+        };
+        action.unwrap_or_else(|err| {
+            if !self.warned {
+                warn!(
+                    "ONNX inference failed in episode {} tick {}: {err}; coasting.",
+                    ctx.episode, ctx.tick
+                );
+                self.warned = true;
+            }
+            CarAction::default()
+        })
+    }
+
+    fn begin_episode(&mut self, _episode: u32) {
+        self.warned = false;
+    }
+}
+
+/// Loads the configured model into `controllers` under [`AgentMode::Onnx`],
+/// logging instead when there is none or it does not fit the live spaces.
+pub fn register_onnx_controller(world: &World, controllers: &mut Controllers) {
+    let Some(path) = world
+        .get_resource::<OnnxControllerConfig>()
+        .and_then(|config| config.model_path.clone())
+    else {
+        return;
+    };
+
+    #[cfg(feature = "onnx")]
+    {
+        let observation = ObservationSpace::from_config(
+            &world
+                .get_resource::<ObservationConfig>()
+                .cloned()
+                .unwrap_or_default(),
+        );
+        let action_mode = world
+            .get_resource::<ActionMode>()
+            .copied()
+            .unwrap_or_default();
+        let action = ActionSpace::for_mode(action_mode);
+        match OnnxController::load(
+            std::path::Path::new(&path),
+            &observation,
+            &action,
+            action_mode,
+        ) {
+            Ok(controller) => {
+                info!("Loaded ONNX policy {path}.");
+                controllers.register(AgentMode::Onnx, controller);
+            }
+            Err(err) => warn!("ONNX controller disabled: {err}"),
+        }
+    }
+    #[cfg(not(feature = "onnx"))]
+    {
+        let _ = controllers;
+        warn!("ONNX model {path} ignored: built without the `onnx` feature.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OnnxError, PolicyOutput, check_input_dim, check_output_dim, decode_policy_output};
+    use crate::agent::action::{ActionMode, CarAction};
+    use crate::agent::observation::ObservationConfig;
+    use crate::agent::spaces::{ActionSpace, ObservationSpace};
+
+    const DISCRETE: ActionMode = ActionMode::Discrete {
+        steering_levels: 3,
+        throttle_levels: 2,
+    };
+
+    #[test]
+    fn model_shapes_are_checked_against_the_spaces() {
+        let observation = ObservationSpace::from_config(&ObservationConfig::default());
+        assert!(check_input_dim(Some(observation.dim), &observation).is_ok());
+        assert!(check_input_dim(None, &observation).is_ok());
+        assert!(matches!(
+            check_input_dim(Some(observation.dim + 1), &observation),
+            Err(OnnxError::InputShape { .. })
+        ));
+
+        let continuous = ActionSpace::for_mode(ActionMode::Continuous);
+        assert_eq!(
+            check_output_dim(Some(2), &continuous).ok(),
+            Some(PolicyOutput::Continuous)
+        );
+        assert!(check_output_dim(Some(6), &continuous).is_err());
+
+        let discrete = ActionSpace::for_mode(DISCRETE);
+        assert_eq!(
+            check_output_dim(Some(6), &discrete).ok(),
+            Some(PolicyOutput::Logits(6))
+        );
+        assert!(matches!(
+            check_output_dim(None, &discrete),
+            Err(OnnxError::OutputShape { found: None, .. })
+        ));
+    }
+
+    #[test]
+    fn outputs_decode_to_clamped_values_or_the_argmax_action() {
+        assert_eq!(
+            decode_policy_output(
+                PolicyOutput::Continuous,
+                ActionMode::Continuous,
+                &[1.5, 0.5]
+            ),
+            Some(CarAction {
+                steering: 1.0,
+                throttle: 0.5
+            })
+        );
+        assert_eq!(
+            decode_policy_output(PolicyOutput::Continuous, ActionMode::Continuous, &[0.5]),
+            None
+        );
+
+        let logits = [0.1, -2.0, 0.3, 4.0, 0.0, 1.0];
+        assert_eq!(
+            decode_policy_output(PolicyOutput::Logits(6), DISCRETE, &logits),
+            DISCRETE.decode(3).ok()
+        );
+    }
+
+    #[cfg(feature = "onnx")]
+    #[test]
+    fn committed_test_policy_produces_the_expected_action() {
+        use super::OnnxController;
+        use crate::agent::controller::{Controller, ControllerContext};
+        use crate::agent::observation::ObservationVector;
+        use crate::game::car::Car;
+        use crate::game::progress::TrackProgress;
+        use bevy::prelude::Transform;
+
+        let config = ObservationConfig::default();
+        let mut controller = OnnxController::load(
+            "assets/models/test_policy.onnx".as_ref(),
+            &ObservationSpace::from_config(&config),
+            &ActionSpace::for_mode(ActionMode::Continuous),
+            ActionMode::Continuous,
+        )
+        .expect("test policy fits the default observation");
+
+        // steering = heading_error, throttle = 0.5 * forward ray + 0.25.
+        let mut values = vec![0.0; config.observation_dim()];
+        values[13] = 0.4;
+        values[5] = 0.8;
+        let observation = ObservationVector { values };
+        let (progress, car) = (TrackProgress::default(), Car::default());
+        let ctx = ControllerContext::new(
+            &observation,
+            &progress,
+            &car,
+            &Transform::default(),
+            None,
+            1.0 / 60.0,
+        );
+        let action = controller.act(&ctx);
+
+        assert!((action.steering - 0.4).abs() < 1e-6);
+        assert!((action.throttle - 0.65).abs() < 1e-6);
+
+        let wider = ObservationSpace::from_config(&ObservationConfig {
+            include_corner_distance: true,
+            ..config
+        });
+        assert!(matches!(
+            OnnxController::load(
+                "assets/models/test_policy.onnx".as_ref(),
+                &wider,
+                &ActionSpace::for_mode(ActionMode::Continuous),
+                ActionMode::Continuous,
+            ),
+            Err(OnnxError::InputShape {
+                expected: 24,
+                found: 23
+            })
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+//! PID centreline follower, a scripted baseline controller.
+//!
+//! Two loops run each tick. A lateral PID on the signed lateral offset from
+//! the [`TrackProgress`] projection asks for a sideways acceleration, and a
+//! speed-hold PID on the progress rate (velocity along the centreline
+//! tangent) asks for a forward one. The car only accelerates along its
+//! heading, so the controller steers the heading towards the combined
+//! acceleration, never more than `max_heading_correction` off the tangent, and
+//! sets the throttle to its component along the heading.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::CarAction;
+use crate::agent::controller::{Controller, ControllerContext};
+
+/// Gains and anti-windup bound of one PID loop.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PidGains {
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+    /// The integral of the error is clamped to `±integral_limit`.
+    pub integral_limit: f32,
+}
+
+impl Default for PidGains {
+    fn default() -> Self {
+        Self {
+            kp: 1.0,
+            ki: 0.0,
+            kd: 0.0,
+            integral_limit: 10.0,
+        }
+    }
+}
+
+/// Tuning for [`PidController`]. Loop outputs are accelerations in world
+/// units per second squared.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PidControllerConfig {
+    /// Lateral loop; error is the negated signed lateral offset.
+    pub lateral: PidGains,
+    /// Speed-hold loop; error is `target_speed` minus the progress rate.
+    pub speed: PidGains,
+    pub target_speed: f32,
+    /// Steering per radian of heading error towards the demanded acceleration.
+    pub heading_gain: f32,
+    /// Largest heading offset from the tangent the lateral loop may demand,
+    /// in radians.
+    pub max_heading_correction: f32,
+}
+
+impl Default for PidControllerConfig {
+    fn default() -> Self {
+        Self {
+            lateral: PidGains {
+                kp: 10.0,
+                ki: 0.5,
+                kd: 4.0,
+                integral_limit: 20.0,
+            },
+            speed: PidGains {
+                kp: 2.0,
+                ki: 1.0,
+                kd: 0.0,
+                integral_limit: 50.0,
+            },
+            target_speed: 120.0,
+            heading_gain: 6.0,
+            max_heading_correction: 1.0,
+        }
+    }
+}
+
+/// P, I, and D contributions of one loop's last update.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PidTerms {
+    pub p: f32,
+    pub i: f32,
+    pub d: f32,
+}
+
+impl PidTerms {
+    pub fn output(&self) -> f32 {
+        self.p + self.i + self.d
+    }
+}
+
+/// One PID loop with a clamped, conditionally integrated error integral.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pid {
+    pub gains: PidGains,
+    integral: f32,
+    previous_error: Option<f32>,
+    pub terms: PidTerms,
+}
+
+impl Pid {
+    pub fn new(gains: PidGains) -> Self {
+        Self {
+            gains,
+            ..Self::default()
+        }
+    }
+
+    /// Clears the integral and derivative history so nothing carries across
+    /// an episode reset.
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.previous_error = None;
+        self.terms = PidTerms::default();
+    }
+
+    pub fn integral(&self) -> f32 {
+        self.integral
+    }
+
+    /// Advances the loop by `dt` and returns its output.
+    ///
+    /// `error_rate` is the error's time derivative when known analytically;
+    /// otherwise it is differenced from the previous error (zero on the first
+    /// update). The integral stops growing while the output is saturated
+    /// outside `[min, max]` in the direction the error pushes, so a long
+    /// saturation cannot wind it up.
+    pub fn update(
+        &mut self,
+        error: f32,
+        error_rate: Option<f32>,
+        dt: f32,
+        min: f32,
+        max: f32,
+    ) -> f32 {
+        let rate = error_rate.unwrap_or_else(|| match self.previous_error {
+            Some(previous) if dt > 0.0 => (error - previous) / dt,
+            _ => 0.0,
+        });
+        self.previous_error = Some(error);
+
+        let limit = self.gains.integral_limit.abs();
+        let candidate = (self.integral + error * dt).clamp(-limit, limit);
+        let p = self.gains.kp * error;
+        let d = self.gains.kd * rate;
+        let unclamped = p + self.gains.ki * candidate + d;
+        let winding_up = (unclamped > max && error > 0.0) || (unclamped < min && error < 0.0);
+        if !winding_up {
+            self.integral = candidate;
+        }
+
+        self.terms = PidTerms {
+            p,
+            i: self.gains.ki * self.integral,
+            d,
+        };
+        self.terms.output().clamp(min, max)
+    }
+}
+
+/// Follows the centreline with a lateral PID for steering and a speed-hold
+/// PID for throttle. See the module docs.
+pub struct PidController {
+    pub config: PidControllerConfig,
+    pub lateral: Pid,
+    pub speed: Pid,
+}
+
+impl PidController {
+    pub fn new(config: PidControllerConfig) -> Self {
+        Self {
+            config,
+            lateral: Pid::new(config.lateral),
+            speed: Pid::new(config.speed),
+        }
+    }
+}
+
+impl Controller for PidController {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+        let config = self.config;
+        let tangent = ctx.progress.tangent.normalize_or(Vec2::X);
+        let normal = tangent.perp();
+        let velocity = ctx.car.velocity;
+        let offset = (ctx.position - ctx.progress.closest_point).dot(normal);
+
+        let thrust = ctx.car.thrust;
+        let lateral_accel = self.lateral.update(
+            -offset,
+            Some(-velocity.dot(normal)),
+            ctx.dt,
+            -thrust,
+            thrust,
+        );
+        let forward_accel = self.speed.update(
+            config.target_speed - velocity.dot(tangent),
+            None,
+            ctx.dt,
+            0.0,
+            thrust,
+        );
+
+        let correction = lateral_accel.atan2(forward_accel.max(1e-3)).clamp(
+            -config.max_heading_correction,
+            config.max_heading_correction,
+        );
+        let target = Vec2::from_angle(tangent.to_angle() + correction);
+        let heading_error = ctx.forward.angle_to(target);
+        let demand = tangent * forward_accel + normal * lateral_accel;
+
+        CarAction {
+            steering: (-config.heading_gain * heading_error).clamp(-1.0, 1.0),
+            throttle: (demand.dot(ctx.forward) / ctx.car.thrust.max(1e-3)).clamp(0.0, 1.0),
+        }
+    }
+
+    fn begin_episode(&mut self, _episode: u32) {
+        self.lateral.reset();
+        self.speed.reset();
+    }
+
+    fn telemetry(&self, out: &mut Vec<(&'static str, f32)>) {
+        out.extend([
+            ("lateral_p", self.lateral.terms.p),
+            ("lateral_i", self.lateral.terms.i),
+            ("lateral_d", self.lateral.terms.d),
+            ("speed_p", self.speed.terms.p),
+            ("speed_i", self.speed.terms.i),
+            ("speed_d", self.speed.terms.d),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pid, PidController, PidControllerConfig, PidGains};
+    use crate::config::AppConfig;
+    use crate::eval::env::HeadlessEnv;
+    use crate::game::episode::{EpisodeConfig, EpisodeEndReason};
+    use crate::maps::centerline::{GridDir, TrackCenterline};
+    use crate::maps::grid::TrackGrid;
+    use crate::maps::parts::TilePart;
+    use crate::maps::track::Track;
+    use bevy::prelude::Vec2;
+
+    fn track_from(grid: TrackGrid) -> Track {
+        let spawn = grid.find_spawn_cell().expect("spawn tile");
+        let centerline =
+            TrackCenterline::build_closed_loop(&grid, spawn, GridDir::East).expect("closed loop");
+        let (spawn_position, spawn_rotation) = grid.find_spawn().expect("spawn tile");
+        Track {
+            grid,
+            spawn_position,
+            spawn_rotation,
+            centerline: Some(centerline),
+        }
+    }
+
+    fn oval_track() -> Track {
+        use TilePart::*;
+        track_from(TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, StraightH, StraightH, CornerNE],
+                vec![CornerSW, StraightH, StraightH, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 100.0),
+        ))
+    }
+
+    fn config_with_timeout(timeout_s: f32) -> AppConfig {
+        AppConfig {
+            episode: EpisodeConfig {
+                timeout_s,
+                ..EpisodeConfig::default()
+            },
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn integral_is_clamped_and_cleared_on_reset() {
+        let mut pid = Pid::new(PidGains {
+            kp: 0.0,
+            ki: 1.0,
+            kd: 0.0,
+            integral_limit: 2.0,
+        });
+        for _ in 0..100 {
+            pid.update(1.0, None, 0.1, f32::MIN, f32::MAX);
+        }
+        assert_eq!(pid.integral(), 2.0);
+
+        // The integral ramps the output up to `max` in five ticks; once
+        // saturated in the error's direction the output holds at `max` and
+        // the integral stops growing.
+        pid.reset();
+        let outputs: Vec<f32> = (0..20)
+            .map(|_| pid.update(1.0, None, 0.1, -0.5, 0.5))
+            .collect();
+        assert!((outputs[0] - 0.1).abs() < 1e-6, "{outputs:?}");
+        assert!(outputs.iter().all(|&output| output <= 0.5), "{outputs:?}");
+        assert!(
+            outputs[5..].iter().all(|&output| output == 0.5),
+            "{outputs:?}"
+        );
+        assert!(pid.integral() <= 0.5 + 1e-6, "{}", pid.integral());
+
+        pid.reset();
+        assert_eq!(pid.integral(), 0.0);
+        assert_eq!(pid.terms.output(), 0.0);
+    }
+
+    #[test]
+    fn holds_the_centreline_on_the_oval_straights() {
+        let mut env = HeadlessEnv::new(oval_track(), &config_with_timeout(60.0), 3);
+        let mut controller = PidController::new(PidControllerConfig {
+            target_speed: 100.0,
+            ..PidControllerConfig::default()
+        });
+
+        let mut worst: f32 = 0.0;
+        for _ in 0..2400 {
+            if let Some(outcome) = env.step_controller(&mut controller) {
+                assert_ne!(outcome.end_reason, EpisodeEndReason::Crash);
+            }
+            // Mid-straight tiles only; the corners are judged by not crashing.
+            if env.progress().closest_point.x.abs() <= 50.0 {
+                worst = worst.max(env.sensors().signed_lateral_offset.abs());
+            }
+        }
+        assert!(worst < 5.0, "max straight offset {worst}");
+    }
+
+    #[test]
+    fn laps_sepang_without_crashing() {
+        let track = crate::maps::monaco::build_monaco_track();
+        let mut env = HeadlessEnv::new(track, &config_with_timeout(200.0), 3);
+        let mut controller = PidController::new(PidControllerConfig {
+            target_speed: 60.0,
+            ..PidControllerConfig::default()
+        });
+
+        let outcome = (0..12_000)
+            .find_map(|_| env.step_controller(&mut controller))
+            .expect("episode ends");
+        assert_eq!(outcome.end_reason, EpisodeEndReason::LapComplete);
+    }
+}
@@ -1,11 +1,21 @@
 use bevy::prelude::*;
 
 use crate::agent::action::{
-    ActionSmoothing, ActionState, action_smoothing_system, keyboard_action_input_system,
+    ActionMode, ActionSmoothing, ActionState, KeyboardConfig, KeyboardStick, SteeringAssist,
+    action_smoothing_system, keyboard_action_input_system, steering_assist_input_system,
+};
+use crate::agent::controller::{Controllers, registered_controller_system};
+use crate::agent::exploration::{ExplorationConfig, ExplorationNoise, exploration_noise_system};
+use crate::agent::gamepad::{
+    ActiveGamepad, GamepadConfig, active_gamepad_system, gamepad_action_input_system,
 };
 use crate::agent::observation::{
-    ObservationConfig, build_observation_vector_system, update_sensor_readings_system,
+    ObservationConfig, RayPresetSwitch, apply_observation_delay_system,
+    apply_ray_preset_switch_system, build_observation_vector_system, ray_preset_cycle_input_system,
+    update_sensor_readings_system,
 };
+use crate::agent::pid::PidControllerConfig;
+use crate::agent::scripted::ScriptedControllerConfig;
 use crate::game::episode::episode_loop_system;
 use crate::game::progress::update_track_progress_system;
 use crate::sim::sets::SimSet;
@@ -15,23 +25,57 @@ pub struct AgentPlugin;
 
 impl Plugin for AgentPlugin {
     fn build(&self, app: &mut App) {
+        // Configs inserted without `AppConfig::from_ron` are checked here.
+        if let Err(err) = app
+            .world()
+            .get_resource::<ObservationConfig>()
+            .map_or(Ok(()), ObservationConfig::validate)
+        {
+            panic!("invalid observation config: {err}");
+        }
+
         app.init_resource::<ActionState>()
+            .init_resource::<ActionMode>()
+            .init_resource::<KeyboardConfig>()
+            .init_resource::<KeyboardStick>()
+            .init_resource::<GamepadConfig>()
+            .init_resource::<ActiveGamepad>()
+            .init_resource::<PidControllerConfig>()
+            .init_resource::<ScriptedControllerConfig>()
+            .init_resource::<Controllers>()
             .init_resource::<ActionSmoothing>()
+            .init_resource::<ExplorationConfig>()
+            .init_resource::<ExplorationNoise>()
+            .init_resource::<SteeringAssist>()
             .init_resource::<ObservationConfig>()
+            .init_resource::<RayPresetSwitch>()
+            .add_systems(
+                Update,
+                (steering_assist_input_system, ray_preset_cycle_input_system),
+            )
             // Actions must be updated on the fixed simulation tick.
             .add_systems(
                 FixedUpdate,
-                (keyboard_action_input_system, action_smoothing_system)
+                (
+                    active_gamepad_system,
+                    keyboard_action_input_system,
+                    gamepad_action_input_system,
+                    registered_controller_system,
+                    exploration_noise_system,
+                    action_smoothing_system,
+                )
                     .chain()
                     .in_set(SimSet::Input),
             )
             .add_systems(
                 FixedUpdate,
                 (
+                    apply_ray_preset_switch_system.after(episode_loop_system),
                     update_sensor_readings_system
                         .after(update_track_progress_system)
                         .after(episode_loop_system),
                     build_observation_vector_system,
+                    apply_observation_delay_system,
                 )
                     .chain()
                     .in_set(SimSet::Measurement),
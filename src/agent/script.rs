@@ -0,0 +1,417 @@
+//! Hand-written action scripts for reproducible scenarios.
+//!
+//! A script is a CSV of `tick,steering,throttle` rows. Each row's action is
+//! held from its episode tick until the next row; the last row marks the
+//! final scripted tick, after which [`ScriptController`] ends the episode or
+//! starts the script over. Unlike a recorded replay, scripts are meant to be
+//! written by hand, so a regression such as "this 400-tick sequence clipped
+//! the wall" can be committed as a few lines of text.
+
+use std::fmt;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::CarAction;
+use crate::agent::controller::{Controller, ControllerContext, Controllers};
+use crate::brain::types::AgentMode;
+
+/// One script row: the action held from episode tick `tick` onwards.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScriptStep {
+    pub tick: u32,
+    pub action: CarAction,
+}
+
+/// A validated action script with strictly increasing ticks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionScript {
+    steps: Vec<ScriptStep>,
+}
+
+/// Reasons an action script fails to load.
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The script has no rows.
+    Empty,
+    /// A row is not three numeric columns.
+    Malformed { line: usize, text: String },
+    /// A row's tick is not after the previous row's.
+    NonMonotonicTick {
+        line: usize,
+        tick: u32,
+        previous: u32,
+    },
+    /// A steering value outside `[-1, 1]` or a throttle outside `[0, 1]`.
+    OutOfRange {
+        line: usize,
+        column: &'static str,
+        value: f32,
+    },
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::Empty => write!(f, "script has no rows"),
+            Self::Malformed { line, text } => {
+                write!(
+                    f,
+                    "line {line}: expected `tick,steering,throttle`, got `{text}`"
+                )
+            }
+            Self::NonMonotonicTick {
+                line,
+                tick,
+                previous,
+            } => write!(
+                f,
+                "line {line}: tick {tick} does not follow the previous tick {previous}"
+            ),
+            Self::OutOfRange {
+                line,
+                column,
+                value,
+            } => write!(f, "line {line}: {column} {value} is out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl ActionScript {
+    /// Reads and parses a script file.
+    pub fn load(path: &Path) -> Result<Self, ScriptError> {
+        let text = std::fs::read_to_string(path).map_err(ScriptError::Io)?;
+        Self::parse_csv(&text)
+    }
+
+    /// Parses `tick,steering,throttle` rows. Blank lines, `#` comments and a
+    /// leading header row starting with `tick` are skipped; line numbers in
+    /// errors are 1-based.
+    pub fn parse_csv(text: &str) -> Result<Self, ScriptError> {
+        let mut steps: Vec<ScriptStep> = Vec::new();
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let row = raw.trim();
+            if row.is_empty() || row.starts_with('#') {
+                continue;
+            }
+            if steps.is_empty() && row.starts_with("tick") {
+                continue;
+            }
+            let step = parse_row(row).ok_or_else(|| ScriptError::Malformed {
+                line,
+                text: row.to_string(),
+            })?;
+            let previous = steps.last().map(|step| step.tick);
+            if let Some(previous) = previous.filter(|previous| step.tick <= *previous) {
+                return Err(ScriptError::NonMonotonicTick {
+                    line,
+                    tick: step.tick,
+                    previous,
+                });
+            }
+            let CarAction { steering, throttle } = step.action;
+            if !(-1.0..=1.0).contains(&steering) {
+                return Err(ScriptError::OutOfRange {
+                    line,
+                    column: "steering",
+                    value: steering,
+                });
+            }
+            if !(0.0..=1.0).contains(&throttle) {
+                return Err(ScriptError::OutOfRange {
+                    line,
+                    column: "throttle",
+                    value: throttle,
+                });
+            }
+            steps.push(step);
+        }
+        if steps.is_empty() {
+            return Err(ScriptError::Empty);
+        }
+        Ok(Self { steps })
+    }
+
+    pub fn steps(&self) -> &[ScriptStep] {
+        &self.steps
+    }
+
+    /// Tick of the last row: the final scripted tick.
+    pub fn end_tick(&self) -> u32 {
+        self.steps.last().map_or(0, |step| step.tick)
+    }
+
+    /// Action held at `tick`: the latest row at or before it, or the neutral
+    /// action before the first row.
+    pub fn action_at(&self, tick: u32) -> CarAction {
+        let held = self.steps.partition_point(|step| step.tick <= tick);
+        held.checked_sub(1)
+            .map_or(CarAction::default(), |index| self.steps[index].action)
+    }
+}
+
+fn parse_row(row: &str) -> Option<ScriptStep> {
+    let mut columns = row.split(',').map(str::trim);
+    let tick = columns.next()?.parse().ok()?;
+    let steering = columns.next()?.parse().ok()?;
+    let throttle = columns.next()?.parse().ok()?;
+    if columns.next().is_some() {
+        return None;
+    }
+    Some(ScriptStep {
+        tick,
+        action: CarAction { steering, throttle },
+    })
+}
+
+/// What a [`ScriptController`] does after the script's final tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScriptEnd {
+    /// Ask for the episode to end on the final scripted tick.
+    #[default]
+    EndEpisode,
+    /// Start the script over from tick 0, within the same episode.
+    Loop,
+}
+
+/// Script selection for the replay controller slot (config `script`).
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptControllerConfig {
+    /// Script CSV to load at startup; `None` keeps the recorded-replay
+    /// controller in the `replay` slot.
+    pub path: Option<String>,
+    pub at_end: ScriptEnd,
+}
+
+/// Plays an [`ActionScript`] by episode tick.
+pub struct ScriptController {
+    script: ActionScript,
+    at_end: ScriptEnd,
+    finished: bool,
+}
+
+impl ScriptController {
+    pub fn new(script: ActionScript, at_end: ScriptEnd) -> Self {
+        Self {
+            script,
+            at_end,
+            finished: false,
+        }
+    }
+}
+
+impl Controller for ScriptController {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+        let end = self.script.end_tick();
+        let tick = match self.at_end {
+            ScriptEnd::EndEpisode => ctx.tick,
+            ScriptEnd::Loop => ctx.tick % (end + 1),
+        };
+        self.finished = self.at_end == ScriptEnd::EndEpisode && tick >= end;
+        self.script.action_at(tick)
+    }
+
+    fn begin_episode(&mut self, _episode: u32) {
+        self.finished = false;
+    }
+
+    fn wants_episode_end(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Loads the configured script into `controllers` under
+/// [`AgentMode::Replay`], logging instead when it cannot be read.
+pub fn register_script_controller(world: &World, controllers: &mut Controllers) {
+    let Some(config) = world
+        .get_resource::<ScriptControllerConfig>()
+        .filter(|config| config.path.is_some())
+    else {
+        return;
+    };
+    let path = config.path.as_deref().unwrap_or_default();
+    match ActionScript::load(Path::new(path)) {
+        Ok(script) => {
+            info!(
+                "Loaded action script {path} ({} rows, {} ticks).",
+                script.steps().len(),
+                script.end_tick() + 1
+            );
+            controllers.register(
+                AgentMode::Replay,
+                ScriptController::new(script, config.at_end),
+            );
+        }
+        Err(err) => warn!("Cannot load action script {path}: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use bevy::prelude::Transform;
+
+    use super::{ActionScript, ScriptController, ScriptEnd, ScriptError};
+    use crate::agent::action::CarAction;
+    use crate::agent::controller::{Controller, ControllerContext};
+    use crate::agent::observation::ObservationVector;
+    use crate::config::AppConfig;
+    use crate::eval::env::HeadlessEnv;
+    use crate::game::car::Car;
+    use crate::game::episode::EpisodeEndReason;
+    use crate::game::progress::TrackProgress;
+    use crate::maps::fixtures::ring_track;
+
+    fn asset(path: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join(path)
+    }
+
+    #[test]
+    fn actions_are_held_until_the_next_row() {
+        let script =
+            ActionScript::parse_csv("tick,steering,throttle\n# warm up\n2, 0.5, 1\n\n5,-1,0\n")
+                .expect("valid script");
+
+        assert_eq!(script.action_at(0), CarAction::default());
+        assert_eq!(script.action_at(2).steering, 0.5);
+        assert_eq!(script.action_at(4).throttle, 1.0);
+        assert_eq!(script.action_at(5).steering, -1.0);
+        assert_eq!(script.end_tick(), 5);
+    }
+
+    #[test]
+    fn loader_rejects_unordered_ticks_bad_values_and_empty_scripts() {
+        assert!(matches!(
+            ActionScript::parse_csv("0,0,1\n10,0,1\n10,0,0\n"),
+            Err(ScriptError::NonMonotonicTick {
+                line: 3,
+                tick: 10,
+                previous: 10
+            })
+        ));
+        assert!(matches!(
+            ActionScript::parse_csv("0,1.5,1\n"),
+            Err(ScriptError::OutOfRange {
+                line: 1,
+                column: "steering",
+                ..
+            })
+        ));
+        assert!(matches!(
+            ActionScript::parse_csv("0,0,-0.1\n"),
+            Err(ScriptError::OutOfRange {
+                column: "throttle",
+                ..
+            })
+        ));
+        assert!(matches!(
+            ActionScript::parse_csv("0,0,NaN\n"),
+            Err(ScriptError::OutOfRange { .. })
+        ));
+        assert!(matches!(
+            ActionScript::parse_csv("0,0\n"),
+            Err(ScriptError::Malformed { line: 1, .. })
+        ));
+        assert!(matches!(
+            ActionScript::parse_csv("# nothing\n"),
+            Err(ScriptError::Empty)
+        ));
+    }
+
+    #[test]
+    fn full_throttle_scenario_crashes_into_the_first_corner() {
+        let script = ActionScript::load(&asset("scenarios/ring_full_throttle_into_corner.csv"))
+            .expect("scenario loads");
+        let mut env = HeadlessEnv::new(ring_track(), &AppConfig::default(), 7);
+        let mut controller = ScriptController::new(script.clone(), ScriptEnd::EndEpisode);
+
+        let outcome = (0..=script.end_tick())
+            .find_map(|_| env.step_controller(&mut controller))
+            .expect("episode ends within the script");
+
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Crash);
+        assert!(outcome.ticks <= script.end_tick());
+        // The straight and the first corner are under a quarter of the loop.
+        assert!(
+            outcome.best_progress_fraction < 0.25,
+            "{}",
+            outcome.best_progress_fraction
+        );
+    }
+
+    #[test]
+    fn creep_scenario_stops_short_of_the_corner_and_ends_on_its_last_tick() {
+        let script = ActionScript::load(&asset("scenarios/ring_creep_and_coast.csv"))
+            .expect("scenario loads");
+        let mut env = HeadlessEnv::new(ring_track(), &AppConfig::default(), 7);
+        let mut controller = ScriptController::new(script.clone(), ScriptEnd::EndEpisode);
+        assert!(env.step_controller(&mut controller).is_none());
+        let start_s = env.progress().s;
+
+        let mut last_s = start_s;
+        let outcome = (1..=script.end_tick())
+            .find_map(|_| {
+                let outcome = env.step_controller(&mut controller);
+                if outcome.is_none() {
+                    last_s = env.progress().s;
+                }
+                outcome
+            })
+            .expect("the script ends the episode");
+
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Timeout);
+        assert_eq!(outcome.ticks, script.end_tick() + 1);
+        let travelled = last_s - start_s;
+        assert!(travelled > 10.0 && travelled < 80.0, "{travelled}");
+    }
+
+    #[test]
+    fn looping_scripts_restart_and_ending_scripts_ask_for_a_reset() {
+        let script = ActionScript::parse_csv("0,0,1\n2,0.5,0\n").expect("valid script");
+        let (observation, progress, car) = (
+            ObservationVector { values: Vec::new() },
+            TrackProgress::default(),
+            Car::default(),
+        );
+        let mut ctx = ControllerContext::new(
+            &observation,
+            &progress,
+            &car,
+            &Transform::default(),
+            None,
+            0.0,
+        );
+
+        let mut looping = ScriptController::new(script.clone(), ScriptEnd::Loop);
+        let throttles: Vec<f32> = (0..7)
+            .map(|tick| {
+                ctx.tick = tick;
+                let action = looping.act(&ctx);
+                assert!(!looping.wants_episode_end());
+                action.throttle
+            })
+            .collect();
+        assert_eq!(throttles, [1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+
+        let mut ending = ScriptController::new(script, ScriptEnd::EndEpisode);
+        ctx.tick = 1;
+        ending.act(&ctx);
+        assert!(!ending.wants_episode_end());
+        ctx.tick = 2;
+        ending.act(&ctx);
+        assert!(ending.wants_episode_end());
+        ending.begin_episode(2);
+        assert!(!ending.wants_episode_end());
+    }
+}
@@ -0,0 +1,413 @@
+//! Observation- and action-space descriptors for external trainers.
+//!
+//! Descriptors are generated from the live configs by walking the same
+//! [`FeatureBlock`](crate::agent::observation::FeatureBlock) layout the
+//! observation builder uses, so they always match the runtime vector.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionMode, ActionRateLimits, CarAction, DiscreteActionAdapter};
+use crate::agent::observation::ObservationConfig;
+
+/// One named scalar with its inclusive bounds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeatureSpec {
+    pub name: String,
+    pub low: f32,
+    pub high: f32,
+}
+
+impl FeatureSpec {
+    pub fn new(name: impl Into<String>, low: f32, high: f32) -> Self {
+        Self {
+            name: name.into(),
+            low,
+            high,
+        }
+    }
+}
+
+/// How a feature's raw sensor value becomes its normalised value. Every
+/// feature uses a fixed rule; none adapt to the data seen at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Normalization {
+    /// The raw range `[min, max]` maps linearly onto the feature bounds and
+    /// is clamped to them.
+    MinMax { min: f32, max: f32 },
+    /// World position over the loaded track's bounding box.
+    TrackBounds,
+    /// A class id from a fixed table, such as
+    /// [`crate::maps::grid::RayHitClass::normalized_id`].
+    Categorical,
+    /// Already in range; passed through.
+    Identity,
+}
+
+/// Geometry of one ray sensor, in the car frame (`x` forward, `y` left).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RaySpec {
+    /// Angle from the car's forward axis, in radians.
+    pub angle: f32,
+    /// Point the ray is cast from, in world units.
+    pub mount: Vec2,
+    /// Distance at which the ray reports no hit, in world units.
+    pub range: f32,
+}
+
+/// Shape, bounds, and feature names of the observation vector, plus the
+/// ray geometry behind the `ray_*` features.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObservationSpace {
+    pub dim: usize,
+    pub features: Vec<FeatureSpec>,
+    pub rays: Vec<RaySpec>,
+}
+
+impl ObservationSpace {
+    pub fn from_config(config: &ObservationConfig) -> Self {
+        let features: Vec<FeatureSpec> = config
+            .feature_blocks()
+            .flat_map(|block| block.features(config))
+            .collect();
+        let rays = config
+            .ray_angles
+            .iter()
+            .enumerate()
+            .map(|(index, &angle)| RaySpec {
+                angle,
+                mount: config.ray_mount(index),
+                range: config.ray_range(index),
+            })
+            .collect();
+        Self {
+            dim: features.len(),
+            features,
+            rays,
+        }
+    }
+}
+
+/// Shape and ranges of [`crate::agent::action::CarAction`], plus the number
+/// of discrete actions in [`ActionMode::Discrete`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionSpace {
+    pub dim: usize,
+    pub actions: Vec<FeatureSpec>,
+    /// Valid action indices are `0..count` when set.
+    pub discrete_count: Option<usize>,
+    /// Dynamics note: how fast the applied action may follow a new one.
+    #[serde(default)]
+    pub rate_limits: ActionRateLimits,
+}
+
+impl ActionSpace {
+    pub fn for_mode(mode: ActionMode) -> Self {
+        let actions = vec![
+            FeatureSpec::new("steering", -1.0, 1.0),
+            FeatureSpec::new("throttle", 0.0, 1.0),
+        ];
+        Self {
+            dim: actions.len(),
+            actions,
+            discrete_count: mode.discrete_count(),
+            rate_limits: ActionRateLimits::default(),
+        }
+    }
+
+    pub fn with_rate_limits(self, rate_limits: ActionRateLimits) -> Self {
+        Self {
+            rate_limits,
+            ..self
+        }
+    }
+}
+
+/// The discrete actions a [`DiscreteActionAdapter`] decodes: index `i` of
+/// `0..count` applies `actions[i]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiscreteActionSpace {
+    pub count: usize,
+    pub actions: Vec<CarAction>,
+}
+
+impl DiscreteActionSpace {
+    pub fn from_adapter(adapter: &DiscreteActionAdapter) -> Self {
+        Self {
+            count: adapter.len(),
+            actions: adapter.actions().to_vec(),
+        }
+    }
+}
+
+/// Both spaces, as printed by `--print-spaces`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EnvSpaces {
+    pub observation: ObservationSpace,
+    pub action: ActionSpace,
+}
+
+impl EnvSpaces {
+    pub fn new(observation: &ObservationConfig, action_mode: ActionMode) -> Self {
+        Self {
+            observation: ObservationSpace::from_config(observation),
+            action: ActionSpace::for_mode(action_mode),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// File name of the [`ObservationManifest`] written into a run's output
+/// directories.
+pub const OBSERVATION_MANIFEST_FILE: &str = "observation_manifest.json";
+
+/// One observation feature with its bounds and normalisation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestFeature {
+    pub name: String,
+    pub low: f32,
+    pub high: f32,
+    pub normalization: Normalization,
+    /// Read from rays or the occupancy patch, so up to
+    /// `sensor_update_interval - 1` ticks stale.
+    #[serde(default)]
+    pub decimated: bool,
+}
+
+/// Everything needed to interpret a run's observation vectors and actions
+/// without the config that produced them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObservationManifest {
+    pub crate_version: String,
+    /// [`crate::sim::rng::RunSeeds::config_hash`] as 16 hex digits.
+    pub config_hash: String,
+    pub dim: usize,
+    /// In vector order.
+    pub features: Vec<ManifestFeature>,
+    pub rays: Vec<RaySpec>,
+    /// Observations per policy input. Frames are not stacked yet, so this is
+    /// always 1.
+    pub stacking_depth: usize,
+    pub observation_delay_ticks: u32,
+    /// [`ObservationConfig::sensor_update_interval`]; features flagged
+    /// `decimated` only refresh on every this-many ticks.
+    pub sensor_update_interval: u32,
+    pub action: ActionSpace,
+    /// Index-to-action table in [`ActionMode::Discrete`].
+    pub discrete_actions: Option<DiscreteActionSpace>,
+}
+
+impl ObservationManifest {
+    pub fn new(observation: &ObservationConfig, action_mode: ActionMode, config_hash: u64) -> Self {
+        let space = ObservationSpace::from_config(observation);
+        let decimating = observation.sensor_update_interval > 1;
+        let normalizations = observation.feature_blocks().flat_map(|block| {
+            let decimated = decimating && block.is_decimated();
+            block
+                .normalizations(observation)
+                .into_iter()
+                .map(move |normalization| (normalization, decimated))
+        });
+        let features = space
+            .features
+            .into_iter()
+            .zip(normalizations)
+            .map(|(feature, (normalization, decimated))| ManifestFeature {
+                name: feature.name,
+                low: feature.low,
+                high: feature.high,
+                normalization,
+                decimated,
+            })
+            .collect();
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            config_hash: format!("{config_hash:016x}"),
+            dim: space.dim,
+            features,
+            rays: space.rays,
+            stacking_depth: 1,
+            observation_delay_ticks: observation.observation_delay_ticks,
+            sensor_update_interval: observation.sensor_update_interval,
+            action: ActionSpace::for_mode(action_mode),
+            discrete_actions: action_mode
+                .discrete_adapter()
+                .map(|adapter| DiscreteActionSpace::from_adapter(&adapter)),
+        }
+    }
+
+    /// Writes the manifest as [`OBSERVATION_MANIFEST_FILE`] in `dir`,
+    /// creating the directory, and returns the file's path.
+    pub fn write_to(&self, dir: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        let path = dir.join(OBSERVATION_MANIFEST_FILE);
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EnvSpaces, Normalization, ObservationManifest, ObservationSpace};
+    use crate::agent::action::ActionMode;
+    use crate::agent::observation::{
+        FeatureBlock, ObservationConfig, ObservationVector, SensorReadings,
+        build_observation_vector_system,
+    };
+    use bevy::prelude::*;
+
+    fn runtime_len(config: &ObservationConfig) -> usize {
+        let mut app = App::new();
+        app.insert_resource(config.clone())
+            .add_systems(Update, build_observation_vector_system);
+        // Start from an empty vector so the length comes from the builder.
+        let car = app
+            .world_mut()
+            .spawn((
+                SensorReadings::for_config(config),
+                ObservationVector { values: Vec::new() },
+            ))
+            .id();
+        app.update();
+        app.world()
+            .get::<ObservationVector>(car)
+            .unwrap()
+            .values
+            .len()
+    }
+
+    #[test]
+    fn descriptor_matches_the_runtime_vector_under_every_flag_combination() {
+        for mask in 0..512u32 {
+            let config = ObservationConfig {
+                include_speed_along_track: mask & 1 != 0,
+                include_corner_distance: mask & 2 != 0,
+                include_body_velocity: mask & 4 != 0,
+                include_previous_action: mask & 8 != 0,
+                include_grid_position: mask & 16 != 0,
+                include_occupancy_grid: mask & 32 != 0,
+                include_lap_progress: mask & 64 != 0,
+                include_time_remaining: mask & 128 != 0,
+                include_ray_incidence: mask & 256 != 0,
+                occupancy_rows: 1 + mask as usize % 3,
+                ray_angles: (0..3 + mask as usize % 5).map(|i| i as f32 * 0.2).collect(),
+                ..ObservationConfig::default()
+            };
+            let space = ObservationSpace::from_config(&config);
+
+            assert_eq!(space.dim, space.features.len());
+            assert_eq!(space.dim, config.observation_dim(), "mask {mask}");
+            assert_eq!(space.dim, runtime_len(&config), "mask {mask}");
+            for block in FeatureBlock::ALL {
+                assert_eq!(block.features(&config).len(), block.len(&config));
+                assert_eq!(block.normalizations(&config).len(), block.len(&config));
+            }
+            let mut names: Vec<&str> = space.features.iter().map(|f| f.name.as_str()).collect();
+            names.sort_unstable();
+            names.dedup();
+            assert_eq!(names.len(), space.dim, "feature names are unique");
+        }
+    }
+
+    #[test]
+    fn manifests_of_two_configs_differ_exactly_in_the_changed_entries() {
+        let base = ObservationConfig::default();
+        let changed = ObservationConfig {
+            ray_max_range: base.ray_max_range * 2.0,
+            observation_delay_ticks: base.observation_delay_ticks + 2,
+            ..base.clone()
+        };
+        let a = ObservationManifest::new(&base, ActionMode::Continuous, 0x1111);
+        let b = ObservationManifest::new(&changed, ActionMode::Continuous, 0x2222);
+
+        let to_map = |manifest: &ObservationManifest| match serde_json::to_value(manifest) {
+            Ok(serde_json::Value::Object(map)) => map,
+            other => panic!("manifest is not an object: {other:?}"),
+        };
+        let (a_map, b_map) = (to_map(&a), to_map(&b));
+        let mut differing: Vec<&str> = a_map
+            .keys()
+            .filter(|key| a_map[*key] != b_map[*key])
+            .map(String::as_str)
+            .collect();
+        differing.sort_unstable();
+        assert_eq!(
+            differing,
+            ["config_hash", "features", "observation_delay_ticks", "rays"]
+        );
+
+        // Only the ray features change, and only in their normalisation.
+        assert_eq!(a.features.len(), b.features.len());
+        for (index, (fa, fb)) in a.features.iter().zip(&b.features).enumerate() {
+            assert_eq!((&fa.name, fa.low, fa.high), (&fb.name, fb.low, fb.high));
+            if index < base.num_rays() {
+                assert_eq!(
+                    fb.normalization,
+                    Normalization::MinMax {
+                        min: 0.0,
+                        max: changed.ray_max_range
+                    }
+                );
+            } else {
+                assert_eq!(fa.normalization, fb.normalization, "{}", fa.name);
+            }
+        }
+        assert!(b.rays.iter().all(|ray| ray.range == changed.ray_max_range));
+        assert_eq!(a.config_hash, "0000000000001111");
+        assert_eq!(a.stacking_depth, 1);
+        assert_eq!(a.crate_version, env!("CARGO_PKG_VERSION"));
+        assert!(a.discrete_actions.is_none());
+    }
+
+    #[test]
+    fn spaces_serialize_with_names_and_bounds() {
+        let config = ObservationConfig {
+            include_previous_action: true,
+            ray_mount_offset: Vec2::new(6.0, 0.0),
+            ..ObservationConfig::default()
+        };
+        let spaces = EnvSpaces::new(
+            &config,
+            ActionMode::Discrete {
+                steering_levels: 3,
+                throttle_levels: 2,
+            },
+        );
+        let json = spaces.to_json().expect("serializes");
+        let parsed: EnvSpaces = serde_json::from_str(&json).expect("parses");
+
+        assert_eq!(parsed, spaces);
+        assert_eq!(spaces.action.dim, 2);
+        assert_eq!(spaces.action.discrete_count, Some(6));
+        assert_eq!(
+            EnvSpaces::new(&config, ActionMode::Continuous)
+                .action
+                .discrete_count,
+            None
+        );
+        assert_eq!(spaces.observation.features[0].name, "ray_0");
+        assert_eq!(spaces.observation.rays.len(), config.num_rays());
+        assert!(
+            spaces
+                .observation
+                .rays
+                .iter()
+                .zip(&config.ray_angles)
+                .all(|(ray, angle)| ray.angle == *angle && ray.mount == Vec2::new(6.0, 0.0))
+        );
+        let last = spaces.observation.features.last().unwrap();
+        assert_eq!(
+            (last.name.as_str(), last.low, last.high),
+            ("previous_throttle", 0.0, 1.0)
+        );
+    }
+}
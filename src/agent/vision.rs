@@ -0,0 +1,425 @@
+//! Low-resolution camera observations rendered to an offscreen texture.
+//!
+//! A second 2D camera draws the world into a small RGBA image, which is read
+//! back to the CPU and sampled on the fixed tick as a [`VisionFrame`]. This
+//! needs the render pipeline, so it is only available in windowed runs:
+//! [`crate::eval::env::HeadlessEnv`] and the other headless modes build on
+//! `MinimalPlugins` and never produce a frame.
+
+use std::f32::consts::FRAC_PI_2;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::RenderLayers;
+use bevy::camera::{RenderTarget, ScalingMode};
+use bevy::prelude::*;
+use bevy::render::gpu_readback::{Readback, ReadbackComplete};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use serde::{Deserialize, Serialize};
+
+use crate::analytics::trackers::transitions::TransitionRecorderConfig;
+use crate::debug::focus::FocusedCar;
+use crate::game::car::Car;
+use crate::game::episode::EpisodeState;
+use crate::sim::sets::SimSet;
+
+/// Render layer carrying debug gizmos while the vision camera is active. The
+/// main camera sees it; the vision camera does not, so overlays never leak
+/// into image observations.
+pub const DEBUG_RENDER_LAYER: usize = 1;
+
+/// Subdirectory of the recorder directory that frame PNGs are written to.
+pub const VISION_FRAMES_DIR: &str = "frames";
+
+/// Where the vision camera looks from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisionView {
+    /// North-up view centred on the car.
+    #[default]
+    TopDown,
+    /// Heading-up view placed ahead of the car, so the car sits in the lower
+    /// part of the frame facing up.
+    Chase,
+}
+
+/// Offscreen camera observation. Off by default.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisionConfig {
+    pub enabled: bool,
+    /// Frame size in pixels.
+    pub width: u32,
+    pub height: u32,
+    pub view: VisionView,
+    /// World units spanned by the frame's width.
+    pub view_extent: f32,
+    /// Fixed ticks between sampled frames. GPU readback trails the render by
+    /// a frame or two, so sampling every tick mostly repeats frames.
+    pub capture_every_ticks: u32,
+    /// Writes each sampled frame as a PNG under the transition recorder's
+    /// directory while recording is enabled.
+    pub record_frames: bool,
+}
+
+impl Default for VisionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            width: 84,
+            height: 84,
+            view: VisionView::TopDown,
+            view_extent: 600.0,
+            capture_every_ticks: 4,
+            record_frames: false,
+        }
+    }
+}
+
+/// Marks the offscreen vision camera.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct VisionCamera;
+
+/// Latest CPU copy of the vision texture, as delivered by GPU readback.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct VisionReadback {
+    pub rgba: Vec<u8>,
+}
+
+/// Image observation sampled on the fixed tick.
+///
+/// `rgba` holds `width * height` tightly packed RGBA8 pixels, top row first.
+/// It is empty until the first readback arrives.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct VisionFrame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+    /// Episode and tick the frame was sampled on.
+    pub episode: u32,
+    pub tick: u32,
+}
+
+impl VisionFrame {
+    /// Luminance of each pixel in `[0, 1]`, row-major: the frame as a
+    /// single-channel observation.
+    pub fn grayscale(&self) -> Vec<f32> {
+        rgba_to_grayscale(&self.rgba)
+    }
+
+    /// Writes the frame as a PNG.
+    pub fn write_png(&self, path: &Path) -> io::Result<()> {
+        let image = Image::new(
+            Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            self.rgba.clone(),
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        let dynamic = image.try_into_dynamic().map_err(io::Error::other)?;
+        dynamic.save(path).map_err(io::Error::other)
+    }
+}
+
+/// Rec. 601 luminance of tightly packed RGBA8 pixels, scaled to `[0, 1]`.
+pub fn rgba_to_grayscale(rgba: &[u8]) -> Vec<f32> {
+    rgba.chunks_exact(4)
+        .map(|pixel| {
+            (0.299 * f32::from(pixel[0])
+                + 0.587 * f32::from(pixel[1])
+                + 0.114 * f32::from(pixel[2]))
+                / 255.0
+        })
+        .collect()
+}
+
+/// Drops the per-row padding GPU texture copies add (rows are aligned to 256
+/// bytes), returning `width * height` packed RGBA8 pixels. `None` when
+/// `data` is too short for the frame.
+pub fn strip_row_padding(data: &[u8], width: u32, height: u32) -> Option<Vec<u8>> {
+    let row_bytes = width as usize * 4;
+    let rows = height as usize;
+    if rows == 0 || data.len() < row_bytes * rows {
+        return None;
+    }
+    let padded_row = data.len() / rows;
+    let mut packed = Vec::with_capacity(row_bytes * rows);
+    for row in data.chunks_exact(padded_row).take(rows) {
+        packed.extend_from_slice(&row[..row_bytes]);
+    }
+    Some(packed)
+}
+
+/// Frame PNG path for `episode` and `tick`, sorting in recording order and
+/// matching the `episode`/`tick` columns of the transition CSVs.
+pub fn frame_path(dir: &Path, episode: u32, tick: u32) -> PathBuf {
+    dir.join(format!("episode_{episode:05}_tick_{tick:06}.png"))
+}
+
+/// Camera translation and rotation for `view` of a car at `position` with
+/// `heading` (radians, world frame).
+pub fn vision_camera_pose(
+    view: VisionView,
+    position: Vec2,
+    heading: f32,
+    extent: f32,
+) -> (Vec2, f32) {
+    match view {
+        VisionView::TopDown => (position, 0.0),
+        // Rotating the camera by `heading - 90°` turns the car's forward
+        // vector to screen-up; looking a quarter-frame ahead puts the car
+        // below centre.
+        VisionView::Chase => (
+            position + Vec2::from_angle(heading) * extent * 0.25,
+            heading - FRAC_PI_2,
+        ),
+    }
+}
+
+/// Plugin for camera observations. Does nothing unless
+/// [`VisionConfig::enabled`] is set.
+pub struct VisionPlugin;
+
+impl Plugin for VisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VisionConfig>()
+            .init_resource::<VisionReadback>()
+            .init_resource::<VisionFrame>()
+            // After `setup_game` (Startup) has spawned the main camera.
+            .add_systems(PostStartup, spawn_vision_camera_system)
+            .add_systems(Update, follow_vision_camera_system)
+            .add_systems(
+                FixedUpdate,
+                capture_vision_frame_system.in_set(SimSet::Measurement),
+            );
+    }
+}
+
+/// Creates the render target, the vision camera, and its readback, and moves
+/// debug gizmos onto [`DEBUG_RENDER_LAYER`].
+fn spawn_vision_camera_system(
+    mut commands: Commands,
+    config: Res<VisionConfig>,
+    mut images: Option<ResMut<Assets<Image>>>,
+    mut gizmo_store: Option<ResMut<GizmoConfigStore>>,
+    main_cameras: Query<Entity, (With<Camera2d>, Without<VisionCamera>)>,
+) {
+    if !config.enabled {
+        return;
+    }
+    let Some(images) = images.as_deref_mut() else {
+        warn!("Vision camera disabled: no render pipeline in this mode.");
+        return;
+    };
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: config.width.max(1),
+            height: config.height.max(1),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    let handle = images.add(image);
+
+    let aspect = config.height.max(1) as f32 / config.width.max(1) as f32;
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: -1,
+            ..default()
+        },
+        RenderTarget::Image(handle.clone().into()),
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width: config.view_extent,
+                height: config.view_extent * aspect,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+        RenderLayers::layer(0),
+        VisionCamera,
+    ));
+    commands.spawn(Readback::texture(handle)).observe(
+        |readback: On<ReadbackComplete>, mut latest: ResMut<VisionReadback>| {
+            latest.rgba.clone_from(&readback.data);
+        },
+    );
+
+    if let Some(store) = gizmo_store.as_deref_mut() {
+        let (gizmos, _) = store.config_mut::<DefaultGizmoConfigGroup>();
+        gizmos.render_layers = RenderLayers::layer(DEBUG_RENDER_LAYER);
+    }
+    for camera in &main_cameras {
+        commands
+            .entity(camera)
+            .insert(RenderLayers::from_layers(&[0, DEBUG_RENDER_LAYER]));
+    }
+    info!(
+        "Vision camera: {}x{} {:?}, sampled every {} ticks",
+        config.width, config.height, config.view, config.capture_every_ticks
+    );
+}
+
+/// Cars the vision camera can follow, with their poses.
+type VisionTargetQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static Transform), (With<Car>, Without<VisionCamera>)>;
+
+/// Keeps the vision camera on the focused car (the first car when unset).
+fn follow_vision_camera_system(
+    config: Res<VisionConfig>,
+    focus: Option<Res<FocusedCar>>,
+    car_query: VisionTargetQuery,
+    mut camera_query: Query<&mut Transform, With<VisionCamera>>,
+) {
+    let Ok(mut camera) = camera_query.single_mut() else {
+        return;
+    };
+    let focused = focus.and_then(|focus| focus.entity);
+    let car = car_query
+        .iter()
+        .find(|(entity, _)| Some(*entity) == focused)
+        .or_else(|| car_query.iter().next());
+    let Some((_, transform)) = car else {
+        return;
+    };
+
+    let forward = (transform.rotation * Vec3::X).truncate();
+    let (position, rotation) = vision_camera_pose(
+        config.view,
+        transform.translation.truncate(),
+        forward.y.atan2(forward.x),
+        config.view_extent,
+    );
+    camera.translation.x = position.x;
+    camera.translation.y = position.y;
+    camera.rotation = Quat::from_rotation_z(rotation);
+}
+
+/// Samples the latest readback into [`VisionFrame`] every
+/// `capture_every_ticks` ticks and, when recording, writes it as a PNG.
+fn capture_vision_frame_system(
+    config: Res<VisionConfig>,
+    recorder: Option<Res<TransitionRecorderConfig>>,
+    episode_state: Res<EpisodeState>,
+    readback: Res<VisionReadback>,
+    mut frame: ResMut<VisionFrame>,
+) {
+    if !config.enabled || readback.rgba.is_empty() {
+        return;
+    }
+    let tick = episode_state.ticks_in_episode;
+    if !tick.is_multiple_of(config.capture_every_ticks.max(1)) {
+        return;
+    }
+    let Some(rgba) = strip_row_padding(&readback.rgba, config.width, config.height) else {
+        return;
+    };
+
+    *frame = VisionFrame {
+        width: config.width,
+        height: config.height,
+        rgba,
+        episode: episode_state.current_episode,
+        tick,
+    };
+
+    let Some(recorder) = recorder.filter(|recorder| config.record_frames && recorder.enabled)
+    else {
+        return;
+    };
+    let dir = recorder.dir.join(VISION_FRAMES_DIR);
+    let path = frame_path(&dir, frame.episode, frame.tick);
+    if let Err(err) = std::fs::create_dir_all(&dir).and_then(|_| frame.write_png(&path)) {
+        warn!("Failed to write vision frame {}: {err}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        VisionFrame, VisionView, frame_path, rgba_to_grayscale, strip_row_padding,
+        vision_camera_pose,
+    };
+    use bevy::prelude::*;
+    use std::path::Path;
+
+    #[test]
+    fn padded_readback_rows_are_repacked_and_short_data_is_rejected() {
+        // 3x2 frame, rows padded from 12 to 16 bytes.
+        let mut padded = Vec::new();
+        for row in 0..2u8 {
+            padded.extend((0..12).map(|byte| row * 100 + byte));
+            padded.extend([255; 4]);
+        }
+        let packed = strip_row_padding(&padded, 3, 2).expect("long enough");
+
+        assert_eq!(packed.len(), 24);
+        assert_eq!(packed[..12], padded[..12]);
+        assert_eq!(packed[12..], padded[16..28]);
+        assert_eq!(strip_row_padding(&packed, 3, 2), Some(packed.clone()));
+        assert_eq!(strip_row_padding(&packed[..20], 3, 2), None);
+    }
+
+    #[test]
+    fn grayscale_maps_black_to_zero_and_white_to_one() {
+        let gray = rgba_to_grayscale(&[0, 0, 0, 255, 255, 255, 255, 255, 0, 255, 0, 255]);
+
+        assert_eq!(gray.len(), 3);
+        assert_eq!(gray[0], 0.0);
+        assert!((gray[1] - 1.0).abs() < 1e-5);
+        assert!((gray[2] - 0.587).abs() < 1e-5);
+    }
+
+    #[test]
+    fn chase_view_looks_ahead_and_turns_the_heading_up() {
+        let (top, top_rotation) =
+            vision_camera_pose(VisionView::TopDown, Vec2::new(10.0, 20.0), 1.0, 400.0);
+        assert_eq!((top, top_rotation), (Vec2::new(10.0, 20.0), 0.0));
+
+        let (chase, rotation) = vision_camera_pose(VisionView::Chase, Vec2::ZERO, 0.0, 400.0);
+        assert!(chase.distance(Vec2::new(100.0, 0.0)) < 1e-4);
+        // The car's forward (+x) ends up pointing screen-up in camera space.
+        let in_camera = Quat::from_rotation_z(rotation).inverse() * Vec3::X;
+        assert!(in_camera.truncate().distance(Vec2::Y) < 1e-5);
+    }
+
+    #[test]
+    fn frames_are_written_as_pngs_named_by_episode_and_tick() {
+        let dir = std::env::temp_dir().join(format!("neurodrive_vision_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let frame = VisionFrame {
+            width: 4,
+            height: 2,
+            rgba: (0..32).map(|i| (i * 8) as u8).collect(),
+            episode: 3,
+            tick: 40,
+        };
+        let path = frame_path(&dir, frame.episode, frame.tick);
+        frame.write_png(&path).expect("png written");
+
+        assert_eq!(
+            path.file_name().unwrap(),
+            Path::new("episode_00003_tick_000040.png")
+        );
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(
+            bytes[..8],
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']
+        );
+        assert!(frame_path(&dir, 3, 40) < frame_path(&dir, 3, 44));
+        assert!(frame_path(&dir, 3, 999_999) < frame_path(&dir, 4, 0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
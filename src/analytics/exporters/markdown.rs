@@ -80,6 +80,11 @@ pub fn export_to_markdown(tracker: &EpisodeTracker, filepath: &str) {
         .count() as f32
         / tracker.episodes.len() as f32;
     let total_crashes: u32 = tracker.episodes.iter().map(|record| record.crashes).sum();
+    let total_reward_anomalies: u32 = tracker
+        .episodes
+        .iter()
+        .map(|record| record.reward_anomalies)
+        .sum();
 
     let mut md = String::new();
     md.push_str("# NeuroDrive Analytics Report\n\n");
@@ -87,11 +92,15 @@ pub fn export_to_markdown(tracker: &EpisodeTracker, filepath: &str) {
 
     md.push_str("## Executive Summary\n\n");
     md.push_str(&format!(
-        "- Episodes: **{}**\n- Max progress: **{:.2}%**\n- Lap completion rate: **{:.2}%**\n- Total crashes: **{}**\n\n",
+        "- Episodes: **{}**\n- Max progress: **{:.2}%**\n- Lap completion rate: **{:.2}%**\n- Total crashes: **{}**\n- Reward anomalies (capped ticks): **{}**\n- Throughput: **{} ticks** in **{:.1}s** unpaused wall time (**{:.0} ticks/s**)\n\n",
         tracker.episodes.len(),
         max_progress_ever * 100.0,
         lap_completion_rate * 100.0,
-        total_crashes
+        total_crashes,
+        total_reward_anomalies,
+        tracker.run_stats.ticks,
+        tracker.run_stats.active_wall_s,
+        tracker.run_stats.average_tps
     ));
     append_insights(&mut md, &insights.overview);
 
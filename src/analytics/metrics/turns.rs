@@ -414,6 +414,7 @@ mod tests {
             reward: 0.0,
             progress_reward: 0.0,
             time_penalty: 0.0,
+            gate_reward: 0.0,
             terminal_reward: 0.0,
             done: false,
             done_reason: None,
@@ -422,6 +423,7 @@ mod tests {
             lookahead_heading_deltas: vec![0.0; 4],
             lookahead_curvatures: vec![curvature, 0.0, 0.0, 0.0],
             value_prediction: None,
+            controller_terms: Default::default(),
         }
     }
 
@@ -1,6 +1,10 @@
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::sim::stats::RunStatsRecord;
+
 pub const NUM_PROGRESS_SECTORS: usize = 20;
 
 /// Exported analytics snapshot for a completed episode.
@@ -11,12 +15,22 @@ pub struct EpisodeRecord {
     pub reward: f32,
     pub pre_terminal_return: f32,
     pub progress_reward_sum: f32,
+    pub gate_reward_sum: f32,
+    pub gates_passed: u32,
     pub time_penalty_sum: f32,
     pub terminal_reward_sum: f32,
     pub crash_penalty_sum: f32,
     pub lap_bonus_sum: f32,
     pub ticks: u32,
     pub crashes: u32,
+    pub invalid_lap_wraps: u32,
+    pub reward_anomalies: u32,
+    /// Seed for this episode's randomisation; replay with `--replay-episode`.
+    pub episode_seed: u64,
+    pub config_hash: String,
+    pub track_checksum: String,
+    /// Episode totals per reward term, keyed by term name.
+    pub reward_terms: BTreeMap<String, f32>,
     pub end_reason: String,
     pub lap_completed: bool,
     pub crash_position: Option<[f32; 2]>,
@@ -24,6 +38,10 @@ pub struct EpisodeRecord {
     pub steering_std: f32,
     pub throttle_mean: f32,
     pub throttle_std: f32,
+    /// Mean `|Δsteering|` per tick of the applied action.
+    pub steering_effort: f32,
+    /// Mean applied throttle per tick.
+    pub throttle_usage: f32,
     pub turn_in_latency_fraction: Option<f32>,
     pub turn_in_latency_ticks: Option<u32>,
     pub throttle_release_latency_fraction: Option<f32>,
@@ -63,6 +81,7 @@ pub struct TickTraceRecord {
     pub reward: f32,
     pub progress_reward: f32,
     pub time_penalty: f32,
+    pub gate_reward: f32,
     pub terminal_reward: f32,
     pub done: bool,
     pub done_reason: Option<String>,
@@ -71,6 +90,10 @@ pub struct TickTraceRecord {
     pub lookahead_heading_deltas: Vec<f32>,
     pub lookahead_curvatures: Vec<f32>,
     pub value_prediction: Option<f32>,
+    /// Internal terms of the registered controller that drove this tick,
+    /// e.g. the PID's P/I/D contributions.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub controller_terms: BTreeMap<String, f32>,
 }
 
 /// Episode-level trajectory trace with derived control mismatch metrics.
@@ -143,6 +166,11 @@ pub struct EpisodeTracker {
     pub episodes: Vec<EpisodeRecord>,
     pub a2c_updates: Vec<A2cUpdateRecord>,
     pub episode_traces: Vec<EpisodeTrace>,
+    #[serde(default)]
+    pub run_stats: RunStatsRecord,
+    /// Path of the run's observation manifest, when one was written.
+    #[serde(default)]
+    pub observation_manifest: Option<String>,
     #[serde(skip)]
     pub last_recorded_update: u64,
 }
@@ -1,8 +1,14 @@
+use std::path::Path;
+
 use bevy::app::AppExit;
 use bevy::ecs::message::MessageReader;
 use bevy::prelude::*;
 
-use crate::agent::observation::build_observation_vector_system;
+use crate::agent::action::{ActionMode, ActionSmoothing};
+use crate::agent::observation::{
+    ObservationConfig, apply_observation_delay_system, build_observation_vector_system,
+};
+use crate::agent::spaces::ObservationManifest;
 use crate::analytics::exporters::json::export_to_json;
 use crate::analytics::exporters::markdown::export_to_markdown;
 use crate::analytics::models::EpisodeTracker;
@@ -15,9 +21,16 @@ use crate::analytics::trackers::trace::{
     EpisodeTraceAccumulator, capture_episode_tick_trace_system,
     snapshot_completed_episode_trace_system,
 };
+use crate::analytics::trackers::transitions::{
+    TransitionRecorderConfig, flush_transition_recorder_system, open_transition_recorder_system,
+    record_transition_system,
+};
 use crate::brain::a2c::a2c_collect_reward_system;
 use crate::game::episode::episode_loop_system;
+use crate::game::seeding::record_run_fingerprint_system;
+use crate::sim::rng::RunSeeds;
 use crate::sim::sets::SimSet;
+use crate::sim::stats::RunStats;
 
 pub struct AnalyticsPlugin;
 
@@ -26,6 +39,12 @@ impl Plugin for AnalyticsPlugin {
         app.init_resource::<EpisodeTracker>()
             .init_resource::<EpisodeActionAccumulator>()
             .init_resource::<EpisodeTraceAccumulator>()
+            .init_resource::<TransitionRecorderConfig>()
+            .add_systems(Startup, open_transition_recorder_system)
+            .add_systems(
+                PostStartup,
+                write_observation_manifest_system.after(record_run_fingerprint_system),
+            )
             .add_systems(
                 FixedUpdate,
                 capture_episode_action_stats_system.in_set(SimSet::Physics),
@@ -50,12 +69,65 @@ impl Plugin for AnalyticsPlugin {
                     .after(episode_loop_system)
                     .in_set(SimSet::Measurement),
             )
+            .add_systems(
+                FixedUpdate,
+                record_transition_system
+                    .after(apply_observation_delay_system)
+                    .after(episode_loop_system)
+                    .in_set(SimSet::Measurement),
+            )
             .add_systems(Update, episode_tracker_system)
-            .add_systems(Last, on_exit_system);
+            .add_systems(Last, (on_exit_system, flush_transition_recorder_system));
     }
 }
 
-fn on_exit_system(mut exit_events: MessageReader<AppExit>, tracker: Res<EpisodeTracker>) {
+/// Writes the observation manifest into `reports/`, and next to the
+/// transition CSVs when recording, once the config hash is known.
+fn write_observation_manifest_system(
+    observation_config: Option<Res<ObservationConfig>>,
+    action_mode: Option<Res<ActionMode>>,
+    smoothing: Option<Res<ActionSmoothing>>,
+    run_seeds: Option<Res<RunSeeds>>,
+    recorder_config: Res<TransitionRecorderConfig>,
+    mut tracker: ResMut<EpisodeTracker>,
+) {
+    let Some(observation_config) = observation_config else {
+        return;
+    };
+    let mut manifest = ObservationManifest::new(
+        &observation_config,
+        action_mode.map_or(ActionMode::Continuous, |mode| *mode),
+        run_seeds.map_or(0, |seeds| seeds.config_hash),
+    );
+    if let Some(smoothing) = smoothing {
+        manifest.action = manifest.action.with_rate_limits(smoothing.rate_limits);
+    }
+
+    let mut dirs = vec![Path::new("reports")];
+    if recorder_config.enabled {
+        dirs.push(recorder_config.dir.as_path());
+    }
+    for dir in dirs {
+        match manifest.write_to(dir) {
+            Ok(path) => {
+                info!("Wrote observation manifest to {}.", path.display());
+                tracker
+                    .observation_manifest
+                    .get_or_insert_with(|| path.display().to_string());
+            }
+            Err(err) => error!(
+                "Cannot write observation manifest to {}: {err}",
+                dir.display()
+            ),
+        }
+    }
+}
+
+fn on_exit_system(
+    mut exit_events: MessageReader<AppExit>,
+    mut tracker: ResMut<EpisodeTracker>,
+    run_stats: Option<Res<RunStats>>,
+) {
     for exit_event in exit_events.read() {
         info!("Game exit event detected: {:?}", exit_event);
 
@@ -63,6 +135,9 @@ fn on_exit_system(mut exit_events: MessageReader<AppExit>, tracker: Res<EpisodeT
             info!("No analytics data to export.");
             return;
         }
+        if let Some(run_stats) = run_stats.as_deref() {
+            tracker.run_stats = run_stats.record();
+        }
 
         info!(
             "Starting analytics export for {} episodes and {} A2C updates...",
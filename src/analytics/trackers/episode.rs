@@ -5,10 +5,12 @@ use crate::analytics::trackers::action::EpisodeActionAccumulator;
 use crate::analytics::trackers::trace::EpisodeTraceAccumulator;
 use crate::brain::a2c::A2cTrainingStats;
 use crate::game::episode::{EpisodeEndReason, EpisodeState};
+use crate::sim::rng::RunSeeds;
 
 pub fn episode_tracker_system(
     episode_state: Res<EpisodeState>,
     a2c_stats: Option<Res<A2cTrainingStats>>,
+    run_seeds: Option<Res<RunSeeds>>,
     mut action_accumulator: ResMut<EpisodeActionAccumulator>,
     mut trace_accumulator: ResMut<EpisodeTraceAccumulator>,
     mut tracker: ResMut<EpisodeTracker>,
@@ -43,12 +45,34 @@ pub fn episode_tracker_system(
                 reward: episode_state.last_episode_return,
                 pre_terminal_return: episode_state.last_episode_pre_terminal_return,
                 progress_reward_sum: episode_state.last_episode_progress_reward_sum,
+                gate_reward_sum: episode_state.last_episode_gate_reward_sum,
+                gates_passed: episode_state.last_episode_gates_passed,
                 time_penalty_sum: episode_state.last_episode_time_penalty_sum,
                 terminal_reward_sum: episode_state.last_episode_terminal_reward_sum,
                 crash_penalty_sum: episode_state.last_episode_crash_penalty_sum,
                 lap_bonus_sum: episode_state.last_episode_lap_bonus_sum,
                 ticks: episode_state.last_episode_ticks,
                 crashes: episode_state.last_episode_crashes,
+                invalid_lap_wraps: episode_state.last_episode_invalid_lap_wraps,
+                reward_anomalies: episode_state.last_episode_reward_anomalies,
+                episode_seed: run_seeds
+                    .as_deref()
+                    .map(|seeds| seeds.seed_for_episode(finished_episode_id))
+                    .unwrap_or_default(),
+                config_hash: run_seeds
+                    .as_deref()
+                    .map(|seeds| format!("{:016x}", seeds.config_hash))
+                    .unwrap_or_default(),
+                track_checksum: run_seeds
+                    .as_deref()
+                    .map(|seeds| format!("{:016x}", seeds.track_checksum))
+                    .unwrap_or_default(),
+                reward_terms: episode_state
+                    .last_episode_breakdown
+                    .terms
+                    .iter()
+                    .map(|entry| (entry.name.clone(), entry.value))
+                    .collect(),
                 end_reason: format!("{:?}", reason),
                 lap_completed: reason == EpisodeEndReason::LapComplete,
                 crash_position: episode_state
@@ -58,6 +82,8 @@ pub fn episode_tracker_system(
                 steering_std: action_summary.steering_std,
                 throttle_mean: action_summary.throttle_mean,
                 throttle_std: action_summary.throttle_std,
+                steering_effort: episode_state.last_episode_steering_effort,
+                throttle_usage: episode_state.last_episode_throttle_usage,
                 turn_in_latency_fraction: trace_metrics.turn_in_latency_fraction,
                 turn_in_latency_ticks: trace_metrics.turn_in_latency_ticks,
                 throttle_release_latency_fraction: trace_metrics.throttle_release_latency_fraction,
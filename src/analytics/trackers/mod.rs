@@ -1,3 +1,4 @@
 pub mod action;
 pub mod episode;
 pub mod trace;
+pub mod transitions;
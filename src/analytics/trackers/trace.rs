@@ -3,6 +3,7 @@ use std::f32::consts::PI;
 use bevy::prelude::*;
 
 use crate::agent::action::ActionState;
+use crate::agent::controller::Controllers;
 use crate::agent::observation::{ObservationConfig, SensorReadings};
 use crate::analytics::metrics::turns::compute_trace_metrics;
 use crate::analytics::models::{EpisodeTrace, TickTraceRecord};
@@ -10,6 +11,7 @@ use crate::brain::a2c::A2cBrain;
 use crate::brain::types::AgentMode;
 use crate::game::car::Car;
 use crate::game::episode::{EpisodeEndReason, EpisodeState};
+use crate::maps::centerline::TrackCenterline;
 use crate::maps::track::Track;
 
 /// Running per-tick trace for the active episode.
@@ -78,6 +80,7 @@ pub fn capture_episode_tick_trace_system(
     sensor_query: Query<&SensorReadings, With<Car>>,
     track_query: Query<&Track>,
     a2c_brain: Option<Res<A2cBrain>>,
+    controllers: Option<Res<Controllers>>,
     mut accumulator: ResMut<EpisodeTraceAccumulator>,
 ) {
     let done = episode_state.current_tick_end_reason.is_some();
@@ -95,8 +98,12 @@ pub fn capture_episode_tick_trace_system(
         return;
     };
 
-    let (lookahead_heading_deltas, lookahead_curvatures) = if let Ok(track) = track_query.single() {
-        compute_lookahead_snapshot(track, &episode_state, &observation_config)
+    let centerline = track_query
+        .single()
+        .ok()
+        .and_then(|track| track.centerline.as_ref());
+    let (lookahead_heading_deltas, lookahead_curvatures) = if let Some(centerline) = centerline {
+        compute_lookahead_snapshot(centerline, &episode_state, &observation_config)
     } else {
         (
             vec![0.0; observation_config.lookahead_distances.len()],
@@ -104,7 +111,7 @@ pub fn capture_episode_tick_trace_system(
         )
     };
 
-    let value_prediction = if *mode == AgentMode::Ai {
+    let value_prediction = if *mode == AgentMode::Learned {
         a2c_brain.and_then(|brain| {
             let values_len = brain.buffer.values.len();
             let rewards_len = brain.buffer.rewards.len();
@@ -134,6 +141,7 @@ pub fn capture_episode_tick_trace_system(
         reward: episode_state.current_tick_reward,
         progress_reward: episode_state.current_tick_progress_reward,
         time_penalty: episode_state.current_tick_time_penalty,
+        gate_reward: episode_state.current_tick_gate_reward,
         terminal_reward: episode_state.current_tick_terminal_reward,
         done,
         done_reason: episode_state
@@ -144,6 +152,15 @@ pub fn capture_episode_tick_trace_system(
         lookahead_heading_deltas,
         lookahead_curvatures,
         value_prediction,
+        controller_terms: controllers
+            .map(|controllers| {
+                controllers
+                    .telemetry()
+                    .iter()
+                    .map(|&(name, value)| (name.to_string(), value))
+                    .collect()
+            })
+            .unwrap_or_default(),
     });
 }
 
@@ -173,7 +190,7 @@ pub fn snapshot_completed_episode_trace_system(
 }
 
 fn compute_lookahead_snapshot(
-    track: &Track,
+    centerline: &TrackCenterline,
     episode_state: &EpisodeState,
     observation_config: &ObservationConfig,
 ) -> (Vec<f32>, Vec<f32>) {
@@ -182,7 +199,7 @@ fn compute_lookahead_snapshot(
 
     for distance in observation_config.lookahead_distances.iter() {
         let lookahead_s = episode_state.current_tick_progress_s + *distance;
-        let lookahead_tangent = track.centerline.tangent_at_s(lookahead_s);
+        let lookahead_tangent = centerline.tangent_at_s(lookahead_s);
         let heading_delta =
             signed_angle_between(episode_state.current_tick_forward, lookahead_tangent);
         let turn_delta =
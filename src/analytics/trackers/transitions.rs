@@ -0,0 +1,661 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use bevy::app::AppExit;
+use bevy::ecs::message::MessageReader;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionState, CarAction};
+use crate::agent::observation::{ObservationConfig, ObservationVector};
+use crate::agent::spaces::OBSERVATION_MANIFEST_FILE;
+use crate::game::car::Car;
+use crate::game::episode::{EpisodeEndReason, EpisodeState};
+
+/// Name of the JSON sidecar describing the CSV column layout.
+pub const TRANSITION_LAYOUT_FILE: &str = "layout.json";
+
+/// Offline-RL transition logging. Off by default.
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransitionRecorderConfig {
+    pub enabled: bool,
+    /// Directory the CSV chunks and layout sidecar are written to.
+    pub dir: PathBuf,
+    /// Transitions per CSV file before rotating to the next one.
+    pub rotate_every: u32,
+    /// Hard cap on bytes across all files in `dir`, including those left by
+    /// earlier runs; recording stops at the cap.
+    pub max_total_bytes: u64,
+    /// Write only every `downsample`th tick of an episode. The first and
+    /// terminal tick of every episode are always written, and each row's
+    /// reward is the sum over the ticks since the previous row. `1` keeps
+    /// every tick.
+    pub downsample: u32,
+}
+
+impl Default for TransitionRecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: PathBuf::from("recordings"),
+            rotate_every: 50_000,
+            max_total_bytes: 512 * 1024 * 1024,
+            downsample: 1,
+        }
+    }
+}
+
+/// Column layout written to [`TRANSITION_LAYOUT_FILE`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransitionLayout {
+    pub observation_dim: usize,
+    pub columns: Vec<String>,
+    /// Sibling file naming and bounding each `obs_*` column.
+    #[serde(default)]
+    pub manifest: String,
+}
+
+impl TransitionLayout {
+    pub fn new(observation_dim: usize) -> Self {
+        let mut columns = vec!["episode".to_string(), "tick".to_string()];
+        columns.extend((0..observation_dim).map(|index| format!("obs_{index}")));
+        columns.extend(
+            ["steering", "throttle", "reward", "done", "truncated"]
+                .into_iter()
+                .map(String::from),
+        );
+        Self {
+            observation_dim,
+            columns,
+            manifest: OBSERVATION_MANIFEST_FILE.to_string(),
+        }
+    }
+}
+
+/// One fixed tick: the observation the action was chosen from, the applied
+/// action, and what the tick produced. In a downsampled log `reward` is summed
+/// over the skipped ticks before the row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transition<'a> {
+    pub episode: u32,
+    pub tick: u32,
+    pub observation: &'a [f32],
+    pub action: CarAction,
+    pub reward: f32,
+    /// The episode ended on its own (crash or completed lap).
+    pub done: bool,
+    /// The episode was cut off by the timeout.
+    pub truncated: bool,
+}
+
+/// Buffered CSV writer with file rotation and a disk-usage cap.
+///
+/// Files are named `transitions_00001.csv`, `transitions_00002.csv`, ...,
+/// each starting with the header row from [`TransitionLayout`]. A run
+/// recording into a directory that already holds transitions continues the
+/// numbering after the last file, so earlier runs are never overwritten.
+#[derive(Resource)]
+pub struct TransitionRecorder {
+    dir: PathBuf,
+    layout: TransitionLayout,
+    rotate_every: u32,
+    max_total_bytes: u64,
+    downsample: u32,
+    /// Reward of ticks skipped by downsampling since the last written row.
+    pending_reward: f32,
+    writer: Option<BufWriter<File>>,
+    file_index: u32,
+    rows_in_file: u32,
+    bytes_written: u64,
+    capped: bool,
+}
+
+impl TransitionRecorder {
+    /// Creates `config.dir` and writes the layout sidecar. Existing
+    /// transition files are kept and their bytes count toward the cap;
+    /// they must have been recorded with the same layout.
+    pub fn new(config: &TransitionRecorderConfig, observation_dim: usize) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        let layout = TransitionLayout::new(observation_dim);
+        let existing = transition_files(&config.dir)?;
+        if !existing.is_empty() {
+            let recorded = fs::read_to_string(config.dir.join(TRANSITION_LAYOUT_FILE))
+                .ok()
+                .and_then(|json| serde_json::from_str::<TransitionLayout>(&json).ok());
+            if recorded.as_ref() != Some(&layout) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{} holds transitions with a different layout",
+                        config.dir.display()
+                    ),
+                ));
+            }
+        }
+        let file_index = existing
+            .iter()
+            .filter_map(|path| transition_file_index(path))
+            .max()
+            .unwrap_or(0);
+        let mut bytes_written = 0;
+        for path in &existing {
+            bytes_written += fs::metadata(path)?.len();
+        }
+        let json = serde_json::to_string_pretty(&layout).map_err(io::Error::other)?;
+        fs::write(config.dir.join(TRANSITION_LAYOUT_FILE), json)?;
+
+        Ok(Self {
+            dir: config.dir.clone(),
+            layout,
+            rotate_every: config.rotate_every.max(1),
+            max_total_bytes: config.max_total_bytes,
+            downsample: config.downsample.max(1),
+            pending_reward: 0.0,
+            writer: None,
+            file_index,
+            rows_in_file: 0,
+            bytes_written,
+            capped: false,
+        })
+    }
+
+    /// Bytes in this directory's CSV files, headers and earlier runs
+    /// included.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Whether the disk cap has been reached and recording stopped.
+    pub fn is_capped(&self) -> bool {
+        self.capped
+    }
+
+    /// Appends one row, rotating files as needed. Ticks skipped by
+    /// downsampling only add their reward to the next row. Rows past the disk
+    /// cap are dropped.
+    pub fn record(&mut self, transition: &Transition) -> io::Result<()> {
+        if self.capped {
+            return Ok(());
+        }
+        let boundary = transition.tick <= 1 || transition.done || transition.truncated;
+        if !boundary && !transition.tick.is_multiple_of(self.downsample) {
+            self.pending_reward += transition.reward;
+            return Ok(());
+        }
+        let row = self.format_row(&Transition {
+            reward: self.pending_reward + transition.reward,
+            ..transition.clone()
+        });
+        self.pending_reward = 0.0;
+
+        let needs_new_file = self.writer.is_none() || self.rows_in_file >= self.rotate_every;
+        let header = needs_new_file.then(|| format!("{}\n", self.layout.columns.join(",")));
+        let cost = (row.len() + header.as_ref().map_or(0, String::len)) as u64;
+        if self.bytes_written + cost > self.max_total_bytes {
+            self.capped = true;
+            warn!(
+                "Transition recording stopped at the {} byte cap.",
+                self.max_total_bytes
+            );
+            return self.flush();
+        }
+
+        if let Some(header) = header {
+            self.flush()?;
+            self.file_index += 1;
+            self.rows_in_file = 0;
+            let path = self.file_path(self.file_index);
+            let mut writer = BufWriter::new(File::create(path)?);
+            writer.write_all(header.as_bytes())?;
+            self.writer = Some(writer);
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            writer.write_all(row.as_bytes())?;
+        }
+        self.rows_in_file += 1;
+        self.bytes_written += cost;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn file_path(&self, index: u32) -> PathBuf {
+        self.dir.join(format!("transitions_{index:05}.csv"))
+    }
+
+    fn format_row(&self, transition: &Transition) -> String {
+        let mut fields = Vec::with_capacity(self.layout.columns.len());
+        fields.push(transition.episode.to_string());
+        fields.push(transition.tick.to_string());
+        fields.extend(
+            (0..self.layout.observation_dim)
+                .map(|index| transition.observation.get(index).copied().unwrap_or(0.0))
+                .map(|value| value.to_string()),
+        );
+        fields.push(transition.action.steering.to_string());
+        fields.push(transition.action.throttle.to_string());
+        fields.push(transition.reward.to_string());
+        fields.push(u8::from(transition.done).to_string());
+        fields.push(u8::from(transition.truncated).to_string());
+        format!("{}\n", fields.join(","))
+    }
+}
+
+/// Every `transitions_*.csv` file in `dir`, in recording order.
+pub fn transition_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("transitions_") && name.ends_with(".csv"))
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// The number in a `transitions_NNNNN.csv` file name.
+fn transition_file_index(path: &Path) -> Option<u32> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("transitions_")?
+        .strip_suffix(".csv")?
+        .parse()
+        .ok()
+}
+
+/// Opens the recorder at startup when enabled.
+pub fn open_transition_recorder_system(
+    mut commands: Commands,
+    config: Res<TransitionRecorderConfig>,
+    observation_config: Res<ObservationConfig>,
+) {
+    if !config.enabled {
+        return;
+    }
+    match TransitionRecorder::new(&config, observation_config.observation_dim()) {
+        Ok(recorder) => {
+            info!("Recording transitions to {}.", config.dir.display());
+            commands.insert_resource(recorder);
+        }
+        Err(err) => error!(
+            "Cannot record transitions to {}: {err}",
+            config.dir.display()
+        ),
+    }
+}
+
+/// Logs the transition completed by this fixed tick.
+///
+/// The row pairs the observation the controller acted on (built on the
+/// previous tick) with this tick's applied action and reward. Terminal ticks
+/// are attributed to the episode that just ended.
+pub fn record_transition_system(
+    recorder: Option<ResMut<TransitionRecorder>>,
+    episode_state: Res<EpisodeState>,
+    action_state: Res<ActionState>,
+    observation_query: Query<&ObservationVector, With<Car>>,
+    mut previous_observation: Local<Option<Vec<f32>>>,
+) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+    let Ok(observation) = observation_query.single() else {
+        return;
+    };
+
+    if let Some(acted_on) = previous_observation.as_deref() {
+        let end_reason = episode_state.current_tick_end_reason;
+        let (episode, tick) = match end_reason {
+            Some(_) => (
+                episode_state.current_episode.saturating_sub(1),
+                episode_state.last_episode_ticks,
+            ),
+            None => (
+                episode_state.current_episode,
+                episode_state.ticks_in_episode,
+            ),
+        };
+        let transition = Transition {
+            episode,
+            tick,
+            observation: acted_on,
+            action: action_state.applied,
+            reward: episode_state.current_tick_reward,
+            done: matches!(
+                end_reason,
+                Some(EpisodeEndReason::Crash | EpisodeEndReason::LapComplete)
+            ),
+            truncated: end_reason == Some(EpisodeEndReason::Timeout),
+        };
+        if let Err(err) = recorder.record(&transition) {
+            error!("Failed to record transition: {err}");
+        }
+    }
+    *previous_observation = Some(observation.values.clone());
+}
+
+/// Flushes buffered transitions when the app exits.
+pub fn flush_transition_recorder_system(
+    mut exit_events: MessageReader<AppExit>,
+    recorder: Option<ResMut<TransitionRecorder>>,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+    if let Some(Err(err)) = recorder.map(|mut recorder| recorder.flush()) {
+        error!("Failed to flush transitions: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TRANSITION_LAYOUT_FILE, Transition, TransitionLayout, TransitionRecorder,
+        TransitionRecorderConfig, transition_files,
+    };
+    use crate::agent::action::CarAction;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("neurodrive_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn recorded_transitions_reload_with_layout_and_episode_boundaries() {
+        let dir = scratch_dir("transitions");
+        let config = TransitionRecorderConfig {
+            enabled: true,
+            dir: dir.clone(),
+            rotate_every: 300,
+            max_total_bytes: u64::MAX,
+            downsample: 1,
+        };
+        let observation_dim = 6;
+        let mut recorder = TransitionRecorder::new(&config, observation_dim).expect("opens");
+
+        // Episodes of 120 ticks; every third one times out, the rest crash.
+        let mut expected_ends = Vec::new();
+        let (mut episode, mut tick) = (1u32, 0u32);
+        for step in 0..1000u32 {
+            tick += 1;
+            let ends = tick == 120;
+            let truncated = ends && episode % 3 == 0;
+            let observation: Vec<f32> = (0..observation_dim)
+                .map(|index| (step + index as u32) as f32 * 0.001)
+                .collect();
+            recorder
+                .record(&Transition {
+                    episode,
+                    tick,
+                    observation: &observation,
+                    action: CarAction {
+                        steering: -0.5,
+                        throttle: 0.75,
+                    },
+                    reward: 0.01,
+                    done: ends && !truncated,
+                    truncated,
+                })
+                .expect("records");
+            if ends {
+                expected_ends.push((step, truncated));
+                episode += 1;
+                tick = 0;
+            }
+        }
+        recorder.flush().expect("flushes");
+
+        let layout: TransitionLayout = serde_json::from_str(
+            &fs::read_to_string(dir.join(TRANSITION_LAYOUT_FILE)).expect("sidecar"),
+        )
+        .expect("layout parses");
+        assert_eq!(layout, TransitionLayout::new(observation_dim));
+        let column = |name: &str| layout.columns.iter().position(|c| c == name).unwrap();
+
+        let files = transition_files(&dir).expect("lists files");
+        assert_eq!(files.len(), 4);
+        let mut rows = Vec::new();
+        for file in &files {
+            let text = fs::read_to_string(file).expect("reads chunk");
+            let mut lines = text.lines();
+            assert_eq!(lines.next(), Some(layout.columns.join(",").as_str()));
+            rows.extend(lines.map(|line| {
+                line.split(',')
+                    .map(|field| field.parse::<f32>().expect("numeric field"))
+                    .collect::<Vec<_>>()
+            }));
+        }
+        assert_eq!(rows.len(), 1000);
+        assert!(rows.iter().all(|row| row.len() == 2 + observation_dim + 5));
+
+        let ends: Vec<(u32, bool)> = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row[column("done")] == 1.0 || row[column("truncated")] == 1.0)
+            .map(|(step, row)| (step as u32, row[column("truncated")] == 1.0))
+            .collect();
+        assert_eq!(ends, expected_ends);
+        for (step, _) in &ends {
+            let (end, next) = (&rows[*step as usize], &rows[*step as usize + 1]);
+            assert_eq!(next[column("episode")], end[column("episode")] + 1.0);
+            assert_eq!(next[column("tick")], 1.0);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn downsampled_log_keeps_boundaries_and_reward_totals() {
+        let dir = scratch_dir("transitions_downsample");
+        let config = TransitionRecorderConfig {
+            enabled: true,
+            dir: dir.clone(),
+            rotate_every: 50_000,
+            max_total_bytes: u64::MAX,
+            downsample: 5,
+        };
+        let mut recorder = TransitionRecorder::new(&config, 2).expect("opens");
+
+        // Episodes of 117 ticks, so the terminal tick is off the stride.
+        let (mut episode, mut tick) = (1u32, 0u32);
+        let mut total_reward = 0.0f64;
+        let mut episodes_started = 0;
+        for step in 0..1001u32 {
+            tick += 1;
+            if tick == 1 {
+                episodes_started += 1;
+            }
+            let ends = tick == 117;
+            let reward = ((step % 7) as f32 - 3.0) * 0.01;
+            total_reward += f64::from(reward);
+            recorder
+                .record(&Transition {
+                    episode,
+                    tick,
+                    observation: &[0.5, -0.5],
+                    action: CarAction::default(),
+                    reward,
+                    done: ends,
+                    truncated: false,
+                })
+                .expect("records");
+            if ends {
+                episode += 1;
+                tick = 0;
+            }
+        }
+        recorder.flush().expect("flushes");
+        // The last step is tick 65 of episode 9, on the stride, so nothing is
+        // left pending.
+        assert_eq!(tick % 5, 0);
+
+        let layout = TransitionLayout::new(2);
+        let column = |name: &str| layout.columns.iter().position(|c| c == name).unwrap();
+        let rows: Vec<Vec<f32>> = transition_files(&dir)
+            .expect("lists files")
+            .iter()
+            .flat_map(|file| {
+                let text = fs::read_to_string(file).expect("reads chunk");
+                text.lines()
+                    .skip(1)
+                    .map(|line| line.split(',').map(|f| f.parse().unwrap()).collect())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let completed = episode - 1;
+        let expected = completed * (117 / 5) + 65 / 5 + episodes_started + completed;
+        assert_eq!(rows.len() as u32, expected);
+        assert!(rows.len() < 1001 / 5 + 2 * episodes_started as usize);
+        let terminal_rows = rows.iter().filter(|row| row[column("done")] == 1.0).count() as u32;
+        assert_eq!(terminal_rows, completed);
+        assert!(rows.iter().all(|row| {
+            let tick = row[column("tick")] as u32;
+            tick == 1 || tick.is_multiple_of(5) || row[column("done")] == 1.0
+        }));
+
+        let logged: f64 = rows
+            .iter()
+            .map(|row| f64::from(row[column("reward")]))
+            .sum();
+        assert!(
+            (logged - total_reward).abs() < 1e-3,
+            "{logged} vs {total_reward}"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_later_run_continues_the_numbering_and_shares_the_cap() {
+        let dir = scratch_dir("transitions_resume");
+        let config = TransitionRecorderConfig {
+            enabled: true,
+            dir: dir.clone(),
+            rotate_every: 10,
+            max_total_bytes: u64::MAX,
+            downsample: 1,
+        };
+        let observation = [0.25; 3];
+        let run = |episode: u32, config: &TransitionRecorderConfig| {
+            let mut recorder = TransitionRecorder::new(config, 3).expect("opens");
+            for tick in 1..=15 {
+                recorder
+                    .record(&Transition {
+                        episode,
+                        tick,
+                        observation: &observation,
+                        action: CarAction::default(),
+                        reward: 0.0,
+                        done: false,
+                        truncated: false,
+                    })
+                    .expect("records");
+            }
+            recorder.flush().expect("flushes");
+            recorder
+        };
+        let on_disk = || -> u64 {
+            transition_files(&dir)
+                .expect("lists files")
+                .iter()
+                .map(|file| fs::metadata(file).expect("metadata").len())
+                .sum()
+        };
+
+        run(1, &config);
+        let first_run: Vec<String> = transition_files(&dir)
+            .expect("lists files")
+            .iter()
+            .map(|file| fs::read_to_string(file).expect("reads chunk"))
+            .collect();
+        assert_eq!(first_run.len(), 2);
+
+        let second = run(2, &config);
+        let files = transition_files(&dir).expect("lists files");
+        assert_eq!(files.len(), 4);
+        assert!(files[3].ends_with("transitions_00004.csv"));
+        for (file, before) in files.iter().zip(&first_run) {
+            assert_eq!(&fs::read_to_string(file).expect("reads chunk"), before);
+        }
+        assert_eq!(second.bytes_written(), on_disk());
+
+        let mut capped = TransitionRecorder::new(
+            &TransitionRecorderConfig {
+                max_total_bytes: on_disk() + 10,
+                ..config.clone()
+            },
+            3,
+        )
+        .expect("opens");
+        assert_eq!(capped.bytes_written(), on_disk());
+        capped
+            .record(&Transition {
+                episode: 3,
+                tick: 1,
+                observation: &observation,
+                action: CarAction::default(),
+                reward: 0.0,
+                done: false,
+                truncated: false,
+            })
+            .expect("records");
+        assert!(capped.is_capped());
+        assert_eq!(transition_files(&dir).expect("lists files").len(), 4);
+
+        assert!(TransitionRecorder::new(&config, 4).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn recording_stops_at_the_disk_cap() {
+        let dir = scratch_dir("transitions_cap");
+        let config = TransitionRecorderConfig {
+            enabled: true,
+            dir: dir.clone(),
+            rotate_every: 10,
+            max_total_bytes: 2_000,
+            downsample: 1,
+        };
+        let mut recorder = TransitionRecorder::new(&config, 4).expect("opens");
+        let observation = [0.5; 4];
+        for tick in 1..=500 {
+            recorder
+                .record(&Transition {
+                    episode: 1,
+                    tick,
+                    observation: &observation,
+                    action: CarAction::default(),
+                    reward: 0.0,
+                    done: false,
+                    truncated: false,
+                })
+                .expect("records");
+        }
+        recorder.flush().expect("flushes");
+
+        assert!(recorder.is_capped());
+        let on_disk: u64 = transition_files(&dir)
+            .expect("lists files")
+            .iter()
+            .map(|file| fs::metadata(file).expect("metadata").len())
+            .sum();
+        assert_eq!(on_disk, recorder.bytes_written());
+        assert!(on_disk <= 2_000);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,185 @@
+//! A2C weight checkpoints.
+//!
+//! [`A2cCheckpoint`] stores the actor and critic layers and the learned
+//! log standard deviations as JSON. Optimizer moments are not saved, so a
+//! restored brain acts exactly like the saved one but resumes training with
+//! fresh Adam state.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::brain::a2c::model::ActorCritic;
+use crate::brain::common::mlp::Linear;
+
+#[derive(Debug)]
+pub enum A2cCheckpointError {
+    Io(std::io::Error),
+    /// The file is not a valid checkpoint.
+    Format(String),
+    /// The network reads a different number of features than configured.
+    ObservationDim {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for A2cCheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::Format(err) => write!(f, "invalid A2C checkpoint: {err}"),
+            Self::ObservationDim { expected, found } => write!(
+                f,
+                "checkpoint reads {found} observation features but the config produces {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for A2cCheckpointError {}
+
+/// Weights and biases of one dense layer, `[out][in]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayerWeights {
+    pub weights: Vec<Vec<f32>>,
+    pub biases: Vec<f32>,
+}
+
+impl LayerWeights {
+    fn from_linear(layer: &Linear) -> Self {
+        Self {
+            weights: layer.weights.clone(),
+            biases: layer.biases.clone(),
+        }
+    }
+
+    fn copy_into(&self, layer: &mut Linear, name: &str) -> Result<(), A2cCheckpointError> {
+        let shape = |weights: &[Vec<f32>]| (weights.len(), weights.first().map_or(0, Vec::len));
+        let (expected, found) = (shape(&layer.weights), shape(&self.weights));
+        if expected != found
+            || self.biases.len() != layer.biases.len()
+            || self.weights.iter().any(|row| row.len() != expected.1)
+        {
+            return Err(A2cCheckpointError::Format(format!(
+                "layer {name} is {found:?} but the network needs {expected:?}"
+            )));
+        }
+        layer.weights.clone_from(&self.weights);
+        layer.biases.clone_from(&self.biases);
+        Ok(())
+    }
+}
+
+/// Serialized [`ActorCritic`] parameters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct A2cCheckpoint {
+    /// Actor hidden, hidden, and mean layers.
+    pub actor: [LayerWeights; 3],
+    pub log_std: Vec<f32>,
+    /// Critic hidden, hidden, and value layers.
+    pub critic: [LayerWeights; 3],
+}
+
+impl A2cCheckpoint {
+    pub fn from_model(model: &ActorCritic) -> Self {
+        Self {
+            actor: [&model.a_fc1, &model.a_fc2, &model.a_mean].map(LayerWeights::from_linear),
+            log_std: model.a_log_std.clone(),
+            critic: [&model.c_fc1, &model.c_fc2, &model.c_value].map(LayerWeights::from_linear),
+        }
+    }
+
+    /// Observation features the actor's first layer reads.
+    pub fn observation_dim(&self) -> usize {
+        self.actor[0].weights.first().map_or(0, Vec::len)
+    }
+
+    /// Rebuilds the network, checking every layer's shape.
+    pub fn to_model(&self) -> Result<ActorCritic, A2cCheckpointError> {
+        let hidden_dim = self.actor[0].biases.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut model = ActorCritic::new(
+            self.observation_dim(),
+            hidden_dim,
+            self.log_std.len(),
+            &mut rng,
+        );
+        let [a_fc1, a_fc2, a_mean] = &self.actor;
+        a_fc1.copy_into(&mut model.a_fc1, "actor.0")?;
+        a_fc2.copy_into(&mut model.a_fc2, "actor.1")?;
+        a_mean.copy_into(&mut model.a_mean, "actor.2")?;
+        let [c_fc1, c_fc2, c_value] = &self.critic;
+        c_fc1.copy_into(&mut model.c_fc1, "critic.0")?;
+        c_fc2.copy_into(&mut model.c_fc2, "critic.1")?;
+        c_value.copy_into(&mut model.c_value, "critic.2")?;
+        model.a_log_std.clone_from(&self.log_std);
+        Ok(model)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), A2cCheckpointError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(A2cCheckpointError::Io)?;
+        }
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|err| A2cCheckpointError::Format(err.to_string()))?;
+        fs::write(path, json).map_err(A2cCheckpointError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, A2cCheckpointError> {
+        let bytes = fs::read(path).map_err(A2cCheckpointError::Io)?;
+        serde_json::from_slice(&bytes).map_err(|err| A2cCheckpointError::Format(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{A2cCheckpoint, A2cCheckpointError};
+    use crate::agent::observation::ObservationVector;
+    use crate::brain::a2c::A2cBrain;
+    use crate::brain::types::Brain;
+    use crate::sim::rng::EpisodeRng;
+
+    #[test]
+    fn a_restored_brain_acts_like_the_saved_one() {
+        let mut saved = A2cBrain::seeded(3, 5);
+        saved.model.a_log_std = vec![-0.5, -1.0];
+        let path = std::env::temp_dir().join("neurodrive_a2c_checkpoint_test.json");
+        A2cCheckpoint::from_model(&saved.model)
+            .save(&path)
+            .expect("saves");
+
+        let mut restored = A2cBrain::from_checkpoint(&path, 5).expect("loads");
+        assert!(matches!(
+            A2cBrain::from_checkpoint(&path, 6),
+            Err(A2cCheckpointError::ObservationDim {
+                expected: 6,
+                found: 5
+            })
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let episode_rng = EpisodeRng::from_seed(1, 99);
+        saved.begin_episode(&episode_rng);
+        restored.begin_episode(&episode_rng);
+        let obs = ObservationVector {
+            values: vec![0.1, -0.4, 0.7, 0.0, 1.0],
+        };
+        for _ in 0..5 {
+            assert_eq!(saved.act(&obs), restored.act(&obs));
+        }
+    }
+
+    #[test]
+    fn mismatched_layer_shapes_are_rejected() {
+        let mut checkpoint = A2cCheckpoint::from_model(&A2cBrain::seeded(3, 5).model);
+        checkpoint.critic[1].biases.pop();
+        assert!(matches!(
+            checkpoint.to_model(),
+            Err(A2cCheckpointError::Format(_))
+        ));
+    }
+}
@@ -1,23 +1,31 @@
 pub mod buffer;
+pub mod checkpoint;
 pub mod model;
 pub mod update;
 
 use bevy::app::AppExit;
 use bevy::ecs::message::MessageReader;
 use bevy::prelude::*;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 
 use crate::agent::action::{
     ActionState, CarAction, action_smoothing_system, keyboard_action_input_system,
 };
-use crate::agent::observation::{OBSERVATION_DIM, ObservationVector};
+use crate::agent::exploration::exploration_noise_system;
+use crate::agent::observation::{ObservationConfig, ObservationVector};
 use crate::brain::types::{AgentMode, Brain};
 use crate::game::episode::EpisodeState;
+use crate::sim::rng::{EpisodeRng, SimRng};
 
 use self::buffer::RolloutBuffer;
+use self::checkpoint::{A2cCheckpoint, A2cCheckpointError};
 use self::model::ActorCritic;
 use self::update::a2c_update;
 
+/// Where the learned policy's weights are written on exit.
+pub const A2C_CHECKPOINT_PATH: &str = "reports/a2c_checkpoint.json";
+
 #[derive(Resource)]
 pub struct A2cBrain {
     pub model: ActorCritic,
@@ -27,19 +35,60 @@ pub struct A2cBrain {
     pub max_steps: usize,
     pub min_update_steps: usize,
     pub step_counter: usize,
+    /// Exploration noise stream, taken from [`EpisodeRng`] at episode start
+    /// so sampled actions are reproducible from the recorded episode seed.
+    pub exploration_rng: SimRng,
+    /// Episode whose exploration stream is currently loaded.
+    pub exploration_episode: u32,
+}
+
+/// Sizes the network input from the live [`ObservationConfig`].
+impl FromWorld for A2cBrain {
+    fn from_world(world: &mut World) -> Self {
+        let obs_dim = world
+            .get_resource::<ObservationConfig>()
+            .cloned()
+            .unwrap_or_default()
+            .observation_dim();
+        Self::from_model(ActorCritic::new(obs_dim, 64, 2, &mut rand::rng()))
+    }
 }
 
-impl Default for A2cBrain {
-    fn default() -> Self {
-        let mut rng = rand::rng();
+impl A2cBrain {
+    /// A brain for `obs_dim` inputs whose initial weights are reproducible
+    /// from `seed`.
+    pub fn seeded(seed: u64, obs_dim: usize) -> Self {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        Self::from_model(ActorCritic::new(obs_dim, 64, 2, &mut rng))
+    }
+
+    /// A brain restored from an [`A2cCheckpoint`] file, checked against the
+    /// configured `obs_dim`.
+    pub fn from_checkpoint(
+        path: &std::path::Path,
+        obs_dim: usize,
+    ) -> Result<Self, A2cCheckpointError> {
+        let checkpoint = A2cCheckpoint::load(path)?;
+        if checkpoint.observation_dim() != obs_dim {
+            return Err(A2cCheckpointError::ObservationDim {
+                expected: obs_dim,
+                found: checkpoint.observation_dim(),
+            });
+        }
+        Ok(Self::from_model(checkpoint.to_model()?))
+    }
+
+    fn from_model(model: ActorCritic) -> Self {
         Self {
-            model: ActorCritic::new(OBSERVATION_DIM, 64, 2, &mut rng),
+            model,
             buffer: RolloutBuffer::new(),
             gamma: 0.99,
             gae_lambda: 0.95,
             max_steps: 512,
             min_update_steps: 128,
             step_counter: 0,
+            exploration_rng: SimRng::new(0),
+            exploration_episode: 0,
         }
     }
 }
@@ -72,7 +121,6 @@ pub struct A2cTrainingStats {
 
 impl Brain for A2cBrain {
     fn act(&mut self, obs: &ObservationVector) -> CarAction {
-        let mut rng = rand::rng();
         let (action_dist, value) = self.model.forward(&obs.values);
 
         let mut actions = vec![0.0; 2];
@@ -81,7 +129,8 @@ impl Brain for A2cBrain {
         for i in 0..2 {
             let mean = action_dist.mean[i];
             let std = action_dist.std[i];
-            let latent = crate::brain::common::math::sample_normal(mean, std, &mut rng);
+            let latent =
+                crate::brain::common::math::sample_normal(mean, std, &mut self.exploration_rng);
             latent_actions[i] = latent;
 
             let squashed = latent.tanh();
@@ -113,6 +162,11 @@ impl Brain for A2cBrain {
 
         applied_action
     }
+
+    fn begin_episode(&mut self, episode_rng: &EpisodeRng) {
+        self.exploration_rng = episode_rng.exploration.clone();
+        self.exploration_episode = episode_rng.episode;
+    }
 }
 
 pub struct A2cPlugin;
@@ -125,6 +179,7 @@ impl Plugin for A2cPlugin {
                 FixedUpdate,
                 a2c_act_system
                     .after(keyboard_action_input_system)
+                    .before(exploration_noise_system)
                     .before(action_smoothing_system)
                     .in_set(crate::sim::sets::SimSet::Input),
             )
@@ -144,11 +199,18 @@ pub fn a2c_act_system(
     obs_query: Query<&ObservationVector>,
     mut action_state: ResMut<ActionState>,
     mut brain: ResMut<A2cBrain>,
+    episode_rng: Option<Res<EpisodeRng>>,
 ) {
-    if *mode != AgentMode::Ai {
+    if *mode != AgentMode::Learned {
         return;
     }
 
+    if let Some(episode_rng) =
+        episode_rng.filter(|episode_rng| episode_rng.episode != brain.exploration_episode)
+    {
+        brain.begin_episode(&episode_rng);
+    }
+
     if let Ok(obs) = obs_query.single() {
         let action = brain.act(obs);
         action_state.desired = action;
@@ -163,7 +225,7 @@ pub fn a2c_collect_reward_system(
     mut brain: ResMut<A2cBrain>,
     mut stats: ResMut<A2cTrainingStats>,
 ) {
-    if *mode != AgentMode::Ai {
+    if *mode != AgentMode::Learned {
         return;
     }
 
@@ -207,19 +269,23 @@ pub fn a2c_flush_on_exit_system(
         return;
     }
 
-    if *mode != AgentMode::Ai {
+    if *mode != AgentMode::Learned {
         brain.buffer.clear();
         return;
     }
 
-    if brain.buffer.rewards.is_empty() {
-        return;
+    if !brain.buffer.rewards.is_empty() {
+        let bootstrap_state = if brain.buffer.dones.last().copied().unwrap_or(true) {
+            None
+        } else {
+            obs_query.single().ok().map(|obs| obs.values.to_vec())
+        };
+        a2c_update(&mut brain, &mut stats, bootstrap_state.as_deref());
     }
 
-    let bootstrap_state = if brain.buffer.dones.last().copied().unwrap_or(true) {
-        None
-    } else {
-        obs_query.single().ok().map(|obs| obs.values.to_vec())
-    };
-    a2c_update(&mut brain, &mut stats, bootstrap_state.as_deref());
+    let path = std::path::Path::new(A2C_CHECKPOINT_PATH);
+    match A2cCheckpoint::from_model(&brain.model).save(path) {
+        Ok(()) => info!("Saved A2C checkpoint to {}.", path.display()),
+        Err(err) => warn!("Failed to save A2C checkpoint {}: {err}", path.display()),
+    }
 }
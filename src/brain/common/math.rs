@@ -1,6 +1,7 @@
 use rand::Rng;
 use rand::RngExt;
-use rand_distr::{Distribution, Normal};
+
+use crate::sim::rng::SimRng;
 
 /// Initializes a weight matrix with Glorot (Xavier) uniform distribution.
 pub fn glorot_uniform(rows: usize, cols: usize, rng: &mut impl Rng) -> Vec<Vec<f32>> {
@@ -26,10 +27,9 @@ pub fn normal_log_prob(value: f32, mean: f32, std: f32) -> f32 {
     -0.5 * (diff * diff / variance + (2.0 * std::f32::consts::PI).ln() + 2.0 * std.ln())
 }
 
-/// Samples from a normal distribution.
-pub fn sample_normal(mean: f32, std: f32, rng: &mut impl Rng) -> f32 {
-    let normal = Normal::new(mean, std).unwrap();
-    normal.sample(rng)
+/// Samples from a normal distribution using a seeded simulation stream.
+pub fn sample_normal(mean: f32, std: f32, rng: &mut SimRng) -> f32 {
+    rng.normal(mean, std)
 }
 
 /// Computes entropy of a normal distribution.
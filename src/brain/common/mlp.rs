@@ -89,7 +89,7 @@ impl Linear {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct Relu {
     pub input_cache: Option<Vec<f32>>,
 }
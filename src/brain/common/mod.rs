@@ -1,3 +1,4 @@
 pub mod math;
 pub mod mlp;
 pub mod optim;
+pub mod policy_mlp;
@@ -0,0 +1,323 @@
+//! Inference-only multilayer perceptron for fixed policies.
+//!
+//! Unlike the training layers in [`super::mlp`], [`PolicyMlp`] keeps every
+//! parameter in one flat vector (per layer: weights row-major `[out][in]`,
+//! then biases) so whole genomes can be copied, mutated, and saved as a
+//! unit. Every layer, the output included, applies `tanh`. The forward pass
+//! reuses two preallocated buffers and sums in a fixed order, so it neither
+//! allocates nor varies between runs.
+//!
+//! Weights load from and save to JSON (`.json`) or a flat little-endian
+//! binary: magic `NDML`, `u32` version, `u32` layer count, `u32` layer sizes,
+//! then the `f32` parameters.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+const MAGIC: &[u8; 4] = b"NDML";
+const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum PolicyMlpError {
+    Io(std::io::Error),
+    /// The file is not valid JSON or binary weights.
+    Format(String),
+    /// Fewer than two layers, or a layer of width zero.
+    Layers(Vec<usize>),
+    /// The parameter count does not match the layer sizes.
+    ParamCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for PolicyMlpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::Format(err) => write!(f, "invalid weights file: {err}"),
+            Self::Layers(sizes) => write!(
+                f,
+                "layer sizes {sizes:?} need at least two non-empty layers"
+            ),
+            Self::ParamCount { expected, found } => write!(
+                f,
+                "layer sizes need {expected} parameters but {found} were given"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PolicyMlpError {}
+
+/// Serialized form of a [`PolicyMlp`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PolicyMlpWeights {
+    pub layer_sizes: Vec<usize>,
+    pub params: Vec<f32>,
+}
+
+/// Fixed-architecture tanh network.
+#[derive(Clone, Debug)]
+pub struct PolicyMlp {
+    layer_sizes: Vec<usize>,
+    params: Vec<f32>,
+    front: Vec<f32>,
+    back: Vec<f32>,
+}
+
+impl PolicyMlp {
+    /// Number of parameters a network with `layer_sizes` holds.
+    pub fn param_count(layer_sizes: &[usize]) -> usize {
+        layer_sizes
+            .windows(2)
+            .map(|pair| pair[0] * pair[1] + pair[1])
+            .sum()
+    }
+
+    pub fn new(layer_sizes: Vec<usize>, params: Vec<f32>) -> Result<Self, PolicyMlpError> {
+        if layer_sizes.len() < 2 || layer_sizes.contains(&0) {
+            return Err(PolicyMlpError::Layers(layer_sizes));
+        }
+        let expected = Self::param_count(&layer_sizes);
+        if params.len() != expected {
+            return Err(PolicyMlpError::ParamCount {
+                expected,
+                found: params.len(),
+            });
+        }
+        let width = layer_sizes.iter().copied().max().unwrap_or(0);
+        Ok(Self {
+            layer_sizes,
+            params,
+            front: vec![0.0; width],
+            back: vec![0.0; width],
+        })
+    }
+
+    /// A network with every weight and bias zero.
+    pub fn zeros(layer_sizes: Vec<usize>) -> Result<Self, PolicyMlpError> {
+        let params = vec![0.0; Self::param_count(&layer_sizes)];
+        Self::new(layer_sizes, params)
+    }
+
+    pub fn layer_sizes(&self) -> &[usize] {
+        &self.layer_sizes
+    }
+
+    pub fn input_dim(&self) -> usize {
+        self.layer_sizes[0]
+    }
+
+    pub fn output_dim(&self) -> usize {
+        self.layer_sizes[self.layer_sizes.len() - 1]
+    }
+
+    pub fn params(&self) -> &[f32] {
+        &self.params
+    }
+
+    pub fn params_mut(&mut self) -> &mut [f32] {
+        &mut self.params
+    }
+
+    /// Runs the network on `input`, which is truncated or zero-padded to
+    /// [`Self::input_dim`].
+    pub fn forward(&mut self, input: &[f32]) -> &[f32] {
+        let Self {
+            layer_sizes,
+            params,
+            front,
+            back,
+        } = self;
+        let input_dim = layer_sizes[0];
+        front[..input_dim].fill(0.0);
+        for (slot, value) in front[..input_dim].iter_mut().zip(input) {
+            *slot = *value;
+        }
+
+        let mut offset = 0;
+        for pair in layer_sizes.windows(2) {
+            let (inputs, outputs) = (pair[0], pair[1]);
+            let weights = &params[offset..offset + inputs * outputs];
+            let biases = &params[offset + inputs * outputs..offset + inputs * outputs + outputs];
+            offset += inputs * outputs + outputs;
+            for ((out, row), bias) in back[..outputs]
+                .iter_mut()
+                .zip(weights.chunks_exact(inputs))
+                .zip(biases)
+            {
+                let sum = row
+                    .iter()
+                    .zip(&front[..inputs])
+                    .fold(*bias, |sum, (weight, value)| sum + weight * value);
+                *out = sum.tanh();
+            }
+            std::mem::swap(front, back);
+        }
+        &front[..layer_sizes[layer_sizes.len() - 1]]
+    }
+
+    pub fn weights(&self) -> PolicyMlpWeights {
+        PolicyMlpWeights {
+            layer_sizes: self.layer_sizes.clone(),
+            params: self.params.clone(),
+        }
+    }
+
+    pub fn from_weights(weights: PolicyMlpWeights) -> Result<Self, PolicyMlpError> {
+        Self::new(weights.layer_sizes, weights.params)
+    }
+
+    /// Encodes the binary weights format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12 + 4 * (self.layer_sizes.len() + self.params.len()));
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.layer_sizes.len() as u32).to_le_bytes());
+        for size in &self.layer_sizes {
+            bytes.extend_from_slice(&(*size as u32).to_le_bytes());
+        }
+        for param in &self.params {
+            bytes.extend_from_slice(&param.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes the binary weights format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PolicyMlpError> {
+        let mut words = bytes
+            .get(4..)
+            .filter(|_| bytes.starts_with(MAGIC))
+            .ok_or_else(|| PolicyMlpError::Format("missing NDML header".to_string()))?
+            .chunks(4)
+            .map(|chunk| {
+                <[u8; 4]>::try_from(chunk)
+                    .map_err(|_| PolicyMlpError::Format("truncated word".to_string()))
+            });
+        let mut next_u32 = || -> Result<u32, PolicyMlpError> {
+            words
+                .next()
+                .ok_or_else(|| PolicyMlpError::Format("unexpected end of file".to_string()))?
+                .map(u32::from_le_bytes)
+        };
+        let version = next_u32()?;
+        if version != VERSION {
+            return Err(PolicyMlpError::Format(format!(
+                "unsupported version {version}"
+            )));
+        }
+        let layers = next_u32()?;
+        let layer_sizes = (0..layers)
+            .map(|_| next_u32().map(|size| size as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+        let params = words
+            .map(|word| word.map(f32::from_le_bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(layer_sizes, params)
+    }
+
+    /// Writes JSON when `path` ends in `.json`, binary otherwise.
+    pub fn save(&self, path: &Path) -> Result<(), PolicyMlpError> {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let bytes = if is_json(path) {
+            serde_json::to_vec_pretty(&self.weights())
+                .map_err(|err| PolicyMlpError::Format(err.to_string()))?
+        } else {
+            self.to_bytes()
+        };
+        fs::write(path, bytes).map_err(PolicyMlpError::Io)
+    }
+
+    /// Reads JSON when `path` ends in `.json`, binary otherwise.
+    pub fn load(path: &Path) -> Result<Self, PolicyMlpError> {
+        let bytes = fs::read(path).map_err(PolicyMlpError::Io)?;
+        if is_json(path) {
+            let weights = serde_json::from_slice(&bytes)
+                .map_err(|err| PolicyMlpError::Format(err.to_string()))?;
+            Self::from_weights(weights)
+        } else {
+            Self::from_bytes(&bytes)
+        }
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PolicyMlp, PolicyMlpError};
+
+    /// 2-2-1 network: hidden = tanh(W1 x + b1), out = tanh(W2 hidden + b2).
+    fn two_two_one() -> PolicyMlp {
+        PolicyMlp::new(
+            vec![2, 2, 1],
+            vec![
+                // W1 rows, then b1.
+                0.5, -1.0, //
+                0.25, 0.75, //
+                0.1, -0.2, //
+                // W2 row, then b2.
+                1.5, -0.5, //
+                0.05,
+            ],
+        )
+        .expect("valid network")
+    }
+
+    #[test]
+    fn forward_pass_matches_hand_computed_values() {
+        let mut net = two_two_one();
+        let x = [0.8_f32, -0.4];
+
+        let h0 = (0.5 * 0.8 + -1.0 * -0.4 + 0.1_f32).tanh();
+        let h1 = (0.25 * 0.8 + 0.75 * -0.4 + -0.2_f32).tanh();
+        let expected = (1.5 * h0 + -0.5 * h1 + 0.05_f32).tanh();
+        // tanh(0.9) = 0.716298, tanh(-0.3) = -0.291313.
+        assert!((h0 - 0.716_298).abs() < 1e-5);
+        assert!((h1 + 0.291_313).abs() < 1e-5);
+
+        let output = net.forward(&x).to_vec();
+        assert_eq!(output.len(), 1);
+        assert!((output[0] - expected).abs() < 1e-6);
+        assert!((output[0] - 0.853_826).abs() < 1e-5);
+
+        // Repeated passes reuse the buffers and give bit-identical output.
+        assert_eq!(net.forward(&x), output.as_slice());
+    }
+
+    #[test]
+    fn weights_round_trip_through_json_and_binary() {
+        let dir =
+            std::env::temp_dir().join(format!("neurodrive_policy_mlp_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let net = two_two_one();
+
+        for name in ["weights.json", "weights.bin"] {
+            let path = dir.join(name);
+            net.save(&path).expect("saves");
+            let loaded = PolicyMlp::load(&path).expect("loads");
+            assert_eq!(loaded.weights(), net.weights(), "{name}");
+        }
+
+        assert!(matches!(
+            PolicyMlp::new(vec![2, 2, 1], vec![0.0; 8]),
+            Err(PolicyMlpError::ParamCount {
+                expected: 9,
+                found: 8
+            })
+        ));
+        let mut truncated = net.to_bytes();
+        truncated.truncate(truncated.len() - 4);
+        assert!(PolicyMlp::from_bytes(&truncated).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod a2c;
 pub mod common;
+pub mod neat;
+pub mod neuroevolution;
 pub mod plugin;
 pub mod types;
@@ -0,0 +1,927 @@
+//! Minimal NEAT (NeuroEvolution of Augmenting Topologies).
+//!
+//! A [`NeatGenome`] is a list of node genes and connection genes; each
+//! connection carries the innovation number it was first created with, so
+//! crossover can line up genes of different topologies. Genomes start as a
+//! direct input → output layer (plus a bias node) and grow by structural
+//! mutation: splitting a connection with a new hidden node, or adding a
+//! connection between unconnected nodes. Connections that would close a
+//! cycle are never added, so every network is feed-forward and is evaluated
+//! in topological order by [`NeatNetwork`] with `tanh` on hidden and output
+//! nodes.
+//!
+//! [`NeatPopulation`] speciates genomes by compatibility distance and shares
+//! fitness within species when assigning offspring. It is driven episode by
+//! episode by the scheduler in [`super::neuroevolution`], exactly like the
+//! fixed-topology MLP population, and uses the same [`SimRng`] for every
+//! random draw.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::brain::neuroevolution::{GenerationStats, NeuroEvolutionConfig, NeuroEvolutionError};
+use crate::sim::rng::SimRng;
+
+/// NEAT-only hyperparameters (config `neuroevolution.neat`). Weight mutation
+/// reuses `mutation_rate` and `mutation_std` from the parent config.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NeatConfig {
+    /// Chance a child gains a hidden node by splitting a connection.
+    pub add_node_rate: f32,
+    /// Chance a child gains a connection between unconnected nodes.
+    pub add_connection_rate: f32,
+    /// Compatibility distance below which two genomes share a species.
+    pub compatibility_threshold: f32,
+    /// Distance weight of excess genes.
+    pub excess_coefficient: f32,
+    /// Distance weight of disjoint genes.
+    pub disjoint_coefficient: f32,
+    /// Distance weight of the mean weight difference of matching genes.
+    pub weight_coefficient: f32,
+    /// Fraction of each species, best first, allowed to reproduce.
+    pub survival_fraction: f32,
+}
+
+impl Default for NeatConfig {
+    fn default() -> Self {
+        Self {
+            add_node_rate: 0.03,
+            add_connection_rate: 0.05,
+            compatibility_threshold: 3.0,
+            excess_coefficient: 1.0,
+            disjoint_coefficient: 1.0,
+            weight_coefficient: 0.4,
+            survival_fraction: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeKind {
+    Input,
+    /// Constant `1.0` input.
+    Bias,
+    Hidden,
+    Output,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeGene {
+    pub id: u32,
+    pub kind: NodeKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionGene {
+    pub innovation: u32,
+    pub from: u32,
+    pub to: u32,
+    pub weight: f32,
+    pub enabled: bool,
+}
+
+/// Hands out innovation numbers and node ids. Structural mutations repeated
+/// within one generation (the same connection added, or the same connection
+/// split) receive the same numbers, as in the original NEAT.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InnovationTracker {
+    next_innovation: u32,
+    next_node: u32,
+    #[serde(skip)]
+    connections: HashMap<(u32, u32), u32>,
+    #[serde(skip)]
+    splits: HashMap<u32, u32>,
+}
+
+impl InnovationTracker {
+    fn connection(&mut self, from: u32, to: u32) -> u32 {
+        let next = &mut self.next_innovation;
+        *self.connections.entry((from, to)).or_insert_with(|| {
+            *next += 1;
+            *next - 1
+        })
+    }
+
+    /// Node id for splitting the connection with `innovation`.
+    fn split(&mut self, innovation: u32) -> u32 {
+        let next = &mut self.next_node;
+        *self.splits.entry(innovation).or_insert_with(|| {
+            *next += 1;
+            *next - 1
+        })
+    }
+
+    /// Ends a generation's breeding; repeats are only shared within one.
+    fn next_generation(&mut self) {
+        self.connections.clear();
+        self.splits.clear();
+    }
+}
+
+/// Node and connection genes of one network.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeatGenome {
+    /// Sorted by id; inputs first, in observation order.
+    pub nodes: Vec<NodeGene>,
+    /// Sorted by innovation number.
+    pub connections: Vec<ConnectionGene>,
+}
+
+/// Size of a network, as shown on the HUD.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NetworkStructure {
+    pub nodes: usize,
+    pub hidden_nodes: usize,
+    pub enabled_connections: usize,
+}
+
+impl NeatGenome {
+    /// Every input and the bias connected directly to every output with
+    /// `N(0, init_std)` weights. Ids and innovation numbers depend only on
+    /// the dimensions, so all initial genomes line up.
+    pub fn minimal(
+        inputs: usize,
+        outputs: usize,
+        init_std: f32,
+        tracker: &mut InnovationTracker,
+        rng: &mut SimRng,
+    ) -> Self {
+        let kinds = std::iter::repeat_n(NodeKind::Input, inputs)
+            .chain([NodeKind::Bias])
+            .chain(std::iter::repeat_n(NodeKind::Output, outputs));
+        let nodes: Vec<NodeGene> = kinds
+            .enumerate()
+            .map(|(id, kind)| NodeGene {
+                id: id as u32,
+                kind,
+            })
+            .collect();
+        tracker.next_node = tracker.next_node.max(nodes.len() as u32);
+
+        let mut connections = Vec::with_capacity((inputs + 1) * outputs);
+        for from in 0..=inputs as u32 {
+            for output in 0..outputs as u32 {
+                let to = inputs as u32 + 1 + output;
+                connections.push(ConnectionGene {
+                    innovation: from * outputs as u32 + output,
+                    from,
+                    to,
+                    weight: rng.normal(0.0, init_std),
+                    enabled: true,
+                });
+            }
+        }
+        tracker.next_innovation = tracker.next_innovation.max(connections.len() as u32);
+        Self { nodes, connections }
+    }
+
+    pub fn structure(&self) -> NetworkStructure {
+        NetworkStructure {
+            nodes: self.nodes.len(),
+            hidden_nodes: self
+                .nodes
+                .iter()
+                .filter(|node| node.kind == NodeKind::Hidden)
+                .count(),
+            enabled_connections: self
+                .connections
+                .iter()
+                .filter(|connection| connection.enabled)
+                .count(),
+        }
+    }
+
+    fn kind_of(&self, id: u32) -> Option<NodeKind> {
+        self.nodes
+            .binary_search_by_key(&id, |node| node.id)
+            .ok()
+            .map(|index| self.nodes[index].kind)
+    }
+
+    /// Perturbs each connection weight with probability `rate`.
+    pub fn mutate_weights(&mut self, rate: f32, std: f32, rng: &mut SimRng) {
+        for connection in &mut self.connections {
+            if rng.next_f32() < rate {
+                connection.weight += rng.normal(0.0, std);
+            }
+        }
+    }
+
+    /// Splits a random enabled connection `a → b` into `a → new` (weight 1)
+    /// and `new → b` (the old weight), disabling the original. Returns
+    /// `false` when there is nothing to split.
+    pub fn mutate_add_node(&mut self, tracker: &mut InnovationTracker, rng: &mut SimRng) -> bool {
+        let enabled: Vec<usize> = (0..self.connections.len())
+            .filter(|&index| self.connections[index].enabled)
+            .collect();
+        if enabled.is_empty() {
+            return false;
+        }
+        let index = enabled[(rng.next_u64() % enabled.len() as u64) as usize];
+        let old = self.connections[index];
+        let node = tracker.split(old.innovation);
+        if self.kind_of(node).is_some() {
+            return false;
+        }
+
+        self.connections[index].enabled = false;
+        let position = self.nodes.partition_point(|gene| gene.id < node);
+        self.nodes.insert(
+            position,
+            NodeGene {
+                id: node,
+                kind: NodeKind::Hidden,
+            },
+        );
+        let incoming = tracker.connection(old.from, node);
+        let outgoing = tracker.connection(node, old.to);
+        self.insert_connection(ConnectionGene {
+            innovation: incoming,
+            from: old.from,
+            to: node,
+            weight: 1.0,
+            enabled: true,
+        });
+        self.insert_connection(ConnectionGene {
+            innovation: outgoing,
+            from: node,
+            to: old.to,
+            weight: old.weight,
+            enabled: true,
+        });
+        true
+    }
+
+    /// Connects two random unconnected nodes with a `N(0, weight_std)`
+    /// weight, skipping pairs that would close a cycle. Returns `false` when
+    /// no candidate was found within a few attempts.
+    pub fn mutate_add_connection(
+        &mut self,
+        weight_std: f32,
+        tracker: &mut InnovationTracker,
+        rng: &mut SimRng,
+    ) -> bool {
+        const ATTEMPTS: usize = 20;
+        let sources: Vec<u32> = self
+            .nodes
+            .iter()
+            .filter(|node| node.kind != NodeKind::Output)
+            .map(|node| node.id)
+            .collect();
+        let targets: Vec<u32> = self
+            .nodes
+            .iter()
+            .filter(|node| matches!(node.kind, NodeKind::Hidden | NodeKind::Output))
+            .map(|node| node.id)
+            .collect();
+        for _ in 0..ATTEMPTS {
+            let from = sources[(rng.next_u64() % sources.len() as u64) as usize];
+            let to = targets[(rng.next_u64() % targets.len() as u64) as usize];
+            let exists = self
+                .connections
+                .iter()
+                .any(|connection| connection.from == from && connection.to == to);
+            if from == to || exists || self.reaches(to, from) {
+                continue;
+            }
+            let innovation = tracker.connection(from, to);
+            self.insert_connection(ConnectionGene {
+                innovation,
+                from,
+                to,
+                weight: rng.normal(0.0, weight_std),
+                enabled: true,
+            });
+            return true;
+        }
+        false
+    }
+
+    /// Whether `target` is reachable from `start` along any connection,
+    /// enabled or not.
+    fn reaches(&self, start: u32, target: u32) -> bool {
+        let mut stack = vec![start];
+        let mut seen = vec![start];
+        while let Some(node) = stack.pop() {
+            if node == target {
+                return true;
+            }
+            for connection in self.connections.iter().filter(|c| c.from == node) {
+                if !seen.contains(&connection.to) {
+                    seen.push(connection.to);
+                    stack.push(connection.to);
+                }
+            }
+        }
+        false
+    }
+
+    fn insert_connection(&mut self, connection: ConnectionGene) {
+        let position = self
+            .connections
+            .partition_point(|gene| gene.innovation < connection.innovation);
+        self.connections.insert(position, connection);
+    }
+
+    /// Child of `fitter` and `other`: matching genes take either parent's
+    /// weight at random, disjoint and excess genes come from `fitter`, so the
+    /// child has `fitter`'s topology.
+    pub fn crossover(fitter: &Self, other: &Self, rng: &mut SimRng) -> Self {
+        let connections = fitter
+            .connections
+            .iter()
+            .map(|gene| {
+                let matching = other
+                    .connections
+                    .binary_search_by_key(&gene.innovation, |other| other.innovation)
+                    .ok()
+                    .map(|index| other.connections[index]);
+                match matching {
+                    Some(other) if rng.next_f32() < 0.5 => ConnectionGene {
+                        weight: other.weight,
+                        ..*gene
+                    },
+                    _ => *gene,
+                }
+            })
+            .collect();
+        Self {
+            nodes: fitter.nodes.clone(),
+            connections,
+        }
+    }
+
+    /// NEAT compatibility distance
+    /// `c_e * excess / n + c_d * disjoint / n + c_w * mean |Δw|`, where `n` is
+    /// the larger gene count (1 below 20 genes).
+    pub fn compatibility(&self, other: &Self, config: &NeatConfig) -> f32 {
+        let (a, b) = (&self.connections, &other.connections);
+        let (mut i, mut j) = (0, 0);
+        let (mut disjoint, mut matching, mut weight_diff) = (0usize, 0usize, 0.0_f32);
+        while i < a.len() && j < b.len() {
+            match a[i].innovation.cmp(&b[j].innovation) {
+                std::cmp::Ordering::Equal => {
+                    matching += 1;
+                    weight_diff += (a[i].weight - b[j].weight).abs();
+                    i += 1;
+                    j += 1;
+                }
+                std::cmp::Ordering::Less => {
+                    disjoint += 1;
+                    i += 1;
+                }
+                std::cmp::Ordering::Greater => {
+                    disjoint += 1;
+                    j += 1;
+                }
+            }
+        }
+        let excess = (a.len() - i) + (b.len() - j);
+        let genes = a.len().max(b.len());
+        let n = if genes < 20 { 1.0 } else { genes as f32 };
+        let mean_weight_diff = if matching == 0 {
+            0.0
+        } else {
+            weight_diff / matching as f32
+        };
+        config.excess_coefficient * excess as f32 / n
+            + config.disjoint_coefficient * disjoint as f32 / n
+            + config.weight_coefficient * mean_weight_diff
+    }
+}
+
+/// Feed-forward phenotype of a [`NeatGenome`].
+#[derive(Clone, Debug)]
+pub struct NeatNetwork {
+    values: Vec<f32>,
+    /// Hidden and output nodes in evaluation order.
+    order: Vec<usize>,
+    /// Enabled incoming `(source, weight)` pairs per node.
+    incoming: Vec<Vec<(usize, f32)>>,
+    inputs: Vec<usize>,
+    bias: Option<usize>,
+    outputs: Vec<usize>,
+    output_values: Vec<f32>,
+}
+
+impl NeatNetwork {
+    pub fn new(genome: &NeatGenome) -> Self {
+        let index_of: HashMap<u32, usize> = genome
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id, index))
+            .collect();
+        let mut incoming = vec![Vec::new(); genome.nodes.len()];
+        let mut outgoing = vec![Vec::new(); genome.nodes.len()];
+        let mut pending = vec![0usize; genome.nodes.len()];
+        for connection in genome.connections.iter().filter(|c| c.enabled) {
+            let (Some(&from), Some(&to)) =
+                (index_of.get(&connection.from), index_of.get(&connection.to))
+            else {
+                continue;
+            };
+            incoming[to].push((from, connection.weight));
+            outgoing[from].push(to);
+            pending[to] += 1;
+        }
+
+        // Kahn's algorithm from every node without enabled inputs.
+        let mut ready: Vec<usize> = (0..genome.nodes.len())
+            .filter(|&index| pending[index] == 0)
+            .rev()
+            .collect();
+        let mut order = Vec::with_capacity(genome.nodes.len());
+        while let Some(index) = ready.pop() {
+            if matches!(
+                genome.nodes[index].kind,
+                NodeKind::Hidden | NodeKind::Output
+            ) {
+                order.push(index);
+            }
+            for &next in &outgoing[index] {
+                pending[next] -= 1;
+                if pending[next] == 0 {
+                    ready.push(next);
+                }
+            }
+        }
+
+        let indices_of = |kind: NodeKind| -> Vec<usize> {
+            (0..genome.nodes.len())
+                .filter(|&index| genome.nodes[index].kind == kind)
+                .collect()
+        };
+        let outputs = indices_of(NodeKind::Output);
+        Self {
+            values: vec![0.0; genome.nodes.len()],
+            order,
+            incoming,
+            inputs: indices_of(NodeKind::Input),
+            bias: indices_of(NodeKind::Bias).first().copied(),
+            output_values: vec![0.0; outputs.len()],
+            outputs,
+        }
+    }
+
+    /// Runs the network on `input`, which is truncated or zero-padded to the
+    /// input count.
+    pub fn forward(&mut self, input: &[f32]) -> &[f32] {
+        self.values.fill(0.0);
+        for (position, &node) in self.inputs.iter().enumerate() {
+            self.values[node] = input.get(position).copied().unwrap_or(0.0);
+        }
+        if let Some(bias) = self.bias {
+            self.values[bias] = 1.0;
+        }
+        for &node in &self.order {
+            let sum = self.incoming[node]
+                .iter()
+                .fold(0.0, |sum, &(source, weight)| {
+                    sum + weight * self.values[source]
+                });
+            self.values[node] = sum.tanh();
+        }
+        for (slot, &node) in self.output_values.iter_mut().zip(&self.outputs) {
+            *slot = self.values[node];
+        }
+        &self.output_values
+    }
+}
+
+/// A group of compatible genomes that compete for offspring among
+/// themselves.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Species {
+    pub id: u32,
+    /// Genome new members are compared against.
+    pub representative: NeatGenome,
+    /// Indices into the generation the species was last formed from.
+    pub members: Vec<usize>,
+}
+
+/// Best NEAT genome seen across all generations.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeatChampion {
+    pub generation: u32,
+    pub fitness: f32,
+    pub genome: NeatGenome,
+}
+
+/// One generation of NEAT genomes plus speciation and innovation state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeatPopulation {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub generation: u32,
+    pub genomes: Vec<NeatGenome>,
+    /// Fitness of each genome this generation; `None` until evaluated.
+    pub fitness: Vec<Option<f32>>,
+    pub species: Vec<Species>,
+    pub best: Option<NeatChampion>,
+    next_species_id: u32,
+    tracker: InnovationTracker,
+    rng: SimRng,
+}
+
+impl NeatPopulation {
+    /// Generation 0 of minimal genomes.
+    pub fn random(inputs: usize, outputs: usize, config: &NeuroEvolutionConfig) -> Self {
+        let mut rng = SimRng::new(config.seed);
+        let mut tracker = InnovationTracker::default();
+        let genomes: Vec<NeatGenome> = (0..config.population_size.max(1))
+            .map(|_| NeatGenome::minimal(inputs, outputs, config.init_std, &mut tracker, &mut rng))
+            .collect();
+        tracker.next_generation();
+        Self {
+            inputs,
+            outputs,
+            generation: 0,
+            fitness: vec![None; genomes.len()],
+            genomes,
+            species: Vec::new(),
+            best: None,
+            next_species_id: 0,
+            tracker,
+            rng,
+        }
+    }
+
+    /// First genome still waiting for a fitness, if any.
+    pub fn next_unevaluated(&self) -> Option<usize> {
+        self.fitness.iter().position(Option::is_none)
+    }
+
+    /// Scores genome `index` and updates the champion.
+    pub fn record_fitness(&mut self, index: usize, fitness: f32) {
+        self.fitness[index] = Some(fitness);
+        if self.best.as_ref().is_none_or(|best| fitness > best.fitness) {
+            self.best = Some(NeatChampion {
+                generation: self.generation,
+                fitness,
+                genome: self.genomes[index].clone(),
+            });
+        }
+    }
+
+    /// Assigns every genome to the first species whose representative is
+    /// within the compatibility threshold, founding new species as needed,
+    /// and drops species left empty.
+    fn speciate(&mut self, config: &NeatConfig) {
+        for species in &mut self.species {
+            species.members.clear();
+        }
+        for (index, genome) in self.genomes.iter().enumerate() {
+            let found = self.species.iter_mut().find(|species| {
+                genome.compatibility(&species.representative, config)
+                    < config.compatibility_threshold
+            });
+            match found {
+                Some(species) => species.members.push(index),
+                None => {
+                    self.species.push(Species {
+                        id: self.next_species_id,
+                        representative: genome.clone(),
+                        members: vec![index],
+                    });
+                    self.next_species_id += 1;
+                }
+            }
+        }
+        self.species.retain(|species| !species.members.is_empty());
+    }
+
+    /// Speciates the evaluated generation and replaces it with offspring.
+    /// The global elites survive unchanged; the remaining slots are shared
+    /// between species in proportion to their mean shifted fitness and
+    /// filled by crossover and mutation among each species' best members.
+    pub fn evolve(&mut self, config: &NeuroEvolutionConfig) -> GenerationStats {
+        let fitness: Vec<f32> = self
+            .fitness
+            .iter()
+            .map(|fitness| fitness.unwrap_or(f32::NEG_INFINITY))
+            .collect();
+        let stats = GenerationStats {
+            generation: self.generation,
+            best: fitness.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            mean: fitness.iter().sum::<f32>() / fitness.len() as f32,
+            worst: fitness.iter().copied().fold(f32::INFINITY, f32::min),
+        };
+        self.speciate(&config.neat);
+
+        let size = self.genomes.len();
+        let mut ranked: Vec<usize> = (0..size).collect();
+        ranked.sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+        let mut next: Vec<NeatGenome> = ranked
+            .iter()
+            .take(config.elite_count.min(size))
+            .map(|&index| self.genomes[index].clone())
+            .collect();
+
+        // Explicit fitness sharing: a species earns its mean shifted fitness.
+        let floor = fitness
+            .iter()
+            .copied()
+            .filter(|value| value.is_finite())
+            .fold(f32::INFINITY, f32::min);
+        let shifted = |index: usize| {
+            if fitness[index].is_finite() {
+                fitness[index] - floor + 1e-3
+            } else {
+                0.0
+            }
+        };
+        let shares: Vec<f32> = self
+            .species
+            .iter()
+            .map(|species| {
+                species
+                    .members
+                    .iter()
+                    .map(|&index| shifted(index))
+                    .sum::<f32>()
+                    / species.members.len() as f32
+            })
+            .collect();
+        let offspring = allocate(&shares, size - next.len());
+
+        for (species, count) in self.species.iter_mut().zip(offspring) {
+            species
+                .members
+                .sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+            let parents = ((species.members.len() as f32 * config.neat.survival_fraction).ceil()
+                as usize)
+                .clamp(1, species.members.len());
+            for _ in 0..count {
+                let pick =
+                    |rng: &mut SimRng| species.members[(rng.next_u64() % parents as u64) as usize];
+                let first = pick(&mut self.rng);
+                let mut child = if self.rng.next_f32() < config.crossover_rate {
+                    let second = pick(&mut self.rng);
+                    let (fitter, other) = if fitness[second] > fitness[first] {
+                        (second, first)
+                    } else {
+                        (first, second)
+                    };
+                    NeatGenome::crossover(
+                        &self.genomes[fitter],
+                        &self.genomes[other],
+                        &mut self.rng,
+                    )
+                } else {
+                    self.genomes[first].clone()
+                };
+                child.mutate_weights(config.mutation_rate, config.mutation_std, &mut self.rng);
+                if self.rng.next_f32() < config.neat.add_node_rate {
+                    child.mutate_add_node(&mut self.tracker, &mut self.rng);
+                }
+                if self.rng.next_f32() < config.neat.add_connection_rate {
+                    child.mutate_add_connection(
+                        config.mutation_std,
+                        &mut self.tracker,
+                        &mut self.rng,
+                    );
+                }
+                next.push(child);
+            }
+            // The species' best member represents it next generation.
+            species.representative = self.genomes[species.members[0]].clone();
+        }
+
+        // The per-generation repeat tables are not saved, so leave them empty
+        // between generations and a loaded population equals the saved one.
+        self.tracker.next_generation();
+        self.genomes = next;
+        self.fitness = vec![None; size];
+        self.generation += 1;
+        stats
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), NeuroEvolutionError> {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|err| NeuroEvolutionError::Format(err.to_string()))?;
+        fs::write(path, json).map_err(NeuroEvolutionError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, NeuroEvolutionError> {
+        let bytes = fs::read(path).map_err(NeuroEvolutionError::Io)?;
+        let population: Self = serde_json::from_slice(&bytes)
+            .map_err(|err| NeuroEvolutionError::Format(err.to_string()))?;
+        if population.genomes.is_empty() || population.fitness.len() != population.genomes.len() {
+            return Err(NeuroEvolutionError::Format(
+                "expected non-empty genomes with one fitness each".to_string(),
+            ));
+        }
+        Ok(population)
+    }
+}
+
+/// Splits `total` slots in proportion to `shares` (largest remainder, ties
+/// to the earlier entry). All-zero shares split evenly.
+fn allocate(shares: &[f32], total: usize) -> Vec<usize> {
+    if shares.is_empty() {
+        return Vec::new();
+    }
+    let sum: f32 = shares.iter().sum();
+    let exact: Vec<f32> = if sum > 0.0 {
+        shares
+            .iter()
+            .map(|share| share / sum * total as f32)
+            .collect()
+    } else {
+        vec![total as f32 / shares.len() as f32; shares.len()]
+    };
+    let mut counts: Vec<usize> = exact.iter().map(|value| value.floor() as usize).collect();
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+    let assigned: usize = counts.iter().sum();
+    for &index in order.iter().cycle().take(total.saturating_sub(assigned)) {
+        counts[index] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        InnovationTracker, NeatConfig, NeatGenome, NeatNetwork, NeatPopulation, NodeKind, allocate,
+    };
+    use crate::brain::neuroevolution::NeuroEvolutionConfig;
+    use crate::sim::rng::SimRng;
+
+    fn minimal(rng: &mut SimRng, tracker: &mut InnovationTracker) -> NeatGenome {
+        NeatGenome::minimal(3, 2, 0.5, tracker, rng)
+    }
+
+    #[test]
+    fn add_node_splits_a_connection_and_preserves_the_signal_path() {
+        let mut rng = SimRng::new(1);
+        let mut tracker = InnovationTracker::default();
+        let mut genome = minimal(&mut rng, &mut tracker);
+        assert_eq!(genome.connections.len(), 8);
+
+        assert!(genome.mutate_add_node(&mut tracker, &mut rng));
+        let structure = genome.structure();
+        assert_eq!((structure.nodes, structure.hidden_nodes), (7, 1));
+        assert_eq!(structure.enabled_connections, 9);
+        let disabled: Vec<_> = genome.connections.iter().filter(|c| !c.enabled).collect();
+        assert_eq!(disabled.len(), 1);
+        let split = *disabled[0];
+        let hidden = genome
+            .nodes
+            .iter()
+            .find(|node| node.kind == NodeKind::Hidden)
+            .expect("hidden node")
+            .id;
+        let into = genome
+            .connections
+            .iter()
+            .find(|c| c.to == hidden)
+            .expect("incoming");
+        let out = genome
+            .connections
+            .iter()
+            .find(|c| c.from == hidden)
+            .expect("outgoing");
+        assert_eq!((into.from, into.weight), (split.from, 1.0));
+        assert_eq!((out.to, out.weight), (split.to, split.weight));
+        assert!(
+            genome
+                .connections
+                .windows(2)
+                .all(|pair| pair[0].innovation < pair[1].innovation)
+        );
+
+        // The same split elsewhere this generation reuses the numbers.
+        let mut twin = minimal(&mut SimRng::new(1), &mut tracker);
+        twin.connections = twin
+            .connections
+            .iter()
+            .map(|c| super::ConnectionGene {
+                enabled: c.innovation == split.innovation,
+                ..*c
+            })
+            .collect();
+        assert!(twin.mutate_add_node(&mut tracker, &mut SimRng::new(9)));
+        let innovations = |genome: &NeatGenome| {
+            genome
+                .connections
+                .iter()
+                .map(|c| c.innovation)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(innovations(&twin), innovations(&genome));
+    }
+
+    #[test]
+    fn add_connection_never_closes_a_cycle() {
+        let mut rng = SimRng::new(2);
+        let mut tracker = InnovationTracker::default();
+        let mut genome = minimal(&mut rng, &mut tracker);
+        for _ in 0..6 {
+            genome.mutate_add_node(&mut tracker, &mut rng);
+        }
+        let mut added = 0;
+        for _ in 0..40 {
+            if genome.mutate_add_connection(0.5, &mut tracker, &mut rng) {
+                added += 1;
+            }
+        }
+        assert!(added > 0);
+        for connection in &genome.connections {
+            assert!(!genome.reaches(connection.to, connection.from));
+            let target = genome.kind_of(connection.to).expect("known target");
+            assert!(matches!(target, NodeKind::Hidden | NodeKind::Output));
+        }
+        // Every hidden and output node is evaluated.
+        let network = NeatNetwork::new(&genome);
+        assert_eq!(network.order.len(), genome.structure().hidden_nodes + 2);
+    }
+
+    #[test]
+    fn weight_mutation_and_crossover_keep_the_topology() {
+        let mut rng = SimRng::new(3);
+        let mut tracker = InnovationTracker::default();
+        let parent = minimal(&mut rng, &mut tracker);
+
+        let mut unchanged = parent.clone();
+        unchanged.mutate_weights(0.0, 1.0, &mut rng);
+        assert_eq!(unchanged, parent);
+        let mut mutated = parent.clone();
+        mutated.mutate_weights(1.0, 1.0, &mut rng);
+        assert!(
+            mutated
+                .connections
+                .iter()
+                .zip(&parent.connections)
+                .all(|(a, b)| a.weight != b.weight && a.innovation == b.innovation)
+        );
+        let config = NeatConfig::default();
+        assert_eq!(parent.compatibility(&parent, &config), 0.0);
+        assert!(mutated.compatibility(&parent, &config) > 0.0);
+
+        let mut fitter = parent.clone();
+        fitter.mutate_add_node(&mut tracker, &mut rng);
+        let child = NeatGenome::crossover(&fitter, &mutated, &mut rng);
+        assert_eq!(child.nodes, fitter.nodes);
+        for (gene, from_fitter) in child.connections.iter().zip(&fitter.connections) {
+            assert_eq!(
+                (gene.innovation, gene.enabled),
+                (from_fitter.innovation, from_fitter.enabled)
+            );
+            let other = mutated
+                .connections
+                .iter()
+                .find(|c| c.innovation == gene.innovation);
+            assert!(
+                gene.weight == from_fitter.weight || other.is_some_and(|o| o.weight == gene.weight)
+            );
+        }
+    }
+
+    #[test]
+    fn minimal_network_is_a_tanh_linear_layer() {
+        let mut tracker = InnovationTracker::default();
+        let genome = NeatGenome::minimal(2, 1, 0.5, &mut tracker, &mut SimRng::new(4));
+        let weight = |from: u32| {
+            genome
+                .connections
+                .iter()
+                .find(|c| c.from == from)
+                .expect("connection")
+                .weight
+        };
+        let expected = (0.5 * weight(0) - 0.25 * weight(1) + weight(2)).tanh();
+        let mut network = NeatNetwork::new(&genome);
+        assert!((network.forward(&[0.5, -0.25])[0] - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn generations_speciate_and_offspring_fill_the_population() {
+        let config = NeuroEvolutionConfig {
+            population_size: 12,
+            seed: 5,
+            ..NeuroEvolutionConfig::default()
+        };
+        let mut population = NeatPopulation::random(3, 2, &config);
+        for _ in 0..10 {
+            for index in 0..population.genomes.len() {
+                let fitness = population.genomes[index].connections[0].weight;
+                population.record_fitness(index, fitness);
+            }
+            population.evolve(&config);
+            assert_eq!(population.genomes.len(), 12);
+            assert!(!population.species.is_empty());
+        }
+        assert_eq!(population.generation, 10);
+        assert_eq!(allocate(&[1.0, 3.0], 5), vec![1, 4]);
+        assert_eq!(allocate(&[0.0, 0.0, 0.0], 4), vec![2, 1, 1]);
+    }
+}
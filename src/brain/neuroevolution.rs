@@ -0,0 +1,1022 @@
+//! Neuroevolution over [`PolicyMlp`] genomes.
+//!
+//! A [`Population`] holds flat weight vectors for one fixed architecture
+//! (observation → [`NeuroEvolutionConfig::hidden_layers`] → steering and
+//! throttle). Under [`AgentMode::Evolve`] each episode is driven by one
+//! genome; its [`EpisodeEndedEvent`] scores that genome and moves on to the
+//! next. Once every genome has a fitness the generation ends: the elites are
+//! kept unchanged and the rest of the population is bred by tournament
+//! selection, uniform crossover, and per-gene Gaussian mutation. Every draw
+//! comes from the population's own [`SimRng`], which is saved with it, so a
+//! resumed run continues exactly where the saved one stopped.
+//!
+//! After each generation the population and the best genome found so far are
+//! written to [`NeuroEvolutionConfig::save_dir`] as `population.json` and
+//! `best_genome.json` (a [`PolicyMlp`] weights file the `mlp` controller can
+//! load).
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::ecs::message::MessageReader;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{
+    ActionState, CarAction, action_smoothing_system, keyboard_action_input_system,
+};
+use crate::agent::controller::{Controller, ControllerContext};
+use crate::agent::exploration::exploration_noise_system;
+use crate::agent::observation::{ObservationConfig, ObservationVector};
+use crate::brain::common::policy_mlp::{PolicyMlp, PolicyMlpError};
+use crate::brain::neat::{NeatConfig, NeatNetwork, NeatPopulation, NetworkStructure};
+use crate::brain::types::AgentMode;
+use crate::config::AppConfig;
+use crate::eval::env::HeadlessEnv;
+use crate::game::episode::{EpisodeEndedEvent, EpisodeState, EpisodeSummary, episode_loop_system};
+use crate::maps::monaco::build_monaco_track;
+use crate::sim::rng::SimRng;
+
+/// Population file inside [`NeuroEvolutionConfig::save_dir`].
+pub const POPULATION_FILE: &str = "population.json";
+/// Best-genome weights file inside [`NeuroEvolutionConfig::save_dir`].
+pub const BEST_GENOME_FILE: &str = "best_genome.json";
+/// NEAT population file inside [`NeuroEvolutionConfig::save_dir`].
+pub const NEAT_POPULATION_FILE: &str = "neat_population.json";
+/// Best NEAT genome (a serialized [`crate::brain::neat::NeatGenome`]) inside
+/// [`NeuroEvolutionConfig::save_dir`].
+pub const BEST_NEAT_GENOME_FILE: &str = "best_neat_genome.json";
+
+/// Genome representation the scheduler evolves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenomeKind {
+    /// Fixed-topology [`PolicyMlp`] weight vectors.
+    #[default]
+    Mlp,
+    /// NEAT node/connection genomes that grow their own topology; see
+    /// [`crate::brain::neat`].
+    Neat,
+}
+
+/// Episode statistic a genome is scored by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FitnessSource {
+    /// Episode-best lap fraction.
+    #[default]
+    BestProgress,
+    /// Undiscounted episode return.
+    Return,
+}
+
+impl FitnessSource {
+    pub fn score(self, summary: &EpisodeSummary) -> f32 {
+        match self {
+            Self::BestProgress => summary.best_progress,
+            Self::Return => summary.episode_return,
+        }
+    }
+}
+
+/// Neuroevolution hyperparameters (config `neuroevolution`).
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NeuroEvolutionConfig {
+    pub genome: GenomeKind,
+    /// Genomes per generation.
+    pub population_size: usize,
+    /// Hidden layer widths between the observation and the two outputs
+    /// (MLP genomes only).
+    pub hidden_layers: Vec<usize>,
+    pub fitness: FitnessSource,
+    /// Best genomes copied unchanged into the next generation.
+    pub elite_count: usize,
+    /// Genomes drawn per tournament when picking a parent.
+    pub tournament_size: usize,
+    /// Chance a child mixes two parents gene by gene instead of copying one.
+    pub crossover_rate: f32,
+    /// Per-gene chance of a Gaussian perturbation.
+    pub mutation_rate: f32,
+    /// Standard deviation of the perturbation.
+    pub mutation_std: f32,
+    /// Standard deviation of the initial random weights.
+    pub init_std: f32,
+    /// Seed of the population's random stream.
+    pub seed: u64,
+    /// Directory the population and best genome are saved to after every
+    /// generation; `None` keeps them in memory only.
+    pub save_dir: Option<String>,
+    /// Continue from the saved population in `save_dir` when it exists.
+    pub resume: bool,
+    /// Structural mutation and speciation settings for NEAT genomes.
+    pub neat: NeatConfig,
+}
+
+impl Default for NeuroEvolutionConfig {
+    fn default() -> Self {
+        Self {
+            genome: GenomeKind::Mlp,
+            population_size: 24,
+            hidden_layers: vec![8],
+            fitness: FitnessSource::BestProgress,
+            elite_count: 2,
+            tournament_size: 3,
+            crossover_rate: 0.5,
+            mutation_rate: 0.1,
+            mutation_std: 0.2,
+            init_std: 0.5,
+            seed: 0,
+            save_dir: Some("runs/neuroevolution".to_string()),
+            resume: true,
+            neat: NeatConfig::default(),
+        }
+    }
+}
+
+impl NeuroEvolutionConfig {
+    /// Full layer sizes for an observation of `observation_dim` features.
+    pub fn layer_sizes(&self, observation_dim: usize) -> Vec<usize> {
+        let mut sizes = Vec::with_capacity(self.hidden_layers.len() + 2);
+        sizes.push(observation_dim);
+        sizes.extend(self.hidden_layers.iter().copied());
+        sizes.push(2);
+        sizes
+    }
+}
+
+#[derive(Debug)]
+pub enum NeuroEvolutionError {
+    Io(std::io::Error),
+    /// The population file is not valid JSON or is inconsistent.
+    Format(String),
+    /// The saved population was built for another architecture.
+    LayerMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+    Network(PolicyMlpError),
+}
+
+impl fmt::Display for NeuroEvolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::Format(err) => write!(f, "invalid population file: {err}"),
+            Self::LayerMismatch { expected, found } => write!(
+                f,
+                "population has layer sizes {found:?} but the policy needs {expected:?}"
+            ),
+            Self::Network(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for NeuroEvolutionError {}
+
+/// Fitness summary of one finished generation.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub generation: u32,
+    pub best: f32,
+    pub mean: f32,
+    pub worst: f32,
+}
+
+/// Best genome seen across all generations.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Champion {
+    pub generation: u32,
+    pub fitness: f32,
+    pub params: Vec<f32>,
+}
+
+/// One generation of genomes and the state needed to breed the next.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Population {
+    pub layer_sizes: Vec<usize>,
+    pub generation: u32,
+    pub genomes: Vec<Vec<f32>>,
+    /// Fitness of each genome this generation; `None` until evaluated.
+    pub fitness: Vec<Option<f32>>,
+    pub best: Option<Champion>,
+    rng: SimRng,
+}
+
+impl Population {
+    /// Generation 0 with every parameter drawn from `N(0, init_std)`.
+    pub fn random(layer_sizes: Vec<usize>, config: &NeuroEvolutionConfig) -> Self {
+        let mut rng = SimRng::new(config.seed);
+        let param_count = PolicyMlp::param_count(&layer_sizes);
+        let genomes = (0..config.population_size.max(1))
+            .map(|_| {
+                (0..param_count)
+                    .map(|_| rng.normal(0.0, config.init_std))
+                    .collect()
+            })
+            .collect::<Vec<Vec<f32>>>();
+        Self {
+            layer_sizes,
+            generation: 0,
+            fitness: vec![None; genomes.len()],
+            genomes,
+            best: None,
+            rng,
+        }
+    }
+
+    /// First genome still waiting for a fitness, if any.
+    pub fn next_unevaluated(&self) -> Option<usize> {
+        self.fitness.iter().position(Option::is_none)
+    }
+
+    /// Scores genome `index` and updates the champion.
+    pub fn record_fitness(&mut self, index: usize, fitness: f32) {
+        self.fitness[index] = Some(fitness);
+        if self.best.as_ref().is_none_or(|best| fitness > best.fitness) {
+            self.best = Some(Champion {
+                generation: self.generation,
+                fitness,
+                params: self.genomes[index].clone(),
+            });
+        }
+    }
+
+    /// Replaces the fully evaluated generation with its offspring and returns
+    /// the finished generation's stats. Unevaluated genomes count as the
+    /// worst possible fitness.
+    pub fn evolve(&mut self, config: &NeuroEvolutionConfig) -> GenerationStats {
+        let fitness: Vec<f32> = self
+            .fitness
+            .iter()
+            .map(|fitness| fitness.unwrap_or(f32::NEG_INFINITY))
+            .collect();
+        let stats = GenerationStats {
+            generation: self.generation,
+            best: fitness.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            mean: fitness.iter().sum::<f32>() / fitness.len() as f32,
+            worst: fitness.iter().copied().fold(f32::INFINITY, f32::min),
+        };
+
+        let mut ranked: Vec<usize> = (0..self.genomes.len()).collect();
+        ranked.sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+
+        let size = self.genomes.len();
+        let mut next: Vec<Vec<f32>> = ranked
+            .iter()
+            .take(config.elite_count.min(size))
+            .map(|&index| self.genomes[index].clone())
+            .collect();
+        while next.len() < size {
+            let first = self.tournament(&fitness, config.tournament_size);
+            let mut child = self.genomes[first].clone();
+            if self.rng.next_f32() < config.crossover_rate {
+                let second = self.tournament(&fitness, config.tournament_size);
+                for (gene, other) in child.iter_mut().zip(&self.genomes[second]) {
+                    if self.rng.next_f32() < 0.5 {
+                        *gene = *other;
+                    }
+                }
+            }
+            for gene in &mut child {
+                if self.rng.next_f32() < config.mutation_rate {
+                    *gene += self.rng.normal(0.0, config.mutation_std);
+                }
+            }
+            next.push(child);
+        }
+
+        self.genomes = next;
+        self.fitness = vec![None; size];
+        self.generation += 1;
+        stats
+    }
+
+    /// Fittest of `size` genomes drawn uniformly with replacement.
+    fn tournament(&mut self, fitness: &[f32], size: usize) -> usize {
+        let mut winner = self.draw_index();
+        for _ in 1..size.max(1) {
+            let challenger = self.draw_index();
+            if fitness[challenger] > fitness[winner] {
+                winner = challenger;
+            }
+        }
+        winner
+    }
+
+    fn draw_index(&mut self) -> usize {
+        (self.rng.next_u64() % self.genomes.len() as u64) as usize
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), NeuroEvolutionError> {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|err| NeuroEvolutionError::Format(err.to_string()))?;
+        fs::write(path, json).map_err(NeuroEvolutionError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, NeuroEvolutionError> {
+        let bytes = fs::read(path).map_err(NeuroEvolutionError::Io)?;
+        let population: Self = serde_json::from_slice(&bytes)
+            .map_err(|err| NeuroEvolutionError::Format(err.to_string()))?;
+        let param_count = PolicyMlp::param_count(&population.layer_sizes);
+        if population.genomes.is_empty()
+            || population.fitness.len() != population.genomes.len()
+            || population
+                .genomes
+                .iter()
+                .any(|genome| genome.len() != param_count)
+        {
+            return Err(NeuroEvolutionError::Format(format!(
+                "expected non-empty genomes of {param_count} parameters with one fitness each"
+            )));
+        }
+        Ok(population)
+    }
+}
+
+/// The population being evolved and the network of its current genome.
+enum Pool {
+    Mlp {
+        population: Population,
+        net: PolicyMlp,
+    },
+    Neat {
+        population: Box<NeatPopulation>,
+        net: NeatNetwork,
+    },
+}
+
+/// The evolving population and the network driving the current episode.
+#[derive(Resource)]
+pub struct NeuroEvolution {
+    config: NeuroEvolutionConfig,
+    pool: Pool,
+    current: usize,
+    last_stats: Option<GenerationStats>,
+}
+
+impl FromWorld for NeuroEvolution {
+    fn from_world(world: &mut World) -> Self {
+        let config = world
+            .get_resource::<NeuroEvolutionConfig>()
+            .cloned()
+            .unwrap_or_default();
+        let observation_dim = world
+            .get_resource::<ObservationConfig>()
+            .cloned()
+            .unwrap_or_default()
+            .observation_dim();
+        Self::resume_or_new(config, observation_dim)
+    }
+}
+
+impl NeuroEvolution {
+    /// A fresh random population of `config.genome` genomes for
+    /// `observation_dim` features.
+    pub fn new(config: NeuroEvolutionConfig, observation_dim: usize) -> Self {
+        match config.genome {
+            GenomeKind::Mlp => {
+                let population = Population::random(config.layer_sizes(observation_dim), &config);
+                Self::from_population(config, population)
+                    .expect("random population fits its layers")
+            }
+            GenomeKind::Neat => {
+                let population = NeatPopulation::random(observation_dim, 2, &config);
+                Self::from_neat_population(config, population)
+            }
+        }
+    }
+
+    /// Continues from a saved MLP population.
+    pub fn from_population(
+        config: NeuroEvolutionConfig,
+        population: Population,
+    ) -> Result<Self, NeuroEvolutionError> {
+        let current = population.next_unevaluated().unwrap_or(0);
+        let net = PolicyMlp::new(
+            population.layer_sizes.clone(),
+            population.genomes[current].clone(),
+        )
+        .map_err(NeuroEvolutionError::Network)?;
+        Ok(Self {
+            config,
+            pool: Pool::Mlp { population, net },
+            current,
+            last_stats: None,
+        })
+    }
+
+    /// Continues from a saved NEAT population.
+    pub fn from_neat_population(config: NeuroEvolutionConfig, population: NeatPopulation) -> Self {
+        let current = population.next_unevaluated().unwrap_or(0);
+        let net = NeatNetwork::new(&population.genomes[current]);
+        Self {
+            config,
+            pool: Pool::Neat {
+                population: Box::new(population),
+                net,
+            },
+            current,
+            last_stats: None,
+        }
+    }
+
+    /// Loads the saved population of `config.genome` from the save directory
+    /// when `resume` is set and it fits `observation_dim`; otherwise starts
+    /// fresh.
+    pub fn resume_or_new(config: NeuroEvolutionConfig, observation_dim: usize) -> Self {
+        let file = match config.genome {
+            GenomeKind::Mlp => POPULATION_FILE,
+            GenomeKind::Neat => NEAT_POPULATION_FILE,
+        };
+        let Some(path) = config
+            .save_dir
+            .as_deref()
+            .filter(|_| config.resume)
+            .map(|dir| Path::new(dir).join(file))
+            .filter(|path| path.exists())
+        else {
+            return Self::new(config, observation_dim);
+        };
+        match Self::load_saved(&config, observation_dim, &path) {
+            Ok(evolution) => {
+                info!(
+                    "Resumed neuroevolution at generation {} from {}.",
+                    evolution.generation(),
+                    path.display()
+                );
+                evolution
+            }
+            Err(err) => {
+                warn!("Starting a new population: {}: {err}", path.display());
+                Self::new(config, observation_dim)
+            }
+        }
+    }
+
+    fn load_saved(
+        config: &NeuroEvolutionConfig,
+        observation_dim: usize,
+        path: &Path,
+    ) -> Result<Self, NeuroEvolutionError> {
+        match config.genome {
+            GenomeKind::Mlp => {
+                let population = Population::load(path)?;
+                let expected = config.layer_sizes(observation_dim);
+                if population.layer_sizes != expected {
+                    return Err(NeuroEvolutionError::LayerMismatch {
+                        expected,
+                        found: population.layer_sizes,
+                    });
+                }
+                Self::from_population(config.clone(), population)
+            }
+            GenomeKind::Neat => {
+                let population = NeatPopulation::load(path)?;
+                let expected = vec![observation_dim, 2];
+                let found = vec![population.inputs, population.outputs];
+                if found != expected {
+                    return Err(NeuroEvolutionError::LayerMismatch { expected, found });
+                }
+                Ok(Self::from_neat_population(config.clone(), population))
+            }
+        }
+    }
+
+    pub fn config(&self) -> &NeuroEvolutionConfig {
+        &self.config
+    }
+
+    /// The MLP population, when evolving fixed-topology genomes.
+    pub fn population(&self) -> Option<&Population> {
+        match &self.pool {
+            Pool::Mlp { population, .. } => Some(population),
+            Pool::Neat { .. } => None,
+        }
+    }
+
+    /// The NEAT population, when evolving topologies.
+    pub fn neat_population(&self) -> Option<&NeatPopulation> {
+        match &self.pool {
+            Pool::Mlp { .. } => None,
+            Pool::Neat { population, .. } => Some(population),
+        }
+    }
+
+    /// Generation currently being evaluated.
+    pub fn generation(&self) -> u32 {
+        match &self.pool {
+            Pool::Mlp { population, .. } => population.generation,
+            Pool::Neat { population, .. } => population.generation,
+        }
+    }
+
+    pub fn population_size(&self) -> usize {
+        match &self.pool {
+            Pool::Mlp { population, .. } => population.genomes.len(),
+            Pool::Neat { population, .. } => population.genomes.len(),
+        }
+    }
+
+    /// Best fitness seen across all generations.
+    pub fn best_fitness(&self) -> Option<f32> {
+        match &self.pool {
+            Pool::Mlp { population, .. } => population.best.as_ref().map(|best| best.fitness),
+            Pool::Neat { population, .. } => population.best.as_ref().map(|best| best.fitness),
+        }
+    }
+
+    /// Structure of the current NEAT genome and the species count of the
+    /// last generation; `None` for MLP genomes.
+    pub fn neat_structure(&self) -> Option<(NetworkStructure, usize)> {
+        self.neat_population().map(|population| {
+            (
+                population.genomes[self.current].structure(),
+                population.species.len(),
+            )
+        })
+    }
+
+    /// Index of the genome driving the current episode.
+    pub fn current_genome(&self) -> usize {
+        self.current
+    }
+
+    /// Stats of the last finished generation.
+    pub fn last_stats(&self) -> Option<GenerationStats> {
+        self.last_stats
+    }
+
+    /// Runs the current genome on `observation`.
+    pub fn act_on(&mut self, observation: &[f32]) -> CarAction {
+        let output = match &mut self.pool {
+            Pool::Mlp { net, .. } => net.forward(observation),
+            Pool::Neat { net, .. } => net.forward(observation),
+        };
+        CarAction {
+            steering: output[0],
+            throttle: 0.5 * (output[1] + 1.0),
+        }
+        .clamped()
+    }
+
+    /// Scores the current genome with a finished episode and loads the next
+    /// one, breeding a new generation first when this was the last. Returns
+    /// the stats of a generation that just ended.
+    pub fn record_episode(&mut self, summary: &EpisodeSummary) -> Option<GenerationStats> {
+        let fitness = self.config.fitness.score(summary);
+        let (stats, current) = match &mut self.pool {
+            Pool::Mlp { population, net } => {
+                population.record_fitness(self.current, fitness);
+                let stats = population
+                    .next_unevaluated()
+                    .is_none()
+                    .then(|| population.evolve(&self.config));
+                let current = population.next_unevaluated().unwrap_or(0);
+                net.params_mut()
+                    .copy_from_slice(&population.genomes[current]);
+                (stats, current)
+            }
+            Pool::Neat { population, net } => {
+                population.record_fitness(self.current, fitness);
+                let stats = population
+                    .next_unevaluated()
+                    .is_none()
+                    .then(|| population.evolve(&self.config));
+                let current = population.next_unevaluated().unwrap_or(0);
+                *net = NeatNetwork::new(&population.genomes[current]);
+                (stats, current)
+            }
+        };
+        self.current = current;
+        if stats.is_some() {
+            self.last_stats = stats;
+        }
+        stats
+    }
+
+    /// Writes the population and the best genome to `dir`.
+    pub fn save(&self, dir: &Path) -> Result<(), NeuroEvolutionError> {
+        match &self.pool {
+            Pool::Mlp { population, .. } => {
+                population.save(&dir.join(POPULATION_FILE))?;
+                if let Some(best) = &population.best {
+                    PolicyMlp::new(population.layer_sizes.clone(), best.params.clone())
+                        .and_then(|net| net.save(&dir.join(BEST_GENOME_FILE)))
+                        .map_err(NeuroEvolutionError::Network)?;
+                }
+            }
+            Pool::Neat { population, .. } => {
+                population.save(&dir.join(NEAT_POPULATION_FILE))?;
+                if let Some(best) = &population.best {
+                    let json = serde_json::to_vec_pretty(&best.genome)
+                        .map_err(|err| NeuroEvolutionError::Format(err.to_string()))?;
+                    fs::write(dir.join(BEST_NEAT_GENOME_FILE), json)
+                        .map_err(NeuroEvolutionError::Io)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves to the configured directory, if any, logging failures.
+    fn save_to_config_dir(&self) {
+        let Some(dir) = self.config.save_dir.as_deref().map(PathBuf::from) else {
+            return;
+        };
+        if let Err(err) = self.save(&dir) {
+            warn!("Failed to save population to {}: {err}", dir.display());
+        }
+    }
+}
+
+impl Controller for NeuroEvolution {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+        self.act_on(&ctx.observation.values)
+    }
+}
+
+fn log_generation(stats: &GenerationStats, best_ever: Option<f32>) {
+    info!(
+        "Generation {}: best {:.3}  mean {:.3}  worst {:.3}  (best ever {:.3})",
+        stats.generation,
+        stats.best,
+        stats.mean,
+        stats.worst,
+        best_ever.unwrap_or(stats.best),
+    );
+}
+
+pub struct NeuroEvolutionPlugin;
+
+impl Plugin for NeuroEvolutionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NeuroEvolutionConfig>()
+            .init_resource::<NeuroEvolution>()
+            .add_systems(
+                FixedUpdate,
+                neuroevolution_act_system
+                    .after(keyboard_action_input_system)
+                    .before(exploration_noise_system)
+                    .before(action_smoothing_system)
+                    .in_set(crate::sim::sets::SimSet::Input),
+            )
+            .add_systems(
+                FixedUpdate,
+                neuroevolution_episode_end_system
+                    .after(episode_loop_system)
+                    .in_set(crate::sim::sets::SimSet::Measurement),
+            );
+    }
+}
+
+/// Drives the car with the current genome under [`AgentMode::Evolve`].
+pub fn neuroevolution_act_system(
+    mode: Res<AgentMode>,
+    obs_query: Query<&ObservationVector>,
+    mut action_state: ResMut<ActionState>,
+    mut evolution: ResMut<NeuroEvolution>,
+) {
+    if *mode != AgentMode::Evolve {
+        return;
+    }
+    if let Ok(obs) = obs_query.single() {
+        action_state.desired = evolution.act_on(&obs.values);
+    }
+}
+
+/// Scores the current genome when its episode ends. Only episodes driven by
+/// [`AgentMode::Evolve`] from their first tick count; `clean_from` is the
+/// first such episode since the mode was last entered.
+pub fn neuroevolution_episode_end_system(
+    mode: Res<AgentMode>,
+    episode_state: Res<EpisodeState>,
+    mut ended_events: MessageReader<EpisodeEndedEvent>,
+    mut evolution: ResMut<NeuroEvolution>,
+    mut clean_from: Local<Option<u32>>,
+) {
+    if *mode != AgentMode::Evolve {
+        *clean_from = None;
+        ended_events.clear();
+        return;
+    }
+    let Some(first_clean) = *clean_from else {
+        let this_tick = ended_events.read().last().map(|event| event.episode);
+        *clean_from = Some(match this_tick {
+            Some(episode) => episode + 1,
+            None if episode_state.ticks_in_episode <= 1 => episode_state.current_episode,
+            None => episode_state.current_episode + 1,
+        });
+        return;
+    };
+
+    for event in ended_events.read() {
+        if event.episode < first_clean {
+            continue;
+        }
+        if let Some(stats) = evolution.record_episode(&event.summary) {
+            log_generation(&stats, evolution.best_fitness());
+            evolution.save_to_config_dir();
+        }
+    }
+}
+
+/// Runs `generations` full generations in `env`, one episode per genome, and
+/// returns their stats. Saves after each generation like the plugin does.
+pub fn run_neuroevolution_headless(
+    env: &mut HeadlessEnv,
+    evolution: &mut NeuroEvolution,
+    generations: u32,
+) -> Vec<GenerationStats> {
+    let mut history = Vec::with_capacity(generations as usize);
+    while history.len() < generations as usize {
+        let observation = env.observation();
+        let action = evolution.act_on(&observation.values);
+        let Some(outcome) = env.step(action) else {
+            continue;
+        };
+        if let Some(stats) = evolution.record_episode(&outcome.summary()) {
+            log_generation(&stats, evolution.best_fitness());
+            evolution.save_to_config_dir();
+            history.push(stats);
+        }
+    }
+    history
+}
+
+/// Generations `--evolve` runs when no count is given.
+pub const DEFAULT_EVOLVE_GENERATIONS: u32 = 50;
+
+/// Runs `--evolve`: headless neuroevolution on the built-in Sepang track,
+/// resuming from the configured save directory, printing each generation's
+/// stats.
+pub fn run_evolve_command(config: &AppConfig, master_seed: u64, generations: u32) {
+    let mut env = HeadlessEnv::new(build_monaco_track(), config, master_seed);
+    let mut evolution = NeuroEvolution::resume_or_new(
+        config.neuroevolution.clone(),
+        config.observation.observation_dim(),
+    );
+    let start = evolution.generation();
+    let history = run_neuroevolution_headless(&mut env, &mut evolution, generations);
+    for stats in &history {
+        println!(
+            "generation {:>4}: best {:.3}  mean {:.3}  worst {:.3}",
+            stats.generation, stats.best, stats.mean, stats.worst
+        );
+    }
+    if let Some(best) = evolution.best_fitness() {
+        println!(
+            "best fitness {best:.3} after generations {start}..{}",
+            evolution.generation()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FitnessSource, GenomeKind, NeuroEvolution, NeuroEvolutionConfig, Population,
+        run_neuroevolution_headless,
+    };
+    use crate::brain::neat::NeatConfig;
+    use crate::config::AppConfig;
+    use crate::eval::env::HeadlessEnv;
+    use crate::game::episode::{EpisodeConfig, EpisodeEndReason, EpisodeSummary};
+    use crate::maps::centerline::{GridDir, TrackCenterline};
+    use crate::maps::grid::TrackGrid;
+    use crate::maps::track::Track;
+    use bevy::prelude::Vec2;
+
+    fn config() -> NeuroEvolutionConfig {
+        NeuroEvolutionConfig {
+            save_dir: None,
+            resume: false,
+            seed: 7,
+            ..NeuroEvolutionConfig::default()
+        }
+    }
+
+    fn summary(best_progress: f32) -> EpisodeSummary {
+        EpisodeSummary {
+            end_reason: EpisodeEndReason::Timeout,
+            ticks: 10,
+            episode_return: -best_progress,
+            best_progress,
+        }
+    }
+
+    #[test]
+    fn evolution_improves_a_simple_objective_deterministically() {
+        // Fitness: closeness of every parameter to 1.
+        let run = || {
+            let config = config();
+            let mut population = Population::random(vec![3, 2, 2], &config);
+            let mut history = Vec::new();
+            for _ in 0..40 {
+                for index in 0..population.genomes.len() {
+                    let error: f32 = population.genomes[index]
+                        .iter()
+                        .map(|gene| (gene - 1.0).powi(2))
+                        .sum();
+                    population.record_fitness(index, -error);
+                }
+                history.push(population.evolve(&config));
+            }
+            (history, population)
+        };
+        let (history, population) = run();
+
+        assert_eq!(population.generation, 40);
+        assert!(history[39].best > history[0].best);
+        assert!(history[39].mean > history[0].mean + 1.0);
+        assert!(history.iter().all(|stats| stats.worst <= stats.mean));
+        // Elitism never loses the best genome.
+        assert!(history.windows(2).all(|pair| pair[1].best >= pair[0].best));
+        assert_eq!(run().0, history);
+    }
+
+    #[test]
+    fn episodes_score_genomes_in_turn_and_resume_continues_the_run() {
+        let config = NeuroEvolutionConfig {
+            population_size: 4,
+            fitness: FitnessSource::BestProgress,
+            ..config()
+        };
+        let mut evolution = NeuroEvolution::new(config.clone(), 5);
+        assert_eq!(
+            evolution.population().expect("mlp").layer_sizes,
+            vec![5, 8, 2]
+        );
+
+        for (index, progress) in [0.1, 0.4, 0.2].into_iter().enumerate() {
+            assert_eq!(evolution.current_genome(), index);
+            assert_eq!(evolution.record_episode(&summary(progress)), None);
+        }
+        let stats = evolution
+            .record_episode(&summary(0.3))
+            .expect("fourth episode ends the generation");
+        assert_eq!((stats.generation, stats.best, stats.worst), (0, 0.4, 0.1));
+        assert!((stats.mean - 0.25).abs() < 1e-6);
+        assert_eq!(evolution.generation(), 1);
+        assert_eq!(evolution.current_genome(), 0);
+        assert_eq!(evolution.best_fitness(), Some(0.4));
+
+        // Halfway through generation 1, save and resume from disk.
+        evolution.record_episode(&summary(0.5));
+        let dir =
+            std::env::temp_dir().join(format!("neurodrive_neuroevolution_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        evolution.save(&dir).expect("saves");
+        assert!(dir.join(super::BEST_GENOME_FILE).exists());
+
+        let resumed = NeuroEvolution::resume_or_new(
+            NeuroEvolutionConfig {
+                save_dir: Some(dir.to_string_lossy().into_owned()),
+                resume: true,
+                ..config.clone()
+            },
+            5,
+        );
+        assert_eq!(resumed.population(), evolution.population());
+        assert_eq!(resumed.current_genome(), 1);
+
+        // A different observation size cannot reuse the population.
+        let fresh = NeuroEvolution::resume_or_new(
+            NeuroEvolutionConfig {
+                save_dir: Some(dir.to_string_lossy().into_owned()),
+                resume: true,
+                ..config
+            },
+            6,
+        );
+        assert_eq!(fresh.generation(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Headless env on the oval with episodes of `timeout_s`.
+    fn oval_env(timeout_s: f32) -> (HeadlessEnv, usize) {
+        use crate::maps::parts::TilePart::*;
+        let grid = TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, StraightH, StraightH, CornerNE],
+                vec![CornerSW, StraightH, StraightH, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 100.0),
+        );
+        let spawn = grid.find_spawn_cell().expect("spawn tile");
+        let centerline =
+            TrackCenterline::build_closed_loop(&grid, spawn, GridDir::East).expect("closed loop");
+        let (spawn_position, spawn_rotation) = grid.find_spawn().expect("spawn tile");
+        let track = Track {
+            grid,
+            spawn_position,
+            spawn_rotation,
+            centerline: Some(centerline),
+        };
+        let config = AppConfig {
+            episode: EpisodeConfig {
+                timeout_s,
+                ..EpisodeConfig::default()
+            },
+            ..AppConfig::default()
+        };
+        let observation_dim = config.observation.observation_dim();
+        (HeadlessEnv::new(track, &config, 0), observation_dim)
+    }
+
+    #[test]
+    fn neat_genomes_train_on_the_oval_and_resume() {
+        let config = NeuroEvolutionConfig {
+            genome: GenomeKind::Neat,
+            population_size: 6,
+            neat: NeatConfig {
+                add_node_rate: 0.5,
+                add_connection_rate: 0.5,
+                ..NeatConfig::default()
+            },
+            ..self::config()
+        };
+        let (mut env, observation_dim) = oval_env(2.0);
+        let mut evolution = NeuroEvolution::new(config.clone(), observation_dim);
+        assert!(evolution.population().is_none());
+
+        let history = run_neuroevolution_headless(&mut env, &mut evolution, 3);
+        assert_eq!(history.len(), 3);
+        assert!(history.iter().all(|stats| stats.best.is_finite()));
+        let population = evolution.neat_population().expect("neat");
+        assert_eq!((population.generation, population.genomes.len()), (3, 6));
+        let (structure, species) = evolution.neat_structure().expect("neat structure");
+        assert!(structure.enabled_connections > 0 && species > 0);
+        assert!(
+            population
+                .genomes
+                .iter()
+                .any(|genome| genome.structure().hidden_nodes > 0),
+            "structural mutations grew some topology"
+        );
+
+        let dir = std::env::temp_dir().join(format!("neurodrive_neat_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        evolution.save(&dir).expect("saves");
+        assert!(dir.join(super::BEST_NEAT_GENOME_FILE).exists());
+        let resumed = NeuroEvolution::resume_or_new(
+            NeuroEvolutionConfig {
+                save_dir: Some(dir.to_string_lossy().into_owned()),
+                resume: true,
+                ..config
+            },
+            observation_dim,
+        );
+        assert_eq!(resumed.neat_population(), evolution.neat_population());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Reduced form of the acceptance run below: a small population on
+    /// short episodes, checking only that selection beats the first
+    /// generation's average.
+    #[test]
+    fn a_short_run_improves_on_the_first_generation_on_the_oval() {
+        let (mut env, observation_dim) = oval_env(3.0);
+        let config = NeuroEvolutionConfig {
+            population_size: 8,
+            ..self::config()
+        };
+        let mut evolution = NeuroEvolution::new(config, observation_dim);
+
+        let history = run_neuroevolution_headless(&mut env, &mut evolution, 8);
+        assert_eq!(history.len(), 8);
+        assert!(history.iter().all(|stats| stats.best.is_finite()));
+        let late_best = history[5..]
+            .iter()
+            .map(|stats| stats.best)
+            .fold(f32::NEG_INFINITY, f32::max);
+        assert!(
+            late_best > history[0].mean,
+            "first generation mean {:.3}, late best {late_best:.3}",
+            history[0].mean
+        );
+    }
+
+    /// Acceptance run: 50 generations on the oval with default
+    /// hyperparameters. Slow; run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn default_hyperparameters_improve_best_progress_on_the_oval() {
+        let (mut env, observation_dim) = oval_env(10.0);
+        let mut evolution = NeuroEvolution::new(self::config(), observation_dim);
+
+        let history = run_neuroevolution_headless(&mut env, &mut evolution, 50);
+        assert_eq!(history.len(), 50);
+        let mean_of = |stats: &[super::GenerationStats]| {
+            stats.iter().map(|stats| stats.mean).sum::<f32>() / stats.len() as f32
+        };
+        let (early, late) = (mean_of(&history[..5]), mean_of(&history[45..]));
+        assert!(
+            late > early + 0.05,
+            "mean best progress {early:.3} -> {late:.3}"
+        );
+    }
+}
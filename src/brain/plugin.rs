@@ -10,30 +10,28 @@ impl Plugin for BrainPlugin {
         app.init_resource::<AgentMode>();
 
         // Add specific brain plugins
-        app.add_plugins(crate::brain::a2c::A2cPlugin);
+        app.add_plugins(crate::brain::a2c::A2cPlugin)
+            .add_plugins(crate::brain::neuroevolution::NeuroEvolutionPlugin);
 
         app.add_systems(Update, toggle_agent_mode_system);
     }
 }
 
+/// Cycles the active controller on `F4`. Leaving or entering the learned
+/// controller discards its partial rollout.
 fn toggle_agent_mode_system(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut mode: ResMut<AgentMode>,
     a2c_brain: Option<ResMut<A2cBrain>>,
 ) {
     if keyboard.just_pressed(KeyCode::F4) {
-        *mode = match *mode {
-            AgentMode::Keyboard => {
-                info!("Agent Mode: AI");
-                AgentMode::Ai
-            }
-            AgentMode::Ai => {
-                info!("Agent Mode: Keyboard");
-                AgentMode::Keyboard
-            }
-        };
+        let previous = *mode;
+        *mode = previous.next();
+        info!("Controller: {}", mode.name());
 
-        if let Some(mut brain) = a2c_brain {
+        if let Some(mut brain) =
+            a2c_brain.filter(|_| AgentMode::Learned == previous || AgentMode::Learned == *mode)
+        {
             brain.buffer.clear();
             brain.step_counter = 0;
             info!("A2C rollout buffer reset after mode switch.");
@@ -1,18 +1,85 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::agent::action::CarAction;
 use crate::agent::observation::ObservationVector;
+use crate::sim::rng::EpisodeRng;
 
-/// The active mode of the agent.
-#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+/// The controller that drives the car.
+///
+/// Exactly one controller writes `ActionState.desired` per fixed tick:
+/// keyboard or gamepad input, the learned policy, or the registered
+/// [`crate::agent::controller::Controller`] for the scripted, PID, replay, or
+/// external slot. Selected by the config's `controller` field or
+/// `--controller <name>`, and cycled at runtime with `F4`.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AgentMode {
     Keyboard,
-    Ai,
+    /// First connected gamepad, falling back to the keyboard without one.
+    Gamepad,
+    Scripted,
+    /// [`crate::agent::pid::PidController`] centreline follower.
+    Pid,
+    Replay,
+    External,
+    /// [`crate::agent::mlp::MlpController`] running built-in MLP weights.
+    Mlp,
+    /// [`crate::agent::onnx::OnnxController`] running an exported policy.
+    Onnx,
+    /// [`crate::brain::neuroevolution::NeuroEvolution`] evaluating one
+    /// genome per episode.
+    Evolve,
+    #[default]
+    Learned,
 }
 
-impl Default for AgentMode {
-    fn default() -> Self {
-        Self::Ai // Default to AI for Milestone 1
+impl AgentMode {
+    /// Every controller, in `F4` cycling order.
+    pub const ALL: [AgentMode; 10] = [
+        AgentMode::Keyboard,
+        AgentMode::Gamepad,
+        AgentMode::Scripted,
+        AgentMode::Pid,
+        AgentMode::Replay,
+        AgentMode::External,
+        AgentMode::Mlp,
+        AgentMode::Onnx,
+        AgentMode::Evolve,
+        AgentMode::Learned,
+    ];
+
+    /// Lower-case name shown in the HUD and accepted by `--controller`.
+    pub fn name(self) -> &'static str {
+        match self {
+            AgentMode::Keyboard => "keyboard",
+            AgentMode::Gamepad => "gamepad",
+            AgentMode::Scripted => "scripted",
+            AgentMode::Pid => "pid",
+            AgentMode::Replay => "replay",
+            AgentMode::External => "external",
+            AgentMode::Mlp => "mlp",
+            AgentMode::Onnx => "onnx",
+            AgentMode::Evolve => "evolve",
+            AgentMode::Learned => "learned",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Whether a person drives, so manual aids such as the steering assist
+    /// apply.
+    pub fn is_manual(self) -> bool {
+        matches!(self, AgentMode::Keyboard | AgentMode::Gamepad)
+    }
+
+    /// The controller after this one in cycling order.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
     }
 }
 
@@ -20,4 +87,7 @@ impl Default for AgentMode {
 pub trait Brain: Send + Sync {
     /// Given an observation, returns the chosen action and any algorithm-specific state.
     fn act(&mut self, obs: &ObservationVector) -> CarAction;
+
+    /// Called before an episode's first action with its seeded streams.
+    fn begin_episode(&mut self, _episode_rng: &EpisodeRng) {}
 }
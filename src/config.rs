@@ -0,0 +1,239 @@
+//! Single-file application tuning.
+//!
+//! [`AppConfig`] aggregates every tuning resource so a run can be configured
+//! from one RON file instead of editing defaults scattered across modules.
+//! Missing fields (or a missing file) fall back to the code defaults.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionMode, ActionSmoothing, KeyboardConfig};
+use crate::agent::exploration::ExplorationConfig;
+use crate::agent::gamepad::GamepadConfig;
+use crate::agent::mlp::MlpControllerConfig;
+use crate::agent::observation::{ObservationConfig, ObservationConfigError};
+use crate::agent::onnx::OnnxControllerConfig;
+use crate::agent::pid::PidControllerConfig;
+use crate::agent::script::ScriptControllerConfig;
+use crate::agent::scripted::ScriptedControllerConfig;
+use crate::agent::vision::VisionConfig;
+use crate::analytics::trackers::transitions::TransitionRecorderConfig;
+use crate::brain::neuroevolution::NeuroEvolutionConfig;
+use crate::brain::types::AgentMode;
+use crate::eval::remote::ExternalControlConfig;
+use crate::eval::websocket::WebSocketConfig;
+use crate::game::car_visual::CarVisualConfig;
+use crate::game::collision::CollisionConfig;
+use crate::game::curriculum::Curriculum;
+use crate::game::episode::EpisodeConfig;
+use crate::game::instant_replay::InstantReplayConfig;
+use crate::game::physics::{BoostPadConfig, CarDynamicsParams, PhysicsConfig};
+use crate::sim::evaluation::EvaluationMode;
+
+/// Config file read at startup when `--config` is not given.
+pub const DEFAULT_CONFIG_PATH: &str = "neurodrive.ron";
+
+/// All tuning for one run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub episode: EpisodeConfig,
+    pub observation: ObservationConfig,
+    pub action_smoothing: ActionSmoothing,
+    pub action_mode: ActionMode,
+    pub exploration: ExplorationConfig,
+    /// Evaluation run: observation and exploration noise are off.
+    pub evaluation: EvaluationMode,
+    pub car: CarDynamicsParams,
+    pub boost: BoostPadConfig,
+    pub physics: PhysicsConfig,
+    pub collision: CollisionConfig,
+    pub car_visual: CarVisualConfig,
+    pub instant_replay: InstantReplayConfig,
+    pub curriculum: Curriculum,
+    pub recorder: TransitionRecorderConfig,
+    /// Controller driving the car at startup.
+    pub controller: AgentMode,
+    pub scripted: ScriptedControllerConfig,
+    /// Action script played in the `replay` slot.
+    pub script: ScriptControllerConfig,
+    pub pid: PidControllerConfig,
+    pub mlp: MlpControllerConfig,
+    pub onnx: OnnxControllerConfig,
+    pub neuroevolution: NeuroEvolutionConfig,
+    pub keyboard: KeyboardConfig,
+    pub gamepad: GamepadConfig,
+    pub external: ExternalControlConfig,
+    pub websocket: WebSocketConfig,
+    pub vision: VisionConfig,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    Invalid(ObservationConfigError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::Parse(err) => write!(f, "parse error: {err}"),
+            Self::Serialize(err) => write!(f, "serialize error: {err}"),
+            Self::Invalid(err) => write!(f, "invalid observation config: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl AppConfig {
+    /// Parses and validates a config.
+    pub fn from_ron(text: &str) -> Result<Self, ConfigError> {
+        let mut config: Self = ron::from_str(text).map_err(ConfigError::Parse)?;
+        if let Some(preset) = config.observation.ray_preset {
+            config.observation.set_ray_preset(preset);
+        }
+        config
+            .observation
+            .validate()
+            .map_err(ConfigError::Invalid)?;
+        Ok(config)
+    }
+
+    pub fn to_ron(&self) -> Result<String, ConfigError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(ConfigError::Serialize)
+    }
+
+    /// Loads `path`, or returns the defaults when the file does not exist.
+    pub fn load_or_default(path: &Path) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::from_ron(&text),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(ConfigError::Io(err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        fs::write(path, self.to_ron()?).map_err(ConfigError::Io)
+    }
+
+    /// Inserts every sub-config as a resource. Plugins use `init_resource`,
+    /// so these take precedence over the code defaults.
+    pub fn insert_into(&self, app: &mut App) {
+        app.insert_resource(self.episode)
+            .insert_resource(self.observation.clone())
+            .insert_resource(self.action_smoothing)
+            .insert_resource(self.action_mode)
+            .insert_resource(self.exploration)
+            .insert_resource(self.evaluation)
+            .insert_resource(self.car)
+            .insert_resource(self.boost)
+            .insert_resource(self.physics)
+            .insert_resource(self.collision)
+            .insert_resource(self.car_visual)
+            .insert_resource(self.instant_replay)
+            .insert_resource(self.curriculum)
+            .insert_resource(self.recorder.clone())
+            .insert_resource(self.controller)
+            .insert_resource(self.scripted)
+            .insert_resource(self.script.clone())
+            .insert_resource(self.pid)
+            .insert_resource(self.mlp.clone())
+            .insert_resource(self.onnx.clone())
+            .insert_resource(self.neuroevolution.clone())
+            .insert_resource(self.keyboard)
+            .insert_resource(self.gamepad)
+            .insert_resource(self.external.clone())
+            .insert_resource(self.websocket.clone())
+            .insert_resource(self.vision.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppConfig, ConfigError};
+    use crate::agent::observation::{ObservationConfigError, RayPreset};
+
+    #[test]
+    fn default_config_round_trips_exactly() {
+        let config = AppConfig::default();
+        let text = config.to_ron().expect("serializes");
+        let parsed = AppConfig::from_ron(&text).expect("parses");
+
+        assert_eq!(parsed.episode, config.episode);
+        assert_eq!(parsed.observation, config.observation);
+        assert_eq!(parsed.action_smoothing, config.action_smoothing);
+        assert_eq!(parsed.action_mode, config.action_mode);
+        assert_eq!(parsed.exploration, config.exploration);
+        assert_eq!(parsed.evaluation, config.evaluation);
+        assert_eq!(parsed.car, config.car);
+        assert_eq!(parsed.boost, config.boost);
+        assert_eq!(parsed.physics, config.physics);
+        assert_eq!(parsed.collision, config.collision);
+        assert_eq!(parsed.car_visual, config.car_visual);
+        assert_eq!(parsed.instant_replay, config.instant_replay);
+        assert_eq!(parsed.curriculum, config.curriculum);
+        assert_eq!(parsed.recorder, config.recorder);
+        assert_eq!(parsed.controller, config.controller);
+        assert_eq!(parsed.scripted, config.scripted);
+        assert_eq!(parsed.script, config.script);
+        assert_eq!(parsed.pid, config.pid);
+        assert_eq!(parsed.mlp, config.mlp);
+        assert_eq!(parsed.onnx, config.onnx);
+        assert_eq!(parsed.neuroevolution, config.neuroevolution);
+        assert_eq!(parsed.keyboard, config.keyboard);
+        assert_eq!(parsed.gamepad, config.gamepad);
+        assert_eq!(parsed.external, config.external);
+        assert_eq!(parsed.websocket, config.websocket);
+        assert_eq!(parsed.vision, config.vision);
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn partial_file_keeps_defaults_for_missing_fields() {
+        let parsed = AppConfig::from_ron("(episode: (timeout_s: 12.5), car: (thrust: 900.0))")
+            .expect("parses");
+
+        assert_eq!(parsed.episode.timeout_s, 12.5);
+        assert_eq!(parsed.car.thrust, 900.0);
+        assert_eq!(parsed.car.drag, AppConfig::default().car.drag);
+        assert_eq!(parsed.observation, AppConfig::default().observation);
+    }
+
+    #[test]
+    fn ray_preset_replaces_the_ray_angles_on_load() {
+        let narrow = AppConfig::from_ron("(observation: (ray_preset: Some(ForwardNarrow)))")
+            .expect("parses");
+        assert_eq!(
+            narrow.observation.ray_angles,
+            RayPreset::ForwardNarrow.angles()
+        );
+        assert_eq!(narrow.observation.num_rays(), 7);
+    }
+
+    #[test]
+    fn ray_layout_is_validated_on_load() {
+        let seven = AppConfig::from_ron(
+            "(observation: (ray_angles: [-1.2, -0.6, -0.2, 0.0, 0.2, 0.6, 1.2]))",
+        )
+        .expect("parses");
+        assert_eq!(seven.observation.num_rays(), 7);
+
+        let empty = AppConfig::from_ron("(observation: (ray_angles: []))");
+        assert!(matches!(
+            empty,
+            Err(ConfigError::Invalid(ObservationConfigError::NoRays))
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll};
+use bevy::prelude::*;
+
+use crate::agent::vision::VisionCamera;
+use crate::game::car::Car;
+use crate::maps::coords::screen_delta_to_world;
+
+/// World units per second the free camera pans at zoom 1.
+pub const FREE_CAMERA_PAN_SPEED: f32 = 600.0;
+/// Orthographic scale bounds for the free camera.
+pub const FREE_CAMERA_MIN_ZOOM: f32 = 0.1;
+pub const FREE_CAMERA_MAX_ZOOM: f32 = 10.0;
+/// Zoom multiplier per scroll line or `-`/`=` press.
+const ZOOM_STEP: f32 = 1.1;
+
+/// Selects the window's 2D camera, leaving out the offscreen
+/// [`VisionCamera`].
+pub type MainCameraFilter = (With<Camera2d>, Without<VisionCamera>);
+
+/// How the camera is placed each frame. `F6` cycles the modes.
+///
+/// - `Follow`: centres on the focused car when more than one car races,
+///   otherwise keeps the whole-track view.
+/// - `Free`: panned and zoomed by hand, independent of the cars.
+/// - `Overview`: the static whole-track view.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraMode {
+    #[default]
+    Follow,
+    Free,
+    Overview,
+}
+
+impl CameraMode {
+    pub fn next(self) -> Self {
+        match self {
+            CameraMode::Follow => CameraMode::Free,
+            CameraMode::Free => CameraMode::Overview,
+            CameraMode::Overview => CameraMode::Follow,
+        }
+    }
+}
+
+/// Position and orthographic scale of the free camera.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct FreeCamera {
+    pub center: Vec2,
+    pub zoom: f32,
+}
+
+impl Default for FreeCamera {
+    fn default() -> Self {
+        Self {
+            center: Vec2::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
+
+/// World-space pan for one frame.
+///
+/// `axis` is the held pan direction (each component in `[-1, 1]`) and
+/// `drag_pixels` the mouse drag in screen pixels (y down). Both are scaled by
+/// `zoom` so the view moves at the same on-screen speed at every zoom level,
+/// and dragging keeps the grabbed point under the cursor.
+pub fn free_camera_pan(axis: Vec2, drag_pixels: Vec2, zoom: f32, dt: f32) -> Vec2 {
+    axis * FREE_CAMERA_PAN_SPEED * dt * zoom - screen_delta_to_world(drag_pixels, zoom)
+}
+
+/// Cycles [`CameraMode`] on `F6`. Entering free mode starts from the current
+/// view so the camera does not jump.
+pub fn camera_mode_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut mode: ResMut<CameraMode>,
+    mut free: ResMut<FreeCamera>,
+    camera_query: Query<(&Transform, &Projection), MainCameraFilter>,
+) {
+    if !keyboard.just_pressed(KeyCode::F6) {
+        return;
+    }
+    *mode = mode.next();
+    if *mode == CameraMode::Free
+        && let Ok((transform, projection)) = camera_query.single()
+    {
+        free.center = transform.translation.truncate();
+        if let Projection::Orthographic(ortho) = projection {
+            free.zoom = ortho.scale;
+        }
+    }
+    info!("Camera mode: {:?}", *mode);
+}
+
+/// Mouse input the free camera reads. Each resource is absent without the
+/// window's input plugins.
+#[derive(SystemParam)]
+pub struct FreeCameraMouse<'w> {
+    buttons: Option<Res<'w, ButtonInput<MouseButton>>>,
+    motion: Option<Res<'w, AccumulatedMouseMotion>>,
+    scroll: Option<Res<'w, AccumulatedMouseScroll>>,
+}
+
+/// Applies free-camera input while in [`CameraMode::Free`].
+///
+/// Panning uses `Shift`+WASD or the arrow keys, so plain WASD keeps driving
+/// the car, and left-mouse drag. The scroll wheel and `-`/`=` zoom.
+pub fn free_camera_system(
+    time: Res<Time>,
+    mode: Res<CameraMode>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: FreeCameraMouse,
+    mut free: ResMut<FreeCamera>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), MainCameraFilter>,
+) {
+    if *mode != CameraMode::Free {
+        return;
+    }
+
+    let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let held = |arrow: KeyCode, letter: KeyCode| {
+        keyboard.pressed(arrow) || (shift && keyboard.pressed(letter))
+    };
+    let mut axis = Vec2::ZERO;
+    if held(KeyCode::ArrowLeft, KeyCode::KeyA) {
+        axis.x -= 1.0;
+    }
+    if held(KeyCode::ArrowRight, KeyCode::KeyD) {
+        axis.x += 1.0;
+    }
+    if held(KeyCode::ArrowUp, KeyCode::KeyW) {
+        axis.y += 1.0;
+    }
+    if held(KeyCode::ArrowDown, KeyCode::KeyS) {
+        axis.y -= 1.0;
+    }
+
+    let dragging = mouse
+        .buttons
+        .is_some_and(|buttons| buttons.pressed(MouseButton::Left));
+    let drag = match mouse.motion {
+        Some(motion) if dragging => motion.delta,
+        _ => Vec2::ZERO,
+    };
+
+    let mut zoom_steps = mouse.scroll.map_or(0.0, |scroll| -scroll.delta.y);
+    if keyboard.just_pressed(KeyCode::Minus) {
+        zoom_steps += 1.0;
+    }
+    if keyboard.just_pressed(KeyCode::Equal) {
+        zoom_steps -= 1.0;
+    }
+
+    let pan = free_camera_pan(axis, drag, free.zoom, time.delta_secs());
+    free.center += pan;
+    free.zoom =
+        (free.zoom * ZOOM_STEP.powf(zoom_steps)).clamp(FREE_CAMERA_MIN_ZOOM, FREE_CAMERA_MAX_ZOOM);
+
+    let Ok((mut transform, mut projection)) = camera_query.single_mut() else {
+        return;
+    };
+    transform.translation.x = free.center.x;
+    transform.translation.y = free.center.y;
+    if let Projection::Orthographic(ortho) = &mut *projection {
+        ortho.scale = free.zoom;
+    }
+}
+
+/// Restores the whole-track view in [`CameraMode::Overview`], and in
+/// [`CameraMode::Follow`] while only one car exists.
+pub fn overview_camera_system(
+    mode: Res<CameraMode>,
+    car_query: Query<(), With<Car>>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), MainCameraFilter>,
+) {
+    let overview = match *mode {
+        CameraMode::Overview => true,
+        CameraMode::Follow => car_query.iter().count() < 2,
+        CameraMode::Free => false,
+    };
+    if !overview {
+        return;
+    }
+    let Ok((mut transform, mut projection)) = camera_query.single_mut() else {
+        return;
+    };
+    transform.translation.x = 0.0;
+    transform.translation.y = 0.0;
+    if let Projection::Orthographic(ortho) = &mut *projection {
+        ortho.scale = 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CameraMode, FREE_CAMERA_PAN_SPEED, free_camera_pan};
+    use bevy::prelude::Vec2;
+
+    #[test]
+    fn free_camera_pan_scales_input_by_zoom() {
+        let dt = 0.5;
+        let right = free_camera_pan(Vec2::X, Vec2::ZERO, 1.0, dt);
+        assert_eq!(right, Vec2::new(FREE_CAMERA_PAN_SPEED * dt, 0.0));
+
+        let zoomed_out = free_camera_pan(Vec2::new(1.0, -1.0), Vec2::ZERO, 2.5, dt);
+        assert_eq!(
+            zoomed_out,
+            Vec2::new(
+                2.5 * FREE_CAMERA_PAN_SPEED * dt,
+                -2.5 * FREE_CAMERA_PAN_SPEED * dt
+            )
+        );
+
+        // Dragging right and down by 10 px moves the view left and up by
+        // 10 px worth of world units.
+        let drag = free_camera_pan(Vec2::ZERO, Vec2::new(10.0, 10.0), 0.5, dt);
+        assert_eq!(drag, Vec2::new(-5.0, 5.0));
+
+        assert_eq!(free_camera_pan(Vec2::ZERO, Vec2::ZERO, 3.0, dt), Vec2::ZERO);
+    }
+
+    #[test]
+    fn camera_modes_cycle() {
+        let mut mode = CameraMode::default();
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            seen.push(mode);
+            mode = mode.next();
+        }
+        assert_eq!(mode, CameraMode::Follow);
+        assert_eq!(
+            seen,
+            [CameraMode::Follow, CameraMode::Free, CameraMode::Overview]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+
+use crate::debug::camera::{CameraMode, MainCameraFilter};
+use crate::game::car::Car;
+
+/// The car the HUD, geometry overlay, and camera follow.
+///
+/// `Tab` cycles through cars in spawn order. When the focused car no longer
+/// exists (or none was chosen yet) the first car is focused.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct FocusedCar {
+    pub entity: Option<Entity>,
+}
+
+impl FocusedCar {
+    /// Focus after cycling once from `current` through `cars`, wrapping
+    /// around at the end.
+    fn next(cars: &[Entity], current: Option<Entity>) -> Option<Entity> {
+        let next_index = current
+            .and_then(|entity| cars.iter().position(|&car| car == entity))
+            .map_or(0, |index| (index + 1) % cars.len().max(1));
+        cars.get(next_index).copied()
+    }
+}
+
+/// Cycles [`FocusedCar`] on `Tab` and keeps it pointing at a live car.
+///
+/// Spawn order is the order cars are first seen; cars spawned in the same
+/// frame keep the query's table order, which is their insertion order.
+/// `Entity` ids are recycled, so their ordering is not spawn order.
+pub fn focused_car_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut focus: ResMut<FocusedCar>,
+    car_query: Query<Entity, With<Car>>,
+    mut spawn_order: Local<Vec<Entity>>,
+) {
+    spawn_order.retain(|&entity| car_query.contains(entity));
+    for entity in &car_query {
+        if !spawn_order.contains(&entity) {
+            spawn_order.push(entity);
+        }
+    }
+    let cars = spawn_order.as_slice();
+
+    if keyboard.just_pressed(KeyCode::Tab) {
+        focus.entity = FocusedCar::next(cars, focus.entity);
+        if let Some(entity) = focus.entity {
+            let index = cars.iter().position(|&car| car == entity).unwrap_or(0);
+            info!("Focused car {} of {}.", index + 1, cars.len());
+        }
+    } else if focus.entity.is_none_or(|entity| !cars.contains(&entity)) {
+        focus.entity = cars.first().copied();
+    }
+}
+
+/// Centres the camera on the focused car when more than one car is racing
+/// in [`CameraMode::Follow`]. A single car keeps the static whole-track view.
+pub fn follow_focused_car_system(
+    mode: Option<Res<CameraMode>>,
+    focus: Res<FocusedCar>,
+    car_query: Query<&Transform, (With<Car>, Without<Camera2d>)>,
+    mut camera_query: Query<&mut Transform, (MainCameraFilter, Without<Car>)>,
+) {
+    if mode.is_some_and(|mode| *mode != CameraMode::Follow) || car_query.iter().count() < 2 {
+        return;
+    }
+    let Some(car) = focus.entity.and_then(|entity| car_query.get(entity).ok()) else {
+        return;
+    };
+    let Ok(mut camera) = camera_query.single_mut() else {
+        return;
+    };
+    camera.translation.x = car.translation.x;
+    camera.translation.y = car.translation.y;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FocusedCar, focused_car_system};
+    use crate::debug::hud::{DrivingHudStats, update_driving_hud_stats_system};
+    use crate::game::car::Car;
+    use crate::game::collision::{CollisionEvent, OffTrackWarning};
+    use crate::game::episode::EpisodeState;
+    use crate::game::progress::TrackProgress;
+    use bevy::prelude::*;
+
+    fn press_tab(app: &mut App) {
+        let mut keyboard = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard.release(KeyCode::Tab);
+        keyboard.clear();
+        keyboard.press(KeyCode::Tab);
+        app.update();
+    }
+
+    #[test]
+    fn tab_cycles_focus_through_cars_and_the_hud_follows_it() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<FocusedCar>()
+            .init_resource::<DrivingHudStats>()
+            .init_resource::<EpisodeState>()
+            .add_message::<CollisionEvent>()
+            .add_message::<OffTrackWarning>()
+            .add_systems(
+                Update,
+                (focused_car_system, update_driving_hud_stats_system).chain(),
+            );
+        let cars: Vec<Entity> = [0.2, 0.5, 0.8]
+            .into_iter()
+            .map(|fraction| {
+                let progress = TrackProgress {
+                    fraction,
+                    ..TrackProgress::default()
+                };
+                app.world_mut().spawn((Car::default(), progress)).id()
+            })
+            .collect();
+
+        app.update();
+        assert_eq!(app.world().resource::<FocusedCar>().entity, Some(cars[0]));
+        assert_eq!(
+            app.world()
+                .resource::<DrivingHudStats>()
+                .best_progress_fraction,
+            0.2
+        );
+
+        press_tab(&mut app);
+        assert_eq!(app.world().resource::<FocusedCar>().entity, Some(cars[1]));
+        assert_eq!(
+            app.world()
+                .resource::<DrivingHudStats>()
+                .best_progress_fraction,
+            0.5
+        );
+
+        let mut visited = Vec::new();
+        for _ in 0..4 {
+            press_tab(&mut app);
+            visited.push(app.world().resource::<FocusedCar>().entity.unwrap());
+        }
+        assert_eq!(visited, [cars[2], cars[0], cars[1], cars[2]]);
+
+        app.world_mut().despawn(cars[2]);
+        app.update();
+        assert_eq!(app.world().resource::<FocusedCar>().entity, Some(cars[0]));
+    }
+}
@@ -9,13 +9,22 @@ use bevy::ui::{
     UiRect, Val,
 };
 
+use crate::agent::action::{KeyboardConfig, KeyboardStick};
+use crate::agent::gamepad::ActiveGamepad;
 use crate::agent::observation::SensorReadings;
 use crate::brain::a2c::A2cTrainingStats;
+use crate::brain::neuroevolution::NeuroEvolution;
+use crate::brain::types::AgentMode;
+use crate::debug::focus::FocusedCar;
 use crate::debug::overlays::DebugOverlayState;
 use crate::game::car::Car;
-use crate::game::collision::CollisionEvent;
+use crate::game::collision::{CollisionEvent, OffTrackWarning};
 use crate::game::episode::{EpisodeConfig, EpisodeEndReason, EpisodeMovingAverages, EpisodeState};
 use crate::game::progress::TrackProgress;
+use crate::game::reward::{RewardBreakdown, RewardStack};
+use crate::sim::clock::SimClock;
+use crate::sim::control::SimControl;
+use crate::sim::stats::RunStats;
 
 const HUD_QUARTER_COUNT: usize = 4;
 const FIXED_TICK_SECONDS: f32 = 1.0 / 60.0;
@@ -24,6 +33,8 @@ const FIXED_TICK_SECONDS: f32 = 1.0 / 60.0;
 #[derive(Resource, Debug)]
 pub struct DrivingHudStats {
     pub deaths: u32,
+    /// Off-road ticks that stayed inside the crash grace window.
+    pub warnings: u32,
     pub best_progress_fraction: f32,
     pub best_progress_episode: u32,
 }
@@ -32,6 +43,7 @@ impl Default for DrivingHudStats {
     fn default() -> Self {
         Self {
             deaths: 0,
+            warnings: 0,
             best_progress_fraction: 0.0,
             best_progress_episode: 1,
         }
@@ -106,12 +118,26 @@ struct QuarterSummary {
 #[derive(Component)]
 pub(crate) struct DrivingHudRoot;
 
+/// Marker for the centred banner shown while the simulation is paused.
+#[derive(Component)]
+pub(crate) struct PausedBanner;
+
+/// Marker for the centred banner shown during an episode's start countdown.
+#[derive(Component)]
+pub(crate) struct CountdownBanner;
+
+/// Marker for the countdown banner's text.
+#[derive(Component)]
+pub(crate) struct CountdownText;
+
 #[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum HudTextRole {
     Assessment,
+    Sim,
     Current,
     Run,
     Learning,
+    Rewards,
     Legend,
 }
 
@@ -148,6 +174,67 @@ fn quarter_column_width(column: QuarterColumn) -> f32 {
 
 /// Spawns the runtime diagnostics HUD used by `F3`.
 pub(crate) fn spawn_driving_hud_system(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(12.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                display: Display::None,
+                ..default()
+            },
+            PausedBanner,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::axes(Val::Px(18.0), Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.55, 0.12, 0.10, 0.88)),
+                ))
+                .with_children(|banner| {
+                    banner.spawn((
+                        Text::new("PAUSED  |  P resume  |  . step one tick"),
+                        TextFont::from_font_size(18.0),
+                        TextColor(Color::srgb(0.98, 0.95, 0.92)),
+                    ));
+                });
+        });
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(38.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                display: Display::None,
+                ..default()
+            },
+            CountdownBanner,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        padding: UiRect::axes(Val::Px(28.0), Val::Px(10.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.05, 0.09, 0.11, 0.85)),
+                ))
+                .with_children(|banner| {
+                    banner.spawn((
+                        Text::new(""),
+                        TextFont::from_font_size(48.0),
+                        TextColor(Color::srgb(0.98, 0.86, 0.35)),
+                        CountdownText,
+                    ));
+                });
+        });
+
     commands
         .spawn((
             Node {
@@ -180,6 +267,13 @@ pub(crate) fn spawn_driving_hud_system(mut commands: Commands) {
                 TextColor(Color::srgb(0.95, 0.98, 0.97)),
             ));
 
+            parent.spawn((
+                Text::new(""),
+                TextFont::from_font_size(12.0),
+                TextColor(Color::srgb(0.96, 0.78, 0.45)),
+                HudTextRole::Sim,
+            ));
+
             parent.spawn((
                 Text::new(""),
                 TextFont::from_font_size(13.0),
@@ -204,6 +298,12 @@ pub(crate) fn spawn_driving_hud_system(mut commands: Commands) {
                 TextColor(Color::srgb(0.80, 0.88, 0.87)),
                 HudTextRole::Learning,
             ));
+            parent.spawn((
+                Text::new(""),
+                TextFont::from_font_size(11.0),
+                TextColor(Color::srgb(0.80, 0.88, 0.87)),
+                HudTextRole::Rewards,
+            ));
 
             parent.spawn((
                 Node {
@@ -372,18 +472,24 @@ pub(crate) fn spawn_driving_hud_system(mut commands: Commands) {
         });
 }
 
-/// Tracks live death count and the best progress reached in any episode so far.
+/// Tracks live death count and the best progress the focused car reached in
+/// any episode so far.
 pub(crate) fn update_driving_hud_stats_system(
     mut hud_stats: ResMut<DrivingHudStats>,
     mut collision_events: MessageReader<CollisionEvent>,
+    mut warning_events: MessageReader<OffTrackWarning>,
     episode_state: Res<EpisodeState>,
+    focus: Res<FocusedCar>,
     progress_query: Query<&TrackProgress, With<Car>>,
 ) {
     for _ in collision_events.read() {
         hud_stats.deaths = hud_stats.deaths.saturating_add(1);
     }
+    for _ in warning_events.read() {
+        hud_stats.warnings = hud_stats.warnings.saturating_add(1);
+    }
 
-    let Ok(progress) = progress_query.single() else {
+    let Some(progress) = focus.entity.and_then(|car| progress_query.get(car).ok()) else {
         return;
     };
 
@@ -433,30 +539,84 @@ pub(crate) fn capture_driving_hud_episode_metrics_system(
     accumulator.reset_for_episode(episode_state.current_episode);
 }
 
-/// Shows or hides the diagnostics panel according to the `F3` toggle.
+/// Shows or hides the diagnostics panel according to the `F3` toggle, and the
+/// paused banner according to [`SimControl`].
 pub(crate) fn update_driving_hud_visibility_system(
     overlay: Res<DebugOverlayState>,
-    mut root_query: Query<&mut Node, With<DrivingHudRoot>>,
+    sim_control: Res<SimControl>,
+    mut root_query: Query<&mut Node, (With<DrivingHudRoot>, Without<PausedBanner>)>,
+    mut banner_query: Query<&mut Node, (With<PausedBanner>, Without<DrivingHudRoot>)>,
 ) {
-    let Ok(mut node) = root_query.single_mut() else {
-        return;
-    };
+    if let Ok(mut node) = root_query.single_mut() {
+        node.display = if overlay.telemetry {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
 
-    node.display = if overlay.telemetry {
-        Display::Flex
-    } else {
-        Display::None
-    };
+    if let Ok(mut node) = banner_query.single_mut() {
+        node.display = if sim_control.paused {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
 }
 
+/// Shows the start countdown in whole seconds while
+/// [`EpisodeState::countdown_remaining`] is non-zero.
+pub(crate) fn update_countdown_banner_system(
+    episode_state: Res<EpisodeState>,
+    config: Res<EpisodeConfig>,
+    mut banner_query: Query<&mut Node, With<CountdownBanner>>,
+    mut text_query: Query<&mut Text, With<CountdownText>>,
+) {
+    let remaining = episode_state.countdown_remaining(&config);
+    if let Ok(mut node) = banner_query.single_mut() {
+        node.display = if remaining > 0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    if remaining == 0 {
+        return;
+    }
+    if let Ok(mut text) = text_query.single_mut() {
+        let seconds = (remaining as f32 * FIXED_TICK_SECONDS).ceil() as u32;
+        let label = seconds.max(1).to_string();
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+/// Manual-input state the HUD describes: the active gamepad and the
+/// keyboard's analog stick with its config.
+type ManualInputs<'w> = (
+    Option<Res<'w, ActiveGamepad>>,
+    Option<Res<'w, KeyboardStick>>,
+    Option<Res<'w, KeyboardConfig>>,
+);
+
 /// Rebuilds the diagnostics text and quarter grid shown in the HUD.
 pub(crate) fn update_driving_hud_text_system(
     overlay: Res<DebugOverlayState>,
     hud_stats: Res<DrivingHudStats>,
     history: Res<DrivingHudHistory>,
-    episode_state: Res<EpisodeState>,
-    moving_avg: Res<EpisodeMovingAverages>,
-    a2c_stats: Option<Res<A2cTrainingStats>>,
+    episode: (
+        Res<EpisodeState>,
+        Res<EpisodeMovingAverages>,
+        Option<Res<RewardStack>>,
+    ),
+    sim_control: Res<SimControl>,
+    sim_clock: Res<SimClock>,
+    run_stats: Res<RunStats>,
+    learning: (Option<Res<A2cTrainingStats>>, Option<Res<NeuroEvolution>>),
+    agent_mode: Option<Res<AgentMode>>,
+    manual: ManualInputs,
+    focus: Res<FocusedCar>,
     car_query: Query<(&TrackProgress, &SensorReadings), With<Car>>,
     summary_query: Query<(Entity, &HudTextRole)>,
     quarter_query: Query<(Entity, &QuarterCell)>,
@@ -466,16 +626,114 @@ pub(crate) fn update_driving_hud_text_system(
         return;
     }
 
-    let Ok((progress, sensors)) = car_query.single() else {
+    let Some((progress, sensors)) = focus.entity.and_then(|car| car_query.get(car).ok()) else {
         return;
     };
 
+    let (episode_state, moving_avg, reward_stack) = episode;
+    let recent_quarters = summarise_recent_history(&history);
+    let (gamepad, keyboard_stick, keyboard_config) = manual;
+    let (assessment, guidance) = assess_recent_run(&recent_quarters);
+    let (a2c_stats, evolution) = learning;
+    let evolving = agent_mode.as_deref() == Some(&AgentMode::Evolve);
+
+    for (entity, role) in &summary_query {
+        let text = match role {
+            HudTextRole::Assessment => format!("Status  {assessment}  |  {guidance}"),
+            HudTextRole::Sim => sim_section(
+                &sim_clock,
+                &run_stats,
+                &sim_control,
+                controller_label(
+                    agent_mode.as_deref(),
+                    gamepad.as_deref(),
+                    keyboard_stick.as_deref(),
+                    keyboard_config.as_deref(),
+                ),
+            ),
+            HudTextRole::Current => current_section(progress, sensors, &episode_state),
+            HudTextRole::Run => run_section(&hud_stats, &episode_state, &moving_avg),
+            HudTextRole::Learning => learning_section(
+                evolution.as_deref().filter(|_| evolving),
+                a2c_stats.as_deref(),
+            ),
+            HudTextRole::Rewards => {
+                // Without a stack every recorded term is shown.
+                let active = match reward_stack.as_deref() {
+                    Some(stack) => stack.active_term_names(),
+                    None => episode_state
+                        .current_breakdown_sums
+                        .terms
+                        .iter()
+                        .map(|entry| entry.name.as_str())
+                        .collect(),
+                };
+                reward_section(
+                    &active,
+                    &episode_state.current_tick_breakdown,
+                    &episode_state.current_breakdown_sums,
+                )
+            }
+            HudTextRole::Legend => {
+                "Lower Gap/Head is better. Higher Prog/Life/Return is better. C/L/T = crashes/laps/timeouts."
+                    .to_string()
+            }
+        };
+        *text_writer.text(entity, 0) = text;
+    }
+
+    for (entity, cell) in &quarter_query {
+        let text = render_quarter_cell(recent_quarters[cell.row], cell.column, cell.row);
+        *text_writer.text(entity, 0) = text;
+    }
+}
+
+fn sim_section(
+    sim_clock: &SimClock,
+    run_stats: &RunStats,
+    sim_control: &SimControl,
+    controller: String,
+) -> String {
+    format!(
+        "Sim  tick {}  t {:.1}s  wall {:.0}s  {:.0} tps (avg {:.0})  {}  ctrl {} (F4)",
+        sim_clock.tick,
+        sim_clock.elapsed_s,
+        run_stats.wall_s,
+        run_stats.instant_tps,
+        run_stats.average_tps,
+        if sim_control.paused {
+            "PAUSED (P resume, . step)"
+        } else {
+            "running (P pause)"
+        },
+        controller,
+    )
+}
+
+fn current_section(
+    progress: &TrackProgress,
+    sensors: &SensorReadings,
+    episode_state: &EpisodeState,
+) -> String {
     let progress_pct = (progress.fraction * 100.0).clamp(0.0, 100.0);
-    let best_progress_pct = (hud_stats.best_progress_fraction * 100.0).clamp(0.0, 100.0);
     let life_best_progress_pct =
         (episode_state.current_best_progress_fraction * 100.0).clamp(0.0, 100.0);
+    format!(
+        "Now  progress {progress_pct:5.2}%  life-best {life_best_progress_pct:5.2}%  offset {offset:+6.2}  line-gap {line_gap:5.2}  heading {heading_error_deg:5.2} deg  slip {slip:+6.1}",
+        offset = sensors.signed_lateral_offset,
+        line_gap = progress.distance,
+        heading_error_deg = sensors.heading_error.to_degrees(),
+        slip = sensors.body_velocity.y,
+    )
+}
+
+fn run_section(
+    hud_stats: &DrivingHudStats,
+    episode_state: &EpisodeState,
+    moving_avg: &EpisodeMovingAverages,
+) -> String {
+    let best_progress_pct = (hud_stats.best_progress_fraction * 100.0).clamp(0.0, 100.0);
     let current_life_seconds = episode_state.ticks_in_episode as f32 * FIXED_TICK_SECONDS;
-    let heading_error_deg = sensors.heading_error.to_degrees();
     let avg_progress_pct = (moving_avg.best_progress_mean * 100.0).clamp(0.0, 100.0);
     let last_reason = match episode_state.last_end_reason {
         Some(EpisodeEndReason::Crash) => "Crash",
@@ -483,28 +741,55 @@ pub(crate) fn update_driving_hud_text_system(
         Some(EpisodeEndReason::LapComplete) => "Lap",
         None => "N/A",
     };
-    let recent_quarters = summarise_recent_history(&history);
-    let (assessment, guidance) = assess_recent_run(&recent_quarters);
-
-    let current_line = format!(
-        "Now  progress {progress_pct:5.2}%  life-best {life_best_progress_pct:5.2}%  offset {offset:+6.2}  line-gap {line_gap:5.2}  heading {heading_error_deg:5.2} deg",
-        offset = sensors.signed_lateral_offset,
-        line_gap = progress.distance,
-    );
-    let run_line = format!(
-        "Run  ep {}  deaths {}  life {:5.2}s  reward {:+7.2}  last {}  best {:5.2}% @ ep {}  recent avg {:5.2}% / {:+6.2}",
+    format!(
+        "Run  ep {}  deaths {}  warnings {}  life {:5.2}s  reward {:+7.2}  idle {:+6.2}  last {}  best {:5.2}% @ ep {}  recent avg {:5.2}% / {:+6.2}  effort {:4.3} / thr {:4.2}",
         episode_state.current_episode,
         hud_stats.deaths,
+        hud_stats.warnings,
         current_life_seconds,
         episode_state.current_return,
+        episode_state.current_breakdown_sums.get("idle"),
         last_reason,
         best_progress_pct,
         hud_stats.best_progress_episode,
         avg_progress_pct,
         moving_avg.return_mean,
-    );
-    let learning_line = match a2c_stats {
-        Some(stats) if stats.last_completed_update > 0 => format!(
+        moving_avg.steering_effort_mean,
+        moving_avg.throttle_usage_mean,
+    )
+}
+
+/// Evolution line while evolving, otherwise the latest A2C update.
+fn learning_section(
+    evolution: Option<&NeuroEvolution>,
+    a2c_stats: Option<&A2cTrainingStats>,
+) -> String {
+    match (evolution, a2c_stats) {
+        (Some(evolution), _) => {
+            let last = match evolution.last_stats() {
+                Some(stats) => format!(
+                    "gen {} best {:5.3} mean {:5.3} worst {:5.3}",
+                    stats.generation, stats.best, stats.mean, stats.worst
+                ),
+                None => "no finished generation yet".to_string(),
+            };
+            let structure = match evolution.neat_structure() {
+                Some((structure, species)) => format!(
+                    "  |  nodes {} conns {} species {}",
+                    structure.nodes, structure.enabled_connections, species
+                ),
+                None => String::new(),
+            };
+            format!(
+                "Evo  gen {}  genome {}/{}  |  last {}{}",
+                evolution.generation(),
+                evolution.current_genome() + 1,
+                evolution.population_size(),
+                last,
+                structure,
+            )
+        }
+        (None, Some(stats)) if stats.last_completed_update > 0 => format!(
             "A2C  upd {}  EV {:5.3}  Vloss {:5.3}  Ent {:5.3}  steer std {:5.3}  throttle std {:5.3}",
             stats.last_completed_update,
             stats.explained_variance,
@@ -514,26 +799,54 @@ pub(crate) fn update_driving_hud_text_system(
             stats.throttle_std,
         ),
         _ => "A2C  no completed updates yet".to_string(),
-    };
-    let legend_line =
-        "Lower Gap/Head is better. Higher Prog/Life/Return is better. C/L/T = crashes/laps/timeouts."
-            .to_string();
-
-    for (entity, role) in &summary_query {
-        let text = match role {
-            HudTextRole::Assessment => format!("Status  {assessment}  |  {guidance}"),
-            HudTextRole::Current => current_line.clone(),
-            HudTextRole::Run => run_line.clone(),
-            HudTextRole::Learning => learning_line.clone(),
-            HudTextRole::Legend => legend_line.clone(),
-        };
-        *text_writer.text(entity, 0) = text;
     }
+}
 
-    for (entity, cell) in &quarter_query {
-        let text = render_quarter_cell(recent_quarters[cell.row], cell.column, cell.row);
-        *text_writer.text(entity, 0) = text;
+/// One row per active reward term: this tick's value and the episode sum,
+/// followed by the totals over every term.
+fn reward_section(active: &[&str], tick: &RewardBreakdown, episode: &RewardBreakdown) -> String {
+    let mut lines = vec![format!(
+        "Rwd  {:<18}{:>10}{:>10}",
+        "term", "tick", "episode"
+    )];
+    for name in active {
+        lines.push(format!(
+            "     {name:<18}{:+10.4}{:+10.2}",
+            tick.get(name),
+            episode.get(name),
+        ));
     }
+    lines.push(format!(
+        "     {:<18}{:+10.4}{:+10.2}",
+        "total",
+        tick.total(),
+        episode.total(),
+    ));
+    lines.join("\n")
+}
+
+/// Controller name for the sim line, with the pad name in gamepad mode and
+/// the virtual stick whenever the analog keyboard drives.
+fn controller_label(
+    mode: Option<&AgentMode>,
+    gamepad: Option<&ActiveGamepad>,
+    stick: Option<&KeyboardStick>,
+    keyboard: Option<&KeyboardConfig>,
+) -> String {
+    let keyboard_drives = match mode {
+        Some(AgentMode::Keyboard) => true,
+        Some(AgentMode::Gamepad) => gamepad.is_none_or(|pad| !pad.is_connected()),
+        _ => false,
+    };
+    let stick = stick
+        .filter(|_| keyboard_drives && keyboard.is_some_and(|keyboard| keyboard.analog))
+        .map(|stick| format!(" [{}]", stick.label()))
+        .unwrap_or_default();
+    let name = match (mode, gamepad) {
+        (Some(AgentMode::Gamepad), Some(pad)) => format!("gamepad [{}]", pad.label()),
+        (mode, _) => mode.map_or("none", |mode| mode.name()).to_string(),
+    };
+    format!("{name}{stick}")
 }
 
 fn summarise_recent_history(history: &DrivingHudHistory) -> [QuarterSummary; HUD_QUARTER_COUNT] {
@@ -667,7 +980,9 @@ fn assess_recent_run(
 
 #[cfg(test)]
 mod tests {
-    use super::{HUD_QUARTER_COUNT, QuarterSummary, assess_recent_run};
+    use super::{HUD_QUARTER_COUNT, QuarterSummary, assess_recent_run, reward_section};
+    use crate::game::episode::EpisodeConfig;
+    use crate::game::reward::{RewardBreakdown, RewardBreakdownEntry, RewardKind, RewardStack};
 
     #[test]
     fn assess_recent_run_reports_improvement_when_latest_quarter_is_cleaner() {
@@ -695,4 +1010,50 @@ mod tests {
         assert_eq!(assessment, "Improving");
         assert!(guidance.contains("worth continuing"));
     }
+
+    #[test]
+    fn reward_section_lists_active_terms_with_tick_and_episode_sums() {
+        let stack = RewardStack::from_config(&EpisodeConfig {
+            idle_penalty_per_tick: 0.0,
+            ..EpisodeConfig::default()
+        });
+        let active = stack.active_term_names();
+        assert!(!active.contains(&"idle"));
+
+        let entry = |name: &str, kind, value| RewardBreakdownEntry {
+            name: name.to_string(),
+            kind,
+            value,
+        };
+        let tick = RewardBreakdown {
+            terms: vec![
+                entry("progress", RewardKind::Shaping, 0.05),
+                entry("time", RewardKind::Shaping, -0.005),
+                entry("idle", RewardKind::Shaping, 0.0),
+            ],
+        };
+        let episode = RewardBreakdown {
+            terms: vec![
+                entry("progress", RewardKind::Shaping, 12.5),
+                entry("time", RewardKind::Shaping, -1.25),
+                entry("idle", RewardKind::Shaping, 0.0),
+                entry("crash", RewardKind::Terminal, -2.0),
+            ],
+        };
+
+        let text = reward_section(&active, &tick, &episode);
+        assert!(text.contains("progress"));
+        assert!(text.contains("+0.0500"));
+        assert!(text.contains("+12.50"));
+        assert!(text.contains("-0.0050"));
+        assert!(text.contains("crash"));
+        assert!(!text.contains("idle"));
+        let total = text.lines().last().unwrap();
+        assert!(total.contains("total"));
+        assert!(total.contains("+0.0450"));
+        assert!(total.contains("+9.25"));
+        // Every row lines up under the header columns.
+        let widths: Vec<usize> = text.lines().map(|line| line.len()).collect();
+        assert!(widths.iter().all(|width| *width == widths[0]));
+    }
 }
@@ -4,6 +4,8 @@
 //! debug overlays and instrumentation cannot accidentally become dependencies
 //! of the environment or agent interfaces.
 
+pub mod camera;
+pub mod focus;
 pub mod hud;
 pub mod overlays;
 pub mod plugin;
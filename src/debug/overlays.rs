@@ -1,9 +1,17 @@
+use std::collections::VecDeque;
+
+use bevy::ecs::message::MessageReader;
 use bevy::math::Isometry2d;
 use bevy::prelude::*;
 
+use crate::agent::action::finite_or_zero;
 use crate::agent::observation::{ObservationConfig, SensorReadings};
+use crate::debug::focus::FocusedCar;
 use crate::game::car::Car;
+use crate::game::collision::CollisionEvent;
+use crate::game::episode::EpisodeState;
 use crate::game::progress::TrackProgress;
+use crate::maps::grid::RayHitClass;
 use crate::maps::track::Track;
 
 /// Debug overlay toggles.
@@ -15,6 +23,32 @@ pub struct DebugOverlayState {
     pub sensors: bool,
     /// Telemetry overlay for the runtime diagnostics HUD.
     pub telemetry: bool,
+    /// Per-tick reward bar above the focused car.
+    pub reward: bool,
+    /// How the sensor overlay colours and labels rays.
+    pub ray_style: RayOverlayStyle,
+}
+
+/// Sensor-overlay ray styles, cycled with `Shift+F2`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RayOverlayStyle {
+    /// Ray lines coloured by what stopped them.
+    #[default]
+    HitClass,
+    /// Ray lines on a gradient by observation index.
+    Index,
+    /// Index gradient, plus each ray's index drawn at its hit point.
+    IndexLabeled,
+}
+
+impl RayOverlayStyle {
+    pub fn next(self) -> Self {
+        match self {
+            Self::HitClass => Self::Index,
+            Self::Index => Self::IndexLabeled,
+            Self::IndexLabeled => Self::HitClass,
+        }
+    }
 }
 
 impl Default for DebugOverlayState {
@@ -23,16 +57,94 @@ impl Default for DebugOverlayState {
             geometry: true,
             sensors: false,
             telemetry: true,
+            reward: false,
+            ray_style: RayOverlayStyle::HitClass,
         }
     }
 }
 
+/// Recent crash contact points, newest last, for the collision overlay.
+///
+/// Holds at most `capacity` marks; each fades out over `lifetime_s` of
+/// simulated time and is then dropped.
+#[derive(Resource, Clone, Debug)]
+pub struct CollisionHistory {
+    pub capacity: usize,
+    pub lifetime_s: f32,
+    marks: VecDeque<CollisionMark>,
+}
+
+/// One remembered crash contact point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CollisionMark {
+    pub point: Vec2,
+    pub age_s: f32,
+}
+
+impl Default for CollisionHistory {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            lifetime_s: 20.0,
+            marks: VecDeque::new(),
+        }
+    }
+}
+
+impl CollisionHistory {
+    /// Records a new contact point, dropping the oldest mark when full.
+    pub fn push(&mut self, point: Vec2) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.marks.len() >= self.capacity {
+            self.marks.pop_front();
+        }
+        self.marks.push_back(CollisionMark { point, age_s: 0.0 });
+    }
+
+    /// Ages every mark by `dt` seconds and drops expired ones.
+    pub fn advance(&mut self, dt: f32) {
+        for mark in &mut self.marks {
+            mark.age_s += dt;
+        }
+        let lifetime = self.lifetime_s;
+        self.marks.retain(|mark| mark.age_s < lifetime);
+    }
+
+    /// Opacity for a mark of `age_s`: `1` when fresh, fading linearly to `0`
+    /// at `lifetime_s`.
+    pub fn alpha(&self, age_s: f32) -> f32 {
+        if self.lifetime_s <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - age_s / self.lifetime_s).clamp(0.0, 1.0)
+    }
+
+    pub fn marks(&self) -> impl Iterator<Item = &CollisionMark> {
+        self.marks.iter()
+    }
+}
+
+/// Ages the collision history and records this tick's crash contact points.
+pub fn record_collision_history_system(
+    time: Res<Time<bevy::time::Fixed>>,
+    mut history: ResMut<CollisionHistory>,
+    mut collision_events: MessageReader<CollisionEvent>,
+) {
+    history.advance(time.delta_secs());
+    for event in collision_events.read() {
+        history.push(event.point);
+    }
+}
+
 /// Handles overlay toggle keybindings.
 ///
 /// Milestone 0 convention (per `README.md`):
 /// - F1: geometry overlays
-/// - F2: sensor overlays
+/// - F2: sensor overlays (`Shift+F2` cycles the ray style)
 /// - F3: telemetry overlay
+/// - F9: reward overlay
 pub fn debug_overlay_toggle_system(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut overlay: ResMut<DebugOverlayState>,
@@ -42,19 +154,87 @@ pub fn debug_overlay_toggle_system(
         info!("Debug overlay F1 (geometry): {}", overlay.geometry);
     }
     if keyboard.just_pressed(KeyCode::F2) {
-        overlay.sensors = !overlay.sensors;
-        info!("Debug overlay F2 (sensors): {}", overlay.sensors);
+        if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            overlay.ray_style = overlay.ray_style.next();
+            info!("Debug overlay ray style: {:?}", overlay.ray_style);
+        } else {
+            overlay.sensors = !overlay.sensors;
+            info!("Debug overlay F2 (sensors): {}", overlay.sensors);
+        }
     }
     if keyboard.just_pressed(KeyCode::F3) {
         overlay.telemetry = !overlay.telemetry;
         info!("Debug overlay F3 (telemetry): {}", overlay.telemetry);
     }
+    if keyboard.just_pressed(KeyCode::F9) {
+        overlay.reward = !overlay.reward;
+        info!("Debug overlay F9 (reward): {}", overlay.reward);
+    }
+}
+
+/// Per-tick reward magnitude drawn at full saturation and full bar length.
+pub const REWARD_OVERLAY_FULL_SCALE: f32 = 1.0;
+
+/// Colour of a per-tick reward: neutral grey at zero, blending towards green
+/// for positive and red for negative rewards. `|reward| / full_scale`,
+/// clamped to `[0, 1]`, sets how far the blend goes, so larger magnitudes
+/// are more saturated.
+pub fn reward_color(reward: f32, full_scale: f32) -> Color {
+    const NEUTRAL: Vec3 = Vec3::new(0.5, 0.5, 0.5);
+    const POSITIVE: Vec3 = Vec3::new(0.1, 0.9, 0.1);
+    const NEGATIVE: Vec3 = Vec3::new(0.95, 0.1, 0.1);
+
+    let reward = finite_or_zero(reward);
+    let magnitude = (reward.abs() / full_scale.max(1e-6)).clamp(0.0, 1.0);
+    let target = if reward >= 0.0 { POSITIVE } else { NEGATIVE };
+    let rgb = NEUTRAL.lerp(target, magnitude);
+    Color::srgb(rgb.x, rgb.y, rgb.z)
+}
+
+/// Draws the last tick's total reward as a bar above the focused car,
+/// growing right for positive and left for negative rewards, coloured by
+/// [`reward_color`].
+pub fn draw_reward_overlay_system(
+    overlay: Res<DebugOverlayState>,
+    focus: Res<FocusedCar>,
+    episode_state: Res<EpisodeState>,
+    car_query: Query<&Transform, With<Car>>,
+    mut gizmos: Gizmos,
+) {
+    const HALF_LENGTH: f32 = 20.0;
+    const HEIGHT_ABOVE_CAR: f32 = 14.0;
+
+    if !overlay.reward {
+        return;
+    }
+    let Some(transform) = focus.entity.and_then(|entity| car_query.get(entity).ok()) else {
+        return;
+    };
+
+    let reward = episode_state.current_tick_reward;
+    let anchor = transform.translation.truncate() + Vec2::new(0.0, HEIGHT_ABOVE_CAR);
+    let fraction = (finite_or_zero(reward) / REWARD_OVERLAY_FULL_SCALE).clamp(-1.0, 1.0);
+    let color = reward_color(reward, REWARD_OVERLAY_FULL_SCALE);
+
+    gizmos.line_2d(
+        anchor - Vec2::new(HALF_LENGTH, 0.0),
+        anchor + Vec2::new(HALF_LENGTH, 0.0),
+        Color::srgba(0.5, 0.5, 0.5, 0.4),
+    );
+    gizmos.line_2d(
+        anchor,
+        anchor + Vec2::new(fraction * HALF_LENGTH, 0.0),
+        color,
+    );
+    gizmos.circle_2d(Isometry2d::from_translation(anchor), 2.0, color);
 }
 
-/// Draws centreline and projection debug geometry using gizmos.
+/// Draws centreline and projection debug geometry using gizmos; the
+/// projection and lookahead are drawn for the focused car.
 pub fn draw_geometry_overlay_system(
     overlay: Res<DebugOverlayState>,
     observation_config: Res<ObservationConfig>,
+    focus: Res<FocusedCar>,
     track_query: Query<&Track>,
     car_query: Query<(&Transform, &TrackProgress, &Car), With<Car>>,
     mut gizmos: Gizmos,
@@ -63,12 +243,16 @@ pub fn draw_geometry_overlay_system(
         return;
     }
 
-    let Ok(track) = track_query.single() else {
+    let Some(centerline) = track_query
+        .single()
+        .ok()
+        .and_then(|track| track.centerline.as_ref())
+    else {
         return;
     };
 
     // Track centreline.
-    let pts = &track.centerline.points;
+    let pts = &centerline.points;
     if pts.len() >= 2 {
         let line_color = Color::srgb(0.1, 0.9, 0.1);
         for i in 0..pts.len() {
@@ -79,7 +263,9 @@ pub fn draw_geometry_overlay_system(
     }
 
     // Car projection.
-    for (transform, progress, car) in car_query.iter() {
+    if let Some((transform, progress, car)) =
+        focus.entity.and_then(|entity| car_query.get(entity).ok())
+    {
         let car_pos = Vec2::new(transform.translation.x, transform.translation.y);
 
         gizmos.line_2d(car_pos, progress.closest_point, Color::srgb(0.9, 0.9, 0.1));
@@ -120,8 +306,8 @@ pub fn draw_geometry_overlay_system(
         let mut previous_point = car_pos;
         for (index, distance) in observation_config.lookahead_distances.iter().enumerate() {
             let lookahead_s = progress.s + *distance;
-            let lookahead_point = track.centerline.point_at_s(lookahead_s);
-            let lookahead_tangent = track.centerline.tangent_at_s(lookahead_s);
+            let lookahead_point = centerline.point_at_s(lookahead_s);
+            let lookahead_tangent = centerline.tangent_at_s(lookahead_s);
 
             let t = if lookahead_count <= 1 {
                 0.0
@@ -147,37 +333,246 @@ pub fn draw_geometry_overlay_system(
     }
 }
 
-/// Draws raycast sensor lines and hit points.
+/// Draws recent crash contact points as fading crosses with the geometry
+/// overlay, so crash clusters show up as dense spots on problem corners.
+pub fn draw_collision_history_system(
+    overlay: Res<DebugOverlayState>,
+    history: Res<CollisionHistory>,
+    mut gizmos: Gizmos,
+) {
+    if !overlay.geometry {
+        return;
+    }
+
+    const ARM: f32 = 7.0;
+    for mark in history.marks() {
+        let color = Color::srgba(1.0, 0.15, 0.15, history.alpha(mark.age_s));
+        gizmos.line_2d(
+            mark.point + Vec2::new(-ARM, -ARM),
+            mark.point + Vec2::new(ARM, ARM),
+            color,
+        );
+        gizmos.line_2d(
+            mark.point + Vec2::new(-ARM, ARM),
+            mark.point + Vec2::new(ARM, -ARM),
+            color,
+        );
+    }
+}
+
+/// Draws raycast sensor lines from their mount points to their hits,
+/// coloured by hit class (a ray that reached its own max range reads as
+/// [`RayHitClass::None`]), and the occupancy patch (when enabled) as
+/// car-aligned squares tinted green on road and red off it.
 pub fn draw_sensor_overlay_system(
     overlay: Res<DebugOverlayState>,
-    observation_config: Res<ObservationConfig>,
-    car_query: Query<(&Transform, &SensorReadings), With<Car>>,
+    config: Option<Res<ObservationConfig>>,
+    car_query: Query<(&SensorReadings, &Transform), With<Car>>,
     mut gizmos: Gizmos,
 ) {
     if !overlay.sensors {
         return;
     }
 
-    for (transform, sensors) in &car_query {
-        let origin = transform.translation.truncate();
+    let draw_normals = config
+        .as_ref()
+        .is_some_and(|config| config.include_ray_incidence);
+    let occupancy_cell = config
+        .filter(|config| config.include_occupancy_grid)
+        .map(|config| config.occupancy_cell_size);
+    for (sensors, transform) in &car_query {
+        if let Some(cell) = occupancy_cell {
+            let forward = (transform.rotation * Vec3::X).truncate();
+            let rotation = Rot2::radians(forward.y.atan2(forward.x));
+            let cells = sensors.occupancy.iter().zip(&sensors.occupancy_centers);
+            for (occupied, center) in cells {
+                let color = if *occupied > 0.5 {
+                    Color::srgba(0.2, 0.9, 0.3, 0.5)
+                } else {
+                    Color::srgba(1.0, 0.2, 0.2, 0.5)
+                };
+                gizmos.rect_2d(
+                    Isometry2d::new(*center, rotation),
+                    Vec2::splat(cell * 0.9),
+                    color,
+                );
+            }
+        }
 
-        for index in 0..sensors.ray_distances.len() {
-            let hit = sensors.ray_hits[index];
-            let distance = sensors.ray_distances[index];
-            let maxed = distance >= observation_config.ray_max_range - 1e-3;
-            let line_color = if maxed {
-                Color::srgb(0.6, 0.6, 0.6)
-            } else {
-                Color::srgb(1.0, 0.5, 0.1)
-            };
-            let hit_color = if maxed {
-                Color::srgb(0.5, 0.5, 0.5)
-            } else {
-                Color::srgb(1.0, 0.2, 0.2)
+        let count = sensors.ray_hits.len();
+        let rays = sensors.ray_origins.iter().zip(&sensors.ray_hits);
+        for (index, ((origin, hit), class)) in rays.zip(&sensors.ray_classes).enumerate() {
+            let (class_color, hit_color) = ray_class_colors(*class);
+            let line_color = match overlay.ray_style {
+                RayOverlayStyle::HitClass => class_color,
+                RayOverlayStyle::Index | RayOverlayStyle::IndexLabeled => {
+                    ray_index_color(index, count)
+                }
             };
+            gizmos.line_2d(*origin, *hit, line_color);
+            gizmos.circle_2d(Isometry2d::from_translation(*hit), 2.0, hit_color);
+            // Short tick along the surface normal at the hit.
+            let normal = sensors
+                .ray_normals
+                .get(index)
+                .copied()
+                .unwrap_or(Vec2::ZERO);
+            if draw_normals && normal != Vec2::ZERO {
+                gizmos.line_2d(*hit, *hit + normal * 10.0, Color::srgb(0.95, 0.95, 0.95));
+            }
+        }
+    }
+}
 
-            gizmos.line_2d(origin, hit, line_color);
-            gizmos.circle_2d(Isometry2d::from_translation(hit), 2.0, hit_color);
+/// Colour of ray `index` out of `count` on a hue gradient from red (index
+/// 0, the first ray feature) through green to violet (the last). The hue
+/// stops short of wrapping back to red, so the ends stay distinguishable.
+pub fn ray_index_color(index: usize, count: usize) -> Color {
+    const HUE_SPAN: f32 = 280.0;
+    let fraction = index as f32 / count.saturating_sub(1).max(1) as f32;
+    Color::hsl(fraction.clamp(0.0, 1.0) * HUE_SPAN, 0.9, 0.55)
+}
+
+/// World-space index label of one ray of the focused car.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct RayIndexLabel(pub usize);
+
+/// Keeps one index label at each ray hit of the focused car while the
+/// sensor overlay uses [`RayOverlayStyle::IndexLabeled`], and removes the
+/// labels otherwise.
+pub fn sync_ray_index_labels_system(
+    mut commands: Commands,
+    overlay: Res<DebugOverlayState>,
+    focus: Res<FocusedCar>,
+    car_query: Query<&SensorReadings, With<Car>>,
+    mut label_query: Query<(Entity, &RayIndexLabel, &mut Transform)>,
+) {
+    const HIT_OFFSET: Vec2 = Vec2::new(0.0, 8.0);
+    const LABEL_Z: f32 = 5.0;
+
+    let sensors = focus
+        .entity
+        .and_then(|entity| car_query.get(entity).ok())
+        .filter(|_| overlay.sensors && overlay.ray_style == RayOverlayStyle::IndexLabeled);
+    let Some(sensors) = sensors else {
+        for (entity, _, _) in &label_query {
+            commands.entity(entity).despawn();
         }
+        return;
+    };
+
+    let count = sensors.ray_hits.len();
+    let mut labelled = vec![false; count];
+    for (entity, label, mut transform) in &mut label_query {
+        match sensors.ray_hits.get(label.0) {
+            Some(hit) if !labelled[label.0] => {
+                labelled[label.0] = true;
+                transform.translation = (*hit + HIT_OFFSET).extend(LABEL_Z);
+            }
+            _ => commands.entity(entity).despawn(),
+        }
+    }
+    for (index, hit) in sensors.ray_hits.iter().enumerate() {
+        if !labelled[index] {
+            commands.spawn((
+                Text2d::new(index.to_string()),
+                TextFont::from_font_size(11.0),
+                TextColor(ray_index_color(index, count)),
+                Transform::from_translation((*hit + HIT_OFFSET).extend(LABEL_Z)),
+                RayIndexLabel(index),
+            ));
+        }
+    }
+}
+
+/// Line and hit-point colours of a ray by what stopped it.
+fn ray_class_colors(class: RayHitClass) -> (Color, Color) {
+    match class {
+        RayHitClass::None => (Color::srgb(0.6, 0.6, 0.6), Color::srgb(0.5, 0.5, 0.5)),
+        RayHitClass::Wall => (Color::srgb(1.0, 0.5, 0.1), Color::srgb(1.0, 0.2, 0.2)),
+        RayHitClass::OffGrid => (Color::srgb(0.7, 0.3, 1.0), Color::srgb(0.55, 0.15, 0.9)),
+        RayHitClass::Obstacle => (Color::srgb(1.0, 0.9, 0.2), Color::srgb(0.9, 0.75, 0.1)),
+        RayHitClass::Car => (Color::srgb(0.2, 0.8, 1.0), Color::srgb(0.1, 0.55, 1.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CollisionHistory, ray_index_color, reward_color};
+    use bevy::prelude::*;
+
+    #[test]
+    fn reward_colors_run_from_red_through_neutral_to_green() {
+        let rgb = |reward: f32| {
+            let color = reward_color(reward, 1.0).to_srgba();
+            (color.red, color.green, color.blue)
+        };
+        let saturation = |reward: f32| Hsla::from(reward_color(reward, 1.0)).saturation;
+
+        let (r, g, b) = rgb(0.0);
+        assert!((r - g).abs() < 1e-6 && (g - b).abs() < 1e-6, "neutral");
+        assert_eq!(saturation(0.0), 0.0);
+
+        let (r, g, _) = rgb(0.8);
+        assert!(g > r, "positive is green");
+        let (r, g, _) = rgb(-0.8);
+        assert!(r > g, "negative is red");
+
+        assert!(saturation(0.2) < saturation(0.6));
+        assert!(saturation(-0.2) < saturation(-0.6));
+        assert_eq!(rgb(1.0), rgb(5.0), "clamped at full scale");
+        assert_eq!(rgb(f32::NAN), rgb(0.0));
+    }
+
+    #[test]
+    fn ray_index_colors_are_distinct_across_the_fan_and_stable_per_index() {
+        for count in [1, 5, 11, 19, 64] {
+            let hues: Vec<f32> = (0..count)
+                .map(|index| Hsla::from(ray_index_color(index, count)).hue)
+                .collect();
+            for (index, hue) in hues.iter().enumerate() {
+                assert_eq!(ray_index_color(index, count), ray_index_color(index, count));
+                assert!(hue.is_finite());
+            }
+            assert!(
+                hues.windows(2).all(|pair| pair[1] - pair[0] > 1.0),
+                "{count} rays: {hues:?}"
+            );
+            // The gradient never wraps, so the first and last rays differ.
+            if count > 1 {
+                assert!(hues[count - 1] - hues[0] > 180.0);
+            }
+        }
+    }
+
+    #[test]
+    fn collision_history_caps_entries_and_fades_with_age() {
+        let mut history = CollisionHistory {
+            capacity: 3,
+            lifetime_s: 10.0,
+            ..CollisionHistory::default()
+        };
+        for i in 0..5 {
+            history.push(Vec2::new(i as f32, 0.0));
+            history.advance(1.0);
+        }
+
+        let xs: Vec<f32> = history.marks().map(|mark| mark.point.x).collect();
+        assert_eq!(xs, [2.0, 3.0, 4.0]);
+
+        let alphas: Vec<f32> = history
+            .marks()
+            .map(|mark| history.alpha(mark.age_s))
+            .collect();
+        assert!(
+            alphas.windows(2).all(|pair| pair[0] < pair[1]),
+            "{alphas:?}"
+        );
+        assert_eq!(history.alpha(0.0), 1.0);
+        assert_eq!(history.alpha(10.0), 0.0);
+
+        history.advance(8.5);
+        let xs: Vec<f32> = history.marks().map(|mark| mark.point.x).collect();
+        assert_eq!(xs, [4.0]);
     }
 }
@@ -1,14 +1,19 @@
 use bevy::prelude::*;
 
+use crate::debug::camera::{
+    CameraMode, FreeCamera, camera_mode_toggle_system, free_camera_system, overview_camera_system,
+};
+use crate::debug::focus::{FocusedCar, focused_car_system, follow_focused_car_system};
 use crate::debug::hud::{
     DrivingHudEpisodeAccumulator, DrivingHudHistory, DrivingHudStats,
     capture_driving_hud_episode_metrics_system, spawn_driving_hud_system,
-    update_driving_hud_stats_system, update_driving_hud_text_system,
-    update_driving_hud_visibility_system,
+    update_countdown_banner_system, update_driving_hud_stats_system,
+    update_driving_hud_text_system, update_driving_hud_visibility_system,
 };
 use crate::debug::overlays::{
-    DebugOverlayState, debug_overlay_toggle_system, draw_geometry_overlay_system,
-    draw_sensor_overlay_system,
+    CollisionHistory, DebugOverlayState, debug_overlay_toggle_system,
+    draw_collision_history_system, draw_geometry_overlay_system, draw_reward_overlay_system,
+    draw_sensor_overlay_system, record_collision_history_system, sync_ray_index_labels_system,
 };
 use crate::sim::sets::SimSet;
 
@@ -18,13 +23,21 @@ pub struct DebugPlugin;
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DebugOverlayState>()
+            .init_resource::<FocusedCar>()
+            .init_resource::<CameraMode>()
+            .init_resource::<FreeCamera>()
+            .init_resource::<CollisionHistory>()
             .init_resource::<DrivingHudStats>()
             .init_resource::<DrivingHudHistory>()
             .init_resource::<DrivingHudEpisodeAccumulator>()
             .add_systems(Startup, spawn_driving_hud_system)
             .add_systems(
                 FixedUpdate,
-                update_driving_hud_stats_system.in_set(SimSet::Measurement),
+                (
+                    update_driving_hud_stats_system,
+                    record_collision_history_system,
+                )
+                    .in_set(SimSet::Measurement),
             )
             .add_systems(
                 FixedUpdate,
@@ -36,10 +49,22 @@ impl Plugin for DebugPlugin {
                 Update,
                 (
                     debug_overlay_toggle_system,
+                    (
+                        focused_car_system,
+                        camera_mode_toggle_system,
+                        follow_focused_car_system,
+                        free_camera_system,
+                        overview_camera_system,
+                    )
+                        .chain(),
                     draw_geometry_overlay_system,
+                    draw_collision_history_system,
                     draw_sensor_overlay_system,
+                    sync_ray_index_labels_system,
+                    draw_reward_overlay_system,
                     update_driving_hud_visibility_system,
                     update_driving_hud_text_system,
+                    update_countdown_banner_system,
                 ),
             );
     }
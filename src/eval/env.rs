@@ -0,0 +1,897 @@
+use std::time::Instant;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{
+    ActionIndexError, ActionMode, ActionSmoothing, ActionState, CarAction, SteeringAssist,
+    action_smoothing_system,
+};
+use crate::agent::controller::{Controller, ControllerContext};
+use crate::agent::exploration::{ExplorationNoise, exploration_noise_system};
+use crate::agent::observation::{
+    ObservationConfig, ObservationDelayBuffer, ObservationVector, SensorReadings,
+    apply_observation_delay_system, build_observation_vector_system, update_sensor_readings_system,
+};
+use crate::agent::spaces::{ActionSpace, DiscreteActionSpace, ObservationSpace};
+use crate::agent::vision::VisionFrame;
+use crate::brain::types::AgentMode;
+use crate::config::AppConfig;
+use crate::game::car::Car;
+use crate::game::checkpoints::{CheckpointProgress, setup_checkpoint_gates_system};
+use crate::game::collision::{
+    CarCollisionConfig, CarCollisionEvent, CollisionConfig, CollisionEvent, OffTrackState,
+    OffTrackWarning, car_collision_system, collision_detection_system,
+};
+use crate::game::curriculum::apply_initial_curriculum_system;
+use crate::game::episode::{
+    EpisodeEndReason, EpisodeEndedEvent, EpisodeMovingAverages, EpisodeResetRequest, EpisodeState,
+    EpisodeSummary, LapValidation, episode_loop_system, normalize_reward_to_track_system,
+};
+use crate::game::physics::car_physics_system;
+use crate::game::progress::{TrackProgress, update_track_progress_system};
+use crate::game::reward::RewardStack;
+use crate::game::seeding::track_checksum;
+use crate::maps::raceline::setup_raceline_system;
+use crate::maps::track::Track;
+use crate::sim::clock::{SimClock, advance_sim_clock_system};
+use crate::sim::rng::{EpisodeRng, derive_episode_seed};
+use crate::sim::stats::RunStats;
+
+/// Result of [`HeadlessEnv::step_frames`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameSkipStep {
+    /// Set when one of the ticks ended an episode; no ticks follow it.
+    pub outcome: Option<EpisodeOutcome>,
+    /// Sum of the rewards of the ticks taken.
+    pub reward: f32,
+    /// Ticks advanced: the frame skip, or fewer if an episode ended.
+    pub ticks: u32,
+}
+
+/// Summary of one finished episode in a [`HeadlessEnv`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpisodeOutcome {
+    pub episode: u32,
+    pub seed: u64,
+    pub end_reason: EpisodeEndReason,
+    pub ticks: u32,
+    pub episode_return: f32,
+    pub best_progress_fraction: f32,
+}
+
+impl EpisodeOutcome {
+    pub fn summary(&self) -> EpisodeSummary {
+        EpisodeSummary {
+            end_reason: self.end_reason,
+            ticks: self.ticks,
+            episode_return: self.episode_return,
+            best_progress: self.best_progress_fraction,
+        }
+    }
+}
+
+/// Windowless single-car simulation on one track.
+///
+/// Runs the same fixed-tick systems as the game (exploration noise, smoothing, physics,
+/// collision, progress, episode loop, sensors) in the same order, without
+/// rendering, input, or learning. Each episode's [`EpisodeRng`] is seeded
+/// from `master_seed` exactly as in a windowed run. Observation noise follows
+/// the config and is off only when [`crate::sim::evaluation::EvaluationMode`]
+/// is enabled, as in the windowed app. [`RunStats`] counts every step, with
+/// the wall time between steps standing in for the render frame.
+pub struct HeadlessEnv {
+    app: App,
+    master_seed: u64,
+    last_step: Instant,
+}
+
+impl HeadlessEnv {
+    /// Panics when `config.observation` fails
+    /// [`ObservationConfig::validate`], since no sensor pipeline can run on
+    /// it.
+    pub fn new(track: Track, config: &AppConfig, master_seed: u64) -> Self {
+        if let Err(err) = config.observation.validate() {
+            panic!("invalid observation config: {err}");
+        }
+        let (spawn_position, spawn_rotation) = (track.spawn_position, track.spawn_rotation);
+
+        let mut app = App::new();
+        config.insert_into(&mut app);
+        app.insert_resource(Time::<Fixed>::from_hz(60.0))
+            .insert_resource(AgentMode::Learned)
+            .init_resource::<SimClock>()
+            .init_resource::<RunStats>()
+            .init_resource::<ActionState>()
+            .init_resource::<ExplorationNoise>()
+            .init_resource::<SteeringAssist>()
+            .init_resource::<EpisodeState>()
+            .init_resource::<EpisodeResetRequest>()
+            .init_resource::<EpisodeMovingAverages>()
+            .init_resource::<RewardStack>()
+            .init_resource::<CollisionConfig>()
+            .init_resource::<CarCollisionConfig>()
+            .insert_resource(EpisodeRng::from_seed(
+                1,
+                derive_episode_seed(master_seed, 1),
+            ))
+            .add_message::<CollisionEvent>()
+            .add_message::<OffTrackWarning>()
+            .add_message::<CarCollisionEvent>()
+            .add_message::<EpisodeEndedEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    advance_sim_clock_system,
+                    exploration_noise_system,
+                    action_smoothing_system,
+                    car_physics_system,
+                    collision_detection_system,
+                    car_collision_system,
+                    update_track_progress_system,
+                    episode_loop_system,
+                    update_sensor_readings_system,
+                    build_observation_vector_system,
+                    apply_observation_delay_system,
+                )
+                    .chain(),
+            );
+
+        let mut sensor_readings = SensorReadings::for_config(&config.observation);
+        sensor_readings.previous_heading = spawn_rotation;
+        // Project at spawn so progress reads the spawn pose before the first
+        // tick, including through a start countdown.
+        let mut progress = TrackProgress::default();
+        if let Some(centerline) = &track.centerline {
+            progress.set_projection(&centerline.project(spawn_position));
+        }
+        app.world_mut().spawn(track);
+        app.world_mut().spawn((
+            Transform::from_xyz(spawn_position.x, spawn_position.y, 10.0)
+                .with_rotation(Quat::from_rotation_z(spawn_rotation)),
+            Car::from_params(config.car),
+            progress,
+            CheckpointProgress::default(),
+            LapValidation::default(),
+            OffTrackState::default(),
+            sensor_readings,
+            ObservationVector::for_config(&config.observation),
+            ObservationDelayBuffer::default(),
+        ));
+
+        // The game runs these in `PostStartup`.
+        let world = app.world_mut();
+        let _ = world.run_system_once(setup_checkpoint_gates_system);
+        let _ = world.run_system_once(setup_raceline_system);
+        let _ = world.run_system_once(normalize_reward_to_track_system);
+        let _ = world.run_system_once(apply_initial_curriculum_system);
+
+        Self {
+            app,
+            master_seed,
+            last_step: Instant::now(),
+        }
+    }
+
+    /// Throughput of this env since it was built.
+    pub fn run_stats(&self) -> &RunStats {
+        self.app.world().resource::<RunStats>()
+    }
+
+    /// Observation produced by the last tick (zeros before the first).
+    pub fn observation(&mut self) -> ObservationVector {
+        let mut cars = self.app.world_mut().query::<&ObservationVector>();
+        cars.single(self.app.world())
+            .cloned()
+            .expect("headless env has one car")
+    }
+
+    /// Centreline projection of the car after the last tick.
+    pub fn progress(&mut self) -> TrackProgress {
+        let mut cars = self.app.world_mut().query::<&TrackProgress>();
+        *cars
+            .single(self.app.world())
+            .expect("headless env has one car")
+    }
+
+    /// Sensor readings of the car after the last tick.
+    pub fn sensors(&mut self) -> SensorReadings {
+        let mut cars = self.app.world_mut().query::<&SensorReadings>();
+        cars.single(self.app.world())
+            .cloned()
+            .expect("headless env has one car")
+    }
+
+    /// [`track_checksum`] of the track being driven.
+    pub fn track_checksum(&mut self) -> u64 {
+        let mut tracks = self.app.world_mut().query::<&Track>();
+        track_checksum(
+            &tracks
+                .single(self.app.world())
+                .expect("headless env has one track")
+                .grid,
+        )
+    }
+
+    /// Latest camera observation. Always `None` here: the env builds no
+    /// render pipeline, and [`VisionFrame`]s only exist in windowed runs with
+    /// [`crate::agent::vision::VisionConfig::enabled`] set.
+    pub fn image_observation(&self) -> Option<&VisionFrame> {
+        self.app
+            .world()
+            .get_resource::<VisionFrame>()
+            .filter(|frame| !frame.rgba.is_empty())
+    }
+
+    /// Layout and bounds of [`Self::observation`].
+    pub fn observation_space(&self) -> ObservationSpace {
+        ObservationSpace::from_config(self.app.world().resource::<ObservationConfig>())
+    }
+
+    /// Layout and ranges of the actions [`Self::step`] accepts, with the
+    /// index count [`Self::step_discrete`] accepts in discrete mode.
+    pub fn action_space(&self) -> ActionSpace {
+        let world = self.app.world();
+        ActionSpace::for_mode(*world.resource::<ActionMode>())
+            .with_rate_limits(world.resource::<ActionSmoothing>().rate_limits)
+    }
+
+    /// The steering / throttle pair behind every index
+    /// [`Self::step_discrete`] accepts, or `None` in continuous mode.
+    pub fn discrete_action_space(&self) -> Option<DiscreteActionSpace> {
+        self.app
+            .world()
+            .resource::<ActionMode>()
+            .discrete_adapter()
+            .map(|adapter| DiscreteActionSpace::from_adapter(&adapter))
+    }
+
+    /// Reward earned by the last tick.
+    pub fn tick_reward(&self) -> f32 {
+        self.app
+            .world()
+            .resource::<EpisodeState>()
+            .current_tick_reward
+    }
+
+    /// Ends the current episode on the next [`Self::step`], which then
+    /// reports it as a [`EpisodeEndReason::Timeout`].
+    pub fn request_reset(&mut self) {
+        self.app
+            .world_mut()
+            .resource_mut::<EpisodeResetRequest>()
+            .pending = true;
+    }
+
+    /// Random streams of the episode the next tick belongs to.
+    pub fn episode_rng(&self) -> &EpisodeRng {
+        self.app.world().resource::<EpisodeRng>()
+    }
+
+    /// Fixed timestep in seconds.
+    pub fn tick_seconds(&self) -> f32 {
+        self.app
+            .world()
+            .resource::<Time<Fixed>>()
+            .timestep()
+            .as_secs_f32()
+    }
+
+    /// Decodes a discrete action index and steps with it. Fails without
+    /// advancing in continuous mode or for an out-of-range index.
+    pub fn step_discrete(
+        &mut self,
+        index: usize,
+    ) -> Result<Option<EpisodeOutcome>, ActionIndexError> {
+        let action = self.decode_discrete(index)?;
+        Ok(self.step(action))
+    }
+
+    /// The steering / throttle pair behind a discrete action index.
+    pub fn decode_discrete(&self, index: usize) -> Result<CarAction, ActionIndexError> {
+        self.app.world().resource::<ActionMode>().decode(index)
+    }
+
+    /// Steps with the action `controller` picks from the current car state,
+    /// starting its next episode when this tick ends one.
+    pub fn step_controller(&mut self, controller: &mut dyn Controller) -> Option<EpisodeOutcome> {
+        let dt = self.tick_seconds();
+        let world = self.app.world_mut();
+        let mut cars = world.query::<(&ObservationVector, &TrackProgress, &Car, &Transform)>();
+        let world = &*world;
+        let (observation, progress, car, transform) =
+            cars.single(world).expect("headless env has one car");
+        let ctx = ControllerContext::new(
+            observation,
+            progress,
+            car,
+            transform,
+            Some(world.resource::<EpisodeState>()),
+            dt,
+        );
+        let action = controller.act(&ctx);
+        if controller.wants_episode_end() {
+            self.request_reset();
+        }
+
+        let outcome = self.step(action);
+        if let Some(outcome) = &outcome {
+            controller.begin_episode(outcome.episode + 1);
+        }
+        outcome
+    }
+
+    /// Applies `action` for `frame_skip` consecutive ticks (at least one)
+    /// and returns their summed reward. Stops early on the tick that ends an
+    /// episode, so a skipped step never spans two episodes.
+    pub fn step_frames(&mut self, action: CarAction, frame_skip: u32) -> FrameSkipStep {
+        let mut step = FrameSkipStep {
+            outcome: None,
+            reward: 0.0,
+            ticks: 0,
+        };
+        while step.ticks < frame_skip.max(1) {
+            step.outcome = self.step(action);
+            step.reward += self.tick_reward();
+            step.ticks += 1;
+            if step.outcome.is_some() {
+                break;
+            }
+        }
+        step
+    }
+
+    /// Applies `action` as the controller's desired action and advances one
+    /// fixed tick. Returns the outcome when this tick ended an episode; the
+    /// next episode is already reset and seeded. In discrete mode the action
+    /// is snapped to the nearest discrete action.
+    pub fn step(&mut self, action: CarAction) -> Option<EpisodeOutcome> {
+        let seed = self.episode_rng().seed;
+        let world = self.app.world_mut();
+        world.resource_mut::<ActionState>().desired = action;
+        let timestep = world.resource::<Time<Fixed>>().timestep();
+        world.resource_mut::<Time<Fixed>>().advance_by(timestep);
+        world.run_schedule(FixedUpdate);
+
+        let now = Instant::now();
+        let wall_dt_s = now.duration_since(self.last_step).as_secs_f64();
+        self.last_step = now;
+        let tick = world.resource::<SimClock>().tick;
+        let episodes = world
+            .resource::<EpisodeState>()
+            .current_episode
+            .saturating_sub(1);
+        world
+            .resource_mut::<RunStats>()
+            .record_frame(wall_dt_s, tick, episodes, false);
+        self.take_outcome(seed)
+    }
+
+    /// Ends the current episode where the car stands, as a
+    /// [`EpisodeEndReason::Timeout`], and returns the car to the spawn with
+    /// fresh sensors and observation. Unlike [`Self::request_reset`] no
+    /// physics tick runs and the clock does not advance. Returns `None`
+    /// while the start countdown holds the car, since no episode has begun.
+    pub fn reset_episode(&mut self) -> Option<EpisodeOutcome> {
+        let seed = self.episode_rng().seed;
+        self.request_reset();
+        let world = self.app.world_mut();
+        // The last tick's systems already read these; a one-shot reader
+        // starts from the oldest message and would see them again.
+        world.resource_mut::<Messages<CollisionEvent>>().clear();
+        world.resource_mut::<Messages<OffTrackWarning>>().clear();
+        world.resource_mut::<Messages<CarCollisionEvent>>().clear();
+        let _ = world.run_system_once(episode_loop_system);
+        let _ = world.run_system_once(update_sensor_readings_system);
+        let _ = world.run_system_once(build_observation_vector_system);
+        let _ = world.run_system_once(apply_observation_delay_system);
+        world.resource_mut::<EpisodeResetRequest>().pending = false;
+        self.take_outcome(seed)
+    }
+
+    /// Reads the episode the last run of [`episode_loop_system`] ended, if
+    /// any, and seeds the next one.
+    fn take_outcome(&mut self, seed: u64) -> Option<EpisodeOutcome> {
+        let world = self.app.world_mut();
+        let state = world.resource::<EpisodeState>();
+        let end_reason = state.current_tick_end_reason?;
+        let outcome = EpisodeOutcome {
+            episode: state.current_episode - 1,
+            seed,
+            end_reason,
+            ticks: state.last_episode_ticks,
+            episode_return: state.last_episode_return,
+            best_progress_fraction: state.last_episode_best_progress_fraction,
+        };
+
+        let next = state.current_episode;
+        world.insert_resource(EpisodeRng::from_seed(
+            next,
+            derive_episode_seed(self.master_seed, next),
+        ));
+        Some(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HeadlessEnv;
+    use crate::agent::action::{ActionIndexError, ActionMode, CarAction};
+    use crate::agent::exploration::ExplorationNoiseKind;
+    use crate::agent::pid::{PidController, PidControllerConfig};
+    use crate::agent::spaces::ObservationManifest;
+    use crate::config::AppConfig;
+    use crate::game::checkpoints::build_checkpoint_gates;
+    use crate::game::collision::CollisionConfig;
+    use crate::game::curriculum::{Curriculum, LinearSchedule};
+    use crate::game::episode::{EpisodeConfig, EpisodeEndReason, EpisodeState};
+    use crate::maps::fixtures::ring_track;
+    use crate::sim::clock::SimClock;
+    use crate::sim::rng::derive_episode_seed;
+
+    #[test]
+    fn full_throttle_into_the_wall_ends_in_a_seeded_crash() {
+        let mut env = HeadlessEnv::new(ring_track(), &AppConfig::default(), 7);
+        let full = CarAction {
+            steering: 0.0,
+            throttle: 1.0,
+        };
+
+        let outcome = (0..600).find_map(|_| env.step(full)).expect("episode ends");
+
+        assert_eq!(outcome.episode, 1);
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Crash);
+        assert_eq!(outcome.seed, derive_episode_seed(7, 1));
+        assert_eq!(env.episode_rng().episode, 2);
+        assert_eq!(env.episode_rng().seed, derive_episode_seed(7, 2));
+        assert_eq!(env.observation_space().dim, env.observation().values.len());
+        assert_eq!(env.action_space().dim, 2);
+        assert_eq!(env.step_discrete(0), Err(ActionIndexError::NotDiscrete));
+        assert_eq!(env.discrete_action_space(), None);
+    }
+
+    #[test]
+    fn free_roam_drives_off_road_without_reset_or_crash_penalty() {
+        let config = AppConfig {
+            collision: CollisionConfig {
+                enabled: false,
+                ..CollisionConfig::default()
+            },
+            ..AppConfig::default()
+        };
+        let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+        let full = CarAction {
+            steering: 0.0,
+            throttle: 1.0,
+        };
+
+        // The same run crashes into the wall with collisions enabled.
+        for tick in 0..600 {
+            assert_eq!(env.step(full), None, "tick {tick}");
+            assert!(
+                env.tick_reward() > config.episode.crash_penalty * 0.5,
+                "tick {tick} paid a crash penalty"
+            );
+        }
+        assert!(
+            env.progress().distance > 150.0,
+            "car should have left the road, {} from the centreline",
+            env.progress().distance
+        );
+    }
+
+    #[test]
+    fn timeout_extensions_return_to_the_last_checkpoint_until_exhausted() {
+        let config = AppConfig {
+            episode: EpisodeConfig {
+                timeout_s: 2.0,
+                timeout_checkpoint_extensions: 2,
+                ..EpisodeConfig::default()
+            },
+            ..AppConfig::default()
+        };
+        let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+        let gates = build_checkpoint_gates(
+            ring_track().centerline.as_ref().unwrap(),
+            config.episode.checkpoint_gate_count,
+            50.0,
+        );
+        let mut controller = PidController::new(PidControllerConfig::default());
+
+        // The follower only moves forward, so a drop in `s` is a relocation.
+        let (mut best_s, mut previous_s) = (0.0_f32, 0.0_f32);
+        let mut relocations = Vec::new();
+        let outcome = (0..2000)
+            .find_map(|_| {
+                let outcome = env.step_controller(&mut controller);
+                let progress = env.progress();
+                if outcome.is_none() && progress.s < previous_s - 5.0 {
+                    relocations.push((best_s, progress));
+                    assert_eq!(env.episode_rng().episode, 1, "episode continues");
+                }
+                best_s = best_s.max(progress.s);
+                previous_s = progress.s;
+                outcome
+            })
+            .expect("episode ends");
+
+        assert_eq!(relocations.len(), 2);
+        for (best_s, progress) in relocations {
+            let gate = gates
+                .gates
+                .iter()
+                .rev()
+                .find(|gate| gate.s <= best_s)
+                .expect("passed a gate within the first window");
+            assert!(
+                (progress.s - gate.s).abs() < 1.0,
+                "{} vs {}",
+                progress.s,
+                gate.s
+            );
+            assert!(progress.distance < 1.0);
+        }
+        assert_eq!(outcome.episode, 1);
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Timeout);
+        assert!(outcome.ticks as f32 / 60.0 >= 3.0 * 2.0 - 0.05);
+    }
+
+    #[test]
+    fn lap_progress_and_time_remaining_run_down_and_reset_with_the_episode() {
+        let mut config = AppConfig {
+            episode: EpisodeConfig {
+                timeout_s: 1.0,
+                ..EpisodeConfig::default()
+            },
+            ..AppConfig::default()
+        };
+        config.observation.include_lap_progress = true;
+        config.observation.include_time_remaining = true;
+        let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+        let mut controller = PidController::new(PidControllerConfig::default());
+        let tail = |env: &mut HeadlessEnv| {
+            let values = env.observation().values;
+            (values[values.len() - 2], values[values.len() - 1])
+        };
+        let names = env.observation_space().features;
+        assert_eq!(names[names.len() - 2].name, "lap_progress");
+        assert_eq!(names[names.len() - 1].name, "time_remaining");
+
+        assert!(env.step_controller(&mut controller).is_none());
+        let (start_progress, remaining) = tail(&mut env);
+        assert!((start_progress - env.progress().fraction).abs() < 1e-6);
+        assert!((remaining - 59.0 / 60.0).abs() < 1e-4, "{remaining}");
+
+        for _ in 1..59 {
+            assert!(env.step_controller(&mut controller).is_none());
+        }
+        let (late_progress, remaining) = tail(&mut env);
+        assert!((late_progress - env.progress().fraction).abs() < 1e-6);
+        assert!(late_progress > start_progress);
+        assert!((remaining - 1.0 / 60.0).abs() < 1e-4, "{remaining}");
+
+        let outcome = env.step_controller(&mut controller).expect("timeout");
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Timeout);
+        let (reset_progress, remaining) = tail(&mut env);
+        assert!((reset_progress - env.progress().fraction).abs() < 1e-6);
+        assert!(reset_progress < start_progress);
+        assert_eq!(remaining, 1.0);
+    }
+
+    #[test]
+    fn the_start_countdown_holds_the_car_and_delays_the_episode_clock() {
+        let config = AppConfig {
+            episode: EpisodeConfig {
+                timeout_s: 1.0,
+                countdown_ticks: 30,
+                ..EpisodeConfig::default()
+            },
+            ..AppConfig::default()
+        };
+        let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+        let spawn = env.progress().s;
+        let full = CarAction {
+            steering: 0.0,
+            throttle: 1.0,
+        };
+
+        for _ in 0..30 {
+            assert!(env.step(full).is_none());
+            assert_eq!(env.sensors().speed, 0.0);
+            assert_eq!(env.tick_reward(), 0.0);
+        }
+        assert_eq!(env.progress().s, spawn);
+
+        // The 60-tick timeout only starts counting once the countdown ends.
+        let idle = CarAction::default();
+        for _ in 0..59 {
+            assert!(env.step(idle).is_none());
+        }
+        let outcome = env.step(idle).expect("timeout");
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Timeout);
+        assert_eq!(outcome.ticks, 60);
+
+        // The next episode counts down again.
+        assert!(env.step(full).is_none());
+        assert_eq!(env.sensors().speed, 0.0);
+    }
+
+    #[test]
+    fn frame_skip_repeats_the_action_and_sums_the_rewards() {
+        let config = AppConfig::default();
+        let full = CarAction {
+            steering: 0.0,
+            throttle: 1.0,
+        };
+        let mut single = HeadlessEnv::new(ring_track(), &config, 7);
+        let mut rewards = Vec::new();
+        for _ in 0..12 {
+            assert!(single.step(full).is_none());
+            rewards.push(single.tick_reward());
+        }
+
+        let mut skipped = HeadlessEnv::new(ring_track(), &config, 7);
+        for chunk in rewards.chunks(4) {
+            let step = skipped.step_frames(full, 4);
+            assert_eq!(step.ticks, 4);
+            assert!(step.outcome.is_none());
+            assert_eq!(step.reward, chunk.iter().sum::<f32>());
+        }
+        assert_eq!(skipped.observation().values, single.observation().values);
+
+        // Zero still advances one tick.
+        assert_eq!(skipped.step_frames(full, 0).ticks, 1);
+    }
+
+    #[test]
+    fn observation_noise_applies_unless_the_run_is_an_evaluation() {
+        let observe = |evaluation: bool, noise: f32| {
+            let mut config = AppConfig::default();
+            config.observation.noise.speed = noise;
+            config.evaluation.enabled = evaluation;
+            let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+            for _ in 0..5 {
+                assert!(env.step(CarAction::default()).is_none());
+            }
+            env.observation().values
+        };
+
+        let clean = observe(false, 0.0);
+        assert_ne!(observe(false, 0.05), clean);
+        assert_eq!(observe(false, 0.05), observe(false, 0.05));
+        assert_eq!(observe(true, 0.05), clean);
+    }
+
+    #[test]
+    fn exploration_noise_perturbs_training_runs_but_not_evaluation() {
+        let drive = |evaluation: bool, sigma: f32| {
+            let mut config = AppConfig::default();
+            config.exploration.kind = ExplorationNoiseKind::Gaussian;
+            config.exploration.steering.sigma = sigma;
+            config.evaluation.enabled = evaluation;
+            let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+            let full = CarAction {
+                steering: 0.0,
+                throttle: 1.0,
+            };
+            for _ in 0..10 {
+                assert!(env.step(full).is_none());
+            }
+            env.observation().values
+        };
+
+        let clean = drive(false, 0.0);
+        assert_ne!(drive(false, 0.3), clean);
+        assert_eq!(drive(false, 0.3), drive(false, 0.3));
+        assert_eq!(drive(true, 0.3), clean);
+    }
+
+    #[test]
+    fn run_stats_count_headless_ticks_and_episodes() {
+        let mut env = HeadlessEnv::new(ring_track(), &AppConfig::default(), 7);
+        for _ in 0..10 {
+            assert!(env.step(CarAction::default()).is_none());
+        }
+        assert_eq!(env.run_stats().ticks, 10);
+        assert_eq!(env.run_stats().episodes, 0);
+
+        env.request_reset();
+        assert!(env.step(CarAction::default()).is_some());
+        let stats = env.run_stats();
+        assert_eq!(
+            (stats.ticks, stats.episodes, stats.stepped_ticks),
+            (11, 1, 0)
+        );
+        assert!(stats.wall_s > 0.0 && stats.active_wall_s == stats.wall_s);
+    }
+
+    #[test]
+    fn the_sim_clock_keeps_counting_across_episode_ends() {
+        let mut env = HeadlessEnv::new(ring_track(), &AppConfig::default(), 7);
+        let full = CarAction {
+            steering: 0.0,
+            throttle: 1.0,
+        };
+
+        let mut ticks = 0u64;
+        let mut outcomes = Vec::new();
+        while outcomes.len() < 2 {
+            ticks += 1;
+            outcomes.extend(env.step(full));
+            assert!(ticks < 1200, "two crashes within 1200 ticks");
+        }
+        for _ in 0..5 {
+            ticks += 1;
+            assert!(env.step(full).is_none());
+        }
+
+        assert_eq!(outcomes[0].end_reason, EpisodeEndReason::Crash);
+        assert_eq!(outcomes[1].episode, outcomes[0].episode + 1);
+        let timestep = f64::from(env.tick_seconds());
+        let world = env.app.world();
+        let clock = *world.resource::<SimClock>();
+        assert_eq!(clock.tick, ticks);
+        assert!((clock.elapsed_s - ticks as f64 * timestep).abs() < 1e-6);
+        assert!(u64::from(world.resource::<EpisodeState>().ticks_in_episode) < ticks);
+    }
+
+    #[test]
+    fn reset_episode_returns_the_car_to_the_spawn_without_ticking() {
+        let track = ring_track();
+        let spawn = track.spawn_position;
+        let mut env = HeadlessEnv::new(track, &AppConfig::default(), 7);
+        let throttle = CarAction {
+            steering: 0.0,
+            throttle: 0.5,
+        };
+        for _ in 0..20 {
+            assert!(env.step(throttle).is_none());
+        }
+        assert!(env.sensors().speed > 0.0);
+
+        let outcome = env.reset_episode().expect("episode ends");
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Timeout);
+        assert_eq!(outcome.seed, derive_episode_seed(7, 1));
+        assert_eq!(env.episode_rng().seed, derive_episode_seed(7, 2));
+        assert_eq!(env.app.world().resource::<SimClock>().tick, 20);
+        assert_eq!(env.run_stats().ticks, 20);
+
+        assert_eq!(env.sensors().speed, 0.0);
+        assert!(env.progress().closest_point.distance(spawn) < 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid observation config")]
+    fn mismatched_ray_layouts_are_rejected_when_the_env_is_built() {
+        let mut config = AppConfig::default();
+        config.observation.ray_max_ranges = vec![100.0; 3];
+        HeadlessEnv::new(ring_track(), &config, 7);
+    }
+
+    #[test]
+    fn frame_skip_stops_on_the_tick_that_ends_an_episode() {
+        let mut env = HeadlessEnv::new(ring_track(), &AppConfig::default(), 7);
+        env.request_reset();
+        let step = env.step_frames(CarAction::default(), 5);
+
+        assert_eq!(step.ticks, 1);
+        let outcome = step.outcome.expect("the reset ends the episode");
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Timeout);
+        assert_eq!(step.reward, env.tick_reward());
+    }
+
+    #[test]
+    fn decimated_rays_only_change_on_update_ticks() {
+        let mut config = AppConfig::default();
+        config.observation.sensor_update_interval = 3;
+        let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+        let rays = config.observation.num_rays();
+        let full = CarAction {
+            steering: 0.2,
+            throttle: 1.0,
+        };
+
+        let mut previous = env.sensors();
+        let mut casts = 0;
+        for _ in 0..30 {
+            assert!(env.step(full).is_none());
+            let sensors = env.sensors();
+            let age = sensors.ray_age_ticks.expect("rays cast");
+            assert!(age < 3, "{age}");
+            if age == 0 {
+                casts += 1;
+                assert_ne!(sensors.ray_distances, previous.ray_distances);
+            } else {
+                assert_eq!(sensors.ray_distances, previous.ray_distances);
+            }
+            // Kinematics stay current every tick.
+            assert_ne!(sensors.speed, previous.speed);
+            previous = sensors;
+        }
+        // The first tick casts, then every episode tick divisible by 3.
+        assert_eq!(casts, 1 + 10);
+
+        let manifest = ObservationManifest::new(&config.observation, ActionMode::Continuous, 0);
+        assert_eq!(manifest.sensor_update_interval, 3);
+        assert!(manifest.features[..rays].iter().all(|f| f.decimated));
+        assert!(!manifest.features[rays].decimated);
+    }
+
+    #[test]
+    fn discrete_mode_steps_by_index() {
+        let config = AppConfig {
+            action_mode: ActionMode::Discrete {
+                steering_levels: 3,
+                throttle_levels: 2,
+            },
+            ..AppConfig::default()
+        };
+        let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+        assert_eq!(env.action_space().discrete_count, Some(6));
+        let space = env.discrete_action_space().expect("discrete mode");
+        assert_eq!(space.count, 6);
+        assert_eq!(
+            space.actions[3],
+            CarAction {
+                steering: 0.0,
+                throttle: 1.0
+            }
+        );
+
+        // Straight ahead at full throttle, as in the continuous crash test.
+        let outcome = (0..600)
+            .find_map(|_| env.step_discrete(3).expect("valid index"))
+            .expect("episode ends");
+        assert_eq!(outcome.end_reason, EpisodeEndReason::Crash);
+        assert_eq!(
+            env.step_discrete(6),
+            Err(ActionIndexError::OutOfRange { index: 6, count: 6 })
+        );
+    }
+
+    #[test]
+    fn the_curriculum_changes_the_paid_reward_from_the_first_episode_on() {
+        let config = AppConfig {
+            episode: EpisodeConfig {
+                idle_penalty_per_tick: -0.1,
+                idle_grace_ticks: 0,
+                ..EpisodeConfig::default()
+            },
+            curriculum: Curriculum {
+                enabled: true,
+                timeout_s: None,
+                idle_speed_threshold: Some(LinearSchedule {
+                    start: 0.0,
+                    end: 1000.0,
+                    episodes: 1,
+                }),
+            },
+            ..AppConfig::default()
+        };
+        let mut env = HeadlessEnv::new(ring_track(), &config, 7);
+        let idle = |env: &HeadlessEnv| {
+            env.app
+                .world()
+                .resource::<EpisodeState>()
+                .current_breakdown_sums
+                .get("idle")
+        };
+
+        // Stage one: nothing counts as idle, though the file says 40.
+        for _ in 0..30 {
+            env.step(CarAction::default());
+        }
+        assert_eq!(idle(&env), 0.0);
+
+        let full = CarAction {
+            steering: 0.0,
+            throttle: 1.0,
+        };
+        assert!((0..600).any(|_| env.step(full).is_some()), "episode ends");
+        for _ in 0..30 {
+            env.step(CarAction::default());
+        }
+        assert!((idle(&env) + 3.0).abs() < 1e-4, "idle {}", idle(&env));
+    }
+}
@@ -0,0 +1,15 @@
+//! Headless evaluation.
+//!
+//! [`env::HeadlessEnv`] runs the fixed-tick simulation for one car on one
+//! track without a window; [`tournament`] drives it across every track in a
+//! directory and aggregates a comparable report. [`remote`] lets an outside
+//! process drive the car over TCP, headless or windowed, and [`websocket`]
+//! streams telemetry to browsers (the endpoint needs the `websocket` feature).
+//! [`replay`] re-runs a recorded action log and checks the observations and
+//! rewards it reproduces.
+
+pub mod env;
+pub mod remote;
+pub mod replay;
+pub mod tournament;
+pub mod websocket;
@@ -0,0 +1,1114 @@
+//! External control over TCP.
+//!
+//! An outside process (a Python trainer, say) drives the car in lockstep
+//! with the fixed tick. Every message is one frame: a 4-byte big-endian
+//! length followed by that many bytes of UTF-8 JSON.
+//!
+//! 1. The server sends [`ServerMessage::Hello`] with the protocol version,
+//!    the observation and action spaces, and the track checksum.
+//! 2. The client answers [`ClientMessage::Hello`], optionally naming the
+//!    checksum it expects; a mismatch ends the session with an error frame.
+//! 3. The server sends [`ServerMessage::Step`] for tick 0, then one per
+//!    fixed tick. The client answers step `n` with an
+//!    [`ClientMessage::Action`] or [`ClientMessage::Reset`] tagged `n`;
+//!    replies tagged with an older tick arrived too late and are dropped.
+//!
+//! A reset ends the episode on the next tick as a truncation; that tick is
+//! driven with the fallback action and its step carries the first
+//! observation of the new episode.
+//!
+//! Headless ([`serve_headless`]) the simulation waits up to `timeout_ms` for
+//! each reply. Windowed ([`ExternalControlPlugin`]) background threads own
+//! the socket, so a fixed tick waits at most `windowed_wait_ms`. Either way a
+//! tick without a reply is a control timeout: the previous action is held for
+//! up to `max_skipped_frames` ticks, then decays to the fallback action over
+//! `timeout_decay_ticks` ([`ActionHold`]).
+//!
+//! With the `unix-socket` feature on Unix, `unix_socket` serves the same
+//! protocol on a Unix domain socket instead of TCP.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+#[cfg(all(unix, feature = "unix-socket"))]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionMode, ActionSmoothing, CarAction};
+use crate::agent::controller::{Controllers, ExternalActionHandle, registered_controller_system};
+use crate::agent::observation::{
+    ObservationConfig, ObservationVector, apply_observation_delay_system,
+};
+use crate::agent::spaces::EnvSpaces;
+use crate::brain::types::AgentMode;
+use crate::config::AppConfig;
+use crate::eval::env::HeadlessEnv;
+use crate::game::episode::{EpisodeEndReason, EpisodeResetRequest, EpisodeState};
+use crate::game::seeding::track_checksum;
+use crate::maps::monaco::build_monaco_track;
+use crate::maps::track::Track;
+use crate::sim::sets::SimSet;
+
+/// Bumped whenever a message changes shape.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Frames longer than this are rejected as corrupt.
+const MAX_FRAME_BYTES: u32 = 16 * 1024 * 1024;
+
+/// Listener and timing settings for external control (config `external`).
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExternalControlConfig {
+    /// Listen in windowed runs. Headless serving is started with
+    /// `--serve-external` regardless.
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+    /// Listen on this Unix domain socket path instead of TCP. Needs the
+    /// `unix-socket` feature; any stale file at the path is replaced.
+    pub unix_socket: Option<PathBuf>,
+    /// Headless: how long a tick waits for its reply. Also bounds the
+    /// handshake in both modes.
+    pub timeout_ms: u64,
+    /// What the held action decays to once replies stop, and the action
+    /// after a reset or disconnect.
+    pub fallback_action: CarAction,
+    /// Windowed: how long a fixed tick blocks waiting for its reply.
+    pub windowed_wait_ms: u64,
+    /// Ticks the previous action is held without a reply before it starts
+    /// decaying.
+    pub max_skipped_frames: u32,
+    /// Ticks the held action takes to blend linearly into the fallback;
+    /// `0` switches at once.
+    pub timeout_decay_ticks: u32,
+}
+
+impl Default for ExternalControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 5555,
+            unix_socket: None,
+            timeout_ms: 1000,
+            fallback_action: CarAction::default(),
+            windowed_wait_ms: 4,
+            max_skipped_frames: 3,
+            timeout_decay_ticks: 30,
+        }
+    }
+}
+
+impl ExternalControlConfig {
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    fn bind(&self) -> io::Result<Listener> {
+        match &self.unix_socket {
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Some(path) => {
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+                UnixListener::bind(path).map(Listener::Unix)
+            }
+            #[cfg(not(all(unix, feature = "unix-socket")))]
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Unix sockets need the `unix-socket` feature on a Unix host",
+            )),
+            None => TcpListener::bind((self.bind_address.as_str(), self.port)).map(Listener::Tcp),
+        }
+    }
+
+    /// Where [`Self::bind`] listens, for log messages.
+    pub fn endpoint(&self) -> String {
+        match &self.unix_socket {
+            Some(path) => format!("unix:{}", path.display()),
+            None => format!("{}:{}", self.bind_address, self.port),
+        }
+    }
+}
+
+/// A bound external-control listener.
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(all(unix, feature = "unix-socket"))]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Blocks for the next client, returning it with a label for logs.
+    fn accept(&self) -> io::Result<(Connection, String)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, peer) = listener.accept()?;
+                Ok((Connection::Tcp(stream), peer.to_string()))
+            }
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Listener::Unix(listener) => {
+                let (stream, _) = listener.accept()?;
+                Ok((Connection::Unix(stream), "unix client".to_string()))
+            }
+        }
+    }
+}
+
+/// One client's byte stream, over TCP or a Unix domain socket.
+pub enum Connection {
+    Tcp(TcpStream),
+    #[cfg(all(unix, feature = "unix-socket"))]
+    Unix(UnixStream),
+}
+
+impl Connection {
+    fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Connection::Tcp(stream) => stream.try_clone().map(Connection::Tcp),
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Connection::Unix(stream) => stream.try_clone().map(Connection::Unix),
+        }
+    }
+
+    /// Disables Nagle's algorithm on TCP; a no-op on Unix sockets.
+    fn set_nodelay(&self) -> io::Result<()> {
+        match self {
+            Connection::Tcp(stream) => stream.set_nodelay(true),
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Connection::Unix(_) => Ok(()),
+        }
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        match self {
+            Connection::Tcp(stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Connection::Unix(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+}
+
+impl From<TcpStream> for Connection {
+    fn from(stream: TcpStream) -> Self {
+        Connection::Tcp(stream)
+    }
+}
+
+#[cfg(all(unix, feature = "unix-socket"))]
+impl From<UnixStream> for Connection {
+    fn from(stream: UnixStream) -> Self {
+        Connection::Unix(stream)
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Connection::Tcp(stream) => stream.read(buf),
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Connection::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Connection::Tcp(stream) => stream.write(buf),
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Connection::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Connection::Tcp(stream) => stream.flush(),
+            #[cfg(all(unix, feature = "unix-socket"))]
+            Connection::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Server-to-client frames.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Hello {
+        protocol_version: u32,
+        spaces: EnvSpaces,
+        track_checksum: u64,
+    },
+    /// State after fixed tick `tick` (tick 0 is the state before any
+    /// action). `terminated` marks a crash or completed lap, `truncated` a
+    /// timeout or requested reset.
+    Step {
+        tick: u64,
+        observation: Vec<f32>,
+        reward: f32,
+        terminated: bool,
+        truncated: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl ServerMessage {
+    fn step(
+        tick: u64,
+        observation: &ObservationVector,
+        reward: f32,
+        end_reason: Option<EpisodeEndReason>,
+    ) -> Self {
+        ServerMessage::Step {
+            tick,
+            observation: observation.values.clone(),
+            reward,
+            terminated: matches!(
+                end_reason,
+                Some(EpisodeEndReason::Crash | EpisodeEndReason::LapComplete)
+            ),
+            truncated: end_reason == Some(EpisodeEndReason::Timeout),
+        }
+    }
+}
+
+/// Client-to-server frames.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Hello {
+        #[serde(default)]
+        expected_track_checksum: Option<u64>,
+    },
+    Action {
+        tick: u64,
+        steering: f32,
+        throttle: f32,
+    },
+    Reset {
+        tick: u64,
+    },
+}
+
+#[derive(Debug)]
+pub enum RemoteError {
+    Io(io::Error),
+    ChecksumMismatch { expected: u64, actual: u64 },
+    Protocol(String),
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "i/o error: {err}"),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "track checksum mismatch: client expects {expected:#018x}, server has {actual:#018x}"
+            ),
+            Self::Protocol(message) => write!(f, "protocol error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+impl From<io::Error> for RemoteError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Writes `message` as one length-prefixed JSON frame.
+pub fn write_frame(writer: &mut impl Write, message: &impl Serialize) -> io::Result<()> {
+    let body = serde_json::to_vec(message).map_err(io::Error::other)?;
+    let len = u32::try_from(body.len())
+        .ok()
+        .filter(|len| *len <= MAX_FRAME_BYTES)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&body)?;
+    writer.flush()
+}
+
+/// Reads one length-prefixed JSON frame.
+pub fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> io::Result<T> {
+    let mut header = [0u8; 4];
+    reader.read_exact(&mut header)?;
+    let len = u32::from_be_bytes(header);
+    if len > MAX_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds the {MAX_FRAME_BYTES}-byte limit"),
+        ));
+    }
+    let mut body = vec![0; len as usize];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// What the server tells a client in its hello.
+#[derive(Clone, Debug)]
+struct Handshake {
+    spaces: EnvSpaces,
+    track_checksum: u64,
+}
+
+impl Handshake {
+    fn message(&self) -> ServerMessage {
+        ServerMessage::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            spaces: self.spaces.clone(),
+            track_checksum: self.track_checksum,
+        }
+    }
+}
+
+/// The answer to one step, as far as the simulation is concerned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Reply {
+    Action(CarAction),
+    Reset,
+    TimedOut,
+    Disconnected,
+}
+
+/// One connected client. A reader thread decodes incoming frames so that
+/// waiting for a reply never leaves a half-read frame on the socket.
+struct Session {
+    writer: Connection,
+    replies: Mutex<Receiver<ClientMessage>>,
+    /// Steps sent so far; the last one sent is `tick - 1`.
+    tick: u64,
+}
+
+impl Session {
+    /// Starts the reader and runs the handshake.
+    fn open(
+        stream: Connection,
+        handshake: &Handshake,
+        timeout: Duration,
+    ) -> Result<Self, RemoteError> {
+        stream.set_nodelay()?;
+        let replies = spawn_reader(stream.try_clone()?);
+        let mut session = Self {
+            writer: stream,
+            replies: Mutex::new(replies),
+            tick: 0,
+        };
+
+        write_frame(&mut session.writer, &handshake.message())?;
+        let reply = session
+            .replies
+            .get_mut()
+            .map_err(|_| RemoteError::Protocol("reply channel poisoned".to_string()))?
+            .recv_timeout(timeout);
+        match reply {
+            Ok(ClientMessage::Hello {
+                expected_track_checksum,
+            }) => {
+                let actual = handshake.track_checksum;
+                if let Some(expected) =
+                    expected_track_checksum.filter(|expected| *expected != actual)
+                {
+                    let err = RemoteError::ChecksumMismatch { expected, actual };
+                    let _ = write_frame(
+                        &mut session.writer,
+                        &ServerMessage::Error {
+                            message: err.to_string(),
+                        },
+                    );
+                    return Err(err);
+                }
+                Ok(session)
+            }
+            Ok(other) => Err(RemoteError::Protocol(format!(
+                "expected hello, got {other:?}"
+            ))),
+            Err(_) => Err(RemoteError::Protocol(
+                "client sent no hello before the timeout".to_string(),
+            )),
+        }
+    }
+
+    fn send_step(&mut self, message: &ServerMessage) -> io::Result<()> {
+        write_frame(&mut self.writer, message)?;
+        self.tick += 1;
+        Ok(())
+    }
+
+    /// Waits up to `timeout` for the reply to the last step sent, dropping
+    /// replies to earlier steps.
+    fn wait_for_reply(&self, timeout: Duration) -> Reply {
+        let Some(expected) = self.tick.checked_sub(1) else {
+            return Reply::TimedOut;
+        };
+        let Ok(replies) = self.replies.lock() else {
+            return Reply::Disconnected;
+        };
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match replies.recv_timeout(remaining) {
+                Ok(ClientMessage::Action {
+                    tick,
+                    steering,
+                    throttle,
+                }) if tick == expected => {
+                    return Reply::Action(CarAction { steering, throttle }.clamped());
+                }
+                Ok(ClientMessage::Reset { tick }) if tick == expected => return Reply::Reset,
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => return Reply::TimedOut,
+                Err(RecvTimeoutError::Disconnected) => return Reply::Disconnected,
+            }
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // Unblocks the reader thread.
+        let _ = self.writer.shutdown();
+    }
+}
+
+/// Decodes frames from `stream` on a background thread until it closes or
+/// sends something malformed.
+fn spawn_reader(mut stream: Connection) -> Receiver<ClientMessage> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        loop {
+            match read_frame(&mut stream) {
+                Ok(message) => {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    if err.kind() == io::ErrorKind::InvalidData {
+                        warn!("Dropping external client: {err}");
+                    }
+                    break;
+                }
+            }
+        }
+    });
+    receiver
+}
+
+/// Missing-reply policy: tracks the last answered action and how many ticks
+/// in a row went unanswered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActionHold {
+    last: CarAction,
+    missed: u32,
+}
+
+impl ActionHold {
+    /// Holds `initial` until the first reply.
+    pub fn new(initial: CarAction) -> Self {
+        Self {
+            last: initial,
+            missed: 0,
+        }
+    }
+
+    /// Records a reply and returns it.
+    pub fn replied(&mut self, action: CarAction) -> CarAction {
+        self.last = action;
+        self.missed = 0;
+        action
+    }
+
+    /// Action for a tick without a reply: the last reply for
+    /// `max_skipped_frames` ticks, then a linear blend into the fallback over
+    /// `timeout_decay_ticks`.
+    pub fn missing(&mut self, config: &ExternalControlConfig) -> CarAction {
+        self.missed = self.missed.saturating_add(1);
+        let decaying = self.missed.saturating_sub(config.max_skipped_frames);
+        if decaying == 0 {
+            return self.last;
+        }
+        let t = if config.timeout_decay_ticks == 0 {
+            1.0
+        } else {
+            (decaying as f32 / config.timeout_decay_ticks as f32).min(1.0)
+        };
+        let fallback = config.fallback_action;
+        CarAction {
+            steering: self.last.steering + (fallback.steering - self.last.steering) * t,
+            throttle: self.last.throttle + (fallback.throttle - self.last.throttle) * t,
+        }
+    }
+
+    /// Forgets the last reply, so a following timeout holds `fallback`.
+    pub fn reset(&mut self, fallback: CarAction) -> CarAction {
+        self.replied(fallback)
+    }
+}
+
+/// Totals for one headless session.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionSummary {
+    pub ticks: u64,
+    /// Ticks without a reply in time, driven by [`ActionHold::missing`].
+    pub control_timeouts: u64,
+    pub episodes: u32,
+}
+
+/// Drives `env` in lockstep with the client on `stream` until it
+/// disconnects.
+pub fn serve_headless(
+    env: &mut HeadlessEnv,
+    stream: impl Into<Connection>,
+    config: &ExternalControlConfig,
+) -> Result<SessionSummary, RemoteError> {
+    let handshake = Handshake {
+        spaces: EnvSpaces {
+            observation: env.observation_space(),
+            action: env.action_space(),
+        },
+        track_checksum: env.track_checksum(),
+    };
+    let mut session = Session::open(stream.into(), &handshake, config.timeout())?;
+    let mut summary = SessionSummary::default();
+    let mut hold = ActionHold::new(config.fallback_action);
+    session.send_step(&ServerMessage::step(0, &env.observation(), 0.0, None))?;
+
+    loop {
+        let action = match session.wait_for_reply(config.timeout()) {
+            Reply::Action(action) => hold.replied(action),
+            Reply::Reset => {
+                env.request_reset();
+                hold.reset(config.fallback_action)
+            }
+            Reply::TimedOut => {
+                summary.control_timeouts += 1;
+                hold.missing(config)
+            }
+            Reply::Disconnected => return Ok(summary),
+        };
+        let outcome = env.step(action);
+        summary.ticks += 1;
+        if outcome.is_some() {
+            summary.episodes += 1;
+        }
+        let message = ServerMessage::step(
+            session.tick,
+            &env.observation(),
+            env.tick_reward(),
+            outcome.map(|outcome| outcome.end_reason),
+        );
+        if session.send_step(&message).is_err() {
+            return Ok(summary);
+        }
+    }
+}
+
+/// Headless `--serve-external` entry point: serves clients one at a time on
+/// the Sepang circuit, each in a fresh [`HeadlessEnv`] seeded from
+/// `master_seed`. Returns `false` if the listener cannot be bound.
+pub fn run_serve_external_command(config: &AppConfig, master_seed: u64) -> bool {
+    let endpoint = config.external.endpoint();
+    let listener = match config.external.bind() {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("cannot listen on {endpoint}: {err}");
+            return false;
+        }
+    };
+    println!("serving external control on {endpoint}");
+
+    loop {
+        let (stream, peer) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                eprintln!("accept failed: {err}");
+                continue;
+            }
+        };
+        let mut env = HeadlessEnv::new(build_monaco_track(), config, master_seed);
+        match serve_headless(&mut env, stream, &config.external) {
+            Ok(summary) => println!(
+                "{peer}: {} ticks, {} episodes, {} control timeouts",
+                summary.ticks, summary.episodes, summary.control_timeouts
+            ),
+            Err(err) => eprintln!("{peer}: {err}"),
+        }
+    }
+}
+
+/// Windowed external control: listens when [`ExternalControlConfig::enabled`]
+/// and feeds replies to the [`AgentMode::External`] controller.
+pub struct ExternalControlPlugin;
+
+impl Plugin for ExternalControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExternalControlConfig>()
+            .add_systems(PostStartup, start_external_server_system)
+            .add_systems(
+                FixedUpdate,
+                external_action_system
+                    .in_set(SimSet::Input)
+                    .before(registered_controller_system),
+            )
+            .add_systems(
+                FixedUpdate,
+                external_publish_system
+                    .in_set(SimSet::Measurement)
+                    .after(apply_observation_delay_system),
+            );
+    }
+}
+
+/// Windowed server state: sessions handed over by the accept thread and the
+/// external controller's action handle.
+#[derive(Resource)]
+pub struct ExternalLink {
+    incoming: Mutex<Receiver<Session>>,
+    session: Option<Session>,
+    handle: ExternalActionHandle,
+    hold: ActionHold,
+}
+
+impl ExternalLink {
+    /// Switches to the newest client that finished its handshake.
+    fn adopt_incoming(&mut self) {
+        let newest = self
+            .incoming
+            .get_mut()
+            .ok()
+            .and_then(|incoming| incoming.try_iter().last());
+        if let Some(session) = newest {
+            info!("External client connected.");
+            self.session = Some(session);
+            self.hold = ActionHold::new(self.handle.get());
+        }
+    }
+
+    fn drop_session(&mut self, fallback: CarAction) {
+        self.session = None;
+        self.handle.set(self.hold.reset(fallback));
+    }
+}
+
+/// Binds the listener and starts the accept thread once the track exists.
+pub fn start_external_server_system(
+    mut commands: Commands,
+    config: Res<ExternalControlConfig>,
+    observation: Res<ObservationConfig>,
+    action_mode: Res<ActionMode>,
+    smoothing: Res<ActionSmoothing>,
+    controllers: Res<Controllers>,
+    tracks: Query<&Track>,
+) {
+    if !config.enabled {
+        return;
+    }
+    let Ok(track) = tracks.single() else {
+        warn!("External control needs exactly one track; not listening.");
+        return;
+    };
+    let listener = match config.bind() {
+        Ok(listener) => listener,
+        Err(err) => {
+            warn!(
+                "External control cannot listen on {}: {err}",
+                config.endpoint()
+            );
+            return;
+        }
+    };
+    let mut spaces = EnvSpaces::new(&observation, *action_mode);
+    spaces.action = spaces.action.with_rate_limits(smoothing.rate_limits);
+    let handshake = Handshake {
+        spaces,
+        track_checksum: track_checksum(&track.grid),
+    };
+    let (sender, incoming) = mpsc::channel();
+    let timeout = config.timeout();
+    std::thread::spawn(move || accept_loop(listener, handshake, timeout, sender));
+    info!("External control listening on {}.", config.endpoint());
+
+    let handle = controllers.external_handle();
+    handle.set(config.fallback_action);
+    commands.insert_resource(ExternalLink {
+        incoming: Mutex::new(incoming),
+        session: None,
+        handle,
+        hold: ActionHold::new(config.fallback_action),
+    });
+}
+
+fn accept_loop(
+    listener: Listener,
+    handshake: Handshake,
+    timeout: Duration,
+    sessions: Sender<Session>,
+) {
+    loop {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(err) => {
+                warn!("External control accept failed: {err}");
+                continue;
+            }
+        };
+        match Session::open(stream, &handshake, timeout) {
+            Ok(session) => {
+                if sessions.send(session).is_err() {
+                    return;
+                }
+            }
+            Err(err) => warn!("External client rejected: {err}"),
+        }
+    }
+}
+
+/// Applies the client's reply to the last step before the external
+/// controller reads its handle.
+pub fn external_action_system(
+    mode: Option<Res<AgentMode>>,
+    config: Res<ExternalControlConfig>,
+    link: Option<ResMut<ExternalLink>>,
+    reset_request: Option<ResMut<EpisodeResetRequest>>,
+) {
+    let Some(mut link) = link else {
+        return;
+    };
+    link.adopt_incoming();
+    if mode.is_none_or(|mode| *mode != AgentMode::External) {
+        return;
+    }
+    let Some(session) = &link.session else {
+        return;
+    };
+
+    match session.wait_for_reply(Duration::from_millis(config.windowed_wait_ms)) {
+        Reply::Action(action) => {
+            let action = link.hold.replied(action);
+            link.handle.set(action);
+        }
+        Reply::Reset => {
+            if let Some(mut request) = reset_request {
+                request.pending = true;
+            }
+            let action = link.hold.reset(config.fallback_action);
+            link.handle.set(action);
+        }
+        Reply::TimedOut => {
+            let action = link.hold.missing(&config);
+            link.handle.set(action);
+            link.handle.record_timeout();
+        }
+        Reply::Disconnected => {
+            info!("External client disconnected.");
+            link.drop_session(config.fallback_action);
+        }
+    }
+}
+
+/// Sends the state after this tick to the connected client.
+pub fn external_publish_system(
+    mode: Option<Res<AgentMode>>,
+    config: Res<ExternalControlConfig>,
+    link: Option<ResMut<ExternalLink>>,
+    episode_state: Res<EpisodeState>,
+    observations: Query<&ObservationVector>,
+) {
+    if mode.is_none_or(|mode| *mode != AgentMode::External) {
+        return;
+    }
+    let Some(mut link) = link else {
+        return;
+    };
+    let Ok(observation) = observations.single() else {
+        return;
+    };
+    let Some(session) = &mut link.session else {
+        return;
+    };
+
+    let message = ServerMessage::step(
+        session.tick,
+        observation,
+        episode_state.current_tick_reward,
+        episode_state.current_tick_end_reason,
+    );
+    if let Err(err) = session.send_step(&message) {
+        info!("External client dropped: {err}");
+        link.drop_session(config.fallback_action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ActionHold, ClientMessage, Connection, ExternalControlConfig, ExternalLink, Handshake,
+        PROTOCOL_VERSION, RemoteError, ServerMessage, Session, SessionSummary,
+        external_action_system, external_publish_system, read_frame, serve_headless, write_frame,
+    };
+    use crate::agent::action::{ActionMode, ActionState, CarAction};
+    use crate::agent::controller::{Controllers, registered_controller_system};
+    use crate::agent::observation::{ObservationConfig, ObservationVector};
+    use crate::agent::spaces::EnvSpaces;
+    use crate::brain::types::AgentMode;
+    use crate::config::AppConfig;
+    use crate::eval::env::HeadlessEnv;
+    use crate::game::car::Car;
+    use crate::game::episode::EpisodeState;
+    use crate::game::progress::TrackProgress;
+    use crate::game::seeding::track_checksum;
+    use crate::maps::fixtures::ring_track;
+    use bevy::prelude::*;
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Mutex, mpsc};
+    use std::thread;
+    use std::time::Duration;
+
+    /// Runs `client` against a headless server on a loopback port.
+    fn session<T: Send + 'static>(
+        config: ExternalControlConfig,
+        client: impl FnOnce(TcpStream) -> T + Send + 'static,
+    ) -> (Result<SessionSummary, RemoteError>, T) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binds");
+        let addr = listener.local_addr().expect("bound address");
+        let client = thread::spawn(move || client(TcpStream::connect(addr).expect("connects")));
+        let (stream, _) = listener.accept().expect("accepts");
+        let mut env = HeadlessEnv::new(ring_track(), &AppConfig::default(), 7);
+        let result = serve_headless(&mut env, stream, &config);
+        (result, client.join().expect("client thread"))
+    }
+
+    fn hello(checksum: Option<u64>) -> ClientMessage {
+        ClientMessage::Hello {
+            expected_track_checksum: checksum,
+        }
+    }
+
+    #[test]
+    fn mock_client_drives_a_headless_session() {
+        let config = ExternalControlConfig {
+            timeout_ms: 50,
+            ..ExternalControlConfig::default()
+        };
+        let checksum = track_checksum(&ring_track().grid);
+
+        let (result, steps) = session(config, move |mut stream| {
+            let ServerMessage::Hello {
+                protocol_version,
+                spaces,
+                track_checksum,
+            } = read_frame(&mut stream).expect("hello")
+            else {
+                panic!("server must open with hello");
+            };
+            assert_eq!(protocol_version, PROTOCOL_VERSION);
+            assert_eq!(track_checksum, checksum);
+            write_frame(&mut stream, &hello(Some(checksum))).expect("sends hello");
+
+            let mut steps = Vec::new();
+            for _ in 0..40 {
+                let step: ServerMessage = read_frame(&mut stream).expect("step");
+                let ServerMessage::Step {
+                    tick, observation, ..
+                } = &step
+                else {
+                    panic!("expected a step, got {step:?}");
+                };
+                assert_eq!(observation.len(), spaces.observation.dim);
+                let tick = *tick;
+                steps.push(step);
+                let reply = match tick {
+                    // Left unanswered: the server falls back after the timeout.
+                    10 => continue,
+                    20 => ClientMessage::Reset { tick },
+                    _ => ClientMessage::Action {
+                        tick,
+                        steering: 0.0,
+                        throttle: 0.3,
+                    },
+                };
+                write_frame(&mut stream, &reply).expect("sends reply");
+            }
+            steps
+        });
+
+        let summary = result.expect("session ends cleanly");
+        assert_eq!(summary.control_timeouts, 1);
+        assert_eq!(summary.episodes, 1);
+        for (expected, step) in steps.iter().enumerate() {
+            let ServerMessage::Step {
+                tick,
+                terminated,
+                truncated,
+                ..
+            } = step
+            else {
+                unreachable!();
+            };
+            assert_eq!(*tick, expected as u64);
+            assert!(!terminated);
+            // Only the tick after the reset ends the episode.
+            assert_eq!(*truncated, expected == 21, "tick {expected}");
+        }
+    }
+
+    #[test]
+    fn in_process_client_action_reaches_the_desired_action_and_sees_the_car_vector() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("binds");
+        let addr = listener.local_addr().expect("bound address");
+        let sent = CarAction {
+            steering: -0.25,
+            throttle: 0.75,
+        };
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).expect("connects");
+            let _: ServerMessage = read_frame(&mut stream).expect("hello");
+            write_frame(&mut stream, &hello(None)).expect("sends hello");
+            let mut observations = Vec::new();
+            for _ in 0..2 {
+                let ServerMessage::Step {
+                    tick, observation, ..
+                } = read_frame(&mut stream).expect("step")
+                else {
+                    panic!("expected a step");
+                };
+                observations.push(observation);
+                if tick == 0 {
+                    let reply = ClientMessage::Action {
+                        tick,
+                        steering: sent.steering,
+                        throttle: sent.throttle,
+                    };
+                    write_frame(&mut stream, &reply).expect("sends action");
+                }
+            }
+            observations
+        });
+        let (stream, _) = listener.accept().expect("accepts");
+        let handshake = Handshake {
+            spaces: EnvSpaces::new(&ObservationConfig::default(), ActionMode::Continuous),
+            track_checksum: 0,
+        };
+        let session = Session::open(Connection::from(stream), &handshake, Duration::from_secs(5))
+            .expect("handshake");
+
+        let mut app = App::new();
+        let controllers = Controllers::from_world(&mut World::new());
+        let (sessions, incoming) = mpsc::channel();
+        sessions.send(session).expect("queues the session");
+        app.insert_resource(ExternalLink {
+            incoming: Mutex::new(incoming),
+            session: None,
+            handle: controllers.external_handle(),
+            hold: ActionHold::new(CarAction::default()),
+        })
+        .insert_resource(controllers)
+        .insert_resource(ExternalControlConfig {
+            windowed_wait_ms: 5_000,
+            ..ExternalControlConfig::default()
+        })
+        .insert_resource(AgentMode::External)
+        .insert_resource(Time::<()>::default())
+        .init_resource::<ActionState>()
+        .init_resource::<EpisodeState>()
+        .add_systems(
+            Update,
+            (
+                external_action_system,
+                registered_controller_system,
+                external_publish_system,
+            )
+                .chain(),
+        );
+        let vector = vec![0.5, -0.125, 1.0];
+        let car = app
+            .world_mut()
+            .spawn((
+                ObservationVector {
+                    values: vector.clone(),
+                },
+                TrackProgress::default(),
+                Car::default(),
+                Transform::default(),
+            ))
+            .id();
+
+        // Tick 0 has nothing to answer yet; it publishes the first step.
+        app.update();
+        assert_eq!(
+            app.world().resource::<ActionState>().desired,
+            CarAction::default()
+        );
+        // Tick 1 waits for the reply to step 0.
+        app.world_mut()
+            .get_mut::<ObservationVector>(car)
+            .unwrap()
+            .values[0] = 0.25;
+        app.update();
+        assert_eq!(app.world().resource::<ActionState>().desired, sent);
+
+        drop(app);
+        let observations = client.join().expect("client thread");
+        assert_eq!(observations, [vector, vec![0.25, -0.125, 1.0]]);
+    }
+
+    #[test]
+    fn missing_replies_hold_the_last_action_then_decay_to_the_fallback() {
+        let config = ExternalControlConfig {
+            max_skipped_frames: 2,
+            timeout_decay_ticks: 4,
+            ..ExternalControlConfig::default()
+        };
+        let sent = CarAction {
+            steering: 0.8,
+            throttle: 1.0,
+        };
+        let mut hold = ActionHold::new(config.fallback_action);
+        assert_eq!(hold.missing(&config), CarAction::default());
+        assert_eq!(hold.replied(sent), sent);
+
+        let missed: Vec<CarAction> = (0..8).map(|_| hold.missing(&config)).collect();
+        assert_eq!(&missed[..2], &[sent, sent]);
+        let decay: Vec<f32> = missed[2..].iter().map(|action| action.throttle).collect();
+        assert_eq!(decay, [0.75, 0.5, 0.25, 0.0, 0.0, 0.0]);
+        assert_eq!(missed[3].steering, 0.4);
+        assert_eq!(missed[7], CarAction::default());
+
+        // A reply ends the timeout, and the hold starts over from it.
+        assert_eq!(hold.replied(sent), sent);
+        assert_eq!(hold.missing(&config), sent);
+
+        let abrupt = ExternalControlConfig {
+            max_skipped_frames: 0,
+            timeout_decay_ticks: 0,
+            ..config
+        };
+        assert_eq!(hold.missing(&abrupt), CarAction::default());
+    }
+
+    #[test]
+    fn checksum_mismatch_is_reported_and_ends_the_session() {
+        let (result, reply) = session(ExternalControlConfig::default(), |mut stream| {
+            let _: ServerMessage = read_frame(&mut stream).expect("hello");
+            write_frame(&mut stream, &hello(Some(1))).expect("sends hello");
+            read_frame::<ServerMessage>(&mut stream).expect("error frame")
+        });
+
+        assert!(matches!(
+            result,
+            Err(RemoteError::ChecksumMismatch { expected: 1, .. })
+        ));
+        assert!(matches!(reply, ServerMessage::Error { .. }));
+    }
+}
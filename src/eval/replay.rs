@@ -0,0 +1,323 @@
+use std::fmt;
+
+use crate::agent::action::CarAction;
+use crate::analytics::trackers::transitions::Transition;
+use crate::config::AppConfig;
+use crate::eval::env::HeadlessEnv;
+use crate::maps::track::Track;
+
+/// One recorded fixed tick: the observation the action was chosen from, the
+/// action, and what the tick produced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedTick {
+    pub observation: Vec<f32>,
+    pub action: CarAction,
+    pub reward: f32,
+    /// The episode ended on this tick, for any reason.
+    pub done: bool,
+}
+
+impl From<&Transition<'_>> for RecordedTick {
+    fn from(transition: &Transition<'_>) -> Self {
+        Self {
+            observation: transition.observation.to_vec(),
+            action: transition.action,
+            reward: transition.reward,
+            done: transition.done || transition.truncated,
+        }
+    }
+}
+
+/// Action log of the first episode of a run, with the observations and
+/// rewards it produced.
+///
+/// Replaying is only meaningful for a log of every tick (no transition
+/// downsampling), recorded without observation noise and with the actions
+/// the sim was asked for; with action smoothing on, a log of applied actions
+/// is smoothed a second time on replay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedEpisode {
+    pub master_seed: u64,
+    pub ticks: Vec<RecordedTick>,
+}
+
+impl RecordedEpisode {
+    /// Runs a [`HeadlessEnv`] with actions from `policy` until the first
+    /// episode ends or `max_ticks` have passed, recording every tick.
+    pub fn record(
+        track: Track,
+        config: &AppConfig,
+        master_seed: u64,
+        max_ticks: u32,
+        mut policy: impl FnMut(&[f32]) -> CarAction,
+    ) -> Self {
+        let mut env = HeadlessEnv::new(track, config, master_seed);
+        let mut ticks = Vec::new();
+        for _ in 0..max_ticks {
+            let observation = env.observation().values;
+            let action = policy(&observation);
+            let done = env.step(action).is_some();
+            ticks.push(RecordedTick {
+                observation,
+                action,
+                reward: env.tick_reward(),
+                done,
+            });
+            if done {
+                break;
+            }
+        }
+        Self { master_seed, ticks }
+    }
+}
+
+/// Largest absolute differences still counted as a match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplayTolerance {
+    pub observation: f32,
+    pub reward: f32,
+}
+
+impl Default for ReplayTolerance {
+    fn default() -> Self {
+        Self {
+            observation: 1e-4,
+            reward: 1e-4,
+        }
+    }
+}
+
+/// First place a replay disagreed with its recording. `tick` indexes
+/// [`RecordedEpisode::ticks`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplayDivergence {
+    ObservationLength {
+        tick: usize,
+        recorded: usize,
+        replayed: usize,
+    },
+    Observation {
+        tick: usize,
+        feature: usize,
+        recorded: f32,
+        replayed: f32,
+    },
+    Reward {
+        tick: usize,
+        recorded: f32,
+        replayed: f32,
+    },
+    Termination {
+        tick: usize,
+        recorded: bool,
+        replayed: bool,
+    },
+}
+
+impl ReplayDivergence {
+    pub fn tick(&self) -> usize {
+        match *self {
+            Self::ObservationLength { tick, .. }
+            | Self::Observation { tick, .. }
+            | Self::Reward { tick, .. }
+            | Self::Termination { tick, .. } => tick,
+        }
+    }
+}
+
+impl fmt::Display for ReplayDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ObservationLength {
+                tick,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "tick {tick}: observation has {replayed} features, recorded {recorded}"
+            ),
+            Self::Observation {
+                tick,
+                feature,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "tick {tick}: observation feature {feature} is {replayed}, recorded {recorded}"
+            ),
+            Self::Reward {
+                tick,
+                recorded,
+                replayed,
+            } => write!(f, "tick {tick}: reward is {replayed}, recorded {recorded}"),
+            Self::Termination {
+                tick,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "tick {tick}: episode end is {replayed}, recorded {recorded}"
+            ),
+        }
+    }
+}
+
+/// Result of [`validate_replay`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplayValidation {
+    /// Ticks that matched before the divergence, or all of them.
+    pub ticks_matched: usize,
+    pub divergence: Option<ReplayDivergence>,
+}
+
+impl ReplayValidation {
+    pub fn is_valid(&self) -> bool {
+        self.divergence.is_none()
+    }
+
+    pub fn first_divergent_tick(&self) -> Option<usize> {
+        self.divergence.map(|divergence| divergence.tick())
+    }
+}
+
+/// Steps a fresh [`HeadlessEnv`] seeded with `trace.master_seed` through the
+/// recorded actions, checking each tick's observation before the step and its
+/// reward and episode end after it. Stops at the first mismatch.
+pub fn validate_replay(
+    track: Track,
+    config: &AppConfig,
+    trace: &RecordedEpisode,
+    tolerance: ReplayTolerance,
+) -> ReplayValidation {
+    let mut env = HeadlessEnv::new(track, config, trace.master_seed);
+    for (tick, recorded) in trace.ticks.iter().enumerate() {
+        let divergence = replay_tick(&mut env, tick, recorded, tolerance);
+        if divergence.is_some() {
+            return ReplayValidation {
+                ticks_matched: tick,
+                divergence,
+            };
+        }
+    }
+    ReplayValidation {
+        ticks_matched: trace.ticks.len(),
+        divergence: None,
+    }
+}
+
+fn replay_tick(
+    env: &mut HeadlessEnv,
+    tick: usize,
+    recorded: &RecordedTick,
+    tolerance: ReplayTolerance,
+) -> Option<ReplayDivergence> {
+    let observation = env.observation().values;
+    if observation.len() != recorded.observation.len() {
+        return Some(ReplayDivergence::ObservationLength {
+            tick,
+            recorded: recorded.observation.len(),
+            replayed: observation.len(),
+        });
+    }
+    let mismatch = observation
+        .iter()
+        .zip(&recorded.observation)
+        .position(|(replayed, recorded)| (replayed - recorded).abs() > tolerance.observation);
+    if let Some(feature) = mismatch {
+        return Some(ReplayDivergence::Observation {
+            tick,
+            feature,
+            recorded: recorded.observation[feature],
+            replayed: observation[feature],
+        });
+    }
+
+    let done = env.step(recorded.action).is_some();
+    let reward = env.tick_reward();
+    if (reward - recorded.reward).abs() > tolerance.reward {
+        return Some(ReplayDivergence::Reward {
+            tick,
+            recorded: recorded.reward,
+            replayed: reward,
+        });
+    }
+    if done != recorded.done {
+        return Some(ReplayDivergence::Termination {
+            tick,
+            recorded: recorded.done,
+            replayed: done,
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maps::fixtures::ring_track;
+
+    fn recorded_episode() -> RecordedEpisode {
+        let config = AppConfig::default();
+        let mut tick = 0u32;
+        let trace = RecordedEpisode::record(ring_track(), &config, 11, 300, |_| {
+            tick += 1;
+            CarAction {
+                steering: ((tick as f32) * 0.05).sin() * 0.5,
+                throttle: 0.6,
+            }
+        });
+        assert!(trace.ticks.len() > 20, "episode too short to corrupt");
+        trace
+    }
+
+    #[test]
+    fn a_faithfully_recorded_episode_replays_tick_for_tick() {
+        let trace = recorded_episode();
+
+        let validation = validate_replay(
+            ring_track(),
+            &AppConfig::default(),
+            &trace,
+            ReplayTolerance::default(),
+        );
+
+        assert!(validation.is_valid(), "{:?}", validation.divergence);
+        assert_eq!(validation.ticks_matched, trace.ticks.len());
+    }
+
+    #[test]
+    fn corrupted_recordings_report_the_first_divergent_tick() {
+        let trace = recorded_episode();
+        let observation_tick = trace.ticks.len() / 2;
+        let reward_tick = trace.ticks.len() / 3;
+
+        let mut corrupted = trace.clone();
+        corrupted.ticks[observation_tick].observation[2] += 0.5;
+        let validation = validate_replay(
+            ring_track(),
+            &AppConfig::default(),
+            &corrupted,
+            ReplayTolerance::default(),
+        );
+        assert_eq!(validation.first_divergent_tick(), Some(observation_tick));
+        assert_eq!(validation.ticks_matched, observation_tick);
+        assert!(matches!(
+            validation.divergence,
+            Some(ReplayDivergence::Observation { feature: 2, .. })
+        ));
+
+        // The earlier reward corruption is reported first.
+        corrupted.ticks[reward_tick].reward += 1.0;
+        let validation = validate_replay(
+            ring_track(),
+            &AppConfig::default(),
+            &corrupted,
+            ReplayTolerance::default(),
+        );
+        assert_eq!(validation.first_divergent_tick(), Some(reward_tick));
+        assert!(matches!(
+            validation.divergence,
+            Some(ReplayDivergence::Reward { .. })
+        ));
+    }
+}
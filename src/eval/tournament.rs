@@ -0,0 +1,565 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::CarAction;
+use crate::agent::controller::{Controller, ControllerContext};
+use crate::agent::mlp::MlpController;
+use crate::brain::a2c::{A2C_CHECKPOINT_PATH, A2cBrain};
+use crate::brain::common::policy_mlp::PolicyMlp;
+use crate::brain::types::Brain;
+use crate::config::AppConfig;
+use crate::eval::env::{EpisodeOutcome, HeadlessEnv};
+use crate::game::episode::EpisodeEndReason;
+use crate::maps::loader::{build_track, load_track_file};
+use crate::maps::monaco::build_monaco_track;
+use crate::maps::track::Track;
+use crate::sim::evaluation::EvaluationMode;
+use crate::sim::rng::{EpisodeRng, derive_episode_seed};
+
+/// Where `--evaluate-all` writes its JSON report.
+pub const TOURNAMENT_REPORT_PATH: &str = "reports/tournament.json";
+
+/// Episodes per track when `--evaluate-all` is given no count.
+pub const DEFAULT_EPISODES_PER_TRACK: u32 = 5;
+
+/// Registry name of the built-in Sepang circuit.
+pub const BUILTIN_TRACK_NAME: &str = "sepang";
+
+/// A named track taking part in a tournament.
+pub struct RegisteredTrack {
+    pub name: String,
+    pub track: Track,
+}
+
+/// Per-run tournament parameters.
+#[derive(Clone, Copy, Debug)]
+pub struct TournamentSettings {
+    /// Master seed for every track's episode seeds.
+    pub master_seed: u64,
+    pub episodes_per_track: u32,
+}
+
+/// Aggregated results of one controller across every registered track.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TournamentReport {
+    pub controller: String,
+    pub master_seed: u64,
+    pub episodes_per_track: u32,
+    pub tracks: Vec<TrackResult>,
+}
+
+/// Results for one track.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrackResult {
+    pub track: String,
+    pub episodes: u32,
+    pub laps: u32,
+    pub crashes: u32,
+    pub timeouts: u32,
+    /// Fraction of episodes that ended with a completed lap.
+    pub lap_rate: f32,
+    pub best_lap_ticks: Option<u32>,
+    pub best_lap_s: Option<f32>,
+    pub mean_return: f32,
+    pub mean_best_progress: f32,
+    pub outcomes: Vec<EpisodeOutcome>,
+}
+
+impl TrackResult {
+    fn from_outcomes(track: String, outcomes: Vec<EpisodeOutcome>, tick_seconds: f32) -> Self {
+        let count = |reason| outcomes.iter().filter(|o| o.end_reason == reason).count() as u32;
+        let episodes = outcomes.len() as u32;
+        let laps = count(EpisodeEndReason::LapComplete);
+        let best_lap_ticks = outcomes
+            .iter()
+            .filter(|o| o.end_reason == EpisodeEndReason::LapComplete)
+            .map(|o| o.ticks)
+            .min();
+        let mean = |value: fn(&EpisodeOutcome) -> f32| {
+            if outcomes.is_empty() {
+                0.0
+            } else {
+                outcomes.iter().map(value).sum::<f32>() / outcomes.len() as f32
+            }
+        };
+
+        Self {
+            track,
+            episodes,
+            laps,
+            crashes: count(EpisodeEndReason::Crash),
+            timeouts: count(EpisodeEndReason::Timeout),
+            lap_rate: if episodes == 0 {
+                0.0
+            } else {
+                laps as f32 / episodes as f32
+            },
+            best_lap_ticks,
+            best_lap_s: best_lap_ticks.map(|ticks| ticks as f32 * tick_seconds),
+            mean_return: mean(|o| o.episode_return),
+            mean_best_progress: mean(|o| o.best_progress_fraction),
+            outcomes,
+        }
+    }
+}
+
+/// The policy an `--evaluate-all` run scores.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TournamentPolicy {
+    /// The A2C actor restored from a checkpoint, or initialised from the
+    /// master seed without one.
+    A2c { checkpoint: Option<PathBuf> },
+    /// A [`PolicyMlp`] weights file.
+    Mlp(PathBuf),
+    /// An ONNX model; needs the `onnx` feature.
+    Onnx(PathBuf),
+}
+
+impl TournamentPolicy {
+    /// Parses `--policy <a2c|mlp|onnx>` and its optional `--weights <path>`.
+    /// Without a path, A2C uses [`A2C_CHECKPOINT_PATH`] when it exists and
+    /// MLP and ONNX use the configured `mlp.weights_path` / `onnx.model_path`.
+    pub fn from_args(
+        kind: &str,
+        weights: Option<&str>,
+        config: &AppConfig,
+    ) -> Result<Self, String> {
+        let weights = weights.map(PathBuf::from);
+        match kind {
+            "a2c" => Ok(Self::A2c {
+                checkpoint: weights.or_else(|| {
+                    let default = PathBuf::from(A2C_CHECKPOINT_PATH);
+                    default.exists().then_some(default)
+                }),
+            }),
+            "mlp" => weights
+                .or_else(|| config.mlp.weights_path.as_ref().map(PathBuf::from))
+                .map(Self::Mlp)
+                .ok_or_else(|| "--policy mlp needs --weights or mlp.weights_path".to_string()),
+            "onnx" => weights
+                .or_else(|| config.onnx.model_path.as_ref().map(PathBuf::from))
+                .map(Self::Onnx)
+                .ok_or_else(|| "--policy onnx needs --weights or onnx.model_path".to_string()),
+            other => Err(format!(
+                "unknown policy '{other}' (expected a2c, mlp, or onnx)"
+            )),
+        }
+    }
+
+    /// Report name of the policy.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::A2c { .. } => "a2c",
+            Self::Mlp(_) => "mlp",
+            Self::Onnx(_) => "onnx",
+        }
+    }
+
+    /// Loads the policy as a controller sized for `config`'s observation
+    /// and action spaces.
+    pub fn load(
+        &self,
+        config: &AppConfig,
+        master_seed: u64,
+    ) -> Result<Box<dyn Controller>, String> {
+        let observation_dim = config.observation.observation_dim();
+        match self {
+            Self::A2c { checkpoint } => {
+                let brain = match checkpoint {
+                    Some(path) => A2cBrain::from_checkpoint(path, observation_dim)
+                        .map_err(|err| format!("{}: {err}", path.display()))?,
+                    None => A2cBrain::seeded(master_seed, observation_dim),
+                };
+                Ok(Box::new(A2cEvaluator { brain, master_seed }))
+            }
+            Self::Mlp(path) => PolicyMlp::load(path)
+                .and_then(|net| MlpController::new(net, observation_dim))
+                .map(|controller| Box::new(controller) as Box<dyn Controller>)
+                .map_err(|err| format!("{}: {err}", path.display())),
+            #[cfg(feature = "onnx")]
+            Self::Onnx(path) => {
+                use crate::agent::onnx::OnnxController;
+                use crate::agent::spaces::{ActionSpace, ObservationSpace};
+
+                OnnxController::load(
+                    path,
+                    &ObservationSpace::from_config(&config.observation),
+                    &ActionSpace::for_mode(config.action_mode),
+                    config.action_mode,
+                )
+                .map(|controller| Box::new(controller) as Box<dyn Controller>)
+                .map_err(|err| format!("{}: {err}", path.display()))
+            }
+            #[cfg(not(feature = "onnx"))]
+            Self::Onnx(path) => Err(format!(
+                "{}: built without the `onnx` feature",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Drives an [`A2cBrain`] as a [`Controller`]. Each episode loads the
+/// exploration stream [`HeadlessEnv`] seeds for it, and the rollout buffer
+/// is dropped every tick since nothing trains.
+struct A2cEvaluator {
+    brain: A2cBrain,
+    master_seed: u64,
+}
+
+impl Controller for A2cEvaluator {
+    fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+        let action = self.brain.act(ctx.observation);
+        self.brain.buffer.clear();
+        action
+    }
+
+    fn begin_episode(&mut self, episode: u32) {
+        self.brain.begin_episode(&EpisodeRng::from_seed(
+            episode,
+            derive_episode_seed(self.master_seed, episode),
+        ));
+    }
+}
+
+/// The built-in Sepang circuit followed by every valid `.ron` track directly
+/// inside `dir`, sorted by path. Invalid files are reported on stderr and
+/// skipped.
+pub fn load_track_registry(dir: &Path) -> std::io::Result<Vec<RegisteredTrack>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect();
+    paths.sort();
+
+    let mut tracks = vec![RegisteredTrack {
+        name: BUILTIN_TRACK_NAME.to_string(),
+        track: build_monaco_track(),
+    }];
+    for path in paths {
+        match load_track_file(&path).and_then(|file| Ok((file.name.clone(), build_track(&file)?))) {
+            Ok((name, track)) => tracks.push(RegisteredTrack { name, track }),
+            Err(err) => eprintln!("skipping {}: {err}", path.display()),
+        }
+    }
+    Ok(tracks)
+}
+
+/// Runs `episodes_per_track` headless episodes of `controller` on each track.
+///
+/// Every track starts from the same master seed, so per-episode seeds (and,
+/// for a deterministic controller, the whole report) are reproducible.
+/// Tournaments are always evaluation runs: noise is off whatever `config`
+/// says.
+pub fn run_tournament(
+    tracks: Vec<RegisteredTrack>,
+    config: &AppConfig,
+    settings: TournamentSettings,
+    controller: &mut dyn Controller,
+    controller_name: &str,
+) -> TournamentReport {
+    let config = AppConfig {
+        evaluation: EvaluationMode { enabled: true },
+        ..config.clone()
+    };
+    let mut results = Vec::with_capacity(tracks.len());
+    for RegisteredTrack { name, track } in tracks {
+        let mut env = HeadlessEnv::new(track, &config, settings.master_seed);
+        let mut outcomes = Vec::with_capacity(settings.episodes_per_track as usize);
+
+        controller.begin_episode(env.episode_rng().episode);
+        while outcomes.len() < settings.episodes_per_track as usize {
+            if let Some(outcome) = env.step_controller(controller) {
+                outcomes.push(outcome);
+            }
+        }
+
+        results.push(TrackResult::from_outcomes(
+            name,
+            outcomes,
+            env.tick_seconds(),
+        ));
+    }
+
+    TournamentReport {
+        controller: controller_name.to_string(),
+        master_seed: settings.master_seed,
+        episodes_per_track: settings.episodes_per_track,
+        tracks: results,
+    }
+}
+
+/// Headless `--evaluate-all` entry point: evaluates `policy` on Sepang and
+/// every track in `dir`, prints the table, and writes
+/// [`TOURNAMENT_REPORT_PATH`]. Returns `false` on failure.
+pub fn run_evaluate_all_command(
+    dir: &Path,
+    config: &AppConfig,
+    settings: TournamentSettings,
+    policy: &TournamentPolicy,
+) -> bool {
+    let tracks = match load_track_registry(dir) {
+        Ok(tracks) => tracks,
+        Err(err) => {
+            eprintln!("cannot read track directory {}: {err}", dir.display());
+            return false;
+        }
+    };
+    let mut controller = match policy.load(config, settings.master_seed) {
+        Ok(controller) => controller,
+        Err(err) => {
+            eprintln!("cannot load {} policy: {err}", policy.name());
+            return false;
+        }
+    };
+
+    let report = run_tournament(tracks, config, settings, controller.as_mut(), policy.name());
+    println!("{}", report.table());
+
+    let path = Path::new(TOURNAMENT_REPORT_PATH);
+    match report.write_json(path) {
+        Ok(()) => {
+            println!("wrote {}", path.display());
+            true
+        }
+        Err(err) => {
+            eprintln!("failed to write {}: {err}", path.display());
+            false
+        }
+    }
+}
+
+impl TournamentReport {
+    /// Fixed-width console table, one row per track.
+    pub fn table(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "controller {}  seed {:#018x}  {} episode(s) per track",
+                self.controller, self.master_seed, self.episodes_per_track
+            ),
+            format!(
+                "{:<20} {:>5} {:>5} {:>8} {:>9} {:>11} {:>9}",
+                "track", "eps", "laps", "lap rate", "best lap", "mean return", "progress"
+            ),
+        ];
+        for result in &self.tracks {
+            let best_lap = result
+                .best_lap_s
+                .map(|s| format!("{s:.2}s"))
+                .unwrap_or_else(|| "-".to_string());
+            lines.push(format!(
+                "{:<20} {:>5} {:>5} {:>7.0}% {:>9} {:>11.2} {:>8.0}%",
+                result.track,
+                result.episodes,
+                result.laps,
+                result.lap_rate * 100.0,
+                best_lap,
+                result.mean_return,
+                result.mean_best_progress * 100.0
+            ));
+        }
+        lines.join("\n")
+    }
+
+    pub fn write_json(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        fs::write(path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BUILTIN_TRACK_NAME, RegisteredTrack, TournamentPolicy, TournamentSettings,
+        load_track_registry, run_tournament,
+    };
+    use crate::agent::action::CarAction;
+    use crate::agent::controller::{Controller, ControllerContext};
+    use crate::agent::observation::ObservationConfig;
+    use crate::brain::a2c::A2cBrain;
+    use crate::brain::a2c::checkpoint::A2cCheckpoint;
+    use crate::brain::common::policy_mlp::PolicyMlp;
+    use crate::config::AppConfig;
+    use crate::maps::centerline::LapDirection;
+    use crate::maps::fixtures::ring_track;
+    use crate::maps::grid::GridEdge;
+    use crate::maps::loader::{TrackFile, build_track};
+    use crate::maps::parts::TilePart;
+
+    /// Steers back toward the centreline from the heading-error and
+    /// lateral-offset features.
+    struct CenterlineFollower {
+        kinematic_offset: usize,
+    }
+
+    impl CenterlineFollower {
+        fn new() -> Self {
+            Self {
+                kinematic_offset: ObservationConfig::default().num_rays(),
+            }
+        }
+    }
+
+    impl Controller for CenterlineFollower {
+        fn act(&mut self, ctx: &ControllerContext) -> CarAction {
+            let values = &ctx.observation.values;
+            let lateral = values[self.kinematic_offset + 1];
+            let heading_error = values[self.kinematic_offset + 2];
+            CarAction {
+                steering: (0.8 * lateral - 3.0 * heading_error).clamp(-1.0, 1.0),
+                throttle: 0.35,
+            }
+        }
+    }
+
+    fn tiny_tracks() -> Vec<RegisteredTrack> {
+        use TilePart::*;
+        let oval = build_track(&TrackFile {
+            name: "oval".to_string(),
+            tile_size: 100.0,
+            tiles: vec![
+                vec![CornerNW, SpawnPoint, StraightH, CornerNE],
+                vec![CornerSW, StraightH, StraightH, CornerSE],
+            ],
+            tags: Vec::new(),
+            lap_direction: LapDirection::Forward,
+            edge: GridEdge::Wall,
+            road_width: None,
+            margins: Default::default(),
+        })
+        .expect("valid tiny track");
+        vec![
+            RegisteredTrack {
+                name: "ring".to_string(),
+                track: ring_track(),
+            },
+            RegisteredTrack {
+                name: "oval".to_string(),
+                track: oval,
+            },
+        ]
+    }
+
+    #[test]
+    fn tournament_reports_every_track_and_is_reproducible() {
+        let mut config = AppConfig::default();
+        config.episode.timeout_s = 4.0;
+        let settings = TournamentSettings {
+            master_seed: 11,
+            episodes_per_track: 3,
+        };
+
+        let report = run_tournament(
+            tiny_tracks(),
+            &config,
+            settings,
+            &mut CenterlineFollower::new(),
+            "follower",
+        );
+
+        assert_eq!(report.controller, "follower");
+        assert_eq!(report.master_seed, 11);
+        let names: Vec<&str> = report.tracks.iter().map(|t| t.track.as_str()).collect();
+        assert_eq!(names, ["ring", "oval"]);
+        for result in &report.tracks {
+            assert_eq!(result.episodes, 3);
+            assert_eq!(result.outcomes.len(), 3);
+            assert_eq!(result.laps + result.crashes + result.timeouts, 3);
+            assert!((result.lap_rate - result.laps as f32 / 3.0).abs() < 1e-6);
+            assert_eq!(result.best_lap_ticks.is_some(), result.laps > 0);
+            let episodes: Vec<u32> = result.outcomes.iter().map(|o| o.episode).collect();
+            assert_eq!(episodes, [1, 2, 3]);
+        }
+        assert_eq!(
+            report.tracks[0].outcomes[0].seed,
+            report.tracks[1].outcomes[0].seed
+        );
+        assert_eq!(report.table().lines().count(), 2 + report.tracks.len());
+
+        let json = serde_json::to_string(&report).expect("serializes");
+        let parsed: super::TournamentReport = serde_json::from_str(&json).expect("parses");
+        assert_eq!(parsed, report);
+
+        let rerun = run_tournament(
+            tiny_tracks(),
+            &config,
+            settings,
+            &mut CenterlineFollower::new(),
+            "follower",
+        );
+        assert_eq!(rerun, report);
+    }
+
+    #[test]
+    fn the_registry_puts_sepang_before_the_file_tracks() {
+        let dir = std::env::temp_dir().join("neurodrive_tournament_registry_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("creates dir");
+        std::fs::copy("assets/tracks/ring.ron", dir.join("ring.ron")).expect("copies track");
+        std::fs::write(dir.join("broken.ron"), "not a track").expect("writes file");
+
+        let tracks = load_track_registry(&dir).expect("reads dir");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let names: Vec<&str> = tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names[0], BUILTIN_TRACK_NAME);
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn policies_load_from_their_weight_files() {
+        let config = AppConfig::default();
+        let dim = config.observation.observation_dim();
+        let dir = std::env::temp_dir().join("neurodrive_tournament_policy_test");
+        let (a2c_path, mlp_path) = (dir.join("a2c.json"), dir.join("policy.json"));
+        A2cCheckpoint::from_model(&A2cBrain::seeded(5, dim).model)
+            .save(&a2c_path)
+            .expect("saves checkpoint");
+        let layers = vec![dim, 2];
+        PolicyMlp::new(layers.clone(), vec![0.1; PolicyMlp::param_count(&layers)])
+            .expect("valid network")
+            .save(&mlp_path)
+            .expect("saves weights");
+
+        let a2c = TournamentPolicy::from_args("a2c", a2c_path.to_str(), &config).expect("parses");
+        assert_eq!(
+            a2c,
+            TournamentPolicy::A2c {
+                checkpoint: Some(a2c_path.clone())
+            }
+        );
+        let mlp = TournamentPolicy::from_args("mlp", mlp_path.to_str(), &config).expect("parses");
+        assert!(TournamentPolicy::from_args("mlp", None, &config).is_err());
+        assert!(TournamentPolicy::from_args("pid", None, &config).is_err());
+
+        let settings = TournamentSettings {
+            master_seed: 3,
+            episodes_per_track: 1,
+        };
+        let mut config = config;
+        config.episode.timeout_s = 1.0;
+        for policy in [&a2c, &mlp] {
+            let mut controller = policy.load(&config, 3).expect("loads");
+            let report = run_tournament(
+                tiny_tracks(),
+                &config,
+                settings,
+                controller.as_mut(),
+                policy.name(),
+            );
+            assert_eq!(report.controller, policy.name());
+            assert!(report.tracks.iter().all(|t| t.episodes == 1));
+        }
+
+        let mut resized = config.clone();
+        resized.observation.include_previous_action = !resized.observation.include_previous_action;
+        assert!(a2c.load(&resized, 3).is_err());
+        assert!(mlp.load(&resized, 3).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,454 @@
+//! WebSocket telemetry and control for browser dashboards.
+//!
+//! With the `websocket` cargo feature and config `websocket.enabled`, every
+//! fixed tick is broadcast as a JSON [`WsServerMessage::Telemetry`] frame to
+//! any number of subscribers. Each subscriber has a bounded queue of
+//! `queue_frames`; when a slow client's queue is full its frames are dropped
+//! so the simulation never waits on a socket.
+//!
+//! One session at a time may take control by sending
+//! [`WsClientMessage::Authorize`] with the configured `control_token`. Its
+//! [`WsClientMessage::Action`] frames use the TCP protocol's action fields
+//! and drive the [`AgentMode::External`] controller, latest action wins.
+//! Without a token the endpoint is read-only.
+//!
+//! `examples/telemetry_dashboard.html` plots speed and progress live.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::car::Car;
+use crate::game::episode::{EpisodeState, EpisodeSummary};
+use crate::game::progress::TrackProgress;
+use crate::sim::clock::SimClock;
+#[cfg(feature = "websocket")]
+use crate::sim::sets::SimSet;
+
+/// WebSocket endpoint settings (config `websocket`).
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSocketConfig {
+    /// Only takes effect in builds with the `websocket` feature.
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+    /// Token a session must present to send actions; `None` keeps the
+    /// endpoint read-only.
+    pub control_token: Option<String>,
+    /// Frames buffered per subscriber before new ones are dropped.
+    pub queue_frames: usize,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 8765,
+            control_token: None,
+            queue_frames: 8,
+        }
+    }
+}
+
+/// State of the car after one fixed tick.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryFrame {
+    pub tick: u64,
+    pub episode: u32,
+    pub episode_tick: u32,
+    pub x: f32,
+    pub y: f32,
+    /// Heading in radians, counter-clockwise from east.
+    pub heading: f32,
+    pub speed: f32,
+    /// Lap fraction in `[0, 1)`.
+    pub progress: f32,
+    pub reward: f32,
+    pub episode_return: f32,
+    /// The last finished episode, if any.
+    pub last_episode: Option<EpisodeSummary>,
+}
+
+impl TelemetryFrame {
+    pub fn capture(
+        clock: &SimClock,
+        episode_state: &EpisodeState,
+        transform: &Transform,
+        car: &Car,
+        progress: &TrackProgress,
+    ) -> Self {
+        let forward = (transform.rotation * Vec3::X).truncate();
+        Self {
+            tick: clock.tick,
+            episode: episode_state.current_episode,
+            episode_tick: episode_state.ticks_in_episode,
+            x: transform.translation.x,
+            y: transform.translation.y,
+            heading: forward.y.atan2(forward.x),
+            speed: car.velocity.length(),
+            progress: progress.fraction,
+            reward: episode_state.current_tick_reward,
+            episode_return: episode_state.current_return,
+            last_episode: episode_state.last_episode_summary(),
+        }
+    }
+}
+
+/// Server-to-browser frames.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsServerMessage {
+    Telemetry(TelemetryFrame),
+    ControlGranted,
+    ControlDenied { reason: String },
+}
+
+/// Browser-to-server frames.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsClientMessage {
+    Authorize {
+        token: String,
+    },
+    /// Same fields as the TCP protocol's action; `tick` is informational.
+    Action {
+        #[serde(default)]
+        tick: u64,
+        steering: f32,
+        throttle: f32,
+    },
+}
+
+/// Starts the endpoint and broadcasts telemetry after each tick.
+pub struct WebSocketPlugin;
+
+impl Plugin for WebSocketPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WebSocketConfig>()
+            .add_systems(PostStartup, start_websocket_server_system);
+        #[cfg(feature = "websocket")]
+        app.add_systems(
+            FixedUpdate,
+            server::broadcast_telemetry_system.after(SimSet::Measurement),
+        );
+    }
+}
+
+#[cfg(not(feature = "websocket"))]
+fn start_websocket_server_system(config: Res<WebSocketConfig>) {
+    if config.enabled {
+        warn!("websocket.enabled is set but this build lacks the `websocket` feature.");
+    }
+}
+
+#[cfg(feature = "websocket")]
+use server::start_websocket_server_system;
+
+#[cfg(feature = "websocket")]
+mod server {
+    use std::io;
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+    use tungstenite::{Message, WebSocket};
+
+    use super::{TelemetryFrame, WebSocketConfig, WsClientMessage, WsServerMessage};
+    use crate::agent::action::CarAction;
+    use crate::agent::controller::{Controllers, ExternalActionHandle};
+    use crate::game::car::Car;
+    use crate::game::episode::EpisodeState;
+    use crate::game::progress::TrackProgress;
+    use crate::sim::clock::SimClock;
+
+    /// How long a session thread blocks reading before flushing its queue.
+    const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+    /// Frame queues of the connected subscribers.
+    #[derive(Resource, Default)]
+    pub struct WebSocketHub {
+        subscribers: Arc<Mutex<Vec<SyncSender<Arc<str>>>>>,
+        /// Frames dropped because a subscriber's queue was full.
+        pub dropped_frames: u64,
+    }
+
+    pub fn start_websocket_server_system(
+        mut commands: Commands,
+        config: Res<WebSocketConfig>,
+        controllers: Res<Controllers>,
+    ) {
+        if !config.enabled {
+            return;
+        }
+        let listener = match TcpListener::bind((config.bind_address.as_str(), config.port)) {
+            Ok(listener) => listener,
+            Err(err) => {
+                warn!(
+                    "WebSocket endpoint cannot listen on {}:{}: {err}",
+                    config.bind_address, config.port
+                );
+                return;
+            }
+        };
+        info!(
+            "WebSocket telemetry on ws://{}:{}.",
+            config.bind_address, config.port
+        );
+
+        let hub = WebSocketHub::default();
+        let shared = Shared {
+            subscribers: hub.subscribers.clone(),
+            controller: Arc::new(AtomicU64::new(NO_CONTROLLER)),
+            next_id: Arc::new(AtomicU64::new(1)),
+            token: config.control_token.clone(),
+            queue_frames: config.queue_frames.max(1),
+            handle: controllers.external_handle(),
+        };
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let shared = shared.clone();
+                        std::thread::spawn(move || run_session(stream, shared));
+                    }
+                    Err(err) => warn!("WebSocket accept failed: {err}"),
+                }
+            }
+        });
+        commands.insert_resource(hub);
+    }
+
+    const NO_CONTROLLER: u64 = 0;
+
+    /// State every session thread shares.
+    #[derive(Clone)]
+    struct Shared {
+        subscribers: Arc<Mutex<Vec<SyncSender<Arc<str>>>>>,
+        controller: Arc<AtomicU64>,
+        next_id: Arc<AtomicU64>,
+        token: Option<String>,
+        queue_frames: usize,
+        handle: ExternalActionHandle,
+    }
+
+    fn run_session(stream: TcpStream, shared: Shared) {
+        if let Err(err) = stream.set_read_timeout(Some(POLL_INTERVAL)) {
+            warn!("WebSocket session setup failed: {err}");
+            return;
+        }
+        let mut socket = match tungstenite::accept(stream) {
+            Ok(socket) => socket,
+            Err(err) => {
+                warn!("WebSocket handshake failed: {err}");
+                return;
+            }
+        };
+        let id = shared.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, frames) = mpsc::sync_channel(shared.queue_frames);
+        if let Ok(mut subscribers) = shared.subscribers.lock() {
+            subscribers.push(sender);
+        }
+
+        let _ = serve(&mut socket, &frames, &shared, id);
+
+        // Frees control; the hub drops the queue on its next failed send.
+        let _ = shared.controller.compare_exchange(
+            id,
+            NO_CONTROLLER,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+        let _ = socket.close(None);
+    }
+
+    fn serve(
+        socket: &mut WebSocket<TcpStream>,
+        frames: &Receiver<Arc<str>>,
+        shared: &Shared,
+        id: u64,
+    ) -> Result<(), Box<tungstenite::Error>> {
+        loop {
+            while let Ok(frame) = frames.try_recv() {
+                socket.send(Message::text(&*frame))?;
+            }
+            let message = match socket.read() {
+                Ok(message) => message,
+                Err(tungstenite::Error::Io(err))
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            let Ok(text) = message.to_text() else {
+                continue;
+            };
+            match serde_json::from_str::<WsClientMessage>(text) {
+                Ok(WsClientMessage::Authorize { token }) => {
+                    let reply = authorize(shared, id, &token);
+                    let json = serde_json::to_string(&reply).unwrap_or_default();
+                    socket.send(Message::text(json))?;
+                }
+                Ok(WsClientMessage::Action {
+                    steering, throttle, ..
+                }) => {
+                    if shared.controller.load(Ordering::Acquire) == id {
+                        shared.handle.set(CarAction { steering, throttle });
+                    }
+                }
+                Err(err) => debug!("Ignoring WebSocket message: {err}"),
+            }
+        }
+    }
+
+    fn authorize(shared: &Shared, id: u64, token: &str) -> WsServerMessage {
+        if shared.token.as_deref() != Some(token) {
+            return WsServerMessage::ControlDenied {
+                reason: "invalid token".to_string(),
+            };
+        }
+        match shared.controller.compare_exchange(
+            NO_CONTROLLER,
+            id,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => WsServerMessage::ControlGranted,
+            Err(current) if current == id => WsServerMessage::ControlGranted,
+            Err(_) => WsServerMessage::ControlDenied {
+                reason: "another session has control".to_string(),
+            },
+        }
+    }
+
+    /// Queues this tick's frame for every subscriber, dropping it for any
+    /// whose queue is full and forgetting those that disconnected.
+    pub fn broadcast_telemetry_system(
+        hub: Option<ResMut<WebSocketHub>>,
+        clock: Res<SimClock>,
+        episode_state: Res<EpisodeState>,
+        cars: Query<(&Transform, &Car, &TrackProgress)>,
+    ) {
+        let Some(mut hub) = hub else {
+            return;
+        };
+        let Ok((transform, car, progress)) = cars.single() else {
+            return;
+        };
+        let frame = WsServerMessage::Telemetry(TelemetryFrame::capture(
+            &clock,
+            &episode_state,
+            transform,
+            car,
+            progress,
+        ));
+        let Ok(json) = serde_json::to_string(&frame) else {
+            return;
+        };
+        let json: Arc<str> = json.into();
+
+        let mut dropped = 0;
+        if let Ok(mut subscribers) = hub.subscribers.lock() {
+            subscribers.retain(|subscriber| match subscriber.try_send(json.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    dropped += 1;
+                    true
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            });
+        }
+        hub.dropped_frames += dropped;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TelemetryFrame, WsClientMessage, WsServerMessage};
+    use crate::game::car::Car;
+    use crate::game::episode::{EpisodeEndReason, EpisodeState};
+    use crate::game::progress::TrackProgress;
+    use crate::sim::clock::SimClock;
+    use bevy::prelude::*;
+
+    #[test]
+    fn telemetry_frames_serialize_with_a_type_tag() {
+        let clock = SimClock {
+            tick: 42,
+            elapsed_s: 0.7,
+        };
+        let episode_state = EpisodeState {
+            ticks_in_episode: 12,
+            current_tick_reward: 0.25,
+            last_end_reason: Some(EpisodeEndReason::Crash),
+            last_episode_ticks: 300,
+            ..EpisodeState::default()
+        };
+        let transform = Transform::from_xyz(10.0, -5.0, 0.0)
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let car = Car {
+            velocity: Vec2::new(3.0, 4.0),
+            ..Car::default()
+        };
+        let progress = TrackProgress {
+            fraction: 0.5,
+            ..TrackProgress::default()
+        };
+
+        let frame = TelemetryFrame::capture(&clock, &episode_state, &transform, &car, &progress);
+        assert_eq!(frame.speed, 5.0);
+        assert!((frame.heading - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        let message = WsServerMessage::Telemetry(frame);
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&message).unwrap()).unwrap();
+
+        assert_eq!(json["type"], "telemetry");
+        assert_eq!(json["tick"], 42);
+        assert_eq!(json["episode_tick"], 12);
+        assert_eq!(json["progress"], 0.5);
+        assert_eq!(json["last_episode"]["end_reason"], "Crash");
+        assert_eq!(json["last_episode"]["ticks"], 300);
+        let parsed: WsServerMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, message);
+
+        let denied = serde_json::to_string(&WsServerMessage::ControlDenied {
+            reason: "busy".to_string(),
+        })
+        .unwrap();
+        assert_eq!(denied, r#"{"type":"control_denied","reason":"busy"}"#);
+    }
+
+    #[test]
+    fn control_messages_accept_the_tcp_action_schema() {
+        let action: WsClientMessage =
+            serde_json::from_str(r#"{"type":"action","tick":7,"steering":-0.5,"throttle":1.0}"#)
+                .unwrap();
+        assert_eq!(
+            action,
+            WsClientMessage::Action {
+                tick: 7,
+                steering: -0.5,
+                throttle: 1.0,
+            }
+        );
+        let untimed: WsClientMessage =
+            serde_json::from_str(r#"{"type":"action","steering":0.0,"throttle":0.5}"#).unwrap();
+        assert!(matches!(untimed, WsClientMessage::Action { tick: 0, .. }));
+        let authorize: WsClientMessage =
+            serde_json::from_str(r#"{"type":"authorize","token":"s3cret"}"#).unwrap();
+        assert_eq!(
+            authorize,
+            WsClientMessage::Authorize {
+                token: "s3cret".to_string(),
+            }
+        );
+        assert!(serde_json::from_str::<WsClientMessage>(r#"{"type":"reset","tick":1}"#).is_err());
+    }
+}
@@ -1,6 +1,12 @@
 use bevy::prelude::*;
 
-use crate::agent::observation::{ObservationVector, SensorReadings};
+use crate::agent::observation::{
+    ObservationConfig, ObservationDelayBuffer, ObservationVector, SensorReadings,
+};
+use crate::game::checkpoints::CheckpointProgress;
+use crate::game::collision::OffTrackState;
+use crate::game::episode::LapValidation;
+use crate::game::physics::{BoostState, CarDynamicsParams};
 use crate::game::progress::TrackProgress;
 
 /// Marker component identifying the player's car entity.
@@ -10,15 +16,24 @@ pub struct Car {
     pub rotation_speed: f32,
     pub thrust: f32,
     pub drag: f32,
+    pub boost: BoostState,
 }
 
 impl Default for Car {
     fn default() -> Self {
+        Self::from_params(CarDynamicsParams::default())
+    }
+}
+
+impl Car {
+    /// A stationary car with the given dynamics tuning.
+    pub fn from_params(params: CarDynamicsParams) -> Self {
         Self {
             velocity: Vec2::ZERO,
-            rotation_speed: 4.0,
-            thrust: 750.0,
-            drag: 0.985,
+            rotation_speed: params.rotation_speed,
+            thrust: params.thrust,
+            drag: params.drag,
+            boost: BoostState::default(),
         }
     }
 }
@@ -27,13 +42,40 @@ impl Default for Car {
 pub const CAR_WIDTH: f32 = 12.0;
 pub const CAR_HEIGHT: f32 = 6.0;
 
+/// Clears the per-car history a car teleported to `rotation` must not carry
+/// over, leaving it as [`spawn_car`] would: off-track ticks, the yaw-rate
+/// heading sample and held rays, and delayed observations. Every reset path
+/// calls this after moving the car.
+pub fn clear_car_history(
+    rotation: f32,
+    off_track: Option<&mut OffTrackState>,
+    sensors: Option<&mut SensorReadings>,
+    delay: Option<&mut ObservationDelayBuffer>,
+) {
+    if let Some(off_track) = off_track {
+        *off_track = OffTrackState::default();
+    }
+    if let Some(sensors) = sensors {
+        sensors.reset_history(rotation);
+    }
+    if let Some(delay) = delay {
+        delay.clear();
+    }
+}
+
 /// Spawns the car entity at a given position and rotation.
-pub fn spawn_car(commands: &mut Commands, position: Vec2, rotation: f32) {
+pub fn spawn_car(
+    commands: &mut Commands,
+    position: Vec2,
+    rotation: f32,
+    params: CarDynamicsParams,
+    observation: &ObservationConfig,
+) {
     info!(
         "Spawn car entity at ({:.1}, {:.1}) rot {:.2}.",
         position.x, position.y, rotation
     );
-    let mut sensor_readings = SensorReadings::default();
+    let mut sensor_readings = SensorReadings::for_config(observation);
     sensor_readings.previous_heading = rotation;
 
     commands.spawn((
@@ -44,9 +86,13 @@ pub fn spawn_car(commands: &mut Commands, position: Vec2, rotation: f32) {
         },
         Transform::from_xyz(position.x, position.y, 10.0)
             .with_rotation(Quat::from_rotation_z(rotation)),
-        Car::default(),
+        Car::from_params(params),
         TrackProgress::default(),
+        CheckpointProgress::default(),
+        LapValidation::default(),
+        OffTrackState::default(),
         sensor_readings,
-        ObservationVector::default(),
+        ObservationVector::for_config(observation),
+        ObservationDelayBuffer::default(),
     ));
 }
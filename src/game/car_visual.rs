@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::car::{CAR_HEIGHT, CAR_WIDTH, Car};
+
+/// Visual-only car styling. Collision dimensions are unaffected.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CarVisualConfig {
+    /// Adds a nose triangle over the body so heading is readable at a glance.
+    pub show_nose: bool,
+    /// Draws a fading polyline of recent positions behind the car.
+    pub show_trail: bool,
+    pub body_color: Color,
+    pub nose_color: Color,
+    pub trail_color: Color,
+    /// Maximum number of trail points kept per car.
+    pub trail_length: usize,
+    /// Minimum distance travelled before a new trail point is recorded.
+    pub trail_spacing: f32,
+}
+
+impl Default for CarVisualConfig {
+    fn default() -> Self {
+        Self {
+            show_nose: true,
+            show_trail: true,
+            body_color: Color::srgb(0.9, 0.2, 0.2),
+            nose_color: Color::srgb(1.0, 0.9, 0.3),
+            trail_color: Color::srgb(0.9, 0.4, 0.3),
+            trail_length: 90,
+            trail_spacing: 4.0,
+        }
+    }
+}
+
+/// Marker for the nose-triangle child of a car.
+#[derive(Component)]
+pub struct CarNose;
+
+/// Recent world positions of a car, oldest first.
+#[derive(Component, Clone, Debug, Default)]
+pub struct CarTrail {
+    pub points: VecDeque<Vec2>,
+}
+
+/// Jumps larger than this (episode resets, teleports) restart the trail.
+const TRAIL_BREAK_DISTANCE: f32 = 50.0;
+
+/// Local-space vertices of the nose triangle: the tip sits on the front edge
+/// of the body at `+X`, the base spans the body's full height.
+pub fn nose_triangle_vertices() -> [Vec2; 3] {
+    [
+        Vec2::new(CAR_WIDTH * 0.5, 0.0),
+        Vec2::new(CAR_WIDTH * 0.05, CAR_HEIGHT * 0.5),
+        Vec2::new(CAR_WIDTH * 0.05, -CAR_HEIGHT * 0.5),
+    ]
+}
+
+/// Applies [`CarVisualConfig`] to newly spawned cars: body colour, nose
+/// triangle child, and trail component.
+pub fn attach_car_visuals_system(
+    mut commands: Commands,
+    config: Res<CarVisualConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut car_query: Query<(Entity, &mut Sprite), Added<Car>>,
+) {
+    for (entity, mut sprite) in &mut car_query {
+        sprite.color = config.body_color;
+
+        if config.show_nose {
+            let [tip, left, right] = nose_triangle_vertices();
+            commands.entity(entity).with_children(|parent| {
+                parent.spawn((
+                    CarNose,
+                    Mesh2d(meshes.add(Triangle2d::new(tip, left, right))),
+                    MeshMaterial2d(materials.add(ColorMaterial::from(config.nose_color))),
+                    Transform::from_xyz(0.0, 0.0, 0.1),
+                ));
+            });
+        }
+        if config.show_trail {
+            commands.entity(entity).insert(CarTrail::default());
+        }
+    }
+}
+
+/// Records car positions into their trails.
+pub fn record_car_trail_system(
+    config: Res<CarVisualConfig>,
+    mut car_query: Query<(&Transform, &mut CarTrail), With<Car>>,
+) {
+    for (transform, mut trail) in &mut car_query {
+        let position = transform.translation.truncate();
+        match trail.points.back().copied() {
+            Some(last) if last.distance(position) > TRAIL_BREAK_DISTANCE => {
+                trail.points.clear();
+                trail.points.push_back(position);
+            }
+            Some(last) if last.distance(position) < config.trail_spacing => {}
+            _ => trail.points.push_back(position),
+        }
+        while trail.points.len() > config.trail_length.max(2) {
+            trail.points.pop_front();
+        }
+    }
+}
+
+/// Draws each trail as segments that fade towards the oldest point.
+pub fn draw_car_trail_system(
+    config: Res<CarVisualConfig>,
+    car_query: Query<&CarTrail, With<Car>>,
+    mut gizmos: Gizmos,
+) {
+    if !config.show_trail {
+        return;
+    }
+
+    for trail in &car_query {
+        let count = trail.points.len();
+        for (i, (a, b)) in trail
+            .points
+            .iter()
+            .zip(trail.points.iter().skip(1))
+            .enumerate()
+        {
+            let alpha = (i + 1) as f32 / count as f32;
+            gizmos.line_2d(*a, *b, config.trail_color.with_alpha(alpha * 0.8));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::nose_triangle_vertices;
+    use crate::game::car::{CAR_HEIGHT, CAR_WIDTH};
+
+    #[test]
+    fn nose_triangle_points_along_positive_x() {
+        let [tip, left, right] = nose_triangle_vertices();
+
+        assert!(tip.x > left.x && tip.x > right.x);
+        assert_eq!(tip.y, 0.0);
+        assert_eq!(left.y, -right.y);
+
+        let centroid = (tip + left + right) / 3.0;
+        assert!(centroid.x > 0.0);
+        assert!(
+            (tip - centroid)
+                .normalize()
+                .abs_diff_eq(bevy::prelude::Vec2::X, 1e-6)
+        );
+
+        // The indicator stays within the collision footprint.
+        for vertex in [tip, left, right] {
+            assert!(vertex.x.abs() <= CAR_WIDTH * 0.5);
+            assert!(vertex.y.abs() <= CAR_HEIGHT * 0.5);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::episode::{EpisodeConfig, ProgressRewardMode};
+use crate::maps::centerline::TrackCenterline;
+use crate::maps::track::Track;
+
+/// A single checkpoint line laid across the track.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckpointGate {
+    /// One end of the gate segment (left of the centreline).
+    pub a: Vec2,
+    /// Other end of the gate segment (right of the centreline).
+    pub b: Vec2,
+    /// Unit centreline tangent at the gate; crossings must move along it.
+    pub tangent: Vec2,
+    /// Arc-length position of the gate along the centreline.
+    pub s: f32,
+}
+
+/// Ordered checkpoint gates plus the finish line, built once per track.
+///
+/// Gates sit at evenly spaced centreline fractions `(i + 1) / (K + 1)`, so
+/// none of them coincides with the finish line at `s = 0`.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct CheckpointGates {
+    pub gates: Vec<CheckpointGate>,
+    pub finish: Option<CheckpointGate>,
+}
+
+/// Per-car gate progress, reset at every episode boundary.
+#[derive(Component, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct CheckpointProgress {
+    /// Index of the next gate expected; `gates.len()` means the finish is next.
+    pub next_gate: usize,
+    /// Gates crossed in order during the current lap.
+    pub gates_this_lap: u32,
+    /// Car position on the previous tick, used for swept crossing tests.
+    pub previous_position: Option<Vec2>,
+}
+
+/// Result of advancing gate progress by one tick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GateAdvance {
+    /// Number of gates crossed (in order) this tick.
+    pub gates_crossed: u32,
+    /// `true` when the finish line was crossed after all gates.
+    pub lap_complete: bool,
+}
+
+impl CheckpointProgress {
+    /// Clears gate progress for a new episode.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Tests the swept segment from the previous to the current position
+    /// against the next expected gate(s).
+    ///
+    /// Using the swept segment rather than the current position means a fast
+    /// car cannot skip a gate between ticks. Several gates can be crossed in
+    /// one tick if they are closer together than the distance travelled.
+    pub fn advance(&mut self, position: Vec2, gates: &CheckpointGates) -> GateAdvance {
+        let mut result = GateAdvance::default();
+        let Some(previous) = self.previous_position.replace(position) else {
+            return result;
+        };
+
+        loop {
+            let expected = if self.next_gate < gates.gates.len() {
+                Some(gates.gates[self.next_gate])
+            } else {
+                gates.finish
+            };
+            let Some(gate) = expected else {
+                break;
+            };
+            if !crosses_gate(previous, position, &gate) {
+                break;
+            }
+
+            if self.next_gate < gates.gates.len() {
+                self.next_gate += 1;
+                self.gates_this_lap = self.gates_this_lap.saturating_add(1);
+                result.gates_crossed += 1;
+            } else {
+                self.next_gate = 0;
+                self.gates_this_lap = 0;
+                result.lap_complete = true;
+                break;
+            }
+        }
+
+        result
+    }
+}
+
+/// Builds `count` evenly spaced gates plus the finish line.
+pub fn build_checkpoint_gates(
+    centerline: &TrackCenterline,
+    count: usize,
+    half_width: f32,
+) -> CheckpointGates {
+    let total = centerline.total_length();
+    let gate_at = |s: f32| {
+        let point = centerline.point_at_s(s);
+        let tangent = centerline.tangent_at_s(s);
+        let normal = Vec2::new(-tangent.y, tangent.x);
+        CheckpointGate {
+            a: point + normal * half_width,
+            b: point - normal * half_width,
+            tangent,
+            s,
+        }
+    };
+
+    let gates = (0..count)
+        .map(|i| gate_at(total * (i + 1) as f32 / (count + 1) as f32))
+        .collect();
+
+    CheckpointGates {
+        gates,
+        finish: Some(gate_at(0.0)),
+    }
+}
+
+/// Builds the gate resource from the spawned track and, when a gated mode is
+/// active, renders each gate as a faint line across the road.
+pub fn setup_checkpoint_gates_system(
+    mut commands: Commands,
+    config: Res<EpisodeConfig>,
+    track_query: Query<&Track>,
+) {
+    let Ok(track) = track_query.single() else {
+        return;
+    };
+    let Some(centerline) = &track.centerline else {
+        return;
+    };
+
+    let gates = build_checkpoint_gates(
+        centerline,
+        config.checkpoint_gate_count,
+        track.grid.tile_size * 0.5,
+    );
+
+    if config.progress_mode != ProgressRewardMode::Continuous {
+        for gate in &gates.gates {
+            let delta = gate.b - gate.a;
+            let midpoint = (gate.a + gate.b) * 0.5;
+            commands.spawn((
+                Sprite {
+                    color: Color::srgba(1.0, 1.0, 1.0, 0.18),
+                    custom_size: Some(Vec2::new(delta.length(), 2.0)),
+                    ..default()
+                },
+                Transform::from_xyz(midpoint.x, midpoint.y, 2.0)
+                    .with_rotation(Quat::from_rotation_z(delta.y.atan2(delta.x))),
+            ));
+        }
+        info!(
+            "Checkpoint gates ready: {} gates ({:?} progress mode).",
+            gates.gates.len(),
+            config.progress_mode
+        );
+    }
+
+    commands.insert_resource(gates);
+}
+
+/// Returns `true` if the swept segment crosses `gate` while moving forward
+/// along the gate's tangent.
+fn crosses_gate(from: Vec2, to: Vec2, gate: &CheckpointGate) -> bool {
+    (to - from).dot(gate.tangent) > 0.0 && segments_intersect(from, to, gate.a, gate.b)
+}
+
+fn segments_intersect(p1: Vec2, p2: Vec2, q1: Vec2, q2: Vec2) -> bool {
+    let r = p2 - p1;
+    let s = q2 - q1;
+    let denom = r.perp_dot(s);
+    if denom.abs() <= 1e-9 {
+        return false;
+    }
+
+    let qp = q1 - p1;
+    let t = qp.perp_dot(s) / denom;
+    let u = qp.perp_dot(r) / denom;
+    (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CheckpointProgress, build_checkpoint_gates};
+    use crate::maps::fixtures::ring_centerline;
+
+    #[test]
+    fn scripted_lap_collects_every_gate_exactly_once() {
+        let centerline = ring_centerline();
+        let gates = build_checkpoint_gates(&centerline, 6, 50.0);
+        let mut progress = CheckpointProgress::default();
+
+        let start_s = 50.0;
+        let step = 4.0;
+        let steps = ((centerline.total_length() + 20.0) / step) as usize;
+        let mut total_crossed = 0;
+        let mut laps = 0;
+
+        for i in 0..=steps {
+            let position = centerline.point_at_s(start_s + i as f32 * step);
+            let advance = progress.advance(position, &gates);
+            total_crossed += advance.gates_crossed;
+            if advance.lap_complete {
+                laps += 1;
+                assert_eq!(total_crossed, 6, "lap completed before every gate");
+            }
+        }
+
+        assert_eq!(total_crossed, 6);
+        assert_eq!(laps, 1);
+        assert_eq!(progress.next_gate, 0);
+    }
+
+    #[test]
+    fn reversing_across_a_gate_does_not_count() {
+        let centerline = ring_centerline();
+        let gates = build_checkpoint_gates(&centerline, 4, 50.0);
+        let gate_s = gates.gates[0].s;
+        let mut progress = CheckpointProgress::default();
+
+        progress.advance(centerline.point_at_s(gate_s + 10.0), &gates);
+        let advance = progress.advance(centerline.point_at_s(gate_s - 10.0), &gates);
+
+        assert_eq!(advance.gates_crossed, 0);
+        assert_eq!(progress.next_gate, 0);
+    }
+}
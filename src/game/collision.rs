@@ -1,49 +1,777 @@
 use bevy::ecs::message::MessageWriter;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::game::car::{CAR_HEIGHT, CAR_WIDTH, Car};
+use crate::game::physics::reflect_off_wall;
 use crate::maps::track::Track;
 
-/// Message emitted when the car leaves the driveable road surface.
-#[derive(Message)]
-pub struct CollisionEvent;
+/// Message emitted when a car leaves the driveable road surface.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct CollisionEvent {
+    /// The crashed car; only this car is reset.
+    pub car: Entity,
+    /// World-space contact point: the deepest off-road corner for wall
+    /// crashes, the pair's contact point for car-vs-car resets.
+    pub point: Vec2,
+}
+
+/// Message emitted for each off-road tick that is still inside the grace
+/// window and therefore not (yet) a crash.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct OffTrackWarning {
+    pub car: Entity,
+}
+
+/// Off-track crash detection settings (config `collision`).
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollisionConfig {
+    /// Detect wall contacts at all. `false` is free roam: the car may drive
+    /// anywhere, off-road included, with no warnings, crash reset, or crash
+    /// penalty.
+    pub enabled: bool,
+    /// Consecutive off-road ticks tolerated before a crash is registered.
+    /// `0` crashes on the first off-road tick.
+    pub off_track_grace_ticks: u32,
+    /// Penetration depth (world units past the road edge) that crashes
+    /// immediately, regardless of the grace window.
+    pub max_penetration_depth: f32,
+    /// What happens once an excursion counts as a crash.
+    pub wall_response: WallResponse,
+    /// Fraction of the along-wall speed lost in a [`WallResponse::Bounce`].
+    pub wall_friction: f32,
+}
+
+/// Outcome of a registered wall contact.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WallResponse {
+    /// Terminal [`CollisionEvent`]; the episode ends.
+    Crash,
+    /// Soft wall: the car is pushed back onto the road and its velocity is
+    /// reflected about [`TrackGrid::boundary_normal_at`](crate::maps::grid::TrackGrid::boundary_normal_at).
+    /// The contact still counts as an [`OffTrackWarning`]. `restitution` is
+    /// clamped to `[0, 1]`, so the car never leaves faster than it arrived.
+    Bounce { restitution: f32 },
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            off_track_grace_ticks: 0,
+            max_penetration_depth: 3.0,
+            wall_response: WallResponse::Crash,
+            wall_friction: 0.05,
+        }
+    }
+}
+
+/// Per-car count of consecutive off-road ticks.
+#[derive(Component, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct OffTrackState {
+    pub consecutive_ticks: u32,
+}
+
+/// Outcome of one tick of off-track tracking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffTrackVerdict {
+    OnTrack,
+    /// Off-road but inside the grace window.
+    Warning,
+    Crash,
+}
 
-/// Checks each fixed tick whether any corner of the car's bounding rectangle lies
-/// off the driveable road surface.
+impl OffTrackState {
+    /// Advances the tracker given this tick's penetration depth (`0.0` when
+    /// fully on the road).
+    pub fn update(&mut self, config: &CollisionConfig, depth: f32) -> OffTrackVerdict {
+        if depth <= 0.0 {
+            self.consecutive_ticks = 0;
+            return OffTrackVerdict::OnTrack;
+        }
+
+        self.consecutive_ticks = self.consecutive_ticks.saturating_add(1);
+        if self.consecutive_ticks > config.off_track_grace_ticks
+            || depth > config.max_penetration_depth
+        {
+            self.consecutive_ticks = 0;
+            OffTrackVerdict::Crash
+        } else {
+            OffTrackVerdict::Warning
+        }
+    }
+}
+
+/// Message emitted once per overlapping car pair per tick.
+///
+/// `a` always sorts before `b`, so the event is identical regardless of query
+/// iteration order.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct CarCollisionEvent {
+    pub a: Entity,
+    pub b: Entity,
+    /// World-space contact point (midpoint between the two car centres).
+    pub point: Vec2,
+}
+
+/// What happens physically when two cars overlap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CarCollisionResponse {
+    /// Both cars crash: a terminal [`CollisionEvent`] is raised for each.
+    ResetBoth,
+    /// Equal-mass impulse exchange along the contact normal, plus positional
+    /// separation so the cars stop overlapping.
+    Impulse { restitution: f32 },
+    /// No physical response; only the reward penalty applies.
+    Penalize,
+}
+
+/// Car-vs-car collision settings.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct CarCollisionConfig {
+    pub response: CarCollisionResponse,
+}
+
+impl Default for CarCollisionConfig {
+    fn default() -> Self {
+        Self {
+            response: CarCollisionResponse::Impulse { restitution: 0.3 },
+        }
+    }
+}
+
+/// Oriented car footprint in world space.
+#[derive(Clone, Copy, Debug)]
+pub struct CarBox {
+    pub center: Vec2,
+    pub heading: f32,
+}
+
+/// Separating-axis overlap result for two car boxes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoxContact {
+    /// Unit normal pointing from the first box towards the second.
+    pub normal: Vec2,
+    /// Penetration depth along `normal`.
+    pub depth: f32,
+    pub point: Vec2,
+}
+
+impl CarBox {
+    fn axes(&self) -> [Vec2; 2] {
+        let forward = Vec2::from_angle(self.heading);
+        [forward, forward.perp()]
+    }
+
+    fn half_extents(&self) -> [f32; 2] {
+        [CAR_WIDTH * 0.5, CAR_HEIGHT * 0.5]
+    }
+
+    /// Half-length of this box's projection onto `axis`.
+    fn projected_radius(&self, axis: Vec2) -> f32 {
+        let [x, y] = self.axes();
+        let [hx, hy] = self.half_extents();
+        hx * x.dot(axis).abs() + hy * y.dot(axis).abs()
+    }
+}
+
+impl CarBox {
+    /// Radius of the circle enclosing the box, for broad-phase rejection.
+    pub fn bounding_radius() -> f32 {
+        Vec2::new(CAR_WIDTH, CAR_HEIGHT).length() * 0.5
+    }
+
+    /// Distance along the unit `direction` from `origin` to where the ray
+    /// enters this box, or `None` when it misses within `max_range`. An
+    /// origin inside the box hits at `0`.
+    pub fn ray_distance(&self, origin: Vec2, direction: Vec2, max_range: f32) -> Option<f32> {
+        let [x, y] = self.axes();
+        let offset = origin - self.center;
+        let mut enter = 0.0_f32;
+        let mut exit = max_range;
+        for (axis, half) in [x, y].into_iter().zip(self.half_extents()) {
+            let start = offset.dot(axis);
+            let speed = direction.dot(axis);
+            if speed.abs() < 1e-9 {
+                if start.abs() > half {
+                    return None;
+                }
+                continue;
+            }
+            let (near, far) = {
+                let a = (-half - start) / speed;
+                let b = (half - start) / speed;
+                (a.min(b), a.max(b))
+            };
+            enter = enter.max(near);
+            exit = exit.min(far);
+            if enter > exit {
+                return None;
+            }
+        }
+        Some(enter)
+    }
+
+    /// Outward unit normal of the box face nearest `point`.
+    pub fn surface_normal(&self, point: Vec2) -> Vec2 {
+        let [x, y] = self.axes();
+        let [hx, hy] = self.half_extents();
+        let offset = point - self.center;
+        let (along_x, along_y) = (offset.dot(x), offset.dot(y));
+        if along_x.abs() / hx >= along_y.abs() / hy {
+            x * along_x.signum()
+        } else {
+            y * along_y.signum()
+        }
+    }
+}
+
+/// Tests two car boxes for overlap using the separating-axis theorem.
+pub fn car_boxes_overlap(a: &CarBox, b: &CarBox) -> Option<BoxContact> {
+    let delta = b.center - a.center;
+    let mut best: Option<(f32, Vec2)> = None;
+
+    for axis in a.axes().into_iter().chain(b.axes()) {
+        let overlap = a.projected_radius(axis) + b.projected_radius(axis) - delta.dot(axis).abs();
+        if overlap <= 0.0 {
+            return None;
+        }
+        if best.is_none_or(|(depth, _)| overlap < depth) {
+            let normal = if delta.dot(axis) >= 0.0 { axis } else { -axis };
+            best = Some((overlap, normal));
+        }
+    }
+
+    best.map(|(depth, normal)| BoxContact {
+        normal,
+        depth,
+        point: (a.center + b.center) * 0.5,
+    })
+}
+
+/// Finds every overlapping pair, ordered by key so results do not depend on
+/// input order. Each returned pair has `a < b`.
+pub fn detect_car_pairs<K: Ord + Copy>(cars: &[(K, CarBox)]) -> Vec<(K, K, BoxContact)> {
+    let mut sorted: Vec<(K, CarBox)> = cars.to_vec();
+    sorted.sort_by_key(|(key, _)| *key);
+
+    let mut pairs = Vec::new();
+    for i in 0..sorted.len() {
+        for j in (i + 1)..sorted.len() {
+            let (key_a, box_a) = sorted[i];
+            let (key_b, box_b) = sorted[j];
+            if let Some(contact) = car_boxes_overlap(&box_a, &box_b) {
+                pairs.push((key_a, key_b, contact));
+            }
+        }
+    }
+    pairs
+}
+
+/// Equal-mass impulse exchange along `normal` (pointing from `a` to `b`).
+///
+/// Only approaching cars are affected; cars already separating keep their
+/// velocities.
+pub fn exchange_impulse(va: &mut Vec2, vb: &mut Vec2, normal: Vec2, restitution: f32) {
+    let approach = (*vb - *va).dot(normal);
+    if approach >= 0.0 {
+        return;
+    }
+    let impulse = 0.5 * (1.0 + restitution.clamp(0.0, 1.0)) * approach;
+    *va += normal * impulse;
+    *vb -= normal * impulse;
+}
+
+/// Detects car-vs-car overlaps and applies the configured response.
+///
+/// Pairs are processed in entity order, so the outcome is deterministic and
+/// independent of query iteration order.
+pub fn car_collision_system(
+    config: Res<CarCollisionConfig>,
+    mut car_query: Query<(Entity, &mut Transform, &mut Car)>,
+    mut car_collision_events: MessageWriter<CarCollisionEvent>,
+    mut collision_events: MessageWriter<CollisionEvent>,
+) {
+    let cars: Vec<(Entity, CarBox)> = car_query
+        .iter()
+        .map(|(entity, transform, _)| {
+            let forward = (transform.rotation * Vec3::X).truncate();
+            (
+                entity,
+                CarBox {
+                    center: transform.translation.truncate(),
+                    heading: forward.y.atan2(forward.x),
+                },
+            )
+        })
+        .collect();
+    if cars.len() < 2 {
+        return;
+    }
+
+    for (a, b, contact) in detect_car_pairs(&cars) {
+        car_collision_events.write(CarCollisionEvent {
+            a,
+            b,
+            point: contact.point,
+        });
+
+        match config.response {
+            CarCollisionResponse::ResetBoth => {
+                for car in [a, b] {
+                    collision_events.write(CollisionEvent {
+                        car,
+                        point: contact.point,
+                    });
+                }
+            }
+            CarCollisionResponse::Impulse { restitution } => {
+                let Ok([mut first, mut second]) = car_query.get_many_mut([a, b]) else {
+                    continue;
+                };
+                exchange_impulse(
+                    &mut first.2.velocity,
+                    &mut second.2.velocity,
+                    contact.normal,
+                    restitution,
+                );
+                let push = (contact.normal * contact.depth * 0.5).extend(0.0);
+                first.1.translation -= push;
+                second.1.translation += push;
+            }
+            CarCollisionResponse::Penalize => {}
+        }
+    }
+}
+
+/// Checks each fixed tick whether any corner of each car's bounding rectangle
+/// lies off the driveable road surface.
+///
+/// Every car keeps its own [`OffTrackState`], and the messages it raises carry
+/// its entity.
 ///
 /// The four corners of the car sprite (defined by [`CAR_WIDTH`] × [`CAR_HEIGHT`])
 /// are rotated into world space and tested individually against
-/// `track.grid.is_road_at()`. A collision is triggered as soon as any corner
-/// leaves the road, giving accurate edge-level detection rather than
-/// centre-only checking.
+/// `track.grid.is_road_at()`. Off-road ticks inside
+/// [`CollisionConfig::off_track_grace_ticks`] raise an [`OffTrackWarning`];
+/// a terminal [`CollisionEvent`] is emitted once the excursion outlasts the
+/// window or penetrates deeper than [`CollisionConfig::max_penetration_depth`].
+/// With [`WallResponse::Bounce`] that contact instead reflects the car off
+/// the wall at the deepest off-road corner.
 pub fn collision_detection_system(
-    car_query: Query<&Transform, With<Car>>,
+    config: Res<CollisionConfig>,
+    mut car_query: Query<(Entity, &mut Transform, &mut Car, &mut OffTrackState)>,
     track_query: Query<&Track>,
     mut collision_events: MessageWriter<CollisionEvent>,
+    mut warning_events: MessageWriter<OffTrackWarning>,
 ) {
-    let Ok(car_transform) = car_query.single() else {
+    if !config.enabled {
+        for (_, _, _, mut off_track) in &mut car_query {
+            *off_track = OffTrackState::default();
+        }
         return;
-    };
+    }
     let Ok(track) = track_query.single() else {
         return;
     };
 
-    let car_pos = Vec2::new(car_transform.translation.x, car_transform.translation.y);
+    for (entity, mut car_transform, mut car, mut off_track) in &mut car_query {
+        detect_wall_contact(
+            &config,
+            track,
+            entity,
+            (&mut car_transform, &mut car, &mut off_track),
+            &mut collision_events,
+            &mut warning_events,
+        );
+    }
+}
+
+/// Runs off-track detection and the wall response for one car.
+fn detect_wall_contact(
+    config: &CollisionConfig,
+    track: &Track,
+    entity: Entity,
+    (car_transform, car, off_track): (&mut Transform, &mut Car, &mut OffTrackState),
+    collision_events: &mut MessageWriter<CollisionEvent>,
+    warning_events: &mut MessageWriter<OffTrackWarning>,
+) {
+    let car_pos = car_transform.translation.truncate();
+    let corners = car_corners(car_pos, car_transform.rotation);
+    let deepest = deepest_off_road_corner(car_pos, &corners, |point| track.grid.is_road_at(point));
+    let depth = deepest.map_or(0.0, |(_, depth)| depth);
+
+    match off_track.update(config, depth) {
+        OffTrackVerdict::OnTrack => {}
+        OffTrackVerdict::Warning => {
+            warning_events.write(OffTrackWarning { car: entity });
+        }
+        OffTrackVerdict::Crash => {
+            let bounce = match (config.wall_response, deepest) {
+                (WallResponse::Bounce { restitution }, Some((corner, depth))) => track
+                    .grid
+                    .boundary_normal_at(corner)
+                    .map(|normal| (normal, depth, restitution)),
+                _ => None,
+            };
+            match bounce {
+                Some((normal, depth, restitution)) => {
+                    car.velocity =
+                        reflect_off_wall(car.velocity, normal, restitution, config.wall_friction);
+                    car_transform.translation += (normal * depth).extend(0.0);
+                    warning_events.write(OffTrackWarning { car: entity });
+                }
+                None => {
+                    collision_events.write(CollisionEvent {
+                        car: entity,
+                        point: deepest.map_or(car_pos, |(corner, _)| corner),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// World-space corners of a car rectangle.
+fn car_corners(center: Vec2, rotation: Quat) -> [Vec2; 4] {
     let half_w = CAR_WIDTH * 0.5;
     let half_h = CAR_HEIGHT * 0.5;
-
-    let local_corners = [
+    [
         Vec2::new(half_w, half_h),
         Vec2::new(half_w, -half_h),
         Vec2::new(-half_w, half_h),
         Vec2::new(-half_w, -half_h),
-    ];
+    ]
+    .map(|local| center + (rotation * local.extend(0.0)).truncate())
+}
+
+/// Deepest distance any corner sits past the road edge, measured along the
+/// line from the corner back towards the car centre. `0.0` when every
+/// corner is on the road.
+pub fn penetration_depth(center: Vec2, corners: &[Vec2], is_road: impl Fn(Vec2) -> bool) -> f32 {
+    deepest_off_road_corner(center, corners, is_road).map_or(0.0, |(_, depth)| depth)
+}
+
+/// The off-road corner furthest past the road edge, with its depth.
+fn deepest_off_road_corner(
+    center: Vec2,
+    corners: &[Vec2],
+    is_road: impl Fn(Vec2) -> bool,
+) -> Option<(Vec2, f32)> {
+    let mut deepest: Option<(Vec2, f32)> = None;
+    for &corner in corners {
+        if is_road(corner) {
+            continue;
+        }
+        let depth = if !is_road(center) {
+            corner.distance(center)
+        } else {
+            // Bisect between the off-road corner and the on-road centre.
+            let (mut off, mut on) = (corner, center);
+            for _ in 0..12 {
+                let mid = (off + on) * 0.5;
+                if is_road(mid) {
+                    on = mid;
+                } else {
+                    off = mid;
+                }
+            }
+            corner.distance(on)
+        };
+        if deepest.is_none_or(|(_, best)| depth > best) {
+            deepest = Some((corner, depth));
+        }
+    }
+    deepest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CarBox, CarCollisionConfig, CarCollisionEvent, CarCollisionResponse, CollisionConfig,
+        CollisionEvent, OffTrackState, OffTrackVerdict, car_boxes_overlap, car_collision_system,
+        detect_car_pairs, exchange_impulse, penetration_depth,
+    };
+    use crate::game::car::Car;
+    use crate::game::physics::{
+        CarDynamicsParams, CarKinematicState, reflect_off_wall, step_car_dynamics,
+    };
+    use crate::maps::fixtures::ring_grid;
+    use crate::sim::rng::SimRng;
+    use bevy::prelude::*;
+    use std::f32::consts::PI;
+
+    #[derive(Resource, Default)]
+    struct SeenMessages {
+        crashed: Vec<Entity>,
+        contacts: usize,
+    }
+
+    fn collect_messages(
+        mut crashes: MessageReader<CollisionEvent>,
+        mut contacts: MessageReader<CarCollisionEvent>,
+        mut seen: ResMut<SeenMessages>,
+    ) {
+        seen.crashed.extend(crashes.read().map(|event| event.car));
+        seen.contacts += contacts.read().count();
+    }
+
+    /// Runs one tick of [`car_collision_system`] on two overlapping cars
+    /// closing head-on, returning the messages and both cars afterwards.
+    fn collide(response: CarCollisionResponse) -> (SeenMessages, [(Entity, Vec2, Vec2); 2]) {
+        let mut app = App::new();
+        app.insert_resource(CarCollisionConfig { response })
+            .init_resource::<SeenMessages>()
+            .add_message::<CollisionEvent>()
+            .add_message::<CarCollisionEvent>()
+            .add_systems(Update, (car_collision_system, collect_messages).chain());
+        let cars = [(-4.0, 50.0), (4.0, -50.0)].map(|(x, vx)| {
+            let car = Car {
+                velocity: Vec2::new(vx, 0.0),
+                ..Car::default()
+            };
+            app.world_mut()
+                .spawn((Transform::from_xyz(x, 0.0, 10.0), car))
+                .id()
+        });
+
+        app.update();
+        let state = cars.map(|entity| {
+            let world = app.world();
+            let position = world
+                .get::<Transform>(entity)
+                .unwrap()
+                .translation
+                .truncate();
+            (entity, position, world.get::<Car>(entity).unwrap().velocity)
+        });
+        let seen = app.world_mut().remove_resource::<SeenMessages>().unwrap();
+        (seen, state)
+    }
+
+    #[test]
+    fn reset_both_crashes_each_car_without_moving_them() {
+        let (seen, [(a, pos_a, vel_a), (b, pos_b, vel_b)]) =
+            collide(CarCollisionResponse::ResetBoth);
+
+        assert_eq!(seen.contacts, 1);
+        // Crashes follow query order, so compare them as a set.
+        let mut crashed = seen.crashed.clone();
+        crashed.sort();
+        let mut expected = [a, b];
+        expected.sort();
+        assert_eq!(crashed, expected);
+        assert_eq!((pos_a, pos_b), (Vec2::new(-4.0, 0.0), Vec2::new(4.0, 0.0)));
+        assert_eq!((vel_a.x, vel_b.x), (50.0, -50.0));
+    }
+
+    #[test]
+    fn penalize_reports_the_contact_but_leaves_the_cars_alone() {
+        let (seen, [(_, pos_a, vel_a), (_, pos_b, vel_b)]) =
+            collide(CarCollisionResponse::Penalize);
+
+        assert_eq!(seen.contacts, 1);
+        assert!(seen.crashed.is_empty());
+        assert_eq!((pos_a, pos_b), (Vec2::new(-4.0, 0.0), Vec2::new(4.0, 0.0)));
+        assert_eq!((vel_a.x, vel_b.x), (50.0, -50.0));
+    }
+
+    #[test]
+    fn impulse_separates_the_cars_and_reverses_their_approach() {
+        let (seen, [(_, pos_a, vel_a), (_, pos_b, vel_b)]) =
+            collide(CarCollisionResponse::Impulse { restitution: 0.3 });
+
+        assert_eq!(seen.contacts, 1);
+        assert!(seen.crashed.is_empty());
+        assert!(pos_a.x < -4.0 && pos_b.x > 4.0);
+        assert!(vel_a.x < 0.0 && vel_b.x > 0.0);
+    }
+
+    #[test]
+    fn head_on_cars_produce_one_symmetric_event_and_bounce_apart() {
+        let car = Car::default();
+        let params = CarDynamicsParams {
+            rotation_speed: car.rotation_speed,
+            thrust: car.thrust,
+            drag: car.drag,
+        };
+        let mut left = CarKinematicState {
+            position: Vec2::new(-60.0, 0.0),
+            velocity: Vec2::ZERO,
+            heading: 0.0,
+        };
+        let mut right = CarKinematicState {
+            position: Vec2::new(60.0, 0.0),
+            velocity: Vec2::ZERO,
+            heading: PI,
+        };
+        let boxes = |left: &CarKinematicState, right: &CarKinematicState| {
+            (
+                CarBox {
+                    center: left.position,
+                    heading: left.heading,
+                },
+                CarBox {
+                    center: right.position,
+                    heading: right.heading,
+                },
+            )
+        };
+
+        let mut contact_tick = None;
+        for tick in 0..300 {
+            step_car_dynamics(&mut left, 0.0, 1.0, 1.0 / 60.0, params);
+            step_car_dynamics(&mut right, 0.0, 1.0, 1.0 / 60.0, params);
+            let (a, b) = boxes(&left, &right);
+            let forward = detect_car_pairs(&[(1u32, a), (2u32, b)]);
+            if forward.is_empty() {
+                continue;
+            }
+
+            let reversed = detect_car_pairs(&[(2u32, b), (1u32, a)]);
+            assert_eq!(forward.len(), 1);
+            assert_eq!(forward, reversed);
+            assert_eq!((forward[0].0, forward[0].1), (1, 2));
+            assert!(forward[0].2.normal.dot(Vec2::X) > 0.99);
+
+            exchange_impulse(
+                &mut left.velocity,
+                &mut right.velocity,
+                forward[0].2.normal,
+                0.3,
+            );
+            contact_tick = Some(tick);
+            break;
+        }
+
+        assert!(contact_tick.is_some(), "cars never met");
+        assert!(left.velocity.x < 0.0, "left car should bounce back");
+        assert!(right.velocity.x > 0.0, "right car should bounce back");
+        assert!((left.velocity.x + right.velocity.x).abs() < 1e-3);
+    }
+
+    #[test]
+    fn separated_boxes_do_not_overlap() {
+        let a = CarBox {
+            center: Vec2::ZERO,
+            heading: 0.0,
+        };
+        let b = CarBox {
+            center: Vec2::new(0.0, 10.0),
+            heading: 0.3,
+        };
+        assert!(car_boxes_overlap(&a, &b).is_none());
+    }
+
+    fn road_below(y_edge: f32) -> impl Fn(Vec2) -> bool {
+        move |point: Vec2| point.y <= y_edge
+    }
+
+    #[test]
+    fn penetration_depth_measures_distance_past_the_edge() {
+        let corners = [Vec2::new(0.0, 3.0), Vec2::new(0.0, -3.0)];
+        let depth = penetration_depth(Vec2::ZERO, &corners, road_below(1.0));
+        assert!((depth - 2.0).abs() < 0.01, "depth {depth}");
+        assert_eq!(
+            penetration_depth(Vec2::ZERO, &corners, road_below(5.0)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn one_tick_graze_warns_but_sustained_excursion_crashes() {
+        let config = CollisionConfig {
+            off_track_grace_ticks: 5,
+            max_penetration_depth: 3.0,
+            ..CollisionConfig::default()
+        };
+        let graze = 0.5;
 
-    for local in &local_corners {
-        let rotated = (car_transform.rotation * Vec3::new(local.x, local.y, 0.0)).truncate();
-        if !track.grid.is_road_at(car_pos + rotated) {
-            collision_events.write(CollisionEvent);
-            return;
+        let mut grazing = OffTrackState::default();
+        assert_eq!(grazing.update(&config, graze), OffTrackVerdict::Warning);
+        for _ in 0..20 {
+            assert_eq!(grazing.update(&config, 0.0), OffTrackVerdict::OnTrack);
         }
+
+        let mut sustained = OffTrackState::default();
+        let verdicts: Vec<_> = (0..6).map(|_| sustained.update(&config, graze)).collect();
+        assert!(verdicts[..5].iter().all(|v| *v == OffTrackVerdict::Warning));
+        assert_eq!(verdicts[5], OffTrackVerdict::Crash);
+
+        let mut deep = OffTrackState::default();
+        assert_eq!(deep.update(&config, 4.0), OffTrackVerdict::Crash);
+    }
+
+    #[test]
+    fn default_config_crashes_on_first_off_road_tick() {
+        let mut state = OffTrackState::default();
+        assert_eq!(
+            state.update(&CollisionConfig::default(), 0.1),
+            OffTrackVerdict::Crash
+        );
+    }
+
+    #[test]
+    fn inner_apex_bounce_points_back_onto_the_road() {
+        let grid = ring_grid();
+        // CornerNW's arc centre (the inner apex) is the NW corner of the
+        // empty infield cell.
+        let apex = Vec2::new(-50.0, 50.0);
+        let contact = apex + Vec2::new(3.0, -3.0);
+        assert!(!grid.is_road_at(contact));
+
+        let normal = grid.boundary_normal_at(contact).expect("road nearby");
+        let incoming = Vec2::new(200.0, -200.0);
+        let reflected = reflect_off_wall(incoming, normal, 1.0, 0.0);
+
+        assert!(normal.dot(contact - apex) < 0.0, "normal {normal:?}");
+        assert!(reflected.dot(normal) > 0.0);
+        assert!(grid.is_road_at(contact + reflected / 60.0));
+    }
+
+    #[test]
+    fn outer_arc_bounce_points_towards_the_arc_centre() {
+        let grid = ring_grid();
+        let arc_center = Vec2::new(-50.0, 50.0);
+        let outward = Vec2::new(-1.0, 1.0).normalize();
+        let contact = arc_center + outward * 99.0;
+        assert!(!grid.is_road_at(contact));
+
+        let normal = grid.boundary_normal_at(contact).expect("road nearby");
+        let reflected = reflect_off_wall(outward * 300.0, normal, 1.0, 0.0);
+
+        assert!(normal.dot(-outward) > 0.99, "normal {normal:?}");
+        assert!(reflected.dot(outward) < 0.0);
+        assert!(grid.is_road_at(contact + reflected / 60.0));
+    }
+
+    #[test]
+    fn glancing_bounces_never_gain_speed_even_with_noisy_normals() {
+        let mut rng = SimRng::new(0xB0B0);
+        for _ in 0..10_000 {
+            let wall = Vec2::from_angle(rng.next_f32() * 2.0 * PI);
+            // Glancing: heading into the wall at up to 20 degrees.
+            let incidence = rng.next_f32() * 20f32.to_radians();
+            let speed = 50.0 + rng.next_f32() * 450.0;
+            let incoming = (wall.perp() * incidence.cos() - wall * incidence.sin()) * speed;
+            // A noisy, unnormalised estimate of the wall's normal.
+            let normal = (wall + Vec2::new(rng.normal(0.0, 0.3), rng.normal(0.0, 0.3)))
+                * (0.5 + rng.next_f32() * 1.5);
+            let restitution = rng.next_f32() * 1.5;
+            let friction = rng.next_f32() * 0.2;
+
+            let outgoing = reflect_off_wall(incoming, normal, restitution, friction);
+            assert!(
+                outgoing.length() <= incoming.length(),
+                "{incoming:?} -> {outgoing:?} (normal {normal:?}, e {restitution})"
+            );
+        }
+
+        // Friction takes speed off a perfectly elastic bounce.
+        let elastic = reflect_off_wall(Vec2::new(300.0, -50.0), Vec2::Y, 1.0, 0.0);
+        let damped = reflect_off_wall(Vec2::new(300.0, -50.0), Vec2::Y, 1.0, 0.5);
+        assert_eq!(elastic, Vec2::new(300.0, 50.0));
+        assert_eq!(damped, Vec2::new(150.0, 50.0));
     }
 }
@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::episode::{EpisodeConfig, EpisodeState};
+use crate::game::reward::RewardStack;
+
+/// Linear interpolation from `start` to `end` over `episodes` completed
+/// episodes, held at `end` afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinearSchedule {
+    pub start: f32,
+    pub end: f32,
+    pub episodes: u32,
+}
+
+impl LinearSchedule {
+    pub fn value_at(&self, completed_episodes: u32) -> f32 {
+        if self.episodes == 0 {
+            return self.end;
+        }
+        let t = (completed_episodes as f32 / self.episodes as f32).min(1.0);
+        self.start + (self.end - self.start) * t
+    }
+}
+
+/// Staged-training schedules applied to the live [`EpisodeConfig`] at every
+/// episode boundary.
+///
+/// Each schedule is indexed by completed episodes, so episode 1 runs with
+/// the schedule's `start`. Unset schedules leave their field alone. Off by
+/// default.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Curriculum {
+    pub enabled: bool,
+    /// Episode timeout in seconds.
+    pub timeout_s: Option<LinearSchedule>,
+    /// Speed below which the car counts as idle; raising it tightens the
+    /// idle penalty.
+    pub idle_speed_threshold: Option<LinearSchedule>,
+}
+
+impl Default for Curriculum {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_s: Some(LinearSchedule {
+                start: 60.0,
+                end: 20.0,
+                episodes: 500,
+            }),
+            idle_speed_threshold: None,
+        }
+    }
+}
+
+impl Curriculum {
+    /// Writes the scheduled values for the episode after `completed_episodes`
+    /// into `config`.
+    pub fn apply(&self, config: &mut EpisodeConfig, completed_episodes: u32) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(schedule) = self.timeout_s {
+            config.timeout_s = schedule.value_at(completed_episodes);
+        }
+        if let Some(schedule) = self.idle_speed_threshold {
+            config.idle_speed_threshold = schedule.value_at(completed_episodes);
+        }
+    }
+}
+
+/// Applies the curriculum for the first episode and loads the result into
+/// the reward stack; later boundaries are handled when the episode loop
+/// finalises an episode.
+pub fn apply_initial_curriculum_system(
+    curriculum: Res<Curriculum>,
+    episode_state: Res<EpisodeState>,
+    mut config: ResMut<EpisodeConfig>,
+    mut reward_stack: ResMut<RewardStack>,
+) {
+    if !curriculum.enabled {
+        return;
+    }
+    curriculum.apply(&mut config, episode_state.current_episode.saturating_sub(1));
+    reward_stack.apply_config(&config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Curriculum, LinearSchedule, apply_initial_curriculum_system};
+    use crate::game::episode::{EpisodeConfig, EpisodeState};
+    use crate::game::reward::{RewardContext, RewardStack, RewardTerm, test_context};
+    use bevy::prelude::*;
+
+    #[test]
+    fn timeout_follows_the_scheduled_interpolation() {
+        let curriculum = Curriculum {
+            enabled: true,
+            timeout_s: Some(LinearSchedule {
+                start: 60.0,
+                end: 20.0,
+                episodes: 500,
+            }),
+            idle_speed_threshold: None,
+        };
+        let mut config = EpisodeConfig::default();
+        let idle = config.idle_speed_threshold;
+
+        for (completed, expected) in [(0, 60.0), (250, 40.0), (500, 20.0), (900, 20.0)] {
+            curriculum.apply(&mut config, completed);
+            assert!((config.timeout_s - expected).abs() < 1e-4);
+        }
+        assert_eq!(config.idle_speed_threshold, idle);
+
+        let mut untouched = EpisodeConfig::default();
+        Curriculum::default().apply(&mut untouched, 500);
+        assert_eq!(untouched, EpisodeConfig::default());
+    }
+
+    #[test]
+    fn a_curriculum_step_keeps_pushed_reward_terms() {
+        struct Custom;
+
+        impl RewardTerm for Custom {
+            fn name(&self) -> &str {
+                "custom"
+            }
+
+            fn compute(&mut self, _ctx: &RewardContext) -> f32 {
+                0.5
+            }
+        }
+
+        let config = EpisodeConfig {
+            idle_penalty_per_tick: -0.01,
+            idle_speed_threshold: 40.0,
+            ..EpisodeConfig::default()
+        };
+        let mut reward_stack = RewardStack::from_config(&config);
+        reward_stack.push(Custom);
+
+        let mut app = App::new();
+        app.insert_resource(Curriculum {
+            enabled: true,
+            timeout_s: None,
+            idle_speed_threshold: Some(LinearSchedule {
+                start: 20.0,
+                end: 10.0,
+                episodes: 10,
+            }),
+        })
+        .init_resource::<EpisodeState>()
+        .insert_resource(config)
+        .insert_resource(reward_stack)
+        .add_systems(Update, apply_initial_curriculum_system);
+        app.update();
+
+        let names = app.world().resource::<RewardStack>().active_term_names();
+        assert!(names.contains(&"idle"));
+        assert!(names.contains(&"custom"));
+        // Above the scheduled threshold but below the original one.
+        let breakdown = app
+            .world_mut()
+            .resource_mut::<RewardStack>()
+            .compute(&RewardContext {
+                speed: 30.0,
+                ticks_in_episode: 10_000,
+                ..test_context()
+            });
+        assert_eq!(breakdown.get("custom"), 0.5);
+        assert_eq!(breakdown.get("idle"), 0.0);
+    }
+}
@@ -1,24 +1,84 @@
 use std::collections::VecDeque;
 use std::f32::consts::PI;
 
-use bevy::ecs::message::MessageReader;
+use bevy::ecs::message::{MessageReader, MessageWriter};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::game::car::Car;
-use crate::game::collision::CollisionEvent;
+use crate::agent::action::{ActionState, CarAction};
+use crate::agent::observation::{ObservationDelayBuffer, SensorReadings};
+use crate::brain::types::AgentMode;
+use crate::game::car::{Car, clear_car_history};
+use crate::game::checkpoints::{CheckpointGates, CheckpointProgress};
+use crate::game::collision::{CarCollisionEvent, CollisionEvent, OffTrackState, OffTrackWarning};
+use crate::game::curriculum::Curriculum;
 use crate::game::progress::TrackProgress;
+use crate::game::reward::{RewardBreakdown, RewardContext, RewardKind, RewardStack};
+use crate::maps::centerline::TrackCenterline;
+use crate::maps::raceline::Raceline;
 use crate::maps::track::Track;
 
 /// Why an episode ended.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EpisodeEndReason {
     Crash,
     Timeout,
     LapComplete,
 }
 
+impl EpisodeEndReason {
+    /// Stable snake-case name used in structured logs and external APIs.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Crash => "crash",
+            Self::Timeout => "timeout",
+            Self::LapComplete => "lap_complete",
+        }
+    }
+}
+
+/// `tracing` target of the structured `episode_start` / `episode_end`
+/// events, for log processors to filter on.
+pub const EPISODE_LOG_TARGET: &str = "neurodrive::episode";
+
+/// Headline stats of a finished episode.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpisodeSummary {
+    pub end_reason: EpisodeEndReason,
+    pub ticks: u32,
+    pub episode_return: f32,
+    pub best_progress: f32,
+}
+
+/// Sent by [`episode_loop_system`] once per finished episode, after the
+/// `last_episode_*` stats in [`EpisodeState`] are final and before the car
+/// respawns.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct EpisodeEndedEvent {
+    /// Index of the episode that ended.
+    pub episode: u32,
+    pub summary: EpisodeSummary,
+}
+
+/// Selects which progress signal drives reward and lap completion.
+///
+/// - `Continuous`: dense reward from episode-best centreline progress; laps
+///   are detected by fraction wrap.
+/// - `Gated`: reward only when the next checkpoint gate is crossed in order;
+///   a lap needs every gate plus the finish line.
+/// - `Both`: both rewards are paid (and logged separately); lap completion
+///   uses the stricter gated rule.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProgressRewardMode {
+    #[default]
+    Continuous,
+    Gated,
+    Both,
+}
+
 /// Core episode loop configuration.
-#[derive(Resource, Clone, Copy, Debug)]
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EpisodeConfig {
     /// Timeout duration in seconds.
     pub timeout_s: f32,
@@ -42,6 +102,75 @@ pub struct EpisodeConfig {
     pub lap_bonus: f32,
     /// Number of episodes used for moving averages.
     pub moving_average_window: usize,
+    /// Progress reward and lap-completion mode.
+    pub progress_mode: ProgressRewardMode,
+    /// Number of checkpoint gates placed along the centreline.
+    pub checkpoint_gate_count: usize,
+    /// Reward granted per gate crossed in order (gated modes only).
+    pub checkpoint_gate_reward: f32,
+    /// Number of progress sectors (at most 32) that must be passed in order
+    /// before a wrap-based lap counts. `0` disables the check.
+    pub lap_validation_sectors: u32,
+    /// Upper bound on the magnitude of the shaped (non-terminal) reward in a
+    /// single tick. Larger values are treated as anomalies and clamped.
+    pub max_abs_reward_per_tick: f32,
+    /// Per-tick penalty (negative) while speed is below
+    /// `idle_speed_threshold`. `0.0` disables the term.
+    pub idle_penalty_per_tick: f32,
+    /// Speed in world units / second below which the car counts as idle.
+    pub idle_speed_threshold: f32,
+    /// Ticks at the start of each episode during which idling is not
+    /// penalised, so standing starts are free.
+    pub idle_grace_ticks: u32,
+    /// Measures the idle floor against forward speed (velocity along the
+    /// car's heading) instead of scalar speed, so reversing or sliding
+    /// sideways also counts as idle.
+    pub idle_uses_forward_speed: bool,
+    /// Per-tick penalty (negative) while off-road inside
+    /// [`CollisionConfig::off_track_grace_ticks`](crate::game::collision::CollisionConfig).
+    pub off_track_warning_penalty: f32,
+    /// Rescales length-dependent reward terms to the active track's
+    /// centreline length at startup; see [`EpisodeConfig::normalized_for_track`].
+    pub normalize_progress_to_track: bool,
+    /// Centreline length the default reward scales were tuned on (roughly
+    /// the built-in circuit).
+    pub reference_track_length: f32,
+    /// Penalty (negative) per car-vs-car contact. Applies under every
+    /// [`CarCollisionResponse`](crate::game::collision::CarCollisionResponse).
+    pub car_contact_penalty: f32,
+    /// Weight (positive) of the corner-smoothness penalty on abrupt changes
+    /// in longitudinal acceleration; see
+    /// [`CornerSmoothnessTerm`](crate::game::reward::CornerSmoothnessTerm).
+    /// `0.0` disables the term.
+    pub corner_smoothness_scale: f32,
+    /// Change in longitudinal acceleration between ticks (world units / s²)
+    /// that costs `corner_smoothness_scale` at full corner weight.
+    pub corner_smoothness_accel_norm: f32,
+    /// Curvature ahead (radians / world unit) at which the corner weight
+    /// reaches 1; gentler bends are weighted proportionally.
+    pub corner_smoothness_curvature_norm: f32,
+    /// Times per episode a timeout returns the car to its last checkpoint
+    /// gate (the spawn before the first) and grants another `timeout_s`
+    /// instead of ending the episode. `0` makes every timeout final. Reset
+    /// requests always end the episode.
+    pub timeout_checkpoint_extensions: u32,
+    /// Arc length (world units) `Backspace` moves the car forward along the
+    /// centreline in manual modes.
+    pub respawn_forward_distance: f32,
+    /// Fixed ticks at the start of every episode during which throttle is
+    /// held at zero and the episode clock, rewards and end checks wait.
+    /// `0` starts each episode immediately.
+    pub countdown_ticks: u32,
+    /// Weight (positive) of the penalty on distance from the raceline; see
+    /// [`RacelineTerm`](crate::game::reward::RacelineTerm). `0.0` disables
+    /// the term.
+    pub raceline_reward_scale: f32,
+    /// Distance from the raceline (world units) that costs the full
+    /// `raceline_reward_scale`; farther is no worse.
+    pub raceline_distance_norm: f32,
+    /// Farthest the raceline may move from the centreline while cutting
+    /// corners, in world units.
+    pub raceline_max_offset: f32,
 }
 
 impl Default for EpisodeConfig {
@@ -58,12 +187,195 @@ impl Default for EpisodeConfig {
             crash_penalty: -5.0,
             lap_bonus: 100.0,
             moving_average_window: 20,
+            progress_mode: ProgressRewardMode::Continuous,
+            checkpoint_gate_count: 12,
+            checkpoint_gate_reward: 10.0,
+            lap_validation_sectors: 8,
+            max_abs_reward_per_tick: 20.0,
+            idle_penalty_per_tick: 0.0,
+            idle_speed_threshold: 40.0,
+            idle_grace_ticks: 90,
+            idle_uses_forward_speed: false,
+            off_track_warning_penalty: -0.05,
+            normalize_progress_to_track: false,
+            reference_track_length: 7540.0,
+            car_contact_penalty: -1.0,
+            corner_smoothness_scale: 0.0,
+            corner_smoothness_accel_norm: 1000.0,
+            corner_smoothness_curvature_norm: 0.02,
+            timeout_checkpoint_extensions: 0,
+            respawn_forward_distance: 150.0,
+            countdown_ticks: 0,
+            raceline_reward_scale: 0.0,
+            raceline_distance_norm: 40.0,
+            raceline_max_offset: 30.0,
+        }
+    }
+}
+
+impl EpisodeConfig {
+    /// Returns the effective config for a track whose centreline is
+    /// `track_length` long.
+    ///
+    /// With `normalize_progress_to_track` set, every term that pays out per
+    /// lap scales by `track_length / reference_track_length`:
+    /// - `progress_reward_scale`, so reward per unit of distance driven is the
+    ///   same on every track;
+    /// - `checkpoint_gate_reward`, so a lap's worth of gates keeps the same
+    ///   per-distance value;
+    /// - `lap_bonus` and `crash_penalty`, so their weight relative to a lap's
+    ///   progress reward is unchanged.
+    ///
+    /// Per-tick terms (time, heading/speed, idle) are time-based and left
+    /// alone. Otherwise the config is returned unchanged.
+    pub fn normalized_for_track(&self, track_length: f32) -> Self {
+        if !self.normalize_progress_to_track
+            || track_length <= 0.0
+            || self.reference_track_length <= 0.0
+        {
+            return *self;
         }
+
+        let ratio = track_length / self.reference_track_length;
+        Self {
+            progress_reward_scale: self.progress_reward_scale * ratio,
+            checkpoint_gate_reward: self.checkpoint_gate_reward * ratio,
+            lap_bonus: self.lap_bonus * ratio,
+            crash_penalty: self.crash_penalty * ratio,
+            ..*self
+        }
+    }
+}
+
+/// Applies [`EpisodeConfig::normalized_for_track`] once the track exists and
+/// loads the effective values into the reward stack.
+pub fn normalize_reward_to_track_system(
+    mut config: ResMut<EpisodeConfig>,
+    mut reward_stack: ResMut<RewardStack>,
+    track_query: Query<&Track>,
+) {
+    if !config.normalize_progress_to_track {
+        return;
     }
+    let Some(centerline) = track_query
+        .single()
+        .ok()
+        .and_then(|track| track.centerline.as_ref())
+    else {
+        return;
+    };
+
+    let length = centerline.total_length();
+    *config = config.normalized_for_track(length);
+    reward_stack.apply_config(&config);
+    info!(
+        "Effective reward scales for {:.0}-unit track (reference {:.0}): progress {:.2}, gate {:.2}, lap bonus {:.2}, crash {:.2}.",
+        length,
+        config.reference_track_length,
+        config.progress_reward_scale,
+        config.checkpoint_gate_reward,
+        config.lap_bonus,
+        config.crash_penalty
+    );
+}
+
+/// Per-car record of which progress sectors have been passed, in order,
+/// since the last validated lap.
+///
+/// Sector `k` is only marked once sector `k - 1` has been, so wiggling back
+/// and forth across the start line can never fill the mask.
+#[derive(Component, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct LapValidation {
+    pub visited_mask: u32,
+}
+
+impl LapValidation {
+    /// Marks the sector containing `fraction` if its predecessor was visited.
+    pub fn observe(&mut self, fraction: f32, sectors: u32) {
+        let sectors = sectors.min(32);
+        if sectors == 0 {
+            return;
+        }
+
+        let sector = ((fraction.clamp(0.0, 0.999_999) * sectors as f32) as u32).min(sectors - 1);
+        if sector == 0 || self.visited_mask & (1 << (sector - 1)) != 0 {
+            self.visited_mask |= 1 << sector;
+        }
+    }
+
+    /// Returns `true` once every sector has been passed in order.
+    pub fn is_complete(&self, sectors: u32) -> bool {
+        let sectors = sectors.min(32);
+        if sectors == 0 {
+            return true;
+        }
+        let full = if sectors == 32 {
+            u32::MAX
+        } else {
+            (1u32 << sectors) - 1
+        };
+        self.visited_mask == full
+    }
+
+    pub fn reset(&mut self) {
+        self.visited_mask = 0;
+    }
+}
+
+/// Outcome of the wrap-based lap check for one tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LapWrapOutcome {
+    NoWrap,
+    /// Wrapped after passing every sector in order.
+    Valid,
+    /// Wrapped without a full ordered sector passage; no bonus is paid.
+    Invalid,
+}
+
+/// Checks for a fraction wrap across the start line and validates it against
+/// the ordered sector mask, then records the current sector.
+///
+/// A valid lap clears the mask so the next lap must be earned from scratch.
+pub fn evaluate_lap_wrap(
+    config: &EpisodeConfig,
+    lap_armed: bool,
+    previous_fraction: f32,
+    fraction: f32,
+    validation: &mut LapValidation,
+) -> LapWrapOutcome {
+    let wrapped = lap_armed
+        && previous_fraction >= config.lap_wrap_from_fraction
+        && fraction <= config.lap_wrap_to_fraction;
+
+    let outcome = if !wrapped {
+        LapWrapOutcome::NoWrap
+    } else if validation.is_complete(config.lap_validation_sectors) {
+        validation.reset();
+        LapWrapOutcome::Valid
+    } else {
+        LapWrapOutcome::Invalid
+    };
+
+    validation.observe(fraction, config.lap_validation_sectors);
+    outcome
+}
+
+/// Clamps the shaping terms of `breakdown` to `max_abs` in total magnitude.
+///
+/// Terms are scaled by a common factor so the logged breakdown still sums to
+/// the clamped total. Returns the raw pre-cap total when the cap was hit.
+pub fn cap_shaped_reward(max_abs: f32, breakdown: &mut RewardBreakdown) -> Option<f32> {
+    let raw = breakdown.total_of(RewardKind::Shaping);
+    if max_abs <= 0.0 || raw.abs() <= max_abs {
+        return None;
+    }
+
+    breakdown.scale(RewardKind::Shaping, max_abs / raw.abs());
+    Some(raw)
 }
 
 /// Episode state and accumulators.
-#[derive(Resource, Debug)]
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
 pub struct EpisodeState {
     pub current_episode: u32,
     pub ticks_in_episode: u32,
@@ -72,6 +384,7 @@ pub struct EpisodeState {
     pub current_return: f32,
     pub current_tick_reward: f32,
     pub current_tick_progress_reward: f32,
+    pub current_tick_gate_reward: f32,
     pub current_tick_time_penalty: f32,
     pub current_tick_terminal_reward: f32,
     pub current_tick_end_reason: Option<EpisodeEndReason>,
@@ -82,25 +395,55 @@ pub struct EpisodeState {
     pub current_tick_heading_error: f32,
     pub current_tick_forward: Vec2,
     pub current_tick_tangent: Vec2,
+    /// Per-term rewards paid on the most recent tick.
+    pub current_tick_breakdown: RewardBreakdown,
+    /// Per-term reward totals for the episode so far.
+    pub current_breakdown_sums: RewardBreakdown,
     pub current_progress_reward_sum: f32,
+    pub current_gate_reward_sum: f32,
+    pub current_gates_passed: u32,
     pub current_time_penalty_sum: f32,
     pub current_terminal_reward_sum: f32,
     pub current_crash_penalty_sum: f32,
     pub current_lap_bonus_sum: f32,
     pub current_best_progress_fraction: f32,
     pub current_crashes: u32,
+    /// Off-road ticks inside the grace window this episode.
+    pub current_off_track_warnings: u32,
+    pub current_invalid_lap_wraps: u32,
+    pub current_reward_anomalies: u32,
+    /// Sum of `|Δsteering|` between consecutive applied actions.
+    pub current_steering_change_sum: f32,
+    pub current_throttle_sum: f32,
+    pub current_previous_steering: Option<f32>,
+    /// Timeouts this episode that returned the car to a checkpoint.
+    #[serde(default)]
+    pub current_timeout_extensions: u32,
+    /// Start-countdown ticks already elapsed this episode.
+    #[serde(default)]
+    pub current_countdown_ticks: u32,
     pub last_end_reason: Option<EpisodeEndReason>,
     pub last_episode_return: f32,
     pub last_episode_pre_terminal_return: f32,
     pub last_episode_progress_reward_sum: f32,
+    pub last_episode_gate_reward_sum: f32,
+    pub last_episode_gates_passed: u32,
     pub last_episode_time_penalty_sum: f32,
     pub last_episode_terminal_reward_sum: f32,
     pub last_episode_crash_penalty_sum: f32,
     pub last_episode_lap_bonus_sum: f32,
     pub last_episode_best_progress_fraction: f32,
     pub last_episode_crashes: u32,
+    pub last_episode_off_track_warnings: u32,
+    pub last_episode_invalid_lap_wraps: u32,
+    pub last_episode_reward_anomalies: u32,
+    /// Mean `|Δsteering|` per tick of the applied action.
+    pub last_episode_steering_effort: f32,
+    /// Mean applied throttle per tick.
+    pub last_episode_throttle_usage: f32,
     pub last_episode_ticks: u32,
     pub last_episode_crash_position: Option<Vec2>,
+    pub last_episode_breakdown: RewardBreakdown,
 }
 
 impl Default for EpisodeState {
@@ -113,6 +456,7 @@ impl Default for EpisodeState {
             current_return: 0.0,
             current_tick_reward: 0.0,
             current_tick_progress_reward: 0.0,
+            current_tick_gate_reward: 0.0,
             current_tick_time_penalty: 0.0,
             current_tick_terminal_reward: 0.0,
             current_tick_end_reason: None,
@@ -123,38 +467,98 @@ impl Default for EpisodeState {
             current_tick_heading_error: 0.0,
             current_tick_forward: Vec2::X,
             current_tick_tangent: Vec2::X,
+            current_tick_breakdown: RewardBreakdown::default(),
+            current_breakdown_sums: RewardBreakdown::default(),
             current_progress_reward_sum: 0.0,
+            current_gate_reward_sum: 0.0,
+            current_gates_passed: 0,
             current_time_penalty_sum: 0.0,
             current_terminal_reward_sum: 0.0,
             current_crash_penalty_sum: 0.0,
             current_lap_bonus_sum: 0.0,
             current_best_progress_fraction: 0.0,
             current_crashes: 0,
+            current_off_track_warnings: 0,
+            current_invalid_lap_wraps: 0,
+            current_reward_anomalies: 0,
+            current_steering_change_sum: 0.0,
+            current_throttle_sum: 0.0,
+            current_previous_steering: None,
+            current_timeout_extensions: 0,
+            current_countdown_ticks: 0,
             last_end_reason: None,
             last_episode_return: 0.0,
             last_episode_pre_terminal_return: 0.0,
             last_episode_progress_reward_sum: 0.0,
+            last_episode_gate_reward_sum: 0.0,
+            last_episode_gates_passed: 0,
             last_episode_time_penalty_sum: 0.0,
             last_episode_terminal_reward_sum: 0.0,
             last_episode_crash_penalty_sum: 0.0,
             last_episode_lap_bonus_sum: 0.0,
             last_episode_best_progress_fraction: 0.0,
             last_episode_crashes: 0,
+            last_episode_off_track_warnings: 0,
+            last_episode_invalid_lap_wraps: 0,
+            last_episode_reward_anomalies: 0,
+            last_episode_steering_effort: 0.0,
+            last_episode_throttle_usage: 0.0,
             last_episode_ticks: 0,
             last_episode_crash_position: None,
+            last_episode_breakdown: RewardBreakdown::default(),
         }
     }
 }
 
+impl EpisodeState {
+    /// Summary of the last finished episode, if any.
+    pub fn last_episode_summary(&self) -> Option<EpisodeSummary> {
+        self.last_end_reason.map(|end_reason| EpisodeSummary {
+            end_reason,
+            ticks: self.last_episode_ticks,
+            episode_return: self.last_episode_return,
+            best_progress: self.last_episode_best_progress_fraction,
+        })
+    }
+
+    /// Start-countdown ticks left before this episode's clock starts.
+    pub fn countdown_remaining(&self, config: &EpisodeConfig) -> u32 {
+        config
+            .countdown_ticks
+            .saturating_sub(self.current_countdown_ticks)
+    }
+
+    /// Accumulates steering effort and throttle usage for one tick's applied
+    /// action.
+    pub fn record_applied_action(&mut self, action: CarAction) {
+        if let Some(previous) = self.current_previous_steering {
+            self.current_steering_change_sum += (action.steering - previous).abs();
+        }
+        self.current_previous_steering = Some(action.steering);
+        self.current_throttle_sum += action.throttle;
+    }
+}
+
+/// Set to end the current episode on the next fixed tick, as if it had
+/// timed out. Used by external controllers that ask for a reset.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct EpisodeResetRequest {
+    pub pending: bool,
+}
+
 /// Rolling episode-level telemetry for moving averages.
 #[derive(Resource, Debug)]
 pub struct EpisodeMovingAverages {
     pub returns: VecDeque<f32>,
     pub best_progress_fractions: VecDeque<f32>,
     pub crash_counts: VecDeque<f32>,
+    pub steering_efforts: VecDeque<f32>,
+    pub throttle_usages: VecDeque<f32>,
     pub return_mean: f32,
     pub best_progress_mean: f32,
     pub crash_mean: f32,
+    pub steering_effort_mean: f32,
+    pub throttle_usage_mean: f32,
 }
 
 impl Default for EpisodeMovingAverages {
@@ -163,28 +567,81 @@ impl Default for EpisodeMovingAverages {
             returns: VecDeque::new(),
             best_progress_fractions: VecDeque::new(),
             crash_counts: VecDeque::new(),
+            steering_efforts: VecDeque::new(),
+            throttle_usages: VecDeque::new(),
             return_mean: 0.0,
             best_progress_mean: 0.0,
             crash_mean: 0.0,
+            steering_effort_mean: 0.0,
+            throttle_usage_mean: 0.0,
         }
     }
 }
 
+/// The car [`episode_loop_system`] scores, with the state it clears when an
+/// episode ends.
+type EpisodeCarQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Transform,
+        &'static mut Car,
+        &'static mut TrackProgress,
+        &'static mut CheckpointProgress,
+        &'static mut LapValidation,
+        Option<&'static mut SensorReadings>,
+        Option<&'static mut OffTrackState>,
+        Option<&'static mut ObservationDelayBuffer>,
+    ),
+>;
+
 /// Handles per-tick reward accumulation and episode boundaries:
-/// crash, timeout, and lap completion.
+/// crash, timeout, and lap completion. A pending [`EpisodeResetRequest`]
+/// ends the episode as a timeout (truncation) unless it crashed or finished
+/// a lap on the same tick.
+///
+/// Reward values come from the [`RewardStack`]; the category sums kept in
+/// [`EpisodeState`] bucket them as progress (`"progress"`), gates
+/// (`"gates"`), other shaping terms (step costs and penalties), and terminal
+/// terms.
 pub fn episode_loop_system(
     time: Res<Time<bevy::time::Fixed>>,
-    config: Res<EpisodeConfig>,
+    mut config: ResMut<EpisodeConfig>,
+    curriculum: Option<Res<Curriculum>>,
+    reset_request: Option<ResMut<EpisodeResetRequest>>,
     mut episode_state: ResMut<EpisodeState>,
     mut moving_avg: ResMut<EpisodeMovingAverages>,
     mut collision_events: MessageReader<CollisionEvent>,
+    mut warning_events: MessageReader<OffTrackWarning>,
+    mut car_collision_events: MessageReader<CarCollisionEvent>,
+    mut ended_events: MessageWriter<EpisodeEndedEvent>,
+    gates: Option<Res<CheckpointGates>>,
+    raceline: Option<Res<Raceline>>,
+    action_state: Option<Res<ActionState>>,
+    mut reward_stack: ResMut<RewardStack>,
     track_query: Query<&Track>,
-    mut car_query: Query<(&mut Transform, &mut Car, &mut TrackProgress)>,
+    mut car_query: EpisodeCarQuery,
 ) {
     let Ok(track) = track_query.single() else {
         return;
     };
-    let Ok((mut transform, mut car, mut progress)) = car_query.single_mut() else {
+    // Without a centreline there is no progress to measure or episode to run.
+    let Some(centerline) = track.centerline.as_ref() else {
+        return;
+    };
+    let Ok((
+        car_entity,
+        mut transform,
+        mut car,
+        mut progress,
+        mut checkpoints,
+        mut lap_validation,
+        mut sensors,
+        mut off_track,
+        mut delay,
+    )) = car_query.single_mut()
+    else {
         return;
     };
     let forward = (transform.rotation * Vec3::X)
@@ -193,46 +650,150 @@ pub fn episode_loop_system(
 
     episode_state.current_tick_reward = 0.0;
     episode_state.current_tick_progress_reward = 0.0;
+    episode_state.current_tick_gate_reward = 0.0;
     episode_state.current_tick_time_penalty = 0.0;
     episode_state.current_tick_terminal_reward = 0.0;
     episode_state.current_tick_end_reason = None;
+    episode_state.current_tick_breakdown.terms.clear();
+    // The car is held on the grid until the countdown ends; nothing counts.
+    if episode_state.countdown_remaining(&config) > 0 {
+        episode_state.current_countdown_ticks += 1;
+        car.velocity = Vec2::ZERO;
+        collision_events.clear();
+        warning_events.clear();
+        car_collision_events.clear();
+        return;
+    }
     episode_state.ticks_in_episode = episode_state.ticks_in_episode.saturating_add(1);
+    if episode_state.ticks_in_episode == 1 {
+        log_episode_start(episode_state.current_episode);
+    }
     let previous_best_progress = episode_state.current_best_progress_fraction;
     let progress_gain = (progress.fraction - previous_best_progress).max(0.0);
     episode_state.current_best_progress_fraction = previous_best_progress.max(progress.fraction);
-    let progress_reward = progress_gain * config.progress_reward_scale;
+    let gated = config.progress_mode != ProgressRewardMode::Continuous;
+    let gate_advance = match gates.as_deref() {
+        Some(gates) if gated => checkpoints.advance(transform.translation.truncate(), gates),
+        _ => Default::default(),
+    };
     let heading_error = signed_angle_between(forward, progress.tangent);
-    let heading_error_norm = (heading_error.abs() / PI).clamp(0.0, 1.0);
-    let speed_norm = (car.velocity.length() / config.speed_norm_max_for_penalty).clamp(0.0, 1.0);
-    let heading_speed_penalty =
-        -config.heading_speed_penalty_scale * heading_error_norm * speed_norm;
-    let time_penalty = config.time_penalty_per_tick + heading_speed_penalty;
-    let mut terminal_reward = 0.0;
 
     if progress.fraction >= config.lap_arm_fraction {
         episode_state.lap_armed = true;
     }
 
-    let crashed = collision_events.read().next().is_some();
+    let crashed = collision_events.read().any(|event| event.car == car_entity);
+    let off_track_warning = warning_events.read().any(|event| event.car == car_entity) && !crashed;
+    if off_track_warning {
+        episode_state.current_off_track_warnings =
+            episode_state.current_off_track_warnings.saturating_add(1);
+    }
+    let car_contacts = car_collision_events
+        .read()
+        .filter(|event| event.a == car_entity || event.b == car_entity)
+        .count() as u32;
     let mut crash_position = None;
     if crashed {
         episode_state.current_crashes = episode_state.current_crashes.saturating_add(1);
-        terminal_reward += config.crash_penalty;
         crash_position = Some(transform.translation.truncate());
     }
 
-    let timed_out = (episode_state.ticks_in_episode as f32) * time.delta_secs() >= config.timeout_s;
-    let lap_complete = episode_state.lap_armed
-        && episode_state.previous_progress_fraction >= config.lap_wrap_from_fraction
-        && progress.fraction <= config.lap_wrap_to_fraction;
+    let reset_requested = reset_request.is_some_and(|mut request| {
+        let pending = request.pending;
+        request.pending = false;
+        pending
+    });
+    let deadline_s = config.timeout_s * (episode_state.current_timeout_extensions + 1) as f32;
+    let clock_expired = (episode_state.ticks_in_episode as f32) * time.delta_secs() >= deadline_s;
+    let extend_timeout = clock_expired
+        && !reset_requested
+        && episode_state.current_timeout_extensions < config.timeout_checkpoint_extensions;
+    let timed_out = reset_requested || (clock_expired && !extend_timeout);
+    let wrap_outcome = evaluate_lap_wrap(
+        &config,
+        episode_state.lap_armed,
+        episode_state.previous_progress_fraction,
+        progress.fraction,
+        &mut lap_validation,
+    );
+    if !gated && wrap_outcome == LapWrapOutcome::Invalid {
+        episode_state.current_invalid_lap_wraps =
+            episode_state.current_invalid_lap_wraps.saturating_add(1);
+        warn!(
+            "Invalid lap wrap in episode {} (sector mask {:#b}); no lap bonus granted.",
+            episode_state.current_episode, lap_validation.visited_mask
+        );
+    }
+    let lap_complete = if gated {
+        gate_advance.lap_complete
+    } else {
+        wrap_outcome == LapWrapOutcome::Valid
+    };
 
-    if lap_complete {
-        terminal_reward += config.lap_bonus;
+    let applied_action = action_state
+        .as_deref()
+        .map(|state| state.applied)
+        .unwrap_or_default();
+    episode_state.record_applied_action(applied_action);
+
+    let ctx = RewardContext {
+        progress_gain,
+        progress_fraction: progress.fraction,
+        progress_s: progress.s,
+        centerline_distance: progress.distance,
+        heading_error,
+        velocity: car.velocity,
+        speed: car.velocity.length(),
+        forward,
+        tangent: progress.tangent,
+        curvature_ahead: sensors
+            .as_deref()
+            .map(|sensors| {
+                sensors
+                    .lookahead_curvatures
+                    .iter()
+                    .fold(0.0_f32, |max, curvature| max.max(curvature.abs()))
+            })
+            .unwrap_or(0.0),
+        action: applied_action,
+        crashed,
+        lap_complete,
+        gates_crossed: gate_advance.gates_crossed,
+        off_track_warning,
+        car_contacts,
+        raceline_distance: raceline.map(|raceline| {
+            raceline
+                .line
+                .project(transform.translation.truncate())
+                .distance
+        }),
+        dt: time.delta_secs(),
+        ticks_in_episode: episode_state.ticks_in_episode,
+    };
+    let mut breakdown = reward_stack.compute(&ctx);
+    if let Some(raw) = cap_shaped_reward(config.max_abs_reward_per_tick, &mut breakdown) {
+        episode_state.current_reward_anomalies =
+            episode_state.current_reward_anomalies.saturating_add(1);
+        warn!(
+            "Reward anomaly in episode {} tick {} at ({:.1}, {:.1}): raw shaped reward {:.3} clamped to ±{:.1}.",
+            episode_state.current_episode,
+            episode_state.ticks_in_episode,
+            transform.translation.x,
+            transform.translation.y,
+            raw,
+            config.max_abs_reward_per_tick
+        );
     }
-    let tick_reward = progress_reward + time_penalty + terminal_reward;
+
+    let progress_reward = breakdown.get("progress");
+    let gate_reward = breakdown.get("gates");
+    let time_penalty = breakdown.total_of(RewardKind::Shaping) - progress_reward - gate_reward;
+    let terminal_reward = breakdown.total_of(RewardKind::Terminal);
+    let tick_reward = breakdown.total();
 
     episode_state.current_tick_reward = tick_reward;
     episode_state.current_tick_progress_reward = progress_reward;
+    episode_state.current_tick_gate_reward = gate_reward;
     episode_state.current_tick_time_penalty = time_penalty;
     episode_state.current_tick_terminal_reward = terminal_reward;
     episode_state.current_tick_progress_fraction = progress.fraction;
@@ -243,14 +804,14 @@ pub fn episode_loop_system(
     episode_state.current_tick_forward = forward;
     episode_state.current_tick_tangent = progress.tangent;
     episode_state.current_progress_reward_sum += progress_reward;
+    episode_state.current_gate_reward_sum += gate_reward;
+    episode_state.current_gates_passed += gate_advance.gates_crossed;
     episode_state.current_time_penalty_sum += time_penalty;
     episode_state.current_terminal_reward_sum += terminal_reward;
-    if crashed {
-        episode_state.current_crash_penalty_sum += config.crash_penalty;
-    }
-    if lap_complete {
-        episode_state.current_lap_bonus_sum += config.lap_bonus;
-    }
+    episode_state.current_crash_penalty_sum += breakdown.get("crash");
+    episode_state.current_lap_bonus_sum += breakdown.get("lap_bonus");
+    episode_state.current_breakdown_sums.accumulate(&breakdown);
+    episode_state.current_tick_breakdown = breakdown;
     episode_state.current_return += tick_reward;
 
     let end_reason = if crashed {
@@ -272,27 +833,250 @@ pub fn episode_loop_system(
             reason,
             crash_position,
         );
+        log_episode_end(&episode_state, time.delta_secs());
+        if let Some(summary) = episode_state.last_episode_summary() {
+            ended_events.write(EpisodeEndedEvent {
+                episode: episode_state.current_episode.saturating_sub(1),
+                summary,
+            });
+        }
         reset_car_to_spawn(&mut transform, &mut car, track);
-        sync_progress_to_transform(track, &transform, &mut progress);
+        clear_car_history(
+            track.spawn_rotation,
+            off_track.as_deref_mut(),
+            sensors.as_deref_mut(),
+            delay.as_deref_mut(),
+        );
+        sync_progress_to_transform(centerline, &transform, &mut progress);
+        checkpoints.reset();
+        lap_validation.reset();
+        if let Some(curriculum) = curriculum
+            .as_deref()
+            .filter(|curriculum| curriculum.enabled)
+        {
+            let completed = episode_state.current_episode.saturating_sub(1);
+            curriculum.apply(&mut config, completed);
+            reward_stack.apply_config(&config);
+        }
+        reward_stack.reset();
+    } else if extend_timeout {
+        episode_state.current_timeout_extensions += 1;
+        let best_s = episode_state.current_best_progress_fraction * centerline.total_length();
+        let rotation = match last_checkpoint_pose(gates.as_deref(), &checkpoints, gated, best_s) {
+            Some((position, rotation)) => {
+                relocate_car(&mut transform, &mut car, position, rotation);
+                rotation
+            }
+            None => {
+                reset_car_to_spawn(&mut transform, &mut car, track);
+                track.spawn_rotation
+            }
+        };
+        clear_car_history(
+            rotation,
+            off_track.as_deref_mut(),
+            sensors.as_deref_mut(),
+            delay.as_deref_mut(),
+        );
+        let projection = centerline.project(transform.translation.truncate());
+        progress.set_projection(&projection);
+        checkpoints.previous_position = None;
+        episode_state.previous_progress_fraction = progress.fraction;
+        info!(
+            "Timeout extension {}/{} in episode {}: car returned to its last checkpoint.",
+            episode_state.current_timeout_extensions,
+            config.timeout_checkpoint_extensions,
+            episode_state.current_episode
+        );
     } else {
         episode_state.previous_progress_fraction = progress.fraction;
     }
 }
 
-fn reset_car_to_spawn(transform: &mut Transform, car: &mut Car, track: &Track) {
-    transform.translation.x = track.spawn_position.x;
-    transform.translation.y = track.spawn_position.y;
-    transform.rotation = Quat::from_rotation_z(track.spawn_rotation);
+/// Position and heading of the last checkpoint gate passed this episode, or
+/// `None` before the first. Gated modes use the gate counter; continuous mode
+/// takes the furthest gate behind the episode-best arc length `best_s`.
+fn last_checkpoint_pose(
+    gates: Option<&CheckpointGates>,
+    checkpoints: &CheckpointProgress,
+    gated: bool,
+    best_s: f32,
+) -> Option<(Vec2, f32)> {
+    let gates = gates?;
+    let gate = if gated {
+        gates.gates.get(checkpoints.next_gate.checked_sub(1)?)?
+    } else {
+        gates.gates.iter().rev().find(|gate| gate.s <= best_s)?
+    };
+    Some((
+        (gate.a + gate.b) * 0.5,
+        gate.tangent.y.atan2(gate.tangent.x),
+    ))
+}
+
+/// Position and heading `distance` world units further along the
+/// centreline than arc length `s`, wrapping past the start line. The
+/// position lies on the centreline, so it is always on the road.
+pub fn respawn_forward_pose(centerline: &TrackCenterline, s: f32, distance: f32) -> (Vec2, f32) {
+    let target = s + distance.max(0.0);
+    let tangent = centerline.tangent_at_s(target);
+    (centerline.point_at_s(target), tangent.y.atan2(tangent.x))
+}
+
+/// Teleports the car to `position` facing `rotation`, stopped.
+fn relocate_car(transform: &mut Transform, car: &mut Car, position: Vec2, rotation: f32) {
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+    transform.rotation = Quat::from_rotation_z(rotation);
     car.velocity = Vec2::ZERO;
+    car.boost = Default::default();
 }
 
-fn sync_progress_to_transform(track: &Track, transform: &Transform, progress: &mut TrackProgress) {
-    let projection = track.centerline.project(transform.translation.truncate());
-    progress.s = projection.s;
-    progress.fraction = projection.fraction;
-    progress.closest_point = projection.closest_point;
-    progress.tangent = projection.tangent;
-    progress.distance = projection.distance;
+fn reset_car_to_spawn(transform: &mut Transform, car: &mut Car, track: &Track) {
+    relocate_car(transform, car, track.spawn_position, track.spawn_rotation);
+}
+
+/// Cars [`reset_crashed_cars_system`] can send back to the spawn. Only the
+/// pose and [`Car`] are required; any tracking state present is cleared.
+type CarResetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static mut Car,
+        Option<&'static mut TrackProgress>,
+        Option<&'static mut CheckpointProgress>,
+        Option<&'static mut LapValidation>,
+        Option<&'static mut OffTrackState>,
+        Option<&'static mut SensorReadings>,
+        Option<&'static mut ObservationDelayBuffer>,
+    ),
+>;
+
+/// Returns each crashed car to the spawn when several cars share the track.
+///
+/// Only the cars named by this tick's [`CollisionEvent`]s move; the rest keep
+/// driving. Each reset car's history is cleared as at an episode end. With a
+/// single car [`episode_loop_system`] owns the reset as part of ending the
+/// episode, so this system does nothing.
+pub fn reset_crashed_cars_system(
+    mut collision_events: MessageReader<CollisionEvent>,
+    track_query: Query<&Track>,
+    mut car_query: CarResetQuery,
+) {
+    let mut crashed: Vec<Entity> = collision_events.read().map(|event| event.car).collect();
+    if crashed.is_empty() || car_query.iter().count() < 2 {
+        return;
+    }
+    let Ok(track) = track_query.single() else {
+        return;
+    };
+    crashed.sort();
+    crashed.dedup();
+
+    for entity in crashed {
+        let Ok((
+            mut transform,
+            mut car,
+            progress,
+            checkpoints,
+            lap_validation,
+            mut off_track,
+            mut sensors,
+            mut delay,
+        )) = car_query.get_mut(entity)
+        else {
+            continue;
+        };
+        reset_car_to_spawn(&mut transform, &mut car, track);
+        clear_car_history(
+            track.spawn_rotation,
+            off_track.as_deref_mut(),
+            sensors.as_deref_mut(),
+            delay.as_deref_mut(),
+        );
+        if let (Some(centerline), Some(mut progress)) = (track.centerline.as_ref(), progress) {
+            sync_progress_to_transform(centerline, &transform, &mut progress);
+        }
+        if let Some(mut checkpoints) = checkpoints {
+            checkpoints.reset();
+        }
+        if let Some(mut lap_validation) = lap_validation {
+            lap_validation.reset();
+        }
+    }
+}
+
+/// Manual practice keys. `R` ends the episode and returns the car to the
+/// spawn through [`EpisodeResetRequest`]. `Backspace` moves the car
+/// [`EpisodeConfig::respawn_forward_distance`] further along the centreline,
+/// stopped and facing along the track, within the same episode; the skipped
+/// distance raises the episode-best progress without earning progress
+/// reward. Both only act in manual modes, and neither the headless env nor
+/// eval runs register this system.
+pub fn manual_respawn_input_system(
+    keyboard: Option<Res<ButtonInput<KeyCode>>>,
+    mode: Option<Res<AgentMode>>,
+    config: Res<EpisodeConfig>,
+    mut reset_request: ResMut<EpisodeResetRequest>,
+    mut episode_state: ResMut<EpisodeState>,
+    track_query: Query<&Track>,
+    mut car_query: Query<(
+        &mut Transform,
+        &mut Car,
+        &mut TrackProgress,
+        &mut CheckpointProgress,
+    )>,
+) {
+    let Some(keyboard) = keyboard else {
+        return;
+    };
+    if mode.is_some_and(|mode| !mode.is_manual()) {
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        reset_request.pending = true;
+        info!("Manual reset to spawn.");
+        return;
+    }
+    if !keyboard.just_pressed(KeyCode::Backspace) {
+        return;
+    }
+    let centerline = track_query
+        .single()
+        .ok()
+        .and_then(|track| track.centerline.as_ref());
+    let (Some(centerline), Ok((mut transform, mut car, mut progress, mut checkpoints))) =
+        (centerline, car_query.single_mut())
+    else {
+        return;
+    };
+
+    let (position, rotation) =
+        respawn_forward_pose(centerline, progress.s, config.respawn_forward_distance);
+    relocate_car(&mut transform, &mut car, position, rotation);
+    progress.set_projection(&centerline.project(position));
+    checkpoints.previous_position = None;
+    episode_state.previous_progress_fraction = progress.fraction;
+    episode_state.current_best_progress_fraction = episode_state
+        .current_best_progress_fraction
+        .max(progress.fraction);
+    info!(
+        "Respawned {:.0} units forward at s = {:.0}.",
+        config.respawn_forward_distance, progress.s
+    );
+}
+
+/// Re-projects after a reset teleport and clears the per-episode lap and
+/// distance counters.
+fn sync_progress_to_transform(
+    centerline: &TrackCenterline,
+    transform: &Transform,
+    progress: &mut TrackProgress,
+) {
+    let projection = centerline.project(transform.translation.truncate());
+    *progress = TrackProgress::default();
+    progress.set_projection(&projection);
 }
 
 fn finalize_episode(
@@ -304,9 +1088,12 @@ fn finalize_episode(
 ) {
     episode_state.last_end_reason = Some(reason);
     episode_state.last_episode_return = episode_state.current_return;
-    episode_state.last_episode_pre_terminal_return =
-        episode_state.current_progress_reward_sum + episode_state.current_time_penalty_sum;
+    episode_state.last_episode_pre_terminal_return = episode_state.current_progress_reward_sum
+        + episode_state.current_gate_reward_sum
+        + episode_state.current_time_penalty_sum;
     episode_state.last_episode_progress_reward_sum = episode_state.current_progress_reward_sum;
+    episode_state.last_episode_gate_reward_sum = episode_state.current_gate_reward_sum;
+    episode_state.last_episode_gates_passed = episode_state.current_gates_passed;
     episode_state.last_episode_time_penalty_sum = episode_state.current_time_penalty_sum;
     episode_state.last_episode_terminal_reward_sum = episode_state.current_terminal_reward_sum;
     episode_state.last_episode_crash_penalty_sum = episode_state.current_crash_penalty_sum;
@@ -314,8 +1101,16 @@ fn finalize_episode(
     episode_state.last_episode_best_progress_fraction =
         episode_state.current_best_progress_fraction;
     episode_state.last_episode_crashes = episode_state.current_crashes;
+    episode_state.last_episode_off_track_warnings = episode_state.current_off_track_warnings;
+    episode_state.last_episode_invalid_lap_wraps = episode_state.current_invalid_lap_wraps;
+    episode_state.last_episode_reward_anomalies = episode_state.current_reward_anomalies;
+    let ticks = episode_state.ticks_in_episode.max(1) as f32;
+    episode_state.last_episode_steering_effort = episode_state.current_steering_change_sum / ticks;
+    episode_state.last_episode_throttle_usage = episode_state.current_throttle_sum / ticks;
     episode_state.last_episode_ticks = episode_state.ticks_in_episode;
     episode_state.last_episode_crash_position = crash_position;
+    episode_state.last_episode_breakdown =
+        std::mem::take(&mut episode_state.current_breakdown_sums);
 
     push_with_limit(
         &mut moving_avg.returns,
@@ -332,9 +1127,21 @@ fn finalize_episode(
         episode_state.last_episode_crashes as f32,
         config.moving_average_window,
     );
+    push_with_limit(
+        &mut moving_avg.steering_efforts,
+        episode_state.last_episode_steering_effort,
+        config.moving_average_window,
+    );
+    push_with_limit(
+        &mut moving_avg.throttle_usages,
+        episode_state.last_episode_throttle_usage,
+        config.moving_average_window,
+    );
     moving_avg.return_mean = mean(&moving_avg.returns);
     moving_avg.best_progress_mean = mean(&moving_avg.best_progress_fractions);
     moving_avg.crash_mean = mean(&moving_avg.crash_counts);
+    moving_avg.steering_effort_mean = mean(&moving_avg.steering_efforts);
+    moving_avg.throttle_usage_mean = mean(&moving_avg.throttle_usages);
 
     episode_state.current_episode = episode_state.current_episode.saturating_add(1);
     episode_state.ticks_in_episode = 0;
@@ -342,12 +1149,57 @@ fn finalize_episode(
     episode_state.lap_armed = false;
     episode_state.current_return = 0.0;
     episode_state.current_progress_reward_sum = 0.0;
+    episode_state.current_gate_reward_sum = 0.0;
+    episode_state.current_gates_passed = 0;
     episode_state.current_time_penalty_sum = 0.0;
     episode_state.current_terminal_reward_sum = 0.0;
     episode_state.current_crash_penalty_sum = 0.0;
     episode_state.current_lap_bonus_sum = 0.0;
     episode_state.current_best_progress_fraction = 0.0;
     episode_state.current_crashes = 0;
+    episode_state.current_off_track_warnings = 0;
+    episode_state.current_invalid_lap_wraps = 0;
+    episode_state.current_reward_anomalies = 0;
+    episode_state.current_steering_change_sum = 0.0;
+    episode_state.current_throttle_sum = 0.0;
+    episode_state.current_previous_steering = None;
+    episode_state.current_timeout_extensions = 0;
+    episode_state.current_countdown_ticks = 0;
+}
+
+/// Emits the structured `episode_start` event.
+fn log_episode_start(episode: u32) {
+    tracing::info!(
+        target: EPISODE_LOG_TARGET,
+        event = "episode_start",
+        episode,
+        "episode {episode} started"
+    );
+}
+
+/// Emits the structured `episode_end` event for the episode
+/// [`finalize_episode`] just closed. `lap_time_s` is present only for
+/// completed laps.
+fn log_episode_end(episode_state: &EpisodeState, dt: f32) {
+    let episode = episode_state.current_episode.saturating_sub(1);
+    let reason = episode_state
+        .last_end_reason
+        .map_or("unknown", EpisodeEndReason::as_str);
+    let ticks = episode_state.last_episode_ticks;
+    let lap_time_s = (episode_state.last_end_reason == Some(EpisodeEndReason::LapComplete))
+        .then_some(ticks as f32 * dt);
+    tracing::info!(
+        target: EPISODE_LOG_TARGET,
+        event = "episode_end",
+        episode,
+        reason,
+        episode_return = episode_state.last_episode_return,
+        best_progress = episode_state.last_episode_best_progress_fraction,
+        crashes = episode_state.last_episode_crashes,
+        ticks,
+        lap_time_s,
+        "episode {episode} ended: {reason}"
+    );
 }
 
 fn push_with_limit(buffer: &mut VecDeque<f32>, value: f32, limit: usize) {
@@ -382,3 +1234,571 @@ fn signed_angle_between(from: Vec2, to: Vec2) -> f32 {
     }
     wrap_angle(to_n.to_angle() - from_n.to_angle())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::{
+        EpisodeConfig, EpisodeEndReason, EpisodeMovingAverages, EpisodeState, LapValidation,
+        LapWrapOutcome, ProgressRewardMode, cap_shaped_reward, evaluate_lap_wrap, finalize_episode,
+        log_episode_end, respawn_forward_pose,
+    };
+    use crate::agent::action::CarAction;
+    use crate::game::car::Car;
+    use crate::game::reward::{RewardContext, RewardStack, test_context};
+    use crate::maps::centerline::{GridDir, TrackCenterline};
+    use crate::maps::fixtures::{ring_centerline, ring_grid, ring_track};
+    use crate::maps::grid::TrackGrid;
+    use crate::maps::parts::TilePart;
+    use crate::maps::track::Track;
+    use bevy::prelude::Vec2;
+
+    /// Drives the wrap check over a fraction sequence, arming like the
+    /// episode loop does, and returns (valid laps, invalid wraps).
+    fn run_fractions(config: &EpisodeConfig, fractions: &[f32]) -> (u32, u32) {
+        let mut validation = LapValidation::default();
+        let mut armed = false;
+        let mut previous = 0.0;
+        let mut valid = 0;
+        let mut invalid = 0;
+
+        for &fraction in fractions {
+            if fraction >= config.lap_arm_fraction {
+                armed = true;
+            }
+            match evaluate_lap_wrap(config, armed, previous, fraction, &mut validation) {
+                LapWrapOutcome::Valid => valid += 1,
+                LapWrapOutcome::Invalid => invalid += 1,
+                LapWrapOutcome::NoWrap => {}
+            }
+            previous = fraction;
+        }
+
+        (valid, invalid)
+    }
+
+    /// Runs one timed-out episode applying `steering(tick)` at half throttle
+    /// and returns (steering effort, throttle usage, moving steering mean).
+    fn action_effort(ticks: u32, steering: impl Fn(u32) -> f32) -> (f32, f32, f32) {
+        let config = EpisodeConfig::default();
+        let mut state = EpisodeState::default();
+        let mut moving_avg = EpisodeMovingAverages::default();
+        for tick in 0..ticks {
+            state.ticks_in_episode += 1;
+            state.record_applied_action(CarAction {
+                steering: steering(tick),
+                throttle: 0.5,
+            });
+        }
+        finalize_episode(
+            &config,
+            &mut state,
+            &mut moving_avg,
+            EpisodeEndReason::Timeout,
+            None,
+        );
+        assert_eq!(state.current_steering_change_sum, 0.0);
+        assert_eq!(state.current_previous_steering, None);
+        (
+            state.last_episode_steering_effort,
+            state.last_episode_throttle_usage,
+            moving_avg.steering_effort_mean,
+        )
+    }
+
+    #[test]
+    fn steering_effort_scales_with_oscillation_amplitude() {
+        let (constant, throttle, _) = action_effort(600, |_| 0.4);
+        assert!(constant.abs() < 1e-6, "constant steering effort {constant}");
+        assert!((throttle - 0.5).abs() < 1e-6);
+
+        let alternate = |amplitude: f32| {
+            move |tick: u32| {
+                if tick.is_multiple_of(2) {
+                    amplitude
+                } else {
+                    -amplitude
+                }
+            }
+        };
+        let (small, _, small_mean) = action_effort(600, alternate(0.2));
+        let (large, _, _) = action_effort(600, alternate(0.8));
+
+        // Every tick after the first flips sign, a change of 2 * amplitude.
+        assert!(
+            (small - 0.4 * 599.0 / 600.0).abs() < 1e-4,
+            "small effort {small}"
+        );
+        assert!((large / small - 4.0).abs() < 1e-3);
+        assert_eq!(small_mean, small);
+    }
+
+    #[test]
+    fn oscillating_across_the_start_line_earns_no_lap() {
+        let config = EpisodeConfig::default();
+        let mut fractions = vec![0.006, 0.004];
+        for _ in 0..10 {
+            // Reverse over the line (arms via the high fraction), then forward again.
+            fractions.extend_from_slice(&[0.998, 0.995, 0.999, 0.002, 0.004]);
+        }
+
+        let (valid, invalid) = run_fractions(&config, &fractions);
+        assert_eq!(valid, 0);
+        assert_eq!(invalid, 10);
+    }
+
+    #[test]
+    fn full_ordered_lap_is_valid() {
+        let config = EpisodeConfig::default();
+        let mut fractions: Vec<f32> = (1..1000).map(|i| i as f32 / 1000.0).collect();
+        fractions.push(0.002);
+
+        let (valid, invalid) = run_fractions(&config, &fractions);
+        assert_eq!(valid, 1);
+        assert_eq!(invalid, 0);
+    }
+
+    #[test]
+    fn default_cap_never_triggers_on_legitimate_max_speed_driving() {
+        let config = EpisodeConfig {
+            progress_mode: ProgressRewardMode::Both,
+            ..EpisodeConfig::default()
+        };
+        let car = Car::default();
+        let dt = 1.0 / 60.0;
+
+        // Terminal speed of the physics step: v = (v + thrust * dt) * drag.
+        let max_speed = car.thrust * dt * car.drag / (1.0 - car.drag);
+        let max_distance_per_tick = max_speed * dt;
+
+        // The smallest closed loop gives the largest fraction per pixel.
+        let centerline = ring_centerline();
+
+        // Gates are spaced further apart than one tick of travel, so at most
+        // one can be crossed per tick.
+        let gate_spacing = centerline.total_length() / (config.checkpoint_gate_count + 1) as f32;
+        assert!(gate_spacing > max_distance_per_tick);
+
+        let mut stack = RewardStack::from_config(&config);
+        for heading_error in [0.0, PI] {
+            let ctx = RewardContext {
+                progress_gain: max_distance_per_tick / centerline.total_length(),
+                gates_crossed: 1,
+                heading_error,
+                speed: max_speed,
+                ..test_context()
+            };
+            let mut breakdown = stack.compute(&ctx);
+            assert_eq!(
+                cap_shaped_reward(config.max_abs_reward_per_tick, &mut breakdown),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn a_track_without_a_centerline_runs_without_measuring_progress() {
+        use super::{episode_loop_system, normalize_reward_to_track_system};
+        use crate::agent::observation::{SensorReadings, update_sensor_readings_system};
+        use crate::game::checkpoints::{
+            CheckpointGates, CheckpointProgress, setup_checkpoint_gates_system,
+        };
+        use crate::game::collision::{CarCollisionEvent, CollisionEvent, OffTrackWarning};
+        use crate::game::progress::{TrackProgress, update_track_progress_system};
+        use TilePart::*;
+        use bevy::prelude::*;
+
+        let grid = TrackGrid::new(
+            vec![vec![CornerNW, SpawnPoint, StraightH]],
+            100.0,
+            Vec2::new(-150.0, 50.0),
+        );
+        let (spawn_position, spawn_rotation) = grid.find_spawn().expect("spawn tile");
+        let mut app = App::new();
+        app.init_resource::<Time<bevy::time::Fixed>>()
+            .insert_resource(EpisodeConfig {
+                normalize_progress_to_track: true,
+                ..EpisodeConfig::default()
+            })
+            .init_resource::<EpisodeState>()
+            .init_resource::<EpisodeMovingAverages>()
+            .init_resource::<RewardStack>()
+            .init_resource::<crate::agent::observation::ObservationConfig>()
+            .add_message::<CollisionEvent>()
+            .add_message::<OffTrackWarning>()
+            .add_message::<CarCollisionEvent>()
+            .add_message::<super::EpisodeEndedEvent>()
+            .add_systems(
+                Update,
+                (
+                    setup_checkpoint_gates_system,
+                    normalize_reward_to_track_system,
+                    update_track_progress_system,
+                    episode_loop_system,
+                    update_sensor_readings_system,
+                )
+                    .chain(),
+            );
+        app.world_mut().spawn(Track {
+            grid,
+            spawn_position,
+            spawn_rotation,
+            centerline: None,
+        });
+        let car = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(spawn_position.x + 20.0, spawn_position.y, 0.0),
+                Car::default(),
+                TrackProgress::default(),
+                CheckpointProgress::default(),
+                LapValidation::default(),
+                SensorReadings::default(),
+            ))
+            .id();
+
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let progress = app.world().get::<TrackProgress>(car).unwrap();
+        let default = TrackProgress::default();
+        assert_eq!(
+            (progress.s, progress.fraction, progress.laps),
+            (default.s, default.fraction, default.laps)
+        );
+        assert_eq!(progress.closest_point, default.closest_point);
+        assert_eq!(progress.tangent, default.tangent);
+        let state = app.world().resource::<EpisodeState>();
+        assert_eq!(state.ticks_in_episode, 0);
+        assert_eq!(state.current_best_progress_fraction, 0.0);
+        assert!(app.world().get_resource::<CheckpointGates>().is_none());
+        // Rays still work without a centreline.
+        let sensors = app.world().get::<SensorReadings>(car).unwrap();
+        assert!(sensors.ray_distances.iter().any(|distance| *distance > 0.0));
+    }
+
+    #[test]
+    fn only_the_off_road_car_is_reset_to_spawn() {
+        use super::reset_crashed_cars_system;
+        use crate::agent::observation::{ObservationDelayBuffer, SensorReadings};
+        use crate::game::collision::{
+            CollisionConfig, CollisionEvent, OffTrackState, OffTrackWarning,
+            collision_detection_system,
+        };
+        use crate::game::progress::TrackProgress;
+        use bevy::prelude::*;
+
+        let track = ring_track();
+        let (spawn_position, spawn_rotation) = (track.spawn_position, track.spawn_rotation);
+        let mut app = App::new();
+        app.insert_resource(CollisionConfig::default())
+            .add_message::<CollisionEvent>()
+            .add_message::<OffTrackWarning>()
+            .add_systems(
+                Update,
+                (collision_detection_system, reset_crashed_cars_system).chain(),
+            );
+        app.world_mut().spawn(track);
+        let mut spawn_car = |position: Vec2| {
+            app.world_mut()
+                .spawn((
+                    Transform::from_translation(position.extend(0.0)),
+                    Car {
+                        velocity: Vec2::new(40.0, 0.0),
+                        ..Car::default()
+                    },
+                    OffTrackState::default(),
+                    TrackProgress::default(),
+                    LapValidation::default(),
+                    SensorReadings {
+                        previous_heading: 1.0,
+                        ray_age_ticks: Some(3),
+                        ..SensorReadings::default()
+                    },
+                    serde_json::from_str::<ObservationDelayBuffer>(
+                        r#"{"episode": 1, "history": [[0.5], [0.25]]}"#,
+                    )
+                    .unwrap(),
+                ))
+                .id()
+        };
+        let on_road_position = spawn_position + Vec2::new(20.0, 0.0);
+        let on_road = spawn_car(on_road_position);
+        let off_road = spawn_car(Vec2::new(0.0, 0.0));
+
+        app.update();
+
+        let world = app.world();
+        let kept = world.get::<Transform>(on_road).unwrap();
+        assert_eq!(kept.translation.truncate(), on_road_position);
+        assert_eq!(
+            world.get::<Car>(on_road).unwrap().velocity,
+            Vec2::new(40.0, 0.0)
+        );
+        let reset = world.get::<Transform>(off_road).unwrap();
+        assert_eq!(reset.translation.truncate(), spawn_position);
+        assert_eq!(world.get::<Car>(off_road).unwrap().velocity, Vec2::ZERO);
+        let progress = world.get::<TrackProgress>(off_road).unwrap();
+        assert!(progress.closest_point.distance(spawn_position) < 1.0);
+
+        // The reset car starts over like a fresh spawn; the other keeps its
+        // history.
+        assert_eq!(
+            world
+                .get::<OffTrackState>(off_road)
+                .unwrap()
+                .consecutive_ticks,
+            0
+        );
+        let sensors = world.get::<SensorReadings>(off_road).unwrap();
+        assert_eq!(sensors.previous_heading, spawn_rotation);
+        assert_eq!(sensors.ray_age_ticks, None);
+        assert!(
+            world
+                .get::<ObservationDelayBuffer>(off_road)
+                .unwrap()
+                .is_empty()
+        );
+        let kept_sensors = world.get::<SensorReadings>(on_road).unwrap();
+        assert_eq!(kept_sensors.previous_heading, 1.0);
+        assert_eq!(kept_sensors.ray_age_ticks, Some(3));
+        assert!(
+            !world
+                .get::<ObservationDelayBuffer>(on_road)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn respawn_forward_advances_by_the_configured_arc_length_onto_the_road() {
+        let track = ring_track();
+        let spawn_position = track.spawn_position;
+        let centerline = track.centerline.as_ref().unwrap();
+        let distance = EpisodeConfig::default().respawn_forward_distance;
+        let length = centerline.total_length();
+
+        let mut s = centerline.project(spawn_position).s;
+        // Enough hops to wrap past the start line.
+        for _ in 0..(length / distance) as usize + 2 {
+            let (position, rotation) = respawn_forward_pose(centerline, s, distance);
+            let projection = centerline.project(position);
+            let expected = (s + distance).rem_euclid(length);
+            let advanced =
+                (projection.s - expected + length / 2.0).rem_euclid(length) - length / 2.0;
+
+            assert!(advanced.abs() < 0.5, "s {s} -> {}", projection.s);
+            assert!(projection.distance < 0.5, "snapped onto the centreline");
+            assert!(track.grid.is_road_at(position));
+            let heading = Vec2::from_angle(rotation);
+            assert!(
+                heading.dot(projection.tangent) > 0.99,
+                "faces along the track"
+            );
+            s = projection.s;
+        }
+    }
+
+    #[test]
+    fn spikes_are_clamped_and_breakdown_still_sums() {
+        let config = EpisodeConfig::default();
+        let mut stack = RewardStack::from_config(&config);
+        let ctx = RewardContext {
+            // A projection glitch jumping a third of the lap in one tick.
+            progress_gain: 0.35,
+            ..test_context()
+        };
+        let mut breakdown = stack.compute(&ctx);
+
+        let raw = cap_shaped_reward(20.0, &mut breakdown).expect("spike should hit the cap");
+
+        assert!((raw - (0.35 * config.progress_reward_scale - 0.005)).abs() < 1e-3);
+        assert!((breakdown.total() - 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn normalized_reward_per_unit_distance_matches_across_tracks() {
+        use TilePart::*;
+        let config = EpisodeConfig {
+            normalize_progress_to_track: true,
+            ..EpisodeConfig::default()
+        };
+        let small = ring_grid();
+        let large = TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, StraightH, StraightH, CornerNE],
+                vec![StraightV, Empty, Empty, Empty, StraightV],
+                vec![StraightV, Empty, Empty, Empty, StraightV],
+                vec![CornerSW, StraightH, StraightH, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 200.0),
+        );
+
+        let distance = 50.0;
+        let per_unit = |grid: &TrackGrid, spawn: (usize, usize)| {
+            let length = TrackCenterline::build_closed_loop(grid, spawn, GridDir::East)
+                .expect("closed loop")
+                .total_length();
+            let effective = config.normalized_for_track(length);
+            let mut stack = RewardStack::from_config(&effective);
+            let reward = stack
+                .compute(&RewardContext {
+                    progress_gain: distance / length,
+                    ..test_context()
+                })
+                .get("progress");
+            (
+                reward / distance,
+                effective.crash_penalty / length,
+                effective.lap_bonus / length,
+            )
+        };
+
+        let (small_progress, small_crash, small_lap) = per_unit(&small, (0, 1));
+        let (large_progress, large_crash, large_lap) = per_unit(&large, (0, 1));
+
+        assert!((small_progress - large_progress).abs() < 1e-5);
+        assert!((small_crash - large_crash).abs() < 1e-7);
+        assert!((small_lap - large_lap).abs() < 1e-6);
+        assert!(
+            (small_progress - config.progress_reward_scale / config.reference_track_length).abs()
+                < 1e-5
+        );
+
+        let unnormalized = EpisodeConfig::default();
+        assert_eq!(
+            unnormalized
+                .normalized_for_track(714.0)
+                .progress_reward_scale,
+            unnormalized.progress_reward_scale
+        );
+    }
+
+    #[test]
+    fn track_normalization_keeps_pushed_reward_terms() {
+        use super::normalize_reward_to_track_system;
+        use crate::game::reward::RewardTerm;
+        use bevy::prelude::*;
+
+        struct Custom;
+
+        impl RewardTerm for Custom {
+            fn name(&self) -> &str {
+                "custom"
+            }
+
+            fn compute(&mut self, _ctx: &RewardContext) -> f32 {
+                0.5
+            }
+        }
+
+        let config = EpisodeConfig {
+            normalize_progress_to_track: true,
+            ..EpisodeConfig::default()
+        };
+        let mut app = App::new();
+        app.insert_resource(config)
+            .init_resource::<RewardStack>()
+            .add_systems(Update, normalize_reward_to_track_system);
+        app.world_mut().resource_mut::<RewardStack>().push(Custom);
+        let track = ring_track();
+        let length = track.centerline.as_ref().unwrap().total_length();
+        app.world_mut().spawn(track);
+        app.update();
+
+        let effective = config.normalized_for_track(length);
+        assert_ne!(
+            effective.progress_reward_scale,
+            config.progress_reward_scale
+        );
+        let breakdown = app
+            .world_mut()
+            .resource_mut::<RewardStack>()
+            .compute(&RewardContext {
+                progress_gain: 0.1,
+                ..test_context()
+            });
+        assert_eq!(breakdown.get("custom"), 0.5);
+        assert!((breakdown.get("progress") - 0.1 * effective.progress_reward_scale).abs() < 1e-5);
+    }
+
+    #[test]
+    fn episode_end_event_carries_structured_fields() {
+        use std::collections::BTreeMap;
+        use std::fmt::Debug;
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        type Captured = Arc<Mutex<Vec<(String, BTreeMap<String, String>)>>>;
+
+        struct Capture(Captured);
+        struct Fields<'a>(&'a mut BTreeMap<String, String>);
+
+        impl Visit for Fields<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                self.0
+                    .insert(field.name().to_string(), format!("{value:?}"));
+            }
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _span: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = BTreeMap::new();
+                event.record(&mut Fields(&mut fields));
+                let target = event.metadata().target().to_string();
+                self.0.lock().expect("capture lock").push((target, fields));
+            }
+            fn enter(&self, _span: &Id) {}
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let config = EpisodeConfig::default();
+        let mut state = EpisodeState {
+            current_episode: 4,
+            ticks_in_episode: 1200,
+            current_return: 87.5,
+            current_best_progress_fraction: 1.0,
+            current_crashes: 0,
+            ..EpisodeState::default()
+        };
+        let mut moving_avg = EpisodeMovingAverages::default();
+        finalize_episode(
+            &config,
+            &mut state,
+            &mut moving_avg,
+            EpisodeEndReason::LapComplete,
+            None,
+        );
+
+        let captured = Captured::default();
+        tracing::subscriber::with_default(Capture(captured.clone()), || {
+            log_episode_end(&state, 1.0 / 64.0);
+        });
+
+        let captured = captured.lock().expect("capture lock");
+        let (target, fields) = captured.first().expect("one episode_end event");
+        assert_eq!(target, super::EPISODE_LOG_TARGET);
+        assert_eq!(fields["event"], "\"episode_end\"");
+        assert_eq!(fields["episode"], "4");
+        assert_eq!(fields["reason"], "\"lap_complete\"");
+        assert_eq!(fields["episode_return"], "87.5");
+        assert_eq!(fields["best_progress"], "1.0");
+        assert_eq!(fields["crashes"], "0");
+        assert_eq!(fields["ticks"], "1200");
+        assert_eq!(fields["lap_time_s"], "18.75");
+        assert!(fields.contains_key("message"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Instant replay of the last few seconds.
+//!
+//! A fixed-size ring always records the car's pose and applied action each
+//! fixed tick. When an episode ends in a crash the ring is frozen into a
+//! clip, and `F7` plays the latest crash clip (or, before any crash, the
+//! live buffer) as a translucent ghost, one recorded tick per fixed tick.
+//! Playback is visual only and runs while the sim is paused.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::{ActionState, CarAction};
+use crate::game::car::{CAR_HEIGHT, CAR_WIDTH, Car};
+use crate::game::episode::{EpisodeEndReason, EpisodeState};
+
+/// Instant-replay tuning (config `instant_replay`).
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstantReplayConfig {
+    /// Fixed ticks kept in the ring (300 = 5 s at 60 Hz).
+    pub capacity_ticks: usize,
+    pub ghost_color: Color,
+}
+
+impl Default for InstantReplayConfig {
+    fn default() -> Self {
+        Self {
+            capacity_ticks: 300,
+            ghost_color: Color::srgba(0.3, 0.8, 1.0, 0.45),
+        }
+    }
+}
+
+/// Car state recorded for one fixed tick.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReplayFrame {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub action: CarAction,
+}
+
+/// Ring of the most recent frames; once full, each push overwrites the
+/// oldest.
+#[derive(Clone, Debug)]
+pub struct ReplayRing {
+    frames: VecDeque<ReplayFrame>,
+    capacity: usize,
+}
+
+impl ReplayRing {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, frame: ReplayFrame) {
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Frames oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &ReplayFrame> {
+        self.frames.iter()
+    }
+
+    /// A playback of the current contents, oldest first.
+    pub fn playback(&self) -> ReplayPlayback {
+        ReplayPlayback {
+            frames: self.frames.iter().copied().collect(),
+            next: 0,
+        }
+    }
+}
+
+/// A frozen clip being played back.
+#[derive(Clone, Debug)]
+pub struct ReplayPlayback {
+    frames: Vec<ReplayFrame>,
+    next: usize,
+}
+
+impl Iterator for ReplayPlayback {
+    type Item = ReplayFrame;
+
+    fn next(&mut self) -> Option<ReplayFrame> {
+        let frame = self.frames.get(self.next).copied()?;
+        self.next += 1;
+        Some(frame)
+    }
+}
+
+/// The always-on ring, the latest crash clip, and any playback in progress.
+#[derive(Resource, Debug)]
+pub struct InstantReplay {
+    pub ring: ReplayRing,
+    pub crash_clip: Option<ReplayRing>,
+    pub playback: Option<ReplayPlayback>,
+}
+
+impl FromWorld for InstantReplay {
+    fn from_world(world: &mut World) -> Self {
+        let capacity = world
+            .get_resource::<InstantReplayConfig>()
+            .copied()
+            .unwrap_or_default()
+            .capacity_ticks;
+        Self {
+            ring: ReplayRing::new(capacity),
+            crash_clip: None,
+            playback: None,
+        }
+    }
+}
+
+/// Marker for the ghost car shown during playback.
+#[derive(Component)]
+pub struct ReplayGhost;
+
+/// Records the car after physics and collision, before the episode loop can
+/// move it back to the spawn.
+pub fn record_instant_replay_system(
+    mut replay: ResMut<InstantReplay>,
+    action_state: Option<Res<ActionState>>,
+    car_query: Query<&Transform, With<Car>>,
+) {
+    let Ok(transform) = car_query.single() else {
+        return;
+    };
+    replay.ring.push(ReplayFrame {
+        position: transform.translation.truncate(),
+        rotation: transform.rotation.to_euler(EulerRot::ZYX).0,
+        action: action_state.map(|state| state.applied).unwrap_or_default(),
+    });
+}
+
+/// Freezes the ring into the crash clip when this tick ended in a crash.
+pub fn capture_crash_clip_system(
+    mut replay: ResMut<InstantReplay>,
+    episode_state: Res<EpisodeState>,
+) {
+    if episode_state.current_tick_end_reason == Some(EpisodeEndReason::Crash) {
+        replay.crash_clip = Some(replay.ring.clone());
+    }
+}
+
+/// `F7` starts (or restarts) playback of the latest crash clip, falling
+/// back to the live buffer.
+pub fn instant_replay_hotkey_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut replay: ResMut<InstantReplay>,
+) {
+    if !keyboard.just_pressed(KeyCode::F7) {
+        return;
+    }
+    let clip = replay.crash_clip.as_ref().unwrap_or(&replay.ring);
+    info!("Instant replay: {} ticks.", clip.len());
+    replay.playback = Some(clip.playback());
+}
+
+/// Moves the ghost one recorded tick per fixed tick, spawning it when
+/// playback starts and despawning it at the end of the clip.
+pub fn instant_replay_playback_system(
+    mut commands: Commands,
+    config: Res<InstantReplayConfig>,
+    mut replay: ResMut<InstantReplay>,
+    mut ghosts: Query<(Entity, &mut Transform), With<ReplayGhost>>,
+) {
+    let frame = replay.playback.as_mut().and_then(Iterator::next);
+    let Some(frame) = frame else {
+        replay.playback = None;
+        for (entity, _) in &ghosts {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let transform = Transform::from_xyz(frame.position.x, frame.position.y, 11.0)
+        .with_rotation(Quat::from_rotation_z(frame.rotation));
+    match ghosts.iter_mut().next() {
+        Some((_, mut ghost)) => *ghost = transform,
+        None => {
+            commands.spawn((
+                ReplayGhost,
+                Sprite {
+                    color: config.ghost_color,
+                    custom_size: Some(Vec2::new(CAR_WIDTH, CAR_HEIGHT)),
+                    ..default()
+                },
+                transform,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplayFrame, ReplayRing};
+    use crate::agent::action::CarAction;
+    use bevy::prelude::Vec2;
+
+    fn frame(tick: u32) -> ReplayFrame {
+        ReplayFrame {
+            position: Vec2::new(tick as f32, -(tick as f32)),
+            rotation: tick as f32 * 0.01,
+            action: CarAction {
+                steering: 0.0,
+                throttle: tick as f32 / 100.0,
+            },
+        }
+    }
+
+    #[test]
+    fn ring_keeps_the_newest_ticks_and_plays_them_in_order() {
+        let mut ring = ReplayRing::new(300);
+        for tick in 0..299 {
+            ring.push(frame(tick));
+        }
+        assert_eq!(ring.len(), 299);
+        assert_eq!(ring.iter().next(), Some(&frame(0)));
+
+        for tick in 299..1000 {
+            ring.push(frame(tick));
+        }
+        assert_eq!(ring.len(), ring.capacity());
+        assert_eq!(ring.len(), 300);
+        let expected: Vec<ReplayFrame> = (700..1000).map(frame).collect();
+        assert!(ring.iter().eq(expected.iter()));
+
+        let played: Vec<ReplayFrame> = ring.playback().collect();
+        assert_eq!(played, expected);
+
+        // Playback is a frozen copy: recording on does not change it.
+        let mut playback = ring.playback();
+        ring.push(frame(1000));
+        assert_eq!(playback.next(), Some(frame(700)));
+        assert_eq!(playback.count(), 299);
+    }
+}
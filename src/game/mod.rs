@@ -1,8 +1,15 @@
 pub mod car;
+pub mod car_visual;
+pub mod checkpoints;
 pub mod collision;
+pub mod curriculum;
 pub mod episode;
+pub mod instant_replay;
 pub mod physics;
 pub mod plugin;
 pub mod progress;
+pub mod reward;
+pub mod seeding;
+pub mod snapshot;
 
 pub use plugin::GamePlugin;
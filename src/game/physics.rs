@@ -1,7 +1,9 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::agent::action::ActionState;
+use crate::agent::action::{ActionState, finite_or_zero};
 use crate::game::car::Car;
+use crate::maps::track::Track;
 
 /// Minimal deterministic car state used by the pure replay stepper.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -12,25 +14,114 @@ pub struct CarKinematicState {
 }
 
 /// Immutable car dynamics parameters consumed by the pure stepper.
-#[derive(Clone, Copy, Debug, PartialEq)]
+///
+/// As a resource it holds the tuning newly spawned cars start with.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CarDynamicsParams {
     pub rotation_speed: f32,
     pub thrust: f32,
     pub drag: f32,
 }
 
+impl Default for CarDynamicsParams {
+    fn default() -> Self {
+        Self {
+            rotation_speed: 4.0,
+            thrust: 750.0,
+            drag: 0.985,
+        }
+    }
+}
+
+/// Integration settings for [`car_physics_system`] (config `physics`).
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsConfig {
+    /// Dynamics steps per fixed tick, each over `dt / physics_substeps`.
+    /// `1` (the default) is exactly the single-step integration; `0` is
+    /// treated as `1`.
+    pub physics_substeps: u32,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            physics_substeps: 1,
+        }
+    }
+}
+
+/// Boost given by `BoostPadH`/`BoostPadV` tiles.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoostPadConfig {
+    /// Total forward speed added per pad entry, in world units per second
+    /// (before drag).
+    pub impulse: f32,
+    /// Fixed ticks the impulse is spread over.
+    pub duration_ticks: u32,
+}
+
+impl Default for BoostPadConfig {
+    fn default() -> Self {
+        Self {
+            impulse: 150.0,
+            duration_ticks: 10,
+        }
+    }
+}
+
+/// Per-car boost-pad state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoostState {
+    /// Ticks of boost still to apply.
+    pub ticks_remaining: u32,
+    /// Whether the car was on a pad last tick; a boost starts only on entry.
+    pub on_pad: bool,
+}
+
+/// Starts a boost when the car enters a pad and applies one tick of any
+/// active boost along `heading`.
+pub fn apply_boost(
+    boost: &mut BoostState,
+    on_pad: bool,
+    velocity: &mut Vec2,
+    heading: f32,
+    config: &BoostPadConfig,
+) {
+    if on_pad && !boost.on_pad {
+        boost.ticks_remaining = config.duration_ticks;
+    }
+    boost.on_pad = on_pad;
+    if boost.ticks_remaining == 0 {
+        return;
+    }
+    boost.ticks_remaining -= 1;
+    let per_tick = config.impulse / config.duration_ticks.max(1) as f32;
+    *velocity += Vec2::from_angle(heading) * per_tick;
+}
+
 /// Applies the current action to the car on the fixed simulation tick.
 ///
 /// This system is the only place where actions become state mutation:
 /// it updates the car transform and velocity deterministically given the fixed
-/// timestep and the fixed-tick `ActionState`.
+/// timestep and the fixed-tick `ActionState`. Boost pads under the car's
+/// position at the start of the tick add their impulse before the step,
+/// which is split into [`PhysicsConfig::physics_substeps`] substeps.
 pub fn car_physics_system(
     time: Res<Time<bevy::time::Fixed>>,
     action_state: Res<ActionState>,
+    boost_config: Option<Res<BoostPadConfig>>,
+    physics_config: Option<Res<PhysicsConfig>>,
+    track_query: Query<&Track>,
     mut query: Query<(&mut Transform, &mut Car)>,
 ) {
     let dt = time.delta_secs();
     let action = action_state.applied;
+    let boost_config = boost_config.map_or_else(BoostPadConfig::default, |config| *config);
+    let substeps = physics_config.map_or(1, |config| config.physics_substeps);
+    let track = track_query.single().ok();
 
     for (mut transform, mut car) in query.iter_mut() {
         let forward = (transform.rotation * Vec3::X).truncate();
@@ -40,13 +131,29 @@ pub fn car_physics_system(
             velocity: car.velocity,
             heading,
         };
+
+        let on_pad = track.is_some_and(|track| track.grid.is_boost_pad_at(state.position));
+        apply_boost(
+            &mut car.boost,
+            on_pad,
+            &mut state.velocity,
+            heading,
+            &boost_config,
+        );
         let params = CarDynamicsParams {
             rotation_speed: car.rotation_speed,
             thrust: car.thrust,
             drag: car.drag,
         };
 
-        step_car_dynamics(&mut state, action.steering, action.throttle, dt, params);
+        step_car_dynamics_substepped(
+            &mut state,
+            action.steering,
+            action.throttle,
+            dt,
+            params,
+            substeps,
+        );
 
         transform.translation.x = state.position.x;
         transform.translation.y = state.position.y;
@@ -56,6 +163,9 @@ pub fn car_physics_system(
 }
 
 /// Pure deterministic car step used by runtime physics and replay tests.
+///
+/// Non-finite steering or throttle is treated as zero at this boundary so a
+/// single bad action cannot corrupt the transform and every downstream system.
 pub fn step_car_dynamics(
     state: &mut CarKinematicState,
     steering: f32,
@@ -63,6 +173,9 @@ pub fn step_car_dynamics(
     dt: f32,
     params: CarDynamicsParams,
 ) {
+    let steering = finite_or_zero(steering);
+    let throttle = finite_or_zero(throttle);
+
     state.heading += -steering.clamp(-1.0, 1.0) * params.rotation_speed * dt;
 
     if throttle > 0.0 {
@@ -72,17 +185,63 @@ pub fn step_car_dynamics(
 
     state.velocity *= params.drag;
     state.position += state.velocity * dt;
+
+    debug_assert!(
+        state.position.is_finite() && state.velocity.is_finite() && state.heading.is_finite(),
+        "car dynamics produced a non-finite state: {state:?}"
+    );
+}
+
+/// Advances `dt` in `substeps` equal [`step_car_dynamics`] calls. Drag is a
+/// per-step factor, so each substep uses `drag^(1 / substeps)` to keep the
+/// per-tick decay unchanged. One substep (or zero) is exactly one
+/// [`step_car_dynamics`] call.
+pub fn step_car_dynamics_substepped(
+    state: &mut CarKinematicState,
+    steering: f32,
+    throttle: f32,
+    dt: f32,
+    params: CarDynamicsParams,
+    substeps: u32,
+) {
+    if substeps <= 1 {
+        step_car_dynamics(state, steering, throttle, dt, params);
+        return;
+    }
+    let fraction = 1.0 / substeps as f32;
+    let sub_params = CarDynamicsParams {
+        drag: params.drag.powf(fraction),
+        ..params
+    };
+    for _ in 0..substeps {
+        step_car_dynamics(state, steering, throttle, dt * fraction, sub_params);
+    }
+}
+
+/// Reflects `velocity` off a wall whose `normal` points back into the road.
+/// The component heading into the wall is reversed and scaled by
+/// `restitution`, and the component along the wall loses `friction` of its
+/// magnitude; velocity already leaving the wall is returned unchanged.
+///
+/// Both factors are clamped to `[0, 1]` and the normal is renormalised, and
+/// the result is never faster than `velocity`, so a noisy normal estimate
+/// cannot add energy.
+pub fn reflect_off_wall(velocity: Vec2, normal: Vec2, restitution: f32, friction: f32) -> Vec2 {
+    let normal = normal.normalize_or_zero();
+    let into_wall = velocity.dot(normal);
+    if normal == Vec2::ZERO || into_wall >= 0.0 {
+        return velocity;
+    }
+    let along_wall = velocity - into_wall * normal;
+    let reflected = along_wall * (1.0 - friction.clamp(0.0, 1.0))
+        - restitution.clamp(0.0, 1.0) * into_wall * normal;
+    reflected.clamp_length_max(velocity.length())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn lcg_next(seed: &mut u64) -> f32 {
-        *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
-        let value = ((*seed >> 32) as u32) as f32 / u32::MAX as f32;
-        value
-    }
+    use crate::agent::scripted::{ScriptedController, ScriptedControllerConfig};
 
     #[test]
     fn deterministic_replay_same_seed_same_actions_identical_trajectory() {
@@ -102,30 +261,243 @@ mod tests {
         };
         let mut second_run_state = first_run_state;
 
-        let mut first_seed = seed;
-        for _ in 0..steps {
-            let steering = lcg_next(&mut first_seed) * 2.0 - 1.0;
-            let throttle = if lcg_next(&mut first_seed) > 0.35 {
-                1.0
-            } else {
-                0.0
-            };
-            step_car_dynamics(&mut first_run_state, steering, throttle, dt, params);
-        }
-
-        let mut second_seed = seed;
-        for _ in 0..steps {
-            let steering = lcg_next(&mut second_seed) * 2.0 - 1.0;
-            let throttle = if lcg_next(&mut second_seed) > 0.35 {
-                1.0
-            } else {
-                0.0
-            };
-            step_car_dynamics(&mut second_run_state, steering, throttle, dt, params);
+        for state in [&mut first_run_state, &mut second_run_state] {
+            let mut script = ScriptedController::new(ScriptedControllerConfig::SeededRandom {
+                seed,
+                hold_ticks: 1,
+            });
+            for tick in 0..steps {
+                let action = script.action_at(tick);
+                step_car_dynamics(state, action.steering, action.throttle, dt, params);
+            }
         }
 
         assert_eq!(first_run_state.position, second_run_state.position);
         assert_eq!(first_run_state.velocity, second_run_state.velocity);
         assert_eq!(first_run_state.heading, second_run_state.heading);
     }
+
+    #[test]
+    fn non_finite_actions_are_treated_as_zero() {
+        let dt = 1.0 / 60.0;
+        let params = CarDynamicsParams {
+            rotation_speed: 4.0,
+            thrust: 750.0,
+            drag: 0.985,
+        };
+        let start = CarKinematicState {
+            position: Vec2::new(10.0, -5.0),
+            velocity: Vec2::new(120.0, 40.0),
+            heading: 0.3,
+        };
+
+        let mut reference = start;
+        for _ in 0..30 {
+            step_car_dynamics(&mut reference, 0.0, 0.0, dt, params);
+        }
+
+        for (steering, throttle) in [
+            (f32::NAN, f32::NAN),
+            (f32::INFINITY, f32::NEG_INFINITY),
+            (f32::NEG_INFINITY, f32::NAN),
+        ] {
+            let mut state = start;
+            for _ in 0..30 {
+                step_car_dynamics(&mut state, steering, throttle, dt, params);
+            }
+
+            assert!(state.position.is_finite());
+            assert!(state.velocity.is_finite());
+            assert!(state.heading.is_finite());
+            assert_eq!(state, reference);
+        }
+    }
+
+    /// Drives one scripted second with `substeps` substeps per tick,
+    /// returning the position after each tick.
+    fn scripted_trajectory(substeps: u32) -> Vec<Vec2> {
+        let params = CarDynamicsParams {
+            rotation_speed: 4.0,
+            thrust: 1500.0,
+            drag: 0.985,
+        };
+        let mut script = ScriptedController::new(ScriptedControllerConfig::SineSteer {
+            amplitude: 0.8,
+            period_ticks: 40,
+            throttle: 1.0,
+        });
+        let mut state = CarKinematicState {
+            position: Vec2::ZERO,
+            velocity: Vec2::new(200.0, 0.0),
+            heading: 0.0,
+        };
+        (0..60)
+            .map(|tick| {
+                let action = script.action_at(tick);
+                step_car_dynamics_substepped(
+                    &mut state,
+                    action.steering,
+                    action.throttle,
+                    1.0 / 60.0,
+                    params,
+                    substeps,
+                );
+                state.position
+            })
+            .collect()
+    }
+
+    fn max_gap(a: &[Vec2], b: &[Vec2]) -> f32 {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| a.distance(*b))
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn one_substep_is_bit_identical_to_the_single_step() {
+        let params = CarDynamicsParams::default();
+        let mut single = CarKinematicState {
+            position: Vec2::new(3.0, -7.0),
+            velocity: Vec2::new(180.0, 25.0),
+            heading: 0.4,
+        };
+        let mut substepped = single;
+        for tick in 0..120 {
+            let steering = ((tick as f32) * 0.1).sin();
+            step_car_dynamics(&mut single, steering, 0.9, 1.0 / 60.0, params);
+            step_car_dynamics_substepped(&mut substepped, steering, 0.9, 1.0 / 60.0, params, 1);
+            assert_eq!(single, substepped);
+        }
+
+        let mut zero = CarKinematicState {
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            heading: 0.0,
+        };
+        let mut one = zero;
+        step_car_dynamics_substepped(&mut zero, 0.5, 1.0, 1.0 / 60.0, params, 0);
+        step_car_dynamics_substepped(&mut one, 0.5, 1.0, 1.0 / 60.0, params, 1);
+        assert_eq!(zero, one);
+    }
+
+    #[test]
+    fn substepped_trajectories_converge_as_substeps_increase() {
+        let trajectories: Vec<Vec<Vec2>> = [1, 2, 4, 8, 16]
+            .into_iter()
+            .map(scripted_trajectory)
+            .collect();
+        let travelled = trajectories[0][59].length();
+
+        // Each doubling moves the trajectory less than the one before.
+        let gaps: Vec<f32> = trajectories
+            .windows(2)
+            .map(|pair| max_gap(&pair[0], &pair[1]))
+            .collect();
+        for pair in gaps.windows(2) {
+            assert!(pair[1] < pair[0], "{gaps:?}");
+        }
+        // And every refinement stays close to the single-step path.
+        for trajectory in &trajectories[1..] {
+            let gap = max_gap(&trajectories[0], trajectory);
+            assert!(gap < 0.05 * travelled, "{gap} of {travelled}");
+        }
+    }
+
+    /// Drives straight east at full throttle along the oval's top row,
+    /// returning the car velocity and boost state after each tick.
+    fn drive_top_row(pad: bool, ticks: usize) -> Vec<(Vec2, BoostState)> {
+        use crate::agent::action::CarAction;
+        use crate::maps::centerline::{GridDir, TrackCenterline};
+        use crate::maps::grid::TrackGrid;
+        use crate::maps::parts::TilePart::*;
+        use std::time::Duration;
+
+        let third = if pad { BoostPadH } else { StraightH };
+        let grid = TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, third, StraightH, CornerNE],
+                vec![CornerSW, StraightH, StraightH, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 100.0),
+        );
+        let centerline =
+            TrackCenterline::build_closed_loop(&grid, (0, 1), GridDir::East).expect("oval loop");
+        let (spawn_position, spawn_rotation) = grid.find_spawn().expect("spawn tile");
+
+        let mut app = App::new();
+        app.init_resource::<Time<Fixed>>()
+            .insert_resource(ActionState {
+                applied: CarAction {
+                    steering: 0.0,
+                    throttle: 1.0,
+                },
+                ..ActionState::default()
+            })
+            .insert_resource(BoostPadConfig {
+                impulse: 120.0,
+                duration_ticks: 8,
+            })
+            .add_systems(FixedUpdate, car_physics_system);
+        app.world_mut().spawn(Track {
+            grid,
+            spawn_position,
+            spawn_rotation,
+            centerline: Some(centerline),
+        });
+        app.world_mut().spawn((
+            Transform::from_xyz(spawn_position.x, spawn_position.y, 10.0)
+                .with_rotation(Quat::from_rotation_z(spawn_rotation)),
+            Car::default(),
+        ));
+
+        (0..ticks)
+            .map(|_| {
+                app.world_mut()
+                    .resource_mut::<Time<Fixed>>()
+                    .advance_by(Duration::from_secs_f64(1.0 / 60.0));
+                app.world_mut().run_schedule(FixedUpdate);
+                let mut cars = app.world_mut().query::<&Car>();
+                let car = cars.single(app.world()).expect("one car");
+                (car.velocity, car.boost)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn crossing_a_boost_pad_adds_the_configured_impulse() {
+        let ticks = 60;
+        let boosted = drive_top_row(true, ticks);
+        let plain = drive_top_row(false, ticks);
+
+        let start = boosted
+            .iter()
+            .position(|(_, boost)| boost.ticks_remaining > 0)
+            .expect("car reaches the pad");
+        let end = start + 7;
+        assert_eq!(boosted[end].1.ticks_remaining, 0);
+        assert!(
+            plain
+                .iter()
+                .all(|(_, boost)| *boost == BoostState::default())
+        );
+        // Same inputs before the pad, so identical velocities.
+        assert_eq!(boosted[start - 1].0, plain[start - 1].0);
+
+        // Drag scales velocity linearly, so each 15 u/s tick of boost decays
+        // once per tick that follows it, including its own.
+        let drag = CarDynamicsParams::default().drag;
+        let expected: f32 = (1..=8).map(|k| 15.0 * drag.powi(k)).sum();
+        let gained = boosted[end].0 - plain[end].0;
+        assert!((gained.x - expected).abs() < 1e-3, "{gained} vs {expected}");
+        assert!(gained.y.abs() < 1e-3);
+
+        // Staying on the pad does not retrigger the boost.
+        assert!(
+            boosted[end + 1..]
+                .iter()
+                .all(|(_, boost)| boost.ticks_remaining == 0)
+        );
+    }
 }
@@ -1,12 +1,37 @@
+use crate::agent::observation::ObservationConfig;
 use crate::game::car::spawn_car;
-use crate::game::collision::{CollisionEvent, collision_detection_system};
+use crate::game::car_visual::{
+    CarVisualConfig, attach_car_visuals_system, draw_car_trail_system, record_car_trail_system,
+};
+use crate::game::checkpoints::setup_checkpoint_gates_system;
+use crate::game::collision::{
+    CarCollisionConfig, CarCollisionEvent, CollisionConfig, CollisionEvent, OffTrackWarning,
+    car_collision_system, collision_detection_system,
+};
+use crate::game::curriculum::{Curriculum, apply_initial_curriculum_system};
 use crate::game::episode::{
-    EpisodeConfig, EpisodeMovingAverages, EpisodeState, episode_loop_system,
+    EpisodeConfig, EpisodeEndedEvent, EpisodeMovingAverages, EpisodeResetRequest, EpisodeState,
+    episode_loop_system, manual_respawn_input_system, normalize_reward_to_track_system,
+    reset_crashed_cars_system,
 };
-use crate::game::physics::car_physics_system;
+use crate::game::instant_replay::{
+    InstantReplay, InstantReplayConfig, capture_crash_clip_system, instant_replay_hotkey_system,
+    instant_replay_playback_system, record_instant_replay_system,
+};
+use crate::game::physics::{BoostPadConfig, CarDynamicsParams, PhysicsConfig, car_physics_system};
 use crate::game::progress::update_track_progress_system;
+use crate::game::reward::RewardStack;
+use crate::game::seeding::{record_run_fingerprint_system, seed_episode_rng_system};
+use crate::game::snapshot::{SnapshotSlot, snapshot_hotkey_system};
+use crate::maps::raceline::setup_raceline_system;
 use crate::maps::track::Track;
+use crate::sim::clock::{SimClock, advance_sim_clock_system};
+use crate::sim::control::{
+    SimControl, begin_sim_tick_system, sim_control_input_system, sim_tick_active,
+};
+use crate::sim::rng::{EpisodeRng, RunSeeds};
 use crate::sim::sets::SimSet;
+use crate::sim::stats::{RunStats, log_run_stats_on_exit_system, update_run_stats_system};
 use bevy::prelude::*;
 
 /// Main game plugin that bundles all game systems.
@@ -15,10 +40,43 @@ pub struct GamePlugin;
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<CollisionEvent>()
+            .add_message::<OffTrackWarning>()
+            .add_message::<CarCollisionEvent>()
+            .add_message::<EpisodeEndedEvent>()
             .init_resource::<EpisodeConfig>()
             .init_resource::<EpisodeState>()
+            .init_resource::<EpisodeResetRequest>()
+            .init_resource::<Curriculum>()
+            .init_resource::<RewardStack>()
             .init_resource::<EpisodeMovingAverages>()
-            .add_systems(PostStartup, setup_game)
+            .init_resource::<SimControl>()
+            .init_resource::<SimClock>()
+            .init_resource::<RunStats>()
+            .init_resource::<SnapshotSlot>()
+            .init_resource::<InstantReplayConfig>()
+            .init_resource::<InstantReplay>()
+            .init_resource::<CarVisualConfig>()
+            .init_resource::<CarDynamicsParams>()
+            .init_resource::<BoostPadConfig>()
+            .init_resource::<PhysicsConfig>()
+            .init_resource::<CollisionConfig>()
+            .init_resource::<CarCollisionConfig>()
+            .init_resource::<RunSeeds>()
+            .init_resource::<EpisodeRng>()
+            .add_systems(
+                PostStartup,
+                (
+                    setup_game,
+                    setup_checkpoint_gates_system,
+                    setup_raceline_system,
+                    (
+                        normalize_reward_to_track_system,
+                        apply_initial_curriculum_system,
+                        record_run_fingerprint_system,
+                    )
+                        .chain(),
+                ),
+            )
             .configure_sets(
                 FixedUpdate,
                 (
@@ -26,6 +84,43 @@ impl Plugin for GamePlugin {
                     SimSet::Physics,
                     SimSet::Collision,
                     SimSet::Measurement,
+                )
+                    .chain()
+                    .run_if(sim_tick_active),
+            )
+            // Pause/step gating is decided once per fixed tick, before any set runs.
+            .add_systems(FixedUpdate, begin_sim_tick_system.before(SimSet::Input))
+            .add_systems(
+                FixedUpdate,
+                advance_sim_clock_system
+                    .after(begin_sim_tick_system)
+                    .before(SimSet::Input)
+                    .run_if(sim_tick_active),
+            )
+            .add_systems(
+                FixedUpdate,
+                seed_episode_rng_system
+                    .after(advance_sim_clock_system)
+                    .before(SimSet::Input)
+                    .run_if(sim_tick_active),
+            )
+            .add_systems(
+                Update,
+                (
+                    sim_control_input_system,
+                    update_run_stats_system,
+                    snapshot_hotkey_system,
+                    instant_replay_hotkey_system,
+                    manual_respawn_input_system,
+                ),
+            )
+            .add_systems(Last, log_run_stats_on_exit_system)
+            .add_systems(
+                Update,
+                (
+                    attach_car_visuals_system,
+                    record_car_trail_system,
+                    draw_car_trail_system,
                 )
                     .chain(),
             )
@@ -33,22 +128,37 @@ impl Plugin for GamePlugin {
             .add_systems(FixedUpdate, car_physics_system.in_set(SimSet::Physics))
             .add_systems(
                 FixedUpdate,
-                collision_detection_system.in_set(SimSet::Collision),
+                (
+                    collision_detection_system,
+                    car_collision_system,
+                    reset_crashed_cars_system,
+                )
+                    .chain()
+                    .in_set(SimSet::Collision),
             )
             .add_systems(
                 FixedUpdate,
                 (
                     update_track_progress_system,
+                    record_instant_replay_system,
                     episode_loop_system.after(update_track_progress_system),
+                    capture_crash_clip_system,
                 )
                     .chain()
                     .in_set(SimSet::Measurement),
-            );
+            )
+            // Ghost playback is visual only, so it also runs while paused.
+            .add_systems(FixedUpdate, instant_replay_playback_system);
     }
 }
 
 /// Initial game setup: camera and car spawn.
-fn setup_game(mut commands: Commands, track_query: Query<&Track>) {
+fn setup_game(
+    mut commands: Commands,
+    car_params: Res<CarDynamicsParams>,
+    observation_config: Res<ObservationConfig>,
+    track_query: Query<&Track>,
+) {
     // Spawn 2D camera
     commands.spawn(Camera2d::default());
 
@@ -58,7 +168,13 @@ fn setup_game(mut commands: Commands, track_query: Query<&Track>) {
             "Track ready. Spawning car at ({:.1}, {:.1}) rot {:.2}.",
             track.spawn_position.x, track.spawn_position.y, track.spawn_rotation
         );
-        spawn_car(&mut commands, track.spawn_position, track.spawn_rotation);
+        spawn_car(
+            &mut commands,
+            track.spawn_position,
+            track.spawn_rotation,
+            *car_params,
+            &observation_config,
+        );
     } else {
         warn!("No track found at startup. Car was not spawned.");
     }
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::game::car::Car;
+use crate::game::episode::EpisodeConfig;
+use crate::maps::centerline::CenterlineProjection;
 use crate::maps::track::Track;
 
 /// Continuous progress state along the track centreline.
@@ -8,7 +11,7 @@ use crate::maps::track::Track;
 /// This is an environment measurement (not an observation): it is used for
 /// telemetry, lap logic, and reward shaping, but should not be included in the
 /// agent observation vector.
-#[derive(Component, Clone, Copy, Debug)]
+#[derive(Component, Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct TrackProgress {
     /// Arc-length distance along the centreline from the start point.
     pub s: f32,
@@ -20,6 +23,16 @@ pub struct TrackProgress {
     pub tangent: Vec2,
     /// Euclidean distance from the car position to the centreline.
     pub distance: f32,
+    /// Net forward wraps across the start line this episode.
+    ///
+    /// Uses the same wrap thresholds as the episode lap logic but no sector
+    /// validation, so it counts laps driven rather than laps rewarded.
+    pub laps: u32,
+    /// Centreline distance driven this episode, in either direction.
+    pub total_distance: f32,
+    /// Backward wraps not yet cancelled by a forward wrap; these must be
+    /// undone before `laps` can increase again.
+    pub reverse_wraps: u32,
 }
 
 impl Default for TrackProgress {
@@ -30,27 +43,128 @@ impl Default for TrackProgress {
             closest_point: Vec2::ZERO,
             tangent: Vec2::X,
             distance: 0.0,
+            laps: 0,
+            total_distance: 0.0,
+            reverse_wraps: 0,
         }
     }
 }
 
-/// Updates the car's centreline projection and progress on the fixed tick.
+impl TrackProgress {
+    /// Folds a new centreline projection into the progress state, counting
+    /// start-line wraps and accumulating the distance driven.
+    pub fn advance(
+        &mut self,
+        projection: &CenterlineProjection,
+        track_length: f32,
+        wrap_from_fraction: f32,
+        wrap_to_fraction: f32,
+    ) {
+        let mut delta_s = projection.s - self.s;
+        if self.fraction >= wrap_from_fraction && projection.fraction <= wrap_to_fraction {
+            delta_s += track_length;
+            if self.reverse_wraps > 0 {
+                self.reverse_wraps -= 1;
+            } else {
+                self.laps = self.laps.saturating_add(1);
+            }
+        } else if self.fraction <= wrap_to_fraction && projection.fraction >= wrap_from_fraction {
+            delta_s -= track_length;
+            self.reverse_wraps = self.reverse_wraps.saturating_add(1);
+        }
+        self.total_distance += delta_s.abs();
+
+        self.set_projection(projection);
+    }
+
+    /// Overwrites the projection without accumulating distance or laps, e.g.
+    /// after a teleport.
+    pub fn set_projection(&mut self, projection: &CenterlineProjection) {
+        self.s = projection.s;
+        self.fraction = projection.fraction;
+        self.closest_point = projection.closest_point;
+        self.tangent = projection.tangent;
+        self.distance = projection.distance;
+    }
+}
+
+/// Updates every car's centreline projection, batched through
+/// [`crate::maps::centerline::TrackCenterline::project_many_near`], and
+/// progress on the fixed tick. Equidistant segments resolve towards each
+/// car's previous `s`.
 pub fn update_track_progress_system(
+    config: Res<EpisodeConfig>,
     track_query: Query<&Track>,
     mut car_query: Query<(&Transform, &mut TrackProgress), With<Car>>,
+    mut positions: Local<Vec<Vec2>>,
+    mut previous_s: Local<Vec<f32>>,
+    mut projections: Local<Vec<CenterlineProjection>>,
 ) {
-    let Ok(track) = track_query.single() else {
+    let Some(centerline) = track_query
+        .single()
+        .ok()
+        .and_then(|track| track.centerline.as_ref())
+    else {
         return;
     };
 
-    for (transform, mut progress) in car_query.iter_mut() {
-        let pos = Vec2::new(transform.translation.x, transform.translation.y);
-        let projection = track.centerline.project(pos);
+    positions.clear();
+    positions.extend(
+        car_query
+            .iter()
+            .map(|(transform, _)| transform.translation.truncate()),
+    );
+    previous_s.clear();
+    previous_s.extend(car_query.iter().map(|(_, progress)| progress.s));
+    projections.resize(positions.len(), CenterlineProjection::default());
+    centerline.project_many_near(&positions, &previous_s, &mut projections);
+
+    for ((_, mut progress), projection) in car_query.iter_mut().zip(projections.iter()) {
+        progress.advance(
+            projection,
+            centerline.total_length(),
+            config.lap_wrap_from_fraction,
+            config.lap_wrap_to_fraction,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackProgress;
+    use crate::game::episode::EpisodeConfig;
+    use crate::maps::fixtures::ring_centerline;
+
+    #[test]
+    fn two_laps_count_twice_and_accumulate_twice_the_length() {
+        let centerline = ring_centerline();
+        let length = centerline.total_length();
+        let config = EpisodeConfig::default();
+
+        let mut progress = TrackProgress::default();
+        progress.set_projection(&centerline.project(centerline.point_at_s(0.0)));
+
+        // Drive a few steps past 2L so the second wrap lands inside the wrap
+        // window; the overshoot stays within a few steps of 2L.
+        let step = 5.0;
+        let steps = (2.0 * length / step).ceil() as usize + 2;
+        for i in 1..=steps {
+            let projection = centerline.project(centerline.point_at_s(i as f32 * step));
+            progress.advance(
+                &projection,
+                length,
+                config.lap_wrap_from_fraction,
+                config.lap_wrap_to_fraction,
+            );
+        }
 
-        progress.s = projection.s;
-        progress.fraction = projection.fraction;
-        progress.closest_point = projection.closest_point;
-        progress.tangent = projection.tangent;
-        progress.distance = projection.distance;
+        assert_eq!(progress.laps, 2);
+        let driven = steps as f32 * step;
+        assert!(
+            (progress.total_distance - driven).abs() < 1.0,
+            "driven {driven}, tracked {}",
+            progress.total_distance
+        );
+        assert!((progress.total_distance - 2.0 * length).abs() <= 3.0 * step);
     }
 }
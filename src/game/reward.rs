@@ -0,0 +1,885 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::CarAction;
+use crate::game::episode::{EpisodeConfig, ProgressRewardMode};
+
+/// Per-tick inputs available to every reward term.
+#[derive(Clone, Copy, Debug)]
+pub struct RewardContext {
+    /// Positive gain in episode-best progress fraction this tick.
+    pub progress_gain: f32,
+    pub progress_fraction: f32,
+    pub progress_s: f32,
+    pub centerline_distance: f32,
+    /// Signed angle from the car's forward vector to the centreline tangent.
+    pub heading_error: f32,
+    pub velocity: Vec2,
+    pub speed: f32,
+    pub forward: Vec2,
+    pub tangent: Vec2,
+    /// Largest curvature magnitude (radians / world unit) among the car's
+    /// lookahead samples; `0.0` when the car has no sensors.
+    pub curvature_ahead: f32,
+    /// Action applied to the car this tick.
+    pub action: CarAction,
+    pub crashed: bool,
+    pub lap_complete: bool,
+    /// Checkpoint gates crossed in order this tick.
+    pub gates_crossed: u32,
+    /// Off-road this tick but still inside the crash grace window.
+    pub off_track_warning: bool,
+    /// Car-vs-car contacts involving this car this tick.
+    pub car_contacts: u32,
+    /// Distance from the car to the raceline; `None` without one.
+    pub raceline_distance: Option<f32>,
+    pub dt: f32,
+    /// Ticks elapsed in the current episode, including this one.
+    pub ticks_in_episode: u32,
+}
+
+/// How a term's value is bucketed in episode telemetry.
+///
+/// Shaping terms are subject to the per-tick reward cap; terminal terms
+/// (crash penalty, lap bonus) are paid once at an episode boundary and never
+/// clamped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RewardKind {
+    #[default]
+    Shaping,
+    Terminal,
+}
+
+/// One additive component of the per-tick reward.
+///
+/// Terms are evaluated in stack order every fixed tick. Stateful terms should
+/// clear their state in [`RewardTerm::reset`], which runs at every episode
+/// boundary.
+pub trait RewardTerm: Send + Sync + 'static {
+    /// Stable name used as the breakdown key.
+    fn name(&self) -> &str;
+
+    fn kind(&self) -> RewardKind {
+        RewardKind::Shaping
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32;
+
+    /// `false` when the term's configured weight is zero, so it can never
+    /// contribute. Inactive terms still appear in the breakdown; displays may
+    /// hide them.
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    fn reset(&mut self) {}
+
+    /// Reloads the term's weights from `config` after it changes at run time.
+    /// Terms without config-backed weights keep theirs.
+    fn apply_config(&mut self, _config: &EpisodeConfig) {}
+}
+
+/// Named per-term values for one tick (or accumulated over an episode).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RewardBreakdown {
+    pub terms: Vec<RewardBreakdownEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RewardBreakdownEntry {
+    pub name: String,
+    pub kind: RewardKind,
+    pub value: f32,
+}
+
+impl RewardBreakdown {
+    /// Sum of all terms.
+    pub fn total(&self) -> f32 {
+        self.terms.iter().map(|entry| entry.value).sum()
+    }
+
+    /// Sum of all terms of `kind`.
+    pub fn total_of(&self, kind: RewardKind) -> f32 {
+        self.terms
+            .iter()
+            .filter(|entry| entry.kind == kind)
+            .map(|entry| entry.value)
+            .sum()
+    }
+
+    /// Value recorded under `name`, or `0.0` if the term is absent.
+    pub fn get(&self, name: &str) -> f32 {
+        self.terms
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.value)
+            .unwrap_or(0.0)
+    }
+
+    /// Multiplies every term of `kind` by `factor`.
+    pub fn scale(&mut self, kind: RewardKind, factor: f32) {
+        for entry in self.terms.iter_mut().filter(|entry| entry.kind == kind) {
+            entry.value *= factor;
+        }
+    }
+
+    /// Adds `other` into this breakdown by name, appending unseen terms.
+    pub fn accumulate(&mut self, other: &RewardBreakdown) {
+        for entry in &other.terms {
+            match self.terms.iter_mut().find(|own| own.name == entry.name) {
+                Some(own) => own.value += entry.value,
+                None => self.terms.push(entry.clone()),
+            }
+        }
+    }
+}
+
+/// Ordered set of reward terms evaluated by the episode loop.
+///
+/// Built from [`EpisodeConfig`] at startup. Custom terms can be added by
+/// pushing into the resource before the app runs:
+///
+/// ```ignore
+/// app.world_mut().resource_mut::<RewardStack>().push(MyTerm::default());
+/// ```
+///
+/// Terms hold copies of their weights. When the config changes later (track
+/// normalization, curriculum steps), [`Self::apply_config`] updates them in
+/// place, so pushed terms stay in the stack.
+#[derive(Resource)]
+pub struct RewardStack {
+    terms: Vec<Box<dyn RewardTerm>>,
+}
+
+impl RewardStack {
+    /// Builds the standard term set for `config`.
+    pub fn from_config(config: &EpisodeConfig) -> Self {
+        let mut stack = Self { terms: Vec::new() };
+        if config.progress_mode != ProgressRewardMode::Gated {
+            stack.push(ProgressTerm {
+                scale: config.progress_reward_scale,
+            });
+        }
+        if config.progress_mode != ProgressRewardMode::Continuous {
+            stack.push(GateTerm {
+                reward_per_gate: config.checkpoint_gate_reward,
+            });
+        }
+        stack.push(TimePenaltyTerm {
+            per_tick: config.time_penalty_per_tick,
+        });
+        stack.push(HeadingSpeedPenaltyTerm {
+            scale: config.heading_speed_penalty_scale,
+            speed_norm_max: config.speed_norm_max_for_penalty,
+        });
+        stack.push(IdlePenaltyTerm {
+            penalty: config.idle_penalty_per_tick,
+            speed_threshold: config.idle_speed_threshold,
+            grace_ticks: config.idle_grace_ticks,
+            forward_speed: config.idle_uses_forward_speed,
+        });
+        if config.corner_smoothness_scale > 0.0 {
+            stack.push(CornerSmoothnessTerm::new(
+                config.corner_smoothness_scale,
+                config.corner_smoothness_accel_norm,
+                config.corner_smoothness_curvature_norm,
+            ));
+        }
+        if config.raceline_reward_scale > 0.0 {
+            stack.push(RacelineTerm {
+                scale: config.raceline_reward_scale,
+                distance_norm: config.raceline_distance_norm,
+            });
+        }
+        stack.push(OffTrackWarningTerm {
+            penalty: config.off_track_warning_penalty,
+        });
+        stack.push(CarContactTerm {
+            penalty: config.car_contact_penalty,
+        });
+        stack.push(CrashTerm {
+            penalty: config.crash_penalty,
+        });
+        stack.push(LapBonusTerm {
+            bonus: config.lap_bonus,
+        });
+        stack
+    }
+
+    pub fn push(&mut self, term: impl RewardTerm) {
+        self.terms.push(Box::new(term));
+    }
+
+    /// Updates every term's weights from `config`. The set of terms is the
+    /// one chosen when the stack was built.
+    pub fn apply_config(&mut self, config: &EpisodeConfig) {
+        for term in &mut self.terms {
+            term.apply_config(config);
+        }
+    }
+
+    /// Evaluates every term for one tick.
+    pub fn compute(&mut self, ctx: &RewardContext) -> RewardBreakdown {
+        RewardBreakdown {
+            terms: self
+                .terms
+                .iter_mut()
+                .map(|term| RewardBreakdownEntry {
+                    name: term.name().to_string(),
+                    kind: term.kind(),
+                    value: term.compute(ctx),
+                })
+                .collect(),
+        }
+    }
+
+    /// Names of the terms that can contribute, in stack order.
+    pub fn active_term_names(&self) -> Vec<&str> {
+        self.terms
+            .iter()
+            .filter(|term| term.is_active())
+            .map(|term| term.name())
+            .collect()
+    }
+
+    /// Clears per-episode state in every term.
+    pub fn reset(&mut self) {
+        for term in &mut self.terms {
+            term.reset();
+        }
+    }
+}
+
+impl FromWorld for RewardStack {
+    fn from_world(world: &mut World) -> Self {
+        let config = world
+            .get_resource::<EpisodeConfig>()
+            .copied()
+            .unwrap_or_default();
+        Self::from_config(&config)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Standard terms
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Dense reward for gains in episode-best centreline progress.
+pub struct ProgressTerm {
+    pub scale: f32,
+}
+
+impl RewardTerm for ProgressTerm {
+    fn name(&self) -> &str {
+        "progress"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        ctx.progress_gain * self.scale
+    }
+
+    fn is_active(&self) -> bool {
+        self.scale != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.scale = config.progress_reward_scale;
+    }
+}
+
+/// Sparse reward per checkpoint gate crossed in order.
+pub struct GateTerm {
+    pub reward_per_gate: f32,
+}
+
+impl RewardTerm for GateTerm {
+    fn name(&self) -> &str {
+        "gates"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        ctx.gates_crossed as f32 * self.reward_per_gate
+    }
+
+    fn is_active(&self) -> bool {
+        self.reward_per_gate != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.reward_per_gate = config.checkpoint_gate_reward;
+    }
+}
+
+/// Constant per-tick step cost.
+pub struct TimePenaltyTerm {
+    pub per_tick: f32,
+}
+
+impl RewardTerm for TimePenaltyTerm {
+    fn name(&self) -> &str {
+        "time"
+    }
+
+    fn compute(&mut self, _ctx: &RewardContext) -> f32 {
+        self.per_tick
+    }
+
+    fn is_active(&self) -> bool {
+        self.per_tick != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.per_tick = config.time_penalty_per_tick;
+    }
+}
+
+/// Penalty for driving fast while misaligned with the centreline.
+pub struct HeadingSpeedPenaltyTerm {
+    pub scale: f32,
+    pub speed_norm_max: f32,
+}
+
+impl RewardTerm for HeadingSpeedPenaltyTerm {
+    fn name(&self) -> &str {
+        "heading_speed"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        let heading_error_norm = (ctx.heading_error.abs() / PI).clamp(0.0, 1.0);
+        let speed_norm = (ctx.speed / self.speed_norm_max).clamp(0.0, 1.0);
+        -self.scale * heading_error_norm * speed_norm
+    }
+
+    fn is_active(&self) -> bool {
+        self.scale != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.scale = config.heading_speed_penalty_scale;
+        self.speed_norm_max = config.speed_norm_max_for_penalty;
+    }
+}
+
+/// Soft penalty while the car creeps below a speed threshold, after an
+/// initial grace window each episode.
+pub struct IdlePenaltyTerm {
+    pub penalty: f32,
+    pub speed_threshold: f32,
+    pub grace_ticks: u32,
+    /// Compare forward speed rather than scalar speed against the threshold.
+    pub forward_speed: bool,
+}
+
+impl RewardTerm for IdlePenaltyTerm {
+    fn name(&self) -> &str {
+        "idle"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        let speed = if self.forward_speed {
+            ctx.velocity.dot(ctx.forward)
+        } else {
+            ctx.speed
+        };
+        if ctx.ticks_in_episode > self.grace_ticks && speed < self.speed_threshold {
+            self.penalty
+        } else {
+            0.0
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.penalty != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.penalty = config.idle_penalty_per_tick;
+        self.speed_threshold = config.idle_speed_threshold;
+        self.grace_ticks = config.idle_grace_ticks;
+        self.forward_speed = config.idle_uses_forward_speed;
+    }
+}
+
+/// Penalty on abrupt longitudinal speed changes while a corner is ahead.
+///
+/// Each tick compares the car's longitudinal acceleration (forward speed
+/// change per second) with the previous tick's; the jerk is weighted by how
+/// sharp the curvature ahead is, so braking smoothly into a bend and feeding
+/// the throttle back on costs less than stamping on and off the pedals.
+/// Straights are free.
+pub struct CornerSmoothnessTerm {
+    pub scale: f32,
+    pub accel_norm: f32,
+    pub curvature_norm: f32,
+    previous_speed: Option<f32>,
+    previous_accel: Option<f32>,
+}
+
+impl CornerSmoothnessTerm {
+    pub fn new(scale: f32, accel_norm: f32, curvature_norm: f32) -> Self {
+        Self {
+            scale,
+            accel_norm,
+            curvature_norm,
+            previous_speed: None,
+            previous_accel: None,
+        }
+    }
+}
+
+impl RewardTerm for CornerSmoothnessTerm {
+    fn name(&self) -> &str {
+        "corner_smoothness"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        let speed = ctx.velocity.dot(ctx.forward);
+        let accel = match self.previous_speed.replace(speed) {
+            Some(previous) if ctx.dt > 0.0 => (speed - previous) / ctx.dt,
+            _ => return 0.0,
+        };
+        let Some(previous_accel) = self.previous_accel.replace(accel) else {
+            return 0.0;
+        };
+        let corner_weight =
+            (ctx.curvature_ahead / self.curvature_norm.max(f32::EPSILON)).clamp(0.0, 1.0);
+        -self.scale * corner_weight * (accel - previous_accel).abs() / self.accel_norm.max(1.0)
+    }
+
+    fn reset(&mut self) {
+        self.previous_speed = None;
+        self.previous_accel = None;
+    }
+
+    fn is_active(&self) -> bool {
+        self.scale != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.scale = config.corner_smoothness_scale;
+        self.accel_norm = config.corner_smoothness_accel_norm;
+        self.curvature_norm = config.corner_smoothness_curvature_norm;
+    }
+}
+
+/// Per-tick penalty on distance from the raceline, normalised by
+/// `distance_norm` and capped at `scale`, so policies are pulled onto the
+/// corner-cutting line rather than any on-road path. Free without a raceline.
+pub struct RacelineTerm {
+    pub scale: f32,
+    pub distance_norm: f32,
+}
+
+impl RewardTerm for RacelineTerm {
+    fn name(&self) -> &str {
+        "raceline"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        let Some(distance) = ctx.raceline_distance else {
+            return 0.0;
+        };
+        -self.scale * (distance / self.distance_norm.max(f32::EPSILON)).min(1.0)
+    }
+
+    fn is_active(&self) -> bool {
+        self.scale != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.scale = config.raceline_reward_scale;
+        self.distance_norm = config.raceline_distance_norm;
+    }
+}
+
+/// Small per-tick penalty while off-road inside the crash grace window.
+pub struct OffTrackWarningTerm {
+    pub penalty: f32,
+}
+
+impl RewardTerm for OffTrackWarningTerm {
+    fn name(&self) -> &str {
+        "off_track"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        if ctx.off_track_warning {
+            self.penalty
+        } else {
+            0.0
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.penalty != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.penalty = config.off_track_warning_penalty;
+    }
+}
+
+/// Penalty per car-vs-car contact this tick.
+pub struct CarContactTerm {
+    pub penalty: f32,
+}
+
+impl RewardTerm for CarContactTerm {
+    fn name(&self) -> &str {
+        "car_contact"
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        self.penalty * ctx.car_contacts as f32
+    }
+
+    fn is_active(&self) -> bool {
+        self.penalty != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.penalty = config.car_contact_penalty;
+    }
+}
+
+/// One-off penalty on the crash tick.
+pub struct CrashTerm {
+    pub penalty: f32,
+}
+
+impl RewardTerm for CrashTerm {
+    fn name(&self) -> &str {
+        "crash"
+    }
+
+    fn kind(&self) -> RewardKind {
+        RewardKind::Terminal
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        if ctx.crashed { self.penalty } else { 0.0 }
+    }
+
+    fn is_active(&self) -> bool {
+        self.penalty != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.penalty = config.crash_penalty;
+    }
+}
+
+/// One-off bonus on the lap-complete tick.
+pub struct LapBonusTerm {
+    pub bonus: f32,
+}
+
+impl RewardTerm for LapBonusTerm {
+    fn name(&self) -> &str {
+        "lap_bonus"
+    }
+
+    fn kind(&self) -> RewardKind {
+        RewardKind::Terminal
+    }
+
+    fn compute(&mut self, ctx: &RewardContext) -> f32 {
+        if ctx.lap_complete { self.bonus } else { 0.0 }
+    }
+
+    fn is_active(&self) -> bool {
+        self.bonus != 0.0
+    }
+
+    fn apply_config(&mut self, config: &EpisodeConfig) {
+        self.bonus = config.lap_bonus;
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_context() -> RewardContext {
+    RewardContext {
+        progress_gain: 0.0,
+        progress_fraction: 0.0,
+        progress_s: 0.0,
+        centerline_distance: 0.0,
+        heading_error: 0.0,
+        velocity: Vec2::ZERO,
+        speed: 0.0,
+        forward: Vec2::X,
+        tangent: Vec2::X,
+        curvature_ahead: 0.0,
+        action: CarAction::default(),
+        crashed: false,
+        lap_complete: false,
+        gates_crossed: 0,
+        off_track_warning: false,
+        car_contacts: 0,
+        raceline_distance: None,
+        dt: 1.0 / 60.0,
+        ticks_in_episode: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CornerSmoothnessTerm, IdlePenaltyTerm, RacelineTerm, RewardContext, RewardKind,
+        RewardStack, RewardTerm, test_context,
+    };
+    use crate::game::episode::EpisodeConfig;
+    use bevy::prelude::Vec2;
+
+    struct Constant(&'static str, f32);
+
+    impl RewardTerm for Constant {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn compute(&mut self, _ctx: &RewardContext) -> f32 {
+            self.1
+        }
+    }
+
+    #[test]
+    fn stack_of_constant_terms_sums_and_keys_breakdown() {
+        let mut stack = RewardStack { terms: Vec::new() };
+        stack.push(Constant("alpha", 1.5));
+        stack.push(Constant("beta", -0.25));
+
+        let breakdown = stack.compute(&test_context());
+
+        assert_eq!(breakdown.total(), 1.25);
+        let names: Vec<&str> = breakdown.terms.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "beta"]);
+        assert_eq!(breakdown.get("alpha"), 1.5);
+        assert_eq!(breakdown.get("beta"), -0.25);
+    }
+
+    #[test]
+    fn following_the_raceline_through_a_corner_beats_the_centreline() {
+        let track = crate::maps::monaco::build_monaco_track();
+        let centerline = track.centerline.as_ref().expect("centreline");
+        let raceline = crate::maps::raceline::build_raceline(centerline, 30.0).line;
+
+        // The first point where the raceline has cut well inside the centre.
+        let total = centerline.total_length();
+        let corner_s = (0..total as usize)
+            .step_by(5)
+            .map(|s| s as f32)
+            .find(|&s| raceline.project(centerline.point_at_s(s)).distance > 20.0)
+            .expect("the raceline cuts some corner");
+
+        let config = EpisodeConfig {
+            raceline_reward_scale: 0.05,
+            ..EpisodeConfig::default()
+        };
+        let mut stack = RewardStack::from_config(&config);
+        let mut drive = |positions: &mut dyn Iterator<Item = Vec2>| -> f32 {
+            positions
+                .map(|position| {
+                    stack
+                        .compute(&RewardContext {
+                            raceline_distance: Some(raceline.project(position).distance),
+                            ..test_context()
+                        })
+                        .get("raceline")
+                })
+                .sum()
+        };
+        let corner = || (-20..=20).map(|step| corner_s + step as f32 * 5.0);
+        let on_centreline = drive(&mut corner().map(|s| centerline.point_at_s(s)));
+        let on_raceline = drive(&mut corner().map(|s| {
+            let nearest = raceline.project(centerline.point_at_s(s)).s;
+            raceline.point_at_s(nearest)
+        }));
+
+        assert!(on_centreline < -0.1, "{on_centreline}");
+        assert!(on_raceline > on_centreline + 0.1, "{on_raceline}");
+        assert!(on_raceline > -1e-3, "{on_raceline}");
+
+        // The term is capped and absent without a raceline.
+        let mut term = RacelineTerm {
+            scale: 0.05,
+            distance_norm: 40.0,
+        };
+        let far = RewardContext {
+            raceline_distance: Some(400.0),
+            ..test_context()
+        };
+        assert_eq!(term.compute(&far), -0.05);
+        assert_eq!(term.compute(&test_context()), 0.0);
+        assert_eq!(
+            RewardStack::from_config(&EpisodeConfig::default())
+                .compute(&far)
+                .get("raceline"),
+            0.0
+        );
+    }
+
+    #[test]
+    fn default_stack_pays_crash_as_terminal() {
+        let config = EpisodeConfig::default();
+        let mut stack = RewardStack::from_config(&config);
+        let ctx = RewardContext {
+            crashed: true,
+            ..test_context()
+        };
+
+        let breakdown = stack.compute(&ctx);
+
+        assert_eq!(
+            breakdown.total_of(RewardKind::Terminal),
+            config.crash_penalty
+        );
+        assert_eq!(breakdown.get("time"), config.time_penalty_per_tick);
+    }
+
+    #[test]
+    fn idle_penalty_respects_the_grace_window_boundary() {
+        let mut term = IdlePenaltyTerm {
+            penalty: -0.01,
+            speed_threshold: 40.0,
+            grace_ticks: 90,
+            forward_speed: false,
+        };
+        let stationary = |tick| RewardContext {
+            speed: 0.0,
+            ticks_in_episode: tick,
+            ..test_context()
+        };
+
+        assert_eq!(term.compute(&stationary(1)), 0.0);
+        assert_eq!(term.compute(&stationary(90)), 0.0);
+        assert_eq!(term.compute(&stationary(91)), -0.01);
+    }
+
+    #[test]
+    fn full_speed_run_accrues_no_idle_penalty() {
+        let mut term = IdlePenaltyTerm {
+            penalty: -0.01,
+            speed_threshold: 40.0,
+            grace_ticks: 90,
+            forward_speed: false,
+        };
+
+        let total: f32 = (1..=1800)
+            .map(|tick| {
+                term.compute(&RewardContext {
+                    speed: 800.0,
+                    ticks_in_episode: tick,
+                    ..test_context()
+                })
+            })
+            .sum();
+
+        assert_eq!(total, 0.0);
+    }
+
+    #[test]
+    fn forward_speed_floor_penalises_crawling_and_reversing_only() {
+        let config = EpisodeConfig {
+            idle_penalty_per_tick: -0.01,
+            idle_uses_forward_speed: true,
+            ..EpisodeConfig::default()
+        };
+        let mut stack = RewardStack::from_config(&config);
+        let mut idle_over_run = |velocity: Vec2| -> f32 {
+            stack.reset();
+            (1..=300)
+                .map(|tick| {
+                    stack
+                        .compute(&RewardContext {
+                            velocity,
+                            speed: velocity.length(),
+                            ticks_in_episode: tick,
+                            ..test_context()
+                        })
+                        .get("idle")
+                })
+                .sum()
+        };
+
+        let crawling = idle_over_run(Vec2::new(5.0, 0.0));
+        let cruising = idle_over_run(Vec2::new(200.0, 0.0));
+        let reversing = idle_over_run(Vec2::new(-200.0, 0.0));
+
+        let penalised_ticks = (300 - config.idle_grace_ticks) as f32;
+        assert!((crawling - penalised_ticks * -0.01).abs() < 1e-4);
+        assert_eq!(cruising, 0.0);
+        assert_eq!(reversing, crawling);
+    }
+
+    #[test]
+    fn smooth_speed_through_a_corner_beats_hard_on_off_throttle() {
+        let config = EpisodeConfig {
+            corner_smoothness_scale: 0.05,
+            ..EpisodeConfig::default()
+        };
+        let mut stack = RewardStack::from_config(&config);
+        let dt = 1.0 / 60.0;
+        let corner_ticks = 120;
+        let mut run_return = |speeds: &[f32]| -> f32 {
+            stack.reset();
+            speeds
+                .iter()
+                .enumerate()
+                .map(|(tick, &speed)| {
+                    stack
+                        .compute(&RewardContext {
+                            // Identical progress every tick in both runs.
+                            progress_gain: 0.001,
+                            velocity: Vec2::new(speed, 0.0),
+                            speed,
+                            curvature_ahead: 0.03,
+                            dt,
+                            ticks_in_episode: tick as u32 + 1,
+                            ..test_context()
+                        })
+                        .total()
+                })
+                .sum()
+        };
+
+        // 600 -> 300 -> 600 units/s through the corner either way.
+        let smooth: Vec<f32> = (0..corner_ticks)
+            .map(|tick| {
+                let phase = tick as f32 / (corner_ticks - 1) as f32;
+                450.0 + 150.0 * (2.0 * std::f32::consts::PI * phase).cos()
+            })
+            .collect();
+        let harsh: Vec<f32> = (0..corner_ticks)
+            .map(|tick| match tick {
+                0..20 => 600.0,
+                20..35 => 600.0 - 20.0 * (tick - 19) as f32,
+                35..85 => 300.0,
+                85..100 => 300.0 + 20.0 * (tick - 84) as f32,
+                _ => 600.0,
+            })
+            .collect();
+
+        let smooth_return = run_return(&smooth);
+        let harsh_return = run_return(&harsh);
+        assert!(
+            smooth_return > harsh_return,
+            "smooth {smooth_return} should beat harsh {harsh_return}"
+        );
+
+        // On a straight the term is silent and the runs tie.
+        let mut straight = CornerSmoothnessTerm::new(0.05, 1000.0, 0.02);
+        let penalty: f32 = harsh
+            .iter()
+            .map(|&speed| {
+                straight.compute(&RewardContext {
+                    velocity: Vec2::new(speed, 0.0),
+                    dt,
+                    ..test_context()
+                })
+            })
+            .sum();
+        assert_eq!(penalty, 0.0);
+    }
+}
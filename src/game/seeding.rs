@@ -0,0 +1,269 @@
+use bevy::app::AppExit;
+use bevy::ecs::message::MessageWriter;
+use bevy::prelude::*;
+
+use crate::agent::action::{ActionMode, ActionSmoothing};
+use crate::agent::exploration::ExplorationConfig;
+use crate::agent::observation::ObservationConfig;
+use crate::game::collision::{CarCollisionConfig, CollisionConfig};
+use crate::game::curriculum::Curriculum;
+use crate::game::episode::{EpisodeConfig, EpisodeState};
+use crate::game::physics::{BoostPadConfig, CarDynamicsParams, PhysicsConfig};
+use crate::maps::grid::TrackGrid;
+use crate::maps::track::Track;
+use crate::sim::evaluation::EvaluationMode;
+use crate::sim::rng::{EpisodeRng, RunSeeds, fnv1a64, hash_debug};
+
+/// Checksum of a track's layout: tiles, tile size, origin, and edge mode.
+pub fn track_checksum(grid: &TrackGrid) -> u64 {
+    fnv1a64(
+        format!(
+            "{:?}|{:?}|{:?}|{:?}",
+            grid.tiles, grid.tile_size, grid.origin, grid.edge
+        )
+        .as_bytes(),
+    )
+}
+
+/// Agent-side settings hashed into the run fingerprint; absent without the
+/// agent plugin.
+type AgentConfigs<'w> = (
+    Option<Res<'w, ObservationConfig>>,
+    Option<Res<'w, ActionSmoothing>>,
+    Option<Res<'w, ActionMode>>,
+    Option<Res<'w, ExplorationConfig>>,
+    Option<Res<'w, EvaluationMode>>,
+);
+
+/// Car, integration, and collision settings hashed into the run fingerprint.
+type SimConfigs<'w> = (
+    Res<'w, CarDynamicsParams>,
+    Res<'w, PhysicsConfig>,
+    Res<'w, BoostPadConfig>,
+    Res<'w, CollisionConfig>,
+    Res<'w, CarCollisionConfig>,
+);
+
+/// Records the config hash and track checksum once the track exists.
+///
+/// The hash covers every resource that changes how the simulation runs,
+/// after track normalization and the first curriculum step.
+pub fn record_run_fingerprint_system(
+    mut seeds: ResMut<RunSeeds>,
+    episode: (Res<EpisodeConfig>, Res<Curriculum>),
+    agent: AgentConfigs,
+    sim: SimConfigs,
+    track_query: Query<&Track>,
+) {
+    let (episode_config, curriculum) = episode;
+    let (observation_config, smoothing, action_mode, exploration, evaluation) = agent;
+    let (car, physics, boost, collision, car_collision) = sim;
+    seeds.config_hash = hash_debug(&(
+        (*episode_config, *curriculum),
+        (
+            observation_config.as_deref().cloned(),
+            smoothing.as_deref().copied(),
+            action_mode.as_deref().copied(),
+            exploration.as_deref().copied(),
+            evaluation.as_deref().copied(),
+        ),
+        (*car, *physics, *boost, *collision, *car_collision),
+    ));
+    if let Ok(track) = track_query.single() {
+        seeds.track_checksum = track_checksum(&track.grid);
+    }
+
+    info!(
+        "Run seeds: master {:#018x}, config hash {:#018x}, track checksum {:#018x}{}.",
+        seeds.master_seed,
+        seeds.config_hash,
+        seeds.track_checksum,
+        match seeds.replay_episode_seed {
+            Some(seed) => format!(", replaying episode seed {seed:#018x}"),
+            None => String::new(),
+        }
+    );
+    if let Some(noise) = observation_config
+        .as_deref()
+        .map(|config| config.noise)
+        .filter(|noise| noise.is_enabled())
+    {
+        info!(
+            "Observation noise: ray sigma {}, speed sigma {}, heading sigma {}, yaw-rate sigma {}.",
+            noise.ray_distance, noise.speed, noise.heading_error, noise.yaw_rate
+        );
+    }
+}
+
+/// Reseeds [`EpisodeRng`] whenever a new episode begins.
+///
+/// In replay mode the single replayed episode runs with the recorded seed and
+/// the app exits as soon as it ends.
+pub fn seed_episode_rng_system(
+    seeds: Res<RunSeeds>,
+    episode_state: Res<EpisodeState>,
+    mut episode_rng: ResMut<EpisodeRng>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let episode = episode_state.current_episode;
+    if episode_rng.episode == episode {
+        return;
+    }
+
+    if seeds.replay_episode_seed.is_some() && episode > 1 {
+        info!("Replayed episode finished; exiting.");
+        exit.write(AppExit::Success);
+        return;
+    }
+
+    let seed = seeds.seed_for_episode(episode);
+    *episode_rng = EpisodeRng::from_seed(episode, seed);
+    info!("Episode {} seed {:#018x}.", episode, seed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_run_fingerprint_system, seed_episode_rng_system, track_checksum};
+    use crate::game::car::Car;
+    use crate::game::collision::{CarCollisionConfig, CollisionConfig};
+    use crate::game::curriculum::Curriculum;
+    use crate::game::episode::{EpisodeConfig, EpisodeState};
+    use crate::game::physics::{
+        BoostPadConfig, CarDynamicsParams, CarKinematicState, PhysicsConfig, step_car_dynamics,
+    };
+    use crate::maps::fixtures::ring_grid;
+    use crate::maps::grid::GridEdge;
+    use crate::maps::parts::TilePart;
+    use crate::sim::rng::{EpisodeRng, RunSeeds};
+    use bevy::app::AppExit;
+    use bevy::prelude::*;
+
+    /// Car positions of the current episode, driven by a noisy policy drawn
+    /// from the episode's exploration stream.
+    #[derive(Resource, Default)]
+    struct Trajectory {
+        state: Option<CarKinematicState>,
+        positions: Vec<Vec2>,
+    }
+
+    fn drive_from_exploration_system(
+        mut rng: ResMut<EpisodeRng>,
+        mut trajectory: ResMut<Trajectory>,
+    ) {
+        let car = Car::default();
+        let params = CarDynamicsParams {
+            rotation_speed: car.rotation_speed,
+            thrust: car.thrust,
+            drag: car.drag,
+        };
+        let steering = rng.exploration.normal(0.0, 0.5);
+        let throttle = rng.exploration.next_f32();
+        let state = trajectory.state.get_or_insert(CarKinematicState {
+            position: Vec2::new(0.0, 100.0),
+            velocity: Vec2::ZERO,
+            heading: 0.0,
+        });
+        step_car_dynamics(state, steering, throttle, 1.0 / 60.0, params);
+        let position = state.position;
+        trajectory.positions.push(position);
+    }
+
+    fn seeded_app(seeds: RunSeeds) -> App {
+        let mut app = App::new();
+        app.insert_resource(seeds)
+            .init_resource::<EpisodeState>()
+            .insert_resource(EpisodeRng::from_seed(0, 0))
+            .init_resource::<Trajectory>()
+            .add_message::<AppExit>()
+            .add_systems(
+                Update,
+                (seed_episode_rng_system, drive_from_exploration_system).chain(),
+            );
+        app
+    }
+
+    /// Runs `episode` for `ticks` updates; returns its logged seed and the
+    /// car positions.
+    fn run_episode(app: &mut App, episode: u32, ticks: usize) -> (u64, Vec<Vec2>) {
+        app.world_mut()
+            .resource_mut::<EpisodeState>()
+            .current_episode = episode;
+        app.insert_resource(Trajectory::default());
+        for _ in 0..ticks {
+            app.update();
+        }
+        let seed = app.world().resource::<EpisodeRng>().seed;
+        (
+            seed,
+            app.world_mut()
+                .resource_mut::<Trajectory>()
+                .positions
+                .split_off(0),
+        )
+    }
+
+    #[test]
+    fn replaying_a_recorded_seed_in_a_fresh_app_reproduces_the_trajectory() {
+        let mut run = seeded_app(RunSeeds {
+            master_seed: 12345,
+            ..RunSeeds::default()
+        });
+        let mut episodes: Vec<(u64, Vec<Vec2>)> = (1..=5)
+            .map(|episode| run_episode(&mut run, episode, 300))
+            .collect();
+        let (other_seed, other) = episodes.pop().expect("five episodes");
+        let (recorded_seed, original) = episodes.pop().expect("five episodes");
+        drop(run);
+
+        let mut replay = seeded_app(RunSeeds {
+            replay_episode_seed: Some(recorded_seed),
+            ..RunSeeds::default()
+        });
+        let (replayed_seed, replayed) = run_episode(&mut replay, 1, 300);
+
+        assert_eq!(replayed_seed, recorded_seed);
+        assert_eq!(replayed, original);
+        assert_ne!(other_seed, recorded_seed);
+        assert_ne!(other, original);
+    }
+
+    #[test]
+    fn track_checksum_changes_with_layout() {
+        let grid = ring_grid();
+        let mut edited = ring_grid();
+        edited.tiles[2][1] = TilePart::Crossroads;
+
+        assert_eq!(track_checksum(&grid), track_checksum(&ring_grid()));
+        assert_ne!(track_checksum(&grid), track_checksum(&edited));
+
+        let mut open = ring_grid();
+        open.edge = GridEdge::Open;
+        assert_ne!(track_checksum(&grid), track_checksum(&open));
+    }
+
+    fn config_hash_with(physics: PhysicsConfig) -> u64 {
+        let mut app = App::new();
+        app.init_resource::<RunSeeds>()
+            .init_resource::<EpisodeConfig>()
+            .init_resource::<Curriculum>()
+            .init_resource::<CarDynamicsParams>()
+            .insert_resource(physics)
+            .init_resource::<BoostPadConfig>()
+            .init_resource::<CollisionConfig>()
+            .init_resource::<CarCollisionConfig>()
+            .add_systems(Update, record_run_fingerprint_system);
+        app.update();
+        app.world().resource::<RunSeeds>().config_hash
+    }
+
+    #[test]
+    fn config_hash_changes_with_physics_substeps() {
+        let physics = PhysicsConfig::default();
+        let finer = PhysicsConfig {
+            physics_substeps: physics.physics_substeps + 1,
+        };
+
+        assert_eq!(config_hash_with(physics), config_hash_with(physics));
+        assert_ne!(config_hash_with(physics), config_hash_with(finer));
+    }
+}
@@ -0,0 +1,427 @@
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::agent::action::ActionState;
+use crate::agent::exploration::ExplorationNoise;
+use crate::agent::observation::{ObservationDelayBuffer, SensorReadings};
+use crate::game::car::Car;
+use crate::game::checkpoints::CheckpointProgress;
+use crate::game::collision::OffTrackState;
+use crate::game::episode::{EpisodeState, LapValidation};
+use crate::game::physics::BoostState;
+use crate::game::progress::TrackProgress;
+use crate::sim::clock::SimClock;
+use crate::sim::rng::EpisodeRng;
+
+/// Where F5 additionally writes the quick-save for offline inspection.
+const QUICKSAVE_PATH: &str = "reports/snapshot_quicksave.ron";
+
+/// Complete deterministic simulation state at a fixed-tick boundary.
+///
+/// Covered state, and nothing else:
+/// - [`SimClock`] (global tick and simulated time);
+/// - the car's position, heading, and velocity;
+/// - the car's [`TrackProgress`], [`CheckpointProgress`], [`LapValidation`],
+///   and [`OffTrackState`];
+/// - the car's [`SensorReadings`], including the rays and occupancy patch
+///   held between decimated casts and their age, and its
+///   [`ObservationDelayBuffer`] when it has one;
+/// - [`ActionState`];
+/// - [`EpisodeState`];
+/// - [`EpisodeRng`];
+/// - [`ExplorationNoise`] (the noise offset and its draw stream) when the
+///   resource exists.
+///
+/// Not covered: controller and brain state (A2C weights, optimiser moments,
+/// rollout buffers, its sampling stream), internal state of custom
+/// stateful reward terms, analytics accumulators, HUD history, and
+/// `SimControl`/`RunStats`. Restoring mid-run therefore reproduces the
+/// environment exactly under a replayed action stream, not a learning agent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimSnapshot {
+    pub clock: SimClock,
+    pub car: CarSnapshot,
+    pub action: ActionState,
+    pub episode: EpisodeState,
+    pub episode_rng: EpisodeRng,
+    #[serde(default)]
+    pub exploration: Option<ExplorationNoise>,
+}
+
+/// Per-car portion of a [`SimSnapshot`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CarSnapshot {
+    pub position: Vec2,
+    pub heading: f32,
+    pub velocity: Vec2,
+    #[serde(default)]
+    pub boost: BoostState,
+    pub progress: TrackProgress,
+    pub checkpoints: CheckpointProgress,
+    pub lap_validation: LapValidation,
+    pub off_track: OffTrackState,
+    pub sensors: SensorReadings,
+    #[serde(default)]
+    pub observation_delay: Option<ObservationDelayBuffer>,
+}
+
+/// In-memory quick-save slot used by the F5/F8 debug keys.
+#[derive(Resource, Default)]
+pub struct SnapshotSlot {
+    pub snapshot: Option<SimSnapshot>,
+}
+
+/// Captures the covered state. Returns `None` unless exactly one car exists
+/// and every covered resource is present.
+pub fn capture_snapshot(world: &mut World) -> Option<SimSnapshot> {
+    let mut cars = world.query::<(
+        &Transform,
+        &Car,
+        &TrackProgress,
+        &CheckpointProgress,
+        &LapValidation,
+        &OffTrackState,
+        &SensorReadings,
+        Option<&ObservationDelayBuffer>,
+    )>();
+    let (transform, car, progress, checkpoints, lap_validation, off_track, sensors, delay) =
+        cars.single(world).ok()?;
+
+    let forward = (transform.rotation * Vec3::X).truncate();
+    let car = CarSnapshot {
+        position: transform.translation.truncate(),
+        heading: forward.y.atan2(forward.x),
+        velocity: car.velocity,
+        boost: car.boost,
+        progress: *progress,
+        checkpoints: *checkpoints,
+        lap_validation: *lap_validation,
+        off_track: *off_track,
+        sensors: sensors.clone(),
+        observation_delay: delay.cloned(),
+    };
+
+    Some(SimSnapshot {
+        clock: *world.get_resource::<SimClock>()?,
+        car,
+        action: *world.get_resource::<ActionState>()?,
+        episode: world.get_resource::<EpisodeState>()?.clone(),
+        episode_rng: world.get_resource::<EpisodeRng>()?.clone(),
+        exploration: world.get_resource::<ExplorationNoise>().cloned(),
+    })
+}
+
+/// Restores the covered state. Returns `false` (and changes nothing) unless
+/// exactly one car exists.
+pub fn restore_snapshot(world: &mut World, snapshot: &SimSnapshot) -> bool {
+    let mut cars = world.query::<(
+        &mut Transform,
+        &mut Car,
+        &mut TrackProgress,
+        &mut CheckpointProgress,
+        &mut LapValidation,
+        &mut OffTrackState,
+        &mut SensorReadings,
+        Option<&mut ObservationDelayBuffer>,
+    )>();
+    let Ok((
+        mut transform,
+        mut car,
+        mut progress,
+        mut checkpoints,
+        mut lap_validation,
+        mut off_track,
+        mut sensors,
+        delay,
+    )) = cars.single_mut(world)
+    else {
+        return false;
+    };
+
+    let saved = &snapshot.car;
+    transform.translation.x = saved.position.x;
+    transform.translation.y = saved.position.y;
+    transform.rotation = Quat::from_rotation_z(saved.heading);
+    car.velocity = saved.velocity;
+    car.boost = saved.boost;
+    *progress = saved.progress;
+    *checkpoints = saved.checkpoints;
+    *lap_validation = saved.lap_validation;
+    *off_track = saved.off_track;
+    sensors.clone_from(&saved.sensors);
+    if let (Some(mut delay), Some(saved)) = (delay, &saved.observation_delay) {
+        delay.clone_from(saved);
+    }
+
+    world.insert_resource(snapshot.clock);
+    world.insert_resource(snapshot.action);
+    world.insert_resource(snapshot.episode.clone());
+    world.insert_resource(snapshot.episode_rng.clone());
+    if let Some(exploration) = &snapshot.exploration {
+        world.insert_resource(exploration.clone());
+    }
+    true
+}
+
+/// Debug keys: F5 captures a snapshot into [`SnapshotSlot`] (and writes it
+/// as RON under `reports/`), F8 restores the last capture.
+pub fn snapshot_hotkey_system(world: &mut World) {
+    let Some(keyboard) = world.get_resource::<ButtonInput<KeyCode>>() else {
+        return;
+    };
+    let save = keyboard.just_pressed(KeyCode::F5);
+    let load = keyboard.just_pressed(KeyCode::F8);
+
+    if save {
+        match capture_snapshot(world) {
+            Some(snapshot) => {
+                info!("Snapshot saved at tick {}.", snapshot.clock.tick);
+                write_snapshot_file(&snapshot, Path::new(QUICKSAVE_PATH));
+                world.resource_mut::<SnapshotSlot>().snapshot = Some(snapshot);
+            }
+            None => warn!("Snapshot not saved: simulation state incomplete."),
+        }
+    }
+
+    if load {
+        let Some(snapshot) = world.resource::<SnapshotSlot>().snapshot.clone() else {
+            info!("No snapshot saved yet (F5 to save).");
+            return;
+        };
+        if restore_snapshot(world, &snapshot) {
+            info!("Snapshot restored to tick {}.", snapshot.clock.tick);
+        } else {
+            warn!("Snapshot not restored: no single car to restore into.");
+        }
+    }
+}
+
+fn write_snapshot_file(snapshot: &SimSnapshot, path: &Path) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    match ron::ser::to_string_pretty(snapshot, ron::ser::PrettyConfig::default()) {
+        Ok(text) => {
+            if let Err(err) = fs::write(path, text) {
+                warn!("Failed to write {}: {}", path.display(), err);
+            }
+        }
+        Err(err) => warn!("Failed to serialize snapshot: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{capture_snapshot, restore_snapshot};
+    use crate::agent::action::{ActionState, CarAction};
+    use crate::agent::exploration::{
+        ExplorationConfig, ExplorationNoise, ExplorationNoiseKind, NoiseChannel,
+    };
+    use crate::agent::observation::{
+        ObservationConfig, ObservationDelayBuffer, ObservationVector, SensorReadings,
+        apply_observation_delay_system, build_observation_vector_system,
+        update_sensor_readings_system,
+    };
+    use crate::agent::scripted::{ScriptedController, ScriptedControllerConfig};
+    use crate::game::car::Car;
+    use crate::game::checkpoints::CheckpointProgress;
+    use crate::game::collision::{
+        CarCollisionEvent, CollisionConfig, CollisionEvent, OffTrackState, OffTrackWarning,
+        collision_detection_system,
+    };
+    use crate::game::episode::{
+        EpisodeConfig, EpisodeEndedEvent, EpisodeMovingAverages, EpisodeState, LapValidation,
+        episode_loop_system,
+    };
+    use crate::game::physics::car_physics_system;
+    use crate::game::progress::{TrackProgress, update_track_progress_system};
+    use crate::game::reward::RewardStack;
+    use crate::maps::fixtures::ring_track;
+    use crate::sim::clock::{SimClock, advance_sim_clock_system};
+    use crate::sim::rng::EpisodeRng;
+    use bevy::prelude::*;
+
+    fn ring_app(observation: ObservationConfig) -> App {
+        let track = ring_track();
+        let (spawn_position, spawn_rotation) = (track.spawn_position, track.spawn_rotation);
+
+        let mut app = App::new();
+        app.init_resource::<Time<Fixed>>()
+            .init_resource::<SimClock>()
+            .init_resource::<ActionState>()
+            .init_resource::<EpisodeConfig>()
+            .init_resource::<EpisodeState>()
+            .init_resource::<EpisodeMovingAverages>()
+            .init_resource::<RewardStack>()
+            .init_resource::<EpisodeRng>()
+            .init_resource::<CollisionConfig>()
+            .insert_resource(observation.clone())
+            .add_message::<CollisionEvent>()
+            .add_message::<OffTrackWarning>()
+            .add_message::<CarCollisionEvent>()
+            .add_message::<EpisodeEndedEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    advance_sim_clock_system,
+                    car_physics_system,
+                    collision_detection_system,
+                    update_track_progress_system,
+                    episode_loop_system,
+                    update_sensor_readings_system,
+                    build_observation_vector_system,
+                    apply_observation_delay_system,
+                )
+                    .chain(),
+            );
+        app.world_mut().spawn(track);
+        app.world_mut().spawn((
+            Transform::from_xyz(spawn_position.x, spawn_position.y, 10.0)
+                .with_rotation(Quat::from_rotation_z(spawn_rotation)),
+            Car::default(),
+            TrackProgress::default(),
+            CheckpointProgress::default(),
+            LapValidation::default(),
+            OffTrackState::default(),
+            SensorReadings::for_config(&observation),
+            ObservationVector::for_config(&observation),
+            ObservationDelayBuffer::default(),
+        ));
+        app
+    }
+
+    /// Runs `ticks` fixed updates under a deterministic scripted action
+    /// stream keyed on the global tick, returning the car pose and the
+    /// observation per tick.
+    fn run(app: &mut App, ticks: usize) -> Vec<(Vec2, f32, Vec2, Vec<f32>)> {
+        let mut script = ScriptedController::new(ScriptedControllerConfig::SineSteer {
+            amplitude: 0.6,
+            period_ticks: 90,
+            throttle: 0.7,
+        });
+        let mut trajectory = Vec::with_capacity(ticks);
+        for _ in 0..ticks {
+            let tick = app.world().resource::<SimClock>().tick;
+            app.world_mut().resource_mut::<ActionState>().applied = script.action_at(tick as u32);
+            app.world_mut()
+                .resource_mut::<Time<Fixed>>()
+                .advance_by(Duration::from_secs_f64(1.0 / 60.0));
+            app.world_mut().run_schedule(FixedUpdate);
+
+            let mut cars = app
+                .world_mut()
+                .query::<(&Transform, &Car, &ObservationVector)>();
+            let (transform, car, observation) = cars.single(app.world()).expect("one car");
+            let forward = (transform.rotation * Vec3::X).truncate();
+            trajectory.push((
+                transform.translation.truncate(),
+                forward.y.atan2(forward.x),
+                car.velocity,
+                observation.values.clone(),
+            ));
+        }
+        trajectory
+    }
+
+    #[test]
+    fn restoring_a_snapshot_replays_the_same_trajectory() {
+        let mut app = ring_app(ObservationConfig::default());
+
+        // Drive into the first corner before capturing.
+        run(&mut app, 40);
+        let snapshot = capture_snapshot(app.world_mut()).expect("complete state");
+        let first = run(&mut app, 100);
+        let episode_after_first = app.world().resource::<EpisodeState>().current_episode;
+
+        assert!(restore_snapshot(app.world_mut(), &snapshot));
+        assert_eq!(app.world().resource::<SimClock>().tick, snapshot.clock.tick);
+        let second = run(&mut app, 100);
+
+        assert_eq!(first, second);
+        assert_eq!(
+            app.world().resource::<EpisodeState>().current_episode,
+            episode_after_first
+        );
+
+        let text = ron::ser::to_string(&snapshot).expect("serializable");
+        let parsed: super::SimSnapshot = ron::from_str(&text).expect("round trip");
+        assert_eq!(parsed.clock, snapshot.clock);
+    }
+
+    #[test]
+    fn decimated_rays_and_delayed_observations_restore_mid_interval() {
+        let mut app = ring_app(ObservationConfig {
+            sensor_update_interval: 3,
+            observation_delay_ticks: 2,
+            ..ObservationConfig::default()
+        });
+
+        // Capture between casts, with a full delay buffer.
+        run(&mut app, 40);
+        let mut snapshot = capture_snapshot(app.world_mut()).expect("complete state");
+        while snapshot.car.sensors.ray_age_ticks == Some(0) {
+            run(&mut app, 1);
+            snapshot = capture_snapshot(app.world_mut()).expect("complete state");
+        }
+        assert!(snapshot.car.sensors.ray_age_ticks.is_some());
+        assert!(snapshot.car.observation_delay.is_some());
+        let first = run(&mut app, 60);
+
+        assert!(restore_snapshot(app.world_mut(), &snapshot));
+        assert_eq!(run(&mut app, 60), first);
+
+        // Without the held rays and buffered observations the replay drifts.
+        let mut stripped = snapshot.clone();
+        stripped.car.sensors.ray_age_ticks = None;
+        stripped.car.observation_delay = Some(ObservationDelayBuffer::default());
+        assert!(restore_snapshot(app.world_mut(), &stripped));
+        assert_ne!(run(&mut app, 60), first);
+
+        let text = ron::ser::to_string(&snapshot).expect("serializable");
+        let parsed: super::SimSnapshot = ron::from_str(&text).expect("round trip");
+        assert_eq!(
+            parsed.car.sensors.ray_distances,
+            snapshot.car.sensors.ray_distances
+        );
+    }
+
+    #[test]
+    fn exploration_noise_state_restores_with_the_snapshot() {
+        let mut app = ring_app(ObservationConfig::default());
+        let config = ExplorationConfig {
+            kind: ExplorationNoiseKind::OrnsteinUhlenbeck,
+            steering: NoiseChannel {
+                sigma: 0.3,
+                theta: 1.0,
+            },
+            throttle: NoiseChannel {
+                sigma: 0.2,
+                theta: 1.0,
+            },
+        };
+        app.insert_resource(ExplorationNoise::default());
+        let sample = |app: &mut App, ticks: usize| -> Vec<CarAction> {
+            (0..ticks)
+                .map(|_| {
+                    app.world_mut()
+                        .resource_mut::<ExplorationNoise>()
+                        .sample(&config, 1.0 / 60.0)
+                })
+                .collect()
+        };
+
+        // Capture with a non-zero offset part-way through the draw stream.
+        sample(&mut app, 10);
+        let snapshot = capture_snapshot(app.world_mut()).expect("complete state");
+        assert!(snapshot.exploration.is_some());
+        let first = sample(&mut app, 20);
+
+        assert!(restore_snapshot(app.world_mut(), &snapshot));
+        assert_eq!(sample(&mut app, 20), first);
+    }
+}
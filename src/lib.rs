@@ -0,0 +1,19 @@
+//! NeuroDrive: a tile-based 2D racing simulation for training driving agents.
+//!
+//! The binary (`src/main.rs`) wires these modules into the windowed game and
+//! the headless commands. The library exposes the same modules so other
+//! front ends, such as the optional Python bindings (feature `python`), can
+//! drive the headless environment directly.
+
+pub mod agent;
+pub mod analytics;
+pub mod brain;
+pub mod config;
+pub mod debug;
+pub mod eval;
+pub mod game;
+pub mod maps;
+pub mod sim;
+
+#[cfg(feature = "python")]
+mod python;
@@ -1,22 +1,157 @@
-mod agent;
-mod analytics;
-mod brain;
-mod debug;
-mod game;
-mod maps;
-mod sim;
-
-use agent::AgentPlugin;
-use analytics::plugin::AnalyticsPlugin;
 use bevy::prelude::*;
 use bevy::time::Fixed;
-use brain::plugin::BrainPlugin;
-use debug::DebugPlugin;
-use game::GamePlugin;
-use maps::MonacoPlugin;
+use neurodrive::agent::AgentPlugin;
+use neurodrive::agent::vision::VisionPlugin;
+use neurodrive::analytics::plugin::AnalyticsPlugin;
+use neurodrive::brain::neuroevolution::{DEFAULT_EVOLVE_GENERATIONS, run_evolve_command};
+use neurodrive::brain::plugin::BrainPlugin;
+use neurodrive::brain::types::AgentMode;
+use neurodrive::config::{AppConfig, DEFAULT_CONFIG_PATH};
+use neurodrive::debug::DebugPlugin;
+use neurodrive::eval::remote::ExternalControlPlugin;
+use neurodrive::eval::tournament::{
+    DEFAULT_EPISODES_PER_TRACK, TournamentPolicy, TournamentSettings,
+};
+use neurodrive::eval::websocket::WebSocketPlugin;
+use neurodrive::game::GamePlugin;
+use neurodrive::maps::MonacoPlugin;
+use neurodrive::sim::rng::RunSeeds;
+use neurodrive::{agent, eval, maps};
 
 fn main() {
-    App::new()
+    // `neurodrive maps <dir>` validates track files headlessly and exits.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("maps") {
+        let dir = args.get(2).map(String::as_str).unwrap_or("assets/tracks");
+        let failures = maps::loader::run_maps_command(std::path::Path::new(dir));
+        std::process::exit(if failures == 0 { 0 } else { 1 });
+    }
+
+    let config_path = flag_str(&args, "--config").unwrap_or(DEFAULT_CONFIG_PATH);
+    let mut config = match AppConfig::load_or_default(std::path::Path::new(config_path)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("failed to load {config_path}: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    // `--eval` marks an evaluation run: no observation or exploration noise.
+    if args.iter().any(|arg| arg == "--eval") {
+        config.evaluation.enabled = true;
+    }
+
+    // `neurodrive config [path]` writes the effective config and exits.
+    if args.get(1).map(String::as_str) == Some("config") {
+        let out = args
+            .get(2)
+            .filter(|arg| !arg.starts_with("--"))
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_CONFIG_PATH);
+        match config.save(std::path::Path::new(out)) {
+            Ok(()) => println!("wrote {out}"),
+            Err(err) => {
+                eprintln!("failed to write {out}: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // `--print-spaces` dumps the observation and action spaces as JSON.
+    if args.iter().any(|arg| arg == "--print-spaces") {
+        let mut spaces = agent::spaces::EnvSpaces::new(&config.observation, config.action_mode);
+        spaces.action = spaces
+            .action
+            .with_rate_limits(config.action_smoothing.rate_limits);
+        match spaces.to_json() {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("failed to serialize spaces: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // `--evaluate-all [episodes]` runs the headless cross-track tournament
+    // and exits. Seeds default to 0 so reports compare across versions.
+    // `--policy <a2c|mlp|onnx>` (default a2c) and `--weights <path>` pick
+    // the policy under test.
+    if let Some(index) = args.iter().position(|arg| arg == "--evaluate-all") {
+        let policy = match TournamentPolicy::from_args(
+            flag_str(&args, "--policy").unwrap_or("a2c"),
+            flag_str(&args, "--weights"),
+            &config,
+        ) {
+            Ok(policy) => policy,
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        };
+        let settings = TournamentSettings {
+            master_seed: flag_value(&args, "--seed").unwrap_or(0),
+            episodes_per_track: args
+                .get(index + 1)
+                .and_then(|arg| arg.parse().ok())
+                .unwrap_or(DEFAULT_EPISODES_PER_TRACK),
+        };
+        let dir = flag_str(&args, "--tracks").unwrap_or("assets/tracks");
+        let ok = eval::tournament::run_evaluate_all_command(
+            std::path::Path::new(dir),
+            &config,
+            settings,
+            &policy,
+        );
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
+    // `--serve-external [port]` serves headless external-control sessions
+    // on Sepang until killed.
+    if let Some(index) = args.iter().position(|arg| arg == "--serve-external") {
+        if let Some(port) = args.get(index + 1).and_then(|arg| arg.parse().ok()) {
+            config.external.port = port;
+        }
+        let ok = eval::remote::run_serve_external_command(
+            &config,
+            flag_value(&args, "--seed").unwrap_or(0),
+        );
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
+    // `--evolve [generations]` runs headless neuroevolution on Sepang and
+    // exits, saving to the configured `neuroevolution.save_dir`.
+    if let Some(index) = args.iter().position(|arg| arg == "--evolve") {
+        let generations = args
+            .get(index + 1)
+            .and_then(|arg| arg.parse().ok())
+            .unwrap_or(DEFAULT_EVOLVE_GENERATIONS);
+        run_evolve_command(
+            &config,
+            flag_value(&args, "--seed").unwrap_or(0),
+            generations,
+        );
+        return;
+    }
+
+    let mut seeds = RunSeeds::default();
+    if let Some(seed) = flag_value(&args, "--seed") {
+        seeds.master_seed = seed;
+    }
+    seeds.replay_episode_seed = flag_value(&args, "--replay-episode");
+
+    // `--controller <name>` overrides the configured starting controller.
+    if let Some(name) = flag_str(&args, "--controller") {
+        match AgentMode::from_name(name) {
+            Some(mode) => config.controller = mode,
+            None => eprintln!("ignoring --controller: unknown controller '{name}'"),
+        }
+    }
+
+    let mut app = App::new();
+    config.insert_into(&mut app);
+    app.insert_resource(seeds)
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "NeuroDrive".to_string(),
@@ -33,6 +168,33 @@ fn main() {
         .add_plugins(BrainPlugin)
         .add_plugins(AnalyticsPlugin)
         .add_plugins(GamePlugin)
+        .add_plugins(ExternalControlPlugin)
+        .add_plugins(WebSocketPlugin)
         .add_plugins(DebugPlugin)
+        .add_plugins(VisionPlugin)
         .run();
 }
+
+/// Returns the argument following `--flag`, if any.
+fn flag_str<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Parses `--flag <u64>` (decimal or `0x` hex) from the command line.
+fn flag_value(args: &[String], flag: &str) -> Option<u64> {
+    let raw = flag_str(args, flag)?;
+    let parsed = match raw.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => raw.parse(),
+    };
+    match parsed {
+        Ok(value) => Some(value),
+        Err(_) => {
+            eprintln!("ignoring {flag}: '{raw}' is not a valid u64");
+            None
+        }
+    }
+}
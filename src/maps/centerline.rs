@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use bevy::tasks::ComputeTaskPool;
+use serde::{Deserialize, Serialize};
 
 use crate::maps::grid::TrackGrid;
 use crate::maps::parts::TilePart;
@@ -40,9 +42,39 @@ impl GridDir {
     }
 }
 
+/// Which way a lap runs around the loop, relative to the spawn tile.
+///
+/// Reversing the direction reverses the centreline's point order, so
+/// tangents, arc-length progress, and lap wraps all follow the new direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LapDirection {
+    /// Leave the spawn tile heading east.
+    #[default]
+    Forward,
+    /// Leave the spawn tile heading west.
+    Reverse,
+}
+
+impl LapDirection {
+    /// Direction the centreline traversal leaves the spawn tile in.
+    pub fn start_dir(self) -> GridDir {
+        match self {
+            LapDirection::Forward => GridDir::East,
+            LapDirection::Reverse => GridDir::West,
+        }
+    }
+
+    /// Spawn heading in radians matching [`Self::start_dir`].
+    pub fn spawn_rotation(self) -> f32 {
+        match self {
+            LapDirection::Forward => 0.0,
+            LapDirection::Reverse => std::f32::consts::PI,
+        }
+    }
+}
+
 /// Errors that can occur while constructing a centreline from a tile grid.
 #[derive(Clone, Debug)]
-#[allow(dead_code)]
 pub enum CenterlineBuildError {
     /// The start cell was out of bounds or not a road tile.
     InvalidStartCell { row: usize, col: usize },
@@ -54,6 +86,14 @@ pub enum CenterlineBuildError {
         col: usize,
         options: Vec<GridDir>,
     },
+    /// A route turn named a direction the junction does not offer.
+    InvalidRouteTurn {
+        row: usize,
+        col: usize,
+        dir: GridDir,
+    },
+    /// The loop closed before every route turn was taken.
+    UnusedRouteTurns(usize),
     /// The traversal failed to close back to the start.
     NotClosedLoop,
     /// The computed loop was too short to be meaningful.
@@ -70,6 +110,8 @@ pub struct TrackCenterline {
     pub points: Vec<Vec2>,
     cumulative_lengths: Vec<f32>,
     total_length: f32,
+    /// Flat segment geometry for batch projection, built with the points.
+    segment_table: SegmentTable,
 }
 
 impl TrackCenterline {
@@ -150,7 +192,8 @@ impl TrackCenterline {
     ///
     /// This assumes a single closed loop (degree-2 track). If junction tiles
     /// exist (T-junctions or crossroads), the builder will reject ambiguous
-    /// branches rather than guessing.
+    /// branches rather than guessing; [`Self::build_with_route`] takes them
+    /// along an explicit route.
     ///
     /// The resulting centreline follows the midline of each tile:
     /// - Straights are line segments between open-edge midpoints.
@@ -160,28 +203,121 @@ impl TrackCenterline {
         start_cell: (usize, usize),
         start_dir: GridDir,
     ) -> Result<Self, CenterlineBuildError> {
-        let (cells, dirs) = traverse_cells(grid, start_cell, start_dir)?;
+        Self::build_with_route(grid, start_cell, start_dir, &[])
+    }
+
+    /// Like [`Self::build_closed_loop`], but takes junctions in order: each
+    /// time the traversal reaches a tile with more than one way on, it leaves
+    /// in the next direction of `route`. A junction may be crossed more than
+    /// once (a figure-8 passes its crossroads twice), but never left the same
+    /// way twice. [`suggest_routes`] lists the routes a grid allows.
+    pub fn build_with_route(
+        grid: &TrackGrid,
+        start_cell: (usize, usize),
+        start_dir: GridDir,
+        route: &[GridDir],
+    ) -> Result<Self, CenterlineBuildError> {
+        let (cells, dirs) = traverse_cells(grid, start_cell, start_dir, route)?;
         let points = build_polyline_points(grid, &cells, &dirs);
         if points.len() < 3 {
             return Err(CenterlineBuildError::TooShort);
         }
 
+        Ok(Self::from_polyline(points))
+    }
+
+    /// Builds a closed polyline through `points`, in order, closing back to
+    /// the first. Used for lines derived from a centreline, like the raceline.
+    pub fn from_points(points: Vec<Vec2>) -> Result<Self, CenterlineBuildError> {
+        if points.len() < 3 {
+            return Err(CenterlineBuildError::TooShort);
+        }
+        Ok(Self::from_polyline(points))
+    }
+
+    fn from_polyline(points: Vec<Vec2>) -> Self {
         let (cumulative_lengths, total_length) = compute_lengths(&points);
-        Ok(Self {
+        let segment_table = SegmentTable::new(&points, &cumulative_lengths);
+        Self {
             points,
             cumulative_lengths,
             total_length,
+            segment_table,
+        }
+    }
+
+    /// Enumerates the polyline segments in traversal order, including the
+    /// closing segment back to the first point.
+    ///
+    /// Curvature is estimated from the adjacent segments: the turn from the
+    /// previous segment's tangent to the next one's, divided by the distance
+    /// between their midpoints. Interior arc segments report `1 / radius`;
+    /// segments between two straights report zero.
+    pub fn segments(&self) -> impl Iterator<Item = SegmentInfo> + '_ {
+        let n = self.points.len();
+        let segment = move |i: usize| (self.points[i], self.points[(i + 1) % n]);
+        let heading = move |i: usize| {
+            let (a, b) = segment(i);
+            (b - a).y.atan2((b - a).x)
+        };
+        let length = move |i: usize| {
+            let (a, b) = segment(i);
+            a.distance(b)
+        };
+
+        (0..n).map(move |i| {
+            let (prev, next) = ((i + n - 1) % n, (i + 1) % n);
+            let span = 0.5 * length(prev) + length(i) + 0.5 * length(next);
+            let turn = wrap_to_pi(heading(next) - heading(prev));
+            let (start, end) = segment(i);
+            SegmentInfo {
+                start,
+                end,
+                length: length(i),
+                start_s: self.cumulative_lengths[i],
+                curvature: if span > 1e-6 { turn / span } else { 0.0 },
+            }
         })
     }
 
     /// Projects a world position onto the centreline polyline.
     ///
     /// Returns the closest point on the polyline, its segment tangent, and the
-    /// arc-length progress `s` along the track in `[0, total_length)`.
+    /// arc-length progress `s` along the track in `[0, total_length)`. Ties
+    /// go to the lowest `s`; see [`Self::project_near`].
     pub fn project(&self, world: Vec2) -> CenterlineProjection {
+        self.project_near(world, None)
+    }
+
+    /// Like [`Self::project`], but breaks ties towards `previous_s`.
+    ///
+    /// Segments whose distance is within [`PROJECTION_TIE_TOLERANCE`] of the
+    /// closest count as equally close. Among them the one whose projected `s`
+    /// is nearest `previous_s` around the loop wins, so progress does not jump
+    /// across a near-crossing or a symmetric point when the distances are
+    /// level. Without a previous projection the lowest `s` wins. Remaining
+    /// ties keep the earlier segment.
+    pub fn project_near(&self, world: Vec2, previous_s: Option<f32>) -> CenterlineProjection {
         let n = self.points.len();
         debug_assert!(n >= 2, "centreline must have at least two points");
 
+        let closest = |i: usize| {
+            let a = self.points[i];
+            let d = self.points[(i + 1) % n] - a;
+            let len2 = d.length_squared();
+            if len2 <= 1e-8 {
+                return None;
+            }
+            let t = ((world - a).dot(d) / len2).clamp(0.0, 1.0);
+            let p = a + d * t;
+            Some((a, d, t, world.distance(p)))
+        };
+
+        let min_distance = (0..n)
+            .filter_map(closest)
+            .map(|(_, _, _, dist)| dist)
+            .fold(f32::INFINITY, f32::min);
+
         let mut best = CenterlineProjection {
             closest_point: self.points[0],
             tangent: Vec2::X,
@@ -189,25 +325,23 @@ impl TrackCenterline {
             fraction: 0.0,
             distance: f32::INFINITY,
         };
+        let mut best_key = f32::INFINITY;
 
         for i in 0..n {
-            let a = self.points[i];
-            let b = self.points[(i + 1) % n];
-            let d = b - a;
-            let len2 = d.length_squared();
-            if len2 <= 1e-8 {
+            let Some((a, d, t, dist)) = closest(i) else {
+                continue;
+            };
+            if dist > min_distance + PROJECTION_TIE_TOLERANCE {
                 continue;
             }
 
-            let t = ((world - a).dot(d) / len2).clamp(0.0, 1.0);
-            let p = a + d * t;
-            let dist = world.distance(p);
-
-            if dist < best.distance {
-                let seg_len = len2.sqrt();
-                let s = self.cumulative_lengths[i] + seg_len * t;
+            let seg_len = d.length();
+            let s = self.cumulative_lengths[i] + seg_len * t;
+            let key = self.tie_key(s, previous_s);
+            if key < best_key {
+                best_key = key;
                 best = CenterlineProjection {
-                    closest_point: p,
+                    closest_point: a + d * t,
                     tangent: d / seg_len,
                     s,
                     fraction: (s / self.total_length).clamp(0.0, 1.0),
@@ -218,10 +352,207 @@ impl TrackCenterline {
 
         best
     }
+
+    /// Ordering among equally close candidates: loop distance from
+    /// `previous_s` when there is one, otherwise `s` itself.
+    fn tie_key(&self, s: f32, previous_s: Option<f32>) -> f32 {
+        match previous_s {
+            Some(previous) => {
+                let gap = (s - previous).rem_euclid(self.total_length);
+                gap.min(self.total_length - gap)
+            }
+            None => s,
+        }
+    }
+
+    /// Projects every point in `points` into the matching slot of `out`.
+    ///
+    /// Equivalent to [`Self::project`] per point, but the segment geometry is
+    /// laid out in flat arrays once, when the centreline is built, and the
+    /// per-point scan only compares squared distances, which the compiler can
+    /// vectorise. Batches
+    /// of at least [`PARALLEL_PROJECTION_MIN_POINTS`] are split across the
+    /// compute task pool when one is running.
+    pub fn project_many(&self, points: &[Vec2], out: &mut [CenterlineProjection]) {
+        self.project_batch(points, None, out);
+    }
+
+    /// [`Self::project_many`] with a previous arc length per point, breaking
+    /// ties as [`Self::project_near`] does.
+    pub fn project_many_near(
+        &self,
+        points: &[Vec2],
+        previous_s: &[f32],
+        out: &mut [CenterlineProjection],
+    ) {
+        assert_eq!(points.len(), previous_s.len(), "one previous s per point");
+        self.project_batch(points, Some(previous_s), out);
+    }
+
+    fn project_batch(
+        &self,
+        points: &[Vec2],
+        previous_s: Option<&[f32]>,
+        out: &mut [CenterlineProjection],
+    ) {
+        assert_eq!(points.len(), out.len(), "one output slot per point");
+
+        let pool = ComputeTaskPool::try_get()
+            .filter(|pool| pool.thread_num() > 1 && points.len() >= PARALLEL_PROJECTION_MIN_POINTS);
+        let Some(pool) = pool else {
+            self.project_into(points, previous_s, out);
+            return;
+        };
+        let chunk = points.len().div_ceil(pool.thread_num());
+        pool.scope(|scope| {
+            let chunks = points.chunks(chunk).zip(out.chunks_mut(chunk));
+            for (index, (points, out)) in chunks.enumerate() {
+                let start = index * chunk;
+                let previous_s = previous_s.map(|s| &s[start..start + points.len()]);
+                scope.spawn(async move { self.project_into(points, previous_s, out) });
+            }
+        });
+    }
+
+    fn project_into(
+        &self,
+        points: &[Vec2],
+        previous_s: Option<&[f32]>,
+        out: &mut [CenterlineProjection],
+    ) {
+        for (i, (world, slot)) in points.iter().zip(out).enumerate() {
+            *slot = self
+                .segment_table
+                .project(self, *world, previous_s.map(|s| s[i]));
+        }
+    }
 }
 
-/// Result of projecting a point onto a centreline.
+/// Distance, in world units, within which two centreline segments count as
+/// equally close to a point; see [`TrackCenterline::project_near`].
+pub const PROJECTION_TIE_TOLERANCE: f32 = 1e-3;
+
+/// Batch size from which [`TrackCenterline::project_many`] parallelises.
+pub const PARALLEL_PROJECTION_MIN_POINTS: usize = 256;
+
+/// Non-degenerate centreline segments as flat arrays, built once with the
+/// [`TrackCenterline`] and shared by every [`TrackCenterline::project_many`]
+/// batch.
+#[derive(Clone, Debug, Default)]
+struct SegmentTable {
+    start_x: Vec<f32>,
+    start_y: Vec<f32>,
+    delta_x: Vec<f32>,
+    delta_y: Vec<f32>,
+    len2: Vec<f32>,
+    /// Arc length at each segment's start.
+    start_s: Vec<f32>,
+}
+
+impl SegmentTable {
+    fn new(points: &[Vec2], cumulative_lengths: &[f32]) -> Self {
+        let n = points.len();
+        let mut table = Self {
+            start_x: Vec::with_capacity(n),
+            start_y: Vec::with_capacity(n),
+            delta_x: Vec::with_capacity(n),
+            delta_y: Vec::with_capacity(n),
+            len2: Vec::with_capacity(n),
+            start_s: Vec::with_capacity(n),
+        };
+        for i in 0..n {
+            let a = points[i];
+            let d = points[(i + 1) % n] - a;
+            let len2 = d.length_squared();
+            if len2 <= 1e-8 {
+                continue;
+            }
+            table.start_x.push(a.x);
+            table.start_y.push(a.y);
+            table.delta_x.push(d.x);
+            table.delta_y.push(d.y);
+            table.len2.push(len2);
+            table.start_s.push(cumulative_lengths[i]);
+        }
+        table
+    }
+
+    fn closest(&self, k: usize, world: Vec2) -> (f32, f32) {
+        let (ax, ay) = (self.start_x[k], self.start_y[k]);
+        let (dx, dy) = (self.delta_x[k], self.delta_y[k]);
+        let t = (((world.x - ax) * dx + (world.y - ay) * dy) / self.len2[k]).clamp(0.0, 1.0);
+        let (ex, ey) = (world.x - (ax + dx * t), world.y - (ay + dy * t));
+        (t, ex * ex + ey * ey)
+    }
+
+    /// Two passes: the closest squared distance first, then the tie-break of
+    /// [`TrackCenterline::project_near`] among segments within tolerance.
+    fn project(
+        &self,
+        centerline: &TrackCenterline,
+        world: Vec2,
+        previous_s: Option<f32>,
+    ) -> CenterlineProjection {
+        let min_dist2 = (0..self.len2.len())
+            .map(|k| self.closest(k, world).1)
+            .fold(f32::INFINITY, f32::min);
+        let limit = min_dist2.sqrt() + PROJECTION_TIE_TOLERANCE;
+        let limit2 = limit * limit;
+
+        let mut best_key = f32::INFINITY;
+        let mut best = None;
+        for k in 0..self.len2.len() {
+            let (t, dist2) = self.closest(k, world);
+            if dist2 > limit2 {
+                continue;
+            }
+            let s = self.start_s[k] + self.len2[k].sqrt() * t;
+            let key = centerline.tie_key(s, previous_s);
+            if key < best_key {
+                best_key = key;
+                best = Some((k, t));
+            }
+        }
+
+        let Some((k, t)) = best else {
+            return CenterlineProjection {
+                closest_point: centerline.points[0],
+                tangent: Vec2::X,
+                s: 0.0,
+                fraction: 0.0,
+                distance: f32::INFINITY,
+            };
+        };
+        let a = Vec2::new(self.start_x[k], self.start_y[k]);
+        let d = Vec2::new(self.delta_x[k], self.delta_y[k]);
+        let p = a + d * t;
+        let seg_len = self.len2[k].sqrt();
+        let s = self.start_s[k] + seg_len * t;
+        CenterlineProjection {
+            closest_point: p,
+            tangent: d / seg_len,
+            s,
+            fraction: (s / centerline.total_length).clamp(0.0, 1.0),
+            distance: world.distance(p),
+        }
+    }
+}
+
+/// One polyline segment of a centreline, as yielded by
+/// [`TrackCenterline::segments`].
 #[derive(Clone, Copy, Debug)]
+pub struct SegmentInfo {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub length: f32,
+    /// Arc length at `start`.
+    pub start_s: f32,
+    /// Signed curvature in radians / world-unit; positive turns left.
+    pub curvature: f32,
+}
+
+/// Result of projecting a point onto a centreline.
+#[derive(Clone, Copy, Debug, Default)]
 pub struct CenterlineProjection {
     /// Closest point on the centreline polyline.
     pub closest_point: Vec2,
@@ -235,10 +566,62 @@ pub struct CenterlineProjection {
     pub distance: f32,
 }
 
+/// A closed route through a grid's junctions, as found by
+/// [`suggest_routes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuggestedRoute {
+    /// Turns for [`TrackCenterline::build_with_route`].
+    pub turns: Vec<GridDir>,
+    /// Cells in traversal order from the start cell; junctions crossed more
+    /// than once appear once per pass.
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl SuggestedRoute {
+    /// Distinct road cells the route drives over.
+    pub fn coverage(&self) -> usize {
+        self.cells
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    }
+}
+
+/// Enumerates every closed route from `start_cell` leaving in `start_dir`
+/// that never reverses, never revisits a non-junction cell, and never leaves
+/// a junction the same way twice. Routes covering the most cells come first,
+/// so a full traversal of the grid (every road cell, each crossroads crossed
+/// twice in a figure-8) leads when one exists.
+pub fn suggest_routes(
+    grid: &TrackGrid,
+    start_cell: (usize, usize),
+    start_dir: GridDir,
+) -> Vec<SuggestedRoute> {
+    let mut routes = Vec::new();
+    let mut pending = vec![Vec::new()];
+    while let Some(turns) = pending.pop() {
+        match traverse_cells(grid, start_cell, start_dir, &turns) {
+            Ok((cells, _)) => routes.push(SuggestedRoute { turns, cells }),
+            Err(CenterlineBuildError::AmbiguousBranch { options, .. }) => {
+                // Reversed so the first option is explored first.
+                for dir in options.into_iter().rev() {
+                    let mut longer = turns.clone();
+                    longer.push(dir);
+                    pending.push(longer);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    routes.sort_by_key(|route| std::cmp::Reverse(route.coverage()));
+    routes
+}
+
 fn traverse_cells(
     grid: &TrackGrid,
     start_cell: (usize, usize),
     start_dir: GridDir,
+    route: &[GridDir],
 ) -> Result<(Vec<(usize, usize)>, Vec<GridDir>), CenterlineBuildError> {
     let (start_row, start_col) = start_cell;
     if grid.tile_at(start_row, start_col) == TilePart::Empty {
@@ -253,6 +636,8 @@ fn traverse_cells(
 
     let mut visited = std::collections::HashSet::<(usize, usize)>::new();
     visited.insert(start_cell);
+    let mut junction_exits = std::collections::HashSet::<((usize, usize), GridDir)>::new();
+    let mut turns = route.iter();
 
     let mut current = start_cell;
     let mut incoming = start_dir.opposite();
@@ -261,7 +646,28 @@ fn traverse_cells(
         let next_dir = if current == start_cell && dirs.is_empty() {
             start_dir
         } else {
-            choose_next_dir(grid, current, incoming)?
+            match choose_next_dir(grid, current, incoming) {
+                Err(CenterlineBuildError::AmbiguousBranch { row, col, options }) => {
+                    let dir = match turns.next() {
+                        Some(dir) => *dir,
+                        None => {
+                            return Err(CenterlineBuildError::AmbiguousBranch {
+                                row,
+                                col,
+                                options,
+                            });
+                        }
+                    };
+                    if !options.contains(&dir) {
+                        return Err(CenterlineBuildError::InvalidRouteTurn { row, col, dir });
+                    }
+                    if !junction_exits.insert((current, dir)) {
+                        return Err(CenterlineBuildError::NotClosedLoop);
+                    }
+                    dir
+                }
+                other => other?,
+            }
         };
 
         let next = step_cell(current, next_dir).ok_or(CenterlineBuildError::DeadEnd {
@@ -275,7 +681,7 @@ fn traverse_cells(
             break;
         }
 
-        if !visited.insert(next) {
+        if !visited.insert(next) && !is_junction(grid.tile_at(next.0, next.1)) {
             return Err(CenterlineBuildError::NotClosedLoop);
         }
 
@@ -291,7 +697,16 @@ fn traverse_cells(
         current = next;
     }
 
-    Ok((cells, dirs))
+    match turns.len() {
+        0 => Ok((cells, dirs)),
+        unused => Err(CenterlineBuildError::UnusedRouteTurns(unused)),
+    }
+}
+
+/// Tiles with more than two open edges.
+fn is_junction(tile: TilePart) -> bool {
+    let (n, s, e, w) = tile.open_edges();
+    [n, s, e, w].into_iter().filter(|open| *open).count() > 2
 }
 
 fn build_polyline_points(
@@ -461,3 +876,216 @@ fn compute_lengths(points: &[Vec2]) -> (Vec<f32>, f32) {
 
     (cumulative, total)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CenterlineBuildError, CenterlineProjection, GridDir, TrackCenterline, suggest_routes,
+    };
+    use crate::maps::grid::TrackGrid;
+    use crate::maps::parts::TilePart;
+    use bevy::prelude::Vec2;
+
+    /// A figure-8: an upper loop and a lower loop sharing the crossroads at
+    /// `(2, 2)`.
+    fn figure_eight_grid() -> TrackGrid {
+        use TilePart::*;
+        TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, CornerNE, Empty, Empty],
+                vec![StraightV, Empty, StraightV, Empty, Empty],
+                vec![CornerSW, StraightH, Crossroads, StraightH, CornerNE],
+                vec![Empty, Empty, StraightV, Empty, StraightV],
+                vec![Empty, Empty, CornerSW, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 250.0),
+        )
+    }
+
+    #[test]
+    fn suggested_routes_lead_with_the_figure_eight_through_the_crossroads_twice() {
+        use GridDir::*;
+        let grid = figure_eight_grid();
+        let routes = suggest_routes(&grid, (0, 1), East);
+
+        let turns: Vec<&[GridDir]> = routes.iter().map(|route| route.turns.as_slice()).collect();
+        assert_eq!(turns, [&[South, West][..], &[East, West], &[West]]);
+
+        let full = &routes[0];
+        let road_cells = (0..5)
+            .flat_map(|row| (0..5).map(move |col| (row, col)))
+            .filter(|&(row, col)| grid.tile_at(row, col).is_road())
+            .count();
+        assert_eq!(full.coverage(), road_cells);
+        assert_eq!(full.cells.iter().filter(|cell| **cell == (2, 2)).count(), 2);
+        assert_eq!(full.cells.len(), road_cells + 1);
+
+        let figure_eight = TrackCenterline::build_with_route(&grid, (0, 1), East, &full.turns)
+            .expect("figure-8 builds");
+        let upper = TrackCenterline::build_with_route(&grid, (0, 1), East, &[West])
+            .expect("upper loop builds");
+        assert!(figure_eight.total_length() > upper.total_length() * 1.5);
+    }
+
+    #[test]
+    fn routes_must_match_the_junctions_they_reach() {
+        use GridDir::*;
+        let grid = figure_eight_grid();
+
+        assert!(matches!(
+            TrackCenterline::build_closed_loop(&grid, (0, 1), East),
+            Err(CenterlineBuildError::AmbiguousBranch { row: 2, col: 2, .. })
+        ));
+        assert!(matches!(
+            TrackCenterline::build_with_route(&grid, (0, 1), East, &[North]),
+            Err(CenterlineBuildError::InvalidRouteTurn {
+                row: 2,
+                col: 2,
+                dir: North
+            })
+        ));
+        assert!(matches!(
+            TrackCenterline::build_with_route(&grid, (0, 1), East, &[West, South]),
+            Err(CenterlineBuildError::UnusedRouteTurns(1))
+        ));
+        // Leaving the crossroads south a second time.
+        assert!(matches!(
+            TrackCenterline::build_with_route(&grid, (0, 1), East, &[South, South]),
+            Err(CenterlineBuildError::NotClosedLoop)
+        ));
+    }
+
+    #[test]
+    fn segments_cover_the_loop_and_report_corner_curvature() {
+        use TilePart::*;
+        let grid = TrackGrid::new(
+            vec![
+                vec![CornerNW, SpawnPoint, StraightH, StraightH, CornerNE],
+                vec![CornerSW, StraightH, StraightH, StraightH, CornerSE],
+            ],
+            100.0,
+            Vec2::new(-250.0, 100.0),
+        );
+        let centerline =
+            TrackCenterline::build_closed_loop(&grid, (0, 1), GridDir::East).expect("oval loop");
+        let segments: Vec<_> = centerline.segments().collect();
+
+        assert_eq!(segments.len(), centerline.points.len());
+        let total: f32 = segments.iter().map(|seg| seg.length).sum();
+        assert!((total - centerline.total_length()).abs() < 1e-3);
+        for pair in segments.windows(2) {
+            assert!((pair[0].start_s + pair[0].length - pair[1].start_s).abs() < 1e-3);
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+
+        for seg in &segments {
+            let midpoint = (seg.start + seg.end) * 0.5;
+            let (row, col) = grid.world_to_cell(midpoint).expect("on grid");
+            if grid.tile_at(row, col).is_corner() {
+                // Clockwise loop: every corner turns right.
+                assert!(seg.curvature < -1e-3, "{seg:?}");
+            }
+            if col == 2 {
+                // Middle straights, with straight neighbours on both sides.
+                assert!(seg.curvature.abs() < 1e-6, "{seg:?}");
+            }
+        }
+        let sharpest = segments
+            .iter()
+            .map(|seg| seg.curvature.abs())
+            .fold(0.0, f32::max);
+        assert!((sharpest - 1.0 / 50.0).abs() < 1e-3, "{sharpest}");
+    }
+
+    fn sepang_centerline_samples(count: usize) -> (TrackCenterline, Vec<Vec2>) {
+        let grid = crate::maps::monaco::build_grid();
+        let spawn = grid.find_spawn_cell().expect("spawn tile");
+        let centerline =
+            TrackCenterline::build_closed_loop(&grid, spawn, GridDir::East).expect("sepang loop");
+        let width = grid.cols() as f32 * grid.tile_size;
+        let height = grid.rows() as f32 * grid.tile_size;
+        let mut rng = crate::sim::rng::SimRng::new(0x9E0);
+        let points = (0..count)
+            .map(|_| grid.origin + Vec2::new(rng.next_f32() * width, -rng.next_f32() * height))
+            .collect();
+        (centerline, points)
+    }
+
+    #[test]
+    fn equidistant_projection_ties_are_broken_towards_the_previous_s() {
+        // A long thin rectangle: its middle is 5 units from both long sides,
+        // at s = 50 on the bottom and s = 160 on the top.
+        let points = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        let centerline = TrackCenterline::from_polyline(points);
+        let middle = Vec2::new(50.0, 5.0);
+
+        for _ in 0..3 {
+            assert!((centerline.project(middle).s - 50.0).abs() < 1e-3);
+        }
+        assert!((centerline.project_near(middle, Some(55.0)).s - 50.0).abs() < 1e-3);
+        let top = centerline.project_near(middle, Some(150.0));
+        assert!((top.s - 160.0).abs() < 1e-3, "{top:?}");
+        assert_eq!(top.tangent, Vec2::NEG_X);
+        // Wrapping: s = 210 is 60 from the bottom hit and 50 from the top one.
+        assert!((centerline.project_near(middle, Some(210.0)).s - 160.0).abs() < 1e-3);
+
+        // Nudging the point a hair towards the top does not flip a car that
+        // was on the bottom side.
+        let nudged = middle + Vec2::new(0.0, 0.25 * super::PROJECTION_TIE_TOLERANCE);
+        assert!((centerline.project_near(nudged, Some(49.0)).s - 50.0).abs() < 1e-3);
+
+        let mut batched = [CenterlineProjection::default(); 2];
+        centerline.project_many_near(&[middle, middle], &[48.0, 161.0], &mut batched);
+        assert!((batched[0].s - 50.0).abs() < 1e-3);
+        assert!((batched[1].s - 160.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn project_many_matches_per_point_projection() {
+        // Enough points to take the parallel path when a pool is running.
+        let (centerline, points) = sepang_centerline_samples(1_000);
+        let mut batched = vec![CenterlineProjection::default(); points.len()];
+        centerline.project_many(&points, &mut batched);
+
+        for (point, batch) in points.iter().zip(&batched) {
+            let single = centerline.project(*point);
+            assert!((batch.distance - single.distance).abs() < 1e-3, "{point}");
+            assert!(batch.closest_point.distance(single.closest_point) < 1e-2);
+            assert!((batch.s - single.s).abs() < 1e-2, "{point}");
+            assert!((batch.fraction - single.fraction).abs() < 1e-5);
+            assert!(batch.tangent.distance(single.tangent) < 1e-4);
+        }
+
+        let mut empty: [CenterlineProjection; 0] = [];
+        centerline.project_many(&[], &mut empty);
+    }
+
+    #[test]
+    #[ignore]
+    fn project_many_is_not_slower_than_looping() {
+        let (centerline, points) = sepang_centerline_samples(20_000);
+        let mut batched = vec![CenterlineProjection::default(); points.len()];
+
+        let start = std::time::Instant::now();
+        let looped: f32 = points.iter().map(|&p| centerline.project(p).s).sum();
+        let loop_time = start.elapsed();
+
+        let start = std::time::Instant::now();
+        centerline.project_many(&points, &mut batched);
+        let batch_time = start.elapsed();
+        let summed: f32 = batched.iter().map(|p| p.s).sum();
+
+        println!(
+            "{} points: loop {loop_time:?}, project_many {batch_time:?} ({:.1}x); sums {looped:.0} / {summed:.0}",
+            points.len(),
+            loop_time.as_secs_f64() / batch_time.as_secs_f64().max(1e-9)
+        );
+        assert!(batch_time <= loop_time);
+    }
+}
@@ -0,0 +1,236 @@
+//! Conversions between world, grid, and screen coordinates.
+//!
+//! Conventions used everywhere in the crate:
+//! - **World**: Bevy 2D space, `+x` east and `+y` north (up).
+//! - **Grid**: `(row, col)` cells of side `tile_size`. Row 0 is the topmost
+//!   row (highest world-Y) and rows increase *downward*; columns increase
+//!   eastward. `origin` is the world position of the top-left (north-west)
+//!   corner of cell `(0, 0)`.
+//! - **Tile units**: continuous grid coordinates, `x` in columns and `y` in
+//!   rows (so also y-down), with `(0, 0)` at `origin`.
+//! - **Screen**: viewport pixels, `(0, 0)` at the top-left and `+y` down.
+//!
+//! Every y-axis flip lives in this module.
+
+use bevy::prelude::*;
+
+/// Distance (in tiles) within which [`GridMetrics::cell_index`] snaps a
+/// coordinate onto the nearest cell boundary.
+const CELL_EDGE_EPSILON: f32 = 1e-5;
+
+/// Placement and size of a tile grid in world space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridMetrics {
+    pub tile_size: f32,
+    /// World-space top-left corner of cell `(0, 0)`.
+    pub origin: Vec2,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl GridMetrics {
+    /// World-space position in tile units (`x` columns, `y` rows, y-down).
+    pub fn world_to_tile_units(&self, world: Vec2) -> Vec2 {
+        Vec2::new(world.x - self.origin.x, self.origin.y - world.y) / self.tile_size
+    }
+
+    /// Inverse of [`Self::world_to_tile_units`].
+    pub fn tile_units_to_world(&self, units: Vec2) -> Vec2 {
+        Vec2::new(
+            self.origin.x + units.x * self.tile_size,
+            self.origin.y - units.y * self.tile_size,
+        )
+    }
+
+    /// World X of the western edge of column `col` (may lie outside the grid).
+    pub fn column_edge_x(&self, col: isize) -> f32 {
+        self.origin.x + col as f32 * self.tile_size
+    }
+
+    /// World Y of the northern edge of row `row` (may lie outside the grid).
+    pub fn row_edge_y(&self, row: isize) -> f32 {
+        self.origin.y - row as f32 * self.tile_size
+    }
+
+    /// World-space top-left (north-west) corner of cell `(row, col)`.
+    pub fn cell_top_left(&self, row: usize, col: usize) -> Vec2 {
+        self.tile_units_to_world(Vec2::new(col as f32, row as f32))
+    }
+
+    /// World-space centre of cell `(row, col)`.
+    pub fn cell_center(&self, row: usize, col: usize) -> Vec2 {
+        self.tile_units_to_world(Vec2::new(col as f32 + 0.5, row as f32 + 0.5))
+    }
+
+    /// `(row, col)` of the cell containing `world`, which may lie outside
+    /// the grid.
+    ///
+    /// Positions within [`CELL_EDGE_EPSILON`] tiles of a cell boundary snap to
+    /// the cell on its east (column) or south (row) side, so float noise
+    /// around a shared edge never alternates between the two cells. Every
+    /// cell lookup, including the ray march in
+    /// [`crate::maps::grid::TrackGrid::raycast_hit`], goes through this.
+    pub fn cell_index(&self, world: Vec2) -> (isize, isize) {
+        let units = self.world_to_tile_units(world);
+        (
+            snapped_cell_index(units.y) as isize,
+            snapped_cell_index(units.x) as isize,
+        )
+    }
+
+    /// [`Self::cell_index`] for positions inside the grid.
+    ///
+    /// Returns `None` if the position lies outside the grid.
+    pub fn world_to_cell(&self, world: Vec2) -> Option<(usize, usize)> {
+        let units = self.world_to_tile_units(world);
+        if !units.is_finite() {
+            return None;
+        }
+        let (row, col) = self.cell_index(world);
+        if row < 0 || col < 0 || row as usize >= self.rows || col as usize >= self.cols {
+            return None;
+        }
+        Some((row as usize, col as usize))
+    }
+
+    /// World-space rectangle covered by the grid.
+    pub fn world_bounds(&self) -> Rect {
+        Rect::from_corners(
+            self.origin,
+            self.tile_units_to_world(Vec2::new(self.cols as f32, self.rows as f32)),
+        )
+    }
+
+    /// Position of `world` within [`Self::world_bounds`], with `(0, 0)` at
+    /// the south-west corner and `(1, 1)` at the north-east corner. Clamped
+    /// to `[0, 1]`.
+    pub fn normalized_position(&self, world: Vec2) -> Vec2 {
+        let bounds = self.world_bounds();
+        ((world - bounds.min) / bounds.size().max(Vec2::splat(1e-6))).clamp(Vec2::ZERO, Vec2::ONE)
+    }
+}
+
+/// Cell index for a coordinate in tile units, snapping values within
+/// [`CELL_EDGE_EPSILON`] of an integer up to that boundary.
+fn snapped_cell_index(tiles: f32) -> f32 {
+    let nearest = tiles.round();
+    if (tiles - nearest).abs() < CELL_EDGE_EPSILON {
+        nearest
+    } else {
+        tiles.floor()
+    }
+}
+
+/// World-space displacement for a screen-space pixel displacement under an
+/// orthographic camera of scale `scale` (world units per pixel).
+pub fn screen_delta_to_world(delta: Vec2, scale: f32) -> Vec2 {
+    Vec2::new(delta.x, -delta.y) * scale
+}
+
+/// World position under screen pixel `screen` for an orthographic camera
+/// centred on `camera_center` with `viewport` pixel size and scale `scale`.
+pub fn screen_to_world(screen: Vec2, viewport: Vec2, camera_center: Vec2, scale: f32) -> Vec2 {
+    camera_center + screen_delta_to_world(screen - viewport * 0.5, scale)
+}
+
+/// Inverse of [`screen_to_world`].
+pub fn world_to_screen(world: Vec2, viewport: Vec2, camera_center: Vec2, scale: f32) -> Vec2 {
+    let pixels = (world - camera_center) / scale;
+    viewport * 0.5 + Vec2::new(pixels.x, -pixels.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GridMetrics, screen_delta_to_world, screen_to_world, world_to_screen};
+    use bevy::prelude::Vec2;
+
+    fn metrics() -> GridMetrics {
+        GridMetrics {
+            tile_size: 100.0,
+            origin: Vec2::new(-250.0, 150.0),
+            rows: 3,
+            cols: 5,
+        }
+    }
+
+    #[test]
+    fn rows_run_down_from_the_north_west_origin() {
+        let grid = metrics();
+        assert_eq!(grid.cell_top_left(0, 0), Vec2::new(-250.0, 150.0));
+        assert_eq!(grid.cell_center(0, 0), Vec2::new(-200.0, 100.0));
+        // Bottom-right cell: furthest east and lowest world-Y.
+        assert_eq!(grid.cell_center(2, 4), Vec2::new(200.0, -100.0));
+
+        // Just inside each grid corner.
+        let eps = 1e-2;
+        assert_eq!(
+            grid.world_to_cell(Vec2::new(-250.0 + eps, 150.0 - eps)),
+            Some((0, 0))
+        );
+        assert_eq!(
+            grid.world_to_cell(Vec2::new(250.0 - eps, 150.0 - eps)),
+            Some((0, 4))
+        );
+        assert_eq!(
+            grid.world_to_cell(Vec2::new(-250.0 + eps, -150.0 + eps)),
+            Some((2, 0))
+        );
+        assert_eq!(
+            grid.world_to_cell(Vec2::new(250.0 - eps, -150.0 + eps)),
+            Some((2, 4))
+        );
+        // And just outside.
+        assert_eq!(grid.world_to_cell(Vec2::new(-250.0 - eps, 100.0)), None);
+        assert_eq!(grid.world_to_cell(Vec2::new(0.0, 150.0 + eps)), None);
+        assert_eq!(grid.world_to_cell(Vec2::new(250.0 + eps, 0.0)), None);
+        assert_eq!(grid.world_to_cell(Vec2::new(0.0, -150.0 - eps)), None);
+
+        let bounds = grid.world_bounds();
+        assert_eq!(bounds.min, Vec2::new(-250.0, -150.0));
+        assert_eq!(bounds.max, Vec2::new(250.0, 150.0));
+        assert_eq!(grid.normalized_position(bounds.min), Vec2::ZERO);
+        assert_eq!(grid.normalized_position(bounds.max), Vec2::ONE);
+        assert_eq!(grid.row_edge_y(1), 50.0);
+        assert_eq!(grid.column_edge_x(-1), -350.0);
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        let grid = metrics();
+        for row in 0..grid.rows {
+            for col in 0..grid.cols {
+                assert_eq!(
+                    grid.world_to_cell(grid.cell_center(row, col)),
+                    Some((row, col))
+                );
+                // Top-left corners snap into their own cell.
+                assert_eq!(
+                    grid.world_to_cell(grid.cell_top_left(row, col)),
+                    Some((row, col))
+                );
+            }
+        }
+        for world in [Vec2::new(-12.5, 37.0), Vec2::new(249.0, -149.0), Vec2::ZERO] {
+            let back = grid.tile_units_to_world(grid.world_to_tile_units(world));
+            assert!(back.distance(world) < 1e-4, "{world} -> {back}");
+        }
+
+        let viewport = Vec2::new(1280.0, 720.0);
+        let center = Vec2::new(40.0, -25.0);
+        // Screen y-down: the top-left pixel is north-west of the centre.
+        let top_left = screen_to_world(Vec2::ZERO, viewport, center, 2.0);
+        assert_eq!(top_left, center + Vec2::new(-1280.0, 720.0));
+        assert_eq!(
+            screen_to_world(viewport * 0.5, viewport, center, 2.0),
+            center
+        );
+        for screen in [Vec2::ZERO, Vec2::new(100.0, 600.0), viewport] {
+            let world = screen_to_world(screen, viewport, center, 0.5);
+            assert!(world_to_screen(world, viewport, center, 0.5).distance(screen) < 1e-3);
+        }
+        assert_eq!(
+            screen_delta_to_world(Vec2::new(10.0, 10.0), 0.5),
+            Vec2::new(5.0, -5.0)
+        );
+    }
+}
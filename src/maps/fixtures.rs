@@ -0,0 +1,41 @@
+//! Tracks shared by tests across the crate.
+
+use bevy::prelude::Vec2;
+
+use crate::maps::centerline::{GridDir, TrackCenterline};
+use crate::maps::grid::TrackGrid;
+use crate::maps::parts::TilePart;
+use crate::maps::track::Track;
+
+/// The smallest closed loop: a 3x3 ring of 100-unit tiles centred on the
+/// origin, with the spawn in the middle of the top row facing east.
+pub fn ring_grid() -> TrackGrid {
+    use TilePart::*;
+    TrackGrid::new(
+        vec![
+            vec![CornerNW, SpawnPoint, CornerNE],
+            vec![StraightV, Empty, StraightV],
+            vec![CornerSW, StraightH, CornerSE],
+        ],
+        100.0,
+        Vec2::new(-150.0, 150.0),
+    )
+}
+
+/// Centreline of [`ring_grid`], driven clockwise from the spawn.
+pub fn ring_centerline() -> TrackCenterline {
+    TrackCenterline::build_closed_loop(&ring_grid(), (0, 1), GridDir::East)
+        .expect("ring grid forms a closed loop")
+}
+
+/// [`ring_grid`] as a driveable track with its centreline.
+pub fn ring_track() -> Track {
+    let grid = ring_grid();
+    let (spawn_position, spawn_rotation) = grid.find_spawn().expect("spawn tile");
+    Track {
+        grid,
+        spawn_position,
+        spawn_rotation,
+        centerline: Some(ring_centerline()),
+    }
+}
@@ -1,7 +1,11 @@
+use std::collections::HashMap;
+
 use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
 use bevy::render::render_resource::PrimitiveTopology;
+use serde::{Deserialize, Serialize};
 
+use crate::maps::coords::GridMetrics;
 use crate::maps::parts::TilePart;
 
 /// Number of line segments used to approximate each quarter-circle corner arc.
@@ -14,10 +18,133 @@ const ARC_SEGMENTS: usize = 12;
 /// the inner face of the visual wall.
 const WALL_THICKNESS: f32 = 5.0;
 
+/// Radial resolution of [`TrackGrid::boundary_normal_at`] (steps per tile).
+const BOUNDARY_SEARCH_STEPS: usize = 100;
+/// Angular resolution of [`TrackGrid::boundary_normal_at`].
+const BOUNDARY_SEARCH_DIRECTIONS: usize = 72;
+
 // ─────────────────────────────────────────────────────────────────────────────
 // TrackGrid
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// What lies beyond the outermost cells of a [`TrackGrid`].
+///
+/// Only matters where road reaches the border through an open edge; closed
+/// edges already wall the road in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GridEdge {
+    /// The border is solid: positions outside the grid are off-road, and a
+    /// ray reaching the border hits it there.
+    #[default]
+    Wall,
+    /// The world continues past the border: positions outside the grid are
+    /// driveable, and a ray leaving the grid reads its full range.
+    Open,
+}
+
+/// Inset of the driveable area from each closed wall, in world units, per
+/// tile type. Both default to half the rendered wall thickness, so the car
+/// collides with the wall's inner face.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TileMargins {
+    /// Straight-type tiles (straights, spawn, boost pads): inset on every
+    /// closed edge.
+    pub straight: f32,
+    /// Corner tiles: inset from the outer arc and, for a narrowed
+    /// [`TrackGrid::road_width`], from the inner arc.
+    pub corner: f32,
+}
+
+impl Default for TileMargins {
+    fn default() -> Self {
+        Self {
+            straight: WALL_THICKNESS * 0.5,
+            corner: WALL_THICKNESS * 0.5,
+        }
+    }
+}
+
+impl TileMargins {
+    /// Margin applied inside `tile`.
+    pub fn for_tile(&self, tile: TilePart) -> f32 {
+        if tile.is_corner() {
+            self.corner
+        } else {
+            self.straight
+        }
+    }
+
+    /// The first margin that is negative or not finite, if any.
+    pub fn invalid_margin(&self) -> Option<f32> {
+        [self.straight, self.corner]
+            .into_iter()
+            .find(|margin| !(margin.is_finite() && *margin >= 0.0))
+    }
+}
+
+/// What ended a ray cast by [`TrackGrid::raycast_hit`].
+///
+/// The grid caster only reports `None`, `Wall`, and `OffGrid`; `Obstacle`
+/// and `Car` are reserved for casters that also consult dynamic bodies, so
+/// the observation encoding does not change when they land.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RayHitClass {
+    /// Nothing within range; the ray reads its full length.
+    #[default]
+    None,
+    /// The edge of the driveable area inside the grid.
+    Wall,
+    /// The solid grid border ([`GridEdge::Wall`]).
+    OffGrid,
+    /// A static obstacle.
+    Obstacle,
+    /// Another car.
+    Car,
+}
+
+impl RayHitClass {
+    /// Every class, in encoding order.
+    pub const ALL: [RayHitClass; 5] = [
+        RayHitClass::None,
+        RayHitClass::Wall,
+        RayHitClass::OffGrid,
+        RayHitClass::Obstacle,
+        RayHitClass::Car,
+    ];
+
+    /// Class id scaled to `[0, 1]`: `None` is `0`, `Car` is `1`.
+    pub fn normalized_id(self) -> f32 {
+        let id = Self::ALL
+            .iter()
+            .position(|class| *class == self)
+            .unwrap_or(0);
+        id as f32 / (Self::ALL.len() - 1) as f32
+    }
+}
+
+/// Distance, class and surface normal of a ray hit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RayHit {
+    pub distance: f32,
+    pub class: RayHitClass,
+    /// Unit normal of the surface hit, facing back towards the road the ray
+    /// crossed. Zero when nothing was hit.
+    pub normal: Vec2,
+}
+
+impl RayHit {
+    /// `|cos|` of the angle between `direction` and the hit normal: `1` for
+    /// a head-on hit, approaching `0` at a glancing one, and `0` when
+    /// nothing was hit.
+    pub fn incidence(&self, direction: Vec2) -> f32 {
+        self.normal
+            .dot(direction.normalize_or_zero())
+            .abs()
+            .clamp(0.0, 1.0)
+    }
+}
+
 /// A grid-based track definition.
 ///
 /// Tiles are stored row-major as `tiles[row][col]`.
@@ -29,6 +156,9 @@ const WALL_THICKNESS: f32 = 5.0;
 /// y = origin.y − row * tile_size
 /// ```
 ///
+/// Coordinate conversions delegate to [`GridMetrics`]; see
+/// [`crate::maps::coords`] for the conventions.
+///
 /// The grid owns no Bevy entities. Rendering is performed by
 /// [`render_tile_grid`]; spawning the track entity is the plugin's job.
 pub struct TrackGrid {
@@ -40,6 +170,26 @@ pub struct TrackGrid {
 
     /// World-space position of the top-left corner of cell `[0][0]`.
     pub origin: Vec2,
+
+    /// Optional free-form annotations per cell (e.g. `"sector1"`, `"drs"`).
+    ///
+    /// Tags are a parallel layer: they never change connectivity or collision,
+    /// so analytics and reward shaping can key off regions without new
+    /// `TilePart` variants. Untagged cells have no entry.
+    pub tags: HashMap<(usize, usize), Vec<String>>,
+
+    /// Behaviour at the grid border.
+    pub edge: GridEdge,
+
+    /// Width of the road through corner tiles, centred on the centreline
+    /// arc (radius `tile_size / 2` about the inner tile corner). `None`, or
+    /// any width of at least `tile_size`, keeps the full quarter sector;
+    /// narrower roads become an annular sector between
+    /// [`Self::corner_radii`]. Straight-type tiles always fill their cell.
+    pub road_width: Option<f32>,
+
+    /// Wall insets of the driveable area, per tile type.
+    pub margins: TileMargins,
 }
 
 impl TrackGrid {
@@ -52,9 +202,53 @@ impl TrackGrid {
             tiles,
             tile_size,
             origin,
+            tags: HashMap::new(),
+            edge: GridEdge::default(),
+            road_width: None,
+            margins: TileMargins::default(),
         }
     }
 
+    /// Inner and outer radius of the corner road about the arc centre.
+    ///
+    /// The full-width corner is `(0, tile_size)`; a narrower `road_width`
+    /// keeps the band centred on radius `tile_size / 2`.
+    pub fn corner_radii(&self) -> (f32, f32) {
+        let half = self.tile_size * 0.5;
+        let half_width = self
+            .road_width
+            .map_or(half, |width| (width * 0.5).clamp(0.0, half));
+        (half - half_width, half + half_width)
+    }
+
+    /// Adds `tag` to cell `(row, col)`. Duplicate tags on one cell are ignored.
+    pub fn tag_cell(&mut self, row: usize, col: usize, tag: impl Into<String>) {
+        let tag = tag.into();
+        let cell_tags = self.tags.entry((row, col)).or_default();
+        if !cell_tags.contains(&tag) {
+            cell_tags.push(tag);
+        }
+    }
+
+    /// Returns the tags attached to cell `(row, col)`; empty when untagged.
+    pub fn tags_at_cell(&self, row: usize, col: usize) -> &[String] {
+        self.tags.get(&(row, col)).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns the tags of the cell containing `world`; empty when untagged
+    /// or outside the grid.
+    pub fn tags_at(&self, world: Vec2) -> &[String] {
+        match self.world_to_cell(world) {
+            Some((row, col)) => self.tags_at_cell(row, col),
+            None => &[],
+        }
+    }
+
+    /// Returns `true` if the cell containing `world` carries `tag`.
+    pub fn has_tag_at(&self, world: Vec2, tag: &str) -> bool {
+        self.tags_at(world).iter().any(|t| t == tag)
+    }
+
     /// Number of rows in the grid.
     pub fn rows(&self) -> usize {
         self.tiles.len()
@@ -74,51 +268,63 @@ impl TrackGrid {
             .unwrap_or(TilePart::Empty)
     }
 
+    /// Size and placement of the grid, for coordinate conversions.
+    pub fn metrics(&self) -> GridMetrics {
+        GridMetrics {
+            tile_size: self.tile_size,
+            origin: self.origin,
+            rows: self.rows(),
+            cols: self.cols(),
+        }
+    }
+
+    /// World-space rectangle covered by the grid.
+    pub fn world_bounds(&self) -> Rect {
+        self.metrics().world_bounds()
+    }
+
+    /// See [`GridMetrics::normalized_position`].
+    pub fn normalized_position(&self, world: Vec2) -> Vec2 {
+        self.metrics().normalized_position(world)
+    }
+
     /// Returns the world-space centre of tile `(row, col)`.
     pub fn cell_center(&self, row: usize, col: usize) -> Vec2 {
-        Vec2::new(
-            self.origin.x + col as f32 * self.tile_size + self.tile_size * 0.5,
-            self.origin.y - row as f32 * self.tile_size - self.tile_size * 0.5,
-        )
+        self.metrics().cell_center(row, col)
     }
 
-    /// Converts a world-space position to `(row, col)`.
+    /// Converts a world-space position to `(row, col)`; see
+    /// [`GridMetrics::world_to_cell`] for the edge snapping.
     ///
     /// Returns `None` if the position lies outside the grid.
     pub fn world_to_cell(&self, world: Vec2) -> Option<(usize, usize)> {
-        let rel_x = world.x - self.origin.x;
-        let rel_y = self.origin.y - world.y; // Y increases downward in row space
-
-        if rel_x < 0.0 || rel_y < 0.0 {
-            return None;
-        }
-
-        let col = (rel_x / self.tile_size) as usize;
-        let row = (rel_y / self.tile_size) as usize;
-
-        if row >= self.rows() || col >= self.cols() {
-            return None;
-        }
+        self.metrics().world_to_cell(world)
+    }
 
-        Some((row, col))
+    /// Returns `true` if `world` lies in a boost-pad cell.
+    pub fn is_boost_pad_at(&self, world: Vec2) -> bool {
+        self.world_to_cell(world)
+            .is_some_and(|(row, col)| self.tile_at(row, col).is_boost_pad())
     }
 
     /// Returns `true` if `world` lies within the driveable area of a road tile.
     ///
-    /// The driveable area is the tile cell minus a half-wall-thickness inset on
-    /// every closed edge. This means:
+    /// The driveable area is the tile cell minus a [`Self::margins`] inset
+    /// (by default half the wall thickness) on every closed edge. This means:
     /// - Two adjacent road tiles with closed facing edges produce a full
     ///   wall-thickness dead zone at the boundary, preventing pass-through.
     /// - The car collides at the inner face of the visual wall, not at the
     ///   tile boundary.
     ///
-    /// Corner tiles use an arc-distance check with the same inset applied to
-    /// the arc radius.
+    /// Corner tiles use an arc-distance check with the corner margin applied
+    /// to the outer radius and, for a narrowed [`Self::road_width`], to the
+    /// inner radius as well.
     ///
-    /// Positions outside the grid bounds always return `false`.
+    /// Positions outside the grid bounds are road only with
+    /// [`GridEdge::Open`].
     pub fn is_road_at(&self, world: Vec2) -> bool {
         let Some((row, col)) = self.world_to_cell(world) else {
-            return false;
+            return self.edge == GridEdge::Open;
         };
 
         let tile = self.tile_at(row, col);
@@ -128,11 +334,13 @@ impl TrackGrid {
 
         let center = self.cell_center(row, col);
         let half = self.tile_size * 0.5;
-        let margin = WALL_THICKNESS * 0.5;
+        let margin = self.margins.for_tile(tile);
 
         if tile.is_corner() {
             let (arc_center, _, _) = corner_arc_params(tile, center, half);
-            return world.distance(arc_center) <= self.tile_size - margin;
+            let (inner, outer) = self.corner_radii();
+            let distance = world.distance(arc_center);
+            return distance <= outer - margin && (inner <= 0.0 || distance >= inner + margin);
         }
 
         let (open_n, open_s, open_e, open_w) = tile.open_edges();
@@ -153,6 +361,301 @@ impl TrackGrid {
         true
     }
 
+    /// Returns the unit normal pointing from an off-road point back towards
+    /// the nearest driveable road, or `None` if `world` is on the road or no
+    /// road lies within one tile.
+    ///
+    /// The normal is found by searching rings of increasing radius for road
+    /// samples and averaging the directions of the first ring that hits, so it
+    /// follows the real corridor shape: on a corner's concave outer arc it
+    /// points towards the arc centre, and at a convex inner apex it points
+    /// radially away from the wall back onto the road.
+    pub fn boundary_normal_at(&self, world: Vec2) -> Option<Vec2> {
+        if self.is_road_at(world) {
+            return None;
+        }
+
+        let step = self.tile_size / BOUNDARY_SEARCH_STEPS as f32;
+        for ring in 1..=BOUNDARY_SEARCH_STEPS {
+            let radius = ring as f32 * step;
+            let mut sum = Vec2::ZERO;
+            let mut hits = 0;
+            for i in 0..BOUNDARY_SEARCH_DIRECTIONS {
+                let angle = i as f32 / BOUNDARY_SEARCH_DIRECTIONS as f32 * std::f32::consts::TAU;
+                let direction = Vec2::from_angle(angle);
+                if self.is_road_at(world + direction * radius) {
+                    sum += direction;
+                    hits += 1;
+                }
+            }
+            if hits > 0 {
+                return sum.try_normalize();
+            }
+        }
+        None
+    }
+
+    /// Distance along `direction` from `origin` to the first point where the
+    /// ray leaves the driveable area, capped at `max_range`. Returns `0.0`
+    /// for an off-road origin or a zero direction. At the grid border the
+    /// ray stops with a hit ([`GridEdge::Wall`]) or reads `max_range`
+    /// ([`GridEdge::Open`]).
+    ///
+    /// Exact: cells are visited in ray order with a DDA walk, and in each
+    /// cell the ray is clipped against that cell's road region (the inset
+    /// rectangle of a straight-type tile, or the inset arc disk of a corner),
+    /// both convex, so the first exit is the end of the clipped interval. A
+    /// narrowed corner's annulus is split by the inner disk into at most two
+    /// intervals, and the one the ray is in when entering the cell is used.
+    pub fn raycast_boundary(&self, origin: Vec2, direction: Vec2, max_range: f32) -> f32 {
+        self.raycast_hit(origin, direction, max_range).distance
+    }
+
+    /// [`Self::raycast_boundary`] with the class of what stopped the ray:
+    /// [`RayHitClass::OffGrid`] at a solid grid border,
+    /// [`RayHitClass::Wall`] where the road ends inside the grid (including
+    /// an off-road origin), and [`RayHitClass::None`] at `max_range`.
+    ///
+    /// The normal comes from the surface that ended the clipped interval: an
+    /// inset edge, a corner's outer or inner arc, or the cell edge the ray
+    /// crossed into off-road. An off-road origin reports `-direction`.
+    pub fn raycast_hit(&self, origin: Vec2, direction: Vec2, max_range: f32) -> RayHit {
+        // Slack absorbing rounding where a road interval meets an open edge.
+        const EDGE_EPSILON: f32 = 1e-4;
+
+        let dir = direction.normalize_or_zero();
+        if dir == Vec2::ZERO || max_range <= 0.0 {
+            return RayHit::default();
+        }
+        let stop = |distance: f32, class: RayHitClass, normal: Vec2| {
+            if distance >= max_range {
+                RayHit {
+                    distance: max_range,
+                    class: RayHitClass::None,
+                    normal: Vec2::ZERO,
+                }
+            } else {
+                RayHit {
+                    distance,
+                    class,
+                    normal,
+                }
+            }
+        };
+
+        let size = self.tile_size;
+        let metrics = self.metrics();
+        let (mut row, mut col) = metrics.cell_index(origin);
+        // An origin snapped across a boundary sits up to the snap window
+        // outside its cell; pull it onto the cell's edge so the road
+        // geometry below agrees with `is_road_at`.
+        let origin =
+            if row < 0 || col < 0 || row as usize >= self.rows() || col as usize >= self.cols() {
+                origin
+            } else {
+                let center = self.cell_center(row as usize, col as usize);
+                let half = Vec2::splat(size * 0.5);
+                origin.clamp(center - half, center + half)
+            };
+
+        // Distance along the ray to the next column / row boundary.
+        let step_col: isize = if dir.x > 0.0 { 1 } else { -1 };
+        let step_row: isize = if dir.y < 0.0 { 1 } else { -1 };
+        let mut next_x = if dir.x == 0.0 {
+            f32::INFINITY
+        } else {
+            let boundary = metrics.column_edge_x(col + isize::from(dir.x > 0.0));
+            (boundary - origin.x) / dir.x
+        };
+        let mut next_y = if dir.y == 0.0 {
+            f32::INFINITY
+        } else {
+            let boundary = metrics.row_edge_y(row + isize::from(dir.y < 0.0));
+            (boundary - origin.y) / dir.y
+        };
+        let delta_x = if dir.x == 0.0 {
+            f32::INFINITY
+        } else {
+            size / dir.x.abs()
+        };
+        let delta_y = if dir.y == 0.0 {
+            f32::INFINITY
+        } else {
+            size / dir.y.abs()
+        };
+
+        let mut enter = 0.0_f32;
+        // Normal of the cell edge the ray last crossed.
+        let mut entry_normal = -dir;
+        while enter < max_range {
+            let exit = next_x.min(next_y);
+            if row < 0 || col < 0 || row as usize >= self.rows() || col as usize >= self.cols() {
+                return match self.edge {
+                    GridEdge::Wall => stop(enter, RayHitClass::OffGrid, entry_normal),
+                    GridEdge::Open => stop(max_range, RayHitClass::None, Vec2::ZERO),
+                };
+            }
+
+            let (road_start, road_end) =
+                self.cell_road_interval(row as usize, col as usize, origin, dir, enter);
+            if road_start > enter + EDGE_EPSILON || road_end < road_start {
+                return stop(enter, RayHitClass::Wall, entry_normal);
+            }
+            if road_end < exit - EDGE_EPSILON {
+                let distance = road_end.max(enter);
+                let normal =
+                    self.cell_exit_normal(row as usize, col as usize, origin + dir * distance, dir);
+                return stop(distance, RayHitClass::Wall, normal);
+            }
+
+            if next_x < next_y {
+                col += step_col;
+                next_x += delta_x;
+                entry_normal = Vec2::new(-step_col as f32, 0.0);
+            } else {
+                row += step_row;
+                next_y += delta_y;
+                entry_normal = Vec2::new(0.0, step_row as f32);
+            }
+            enter = exit;
+        }
+        stop(max_range, RayHitClass::None, Vec2::ZERO)
+    }
+
+    /// Normal, facing back onto the road, of the road-region surface of
+    /// `(row, col)` through which a ray along `dir` leaves at `point`.
+    fn cell_exit_normal(&self, row: usize, col: usize, point: Vec2, dir: Vec2) -> Vec2 {
+        let tile = self.tile_at(row, col);
+        let center = self.cell_center(row, col);
+        let half = self.tile_size * 0.5;
+        let margin = self.margins.for_tile(tile);
+
+        if tile.is_corner() {
+            let (arc_center, _, _) = corner_arc_params(tile, center, half);
+            let (inner, outer) = self.corner_radii();
+            let radial = point - arc_center;
+            let radius = radial.length();
+            let outward = radial.normalize_or(-dir);
+            // The outer arc faces the arc centre, the inner arc away from it.
+            let on_inner = inner > 0.0
+                && (radius - (inner + margin)).abs() < (radius - (outer - margin)).abs();
+            return if on_inner { outward } else { -outward };
+        }
+
+        let (open_n, open_s, open_e, open_w) = tile.open_edges();
+        let inset = |open: bool| if open { 0.0 } else { margin };
+        let faces = [
+            (
+                dir.x > 0.0,
+                center.x + half - inset(open_e) - point.x,
+                Vec2::NEG_X,
+            ),
+            (
+                dir.x < 0.0,
+                point.x - (center.x - half + inset(open_w)),
+                Vec2::X,
+            ),
+            (
+                dir.y > 0.0,
+                center.y + half - inset(open_n) - point.y,
+                Vec2::NEG_Y,
+            ),
+            (
+                dir.y < 0.0,
+                point.y - (center.y - half + inset(open_s)),
+                Vec2::Y,
+            ),
+        ];
+        faces
+            .into_iter()
+            .filter(|(leaving, ..)| *leaving)
+            .min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map_or(-dir, |(_, _, normal)| normal)
+    }
+
+    /// Ray-parameter interval over which `origin + t * dir` lies in the road
+    /// region of `(row, col)`, unclipped to the cell. Empty intervals have
+    /// `end < start`. Where the region splits the ray in two (a narrowed
+    /// corner), returns the piece containing or following `enter`.
+    fn cell_road_interval(
+        &self,
+        row: usize,
+        col: usize,
+        origin: Vec2,
+        dir: Vec2,
+        enter: f32,
+    ) -> (f32, f32) {
+        const EMPTY: (f32, f32) = (0.0, -1.0);
+
+        let tile = self.tile_at(row, col);
+        if !tile.is_road() {
+            return EMPTY;
+        }
+
+        let center = self.cell_center(row, col);
+        let half = self.tile_size * 0.5;
+        let margin = self.margins.for_tile(tile);
+
+        if tile.is_corner() {
+            let (arc_center, _, _) = corner_arc_params(tile, center, half);
+            let (inner, outer) = self.corner_radii();
+            let offset = origin - arc_center;
+            let disk = |radius: f32| {
+                let b = offset.dot(dir);
+                let c = offset.length_squared() - radius * radius;
+                let discriminant = b * b - c;
+                if discriminant < 0.0 {
+                    return EMPTY;
+                }
+                let root = discriminant.sqrt();
+                (-b - root, -b + root)
+            };
+
+            let (start, end) = disk(outer - margin);
+            if inner <= 0.0 || end < start {
+                return (start, end);
+            }
+            let (hole_start, hole_end) = disk(inner + margin);
+            if hole_end < hole_start || hole_end <= start || hole_start >= end {
+                return (start, end);
+            }
+            return if enter < hole_start {
+                (start, hole_start)
+            } else {
+                (hole_end, end)
+            };
+        }
+
+        let (open_n, open_s, open_e, open_w) = tile.open_edges();
+        let inset = |open: bool| if open { 0.0 } else { margin };
+        let min = Vec2::new(
+            center.x - half + inset(open_w),
+            center.y - half + inset(open_s),
+        );
+        let max = Vec2::new(
+            center.x + half - inset(open_e),
+            center.y + half - inset(open_n),
+        );
+
+        let mut start = f32::NEG_INFINITY;
+        let mut end = f32::INFINITY;
+        for (o, d, lo, hi) in [
+            (origin.x, dir.x, min.x, max.x),
+            (origin.y, dir.y, min.y, max.y),
+        ] {
+            if d == 0.0 {
+                if o < lo || o > hi {
+                    return EMPTY;
+                }
+                continue;
+            }
+            let (t0, t1) = ((lo - o) / d, (hi - o) / d);
+            start = start.max(t0.min(t1));
+            end = end.min(t0.max(t1));
+        }
+        (start, end)
+    }
+
     /// Locates the `SpawnPoint` tile and returns `(world_centre, heading_radians)`.
     ///
     /// `SpawnPoint` shares `StraightH` connectivity so the car faces east
@@ -195,11 +698,14 @@ impl TrackGrid {
 ///
 /// Corner road surfaces are rendered as quarter-circle meshes that match the
 /// outer wall arc, preventing the road from leaking outside the curved boundary.
+/// With a narrowed [`TrackGrid::road_width`] the surface is an annular sector
+/// between [`TrackGrid::corner_radii`], walled on both arcs.
 ///
 /// ## Arc geometry (corners)
 ///
 /// For each corner tile the arc is a quarter-circle with:
-/// - **Radius = `tile_size`** (equals the full tile side length).
+/// - **Radius = `tile_size`** (equals the full tile side length) at full road
+///   width; the outer corner radius otherwise.
 /// - **Centre** at the tile corner diagonally *opposite* the outer wall
 ///   direction.
 /// - **Endpoints** exactly at the two tile corners adjacent to the open edges.
@@ -220,11 +726,13 @@ pub fn render_tile_grid(
     materials: &mut ResMut<Assets<ColorMaterial>>,
 ) {
     let road_color = Color::srgb(0.28, 0.28, 0.28);
+    let boost_color = Color::srgb(0.85, 0.5, 0.1);
     let wall_color = Color::srgb(0.88, 0.88, 0.88);
     let wall_thickness = WALL_THICKNESS;
 
     let ts = grid.tile_size;
     let half = ts * 0.5;
+    let (inner_radius, outer_radius) = grid.corner_radii();
     let road_material = materials.add(ColorMaterial::from(road_color));
     let wall_material = materials.add(ColorMaterial::from(wall_color));
 
@@ -238,8 +746,8 @@ pub fn render_tile_grid(
             let center = grid.cell_center(row, col);
 
             if tile.is_corner() {
-                // Corner tiles: road surface is a quarter-circle sector that
-                // matches the curved outer wall.
+                // Corner tiles: road surface is a quarter-circle sector (or
+                // annular sector) that matches the curved walls.
                 let (arc_center, start_deg, end_deg) = corner_arc_params(tile, center, half);
                 spawn_corner_surface(
                     commands,
@@ -247,7 +755,7 @@ pub fn render_tile_grid(
                     road_material.clone(),
                     center,
                     arc_center,
-                    ts,
+                    (inner_radius, outer_radius),
                     start_deg,
                     end_deg,
                     ARC_SEGMENTS,
@@ -261,18 +769,36 @@ pub fn render_tile_grid(
                     meshes,
                     wall_material.clone(),
                     arc_center,
-                    ts, // radius = tile_size
+                    outer_radius,
                     start_deg,
                     end_deg,
                     ARC_SEGMENTS,
                     wall_thickness,
                     1.0,
                 );
+                if inner_radius > 0.0 {
+                    spawn_arc_mesh(
+                        commands,
+                        meshes,
+                        wall_material.clone(),
+                        arc_center,
+                        inner_radius,
+                        start_deg,
+                        end_deg,
+                        ARC_SEGMENTS,
+                        wall_thickness,
+                        1.0,
+                    );
+                }
             } else {
                 // Road surface — fills the full cell.
                 commands.spawn((
                     Sprite {
-                        color: road_color,
+                        color: if tile.is_boost_pad() {
+                            boost_color
+                        } else {
+                            road_color
+                        },
                         custom_size: Some(Vec2::splat(ts)),
                         ..default()
                     },
@@ -337,14 +863,16 @@ pub fn render_tile_grid(
 // Arc helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// Spawns a filled quarter-circle mesh for a corner tile's road surface.
+/// Spawns a filled quarter-circle mesh for a corner tile's road surface,
+/// between `inner` and `outer` radius. A zero inner radius gives a plain
+/// sector.
 fn spawn_corner_surface(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     material: Handle<ColorMaterial>,
     tile_center: Vec2,
     arc_center_world: Vec2,
-    radius: f32,
+    (inner, outer): (f32, f32),
     start_deg: f32,
     end_deg: f32,
     segments: usize,
@@ -353,32 +881,30 @@ fn spawn_corner_surface(
     let arc_center_local = arc_center_world - tile_center;
     let sweep = end_deg - start_deg;
 
-    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(segments * 3);
-    let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(segments * 3);
+    let mut positions: Vec<[f32; 3]> = Vec::with_capacity(segments * 6);
 
     let mut mesh = Mesh::new(
         PrimitiveTopology::TriangleList,
         RenderAssetUsages::default(),
     );
-    let center = [arc_center_local.x, arc_center_local.y, 0.0];
+    let at = |radius: f32, angle: f32| {
+        let p = arc_center_local + Vec2::new(radius * angle.cos(), radius * angle.sin());
+        [p.x, p.y, 0.0]
+    };
 
     for i in 0..segments {
         let t0 = i as f32 / segments as f32;
         let t1 = (i + 1) as f32 / segments as f32;
         let a0 = (start_deg + t0 * sweep).to_radians();
         let a1 = (start_deg + t1 * sweep).to_radians();
-        let p0 = arc_center_local + Vec2::new(radius * a0.cos(), radius * a0.sin());
-        let p1 = arc_center_local + Vec2::new(radius * a1.cos(), radius * a1.sin());
 
-        positions.push(center);
-        positions.push([p0.x, p0.y, 0.0]);
-        positions.push([p1.x, p1.y, 0.0]);
-
-        uvs.push([0.0, 0.0]);
-        uvs.push([0.0, 0.0]);
-        uvs.push([0.0, 0.0]);
+        positions.extend_from_slice(&[at(inner, a0), at(outer, a0), at(outer, a1)]);
+        if inner > 0.0 {
+            positions.extend_from_slice(&[at(inner, a0), at(outer, a1), at(inner, a1)]);
+        }
     }
 
+    let uvs = vec![[0.0_f32, 0.0]; positions.len()];
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
     mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
 
@@ -486,3 +1012,268 @@ fn spawn_line_segment_mesh(
         Visibility::Visible,
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{GridEdge, RayHitClass, TileMargins, TrackGrid, corner_arc_params};
+    use crate::maps::fixtures::ring_grid;
+    use crate::maps::parts::TilePart;
+    use bevy::prelude::Vec2;
+
+    fn straight_grid() -> TrackGrid {
+        TrackGrid::new(
+            vec![vec![
+                TilePart::StraightH,
+                TilePart::SpawnPoint,
+                TilePart::StraightH,
+            ]],
+            100.0,
+            Vec2::new(0.0, 100.0),
+        )
+    }
+
+    #[test]
+    fn tagged_cell_reports_tags_via_world_lookup() {
+        let mut grid = straight_grid();
+        grid.tag_cell(0, 1, "sector1");
+        grid.tag_cell(0, 1, "drs");
+        grid.tag_cell(0, 1, "drs");
+
+        let centre = grid.cell_center(0, 1);
+        assert_eq!(grid.tags_at(centre), ["sector1", "drs"]);
+        assert!(grid.has_tag_at(centre + Vec2::new(30.0, -20.0), "drs"));
+
+        assert!(grid.tags_at(grid.cell_center(0, 0)).is_empty());
+        assert!(grid.tags_at(Vec2::new(-50.0, 50.0)).is_empty());
+    }
+
+    #[test]
+    fn narrowed_corner_is_an_annulus_for_road_checks_and_rays() {
+        use TilePart::*;
+        let mut grid = TrackGrid::new(
+            vec![vec![SpawnPoint, CornerNE], vec![Empty, StraightV]],
+            100.0,
+            Vec2::new(0.0, 200.0),
+        );
+        // CornerNE at (0, 1) curves about its SW tile corner.
+        let arc_center = Vec2::new(100.0, 100.0);
+        let diagonal = Vec2::ONE.normalize();
+        let near_apex = arc_center + diagonal * 10.0;
+        let on_centerline = arc_center + diagonal * 50.0;
+        assert_eq!(grid.world_to_cell(near_apex), Some((0, 1)));
+        assert!(grid.is_road_at(near_apex));
+
+        grid.road_width = Some(40.0);
+        assert_eq!(grid.corner_radii(), (30.0, 70.0));
+        assert!(!grid.is_road_at(near_apex));
+        assert!(!grid.is_road_at(arc_center + diagonal * 31.0));
+        assert!(grid.is_road_at(on_centerline));
+        assert!(grid.is_road_at(arc_center + diagonal * 66.0));
+        assert!(!grid.is_road_at(arc_center + diagonal * 69.0));
+        assert!(grid.boundary_normal_at(near_apex).is_some());
+
+        // Inner wall face at 30 + 2.5, outer at 70 - 2.5.
+        let inward = grid.raycast_boundary(on_centerline, -diagonal, 500.0);
+        assert!((inward - 17.5).abs() < 1e-3, "{inward}");
+        let tangent = Vec2::new(-diagonal.x, diagonal.y);
+        let chord = grid.raycast_boundary(on_centerline, tangent, 500.0);
+        let expected = (67.5_f32 * 67.5 - 50.0 * 50.0).sqrt();
+        assert!((chord - expected).abs() < 1e-3, "{chord} vs {expected}");
+    }
+
+    #[test]
+    fn ray_hits_are_classified_as_wall_grid_border_or_nothing() {
+        // The straight's east end is open onto the grid border.
+        let grid = straight_grid();
+        let origin = grid.cell_center(0, 1);
+
+        let wall = grid.raycast_hit(origin, Vec2::Y, 500.0);
+        assert_eq!(wall.class, RayHitClass::Wall);
+        assert!((wall.distance - 47.5).abs() < 1e-4);
+
+        let border = grid.raycast_hit(origin, Vec2::X, 500.0);
+        assert_eq!(border.class, RayHitClass::OffGrid);
+        assert!((border.distance - 150.0).abs() < 1e-4);
+
+        let short = grid.raycast_hit(origin, Vec2::X, 100.0);
+        assert_eq!(short.class, RayHitClass::None);
+        assert_eq!(short.distance, 100.0);
+
+        let mut open = straight_grid();
+        open.edge = GridEdge::Open;
+        let open_end = open.raycast_hit(origin, Vec2::X, 500.0);
+        assert_eq!(open_end.class, RayHitClass::None);
+        assert_eq!(open_end.distance, 500.0);
+
+        assert_eq!(RayHitClass::None.normalized_id(), 0.0);
+        assert_eq!(RayHitClass::Car.normalized_id(), 1.0);
+    }
+
+    #[test]
+    fn a_larger_corner_margin_only_moves_the_corner_walls() {
+        let mut grid = ring_grid();
+        let corner_center = grid.cell_center(0, 0);
+        let (arc_center, _, _) = corner_arc_params(TilePart::CornerNW, corner_center, 50.0);
+        let outward = (corner_center - arc_center).normalize();
+        let (_, outer) = grid.corner_radii();
+        let near_arc = arc_center + outward * (outer - TileMargins::default().corner - 1.0);
+        let straight_top = grid.cell_center(0, 1).y + 50.0;
+        let near_straight_wall =
+            Vec2::new(0.0, straight_top - TileMargins::default().straight - 1.0);
+        let in_straight_wall = Vec2::new(0.0, straight_top - 1.0);
+
+        assert!(grid.is_road_at(near_arc));
+        assert!(grid.is_road_at(near_straight_wall));
+        assert!(!grid.is_road_at(in_straight_wall));
+
+        grid.margins.corner = 6.0;
+        assert!(!grid.is_road_at(near_arc));
+        assert!(grid.is_road_at(near_straight_wall));
+        assert!(!grid.is_road_at(in_straight_wall));
+        // The exact caster clips against the same corridor.
+        let hit = grid.raycast_hit(arc_center + outward * 50.0, outward, 200.0);
+        assert!((hit.distance - (outer - 6.0 - 50.0)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn hit_normals_give_full_incidence_head_on_and_cos_45_at_a_diagonal() {
+        let grid = straight_grid();
+        let origin = grid.cell_center(0, 1);
+
+        let head_on = grid.raycast_hit(origin, Vec2::Y, 500.0);
+        assert_eq!(head_on.normal, Vec2::NEG_Y);
+        assert!((head_on.incidence(Vec2::Y) - 1.0).abs() < 1e-5);
+
+        let diagonal = Vec2::new(1.0, 1.0).normalize();
+        let glancing = grid.raycast_hit(origin, diagonal, 500.0);
+        assert_eq!(glancing.class, RayHitClass::Wall);
+        assert_eq!(glancing.normal, Vec2::NEG_Y);
+        let incidence = glancing.incidence(diagonal);
+        assert!((incidence - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-4);
+
+        // The solid border east of the straight faces back west.
+        let border = grid.raycast_hit(origin, Vec2::X, 500.0);
+        assert_eq!(border.normal, Vec2::NEG_X);
+
+        let miss = grid.raycast_hit(origin, Vec2::X, 100.0);
+        assert_eq!(miss.normal, Vec2::ZERO);
+        assert_eq!(miss.incidence(Vec2::X), 0.0);
+    }
+
+    #[test]
+    fn raycast_stops_at_closed_edges_and_the_grid_boundary() {
+        let grid = straight_grid();
+        let origin = grid.cell_center(0, 1);
+
+        let north = grid.raycast_boundary(origin, Vec2::Y, 500.0);
+        let east = grid.raycast_boundary(origin, Vec2::X, 500.0);
+        let capped = grid.raycast_boundary(origin, Vec2::X, 100.0);
+        let off_road = grid.raycast_boundary(Vec2::new(150.0, 99.0), Vec2::X, 500.0);
+
+        assert!((north - 47.5).abs() < 1e-4);
+        assert!((east - 150.0).abs() < 1e-4);
+        assert_eq!(capped, 100.0);
+        assert_eq!(off_road, 0.0);
+    }
+
+    #[test]
+    fn rays_toward_an_open_border_end_per_edge_mode() {
+        // Both end tiles are open towards the border.
+        let mut grid = straight_grid();
+        let near_edge = Vec2::new(290.0, 50.0);
+
+        for max_range in [50.0, 1e6, f32::INFINITY] {
+            let hit = grid.raycast_boundary(near_edge, Vec2::X, max_range);
+            assert_eq!(hit, 10.0_f32.min(max_range), "range {max_range}");
+        }
+        let diagonal = grid.raycast_boundary(Vec2::new(295.0, 50.0), Vec2::new(1.0, 0.2), 1e6);
+        assert!(diagonal.is_finite() && diagonal <= 6.0, "{diagonal}");
+        assert!(!grid.is_road_at(Vec2::new(301.0, 50.0)));
+
+        grid.edge = GridEdge::Open;
+        assert_eq!(grid.raycast_boundary(near_edge, Vec2::X, 500.0), 500.0);
+        assert!(grid.is_road_at(Vec2::new(301.0, 50.0)));
+        // Closed edges still wall the road in.
+        let north = grid.raycast_boundary(near_edge, Vec2::Y, 500.0);
+        assert!((north - 47.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn road_classification_is_stable_across_a_shared_open_edge() {
+        use TilePart::*;
+        // Straight (open east) meets a corner (open west) at x = 100; at the
+        // corner's inner apex the two tiles disagree about the bottom 2.5 px.
+        let grid = TrackGrid::new(
+            vec![vec![SpawnPoint, CornerNE], vec![Empty, StraightV]],
+            100.0,
+            Vec2::new(0.0, 200.0),
+        );
+
+        for step in 0..=100 {
+            let y = 100.0 + step as f32;
+            let classes: Vec<bool> = [-4e-4, -1e-5, 0.0, 1e-5, 4e-4]
+                .iter()
+                .map(|dx| grid.is_road_at(Vec2::new(100.0 + dx, y)))
+                .collect();
+            assert!(
+                classes.iter().all(|&c| c == classes[0]),
+                "y = {y}: {classes:?}"
+            );
+        }
+        assert_eq!(
+            grid.world_to_cell(Vec2::new(100.0 - 1e-4, 150.0)),
+            Some((0, 1))
+        );
+        assert_eq!(grid.world_to_cell(Vec2::new(-1e-4, 150.0)), Some((0, 0)));
+        assert_eq!(grid.world_to_cell(Vec2::new(-1.0, 150.0)), None);
+    }
+
+    #[test]
+    fn rays_start_in_the_cell_the_lookup_snaps_to() {
+        // Off-road on the west, road on the east of x = 0.
+        let grid = TrackGrid::new(
+            vec![vec![TilePart::Empty, TilePart::StraightH]],
+            100.0,
+            Vec2::new(-100.0, 50.0),
+        );
+
+        // The snap window is 1e-3 world units wide at this tile size.
+        for dx in [-3e-3, -5e-4, -1e-6, 0.0, 5e-4, 3e-3] {
+            let origin = Vec2::new(dx, 0.0);
+            let (row, col) = grid.metrics().cell_index(origin);
+            assert_eq!(
+                grid.world_to_cell(origin),
+                Some((row as usize, col as usize))
+            );
+            let on_road = grid.is_road_at(origin);
+            assert_eq!(on_road, col == 1, "dx = {dx}");
+
+            let east = grid.raycast_hit(origin, Vec2::X, 50.0);
+            assert_eq!(east.distance > 0.0, on_road, "dx = {dx}: {east:?}");
+            let west = grid.raycast_hit(origin, Vec2::NEG_X, 50.0);
+            assert_eq!(west.class, RayHitClass::Wall, "dx = {dx}");
+            assert!(west.distance < 1e-2, "dx = {dx}: {west:?}");
+        }
+    }
+
+    #[test]
+    fn normalized_position_spans_the_grid_bounds() {
+        let grid = ring_grid();
+        let bounds = grid.world_bounds();
+        assert_eq!(bounds.min, Vec2::new(-150.0, -150.0));
+        assert_eq!(bounds.max, Vec2::new(150.0, 150.0));
+
+        let (spawn, _) = grid.find_spawn().expect("spawn tile");
+        let normalized = grid.normalized_position(spawn);
+        assert!(
+            normalized.abs_diff_eq(Vec2::new(0.5, 250.0 / 300.0), 1e-6),
+            "{normalized}"
+        );
+        assert_eq!(grid.normalized_position(bounds.min), Vec2::ZERO);
+        assert_eq!(grid.normalized_position(bounds.max), Vec2::ONE);
+        assert_eq!(
+            grid.normalized_position(Vec2::new(-400.0, 900.0)),
+            Vec2::new(0.0, 1.0)
+        );
+    }
+}
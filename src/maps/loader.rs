@@ -0,0 +1,635 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::maps::centerline::{CenterlineBuildError, GridDir, LapDirection, TrackCenterline};
+use crate::maps::grid::{GridEdge, TileMargins, TrackGrid};
+use crate::maps::parts::TilePart;
+use crate::maps::track::Track;
+
+/// On-disk RON description of a tile track.
+///
+/// ```ron
+/// (
+///     name: "Ring",
+///     tile_size: 100.0,
+///     tiles: [
+///         [CornerNW, SpawnPoint, CornerNE],
+///         [StraightV, Empty, StraightV],
+///         [CornerSW, StraightH, CornerSE],
+///     ],
+///     tags: [(row: 0, col: 1, tag: "start")],
+///     lap_direction: Reverse,
+///     edge: Wall,
+///     road_width: Some(60.0),
+///     margins: (straight: 2.5, corner: 4.0),
+/// )
+/// ```
+///
+/// `lap_direction` defaults to `Forward` (leaving the spawn heading east),
+/// `edge` to `Wall`, `road_width` to `None` (full-tile corners; see
+/// [`TrackGrid::road_width`]), and `margins` to half the wall thickness on
+/// every tile type.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrackFile {
+    pub name: String,
+    pub tile_size: f32,
+    pub tiles: Vec<Vec<TilePart>>,
+    #[serde(default)]
+    pub tags: Vec<TileTag>,
+    #[serde(default)]
+    pub lap_direction: LapDirection,
+    #[serde(default)]
+    pub edge: GridEdge,
+    #[serde(default)]
+    pub road_width: Option<f32>,
+    #[serde(default)]
+    pub margins: TileMargins,
+}
+
+/// A single tag annotation in a [`TrackFile`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TileTag {
+    pub row: usize,
+    pub col: usize,
+    pub tag: String,
+}
+
+/// Reasons a track file can fail to load or validate.
+#[derive(Debug)]
+pub enum TrackLoadError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not valid RON for a [`TrackFile`].
+    Parse(String),
+    /// The grid has no rows or no columns.
+    EmptyGrid,
+    /// A row's length differs from the first row's.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// `tile_size` is not a positive finite number.
+    InvalidTileSize(f32),
+    /// `road_width` is not a positive finite number.
+    InvalidRoadWidth(f32),
+    /// A tile margin is negative or not finite.
+    InvalidMargin(f32),
+    /// The grid has no `SpawnPoint` tile.
+    MissingSpawn,
+    /// The grid has more than one `SpawnPoint` tile.
+    MultipleSpawns(usize),
+    /// An open tile edge does not lead into a matching open edge.
+    BrokenConnection {
+        row: usize,
+        col: usize,
+        edge: GridDir,
+    },
+    /// The centreline traversal from the spawn failed.
+    Centerline(CenterlineBuildError),
+}
+
+impl fmt::Display for TrackLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackLoadError::Io(err) => write!(f, "cannot read track file: {err}"),
+            TrackLoadError::Parse(err) => write!(f, "cannot parse track file: {err}"),
+            TrackLoadError::EmptyGrid => write!(f, "track grid is empty"),
+            TrackLoadError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row} has {found} tiles, expected {expected}"),
+            TrackLoadError::InvalidTileSize(size) => write!(f, "invalid tile size {size}"),
+            TrackLoadError::InvalidRoadWidth(width) => write!(f, "invalid road width {width}"),
+            TrackLoadError::InvalidMargin(margin) => write!(f, "invalid tile margin {margin}"),
+            TrackLoadError::MissingSpawn => write!(f, "track has no SpawnPoint tile"),
+            TrackLoadError::MultipleSpawns(count) => {
+                write!(
+                    f,
+                    "track has {count} SpawnPoint tiles, expected exactly one"
+                )
+            }
+            TrackLoadError::BrokenConnection { row, col, edge } => write!(
+                f,
+                "tile ({row}, {col}) is open to the {edge:?} but the neighbour is not connected back"
+            ),
+            TrackLoadError::Centerline(err) => write!(f, "centreline build failed: {err:?}"),
+        }
+    }
+}
+
+impl std::error::Error for TrackLoadError {}
+
+/// Summary of a successfully validated track file.
+#[derive(Clone, Debug)]
+pub struct TrackReport {
+    pub name: String,
+    pub rows: usize,
+    pub cols: usize,
+    /// Closed centreline length in world units.
+    pub loop_length: f32,
+    /// Number of tiles of each kind, keyed by `TilePart` name.
+    pub tile_counts: BTreeMap<String, usize>,
+    /// Non-fatal issues (e.g. road tiles the racing loop never visits).
+    pub warnings: Vec<String>,
+}
+
+/// Parses a track from RON text without touching the filesystem.
+pub fn parse_track(source: &str) -> Result<TrackFile, TrackLoadError> {
+    ron::from_str(source).map_err(|err| TrackLoadError::Parse(err.to_string()))
+}
+
+/// Reads and parses a track file.
+pub fn load_track_file(path: &Path) -> Result<TrackFile, TrackLoadError> {
+    let source = std::fs::read_to_string(path).map_err(TrackLoadError::Io)?;
+    parse_track(&source)
+}
+
+impl TrackFile {
+    /// Builds the tile grid, centred on the world origin like the built-in track.
+    pub fn to_grid(&self) -> TrackGrid {
+        let rows = self.tiles.len();
+        let cols = self.tiles.first().map(|row| row.len()).unwrap_or(0);
+        let origin = Vec2::new(
+            -(cols as f32 * self.tile_size) * 0.5,
+            (rows as f32 * self.tile_size) * 0.5,
+        );
+
+        let mut grid = TrackGrid::new(self.tiles.clone(), self.tile_size, origin);
+        grid.edge = self.edge;
+        grid.road_width = self.road_width;
+        grid.margins = self.margins;
+        for tag in &self.tags {
+            grid.tag_cell(tag.row, tag.col, tag.tag.clone());
+        }
+        grid
+    }
+}
+
+/// Checks grid shape, spawn, and edge connectivity, then builds the
+/// centreline from the spawn heading east.
+pub fn validate_track(track: &TrackFile) -> Result<TrackReport, TrackLoadError> {
+    let rows = track.tiles.len();
+    let cols = track.tiles.first().map(|row| row.len()).unwrap_or(0);
+    if rows == 0 || cols == 0 {
+        return Err(TrackLoadError::EmptyGrid);
+    }
+    if let Some((row, tiles)) = track
+        .tiles
+        .iter()
+        .enumerate()
+        .find(|(_, tiles)| tiles.len() != cols)
+    {
+        return Err(TrackLoadError::RaggedRow {
+            row,
+            expected: cols,
+            found: tiles.len(),
+        });
+    }
+    if !(track.tile_size.is_finite() && track.tile_size > 0.0) {
+        return Err(TrackLoadError::InvalidTileSize(track.tile_size));
+    }
+    if let Some(width) = track
+        .road_width
+        .filter(|width| !(width.is_finite() && *width > 0.0))
+    {
+        return Err(TrackLoadError::InvalidRoadWidth(width));
+    }
+    if let Some(margin) = track.margins.invalid_margin() {
+        return Err(TrackLoadError::InvalidMargin(margin));
+    }
+
+    let grid = track.to_grid();
+    let mut tile_counts = BTreeMap::new();
+    for &tile in track.tiles.iter().flatten() {
+        *tile_counts.entry(format!("{tile:?}")).or_insert(0) += 1;
+    }
+
+    match tile_counts.get("SpawnPoint").copied().unwrap_or(0) {
+        0 => return Err(TrackLoadError::MissingSpawn),
+        1 => {}
+        count => return Err(TrackLoadError::MultipleSpawns(count)),
+    }
+    check_connectivity(&grid)?;
+
+    let spawn_cell = grid.find_spawn_cell().ok_or(TrackLoadError::MissingSpawn)?;
+    let centerline =
+        TrackCenterline::build_closed_loop(&grid, spawn_cell, track.lap_direction.start_dir())
+            .map_err(TrackLoadError::Centerline)?;
+
+    let mut warnings = Vec::new();
+    let road_tiles = track.tiles.iter().flatten().filter(|t| t.is_road()).count();
+    let loop_tiles = loop_cell_count(&grid, &centerline);
+    if loop_tiles < road_tiles {
+        warnings.push(format!(
+            "{} of {road_tiles} road tiles are not on the racing loop",
+            road_tiles - loop_tiles
+        ));
+    }
+    for tag in &track.tags {
+        if !grid.tile_at(tag.row, tag.col).is_road() {
+            warnings.push(format!(
+                "tag \"{}\" at ({}, {}) is on a non-road cell",
+                tag.tag, tag.row, tag.col
+            ));
+        }
+    }
+
+    Ok(TrackReport {
+        name: track.name.clone(),
+        rows,
+        cols,
+        loop_length: centerline.total_length(),
+        tile_counts,
+        warnings,
+    })
+}
+
+/// Validates `track` and builds the [`Track`] component the game spawns.
+pub fn build_track(track: &TrackFile) -> Result<Track, TrackLoadError> {
+    validate_track(track)?;
+    let grid = track.to_grid();
+    let spawn_cell = grid.find_spawn_cell().ok_or(TrackLoadError::MissingSpawn)?;
+    let (spawn_position, _) = grid.find_spawn().ok_or(TrackLoadError::MissingSpawn)?;
+    let centerline =
+        TrackCenterline::build_closed_loop(&grid, spawn_cell, track.lap_direction.start_dir())
+            .map_err(TrackLoadError::Centerline)?;
+    Ok(Track {
+        grid,
+        spawn_position,
+        spawn_rotation: track.lap_direction.spawn_rotation(),
+        centerline: Some(centerline),
+    })
+}
+
+/// A track loaded for editing, whether or not it is driveable.
+pub struct LenientTrack {
+    pub name: String,
+    pub grid: TrackGrid,
+    /// Built only when the grid passes validation.
+    pub centerline: Option<TrackCenterline>,
+    /// Every problem found; empty when the track is driveable.
+    pub errors: Vec<TrackLoadError>,
+    pub lap_direction: LapDirection,
+}
+
+impl LenientTrack {
+    pub fn is_driveable(&self) -> bool {
+        self.centerline.is_some()
+    }
+
+    /// The drivable [`Track`], or `None` for an editor-only grid (no car
+    /// spawns on it).
+    pub fn into_track(self) -> Option<Track> {
+        let centerline = self.centerline?;
+        let (spawn_position, _) = self.grid.find_spawn()?;
+        Some(Track {
+            grid: self.grid,
+            spawn_position,
+            spawn_rotation: self.lap_direction.spawn_rotation(),
+            centerline: Some(centerline),
+        })
+    }
+
+    /// A [`Track`] for rendering and editing, driveable or not. Without a
+    /// centreline the progress, episode, and overlay systems no-op for it;
+    /// without a spawn tile the spawn is the grid centre.
+    pub fn into_display_track(self) -> Track {
+        let spawn_position = self.grid.find_spawn().map_or_else(
+            || self.grid.world_bounds().center(),
+            |(position, _)| position,
+        );
+        Track {
+            grid: self.grid,
+            spawn_position,
+            spawn_rotation: self.lap_direction.spawn_rotation(),
+            centerline: self.centerline,
+        }
+    }
+}
+
+/// Lenient counterpart of [`build_track`] for the editor.
+///
+/// Loads the tile grid even when validation fails: ragged rows are padded
+/// with `Empty`, and spawn, connectivity, and centreline problems are
+/// collected into [`LenientTrack::errors`] instead of aborting. The
+/// centreline is only built for a valid grid. Fails only when there is no
+/// grid to show (no tiles, or an unusable tile size).
+pub fn build_track_lenient(track: &TrackFile) -> Result<LenientTrack, TrackLoadError> {
+    let cols = track.tiles.iter().map(Vec::len).max().unwrap_or(0);
+    if track.tiles.is_empty() || cols == 0 {
+        return Err(TrackLoadError::EmptyGrid);
+    }
+    if !(track.tile_size.is_finite() && track.tile_size > 0.0) {
+        return Err(TrackLoadError::InvalidTileSize(track.tile_size));
+    }
+
+    let mut errors = Vec::new();
+    let mut padded = track.clone();
+    for (row, tiles) in padded.tiles.iter_mut().enumerate() {
+        if tiles.len() != cols {
+            errors.push(TrackLoadError::RaggedRow {
+                row,
+                expected: cols,
+                found: tiles.len(),
+            });
+            tiles.resize(cols, TilePart::Empty);
+        }
+    }
+    let grid = padded.to_grid();
+
+    let spawns = padded
+        .tiles
+        .iter()
+        .flatten()
+        .filter(|&&tile| tile == TilePart::SpawnPoint)
+        .count();
+    match spawns {
+        0 => errors.push(TrackLoadError::MissingSpawn),
+        1 => {}
+        count => errors.push(TrackLoadError::MultipleSpawns(count)),
+    }
+    errors.extend(connectivity_errors(&grid));
+
+    let mut centerline = None;
+    if errors.is_empty() {
+        match build_track(&padded) {
+            Ok(track) => centerline = track.centerline,
+            Err(err) => errors.push(err),
+        }
+    }
+
+    Ok(LenientTrack {
+        name: track.name.clone(),
+        grid,
+        centerline,
+        errors,
+        lap_direction: track.lap_direction,
+    })
+}
+
+/// Loads, validates, and summarises a single track file.
+pub fn validate_track_file(path: &Path) -> Result<TrackReport, TrackLoadError> {
+    validate_track(&load_track_file(path)?)
+}
+
+/// Validates every `.ron` file directly inside `dir`, sorted by path.
+pub fn validate_track_dir(
+    dir: &Path,
+) -> std::io::Result<Vec<(PathBuf, Result<TrackReport, TrackLoadError>)>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect();
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let result = validate_track_file(&path);
+            (path, result)
+        })
+        .collect())
+}
+
+/// Headless `maps <dir>` entry point: prints one line per track and returns
+/// the number of tracks that failed validation.
+pub fn run_maps_command(dir: &Path) -> usize {
+    let results = match validate_track_dir(dir) {
+        Ok(results) => results,
+        Err(err) => {
+            eprintln!("cannot read track directory {}: {err}", dir.display());
+            return 1;
+        }
+    };
+
+    let mut failures = 0;
+    for (path, result) in &results {
+        match result {
+            Ok(report) => {
+                println!(
+                    "ok    {}  \"{}\" {}x{} loop {:.0}px",
+                    path.display(),
+                    report.name,
+                    report.cols,
+                    report.rows,
+                    report.loop_length
+                );
+                let counts: Vec<String> = report
+                    .tile_counts
+                    .iter()
+                    .map(|(tile, count)| format!("{tile} {count}"))
+                    .collect();
+                println!("      tiles: {}", counts.join(", "));
+                for warning in &report.warnings {
+                    println!("      warning: {warning}");
+                }
+            }
+            Err(err) => {
+                failures += 1;
+                println!("FAIL  {}  {err}", path.display());
+            }
+        }
+    }
+    println!("{} track(s) checked, {failures} failed.", results.len());
+    failures
+}
+
+/// Every open edge must lead to an in-bounds tile whose opposite edge is open.
+fn check_connectivity(grid: &TrackGrid) -> Result<(), TrackLoadError> {
+    match connectivity_errors(grid).into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Every broken edge in the grid, in row-major order.
+fn connectivity_errors(grid: &TrackGrid) -> Vec<TrackLoadError> {
+    let mut errors = Vec::new();
+    for row in 0..grid.rows() {
+        for col in 0..grid.cols() {
+            let (north, south, east, west) = grid.tile_at(row, col).open_edges();
+            for (open, edge) in [
+                (north, GridDir::North),
+                (south, GridDir::South),
+                (east, GridDir::East),
+                (west, GridDir::West),
+            ] {
+                if open && !edge_connects(grid, row, col, edge) {
+                    errors.push(TrackLoadError::BrokenConnection { row, col, edge });
+                }
+            }
+        }
+    }
+    errors
+}
+
+fn edge_connects(grid: &TrackGrid, row: usize, col: usize, edge: GridDir) -> bool {
+    let (dr, dc) = edge.delta();
+    let (Some(next_row), Some(next_col)) = (row.checked_add_signed(dr), col.checked_add_signed(dc))
+    else {
+        return false;
+    };
+    if next_row >= grid.rows() || next_col >= grid.cols() {
+        return false;
+    }
+
+    let (north, south, east, west) = grid.tile_at(next_row, next_col).open_edges();
+    match edge.opposite() {
+        GridDir::North => north,
+        GridDir::South => south,
+        GridDir::East => east,
+        GridDir::West => west,
+    }
+}
+
+/// Counts the distinct cells the centreline passes through.
+fn loop_cell_count(grid: &TrackGrid, centerline: &TrackCenterline) -> usize {
+    let samples = (centerline.total_length() / (grid.tile_size * 0.25)).ceil() as usize;
+    let cells: std::collections::HashSet<(usize, usize)> = (0..samples.max(1))
+        .filter_map(|i| {
+            let s = centerline.total_length() * i as f32 / samples.max(1) as f32;
+            grid.world_to_cell(centerline.point_at_s(s))
+        })
+        .collect();
+    cells.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TrackLoadError, build_track, build_track_lenient, parse_track, validate_track_file,
+    };
+    use crate::maps::centerline::{GridDir, LapDirection};
+    use crate::maps::track::Track;
+    use bevy::prelude::Vec2;
+    use std::path::Path;
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets/tracks")
+            .join(name)
+    }
+
+    #[test]
+    fn known_good_track_produces_a_report() {
+        let report = validate_track_file(&fixture("ring.ron")).expect("ring track is valid");
+
+        assert_eq!(report.name, "Ring");
+        assert_eq!((report.rows, report.cols), (3, 3));
+        assert!(report.loop_length > 700.0 && report.loop_length < 730.0);
+        assert_eq!(report.tile_counts.get("SpawnPoint"), Some(&1));
+        assert_eq!(report.tile_counts.get("Empty"), Some(&1));
+        assert_eq!(report.tile_counts.values().sum::<usize>(), 9);
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn known_broken_track_reports_the_broken_edge() {
+        let err = validate_track_file(&fixture("fixtures/broken_ring.ron"))
+            .expect_err("broken ring must fail validation");
+
+        assert!(
+            matches!(
+                err,
+                TrackLoadError::BrokenConnection {
+                    row: 1,
+                    col: 2,
+                    edge: GridDir::South
+                }
+            ),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn reversed_lap_direction_reverses_the_centreline() {
+        let ring = |direction: &str| {
+            let file = parse_track(&format!(
+                "(name: \"Ring\", tile_size: 100.0, lap_direction: {direction}, tiles: [
+                    [CornerNW, SpawnPoint, CornerNE],
+                    [StraightV, Empty, StraightV],
+                    [CornerSW, StraightH, CornerSE],
+                ])"
+            ))
+            .expect("parses");
+            build_track(&file).expect("valid ring")
+        };
+        let forward = ring("Forward");
+        let reverse = ring("Reverse");
+
+        let centerline = |track: &Track| track.centerline.clone().expect("driveable");
+        let (fwd, rev) = (&centerline(&forward).points, &centerline(&reverse).points);
+        assert_eq!(fwd.len(), rev.len());
+        let n = fwd.len();
+        for (i, point) in rev.iter().enumerate() {
+            assert!(point.distance(fwd[(n + 1 - i) % n]) < 1e-3);
+        }
+        assert!(
+            (centerline(&forward).total_length() - centerline(&reverse).total_length()).abs()
+                < 1e-3
+        );
+
+        for track in [&forward, &reverse] {
+            let heading = Vec2::from_angle(track.spawn_rotation);
+            let at_spawn = centerline(track).project(track.spawn_position);
+            let ahead = centerline(track).project(track.spawn_position + heading * 20.0);
+            assert!(ahead.s - at_spawn.s > 19.0);
+            assert!(at_spawn.tangent.dot(heading) > 0.99);
+        }
+        assert_eq!(
+            LapDirection::Reverse.start_dir(),
+            LapDirection::Forward.start_dir().opposite()
+        );
+    }
+
+    #[test]
+    fn disconnected_grid_loads_leniently_but_not_strictly() {
+        let file = parse_track(
+            "(name: \"Draft\", tile_size: 100.0, tiles: [
+                [CornerNW, SpawnPoint, StraightH],
+                [StraightV, Empty, Empty],
+                [CornerSW, StraightH, CornerSE],
+            ])",
+        )
+        .expect("parses");
+
+        assert!(matches!(
+            build_track(&file),
+            Err(TrackLoadError::BrokenConnection { .. })
+        ));
+
+        let draft = build_track_lenient(&file).expect("loads leniently");
+        assert!(draft.centerline.is_none());
+        assert!(!draft.is_driveable());
+        assert_eq!((draft.grid.rows(), draft.grid.cols()), (3, 3));
+        assert!(draft.errors.len() >= 2, "{:?}", draft.errors);
+        assert!(
+            draft
+                .errors
+                .iter()
+                .all(|err| matches!(err, TrackLoadError::BrokenConnection { .. }))
+        );
+        let display = build_track_lenient(&file).unwrap().into_display_track();
+        assert!(display.centerline.is_none());
+        assert_eq!(display.grid.rows(), 3);
+        assert!(draft.into_track().is_none());
+
+        let ring = parse_track(
+            "(name: \"Ring\", tile_size: 100.0, tiles: [
+                [CornerNW, SpawnPoint, CornerNE],
+                [StraightV, Empty, StraightV],
+                [CornerSW, StraightH, CornerSE],
+            ])",
+        )
+        .expect("parses");
+        let lenient = build_track_lenient(&ring).expect("loads");
+        assert!(lenient.errors.is_empty());
+        assert!(lenient.into_track().is_some());
+    }
+}
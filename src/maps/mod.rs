@@ -1,7 +1,13 @@
 pub mod centerline;
+pub mod coords;
+#[cfg(test)]
+pub(crate) mod fixtures;
 pub mod grid;
+pub mod loader;
 pub mod monaco;
 pub mod parts;
+pub mod preview;
+pub mod raceline;
 pub mod track;
 
 pub use monaco::MonacoPlugin;
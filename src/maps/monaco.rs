@@ -22,56 +22,74 @@ impl Plugin for MonacoPlugin {
 /// World-space side length of each grid cell in pixels.
 const TILE_SIZE: f32 = 100.0;
 
-/// Builds and spawns the tile grid, derives spawn data, and emits all visual
-/// sprites. The resulting `Track` entity is consumed by collision and game systems.
+/// Spawns the Sepang `Track` entity and emits all visual sprites. The entity
+/// is consumed by collision and game systems.
 fn spawn_track(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    let tiles = build_tiles();
-
-    let rows = tiles.len();
-    let cols = tiles[0].len();
+    let track = build_monaco_track();
 
-    // Centre the 14×9 grid in the 1600×900 window.
-    let origin = Vec2::new(
-        -(cols as f32 * TILE_SIZE) * 0.5,
-        (rows as f32 * TILE_SIZE) * 0.5,
+    info!(
+        "Sepang track spawned. Grid {}×{}. Car spawn ({:.0},{:.0}) rot {:.2}.",
+        track.grid.cols(),
+        track.grid.rows(),
+        track.spawn_position.x,
+        track.spawn_position.y,
+        track.spawn_rotation
     );
+    if let Some(centerline) = &track.centerline {
+        info!("Centreline length: {:.0}px.", centerline.total_length());
+    }
 
-    let grid = TrackGrid::new(tiles, TILE_SIZE, origin);
+    render_tile_grid(&mut commands, &track.grid, &mut meshes, &mut materials);
+    render_finish_line(&mut commands, &track.grid);
+
+    commands.spawn(track);
+}
+
+/// Builds the complete Sepang `Track`: tagged grid, spawn pose and closed
+/// centreline. Needs no ECS world, so tests and the headless API use it
+/// directly.
+pub fn build_monaco_track() -> Track {
+    let grid = build_grid();
 
     let spawn_cell = grid
         .find_spawn_cell()
         .expect("Track grid must contain exactly one SpawnPoint tile.");
 
-    let (spawn_pos, spawn_rot) = grid
+    let (spawn_position, spawn_rotation) = grid
         .find_spawn()
         .expect("Track grid must contain exactly one SpawnPoint tile.");
 
     let centerline = TrackCenterline::build_closed_loop(&grid, spawn_cell, GridDir::East)
         .expect("Track grid connectivity must form a single closed loop.");
 
-    info!(
-        "Sepang track spawned. Grid {}×{}. Car spawn ({:.0},{:.0}) rot {:.2}.",
-        grid.cols(),
-        grid.rows(),
-        spawn_pos.x,
-        spawn_pos.y,
-        spawn_rot
-    );
-    info!("Centreline length: {:.0}px.", centerline.total_length());
+    Track {
+        grid,
+        spawn_position,
+        spawn_rotation,
+        centerline: Some(centerline),
+    }
+}
+
+/// Builds the tagged Sepang grid, centred in the 1600×900 window.
+pub(crate) fn build_grid() -> TrackGrid {
+    let tiles = build_tiles();
 
-    render_tile_grid(&mut commands, &grid, &mut meshes, &mut materials);
-    render_finish_line(&mut commands, &grid);
+    let rows = tiles.len();
+    let cols = tiles[0].len();
 
-    commands.spawn(Track {
-        grid,
-        spawn_position: spawn_pos,
-        spawn_rotation: spawn_rot,
-        centerline,
-    });
+    // Centre the 14×9 grid in the 1600×900 window.
+    let origin = Vec2::new(
+        -(cols as f32 * TILE_SIZE) * 0.5,
+        (rows as f32 * TILE_SIZE) * 0.5,
+    );
+
+    let mut grid = TrackGrid::new(tiles, TILE_SIZE, origin);
+    tag_regions(&mut grid);
+    grid
 }
 
 /// Defines the Sepang-inspired tile layout on a 14-column × 9-row grid.
@@ -166,6 +184,22 @@ fn build_tiles() -> Vec<Vec<TilePart>> {
     ]
 }
 
+/// Annotates the two long straights so telemetry and reward shaping can key
+/// off them by name. Only straight tiles are tagged; the corners closing each
+/// run belong to the turns.
+fn tag_regions(grid: &mut TrackGrid) {
+    for (row, cols, tag) in [(1, 1..=12, "main_straight"), (7, 1..=8, "back_straight")] {
+        for col in cols {
+            if matches!(
+                grid.tile_at(row, col),
+                TilePart::StraightH | TilePart::SpawnPoint
+            ) {
+                grid.tag_cell(row, col, tag);
+            }
+        }
+    }
+}
+
 /// Renders the start/finish line as a white vertical stripe.
 ///
 /// Placed at the western boundary of column 3 — the first `StraightH` tile
@@ -175,11 +209,11 @@ fn build_tiles() -> Vec<Vec<TilePart>> {
 fn render_finish_line(commands: &mut Commands, grid: &TrackGrid) {
     let finish_col = 4usize;
     let finish_row = 1usize;
-    let tile_center = grid.cell_center(finish_row, finish_col);
+    let metrics = grid.metrics();
 
     // Western edge of col 3 = eastern edge of col 2 (the NW corner).
-    let x = tile_center.x - grid.tile_size * 0.5;
-    let y = tile_center.y;
+    let x = metrics.column_edge_x(finish_col as isize);
+    let y = metrics.cell_center(finish_row, finish_col).y;
 
     commands.spawn((
         Sprite {
@@ -190,3 +224,59 @@ fn render_finish_line(commands: &mut Commands, grid: &TrackGrid) {
         Transform::from_xyz(x, y, 2.0),
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_monaco_track_has_a_closed_centreline_and_an_on_road_spawn() {
+        let track = build_monaco_track();
+        let centerline = track.centerline.as_ref().expect("Monaco has a centreline");
+
+        // Closed: every segment, including the wrap from the last point back
+        // to the first, stays on the road and spans at most one tile (straights
+        // place one point per tile centre).
+        let points = &centerline.points;
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            assert!(a.distance(b) <= TILE_SIZE + 1e-3, "gap after point {i}");
+            assert!(
+                track.grid.is_road_at(a.lerp(b, 0.5)),
+                "segment {i} off road"
+            );
+        }
+        let lap: f32 = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(a, b)| a.distance(*b))
+            .sum();
+        assert!((lap - centerline.total_length()).abs() < 1e-2);
+
+        assert!(track.grid.is_road_at(track.spawn_position));
+        let projection = centerline.project(track.spawn_position);
+        assert!(projection.distance < 1.0, "{}", projection.distance);
+        let heading = Vec2::from_angle(track.spawn_rotation);
+        assert!(heading.dot(projection.tangent) > 0.99);
+    }
+
+    #[test]
+    fn straight_tags_cover_only_straight_tiles() {
+        let grid = build_grid();
+        for (tag, expected) in [("main_straight", 10), ("back_straight", 6)] {
+            let cells: Vec<(usize, usize)> = grid
+                .tags
+                .iter()
+                .filter(|(_, tags)| tags.iter().any(|t| t == tag))
+                .map(|(&cell, _)| cell)
+                .collect();
+            assert_eq!(cells.len(), expected, "{tag}");
+            assert!(
+                cells
+                    .iter()
+                    .all(|&(row, col)| !grid.tile_at(row, col).is_corner()),
+                "{tag}"
+            );
+        }
+    }
+}
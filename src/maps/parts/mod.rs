@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// Individual tile types that compose a grid-based race track.
 ///
 /// Each tile occupies one square cell in the grid and defines which edges are
@@ -27,9 +29,10 @@
 /// TJunctionW    | N, S, E, _   (stem closes W)
 /// Crossroads    | N, S, E, W   (fully open)
 /// SpawnPoint    | _, _, E, W   (same as StraightH, marks spawn cell)
+/// BoostPadH     | _, _, E, W   (same as StraightH, boosts the car)
+/// BoostPadV     | N, S, _, _   (same as StraightV, boosts the car)
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TilePart {
     /// No road surface. The car is off-track if it occupies this cell.
     Empty,
@@ -79,6 +82,16 @@ pub enum TilePart {
     /// exact centre of this tile facing east, with the finish line rendered
     /// at the tile boundary one position to the west.
     SpawnPoint,
+
+    /// Functionally identical to `StraightH` (open west and east).
+    ///
+    /// A car entering this cell gets a short forward boost; see
+    /// `BoostPadConfig`.
+    BoostPadH,
+
+    /// Functionally identical to `StraightV` (open north and south), with the
+    /// same boost as `BoostPadH`.
+    BoostPadV,
 }
 
 impl TilePart {
@@ -100,7 +113,9 @@ impl TilePart {
             TilePart::Empty => (false, false, false, false),
             TilePart::StraightH => (false, false, true, true),
             TilePart::SpawnPoint => (false, false, true, true),
+            TilePart::BoostPadH => (false, false, true, true),
             TilePart::StraightV => (true, true, false, false),
+            TilePart::BoostPadV => (true, true, false, false),
             TilePart::CornerNW => (false, true, true, false),
             TilePart::CornerNE => (false, true, false, true),
             TilePart::CornerSW => (true, false, true, false),
@@ -113,6 +128,11 @@ impl TilePart {
         }
     }
 
+    /// Returns `true` if a car entering this tile is boosted.
+    pub fn is_boost_pad(self) -> bool {
+        matches!(self, TilePart::BoostPadH | TilePart::BoostPadV)
+    }
+
     /// Returns `true` if this tile type uses a curved arc wall when rendered.
     ///
     /// Corner tiles render a quarter-circle outer arc instead of two straight
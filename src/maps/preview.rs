@@ -0,0 +1,221 @@
+//! Static track thumbnails rasterised on the CPU.
+//!
+//! [`render_track_preview`] draws a [`Track`] into an RGBA buffer without a
+//! window, GPU, or running app, for documentation and track-selection UIs.
+
+use std::io;
+use std::path::Path;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::maps::track::Track;
+
+pub const PREVIEW_BACKGROUND: [u8; 4] = [18, 20, 24, 255];
+pub const PREVIEW_ROAD: [u8; 4] = [70, 74, 82, 255];
+pub const PREVIEW_WALL: [u8; 4] = [235, 235, 235, 255];
+pub const PREVIEW_CENTERLINE: [u8; 4] = [40, 220, 90, 255];
+pub const PREVIEW_SPAWN: [u8; 4] = [255, 200, 40, 255];
+
+/// Spawn marker radius in pixels.
+const SPAWN_RADIUS_PX: f32 = 3.0;
+
+/// A rasterised track: `width * height` tightly packed RGBA8 pixels, top
+/// row first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackPreview {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl TrackPreview {
+    /// Colour of pixel `(x, y)`; `None` outside the image.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        self.rgba[start..start + 4].try_into().ok()
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: [u8; 4]) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        self.rgba[start..start + 4].copy_from_slice(&color);
+    }
+
+    /// Writes the preview as a PNG.
+    pub fn write_png(&self, path: &Path) -> io::Result<()> {
+        let image = Image::new(
+            Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            self.rgba.clone(),
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        let dynamic = image.try_into_dynamic().map_err(io::Error::other)?;
+        dynamic.save(path).map_err(io::Error::other)
+    }
+}
+
+/// Maps the grid's world bounds onto the image, uniformly scaled and
+/// centred so the track keeps its aspect ratio.
+struct PreviewFrame {
+    origin: Vec2,
+    scale: f32,
+    offset: Vec2,
+}
+
+impl PreviewFrame {
+    fn new(track: &Track, width: u32, height: u32) -> Self {
+        let grid = &track.grid;
+        let size = Vec2::new(grid.cols() as f32, grid.rows() as f32) * grid.tile_size;
+        let scale = (width as f32 / size.x.max(1e-3)).min(height as f32 / size.y.max(1e-3));
+        let offset = (Vec2::new(width as f32, height as f32) - size * scale) * 0.5;
+        Self {
+            origin: grid.origin,
+            scale,
+            offset,
+        }
+    }
+
+    /// World point under the centre of pixel `(x, y)`.
+    fn to_world(&self, x: u32, y: u32) -> Vec2 {
+        let pixel = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - self.offset;
+        self.origin + Vec2::new(pixel.x, -pixel.y) / self.scale
+    }
+
+    /// Pixel-space position of `world`; `x` right, `y` down.
+    fn to_pixel(&self, world: Vec2) -> Vec2 {
+        let local = world - self.origin;
+        self.offset + Vec2::new(local.x, -local.y) * self.scale
+    }
+}
+
+/// Rasterises `track` into a `width` × `height` preview: road surface,
+/// walls along every road edge, the centreline (when the track has one),
+/// and the spawn pose as a dot with a heading tick.
+pub fn render_track_preview(track: &Track, width: u32, height: u32) -> TrackPreview {
+    let mut preview = TrackPreview {
+        width,
+        height,
+        rgba: PREVIEW_BACKGROUND.repeat(width as usize * height as usize),
+    };
+    if width == 0 || height == 0 {
+        return preview;
+    }
+    let frame = PreviewFrame::new(track, width, height);
+    let grid = &track.grid;
+
+    let road: Vec<bool> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let world = frame.to_world(x, y);
+            grid.world_to_cell(world).is_some() && grid.is_road_at(world)
+        })
+        .collect();
+    let is_road = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && x < width as i64
+            && y < height as i64
+            && road[y as usize * width as usize + x as usize]
+    };
+
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            let color = if is_road(x, y) {
+                PREVIEW_ROAD
+            } else if is_road(x - 1, y)
+                || is_road(x + 1, y)
+                || is_road(x, y - 1)
+                || is_road(x, y + 1)
+            {
+                PREVIEW_WALL
+            } else {
+                continue;
+            };
+            preview.set(x, y, color);
+        }
+    }
+
+    if let Some(centerline) = &track.centerline {
+        let points = &centerline.points;
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            draw_line(
+                &mut preview,
+                frame.to_pixel(a),
+                frame.to_pixel(b),
+                PREVIEW_CENTERLINE,
+            );
+        }
+    }
+
+    let spawn = frame.to_pixel(track.spawn_position);
+    let radius = SPAWN_RADIUS_PX.max(1.0);
+    let reach = radius.ceil() as i64;
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            if ((dx * dx + dy * dy) as f32) <= radius * radius {
+                preview.set(
+                    spawn.x.floor() as i64 + dx,
+                    spawn.y.floor() as i64 + dy,
+                    PREVIEW_SPAWN,
+                );
+            }
+        }
+    }
+    let heading = Vec2::from_angle(track.spawn_rotation);
+    let tip = spawn + Vec2::new(heading.x, -heading.y) * radius * 3.0;
+    draw_line(&mut preview, spawn, tip, PREVIEW_SPAWN);
+
+    preview
+}
+
+/// Draws a one-pixel line by sampling at most a pixel apart.
+fn draw_line(preview: &mut TrackPreview, a: Vec2, b: Vec2, color: [u8; 4]) {
+    let steps = a.distance(b).ceil().max(1.0) as usize;
+    for step in 0..=steps {
+        let point = a.lerp(b, step as f32 / steps as f32);
+        preview.set(point.x.floor() as i64, point.y.floor() as i64, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PREVIEW_BACKGROUND, PREVIEW_ROAD, PREVIEW_SPAWN, render_track_preview};
+    use crate::maps::monaco::build_monaco_track;
+
+    #[test]
+    fn previews_have_the_requested_size_and_draw_the_road() {
+        let track = build_monaco_track();
+        let preview = render_track_preview(&track, 280, 200);
+
+        assert_eq!((preview.width, preview.height), (280, 200));
+        assert_eq!(preview.rgba.len(), 280 * 200 * 4);
+
+        let pixels: Vec<[u8; 4]> = preview
+            .rgba
+            .chunks_exact(4)
+            .map(|pixel| [pixel[0], pixel[1], pixel[2], pixel[3]])
+            .collect();
+        let count = |color: [u8; 4]| pixels.iter().filter(|pixel| **pixel == color).count();
+        assert_ne!(PREVIEW_ROAD, PREVIEW_BACKGROUND);
+        assert!(count(PREVIEW_ROAD) > pixels.len() / 10, "too little road");
+        assert!(count(PREVIEW_BACKGROUND) > 0, "no background");
+        assert!(count(PREVIEW_SPAWN) > 0, "no spawn marker");
+
+        // The grid's empty border row stays background.
+        assert_eq!(preview.pixel(140, 2), Some(PREVIEW_BACKGROUND));
+        assert_eq!(preview.pixel(280, 0), None);
+    }
+}
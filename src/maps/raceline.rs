@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::game::episode::EpisodeConfig;
+use crate::maps::centerline::TrackCenterline;
+use crate::maps::track::Track;
+
+/// Spacing in world units of the raceline's resampled points.
+const RACELINE_SPACING: f32 = 10.0;
+
+/// Smoothing passes over the resampled points.
+const RACELINE_ITERATIONS: usize = 400;
+
+/// Closed target line through the track, derived from the centreline.
+///
+/// Built by repeatedly pulling each point towards the midpoint of its
+/// neighbours while keeping it within `max_offset` of where it started on
+/// the centreline. The line straightens out and cuts towards the inside of
+/// every corner, a cheap stand-in for a minimum-curvature racing line.
+#[derive(Resource, Clone, Debug)]
+pub struct Raceline {
+    pub line: TrackCenterline,
+}
+
+/// Smooths `centerline` into a raceline whose points stay within
+/// `max_offset` of their centreline origins.
+pub fn build_raceline(centerline: &TrackCenterline, max_offset: f32) -> Raceline {
+    let total = centerline.total_length();
+    let count = ((total / RACELINE_SPACING).ceil() as usize).max(3);
+    let anchors: Vec<Vec2> = (0..count)
+        .map(|i| centerline.point_at_s(total * i as f32 / count as f32))
+        .collect();
+    let max_offset = max_offset.max(0.0);
+
+    let mut points = anchors.clone();
+    let mut next = points.clone();
+    for _ in 0..RACELINE_ITERATIONS {
+        for (i, point) in next.iter_mut().enumerate() {
+            let previous = points[(i + count - 1) % count];
+            let following = points[(i + 1) % count];
+            let smoothed = (previous + following) * 0.5;
+            let offset = (smoothed - anchors[i]).clamp_length_max(max_offset);
+            *point = anchors[i] + offset;
+        }
+        std::mem::swap(&mut points, &mut next);
+    }
+
+    Raceline {
+        line: TrackCenterline::from_points(points).expect("at least three raceline points"),
+    }
+}
+
+/// Builds the [`Raceline`] resource from the spawned track.
+pub fn setup_raceline_system(
+    mut commands: Commands,
+    config: Res<EpisodeConfig>,
+    track_query: Query<&Track>,
+) {
+    let Ok(track) = track_query.single() else {
+        return;
+    };
+    let Some(centerline) = &track.centerline else {
+        return;
+    };
+    let raceline = build_raceline(centerline, config.raceline_max_offset);
+    info!(
+        "Raceline ready: {:.0}px against a {:.0}px centreline.",
+        raceline.line.total_length(),
+        centerline.total_length()
+    );
+    commands.insert_resource(raceline);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_raceline;
+    use crate::maps::monaco::build_monaco_track;
+
+    #[test]
+    fn the_raceline_cuts_corners_but_stays_on_the_road() {
+        let track = build_monaco_track();
+        let centerline = track.centerline.as_ref().expect("centreline");
+        let raceline = build_raceline(centerline, 30.0);
+
+        assert!(raceline.line.total_length() < centerline.total_length() * 0.99);
+        let mut widest = 0.0_f32;
+        for &point in &raceline.line.points {
+            assert!(track.grid.is_road_at(point), "{point:?} off road");
+            let offset = centerline.project(point).distance;
+            assert!(offset <= 30.0 + 1e-3, "{offset}");
+            widest = widest.max(offset);
+        }
+        assert!(widest > 20.0, "the line never leaves the centre: {widest}");
+    }
+}
@@ -23,6 +23,9 @@ pub struct Track {
     /// 0.0 means facing east (+X). Derived from `SpawnPoint` connectivity.
     pub spawn_rotation: f32,
 
-    /// Closed centreline polyline used for progress measurement.
-    pub centerline: TrackCenterline,
+    /// Closed centreline polyline used for progress measurement. `None` for
+    /// a track that can be rendered and edited but not driven for progress
+    /// (a lenient load whose loop does not close); progress, episode,
+    /// observation, and overlay systems then no-op for it.
+    pub centerline: Option<TrackCenterline>,
 }
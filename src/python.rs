@@ -0,0 +1,373 @@
+//! Python bindings for [`HeadlessEnv`] (feature `python`).
+//!
+//! Builds the `neurodrive` extension module with a Gymnasium-style
+//! `NeuroDriveEnv`: `reset(seed)` returns `(obs, info)`, `step(action)`
+//! returns `(obs, reward, terminated, truncated, info)`. Observations are
+//! `float32` NumPy arrays that take ownership of the Rust vector, so no copy
+//! is made. The GIL is released while the simulation ticks.
+//!
+//! `frame_skip=K` applies each action for `K` ticks and returns their summed
+//! reward, stopping early on the tick that ends an episode.
+//!
+//! Like the TCP server, the env auto-resets: when a step ends an episode the
+//! returned observation already belongs to the next one, and a following
+//! `reset()` without a seed returns it unchanged.
+
+use std::path::PathBuf;
+use std::thread::{self, ThreadId};
+
+use numpy::{IntoPyArray, PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::agent::action::CarAction;
+use crate::agent::spaces::FeatureSpec;
+use crate::config::AppConfig;
+use crate::eval::env::{EpisodeOutcome, FrameSkipStep, HeadlessEnv};
+use crate::game::episode::EpisodeEndReason;
+use crate::maps::loader::{TrackFile, build_track, load_track_file};
+use crate::maps::monaco::build_monaco_track;
+use crate::maps::track::Track;
+
+/// An action decoded from Python, before it reaches the env.
+enum PyAction {
+    Continuous(CarAction),
+    Discrete(usize),
+}
+
+/// [`HeadlessEnv`] that may cross into `allow_threads`.
+///
+/// `allow_threads` asks for a `Send` closure because it cannot tell that it
+/// runs the closure on the calling thread. `HeadlessEnv` is not `Send`
+/// because its `App` holds boxed callbacks (the runner and sub-app extract
+/// functions) that are not; the world itself is `Send`.
+struct SendEnv {
+    env: HeadlessEnv,
+    /// Thread that built the env; every use is checked against it.
+    owner: ThreadId,
+}
+
+// SAFETY: a `SendEnv` is only ever touched on the thread that built it:
+// - it lives inside `NeuroDriveEnv`, which is `unsendable`, so PyO3 panics
+//   before handing the object to any other thread;
+// - `allow_threads` releases the GIL but runs its closure synchronously on
+//   the calling thread, and the closure borrows the env only for that call;
+// - `SendEnv` never moves into a spawned thread or a channel.
+// So the non-`Send` callbacks in the `App` are never reached from another
+// thread. `owner` turns a violation of this invariant into a panic.
+unsafe impl Send for SendEnv {}
+
+impl SendEnv {
+    fn new(env: HeadlessEnv) -> Self {
+        Self {
+            env,
+            owner: thread::current().id(),
+        }
+    }
+
+    fn env(&self) -> &HeadlessEnv {
+        assert_eq!(
+            thread::current().id(),
+            self.owner,
+            "env used off its thread"
+        );
+        &self.env
+    }
+
+    fn env_mut(&mut self) -> &mut HeadlessEnv {
+        assert_eq!(
+            thread::current().id(),
+            self.owner,
+            "env used off its thread"
+        );
+        &mut self.env
+    }
+
+    fn step(&mut self, action: PyAction, frame_skip: u32) -> PyResult<FrameSkipStep> {
+        let action = match action {
+            PyAction::Continuous(action) => action,
+            PyAction::Discrete(index) => self
+                .env()
+                .decode_discrete(index)
+                .map_err(|err| PyValueError::new_err(err.to_string()))?,
+        };
+        Ok(self.env_mut().step_frames(action, frame_skip))
+    }
+
+    fn reset_current(&mut self) {
+        self.env_mut().reset_episode();
+    }
+}
+
+/// The headless NeuroDrive env for one car on one track.
+#[pyclass(name = "NeuroDriveEnv", module = "neurodrive", unsendable)]
+pub struct NeuroDriveEnv {
+    env: Option<SendEnv>,
+    /// Track to rebuild from; `None` is the built-in Sepang track.
+    track: Option<TrackFile>,
+    config: AppConfig,
+    seed: u64,
+    /// Ticks each `step` action is applied for.
+    frame_skip: u32,
+    /// No tick has run since the env was built or an episode ended.
+    at_episode_start: bool,
+}
+
+impl NeuroDriveEnv {
+    fn build_track(&self) -> PyResult<Track> {
+        match &self.track {
+            Some(file) => build_track(file).map_err(|err| PyValueError::new_err(err.to_string())),
+            None => Ok(build_monaco_track()),
+        }
+    }
+
+    fn rebuild(&mut self, seed: u64) -> PyResult<()> {
+        let track = self.build_track()?;
+        self.env = Some(SendEnv::new(HeadlessEnv::new(track, &self.config, seed)));
+        self.seed = seed;
+        self.at_episode_start = true;
+        Ok(())
+    }
+
+    fn env_mut(&mut self) -> PyResult<&mut SendEnv> {
+        self.env
+            .as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("env is closed"))
+    }
+
+    fn env_ref(&self) -> PyResult<&SendEnv> {
+        self.env
+            .as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("env is closed"))
+    }
+
+    fn decode_action(&self, action: &Bound<'_, PyAny>) -> PyResult<PyAction> {
+        let values: Vec<f32> = match action.extract::<PyReadonlyArray1<f32>>() {
+            Ok(array) => array.as_array().to_vec(),
+            Err(_) => match action.extract::<Vec<f32>>() {
+                Ok(values) => values,
+                Err(_) => vec![action.extract::<f32>()?],
+            },
+        };
+        let discrete = self
+            .env_ref()?
+            .env()
+            .action_space()
+            .discrete_count
+            .is_some();
+        match (discrete, values.as_slice()) {
+            (true, [index]) if *index >= 0.0 && index.fract() == 0.0 => {
+                Ok(PyAction::Discrete(*index as usize))
+            }
+            (false, [steering, throttle]) => Ok(PyAction::Continuous(CarAction {
+                steering: *steering,
+                throttle: *throttle,
+            })),
+            (true, _) => Err(PyValueError::new_err(
+                "discrete mode expects one non-negative integer action index",
+            )),
+            (false, _) => Err(PyValueError::new_err(
+                "continuous mode expects [steering, throttle]",
+            )),
+        }
+    }
+
+    fn observation<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let values = self.env_mut()?.env_mut().observation().values;
+        Ok(values.into_pyarray(py))
+    }
+
+    fn info<'py>(
+        &mut self,
+        py: Python<'py>,
+        outcome: Option<&EpisodeOutcome>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let seed = self.seed;
+        let env = self.env_mut()?.env_mut();
+        let info = PyDict::new(py);
+        info.set_item("seed", seed)?;
+        info.set_item("episode_seed", env.episode_rng().seed)?;
+        info.set_item("progress", env.progress().fraction)?;
+        if let Some(outcome) = outcome {
+            info.set_item("episode", outcome.episode)?;
+            info.set_item("end_reason", outcome.end_reason.as_str())?;
+            info.set_item("episode_ticks", outcome.ticks)?;
+            info.set_item("episode_return", outcome.episode_return)?;
+            info.set_item("best_progress", outcome.best_progress_fraction)?;
+        }
+        Ok(info)
+    }
+}
+
+#[pymethods]
+impl NeuroDriveEnv {
+    /// `track_path` is a RON track file (default: the built-in Sepang
+    /// track); `config_path` a RON `AppConfig` (default: built-in defaults);
+    /// `frame_skip` the ticks each action is held for (at least 1).
+    #[new]
+    #[pyo3(signature = (track_path=None, config_path=None, seed=0, frame_skip=1))]
+    fn new(
+        track_path: Option<PathBuf>,
+        config_path: Option<PathBuf>,
+        seed: u64,
+        frame_skip: u32,
+    ) -> PyResult<Self> {
+        if frame_skip == 0 {
+            return Err(PyValueError::new_err("frame_skip must be at least 1"));
+        }
+        let track = track_path
+            .map(|path| load_track_file(&path))
+            .transpose()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        let config = match config_path {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path).map_err(|err| {
+                    PyIOError::new_err(format!("cannot read {}: {err}", path.display()))
+                })?;
+                AppConfig::from_ron(&text).map_err(|err| PyValueError::new_err(err.to_string()))?
+            }
+            None => AppConfig::default(),
+        };
+
+        let mut env = Self {
+            env: None,
+            track,
+            config,
+            seed,
+            frame_skip,
+            at_episode_start: true,
+        };
+        env.rebuild(seed)?;
+        Ok(env)
+    }
+
+    /// Starts a new episode and returns `(obs, info)`. A seed rebuilds the
+    /// env so the run replays exactly; without one the current episode is
+    /// cut short in place, without a tick, unless it has not started yet.
+    #[pyo3(signature = (seed=None))]
+    fn reset<'py>(
+        &mut self,
+        py: Python<'py>,
+        seed: Option<u64>,
+    ) -> PyResult<(Bound<'py, PyArray1<f32>>, Bound<'py, PyDict>)> {
+        match seed {
+            Some(seed) => self.rebuild(seed)?,
+            None if self.env.is_none() => self.rebuild(self.seed)?,
+            None if !self.at_episode_start => {
+                let env = self.env_mut()?;
+                py.allow_threads(|| env.reset_current());
+                self.at_episode_start = true;
+            }
+            None => {}
+        }
+        Ok((self.observation(py)?, self.info(py, None)?))
+    }
+
+    /// Advances `frame_skip` fixed ticks with one action and returns their
+    /// summed reward. `action` is `[steering, throttle]` in continuous mode
+    /// or an action index in discrete mode.
+    #[allow(clippy::type_complexity)]
+    fn step<'py>(
+        &mut self,
+        py: Python<'py>,
+        action: &Bound<'py, PyAny>,
+    ) -> PyResult<(
+        Bound<'py, PyArray1<f32>>,
+        f32,
+        bool,
+        bool,
+        Bound<'py, PyDict>,
+    )> {
+        let action = self.decode_action(action)?;
+        let frame_skip = self.frame_skip;
+        let env = self.env_mut()?;
+        let FrameSkipStep {
+            outcome, reward, ..
+        } = py.allow_threads(|| env.step(action, frame_skip))?;
+        let end_reason = outcome.map(|outcome| outcome.end_reason);
+        self.at_episode_start = outcome.is_some();
+
+        let terminated = matches!(
+            end_reason,
+            Some(EpisodeEndReason::Crash | EpisodeEndReason::LapComplete)
+        );
+        let truncated = end_reason == Some(EpisodeEndReason::Timeout);
+        Ok((
+            self.observation(py)?,
+            reward,
+            terminated,
+            truncated,
+            self.info(py, outcome.as_ref())?,
+        ))
+    }
+
+    /// `{"shape", "low", "high", "names"}` of the observation vector.
+    #[getter]
+    fn observation_space<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let space = self.env_ref()?.env().observation_space();
+        space_dict(py, space.dim, &space.features, None)
+    }
+
+    /// `{"shape", "low", "high", "names", "n"}` of the action; `n` is the
+    /// discrete action count, or `None` in continuous mode.
+    #[getter]
+    fn action_space<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let space = self.env_ref()?.env().action_space();
+        space_dict(py, space.dim, &space.actions, Some(space.discrete_count))
+    }
+
+    /// Fixed timestep in seconds.
+    #[getter]
+    fn tick_seconds(&self) -> PyResult<f32> {
+        Ok(self.env_ref()?.env().tick_seconds())
+    }
+
+    /// Drops the simulation. A later `reset()` rebuilds it.
+    fn close(&mut self) {
+        self.env = None;
+    }
+}
+
+fn space_dict<'py>(
+    py: Python<'py>,
+    dim: usize,
+    features: &[FeatureSpec],
+    discrete_count: Option<Option<usize>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("shape", (dim,))?;
+    dict.set_item(
+        "low",
+        features
+            .iter()
+            .map(|feature| feature.low)
+            .collect::<Vec<_>>()
+            .into_pyarray(py),
+    )?;
+    dict.set_item(
+        "high",
+        features
+            .iter()
+            .map(|feature| feature.high)
+            .collect::<Vec<_>>()
+            .into_pyarray(py),
+    )?;
+    dict.set_item(
+        "names",
+        features
+            .iter()
+            .map(|feature| feature.name.as_str())
+            .collect::<Vec<_>>(),
+    )?;
+    if let Some(count) = discrete_count {
+        dict.set_item("n", count)?;
+    }
+    Ok(dict)
+}
+
+#[pymodule]
+fn neurodrive(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<NeuroDriveEnv>()?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Global monotonic simulation clock.
+///
+/// Advances once per simulated fixed tick and is never reset, so it can
+/// timestamp telemetry and replays across episode boundaries. Paused fixed
+/// updates (see [`SimControl`](crate::sim::control::SimControl)) do not count.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimClock {
+    /// Simulated fixed ticks since startup.
+    pub tick: u64,
+    /// Simulated seconds since startup.
+    pub elapsed_s: f64,
+}
+
+impl SimClock {
+    pub fn advance(&mut self, dt_s: f64) {
+        self.tick = self.tick.saturating_add(1);
+        self.elapsed_s += dt_s;
+    }
+}
+
+/// Advances [`SimClock`]; ordered before every other simulation system.
+pub fn advance_sim_clock_system(time: Res<Time<Fixed>>, mut clock: ResMut<SimClock>) {
+    clock.advance(time.timestep().as_secs_f64());
+}
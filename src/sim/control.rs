@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+/// Pause and single-step state for the fixed-timestep simulation.
+///
+/// Pausing does not stop `Time<Fixed>`; instead every [`SimSet`] is gated by
+/// [`sim_tick_active`], so rendering, overlays, and the HUD keep drawing the
+/// frozen state while no simulation system runs.
+///
+/// [`SimSet`]: crate::sim::sets::SimSet
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct SimControl {
+    /// When `true`, fixed ticks only run while `step_requests` is non-zero.
+    pub paused: bool,
+    /// Pending single-step requests; each one releases exactly one fixed tick.
+    pub step_requests: u32,
+    /// Whether the simulation sets run on the current fixed tick.
+    pub tick_active: bool,
+}
+
+impl SimControl {
+    /// Decides whether the current fixed tick advances the simulation.
+    ///
+    /// Consumes one step request when paused, so one key press maps to one
+    /// fixed tick no matter how many fixed updates a render frame contains.
+    pub fn begin_tick(&mut self) -> bool {
+        self.tick_active = if !self.paused {
+            true
+        } else if self.step_requests > 0 {
+            self.step_requests -= 1;
+            true
+        } else {
+            false
+        };
+        self.tick_active
+    }
+}
+
+/// Run condition shared by all simulation sets.
+pub fn sim_tick_active(control: Res<SimControl>) -> bool {
+    control.tick_active
+}
+
+/// Evaluates pause/step state once at the start of every fixed tick.
+pub fn begin_sim_tick_system(mut control: ResMut<SimControl>) {
+    control.begin_tick();
+}
+
+/// Handles pause and single-step keybindings.
+///
+/// - P: toggle pause
+/// - `.`: advance one fixed tick while paused
+pub fn sim_control_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut control: ResMut<SimControl>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyP) {
+        control.paused = !control.paused;
+        control.step_requests = 0;
+        info!("Simulation paused: {}", control.paused);
+    }
+    if keyboard.just_pressed(KeyCode::Period) && control.paused {
+        control.step_requests = control.step_requests.saturating_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SimControl;
+    use crate::sim::clock::SimClock;
+
+    /// Runs `updates` fixed updates, advancing `clock` only on active ones,
+    /// as the gated `advance_sim_clock_system` does.
+    fn run_fixed_updates(control: &mut SimControl, clock: &mut SimClock, updates: usize) {
+        for _ in 0..updates {
+            if control.begin_tick() {
+                clock.advance(1.0 / 60.0);
+            }
+        }
+    }
+
+    #[test]
+    fn single_step_advances_exactly_one_tick_per_request() {
+        let mut control = SimControl {
+            paused: true,
+            ..SimControl::default()
+        };
+        let mut clock = SimClock::default();
+
+        // Many fixed updates without a request: nothing advances.
+        run_fixed_updates(&mut control, &mut clock, 5);
+        assert_eq!(clock.tick, 0);
+
+        // One press, many fixed updates: exactly one tick advances.
+        control.step_requests = 1;
+        run_fixed_updates(&mut control, &mut clock, 5);
+        assert_eq!(clock.tick, 1);
+
+        control.step_requests = 3;
+        run_fixed_updates(&mut control, &mut clock, 10);
+        assert_eq!(clock.tick, 4);
+    }
+
+    #[test]
+    fn unpaused_simulation_advances_every_fixed_tick() {
+        let mut control = SimControl::default();
+        let mut clock = SimClock::default();
+        run_fixed_updates(&mut control, &mut clock, 7);
+        assert_eq!(clock.tick, 7);
+    }
+}
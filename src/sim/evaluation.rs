@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Marks a run as evaluation (config `evaluation`, `--eval`, and every
+/// tournament run).
+///
+/// Evaluation switches off the training-only perturbations, observation
+/// noise and exploration noise, so scores measure the policy itself. It is
+/// independent of where the sim runs: headless training keeps both noise
+/// sources, and a windowed run can evaluate.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvaluationMode {
+    pub enabled: bool,
+}
@@ -4,4 +4,9 @@
 //! pipeline, keeping ordering explicit without creating cross-module
 //! dependencies (e.g. agent code depending on game code).
 
+pub mod clock;
+pub mod control;
+pub mod evaluation;
+pub mod rng;
 pub mod sets;
+pub mod stats;
@@ -0,0 +1,194 @@
+use std::fmt::Debug;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Small deterministic generator (SplitMix64) used for all simulation-side
+/// randomness.
+///
+/// Independent streams are split off by seed mixing rather than by drawing
+/// from a shared generator, so adding a new consumer never shifts the values
+/// another consumer sees.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimRng {
+    state: u64,
+}
+
+/// Stream identifiers for per-episode randomness.
+pub const STREAM_SPAWN: u64 = 1;
+pub const STREAM_DOMAIN: u64 = 2;
+pub const STREAM_EXPLORATION: u64 = 3;
+pub const STREAM_OBSERVATION_NOISE: u64 = 4;
+/// Additive action noise; see [`crate::agent::exploration`].
+pub const STREAM_ACTION_NOISE: u64 = 5;
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix64(self.state)
+    }
+
+    /// Uniform sample in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Gaussian sample via the Box–Muller transform.
+    pub fn normal(&mut self, mean: f32, std: f32) -> f32 {
+        let u1 = self.next_f32().max(f32::MIN_POSITIVE);
+        let u2 = self.next_f32();
+        let radius = (-2.0 * u1.ln()).sqrt();
+        mean + std * radius * (std::f32::consts::TAU * u2).cos()
+    }
+
+    /// Derives an independent child stream; does not advance `self`.
+    pub fn stream(&self, id: u64) -> SimRng {
+        SimRng::new(mix64(
+            self.state ^ mix64(id.wrapping_add(0xA076_1D64_78BD_642F)),
+        ))
+    }
+}
+
+/// Derives the seed for `episode` from a run's master seed.
+pub fn derive_episode_seed(master_seed: u64, episode: u32) -> u64 {
+    SimRng::new(master_seed).stream(episode as u64).next_u64()
+}
+
+/// Seed bookkeeping for the current run.
+#[derive(Resource, Clone, Debug)]
+pub struct RunSeeds {
+    pub master_seed: u64,
+    /// When set (`--replay-episode <seed>`), episode 1 uses this seed and the
+    /// app exits once it ends.
+    pub replay_episode_seed: Option<u64>,
+    /// Hash of the tuning configuration active for this run.
+    pub config_hash: u64,
+    /// Checksum of the track layout active for this run.
+    pub track_checksum: u64,
+}
+
+impl Default for RunSeeds {
+    fn default() -> Self {
+        let master_seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or(0);
+        Self {
+            master_seed,
+            replay_episode_seed: None,
+            config_hash: 0,
+            track_checksum: 0,
+        }
+    }
+}
+
+impl RunSeeds {
+    /// Seed used for `episode`'s randomisation.
+    pub fn seed_for_episode(&self, episode: u32) -> u64 {
+        match self.replay_episode_seed {
+            Some(seed) => seed,
+            None => derive_episode_seed(self.master_seed, episode),
+        }
+    }
+}
+
+/// Per-episode random streams, reseeded at every episode start.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct EpisodeRng {
+    /// Episode the streams were seeded for.
+    pub episode: u32,
+    pub seed: u64,
+    pub spawn: SimRng,
+    pub domain: SimRng,
+    pub exploration: SimRng,
+    pub observation_noise: SimRng,
+}
+
+impl EpisodeRng {
+    pub fn from_seed(episode: u32, seed: u64) -> Self {
+        let root = SimRng::new(seed);
+        Self {
+            episode,
+            seed,
+            spawn: root.stream(STREAM_SPAWN),
+            domain: root.stream(STREAM_DOMAIN),
+            exploration: root.stream(STREAM_EXPLORATION),
+            observation_noise: root.stream(STREAM_OBSERVATION_NOISE),
+        }
+    }
+}
+
+impl Default for EpisodeRng {
+    fn default() -> Self {
+        Self::from_seed(0, 0)
+    }
+}
+
+/// 64-bit FNV-1a hash.
+pub fn fnv1a64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+    }
+    hash
+}
+
+/// Hashes a value through its `Debug` representation.
+///
+/// Floats print with round-trip precision, so any tuning change alters the
+/// hash while identical configs always agree.
+pub fn hash_debug(value: &impl Debug) -> u64 {
+    fnv1a64(format!("{value:?}").as_bytes())
+}
+
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EpisodeRng, RunSeeds, SimRng, derive_episode_seed};
+
+    #[test]
+    fn child_streams_are_independent_of_draw_order() {
+        let root = SimRng::new(42);
+        let mut a = root.stream(1);
+        let mut consumed = root.stream(2);
+        for _ in 0..100 {
+            consumed.next_u64();
+        }
+        let mut a_again = SimRng::new(42).stream(1);
+
+        assert_eq!(a.next_u64(), a_again.next_u64());
+        assert_ne!(root.stream(1), root.stream(2));
+    }
+
+    #[test]
+    fn replay_seed_reproduces_recorded_episode_streams() {
+        let run = RunSeeds {
+            master_seed: 0xDEAD_BEEF,
+            ..RunSeeds::default()
+        };
+        let recorded = run.seed_for_episode(7);
+        assert_eq!(recorded, derive_episode_seed(0xDEAD_BEEF, 7));
+        assert_ne!(recorded, run.seed_for_episode(8));
+
+        let replay = RunSeeds {
+            replay_episode_seed: Some(recorded),
+            ..RunSeeds::default()
+        };
+        let original = EpisodeRng::from_seed(7, recorded);
+        let replayed = EpisodeRng::from_seed(1, replay.seed_for_episode(1));
+
+        assert_eq!(original.exploration, replayed.exploration);
+        assert_eq!(original.spawn, replayed.spawn);
+        assert_eq!(original.domain, replayed.domain);
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::app::AppExit;
+use bevy::ecs::message::MessageReader;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::episode::EpisodeState;
+use crate::sim::clock::SimClock;
+use crate::sim::control::SimControl;
+
+/// Wall-clock window over which the instantaneous tick rate is measured.
+const INSTANT_WINDOW_S: f64 = 1.0;
+
+/// Run-level throughput accounting.
+///
+/// Wall time is read from `Time<Real>` in `Update`, outside the fixed
+/// schedule. Only unpaused wall time counts towards throughput, and ticks
+/// released by single-stepping while paused are tallied separately, so the
+/// tick rates describe free-running simulation only.
+#[derive(Resource, Clone, Debug)]
+pub struct RunStats {
+    /// Simulated fixed ticks since startup.
+    pub ticks: u64,
+    /// Completed episodes.
+    pub episodes: u32,
+    /// Total wall-clock seconds since the first frame.
+    pub wall_s: f64,
+    /// Wall-clock seconds spent unpaused.
+    pub active_wall_s: f64,
+    /// Ticks advanced by single-stepping while paused.
+    pub stepped_ticks: u64,
+    /// Ticks per wall second over the last completed measurement window.
+    pub instant_tps: f64,
+    /// Ticks per unpaused wall second over the whole run.
+    pub average_tps: f64,
+    /// Seconds between periodic log lines. `0.0` disables them.
+    pub log_interval_s: f64,
+    window_ticks: u64,
+    window_wall_s: f64,
+    next_log_s: f64,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            ticks: 0,
+            episodes: 0,
+            wall_s: 0.0,
+            active_wall_s: 0.0,
+            stepped_ticks: 0,
+            instant_tps: 0.0,
+            average_tps: 0.0,
+            log_interval_s: 60.0,
+            window_ticks: 0,
+            window_wall_s: 0.0,
+            next_log_s: 60.0,
+        }
+    }
+}
+
+impl RunStats {
+    /// Folds one render frame into the totals.
+    ///
+    /// `ticks` is the absolute simulated tick count at the end of the frame.
+    pub fn record_frame(&mut self, wall_dt_s: f64, ticks: u64, episodes: u32, paused: bool) {
+        let new_ticks = ticks.saturating_sub(self.ticks);
+        self.ticks = ticks;
+        self.episodes = episodes;
+        self.wall_s += wall_dt_s;
+
+        if paused {
+            self.stepped_ticks += new_ticks;
+            self.instant_tps = 0.0;
+            self.window_ticks = 0;
+            self.window_wall_s = 0.0;
+            return;
+        }
+
+        self.active_wall_s += wall_dt_s;
+        self.window_ticks += new_ticks;
+        self.window_wall_s += wall_dt_s;
+        if self.window_wall_s >= INSTANT_WINDOW_S {
+            self.instant_tps = self.window_ticks as f64 / self.window_wall_s;
+            self.window_ticks = 0;
+            self.window_wall_s = 0.0;
+        }
+        if self.active_wall_s > 0.0 {
+            self.average_tps = (self.ticks - self.stepped_ticks) as f64 / self.active_wall_s;
+        }
+    }
+
+    /// Serializable snapshot for run reports.
+    pub fn record(&self) -> RunStatsRecord {
+        RunStatsRecord {
+            ticks: self.ticks,
+            episodes: self.episodes,
+            wall_s: self.wall_s,
+            active_wall_s: self.active_wall_s,
+            average_tps: self.average_tps,
+        }
+    }
+
+    pub fn summary_line(&self) -> String {
+        format!(
+            "{} ticks, {} episodes, wall {:.1}s ({:.1}s unpaused), {:.0} ticks/s avg, {:.0} ticks/s now",
+            self.ticks,
+            self.episodes,
+            self.wall_s,
+            self.active_wall_s,
+            self.average_tps,
+            self.instant_tps
+        )
+    }
+}
+
+/// Run throughput as exported with the analytics report.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RunStatsRecord {
+    pub ticks: u64,
+    pub episodes: u32,
+    pub wall_s: f64,
+    pub active_wall_s: f64,
+    pub average_tps: f64,
+}
+
+/// Updates [`RunStats`] once per render frame and emits periodic log lines.
+pub fn update_run_stats_system(
+    time: Res<Time<Real>>,
+    clock: Res<SimClock>,
+    control: Res<SimControl>,
+    episode_state: Res<EpisodeState>,
+    mut stats: ResMut<RunStats>,
+) {
+    stats.record_frame(
+        time.delta_secs_f64(),
+        clock.tick,
+        episode_state.current_episode.saturating_sub(1),
+        control.paused,
+    );
+
+    if stats.log_interval_s > 0.0 && stats.wall_s >= stats.next_log_s {
+        stats.next_log_s = stats.wall_s + stats.log_interval_s;
+        info!("Run stats: {}.", stats.summary_line());
+    }
+}
+
+/// Logs the final throughput summary when the app exits.
+pub fn log_run_stats_on_exit_system(mut exit_events: MessageReader<AppExit>, stats: Res<RunStats>) {
+    if exit_events.read().next().is_some() {
+        info!("Run summary: {}.", stats.summary_line());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RunStats;
+
+    #[test]
+    fn paused_time_and_stepped_ticks_do_not_count_towards_throughput() {
+        let mut stats = RunStats::default();
+        let mut ticks = 0;
+
+        // Two seconds free-running at 120 ticks/s.
+        for _ in 0..120 {
+            ticks += 2;
+            stats.record_frame(1.0 / 60.0, ticks, 0, false);
+        }
+        assert!((stats.average_tps - 120.0).abs() < 1e-6);
+        assert!((stats.instant_tps - 120.0).abs() < 1.0);
+
+        // Ten paused seconds with a few single steps.
+        for frame in 0..600 {
+            if frame % 100 == 0 {
+                ticks += 1;
+            }
+            stats.record_frame(1.0 / 60.0, ticks, 1, true);
+        }
+
+        assert_eq!(stats.ticks, 246);
+        assert_eq!(stats.stepped_ticks, 6);
+        assert_eq!(stats.episodes, 1);
+        assert!((stats.wall_s - 12.0).abs() < 1e-9);
+        assert!((stats.active_wall_s - 2.0).abs() < 1e-9);
+        assert!((stats.average_tps - 120.0).abs() < 1e-6);
+        assert_eq!(stats.instant_tps, 0.0);
+    }
+}